@@ -25,6 +25,18 @@ async fn create_instance() -> eyre::Result<()> {
 	let auth = Authorization::basic("username", "password");
 	let _provider = Http::new_with_auth(url, auth)?;
 
+	// Use the builder to combine credentials, static headers and headers computed per request
+	let url = reqwest::Url::parse(RPC_URL)?;
+	let _provider = Http::builder(url)
+		.bearer_auth("my token")
+		.header("X-MY-HEADERS", "Some value")
+		.on_request(|method, headers| {
+			if let Ok(value) = HeaderValue::from_str(method) {
+				headers.insert("X-RPC-METHOD", value);
+			}
+		})
+		.build()?;
+
 	// Instantiate from custom Http Client if you need
 	// finer control over the Http client configuration
 	// (TLS, Proxy, Cookies, Headers, etc.)
@@ -0,0 +1,256 @@
+//! End-to-end scenarios running against a local
+//! [neo-express](https://github.com/neo-project/neo-express) private network.
+//!
+//! Every scenario in `tests/` spawns a fresh network with [`Privatenet::start`], funds the
+//! accounts it needs from the genesis account and then only uses the SDK: scripts are built with
+//! the [`ScriptBuilder`], turned into transactions with a [`TransactionTemplate`], signed locally
+//! and sent to the node over RPC.
+//!
+//! The scenarios need the `neoxp` tool, so they are compiled with the other tests but ignored
+//! unless requested:
+//!
+//! ```sh
+//! dotnet tool install -g Neo.Express
+//! cargo test -p examples-scenarios -- --ignored
+//! ```
+use eyre::{bail, eyre};
+use neo_codec::{encode::NeoSerializable, Encoder};
+use neo_contract::{
+	contract_management::ContractManagement, traits::smart_contract::SmartContractTrait,
+};
+use neo_crypto::{hash::HashableForVec, key_pair::KeyPair};
+use neo_providers::{
+	core::{
+		account::{Account, AccountTrait},
+		responses::neo_application_log::Execution,
+		script::script_builder::ScriptBuilder,
+		transaction::{
+			call_flags::CallFlags,
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction::Transaction,
+			transaction_template::TransactionTemplate,
+			witness::Witness,
+		},
+	},
+	neo_express::{NeoExpress, NeoExpressInstance},
+	Http, Middleware, PendingTransaction, Provider,
+};
+use neo_types::{
+	contract_parameter::ContractParameter, encoding, nep17contract::Nep17Contract, Bytes,
+};
+use primitive_types::H160;
+use std::time::{Duration, Instant};
+
+/// How long [`Privatenet::fund`] waits for the transfer from the genesis account
+const FUNDING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The interval between two balance checks while waiting for a funding transfer
+const FUNDING_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A neo-express network with a single consensus node, and a provider connected to it.
+pub struct Privatenet {
+	node: NeoExpressInstance,
+	provider: Provider<Http>,
+	network_magic: u32,
+}
+
+impl Privatenet {
+	/// Spawns a network producing a block every second.
+	pub async fn start() -> eyre::Result<Self> {
+		let node = NeoExpress::new().seconds_per_block(1).spawn().await?;
+		let provider = node.provider();
+		let network_magic = provider.get_network_magic().await?;
+		Ok(Self { node, provider, network_magic })
+	}
+
+	/// The running neo-express node
+	pub fn node(&self) -> &NeoExpressInstance {
+		&self.node
+	}
+
+	/// A provider connected to the node
+	pub fn provider(&self) -> &Provider<Http> {
+		&self.provider
+	}
+
+	/// Creates an account with a new key pair holding `gas` GAS.
+	pub async fn funded_account(&self, gas: u64) -> eyre::Result<Account> {
+		let account = Account::create()?;
+		self.fund(&account, gas).await?;
+		Ok(account)
+	}
+
+	/// Transfers `gas` GAS from the genesis account to `account` and waits until the transfer is
+	/// persisted.
+	pub async fn fund(&self, account: &Account, gas: u64) -> eyre::Result<()> {
+		let hash = account.get_script_hash();
+		let amount = Nep17Contract::gas().parse_amount(&gas.to_string())?;
+		let expected = self.gas_balance(hash).await? + amount.value_i64()?;
+		self.node.fund(&account.address_or_scripthash().address(), "GAS", gas).await?;
+
+		let start = Instant::now();
+		while self.gas_balance(hash).await? < expected {
+			if start.elapsed() > FUNDING_TIMEOUT {
+				bail!("the funding of {hash:?} was not persisted within {FUNDING_TIMEOUT:?}")
+			}
+			tokio::time::sleep(FUNDING_INTERVAL).await;
+		}
+		Ok(())
+	}
+
+	/// The GAS balance of `account` in datoms.
+	pub async fn gas_balance(&self, account: H160) -> eyre::Result<i64> {
+		self.balance_of(Nep17Contract::gas().script_hash, account).await
+	}
+
+	/// The balance of `account` in the smallest unit of the NEP-17 token `token`.
+	pub async fn balance_of(&self, token: H160, account: H160) -> eyre::Result<i64> {
+		let params = vec![ContractParameter::hash160(&account)];
+		let result = self
+			.provider
+			.invoke_function(&token, "balanceOf".to_string(), params, None)
+			.await?;
+		result
+			.stack
+			.first()
+			.and_then(|item| item.as_int())
+			.ok_or_else(|| eyre!("unexpected result of balanceOf: {:?}", result.stack))
+	}
+
+	/// The committee of the network with the key pairs of its members, read from the config of
+	/// neo-express.
+	///
+	/// With a single consensus node, the committee is also the genesis account holding all NEO
+	/// and the initial GAS.
+	pub fn committee(&self) -> eyre::Result<(Account, Vec<KeyPair>)> {
+		let config: serde_json::Value =
+			serde_json::from_slice(&std::fs::read(self.node.config())?)?;
+		let key_pairs = config["consensus-nodes"]
+			.as_array()
+			.into_iter()
+			.flatten()
+			.filter_map(|node| node["wallet"]["accounts"].get(0)?["private-key"].as_str())
+			.map(|key| {
+				let key: [u8; 32] = hex::decode(key)?
+					.try_into()
+					.map_err(|_| eyre!("invalid private key of a consensus node"))?;
+				Ok(KeyPair::from_private_key(&key)?)
+			})
+			.collect::<eyre::Result<Vec<_>>>()?;
+		if key_pairs.is_empty() {
+			bail!("the neo-express config has no consensus nodes")
+		}
+
+		// the committee signs with a majority of its members
+		let threshold = key_pairs.len() - (key_pairs.len() - 1) / 2;
+		let public_keys: Vec<_> = key_pairs.iter().map(KeyPair::public_key).collect();
+		let committee = Account::create_multi_sig(&public_keys, threshold as u32)?;
+		Ok((committee, key_pairs))
+	}
+
+	/// Sends a transaction executing `script` signed by `sender`, which pays the fees, and
+	/// returns the execution once the transaction is persisted. Fails if the execution faults.
+	pub async fn send(&self, script: Bytes, sender: &Account) -> eyre::Result<Execution> {
+		let key_pair = sender
+			.key_pair()
+			.as_ref()
+			.ok_or_else(|| eyre!("the sender holds no key pair"))?;
+		let mut tx = self.transaction(script, sender).await?;
+		tx.add_witness(Witness::create(tx.get_hash_data(self.network_magic), key_pair)?);
+		self.submit(tx).await
+	}
+
+	/// Like [`Privatenet::send`], for a multi-sig `sender` signed with `key_pairs`. Fails if
+	/// they are fewer than the signing threshold of the account.
+	pub async fn send_multi_sig(
+		&self,
+		script: Bytes,
+		sender: &Account,
+		key_pairs: &[KeyPair],
+	) -> eyre::Result<Execution> {
+		let verification_script = sender
+			.verification_script()
+			.clone()
+			.ok_or_else(|| eyre!("the sender is not a multi-sig account"))?;
+		let mut tx = self.transaction(script, sender).await?;
+		let message = tx.get_hash_data(self.network_magic);
+
+		// the signatures are verified in the order of the public keys in the script
+		let mut signatures = Vec::new();
+		for public_key in verification_script.get_public_keys()? {
+			if let Some(key_pair) = key_pairs.iter().find(|pair| pair.public_key() == public_key) {
+				signatures.push(key_pair.private_key().sign_tx(&message)?);
+			}
+		}
+		tx.add_witness(Witness::create_multi_sig_witness_script(signatures, verification_script)?);
+		self.submit(tx).await
+	}
+
+	/// Deploys the contract `nef` described by `manifest` from `sender` and returns its hash.
+	pub async fn deploy(&self, nef: &[u8], manifest: &str, sender: &Account) -> eyre::Result<H160> {
+		let management =
+			ContractManagement::<Provider<Http>>::calc_native_contract_hash("ContractManagement")?;
+		let params = [
+			ContractParameter::byte_array(nef.to_vec()),
+			ContractParameter::string(manifest.to_string()),
+		];
+		let mut script = ScriptBuilder::new();
+		script.contract_call(&management, "deploy", &params, CallFlags::All)?;
+		self.send(script.to_bytes(), sender).await?;
+
+		let manifest: serde_json::Value = serde_json::from_str(manifest)?;
+		let name = manifest["name"].as_str().ok_or_else(|| eyre!("the manifest has no name"))?;
+		Ok(ContractManagement::<Provider<Http>>::calc_contract_hash(
+			sender.get_script_hash(),
+			nef_checksum(nef)?,
+			name,
+		)?)
+	}
+
+	async fn transaction(&self, script: Bytes, sender: &Account) -> eyre::Result<Transaction> {
+		let signer = Signer::from(AccountSigner::called_by_entry(sender)?);
+		let template = TransactionTemplate::new(script, &[signer], rand::random())?;
+		let mut tx = template.instantiate_with(&self.provider).await?;
+		tx.set_network_magic(self.network_magic);
+		Ok(tx)
+	}
+
+	async fn submit(&self, tx: Transaction) -> eyre::Result<Execution> {
+		let sent = self
+			.provider
+			.send_raw_transaction(encoding::base64_encode(tx.to_array()))
+			.await?;
+		let log = PendingTransaction::new(sent.hash, &self.provider).await_success().await?;
+		log.executions
+			.into_iter()
+			.next()
+			.ok_or_else(|| eyre!("the transaction {:?} has no execution", sent.hash))
+	}
+}
+
+/// Wraps `script` in a NEF file without method tokens, as a compiler would.
+pub fn nef(script: &[u8]) -> eyre::Result<Vec<u8>> {
+	let mut writer = Encoder::new();
+	writer.write_bytes(b"NEF3");
+	writer.write_fixed_string(&Some("examples-scenarios".to_string()), 64)?;
+	// no source url, reserved byte, no method tokens, reserved bytes
+	writer.write_var_string("");
+	writer.write_u8(0);
+	writer.write_var_int(0);
+	writer.write_u16(0);
+	writer.write_var_bytes(script);
+	// the checksum is the start of the double SHA-256 hash of the file
+	let checksum = writer.to_bytes().hash256().hash256();
+	writer.write_bytes(&checksum[..4]);
+	Ok(writer.to_bytes())
+}
+
+/// The checksum at the end of the NEF file `nef`, from which the hash of the contract is derived.
+pub fn nef_checksum(nef: &[u8]) -> eyre::Result<u32> {
+	let checksum = nef
+		.len()
+		.checked_sub(4)
+		.map(|start| &nef[start..])
+		.ok_or_else(|| eyre!("the NEF file is truncated"))?;
+	Ok(u32::from_le_bytes(checksum.try_into()?))
+}
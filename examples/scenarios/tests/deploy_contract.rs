@@ -0,0 +1,42 @@
+//! Deploys a contract and calls it.
+use examples_scenarios::{nef, Privatenet};
+use neo_providers::Middleware;
+use neo_types::op_code::OpCode;
+
+/// The manifest of a contract with a single method `answer` returning 42
+const MANIFEST: &str = r#"{
+	"name": "Answer",
+	"groups": [],
+	"features": {},
+	"supportedstandards": [],
+	"abi": {
+		"methods": [
+			{ "name": "answer", "parameters": [], "returntype": "Integer", "offset": 0, "safe": true }
+		],
+		"events": []
+	},
+	"permissions": [],
+	"trusts": [],
+	"extra": null
+}"#;
+
+#[tokio::test]
+#[ignore = "requires neoxp"]
+async fn deploy_contract() -> eyre::Result<()> {
+	let net = Privatenet::start().await?;
+	// the deployment costs at least 10 GAS
+	let owner = net.funded_account(100).await?;
+
+	let script = [OpCode::PushInt8 as u8, 42, OpCode::Ret as u8];
+	let hash = net.deploy(&nef(&script)?, MANIFEST, &owner).await?;
+
+	let state = net.provider().get_contract_state(hash).await?;
+	assert_eq!(state.manifest.name.as_deref(), Some("Answer"));
+
+	let result = net
+		.provider()
+		.invoke_function(&hash, "answer".to_string(), vec![], None)
+		.await?;
+	assert_eq!(result.stack.first().and_then(|item| item.as_int()), Some(42));
+	Ok(())
+}
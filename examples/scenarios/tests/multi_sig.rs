@@ -0,0 +1,39 @@
+//! Spends GAS from a 2-of-3 multi-sig account.
+use examples_scenarios::Privatenet;
+use neo_crypto::key_pair::KeyPair;
+use neo_providers::core::{
+	account::{Account, AccountTrait},
+	script::script_builder::{ScriptBuilder, TransferIntent},
+};
+use neo_types::nep17contract::Nep17Contract;
+
+#[tokio::test]
+#[ignore = "requires neoxp"]
+async fn multi_sig_spend() -> eyre::Result<()> {
+	let net = Privatenet::start().await?;
+	let key_pairs: Vec<_> = (0..3).map(|_| KeyPair::new_random()).collect();
+	let public_keys: Vec<_> = key_pairs.iter().map(KeyPair::public_key).collect();
+	let multi_sig = Account::create_multi_sig(&public_keys, 2)?;
+	net.fund(&multi_sig, 20).await?;
+	let bob = Account::create()?;
+
+	let gas = Nep17Contract::gas();
+	let transfer = TransferIntent {
+		token: gas.script_hash,
+		from: multi_sig.get_script_hash(),
+		to: bob.get_script_hash(),
+		amount: gas.parse_amount("5")?.value_i64()?,
+		data: None,
+	};
+	let script = ScriptBuilder::build_multi_transfer(&[transfer])?;
+
+	// a single signature does not reach the threshold
+	let signers = [key_pairs[1].clone()];
+	assert!(net.send_multi_sig(script.clone(), &multi_sig, &signers).await.is_err());
+
+	// any two of the three keys do, in any order
+	let signers = [key_pairs[2].clone(), key_pairs[0].clone()];
+	net.send_multi_sig(script, &multi_sig, &signers).await?;
+	assert_eq!(net.gas_balance(bob.get_script_hash()).await?, gas.parse_amount("5")?.value_i64()?);
+	Ok(())
+}
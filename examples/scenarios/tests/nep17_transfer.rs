@@ -0,0 +1,39 @@
+//! Transfers GAS between two accounts.
+use examples_scenarios::Privatenet;
+use neo_providers::core::{
+	account::{Account, AccountTrait},
+	script::script_builder::{ScriptBuilder, TransferIntent},
+};
+use neo_types::{gas::Gas, nep17contract::Nep17Contract};
+
+#[tokio::test]
+#[ignore = "requires neoxp"]
+async fn nep17_transfer() -> eyre::Result<()> {
+	let net = Privatenet::start().await?;
+	let alice = net.funded_account(50).await?;
+	let bob = Account::create()?;
+
+	let gas = Nep17Contract::gas();
+	let amount = gas.parse_amount("12.5")?;
+	let transfer = TransferIntent {
+		token: gas.script_hash,
+		from: alice.get_script_hash(),
+		to: bob.get_script_hash(),
+		amount: amount.value_i64()?,
+		data: None,
+	};
+	let execution = net.send(ScriptBuilder::build_multi_transfer(&[transfer])?, &alice).await?;
+
+	let event = execution
+		.notifications
+		.iter()
+		.find(|notification| notification.contract == gas.script_hash)
+		.expect("transfer event");
+	assert_eq!(event.event_name, "Transfer");
+	assert_eq!(net.gas_balance(bob.get_script_hash()).await?, amount.value_i64()?);
+
+	// alice paid the amount and the fees
+	let remaining = Gas::from_datoms(net.gas_balance(alice.get_script_hash()).await?);
+	assert!(remaining < Gas::from_decimal_str("37.5")?);
+	Ok(())
+}
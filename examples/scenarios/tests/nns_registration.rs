@@ -0,0 +1,63 @@
+//! Deploys the NNS contract and registers a domain.
+//!
+//! The NNS contract is not part of neo-express, so its build output, `NameService.nef` and
+//! `NameService.manifest.json` from <https://github.com/neo-project/non-native-contracts>, is read
+//! from the directory in `NNS_CONTRACT_DIR`. The scenario is skipped if the variable is not set.
+use examples_scenarios::Privatenet;
+use neo_contract::name_service::NeoNameService;
+use neo_providers::core::{
+	account::AccountTrait, script::script_builder::ScriptBuilder,
+	transaction::call_flags::CallFlags,
+};
+use neo_types::contract_parameter::ContractParameter;
+use std::{path::PathBuf, sync::Arc};
+
+/// The directory with the compiled NNS contract
+const NNS_CONTRACT_DIR_VAR: &str = "NNS_CONTRACT_DIR";
+
+#[tokio::test]
+#[ignore = "requires neoxp"]
+async fn nns_registration() -> eyre::Result<()> {
+	let Some(dir) = std::env::var_os(NNS_CONTRACT_DIR_VAR).map(PathBuf::from) else {
+		eprintln!("skipping the NNS scenario, {NNS_CONTRACT_DIR_VAR} is not set");
+		return Ok(())
+	};
+	let nef = std::fs::read(dir.join("NameService.nef"))?;
+	let manifest = std::fs::read_to_string(dir.join("NameService.manifest.json"))?;
+
+	let net = Privatenet::start().await?;
+	// any account can deploy the contract, the committee is only needed to add roots
+	let deployer = net.funded_account(100).await?;
+	let nns = net.deploy(&nef, &manifest, &deployer).await?;
+
+	let (committee, committee_keys) = net.committee()?;
+
+	let mut script = ScriptBuilder::new();
+	script.contract_call(
+		&nns,
+		"addRoot",
+		&[ContractParameter::string("neo".into())],
+		CallFlags::All,
+	)?;
+	net.send_multi_sig(script.to_bytes(), &committee, &committee_keys).await?;
+
+	// the registration burns the price of the domain from the GAS of the owner
+	let alice = net.funded_account(100).await?;
+	let params = [
+		ContractParameter::string("alice.neo".into()),
+		ContractParameter::hash160(&alice.get_script_hash()),
+	];
+	let mut script = ScriptBuilder::new();
+	script.contract_call(&nns, "register", &params, CallFlags::All)?;
+	let execution = net.send(script.to_bytes(), &alice).await?;
+	assert!(execution
+		.notifications
+		.iter()
+		.any(|notification| notification.contract == nns && notification.event_name == "Transfer"));
+
+	net.provider().shared_config().update(|config| config.nns_resolver = nns);
+	let name_service = NeoNameService::new(Some(Arc::new(net.provider().clone())));
+	assert!(!name_service.is_available("alice.neo").await?);
+	assert!(name_service.is_available("bob.neo").await?);
+	Ok(())
+}
@@ -12,7 +12,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 	// Instantiate the connection to the YubiKey. Alternatively, use the
 	// `from_key` method to upload a key you already have, or the `new` method
 	// to generate a new keypair.
-	let wallet = YubiWallet::connect(connector, Credentials::default(), 0);
+	let wallet = YubiWallet::connect(connector, Credentials::default(), 0)?;
 	let client = SignerMiddleware::new(provider, wallet);
 
 	// Create and broadcast a transaction (NNS enabled!)
@@ -2,34 +2,260 @@
 #![deny(unsafe_code, rustdoc::broken_intra_doc_links)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
-use neo_config::NeoNetwork;
-use neo_types::address::Address;
+pub use neo_config::NeoNetwork;
+use neo_types::{address::Address, script_hash::ScriptHash};
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt, fs,
+	path::Path,
+	str::FromStr,
+	sync::RwLock,
+};
+use thiserror::Error;
 
 const CONTRACTS_JSON: &str = include_str!("./contracts/contracts.json");
 
-static ADDRESSBOOK: Lazy<HashMap<String, Contract>> =
-	Lazy::new(|| serde_json::from_str(CONTRACTS_JSON).unwrap());
+static ADDRESSBOOK: Lazy<RwLock<AddressBook>> = Lazy::new(|| RwLock::new(AddressBook::bundled()));
 
-/// Wrapper around a hash map that maps a [Chain] to the contract's deployed address on that chain.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// A network identified by its magic number, so entries can be scoped to private networks.
+///
+/// In address book files, networks are written as `mainnet`, `testnet` or `privatenet`, or as
+/// their magic number in decimal or `0x`-prefixed hex notation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Network(u32);
+
+impl Network {
+	/// The network with the magic number `magic`
+	pub fn from_magic(magic: u32) -> Self {
+		Self(magic)
+	}
+
+	/// The magic number of the network
+	pub fn magic(&self) -> u32 {
+		self.0
+	}
+}
+
+impl From<NeoNetwork> for Network {
+	fn from(network: NeoNetwork) -> Self {
+		Self(network.to_magic())
+	}
+}
+
+impl From<u32> for Network {
+	fn from(magic: u32) -> Self {
+		Self(magic)
+	}
+}
+
+impl fmt::Display for Network {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match NeoNetwork::from_magic(self.0) {
+			Some(NeoNetwork::MainNet) => f.write_str("mainnet"),
+			Some(NeoNetwork::TestNet) => f.write_str("testnet"),
+			Some(NeoNetwork::PrivateNet) => f.write_str("privatenet"),
+			None => write!(f, "{}", self.0),
+		}
+	}
+}
+
+impl FromStr for Network {
+	type Err = AddressBookError;
+
+	fn from_str(network: &str) -> Result<Self, Self::Err> {
+		let magic = match network.to_ascii_lowercase().as_str() {
+			"mainnet" => NeoNetwork::MainNet.to_magic(),
+			"testnet" => NeoNetwork::TestNet.to_magic(),
+			"privatenet" => NeoNetwork::PrivateNet.to_magic(),
+			other => match other.strip_prefix("0x") {
+				Some(hex) => u32::from_str_radix(hex, 16),
+				None => other.parse(),
+			}
+			.map_err(|_| AddressBookError::InvalidNetwork(network.to_string()))?,
+		};
+		Ok(Self(magic))
+	}
+}
+
+impl Serialize for Network {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for Network {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let network = String::deserialize(deserializer)?;
+		network.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+/// Wrapper around a map from a [`Network`] to the contract's deployed address on that
+/// network.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Contract {
-	addresses: HashMap<NeoNetwork, Address>,
+	addresses: BTreeMap<Network, Address>,
 }
 
 impl Contract {
-	/// Returns the address of the contract on the specified chain. If the contract's address is
+	/// Returns the address of the contract on the specified network. If the contract's address is
 	/// not found in the addressbook, the getter returns None.
-	pub fn address(&self, network: NeoNetwork) -> Option<Address> {
-		self.addresses.get(&network).cloned()
+	pub fn address(&self, network: impl Into<Network>) -> Option<Address> {
+		self.addresses.get(&network.into()).cloned()
+	}
+
+	/// Sets the address of the contract on `network`.
+	pub fn set_address(&mut self, network: impl Into<Network>, address: Address) {
+		self.addresses.insert(network.into(), address);
 	}
 }
 
-/// Fetch the addressbook for a contract by its name. If the contract name is not a part of
+/// A collection of contracts by name.
+///
+/// The global address book, used by [`contract`], starts out with the [bundled](AddressBook::bundled)
+/// contracts and can be extended at runtime with [`register_contract`] and [`load`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct AddressBook {
+	contracts: HashMap<String, Contract>,
+}
+
+impl AddressBook {
+	/// An empty address book
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The address book bundled with the crate, holding the native contracts and the Neo Name
+	/// Service.
+	pub fn bundled() -> Self {
+		Self::from_json(CONTRACTS_JSON).expect("bundled address book is valid")
+	}
+
+	/// Parses an address book in the format of the bundled `contracts.json`.
+	pub fn from_json(json: &str) -> Result<Self, AddressBookError> {
+		Ok(serde_json::from_str(json)?)
+	}
+
+	/// Reads the address book file at `path`.
+	pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AddressBookError> {
+		Self::from_json(&fs::read_to_string(path)?)
+	}
+
+	/// Downloads the address book at `url`.
+	#[cfg(feature = "remote")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "remote")))]
+	pub async fn from_url(url: &str) -> Result<Self, AddressBookError> {
+		Ok(reqwest::get(url).await?.error_for_status()?.json().await?)
+	}
+
+	/// Writes the address book to the file at `path`, so it can be loaded again with
+	/// [`AddressBook::from_file`].
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AddressBookError> {
+		fs::write(path, serde_json::to_string_pretty(self)?)?;
+		Ok(())
+	}
+
+	/// Returns the contract named `name`.
+	pub fn get(&self, name: &str) -> Option<&Contract> {
+		self.contracts.get(name)
+	}
+
+	/// Sets the address of the contract `name` on `network`, keeping its addresses on other
+	/// networks.
+	pub fn insert(
+		&mut self,
+		name: impl Into<String>,
+		network: impl Into<Network>,
+		address: Address,
+	) {
+		self.contracts.entry(name.into()).or_default().set_address(network, address);
+	}
+
+	/// Adds the contracts of `other`. Addresses in `other` replace the addresses of contracts
+	/// with the same name on the same network.
+	pub fn merge(&mut self, other: AddressBook) {
+		for (name, contract) in other.contracts {
+			let entry = self.contracts.entry(name).or_default();
+			entry.addresses.extend(contract.addresses);
+		}
+	}
+
+	/// The names of the contracts
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.contracts.keys().map(String::as_str)
+	}
+
+	/// The name of the contract with the script hash `script_hash` on any network. If several
+	/// contracts have it, the first of their names in alphabetical order is returned.
+	pub fn name_of(&self, script_hash: &ScriptHash) -> Option<&str> {
+		self.contracts
+			.iter()
+			.filter(|(_, contract)| {
+				contract.addresses.values().any(|address| address.script_hash() == *script_hash)
+			})
+			.map(|(name, _)| name.as_str())
+			.min()
+	}
+}
+
+/// Fetch the addressbook for a contract by its name. If the contract name is not a part of the
+/// global address book, the getter returns None.
 pub fn contract<S: Into<String>>(name: S) -> Option<Contract> {
-	ADDRESSBOOK.get(&name.into()).cloned()
+	ADDRESSBOOK.read().unwrap().get(&name.into()).cloned()
+}
+
+/// Registers the address of the contract `name` on `network` in the global address book.
+///
+/// ```
+/// use neo_addressbook::{contract, register_contract, Network};
+///
+/// let address = "NiHURyS83nX2mpxtA7xq84cGxVbHojj5Wc".parse().unwrap();
+/// // a neo-express network with the magic number 1234567890
+/// register_contract("myToken", Network::from_magic(1234567890), address);
+/// assert_eq!(contract("myToken").unwrap().address(Network::from_magic(1234567890)), Some(address));
+/// ```
+pub fn register_contract(name: impl Into<String>, network: impl Into<Network>, address: Address) {
+	ADDRESSBOOK.write().unwrap().insert(name, network, address);
+}
+
+/// Merges `book` into the global address book, see [`AddressBook::merge`].
+pub fn load(book: AddressBook) {
+	ADDRESSBOOK.write().unwrap().merge(book);
+}
+
+/// Merges the address book file at `path` into the global address book.
+pub fn load_file(path: impl AsRef<Path>) -> Result<(), AddressBookError> {
+	load(AddressBook::from_file(path)?);
+	Ok(())
+}
+
+/// Merges the address book at `url` into the global address book.
+#[cfg(feature = "remote")]
+#[cfg_attr(docsrs, doc(cfg(feature = "remote")))]
+pub async fn load_url(url: &str) -> Result<(), AddressBookError> {
+	load(AddressBook::from_url(url).await?);
+	Ok(())
+}
+
+/// Errors when loading or saving address books
+#[derive(Debug, Error)]
+pub enum AddressBookError {
+	/// A network is neither a known network nor a magic number
+	#[error("invalid network: {0}")]
+	InvalidNetwork(String),
+	/// The address book is not valid JSON
+	#[error(transparent)]
+	Json(#[from] serde_json::Error),
+	/// The address book file could not be read or written
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
+	/// The address book could not be downloaded
+	#[cfg(feature = "remote")]
+	#[error(transparent)]
+	Http(#[from] reqwest::Error),
 }
 
 #[cfg(test)]
@@ -38,14 +264,37 @@ mod tests {
 
 	#[test]
 	fn test_tokens() {
-		assert!(contract("dai").is_some());
-		assert!(contract("usdc").is_some());
-		assert!(contract("usdt").is_some());
+		assert!(contract("neo").is_some());
+		assert!(contract("gas").is_some());
+		assert!(contract("policy").is_some());
 		assert!(contract("rand").is_none());
 	}
 
 	#[test]
 	fn test_addrs() {
-		assert!(contract("dai").unwrap().address(NeoNetwork::MainNet).is_some());
+		assert!(contract("neo").unwrap().address(NeoNetwork::MainNet).is_some());
+	}
+
+	#[test]
+	fn test_name_of() {
+		let book = AddressBook::bundled();
+		let gas = book.get("gas").unwrap().address(NeoNetwork::MainNet).unwrap();
+		assert_eq!(book.name_of(&gas.script_hash()), Some("gas"));
+		assert_eq!(book.name_of(&ScriptHash::zero()), None);
+	}
+
+	#[test]
+	fn test_private_networks() {
+		let json = r#"{"token": {"addresses": {"0x4d2": "NiHURyS83nX2mpxtA7xq84cGxVbHojj5Wc"}}}"#;
+		let mut book = AddressBook::new();
+		book.merge(AddressBook::from_json(json).unwrap());
+		let address = book.get("token").unwrap().address(Network::from_magic(1234)).unwrap();
+		assert_eq!(address.to_string(), "NiHURyS83nX2mpxtA7xq84cGxVbHojj5Wc");
+		assert_eq!(book.get("token").unwrap().address(NeoNetwork::MainNet), None);
+
+		let dir = std::env::temp_dir().join("neo-addressbook-test.json");
+		book.save(&dir).unwrap();
+		assert_eq!(AddressBook::from_file(&dir).unwrap(), book);
+		let _ = fs::remove_file(dir);
 	}
 }
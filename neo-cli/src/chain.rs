@@ -0,0 +1,61 @@
+//! Queries of the chain state: balances, blocks and transactions.
+
+use crate::input;
+use eyre::{eyre, Result};
+use neo_addressbook::AddressBook;
+use neo_providers::{
+	core::transaction::{transaction::Transaction, transaction_summary::ManifestRegistry},
+	Http, Middleware, Provider,
+};
+use neo_types::{serde_value::ValueExtension, token_amount::TokenAmount};
+use serde::Serialize;
+
+/// Prints the NEP-17 balances of the account `account`.
+pub async fn balance(provider: &Provider<Http>, account: &str) -> Result<()> {
+	let script_hash = input::parse_account(account)?;
+	let balances = provider.get_nep17_balances(script_hash).await?;
+	if balances.balances.is_empty() {
+		println!("No NEP-17 balances");
+	}
+	for balance in balances.balances {
+		let value: i128 = balance.amount.parse()?;
+		let decimals = balance.decimals.as_deref().unwrap_or("0").parse()?;
+		let amount = TokenAmount::new(value, decimals, balance.asset_hash);
+		let symbol = balance.symbol.unwrap_or_else(|| format!("{:#x}", balance.asset_hash));
+		println!("{} {symbol}", amount.to_decimal_string());
+	}
+	Ok(())
+}
+
+/// Prints the block `id`, a block index or hash, with its transactions.
+pub async fn block(provider: &Provider<Http>, id: &str) -> Result<()> {
+	let block = match id.parse::<u32>() {
+		Ok(index) => provider.get_block_by_index(index, true).await?,
+		Err(_) => provider.get_block(input::parse_hash(id)?, true).await?,
+	};
+	print_json(&block)
+}
+
+/// Prints the transaction `hash`, or with `explain` a summary of what it does.
+pub async fn transaction(provider: &Provider<Http>, hash: &str, explain: bool) -> Result<()> {
+	let hash = input::parse_hash(hash)?;
+	if !explain {
+		let tx = provider
+			.get_transaction(hash)
+			.await?
+			.ok_or_else(|| eyre!("unknown transaction"))?;
+		return print_json(&tx)
+	}
+
+	let raw: String = provider.request("getrawtransaction", [hash.to_value()]).await?;
+	let tx = Transaction::from_raw(&raw)?;
+	let mut manifests = ManifestRegistry::new();
+	manifests.fetch_for(provider, &tx).await?;
+	println!("{}", tx.explain(&AddressBook::bundled(), &manifests));
+	Ok(())
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+	println!("{}", serde_json::to_string_pretty(value)?);
+	Ok(())
+}
@@ -0,0 +1,169 @@
+//! Parsing of command line input: accounts, hashes, contract parameters and passwords.
+
+use eyre::{bail, eyre, Result, WrapErr};
+use neo_crypto::keys::Secp256r1PublicKey;
+use neo_types::{
+	address::Address, contract_parameter::ContractParameter,
+	contract_parameter_type::ContractParameterType, script_hash::ScriptHashBE,
+};
+use primitive_types::{H160, H256};
+use serde_json::Value;
+use std::{
+	io::{self, BufRead, Write},
+	str::FromStr,
+};
+use zeroize::Zeroizing;
+
+/// Parses an address like `NiHURyS83nX2mpxtA7xq84cGxVbHojj5Wc` or a script hash in the
+/// big-endian form of explorers and RPC responses, with or without `0x` prefix.
+pub fn parse_account(input: &str) -> Result<H160> {
+	if let Ok(address) = Address::from_str(input) {
+		return Ok(address.script_hash())
+	}
+	ScriptHashBE::from_str(input)
+		.map(H160::from)
+		.map_err(|_| eyre!("{input} is neither an address nor a script hash"))
+}
+
+/// Parses a block or transaction hash, with or without `0x` prefix.
+pub fn parse_hash(input: &str) -> Result<H256> {
+	H256::from_str(input.strip_prefix("0x").unwrap_or(input))
+		.map_err(|_| eyre!("{input} is not a hash"))
+}
+
+/// Parses the JSON array of parameters of a contract call.
+///
+/// Booleans, integers and strings are passed as such, `null` as `Any` and arrays as `Array`.
+/// Other types are given as objects `{"type": "Hash160", "value": "N..."}`.
+pub fn parse_params(json: &str) -> Result<Vec<ContractParameter>> {
+	let values: Vec<Value> =
+		serde_json::from_str(json).wrap_err("the parameters must be a JSON array")?;
+	values.into_iter().map(parse_param).collect()
+}
+
+fn parse_param(value: Value) -> Result<ContractParameter> {
+	let object = match value {
+		Value::Null => return Ok(ContractParameter::new(ContractParameterType::Any)),
+		Value::Bool(value) => return Ok(ContractParameter::bool(value)),
+		Value::Number(number) => {
+			let value = number.as_i64().ok_or_else(|| eyre!("{number} is not an integer"))?;
+			return Ok(ContractParameter::integer(value))
+		},
+		Value::String(value) => return Ok(ContractParameter::string(value)),
+		Value::Array(values) =>
+			return Ok(ContractParameter::array(
+				values.into_iter().map(parse_param).collect::<Result<_>>()?,
+			)),
+		Value::Object(object) => object,
+	};
+
+	let typ = object
+		.get("type")
+		.and_then(Value::as_str)
+		.ok_or_else(|| eyre!("parameter objects need a \"type\""))?;
+	let value = object.get("value").cloned().unwrap_or(Value::Null);
+	let text = || value.as_str().ok_or_else(|| eyre!("the value of a {typ} must be a string"));
+	Ok(match typ {
+		"Any" => ContractParameter::new(ContractParameterType::Any),
+		"Boolean" => ContractParameter::bool(
+			value
+				.as_bool()
+				.ok_or_else(|| eyre!("the value of a Boolean must be a boolean"))?,
+		),
+		"Integer" => match &value {
+			Value::String(number) => ContractParameter::integer(
+				number.parse().map_err(|_| eyre!("{number} is not an integer"))?,
+			),
+			_ => ContractParameter::integer(
+				value.as_i64().ok_or_else(|| eyre!("{value} is not an integer"))?,
+			),
+		},
+		"String" => ContractParameter::string(text()?.to_string()),
+		"Hash160" => ContractParameter::hash160(&parse_account(text()?)?),
+		"Hash256" => ContractParameter::hash256(&parse_hash(text()?)?),
+		"ByteArray" => {
+			let hex = text()?;
+			ContractParameter::byte_array(
+				hex::decode(hex.strip_prefix("0x").unwrap_or(hex))
+					.map_err(|_| eyre!("{hex} is not hex"))?,
+			)
+		},
+		"PublicKey" => {
+			let key = text()?;
+			ContractParameter::public_key(
+				&Secp256r1PublicKey::from_encoded(key)
+					.ok_or_else(|| eyre!("{key} is not a public key"))?,
+			)
+		},
+		"Array" => match value {
+			Value::Array(values) => ContractParameter::array(
+				values.into_iter().map(parse_param).collect::<Result<_>>()?,
+			),
+			_ => bail!("the value of an Array must be an array"),
+		},
+		_ => bail!("unsupported parameter type {typ}"),
+	})
+}
+
+/// The password given on the command line, or else the next line of stdin.
+pub fn password(given: Option<String>) -> Result<Zeroizing<String>> {
+	if let Some(password) = given {
+		return Ok(Zeroizing::new(password))
+	}
+	eprint!("Password: ");
+	io::stderr().flush()?;
+	let mut line = Zeroizing::new(String::new());
+	io::stdin().lock().read_line(&mut line)?;
+	Ok(Zeroizing::new(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+/// Asks `question` on stderr, true if the answer on stdin starts with `y`.
+pub fn confirm(question: &str) -> Result<bool> {
+	eprint!("{question} [y/N] ");
+	io::stderr().flush()?;
+	let mut answer = String::new();
+	io::stdin().lock().read_line(&mut answer)?;
+	Ok(answer.trim().to_lowercase().starts_with('y'))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_account() {
+		let hash = parse_account("0xd2a4cff31913016155e38e474a2c06d08be276cf").unwrap();
+		assert_eq!(parse_account("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap(), hash);
+		let address = Address::from_script_hash(hash).to_string();
+		assert_eq!(parse_account(&address).unwrap(), hash);
+		assert!(parse_account("GAS").is_err());
+	}
+
+	#[test]
+	fn test_parse_params() {
+		let params = parse_params(
+			r#"[true, 7, "text", null, [1],
+				{"type": "Hash160", "value": "0xd2a4cff31913016155e38e474a2c06d08be276cf"},
+				{"type": "Integer", "value": "100000000000"},
+				{"type": "ByteArray", "value": "0x0102"}]"#,
+		)
+		.unwrap();
+		let types: Vec<_> = params.iter().map(ContractParameter::get_type).collect();
+		assert_eq!(
+			types,
+			[
+				ContractParameterType::Boolean,
+				ContractParameterType::Integer,
+				ContractParameterType::String,
+				ContractParameterType::Any,
+				ContractParameterType::Array,
+				ContractParameterType::H160,
+				ContractParameterType::Integer,
+				ContractParameterType::ByteArray,
+			]
+		);
+		assert!(parse_params(r#"{"type": "Integer"}"#).is_err());
+		assert!(parse_params(r#"[{"type": "Hash160", "value": "nope"}]"#).is_err());
+		assert!(parse_params("[1.5]").is_err());
+	}
+}
@@ -0,0 +1,89 @@
+//! `neo-cli`, a command line wallet and chain explorer for Neo N3.
+//!
+//! The commands only use the public API of the neo crates, see the README for examples.
+
+mod chain;
+mod input;
+mod send;
+mod wallet;
+
+use clap::{Parser, Subcommand};
+use eyre::{Result, WrapErr};
+use neo_providers::{Http, Provider};
+use std::convert::TryFrom;
+
+#[derive(Debug, Parser)]
+#[command(name = "neo-cli", version, about = "A command line wallet and chain explorer for Neo N3")]
+struct Cli {
+	/// The JSON-RPC endpoint of the node
+	#[arg(long, global = true, env = "NEO_RPC_URL", default_value = "http://localhost:10332")]
+	rpc: String,
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Manages NEP-6 wallet files
+	Wallet {
+		#[command(subcommand)]
+		command: wallet::WalletCommand,
+	},
+	/// Shows the NEP-17 balances of an address
+	Balance {
+		/// An address or script hash
+		account: String,
+	},
+	/// Transfers NEP-17 tokens
+	Transfer(send::TransferArgs),
+	/// Calls a contract method
+	Invoke(send::InvokeArgs),
+	/// Shows a block by index or hash
+	Block {
+		/// A block index or hash
+		id: String,
+	},
+	/// Shows a transaction
+	Tx {
+		/// The hash of the transaction
+		hash: String,
+		/// Summarizes what the transaction does instead of showing its fields
+		#[arg(long)]
+		explain: bool,
+	},
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+	let cli = Cli::parse();
+	let provider = || {
+		Provider::<Http>::try_from(cli.rpc.as_str())
+			.wrap_err_with(|| format!("invalid RPC url {}", cli.rpc))
+	};
+	match cli.command {
+		Command::Wallet { command } => command.run(),
+		Command::Balance { account } => chain::balance(&provider()?, &account).await,
+		Command::Transfer(args) => send::transfer(&provider()?, args).await,
+		Command::Invoke(args) => send::invoke(&provider()?, args).await,
+		Command::Block { id } => chain::block(&provider()?, &id).await,
+		Command::Tx { hash, explain } => chain::transaction(&provider()?, &hash, explain).await,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::CommandFactory;
+
+	#[test]
+	fn test_cli() {
+		Cli::command().debug_assert();
+		let cli = Cli::parse_from(["neo-cli", "tx", "0x01", "--explain", "--rpc", "http://node"]);
+		assert_eq!(cli.rpc, "http://node");
+		assert!(matches!(cli.command, Command::Tx { explain: true, .. }));
+		let invoke = ["neo-cli", "invoke", "--contract", "GAS", "--method", "symbol"];
+		assert!(Cli::try_parse_from(invoke).is_ok());
+		// sending needs a wallet
+		assert!(Cli::try_parse_from(invoke.iter().chain(&["--send"])).is_err());
+	}
+}
@@ -0,0 +1,253 @@
+//! Commands sending transactions: NEP-17 transfers and contract invocations.
+
+use crate::{input, wallet};
+use clap::Args;
+use eyre::{bail, eyre, Result};
+use neo_addressbook::{AddressBook, Network};
+use neo_codec::encode::NeoSerializable;
+use neo_providers::{
+	core::{
+		account::{Account, AccountTrait},
+		script::script_builder::ScriptBuilder,
+		transaction::{
+			call_flags::CallFlags,
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction_summary::ManifestRegistry,
+			transaction_template::TransactionTemplate,
+			witness::Witness,
+		},
+	},
+	Http, Middleware, Provider,
+};
+use neo_types::{
+	contract_parameter::ContractParameter, contract_parameter_type::ContractParameterType,
+	encoding, nep17contract::Nep17Contract, stack_item::FromStackItem, Bytes,
+};
+use primitive_types::H160;
+
+/// The wallet account signing a transaction
+#[derive(Debug, Args)]
+pub struct SignerArgs {
+	/// The NEP-6 wallet file holding the account
+	#[arg(long)]
+	wallet: String,
+	/// The address of the signing account, the default account of the wallet if not given
+	#[arg(long)]
+	from: Option<String>,
+	/// The password of the wallet
+	#[arg(long, env = "NEO_CLI_PASSWORD", hide_env_values = true)]
+	password: Option<String>,
+	/// Sends the transaction without asking for confirmation
+	#[arg(long, short)]
+	yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TransferArgs {
+	#[command(flatten)]
+	signer: SignerArgs,
+	/// The address receiving the tokens
+	#[arg(long)]
+	to: String,
+	/// The token: NEO, GAS, a name of the address book or a script hash
+	#[arg(long, default_value = "GAS")]
+	token: String,
+	/// The amount in tokens, e.g. `1.5`
+	amount: String,
+}
+
+#[derive(Debug, Args)]
+pub struct InvokeArgs {
+	/// The contract: a name of the address book or a script hash
+	#[arg(long)]
+	contract: String,
+	/// The method to call
+	#[arg(long)]
+	method: String,
+	/// The parameters as JSON array
+	#[arg(long, default_value = "[]")]
+	params: String,
+	/// Sends the call as transaction instead of printing the result of a test invocation
+	#[arg(long, requires = "wallet")]
+	send: bool,
+	/// The NEP-6 wallet file holding the account sending the transaction
+	#[arg(long)]
+	wallet: Option<String>,
+	/// The address of the sending account, the default account of the wallet if not given
+	#[arg(long)]
+	from: Option<String>,
+	/// The password of the wallet
+	#[arg(long, env = "NEO_CLI_PASSWORD", hide_env_values = true)]
+	password: Option<String>,
+	/// Sends the transaction without asking for confirmation
+	#[arg(long, short)]
+	yes: bool,
+}
+
+/// Transfers NEP-17 tokens from a wallet account.
+pub async fn transfer(provider: &Provider<Http>, args: TransferArgs) -> Result<()> {
+	let signer = args.signer;
+	let account = wallet::unlock(&signer.wallet, signer.from.as_deref(), signer.password)?;
+	let to = input::parse_account(&args.to)?;
+	let token = resolve_token(provider, &args.token).await?;
+	let amount = token.parse_amount(&args.amount)?;
+	if amount.value() <= 0 {
+		bail!("the amount must be positive")
+	}
+
+	let script =
+		transfer_script(&token.script_hash, &account.get_script_hash(), &to, amount.value_i64()?)?;
+	sign_and_send(provider, &account, script, signer.yes).await
+}
+
+/// Calls a contract method, as test invocation or as transaction.
+pub async fn invoke(provider: &Provider<Http>, args: InvokeArgs) -> Result<()> {
+	let contract = resolve_contract(provider, &args.contract).await?;
+	let params = input::parse_params(&args.params)?;
+	let wallet = match args.wallet {
+		Some(wallet) if args.send => wallet,
+		_ => {
+			let result = provider.invoke_function(&contract, args.method, params, None).await?;
+			println!("{}", serde_json::to_string_pretty(&result)?);
+			return Ok(())
+		},
+	};
+
+	let account = wallet::unlock(&wallet, args.from.as_deref(), args.password)?;
+	let script = invoke_script(&contract, &args.method, &params)?;
+	sign_and_send(provider, &account, script, args.yes).await
+}
+
+/// The script transferring `amount` of the smallest unit of `token` from `from` to `to`.
+fn transfer_script(token: &H160, from: &H160, to: &H160, amount: i64) -> Result<Bytes> {
+	let params = [
+		ContractParameter::hash160(from),
+		ContractParameter::hash160(to),
+		ContractParameter::integer(amount),
+		ContractParameter::new(ContractParameterType::Any),
+	];
+	invoke_script(token, "transfer", &params)
+}
+
+/// The script calling `method` of `contract` with `params`.
+fn invoke_script(contract: &H160, method: &str, params: &[ContractParameter]) -> Result<Bytes> {
+	Ok(ScriptBuilder::new()
+		.contract_call(contract, method, params, CallFlags::All)?
+		.to_bytes())
+}
+
+/// Builds a transaction running `script` with `account` as sender, prints its summary, and signs
+/// and broadcasts it once confirmed.
+async fn sign_and_send(
+	provider: &Provider<Http>,
+	account: &Account,
+	script: Bytes,
+	yes: bool,
+) -> Result<()> {
+	let signer: Signer = AccountSigner::called_by_entry(account)?.into();
+	let mut tx = TransactionTemplate::new(script, &[signer], rand::random())?
+		.instantiate_with(provider)
+		.await?;
+
+	let mut manifests = ManifestRegistry::new();
+	manifests.fetch_for(provider, &tx).await?;
+	println!("{}", tx.explain(&AddressBook::bundled(), &manifests));
+	if !yes && !input::confirm("Send the transaction?")? {
+		bail!("aborted")
+	}
+
+	let network_magic = network_magic(provider).await?;
+	let key_pair = account.key_pair().as_ref().ok_or_else(|| eyre!("the account is locked"))?;
+	let witness = Witness::create(tx.get_hash_data(network_magic), key_pair)?;
+	tx.add_witness(witness);
+	let sent = provider
+		.send_raw_transaction_idempotent(encoding::base64_encode(tx.to_array()))
+		.await?;
+	println!("Sent {:#x}", sent.hash);
+	Ok(())
+}
+
+async fn network_magic(provider: &Provider<Http>) -> Result<u32> {
+	let version = provider.get_version().await?;
+	let protocol = version.protocol.ok_or_else(|| eyre!("getversion returned no protocol"))?;
+	Ok(protocol.network)
+}
+
+/// Resolves `name` to a script hash, looking it up in the address book on the network of the
+/// node unless it is a script hash.
+async fn resolve_contract(provider: &Provider<Http>, name: &str) -> Result<H160> {
+	if let Ok(script_hash) = input::parse_account(name) {
+		return Ok(script_hash)
+	}
+	let contract = AddressBook::bundled()
+		.get(name)
+		.cloned()
+		.ok_or_else(|| eyre!("unknown contract {name}"))?;
+	let network = Network::from_magic(network_magic(provider).await?);
+	let address = contract.address(network).ok_or_else(|| eyre!("{name} is not on {network}"))?;
+	Ok(address.script_hash())
+}
+
+/// Resolves the NEP-17 token `name`, asking the contract for its symbol and decimals unless it is
+/// NEO or GAS.
+async fn resolve_token(provider: &Provider<Http>, name: &str) -> Result<Nep17Contract> {
+	for native in [Nep17Contract::neo(), Nep17Contract::gas()] {
+		if name.eq_ignore_ascii_case(&native.symbol) {
+			return Ok(native)
+		}
+	}
+	let script_hash = resolve_contract(provider, name).await?;
+	let symbol: String = call(provider, &script_hash, "symbol").await?;
+	let decimals: i64 = call(provider, &script_hash, "decimals").await?;
+	let decimals = u8::try_from(decimals).map_err(|_| eyre!("{symbol} has {decimals} decimals"))?;
+	Ok(Nep17Contract::new(script_hash, symbol, decimals))
+}
+
+/// Calls the parameterless method `method` of `contract` and decodes its result.
+async fn call<T: FromStackItem>(
+	provider: &Provider<Http>,
+	contract: &H160,
+	method: &str,
+) -> Result<T> {
+	let result = provider.invoke_function(contract, method.to_string(), vec![], None).await?;
+	let item = result.into_result()?.stack.into_iter().next();
+	Ok(T::from_stack_item(item.ok_or_else(|| eyre!("{method} returned nothing"))?)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_providers::core::script::script_reader::ScriptReader;
+
+	#[test]
+	fn test_transfer_script() {
+		let token = Nep17Contract::gas().script_hash;
+		let from = H160::repeat_byte(1);
+		let to = H160::repeat_byte(2);
+		let script = transfer_script(&token, &from, &to, 150_000_000).unwrap();
+
+		let calls = ScriptReader::contract_calls(&script).unwrap();
+		assert_eq!(calls.len(), 1);
+		assert_eq!(calls[0].call_flags, CallFlags::All.value());
+		assert_eq!(calls[0].as_nep17_transfer(), Some((token, from, to, 150_000_000)));
+	}
+
+	#[test]
+	fn test_invoke_script() {
+		let contract = H160::repeat_byte(3);
+		let params = input::parse_params(r#"[{"type": "String", "value": "neo"}, 7]"#).unwrap();
+		let script = invoke_script(&contract, "register", &params).unwrap();
+
+		let expected = ScriptBuilder::new()
+			.contract_call(&contract, "register", &params, CallFlags::All)
+			.unwrap()
+			.to_bytes();
+		assert_eq!(script, expected);
+		let calls = ScriptReader::contract_calls(&script).unwrap();
+		assert_eq!(calls.len(), 1);
+		assert_eq!(calls[0].contract, contract);
+		assert_eq!(calls[0].method, "register");
+		assert_eq!(calls[0].args.len(), 2);
+		assert_eq!(calls[0].args[1].as_int(), Some(7));
+	}
+}
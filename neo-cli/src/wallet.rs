@@ -0,0 +1,156 @@
+//! NEP-6 wallet files: creating them, adding accounts and unlocking them for signing.
+
+use crate::input;
+use clap::Subcommand;
+use eyre::{bail, eyre, Result, WrapErr};
+use neo_providers::core::{
+	account::{Account, AccountTrait},
+	wallet::WalletTrait,
+};
+use neo_signers::{NEP6Wallet, Wallet};
+use std::{fs, path::Path};
+
+#[derive(Debug, Subcommand)]
+pub enum WalletCommand {
+	/// Creates a wallet file with one new account
+	Create {
+		/// The path of the wallet file, which must not exist yet
+		path: String,
+		/// The name of the wallet
+		#[arg(long, default_value = "neo-cli")]
+		name: String,
+		/// The password encrypting the private keys
+		#[arg(long, env = "NEO_CLI_PASSWORD", hide_env_values = true)]
+		password: Option<String>,
+	},
+	/// Lists the accounts of a wallet file
+	List {
+		/// The path of the wallet file
+		path: String,
+	},
+	/// Adds an account with a new private key to a wallet file
+	NewAccount {
+		/// The path of the wallet file
+		path: String,
+		/// The label of the account
+		#[arg(long)]
+		label: Option<String>,
+		/// The password of the wallet
+		#[arg(long, env = "NEO_CLI_PASSWORD", hide_env_values = true)]
+		password: Option<String>,
+	},
+}
+
+impl WalletCommand {
+	pub fn run(self) -> Result<()> {
+		match self {
+			WalletCommand::Create { path, name, password } => {
+				if Path::new(&path).exists() {
+					bail!("{path} already exists")
+				}
+				let password = input::password(password)?;
+				let address = create(&path, name, &password)?;
+				println!("Created {path} with the account {address}");
+			},
+			WalletCommand::List { path } => {
+				let wallet = read(&path)?;
+				let default = wallet.default_account().get_script_hash();
+				let mut accounts: Vec<_> = wallet.accounts().values().collect();
+				accounts.sort_by_key(|account| account.address_or_scripthash().address());
+				for account in accounts {
+					let marker = if account.get_script_hash() == default { "*" } else { " " };
+					let label = account.label().clone().unwrap_or_default();
+					println!("{marker} {} {label}", account.address_or_scripthash().address());
+				}
+			},
+			WalletCommand::NewAccount { path, label, password } => {
+				let mut wallet = read(&path)?;
+				let password = input::password(password)?;
+				// all accounts of a NEP-6 file are encrypted with the same password
+				let mut default = wallet.default_account().clone();
+				default.decrypt_private_key(&password).wrap_err("wrong password")?;
+
+				let mut account = Account::create()?;
+				account.label = label;
+				account.encrypt_private_key(&password)?;
+				let address = account.address_or_scripthash().address();
+				wallet.add_account(account);
+				wallet.save_to_file(path.into())?;
+				println!("Added the account {address}");
+			},
+		}
+		Ok(())
+	}
+}
+
+/// Writes a wallet file named `name` at `path` with one new account, encrypted with `password`,
+/// and returns the address of the account.
+pub fn create(path: &str, name: String, password: &str) -> Result<String> {
+	let account = Account::create()?;
+	let address = account.address_or_scripthash().address();
+	let mut wallet = Wallet::new();
+	wallet.set_name(name);
+	wallet.set_default_account(account.get_script_hash());
+	wallet.add_account(account);
+	wallet.encrypt_accounts(password);
+	wallet.save_to_file(path.into())?;
+	Ok(address)
+}
+
+/// Reads the wallet file at `path`, with its private keys still encrypted.
+pub fn read(path: &str) -> Result<Wallet> {
+	let json = fs::read_to_string(path).wrap_err_with(|| format!("cannot read {path}"))?;
+	let nep6: NEP6Wallet =
+		serde_json::from_str(&json).wrap_err_with(|| format!("{path} is not a NEP-6 wallet"))?;
+	Ok(Wallet::from_nep6(nep6)?)
+}
+
+/// Reads the wallet file at `path` and decrypts the private key of the account `address`, or of
+/// the default account.
+pub fn unlock(path: &str, address: Option<&str>, password: Option<String>) -> Result<Account> {
+	let wallet = read(path)?;
+	let mut account = match address {
+		Some(address) => {
+			let script_hash = input::parse_account(address)?;
+			wallet
+				.get_account(&script_hash)
+				.cloned()
+				.ok_or_else(|| eyre!("{address} is not in {path}"))?
+		},
+		None => wallet.default_account().clone(),
+	};
+	let password = input::password(password)?;
+	account.decrypt_private_key(&password).wrap_err("wrong password")?;
+	Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_create_and_unlock() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("wallet.json");
+		let path = path.to_str().unwrap();
+		let address = create(path, "test".to_string(), "secret").unwrap();
+
+		let wallet = read(path).unwrap();
+		assert_eq!(wallet.name(), "test");
+		assert_eq!(wallet.accounts().len(), 1);
+		assert_eq!(wallet.default_account().address_or_scripthash().address(), address);
+		assert!(wallet.default_account().key_pair().is_none());
+
+		let account = unlock(path, None, Some("secret".to_string())).unwrap();
+		assert_eq!(account.address_or_scripthash().address(), address);
+		let key_pair = account.key_pair().clone().unwrap();
+		assert_eq!(
+			Account::from_key_pair(key_pair, None, None).unwrap().get_script_hash(),
+			account.get_script_hash()
+		);
+
+		let account = unlock(path, Some(&address), Some("secret".to_string())).unwrap();
+		assert_eq!(account.address_or_scripthash().address(), address);
+		assert!(unlock(path, None, Some("wrong".to_string())).is_err());
+	}
+}
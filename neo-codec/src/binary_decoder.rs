@@ -1,22 +1,24 @@
 use crate::encode::NeoSerializable;
-/// This module provides a binary decoder that can read various types of data from a byte slice.
+/// This module provides a binary decoder that reads the Neo binary format from a byte slice.
+///
+/// All reads fail with a [`CodecError`] carrying the byte offset of the failure instead of
+/// panicking, and [`Decoder::read_slice`] and [`Decoder::read_var_slice`] borrow from the input
+/// instead of copying it.
 ///
 /// # Examples
 ///
 /// ```
 ///
 /// use neo_codec::Decoder;
-/// let data = [0x01, 0x02, 0x03, 0x04];
+/// let data = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x02, 0xab, 0xcd];
 /// let mut decoder = Decoder::new(&data);
 ///
-/// assert_eq!(decoder.read_bool(), true);
-/// assert_eq!(decoder.read_u8(), 2);
-/// assert_eq!(decoder.read_u16(), 0x0403);
-/// assert_eq!(decoder.read_i16(), 0x0403);
-/// assert_eq!(decoder.read_u32(), 0x04030201);
-/// assert_eq!(decoder.read_i32(), 0x04030201);
-/// assert_eq!(decoder.read_u64(), 0x0807060504030201);
-/// assert_eq!(decoder.read_i64(), 0x0807060504030201);
+/// assert_eq!(decoder.read_bool().unwrap(), true);
+/// assert_eq!(decoder.read_u8().unwrap(), 2);
+/// assert_eq!(decoder.read_u16().unwrap(), 0x0403);
+/// assert_eq!(decoder.read_u32().unwrap(), 0x08070605);
+/// assert_eq!(decoder.read_var_slice().unwrap(), &[0xab, 0xcd]);
+/// assert!(decoder.read_u8().is_err());
 /// ```
 use crate::CodecError;
 use getset::{Getters, Setters};
@@ -54,67 +56,72 @@ impl<'a> Decoder<'a> {
 		Self { data, pointer: 0, marker: 0 }
 	}
 
-	/// Reads a boolean value from the byte slice.
-	pub fn read_bool(&mut self) -> bool {
-		let val = self.data[self.pointer] == 1;
-		self.pointer += 1;
-		val
+	/// An [`CodecError::InvalidData`] error at the current position, for decoders of types
+	/// rejecting what they read.
+	pub fn invalid_data(&self, message: impl Into<String>) -> CodecError {
+		CodecError::InvalidData { offset: self.pointer, message: message.into() }
+	}
+
+	/// Reads a boolean, which has to be encoded as 0 or 1.
+	pub fn read_bool(&mut self) -> Result<bool, CodecError> {
+		match self.read_u8()? {
+			0 => Ok(false),
+			1 => Ok(true),
+			byte => {
+				self.pointer -= 1;
+				Err(self.invalid_data(format!("invalid boolean {byte:#04x}")))
+			},
+		}
 	}
 
 	/// Reads an unsigned 8-bit integer from the byte slice.
-	pub fn read_u8(&mut self) -> u8 {
-		let val = self.data[self.pointer];
-		self.pointer += 1;
-		val
+	pub fn read_u8(&mut self) -> Result<u8, CodecError> {
+		Ok(self.read_array::<1>()?[0])
 	}
 
-	/// Reads an unsigned 16-bit integer from the byte slice.
-	pub fn read_u16(&mut self) -> u16 {
-		let bytes = self.read_bytes(2).unwrap();
-		u16::from_ne_bytes(bytes.try_into().unwrap())
+	/// Reads an unsigned 16-bit integer in little-endian order.
+	pub fn read_u16(&mut self) -> Result<u16, CodecError> {
+		self.read_array().map(u16::from_le_bytes)
 	}
 
-	/// Reads a signed 16-bit integer from the byte slice.
-	pub fn read_i16(&mut self) -> i16 {
-		let bytes = self.read_bytes(2).unwrap();
-		i16::from_ne_bytes(bytes.try_into().unwrap())
+	/// Reads a signed 16-bit integer in little-endian order.
+	pub fn read_i16(&mut self) -> Result<i16, CodecError> {
+		self.read_array().map(i16::from_le_bytes)
 	}
 
-	/// Reads an unsigned 32-bit integer from the byte slice.
-	pub fn read_u32(&mut self) -> u32 {
-		let bytes = self.read_bytes(4).unwrap();
-		u32::from_ne_bytes(bytes.try_into().unwrap())
+	/// Reads an unsigned 32-bit integer in little-endian order.
+	pub fn read_u32(&mut self) -> Result<u32, CodecError> {
+		self.read_array().map(u32::from_le_bytes)
 	}
 
-	/// Reads a signed 32-bit integer from the byte slice.
-	pub fn read_i32(&mut self) -> i32 {
-		let bytes = self.read_bytes(4).unwrap();
-		i32::from_ne_bytes(bytes.try_into().unwrap())
+	/// Reads a signed 32-bit integer in little-endian order.
+	pub fn read_i32(&mut self) -> Result<i32, CodecError> {
+		self.read_array().map(i32::from_le_bytes)
 	}
 
-	/// Reads an unsigned 64-bit integer from the byte slice.
-	pub fn read_u64(&mut self) -> u64 {
-		let bytes = self.read_bytes(8).unwrap();
-		u64::from_ne_bytes(bytes.try_into().unwrap())
+	/// Reads an unsigned 64-bit integer in little-endian order.
+	pub fn read_u64(&mut self) -> Result<u64, CodecError> {
+		self.read_array().map(u64::from_le_bytes)
 	}
-	pub fn read_i64(&mut self) -> i64 {
-		let bytes = self.read_bytes(8).unwrap();
-		i64::from_ne_bytes(bytes.try_into().unwrap())
+
+	/// Reads a signed 64-bit integer in little-endian order.
+	pub fn read_i64(&mut self) -> Result<i64, CodecError> {
+		self.read_array().map(i64::from_le_bytes)
 	}
 
 	pub fn read_bigint(&mut self) -> Result<BigInt, CodecError> {
-		let byte = self.read_u8();
+		let byte = self.read_u8()?;
 
 		let negative = byte & 0x80 != 0;
 		let len = match byte {
 			0..=0x4b => 1,
-			0x4c => self.read_u8() as usize,
-			0x4d => self.read_u16() as usize,
-			0x4e => self.read_u32() as usize,
-			_ => return Err(CodecError::InvalidFormat),
+			0x4c => self.read_u8()? as usize,
+			0x4d => self.read_u16()? as usize,
+			0x4e => self.read_u32()? as usize,
+			_ => return Err(self.invalid_data(format!("invalid integer prefix {byte:#04x}"))),
 		};
 
-		let bytes = self.read_bytes(len).unwrap();
+		let bytes = self.read_slice(len)?;
 		if negative {
 			// Flip sign bit
 			if let Some(byte) = bytes.to_owned().get_mut(len - 1) {
@@ -125,53 +132,96 @@ impl<'a> Decoder<'a> {
 			// bytes.get_mut()[len - 1] ^= 0x80;
 		}
 		//TODO:: need to check be or le and sign
-		Ok(BigInt::from_bytes_be(Sign::Minus, &bytes))
+		Ok(BigInt::from_bytes_be(Sign::Minus, bytes))
 	}
 
 	/// Reads an encoded EC point from the byte slice.
-	pub fn read_encoded_ec_point(&mut self) -> Result<Vec<u8>, &'static str> {
-		let byte = self.read_u8();
-		match byte {
-			0x02 | 0x03 => Ok(self.read_bytes(32).unwrap()),
-			_ => Err("Invalid encoded EC point"),
+	pub fn read_encoded_ec_point(&mut self) -> Result<Vec<u8>, CodecError> {
+		match self.read_u8()? {
+			0x02 | 0x03 => self.read_bytes(32),
+			prefix => Err(self.invalid_data(format!("invalid EC point prefix {prefix:#04x}"))),
 		}
 	}
 
-	/// Reads a byte slice of the given length from the byte slice.
-	pub fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>, CodecError> {
-		if self.pointer + length > self.data.len() {
-			return Err(CodecError::IndexOutOfBounds("Read beyond end of buffer".to_string()))
+	/// Reads `length` bytes without copying them.
+	pub fn read_slice(&mut self, length: usize) -> Result<&'a [u8], CodecError> {
+		let available = self.available();
+		if length > available {
+			return Err(CodecError::UnexpectedEnd {
+				offset: self.pointer,
+				needed: length,
+				available,
+			})
 		}
-		let result = self.data[self.pointer..self.pointer + length].to_vec();
+		let slice = &self.data[self.pointer..self.pointer + length];
 		self.pointer += length;
-		Ok(result)
+		Ok(slice)
+	}
+
+	/// Reads `N` bytes into an array.
+	pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], CodecError> {
+		let mut array = [0; N];
+		array.copy_from_slice(self.read_slice(N)?);
+		Ok(array)
+	}
+
+	/// Reads a byte slice of the given length from the byte slice.
+	pub fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>, CodecError> {
+		self.read_slice(length).map(<[u8]>::to_vec)
+	}
+
+	/// Reads bytes prefixed with their length as var int without copying them.
+	pub fn read_var_slice(&mut self) -> Result<&'a [u8], CodecError> {
+		let len = self.read_var_length()?;
+		self.read_slice(len)
 	}
 
 	/// Reads a variable-length byte slice from the byte slice.
 	pub fn read_var_bytes(&mut self) -> Result<Vec<u8>, CodecError> {
-		let len = self.read_var_int().unwrap() as usize;
-		self.read_bytes(len)
+		self.read_var_slice().map(<[u8]>::to_vec)
+	}
+
+	/// Reads a variable-length integer, rejecting values not encoded in the shortest form.
+	pub fn read_var_int(&mut self) -> Result<u64, CodecError> {
+		let offset = self.pointer;
+		let (value, min) = match self.read_u8()? {
+			0xfd => (self.read_u16()? as u64, 0xfd),
+			0xfe => (self.read_u32()? as u64, 0x1_0000),
+			0xff => (self.read_u64()?, 0x1_0000_0000),
+			first => return Ok(first as u64),
+		};
+		if value < min {
+			return Err(CodecError::InvalidData {
+				offset,
+				message: format!("var int {value} is not in the shortest form"),
+			})
+		}
+		Ok(value)
 	}
 
-	/// Reads a variable-length integer from the byte slice.
-	pub fn read_var_int(&mut self) -> Result<i64, CodecError> {
-		let first = self.read_u8();
-		match first {
-			0xfd => Ok(self.read_u16() as i64),
-			0xfe => Ok(self.read_u32() as i64),
-			0xff => Ok(self.read_u64() as i64),
-			_ => Ok(first as i64),
+	/// Reads a var int counting bytes or items that follow, which cannot be more than the bytes
+	/// left, so a corrupt length fails before anything is allocated for it.
+	fn read_var_length(&mut self) -> Result<usize, CodecError> {
+		let offset = self.pointer;
+		let len = self.read_var_int()?;
+		if len > self.available() as u64 {
+			return Err(CodecError::InvalidData {
+				offset,
+				message: format!("length {len} exceeds the {} bytes left", self.available()),
+			})
 		}
+		Ok(len as usize)
 	}
 
 	pub fn read_var_string(&mut self) -> Result<String, CodecError> {
-		let bytes = self.read_var_bytes().unwrap();
+		let offset = self.pointer;
+		let bytes = self.read_var_slice()?;
 
-		let string = match String::from_utf8(bytes.to_vec()) {
+		let string = match std::str::from_utf8(bytes) {
 			Ok(s) => s,
 			Err(e) => {
 				// Handle invalid UTF-8
-				return Err(CodecError::InvalidEncoding(e.to_string()))
+				return Err(CodecError::InvalidData { offset, message: e.to_string() })
 			},
 		};
 
@@ -183,12 +233,12 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a push byte slice from the byte slice.
 	pub fn read_push_bytes(&mut self) -> Result<Vec<u8>, CodecError> {
-		let opcode = self.read_u8();
+		let opcode = self.read_u8()?;
 		let len = match opcode {
 			0x01..=0x4B => opcode as usize,
-			0x4C => self.read_u8() as usize,
-			0x4D => self.read_u16() as usize,
-			0x4E => self.read_u32() as usize,
+			0x4C => self.read_u8()? as usize,
+			0x4D => self.read_u16()? as usize,
+			0x4E => self.read_u32()? as usize,
 			_ => return Err(CodecError::InvalidOpCode),
 		};
 
@@ -197,7 +247,7 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a push integer from the byte slice.
 	pub fn read_push_int(&mut self) -> Result<i64, CodecError> {
-		let opcode = self.read_u8();
+		let opcode = self.read_u8()?;
 		match opcode {
 			0x00..=0x16 => Ok(opcode as i64 - 1),
 			0x01..=0x04 => {
@@ -206,11 +256,9 @@ impl<'a> Decoder<'a> {
 					0x52 => 2,
 					0x53 => 4,
 					0x54 => 8,
-					_ => {
-						panic!("Invalid opcode")
-					},
+					_ => return Err(CodecError::InvalidOpCode),
 				};
-				let bytes = self.read_bytes(n).unwrap();
+				let bytes = self.read_bytes(n)?;
 				Ok(i64::from_be_bytes(bytes.try_into().unwrap()))
 			},
 			_ => Err(CodecError::InvalidOpCode),
@@ -219,40 +267,47 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a push string from the byte slice.
 	pub fn read_push_string(&mut self) -> Result<String, CodecError> {
-		let bytes = self.read_push_bytes().unwrap();
-		String::from_utf8(Vec::from(bytes))
+		let bytes = self.read_push_bytes()?;
+		String::from_utf8(bytes)
 			.map_err(|_| CodecError::InvalidEncoding("Invalid UTF-8".to_string()))
 	}
 
 	/// Reads a deserializable value from the byte slice.
-	pub fn read_serializable<T: NeoSerializable>(&mut self) -> Result<T, CodecError> {
-		T::decode(self).map_err(|_e| CodecError::InvalidFormat)
+	pub fn read_serializable<T: NeoSerializable>(&mut self) -> Result<T, T::Error> {
+		T::decode(self)
 	}
 
-	/// Reads a list of deserializable values from the byte slice.
-	pub fn read_serializable_list<T: NeoSerializable>(&mut self) -> Result<Vec<T>, CodecError> {
-		let len = self.read_var_int().unwrap();
-		let mut list = Vec::with_capacity(len as usize);
+	/// Reads a list of deserializable values prefixed with their count as var int.
+	///
+	/// The errors of the items are returned unchanged, so they keep the offset they failed at.
+	pub fn read_serializable_list<T: NeoSerializable>(&mut self) -> Result<Vec<T>, T::Error>
+	where
+		T::Error: From<CodecError>,
+	{
+		// every item takes at least one byte
+		let len = self.read_var_length()?;
+		let mut list = Vec::with_capacity(len);
 		for _ in 0..len {
-			T::decode(self)
-				.and_then(|item| Ok(list.push(item)))
-				.expect("TODO: panic message");
+			list.push(T::decode(self)?);
 		}
 		Ok(list)
 	}
 
+	/// Reads a list of deserializable values prefixed with their total size in bytes as var int.
 	pub fn read_serializable_list_var_bytes<T: NeoSerializable>(
 		&mut self,
-	) -> Result<Vec<T>, CodecError> {
-		let len = self.read_var_int().unwrap();
-		let mut bytes_read = 0;
-		let mut offset = self.pointer;
-		let mut list = Vec::with_capacity(len as usize);
-		while bytes_read < len {
-			T::decode(self)
-				.and_then(|item| Ok(list.push(item)))
-				.expect("TODO: panic message");
-			bytes_read = (self.pointer - offset) as i64;
+	) -> Result<Vec<T>, T::Error>
+	where
+		T::Error: From<CodecError>,
+	{
+		let len = self.read_var_length()?;
+		let end = self.pointer + len;
+		let mut list = vec![];
+		while self.pointer < end {
+			list.push(T::decode(self)?);
+		}
+		if self.pointer != end {
+			return Err(self.invalid_data("list items exceed the size of the list").into())
 		}
 		Ok(list)
 	}
@@ -285,3 +340,53 @@ impl<'a> Decoder<'a> {
 		self.data.len() - self.pointer
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_read_past_end() {
+		let mut decoder = Decoder::new(&[0x01, 0x02, 0x03]);
+		assert_eq!(decoder.read_u16().unwrap(), 0x0201);
+		assert_eq!(
+			decoder.read_u32(),
+			Err(CodecError::UnexpectedEnd { offset: 2, needed: 4, available: 1 })
+		);
+		// a failed read consumes nothing
+		assert_eq!(decoder.read_u8().unwrap(), 0x03);
+	}
+
+	#[test]
+	fn test_read_var_int() {
+		let data = [0x05, 0xfd, 0x34, 0x12, 0xfd, 0x05, 0x00];
+		let mut decoder = Decoder::new(&data);
+		assert_eq!(decoder.read_var_int().unwrap(), 5);
+		assert_eq!(decoder.read_var_int().unwrap(), 0x1234);
+		assert_eq!(decoder.read_var_int().unwrap_err().offset(), Some(4));
+	}
+
+	#[test]
+	fn test_read_var_slice() {
+		let data = [0x02, 0xab, 0xcd, 0x05, 0x01];
+		let mut decoder = Decoder::new(&data);
+		let slice = decoder.read_var_slice().unwrap();
+		assert_eq!(slice, &[0xab, 0xcd]);
+		assert_eq!(slice.as_ptr(), data[1..].as_ptr());
+		assert!(matches!(decoder.read_var_bytes(), Err(CodecError::InvalidData { offset: 3, .. })));
+	}
+
+	#[test]
+	fn test_read_serializable_list() {
+		let data = [0x02, 0x07, 0x08, 0x03, 0x01];
+		let mut decoder = Decoder::new(&data);
+		assert_eq!(decoder.read_serializable_list::<u8>().unwrap(), vec![7, 8]);
+		assert_eq!(
+			decoder.read_serializable_list::<u8>(),
+			Err(CodecError::InvalidData {
+				offset: 3,
+				message: "length 3 exceeds the 1 bytes left".to_string()
+			})
+		);
+	}
+}
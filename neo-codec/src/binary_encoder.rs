@@ -1,4 +1,7 @@
-use crate::{encode::NeoSerializable, CodecError};
+use crate::{
+	encode::{var_size, NeoSerializable},
+	CodecError,
+};
 /// A binary encoder that can write various primitive types and serializable objects to a byte vector.
 ///
 /// # Examples
@@ -59,21 +62,29 @@ impl Encoder {
 		self.data.extend_from_slice(&v.to_le_bytes());
 	}
 
+	pub fn write_u64(&mut self, v: u64) {
+		self.data.extend_from_slice(&v.to_le_bytes());
+	}
+
 	pub fn write_bytes(&mut self, bytes: &[u8]) {
 		self.data.extend_from_slice(bytes);
 	}
 
-	// Other primitive write methods
-	pub fn write_var_int(&mut self, value: i64) {
+	/// Writes a variable-length integer in its shortest form, which takes [`var_size`] bytes.
+	pub fn write_var_int(&mut self, value: u64) {
 		match value {
-			0..=0xfd => self.write_u8(value as u8),
-			0x10000..=0xffffffff => {
+			0..=0xfc => self.write_u8(value as u8),
+			0xfd..=0xffff => {
 				self.write_u8(0xfd);
 				self.write_u16(value as u16);
 			},
+			0x1_0000..=0xffff_ffff => {
+				self.write_u8(0xfe);
+				self.write_u32(value as u32);
+			},
 			_ => {
 				self.write_u8(0xff);
-				self.write_u64(value as u64);
+				self.write_u64(value);
 			},
 		}
 	}
@@ -96,8 +107,9 @@ impl Encoder {
 		Ok(self.write_bytes(&padded))
 	}
 
+	/// Writes the length of `bytes` as var int followed by the bytes.
 	pub fn write_var_bytes(&mut self, bytes: &[u8]) {
-		self.write_var_int(bytes.len() as i64);
+		self.write_var_int(bytes.len() as u64);
 		self.write_bytes(bytes);
 	}
 
@@ -109,18 +121,21 @@ impl Encoder {
 	}
 
 	pub fn write_serializable_variable_bytes<S: NeoSerializable>(&mut self, values: &S) {
-		self.write_var_int(values.to_array().len() as i64);
+		self.write_var_int(values.size() as u64);
 		values.encode(self);
 	}
 
-	pub fn write_serializable_variable_list<S: NeoSerializable>(&mut self, values: &[S]) {
-		self.write_var_int(values.len() as i64);
+	/// Writes the number of `values` as var int followed by the values, the counterpart of
+	/// [`Decoder::read_serializable_list`](crate::Decoder::read_serializable_list). The encoded
+	/// list takes [`VarSizeTrait::var_size`](crate::encode::VarSizeTrait::var_size) bytes.
+	pub fn write_serializable_list<S: NeoSerializable>(&mut self, values: &[S]) {
+		self.write_var_int(values.len() as u64);
 		self.write_serializable_list_fixed(values);
 	}
 
 	pub fn write_serializable_variable_list_bytes<S: NeoSerializable>(&mut self, values: &[S]) {
-		let total_size: usize = values.iter().map(|item| item.to_array().len()).sum();
-		self.write_var_int(total_size as i64);
+		let total_size: usize = values.iter().map(|item| item.size()).sum();
+		self.write_var_int(total_size as u64);
 		self.write_serializable_list_fixed(values);
 	}
 
@@ -142,3 +157,34 @@ impl Hasher for Encoder {
 		self.write_bytes(bytes);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::encode::VarSizeTrait;
+
+	#[test]
+	fn test_write_var_int() {
+		for (value, expected) in [
+			(0xfc, vec![0xfc]),
+			(0xfd, vec![0xfd, 0xfd, 0x00]),
+			(0xffff, vec![0xfd, 0xff, 0xff]),
+			(0x1_0000, vec![0xfe, 0x00, 0x00, 0x01, 0x00]),
+			(0x1_0000_0000, vec![0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]),
+		] {
+			let mut encoder = Encoder::new();
+			encoder.write_var_int(value);
+			assert_eq!(encoder.to_bytes(), expected);
+			assert_eq!(var_size(value as usize), expected.len());
+		}
+	}
+
+	#[test]
+	fn test_write_serializable_list() {
+		let values: Vec<u8> = vec![7; 300];
+		let mut encoder = Encoder::new();
+		encoder.write_serializable_list(&values);
+		assert_eq!(&encoder.to_bytes()[..3], &[0xfd, 0x2c, 0x01]);
+		assert_eq!(encoder.size(), values.var_size());
+	}
+}
@@ -13,27 +13,35 @@ pub trait NeoSerializable {
 	fn to_array(&self) -> Vec<u8>;
 }
 
+/// Serialized as the 20 bytes in little-endian order, the reverse of the order used by the RPC
+/// interface and for display.
 impl NeoSerializable for H160 {
 	type Error = CodecError;
+
 	fn size(&self) -> usize {
 		H160::len_bytes()
 	}
 	fn encode(&self, writer: &mut Encoder) {
-		writer.write_var_bytes(&self.as_bytes());
+		writer.write_bytes(&self.to_array());
 	}
 
-	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error>
+	fn decode(reader: &mut Decoder) -> Result<Self, CodecError>
 	where
 		Self: Sized,
 	{
-		reader.read_var_bytes().map(|bytes| H160::from_slice(&bytes))
+		let mut bytes = reader.read_bytes(H160::len_bytes())?;
+		bytes.reverse();
+		Ok(H160::from_slice(&bytes))
 	}
 
 	fn to_array(&self) -> Vec<u8> {
-		self.as_bytes().to_vec()
+		let mut bytes = self.as_bytes().to_vec();
+		bytes.reverse();
+		bytes
 	}
 }
 
+/// Serialized like [`H160`], as the 32 bytes in little-endian order.
 impl NeoSerializable for H256 {
 	type Error = CodecError;
 
@@ -41,18 +49,22 @@ impl NeoSerializable for H256 {
 		H256::len_bytes()
 	}
 	fn encode(&self, writer: &mut Encoder) {
-		writer.write_var_bytes(&self.as_bytes());
+		writer.write_bytes(&self.to_array());
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, CodecError>
 	where
 		Self: Sized,
 	{
-		reader.read_var_bytes().map(|bytes| H256::from_slice(&bytes))
+		let mut bytes = reader.read_bytes(H256::len_bytes())?;
+		bytes.reverse();
+		Ok(H256::from_slice(&bytes))
 	}
 
 	fn to_array(&self) -> Vec<u8> {
-		self.as_bytes().to_vec()
+		let mut bytes = self.as_bytes().to_vec();
+		bytes.reverse();
+		bytes
 	}
 }
 
@@ -70,7 +82,7 @@ impl NeoSerializable for u8 {
 	where
 		Self: Sized,
 	{
-		Ok(reader.read_u8())
+		reader.read_u8()
 	}
 
 	fn to_array(&self) -> Vec<u8> {
@@ -78,25 +90,30 @@ impl NeoSerializable for u8 {
 	}
 }
 
+/// The number of bytes [`Encoder::write_var_int`] takes to write `n`.
+pub fn var_size(n: usize) -> usize {
+	match n as u64 {
+		0..=0xfc => 1,
+		0xfd..=0xffff => 3,
+		0x1_0000..=0xffff_ffff => 5,
+		_ => 9,
+	}
+}
+
+/// The size of a list written with [`Encoder::write_serializable_list`], its item count as var int
+/// followed by the items.
 pub trait VarSizeTrait {
 	fn var_size(&self) -> usize;
 }
 
-impl<T: NeoSerializable> VarSizeTrait for Vec<T> {
+impl<T: NeoSerializable> VarSizeTrait for [T] {
 	fn var_size(&self) -> usize {
-		let count_var_size = self.len();
-		count_var_size + self.iter().map(|item| item.size()).sum::<usize>()
+		var_size(self.len()) + self.iter().map(|item| item.size()).sum::<usize>()
 	}
 }
 
-// impl<T:NeoSerializable> VarSizeTrait for &[T] {
-// 	fn var_size(&self) -> usize {
-// 		let count_var_size = self.len();
-// 		count_var_size + self.iter().map(|item| item.size()).sum::<usize>()
-// 	}
-// }
-
-// fn var_size_of_serializables<T: NeoSerializable>(elements: &[T]) -> usize {
-// 	let count_var_size = elements.len();
-// 	count_var_size + elements.iter().map(|item| item.size()).sum::<usize>()
-// }
+impl<T: NeoSerializable> VarSizeTrait for Vec<T> {
+	fn var_size(&self) -> usize {
+		self.as_slice().var_size()
+	}
+}
@@ -12,4 +12,18 @@ pub enum CodecError {
 	InvalidEncoding(String),
 	#[error("Invalid op code")]
 	InvalidOpCode,
+	#[error("Unexpected end of data at byte {offset}: {needed} bytes needed, {available} left")]
+	UnexpectedEnd { offset: usize, needed: usize, available: usize },
+	#[error("Invalid data at byte {offset}: {message}")]
+	InvalidData { offset: usize, message: String },
+}
+
+impl CodecError {
+	/// The byte offset in the decoded data the error occurred at, if known
+	pub fn offset(&self) -> Option<usize> {
+		match self {
+			Self::UnexpectedEnd { offset, .. } | Self::InvalidData { offset, .. } => Some(*offset),
+			_ => None,
+		}
+	}
 }
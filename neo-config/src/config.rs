@@ -1,30 +1,34 @@
+use arc_swap::ArcSwap;
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 use std::{
+	collections::BTreeMap,
 	hash::{Hash, Hasher},
-	sync::{Arc, Mutex},
+	sync::{
+		atomic::{AtomicU32, Ordering},
+		Arc,
+	},
 };
-use tokio::runtime::Handle;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum NeoNetwork {
-	MainNet = 0x00746e41,
-	TestNet = 0x74746e41,
+	MainNet = 0x334f454e,
+	TestNet = 0x3554334e,
 	PrivateNet = 0x4e454e,
 }
 
 impl NeoNetwork {
 	pub fn to_magic(&self) -> u32 {
 		match self {
-			NeoNetwork::MainNet => 0x00746e41,
-			NeoNetwork::TestNet => 0x74746e41,
+			NeoNetwork::MainNet => 0x334f454e,
+			NeoNetwork::TestNet => 0x3554334e,
 			NeoNetwork::PrivateNet => 0x4e454e,
 		}
 	}
 	pub fn from_magic(magic: u32) -> Option<NeoNetwork> {
 		match magic {
-			0x00746e41 => Some(NeoNetwork::MainNet),
-			0x74746e41 => Some(NeoNetwork::TestNet),
+			0x334f454e => Some(NeoNetwork::MainNet),
+			0x3554334e => Some(NeoNetwork::TestNet),
 			0x4e454e => Some(NeoNetwork::PrivateNet),
 			_ => None,
 		}
@@ -35,26 +39,24 @@ pub const DEFAULT_BLOCK_TIME: u64 = 15_000;
 pub const DEFAULT_ADDRESS_VERSION: u8 = 0x35;
 pub const MAX_VALID_UNTIL_BLOCK_INCREMENT_BASE: u64 = 86_400_000;
 
-#[derive(Clone, Debug)]
+/// The configuration of a client.
+///
+/// A `NeoConfig` is a plain value. To share it between clients and update it at runtime, wrap it
+/// in a [`SharedConfig`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct NeoConfig {
 	pub network_magic: Option<u32>,
 	pub block_interval: u32,
 	pub max_valid_until_block_increment: u32,
 	pub polling_interval: u32,
-	pub executor: Arc<Mutex<Handle>>,
 	pub allows_transmission_on_fault: bool,
 	pub nns_resolver: H160,
-}
-
-impl Hash for NeoConfig {
-	fn hash<H: Hasher>(&self, state: &mut H) {
-		self.network_magic.hash(state);
-		self.block_interval.hash(state);
-		self.max_valid_until_block_increment.hash(state);
-		self.polling_interval.hash(state);
-		self.allows_transmission_on_fault.hash(state);
-		self.nns_resolver.hash(state);
-	}
+	pub address_version: u8,
+	/// The number of consensus nodes, if known
+	pub validators_count: Option<u32>,
+	/// The hashes of the native contracts by name, e.g. `GasToken`. Empty unless configured or
+	/// detected.
+	pub native_contracts: BTreeMap<String, H160>,
 }
 
 impl Default for NeoConfig {
@@ -65,7 +67,6 @@ impl Default for NeoConfig {
 			max_valid_until_block_increment: (MAX_VALID_UNTIL_BLOCK_INCREMENT_BASE
 				/ DEFAULT_BLOCK_TIME) as u32,
 			polling_interval: DEFAULT_BLOCK_TIME as u32,
-			executor: Arc::new(Mutex::new(tokio::runtime::Handle::current())),
 			allows_transmission_on_fault: false,
 			nns_resolver: H160::from_slice(
 				[
@@ -74,6 +75,9 @@ impl Default for NeoConfig {
 				]
 				.as_slice(),
 			),
+			address_version: DEFAULT_ADDRESS_VERSION,
+			validators_count: None,
+			native_contracts: BTreeMap::new(),
 		}
 	}
 }
@@ -85,7 +89,6 @@ impl NeoConfig {
 		block_interval: u32,
 		max_valid_until_block_increment: u32,
 		polling_interval: u32,
-		scheduled_executor_service: Arc<Mutex<Handle>>,
 		allows_transmission_on_fault: bool,
 		nns_resolver: [u8; 20],
 	) -> Self {
@@ -94,59 +97,274 @@ impl NeoConfig {
 			block_interval,
 			max_valid_until_block_increment,
 			polling_interval,
-			executor: scheduled_executor_service,
 			allows_transmission_on_fault,
 			nns_resolver: H160::from_slice(nns_resolver.as_slice()),
+			..Default::default()
 		}
 	}
 
+	/// A builder for the config of a custom or private network.
+	pub fn builder() -> NeoConfigBuilder {
+		NeoConfigBuilder::default()
+	}
+
+	/// Returns the hash of the native contract `name`, e.g. `GasToken`.
+	pub fn native_contract(&self, name: &str) -> Option<H160> {
+		self.native_contracts.get(name).copied()
+	}
+
 	// setters
 	pub fn set_polling_interval(&mut self, interval: u32) {
 		self.polling_interval = interval;
 	}
 
-	pub fn set_executor(&mut self, executor: Arc<Mutex<Handle>>) {
-		self.executor = executor;
+	pub fn set_network_magic(&mut self, magic: u32) {
+		self.network_magic = Some(magic);
+	}
+
+	pub fn set_allows_transmission_on_fault(&mut self, allows: bool) {
+		self.allows_transmission_on_fault = allows;
+	}
+}
+
+/// Builds a [`NeoConfig`], e.g. for a private network.
+///
+/// Values that are not set keep their defaults. Unless set explicitly, the polling interval
+/// follows the block time, and the `max_valid_until_block_increment` is derived from it like on
+/// the public networks.
+///
+/// ```
+/// use neo_config::NeoConfig;
+///
+/// let config = NeoConfig::builder()
+/// 	.network_magic(1234567890)
+/// 	.ms_per_block(1_000)
+/// 	.validators_count(1)
+/// 	.build();
+/// assert_eq!(config.network_magic, Some(1234567890));
+/// assert_eq!(config.polling_interval, 1_000);
+/// assert_eq!(config.max_valid_until_block_increment, 86_400);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NeoConfigBuilder {
+	config: NeoConfig,
+	block_interval: Option<u32>,
+	polling_interval: Option<u32>,
+	max_valid_until_block_increment: Option<u32>,
+}
+
+impl NeoConfigBuilder {
+	/// Sets the magic number of a known network.
+	#[must_use]
+	pub fn network(self, network: NeoNetwork) -> Self {
+		self.network_magic(network.to_magic())
+	}
+
+	/// Sets the network magic number.
+	#[must_use]
+	pub fn network_magic(mut self, magic: u32) -> Self {
+		self.config.network_magic = Some(magic);
+		self
+	}
+
+	/// Sets the address version.
+	#[must_use]
+	pub fn address_version(mut self, version: u8) -> Self {
+		self.config.address_version = version;
+		self
+	}
+
+	/// Sets the block time in milliseconds.
+	#[must_use]
+	pub fn ms_per_block(mut self, ms_per_block: u32) -> Self {
+		self.block_interval = Some(ms_per_block);
+		self
+	}
+
+	/// Sets the polling interval in milliseconds.
+	#[must_use]
+	pub fn polling_interval(mut self, interval: u32) -> Self {
+		self.polling_interval = Some(interval);
+		self
+	}
+
+	/// Sets the maximum number of blocks a transaction stays valid for.
+	#[must_use]
+	pub fn max_valid_until_block_increment(mut self, increment: u32) -> Self {
+		self.max_valid_until_block_increment = Some(increment);
+		self
+	}
+
+	/// Sets the number of consensus nodes.
+	#[must_use]
+	pub fn validators_count(mut self, count: u32) -> Self {
+		self.config.validators_count = Some(count);
+		self
+	}
+
+	/// Sets the hash of the native contract `name`, e.g. `GasToken`.
+	#[must_use]
+	pub fn native_contract(mut self, name: impl Into<String>, hash: H160) -> Self {
+		self.config.native_contracts.insert(name.into(), hash);
+		self
+	}
+
+	/// Sets the NNS resolver contract.
+	#[must_use]
+	pub fn nns_resolver(mut self, resolver: H160) -> Self {
+		self.config.nns_resolver = resolver;
+		self
 	}
 
-	pub fn set_network_magic(&mut self, magic: u32) -> Result<(), &'static str> {
-		if &magic > &0xFFFFFFFFu32 {
-			return Err("Network magic must fit in 32 bits")
+	/// Sets whether transactions whose test invocation faulted may be sent.
+	#[must_use]
+	pub fn allows_transmission_on_fault(mut self, allows: bool) -> Self {
+		self.config.allows_transmission_on_fault = allows;
+		self
+	}
+
+	/// Builds the config.
+	pub fn build(self) -> NeoConfig {
+		let mut config = self.config;
+		if let Some(block_interval) = self.block_interval {
+			config.block_interval = block_interval;
+			config.polling_interval = block_interval;
+			config.max_valid_until_block_increment =
+				(MAX_VALID_UNTIL_BLOCK_INCREMENT_BASE / u64::from(block_interval.max(1))) as u32;
+		}
+		if let Some(interval) = self.polling_interval {
+			config.polling_interval = interval;
 		}
+		if let Some(increment) = self.max_valid_until_block_increment {
+			config.max_valid_until_block_increment = increment;
+		}
+		config
+	}
+}
 
-		self.network_magic = Some(magic);
-		Ok(())
+impl From<NeoConfig> for NeoConfigBuilder {
+	fn from(config: NeoConfig) -> Self {
+		Self { config, ..Default::default() }
+	}
+}
+
+/// A [`NeoConfig`] shared between clients.
+///
+/// Readers take an immutable snapshot with [`SharedConfig::load`]. Updates build a new config and
+/// swap it in atomically, so they never block readers, never hold a lock across an `.await` and
+/// are visible to every clone of the handle. A snapshot taken before an update is not affected
+/// by it.
+#[derive(Clone, Debug, Default)]
+pub struct SharedConfig(Arc<ArcSwap<NeoConfig>>);
+
+impl SharedConfig {
+	pub fn new(config: NeoConfig) -> Self {
+		Self(Arc::new(ArcSwap::from_pointee(config)))
+	}
+
+	/// Returns a snapshot of the current config.
+	pub fn load(&self) -> Arc<NeoConfig> {
+		self.0.load_full()
+	}
+
+	/// Replaces the config.
+	pub fn store(&self, config: NeoConfig) {
+		self.0.store(Arc::new(config));
+	}
+
+	/// Applies `update` to a copy of the current config and swaps the result in. If another update
+	/// happens concurrently, `update` is applied again to the newer config, so it must not have
+	/// side effects.
+	pub fn update(&self, update: impl Fn(&mut NeoConfig)) {
+		self.0.rcu(|current| {
+			let mut config = NeoConfig::clone(current);
+			update(&mut config);
+			config
+		});
 	}
 
-	// other methods
+	pub fn set_network_magic(&self, magic: u32) {
+		self.update(|config| config.set_network_magic(magic));
+	}
+
+	pub fn set_polling_interval(&self, interval: u32) {
+		self.update(|config| config.set_polling_interval(interval));
+	}
+
+	pub fn set_allows_transmission_on_fault(&self, allows: bool) {
+		self.update(|config| config.set_allows_transmission_on_fault(allows));
+	}
+}
+
+impl From<NeoConfig> for SharedConfig {
+	fn from(config: NeoConfig) -> Self {
+		Self::new(config)
+	}
 }
 
 #[derive(Clone, Debug)]
 pub struct Counter {
-	count: Arc<Mutex<u32>>,
+	count: Arc<AtomicU32>,
 }
 
 impl Hash for Counter {
 	fn hash<H: Hasher>(&self, state: &mut H) {
-		self.count.lock().unwrap().hash(state);
+		self.count.load(Ordering::SeqCst).hash(state);
 	}
 }
 
 impl PartialEq for Counter {
 	fn eq(&self, other: &Self) -> bool {
-		*self.count.lock().unwrap() == *other.count.lock().unwrap()
+		self.count.load(Ordering::SeqCst) == other.count.load(Ordering::SeqCst)
 	}
 }
 
 impl Counter {
 	pub fn new() -> Self {
-		Counter { count: Arc::new(Mutex::new(1)) }
+		Counter { count: Arc::new(AtomicU32::new(1)) }
 	}
 
 	pub fn get_and_increment(&self) -> u32 {
-		let mut count = self.count.lock().unwrap();
-		let v: u32 = *count;
-		*count += 1;
-		v
+		self.count.fetch_add(1, Ordering::SeqCst)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_shared_config_updates() {
+		let shared = SharedConfig::default();
+		let clone = shared.clone();
+		let snapshot = shared.load();
+
+		clone.set_network_magic(NeoNetwork::TestNet.to_magic());
+		clone.set_polling_interval(1_000);
+
+		let config = shared.load();
+		assert_eq!(config.network_magic, Some(NeoNetwork::TestNet.to_magic()));
+		assert_eq!(config.polling_interval, 1_000);
+		assert_eq!(snapshot.network_magic, None);
+		assert_eq!(snapshot.polling_interval, DEFAULT_BLOCK_TIME as u32);
+	}
+
+	#[test]
+	fn test_builder() {
+		let gas = H160::repeat_byte(0xcf);
+		let config = NeoConfig::builder()
+			.network(NeoNetwork::TestNet)
+			.address_version(0x17)
+			.ms_per_block(5_000)
+			.polling_interval(1_000)
+			.native_contract("GasToken", gas)
+			.build();
+		assert_eq!(config.network_magic, Some(NeoNetwork::TestNet.to_magic()));
+		assert_eq!(config.address_version, 0x17);
+		assert_eq!(config.block_interval, 5_000);
+		assert_eq!(config.polling_interval, 1_000);
+		assert_eq!(config.max_valid_until_block_increment, 17_280);
+		assert_eq!(config.native_contract("GasToken"), Some(gas));
+		assert_eq!(NeoConfig::builder().build(), NeoConfig::default());
 	}
 }
@@ -0,0 +1,126 @@
+use crate::error::ContractError;
+use neo_providers::{
+	core::transaction::{signers::signer::Signer, transaction::Transaction},
+	Middleware, PendingTransaction,
+};
+use neo_types::{encoding, invocation_result::InvocationResult, Bytes};
+use std::sync::Arc;
+
+/// A script calling contract methods, with the signers of the transaction running it.
+///
+/// Returned by the contract methods changing state. The call is test invoked with
+/// [`ContractCall::call`] and sent with [`ContractCall::send`], both through the client of the
+/// contract, so that its middlewares, e.g. the signer or the policy middleware, apply.
+#[derive(Debug, Clone)]
+pub struct ContractCall<M> {
+	client: Arc<M>,
+	script: Bytes,
+	signers: Vec<Signer>,
+}
+
+impl<M: Middleware + 'static> ContractCall<M> {
+	/// A call running `script`, without signers.
+	pub fn new(client: Arc<M>, script: Bytes) -> Self {
+		Self { client, script, signers: Vec::new() }
+	}
+
+	/// Sets the signers of the transaction, the first one paying the fees.
+	pub fn set_signers(&mut self, signers: Vec<Signer>) -> &mut Self {
+		self.signers = signers;
+		self
+	}
+
+	pub fn script(&self) -> &Bytes {
+		&self.script
+	}
+
+	pub fn signers(&self) -> &[Signer] {
+		&self.signers
+	}
+
+	/// The client the call is sent through
+	pub fn client(&self) -> &Arc<M> {
+		&self.client
+	}
+
+	/// Test invokes the script with the signers, without changing the state of the chain.
+	pub async fn call(&self) -> Result<InvocationResult, ContractError> {
+		self.client
+			.invoke_script(encoding::base64_encode(&self.script), self.signers.clone())
+			.await
+			.map_err(ContractError::from_middleware_error)
+	}
+
+	/// The unsigned transaction running the script. The fees, the validity and the witnesses are
+	/// left to the middleware sending it.
+	pub fn transaction(&self) -> Transaction {
+		Transaction {
+			script: self.script.clone(),
+			signers: self.signers.clone(),
+			..Default::default()
+		}
+	}
+
+	/// Sends the transaction through the client, see [`ContractCall::transaction`]. The client
+	/// must sign it, e.g. a `SignerMiddleware`.
+	pub async fn send(&self) -> Result<PendingTransaction<'_, M::Provider>, ContractError> {
+		self.client
+			.send_transaction(self.transaction())
+			.await
+			.map_err(ContractError::from_middleware_error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{gas_token::GasToken, traits::fungible_token::FungibleTokenTrait};
+	use neo_providers::{
+		core::{
+			account::{Account, AccountTrait},
+			script::script_reader::ScriptReader,
+		},
+		Expectation, MockProvider, Provider,
+	};
+	use neo_types::natives;
+	use primitive_types::H160;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_transfer_is_invoked_through_the_client() {
+		let (provider, mock) = Provider::mocked();
+		let gas = GasToken::new(Some(Arc::new(provider)));
+		let from = Account::create().unwrap();
+		let to = H160::repeat_byte(1);
+
+		let call = gas.transfer_from_account(&from, &to, 5, None).await.unwrap();
+		let calls = ScriptReader::contract_calls(call.script()).unwrap();
+		assert_eq!(
+			calls[0].as_nep17_transfer(),
+			Some((natives::GAS_TOKEN.hash, from.get_script_hash(), to, 5))
+		);
+		assert_eq!(call.signers().len(), 1);
+		assert_eq!(*call.signers()[0].get_signer_hash(), from.get_script_hash());
+
+		let tx = call.transaction();
+		assert_eq!(&tx.script, call.script());
+		assert_eq!(tx.signers, call.signers());
+
+		mock.expect(Expectation::new("invokescript").returning(json!({
+			"script": encoding::base64_encode(call.script()),
+			"state": "HALT",
+			"gasconsumed": "997775",
+			"stack": [{ "type": "Boolean", "value": true }],
+		})));
+		let result = call.call().await.unwrap();
+		assert_eq!(result.gas_consumed, "997775");
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_calls_need_a_client() {
+		let gas = GasToken::<Provider<MockProvider>>::new(None);
+		let from = Account::create().unwrap();
+		assert!(gas.transfer_from_account(&from, &H160::zero(), 5, None).await.is_err());
+	}
+}
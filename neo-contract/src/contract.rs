@@ -0,0 +1,429 @@
+//! A deployed contract, described by its manifest.
+use crate::{error::ContractError, traits::smart_contract::SmartContractTrait};
+use async_trait::async_trait;
+use neo_codec::encode::NeoSerializable;
+use neo_providers::{
+	core::{
+		account::{Account, AccountTrait},
+		transaction::{
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction_template::TransactionTemplate,
+			witness::Witness,
+		},
+	},
+	Middleware, PendingTransaction,
+};
+use neo_types::{
+	contract_manifest::{ContractManifest, ContractMethod},
+	contract_parameter::ContractParameter,
+	contract_parameter_type::ContractParameterType,
+	encoding,
+	stack_item::FromStackItem,
+};
+use primitive_types::H160;
+use std::sync::Arc;
+
+/// A deployed contract, identified by its script hash and described by its manifest, that is
+/// called through the middleware stack `M`.
+///
+/// Methods are invoked by name, without generated bindings. The parameters are checked against
+/// the ABI in the manifest before anything is sent to the node.
+///
+/// Instances are returned by [`ContractFactory`](crate::factory::ContractFactory) once a
+/// deployment is persisted.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_contract::Contract;
+/// use neo_providers::{
+/// 	core::account::{Account, AccountTrait},
+/// 	Http, Middleware, Provider,
+/// };
+/// use neo_types::{
+/// 	contract_parameter::ContractParameter, contract_parameter_type::ContractParameterType,
+/// };
+/// use primitive_types::H160;
+/// use std::{convert::TryFrom, sync::Arc};
+///
+/// # async fn foo(token: H160, sender: Account, to: H160) -> Result<(), Box<dyn std::error::Error>>
+/// # {
+/// let provider = Arc::new(Provider::<Http>::try_from("http://localhost:10332")?);
+/// let manifest = provider.get_contract_state(token).await?.manifest;
+/// let contract = Contract::new(token, manifest, provider);
+/// let symbol: String = contract.call("symbol", vec![]).await?;
+/// let data = ContractParameter::new(ContractParameterType::Any);
+/// let params = vec![sender.get_script_hash().into(), to.into(), 1.into(), data];
+/// let receipt = contract.send("transfer", params, &sender).await?.await?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct Contract<M: Middleware> {
+	script_hash: H160,
+	manifest: ContractManifest,
+	client: Arc<M>,
+}
+
+impl<M: Middleware> Clone for Contract<M> {
+	fn clone(&self) -> Self {
+		Self {
+			script_hash: self.script_hash,
+			manifest: self.manifest.clone(),
+			client: self.client.clone(),
+		}
+	}
+}
+
+impl<M: Middleware + 'static> Contract<M> {
+	pub fn new(script_hash: H160, manifest: ContractManifest, client: Arc<M>) -> Self {
+		Self { script_hash, manifest, client }
+	}
+
+	/// The manifest of the contract
+	pub fn manifest(&self) -> &ContractManifest {
+		&self.manifest
+	}
+
+	/// Returns a new instance of the contract calling through `client`, e.g. a different
+	/// middleware stack on top of the same node.
+	pub fn connect<N: Middleware + 'static>(&self, client: Arc<N>) -> Contract<N> {
+		Contract::new(self.script_hash, self.manifest.clone(), client)
+	}
+
+	/// Test invokes `method` with `params` and decodes the first item of the result stack.
+	///
+	/// Nothing is persisted, so this is meant for read-only methods. Fails if `params` do not
+	/// match a method of the ABI or the invocation faults.
+	pub async fn call<R: FromStackItem>(
+		&self,
+		method: &str,
+		params: Vec<ContractParameter>,
+	) -> Result<R, ContractError> {
+		self.method(method, &params)?;
+		let output = self.call_invoke_function(method, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+		let item = output.stack.into_iter().next().ok_or_else(|| {
+			ContractError::UnexpectedReturnType(format!("{method} returned an empty stack"))
+		})?;
+		R::from_stack_item(item).map_err(|e| ContractError::UnexpectedReturnType(e.to_string()))
+	}
+
+	/// Sends a transaction invoking `method` with `params`, signed by `signer` who also pays the
+	/// fees and must hold its key pair.
+	///
+	/// Fails if `params` do not match a method of the ABI, or the test invocation run to
+	/// calculate the system fee faults.
+	pub async fn send(
+		&self,
+		method: &str,
+		params: Vec<ContractParameter>,
+		signer: &Account,
+	) -> Result<PendingTransaction<'_, M::Provider>, ContractError> {
+		self.method(method, &params)?;
+		let key_pair = signer.key_pair().as_ref().ok_or_else(|| {
+			ContractError::InvalidStateError(
+				"The signer does not hold a private key to sign the transaction".to_string(),
+			)
+		})?;
+		let script = self.build_invoke_function_script(method, params).await?;
+		let account_signer = AccountSigner::called_by_entry(signer)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let template =
+			TransactionTemplate::new(script, &[Signer::from(account_signer)], rand::random())
+				.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let mut tx = template
+			.instantiate_with(self.client.as_ref())
+			.await
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+
+		let network_magic = self
+			.client
+			.get_network_magic()
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		tx.set_network_magic(network_magic);
+		let witness = Witness::create(tx.get_hash_data(network_magic), key_pair)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		tx.add_witness(witness);
+
+		let sent = self
+			.client
+			.send_raw_transaction(encoding::base64_encode(tx.to_array()))
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		Ok(PendingTransaction::new(sent.hash, self.client.provider()))
+	}
+
+	/// Finds the method of the ABI named `name` that takes `params`.
+	///
+	/// Methods are overloaded by their number of parameters. A parameter matches if its type is
+	/// compatible with the declared one, see [`is_compatible`].
+	pub fn method(
+		&self,
+		name: &str,
+		params: &[ContractParameter],
+	) -> Result<&ContractMethod, ContractError> {
+		let abi = self.manifest.abi.as_ref().ok_or_else(|| {
+			ContractError::InvalidStateError("The manifest of the contract has no ABI".to_string())
+		})?;
+		let method = abi
+			.methods
+			.iter()
+			.find(|method| method.name == name && method.parameters.len() == params.len())
+			.ok_or_else(|| {
+				ContractError::InvalidArgError(format!(
+					"The contract has no method {name} taking {} parameters",
+					params.len()
+				))
+			})?;
+
+		for (declared, param) in method.parameters.iter().zip(params) {
+			if !is_compatible(&declared.get_type(), &param.get_type()) {
+				return Err(ContractError::InvalidArgError(format!(
+					"The parameter {} of {name} is a {}, not a {}",
+					declared.get_name().unwrap_or_default(),
+					declared.get_type(),
+					param.get_type()
+				)))
+			}
+		}
+		Ok(method)
+	}
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> SmartContractTrait for Contract<M> {
+	type M = M;
+
+	fn script_hash(&self) -> H160 {
+		self.script_hash
+	}
+
+	fn set_script_hash(&mut self, script_hash: H160) {
+		self.script_hash = script_hash;
+	}
+
+	fn client(&self) -> Option<&Arc<M>> {
+		Some(&self.client)
+	}
+
+	async fn get_manifest(&self) -> ContractManifest {
+		self.manifest.clone()
+	}
+}
+
+/// Whether a parameter of type `actual` can be passed where the ABI declares `declared`.
+///
+/// `Any` matches every type, and `Any` parameters, i.e. `null`, can be passed for every type.
+/// Byte strings are interchangeable in the VM, so strings, hashes, public keys and signatures
+/// can be passed for byte arrays and the other way around.
+pub fn is_compatible(declared: &ContractParameterType, actual: &ContractParameterType) -> bool {
+	let is_bytes = |typ: &ContractParameterType| {
+		matches!(
+			typ,
+			ContractParameterType::ByteArray
+				| ContractParameterType::String
+				| ContractParameterType::H160
+				| ContractParameterType::H256
+				| ContractParameterType::PublicKey
+				| ContractParameterType::Signature
+		)
+	};
+	declared == actual
+		|| *declared == ContractParameterType::Any
+		|| *actual == ContractParameterType::Any
+		|| (*declared == ContractParameterType::ByteArray && is_bytes(actual))
+		|| (*actual == ContractParameterType::ByteArray && is_bytes(declared))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures_util::TryStreamExt;
+	use neo_providers::{Expectation, MockProvider, Provider};
+	use neo_types::stack_item::StackItem;
+	use serde_json::json;
+
+	fn contract() -> Contract<Provider<MockProvider>> {
+		mocked_contract().0
+	}
+
+	fn mocked_contract() -> (Contract<Provider<MockProvider>>, MockProvider) {
+		let manifest = serde_json::from_value(json!({
+			"name": "Token",
+			"groups": [],
+			"features": {},
+			"supportedstandards": [],
+			"abi": {
+				"methods": [
+					{
+						"name": "mint",
+						"parameters": [{"name": "to", "type": "Hash160"}],
+						"returntype": "Void",
+						"offset": 0,
+						"safe": false
+					},
+					{
+						"name": "mint",
+						"parameters": [
+							{"name": "to", "type": "Hash160"},
+							{"name": "amount", "type": "Integer"}
+						],
+						"returntype": "Void",
+						"offset": 8,
+						"safe": false
+					},
+					{
+						"name": "setName",
+						"parameters": [{"name": "name", "type": "ByteArray"}],
+						"returntype": "Void",
+						"offset": 16,
+						"safe": false
+					}
+				],
+				"events": []
+			},
+			"permissions": [],
+			"trusts": [],
+			"extra": null
+		}))
+		.unwrap();
+		let (provider, mock) = Provider::mocked();
+		(Contract::new(H160::repeat_byte(1), manifest, Arc::new(provider)), mock)
+	}
+
+	fn version(session_enabled: bool) -> serde_json::Value {
+		json!({
+			"nonce": 1,
+			"useragent": "/Neo:3.6.0/",
+			"rpc": { "maxiteratorresultitems": 100, "sessionenabled": session_enabled }
+		})
+	}
+
+	fn tokens(stack: serde_json::Value, session: Option<&str>) -> serde_json::Value {
+		json!({
+			"script": "",
+			"state": "HALT",
+			"gasconsumed": "0",
+			"stack": [stack],
+			"session": session
+		})
+	}
+
+	#[test]
+	fn test_method_overloads() {
+		let contract = contract();
+		let to = ContractParameter::hash160(&H160::repeat_byte(2));
+
+		let method = contract.method("mint", &[to.clone()]).unwrap();
+		assert_eq!(method.offset, 0);
+		let method = contract.method("mint", &[to.clone(), ContractParameter::integer(5)]).unwrap();
+		assert_eq!(method.offset, 8);
+
+		assert!(matches!(contract.method("mint", &[]), Err(ContractError::InvalidArgError(_))));
+		assert!(contract.method("burn", &[to]).is_err());
+	}
+
+	#[test]
+	fn test_method_parameter_types() {
+		let contract = contract();
+		let to = ContractParameter::hash160(&H160::repeat_byte(2));
+
+		// strings and hashes are byte arrays in the VM
+		assert!(contract.method("setName", &[ContractParameter::string("name".into())]).is_ok());
+		assert!(contract.method("setName", &[to.clone()]).is_ok());
+		assert!(contract
+			.method("mint", &[ContractParameter::new(ContractParameterType::Any)])
+			.is_ok());
+
+		let err = contract.method("mint", &[ContractParameter::integer(5)]).unwrap_err();
+		assert!(
+			matches!(err, ContractError::InvalidArgError(message) if message.contains("parameter to "))
+		);
+		assert!(contract.method("mint", &[to, ContractParameter::bool(true)]).is_err());
+	}
+
+	#[test]
+	fn test_is_compatible() {
+		use ContractParameterType::*;
+
+		assert!(is_compatible(&ByteArray, &H160));
+		assert!(is_compatible(&H160, &ByteArray));
+		assert!(is_compatible(&ByteArray, &String));
+		assert!(is_compatible(&String, &ByteArray));
+		assert!(is_compatible(&Any, &Integer));
+		assert!(is_compatible(&PublicKey, &Any));
+		assert!(!is_compatible(&H160, &String));
+		assert!(!is_compatible(&H160, &H256));
+		assert!(!is_compatible(&Integer, &Boolean));
+		assert!(!is_compatible(&ByteArray, &Integer));
+	}
+
+	#[tokio::test]
+	async fn test_iterator_without_sessions_is_unwrapped() {
+		let (contract, mock) = mocked_contract();
+		mock.expect(Expectation::new("getversion").returning(version(false)));
+		mock.expect(Expectation::new("invokescript").returning(tokens(
+			json!({"type": "Array", "value": [
+				{"type": "Integer", "value": 1},
+				{"type": "Integer", "value": 2}
+			]}),
+			None,
+		)));
+
+		let mapper = Arc::new(|item: StackItem| item.as_int().unwrap_or_default());
+		let iterator = contract
+			.call_function_returning_iterator("tokens", vec![], mapper)
+			.await
+			.unwrap();
+		assert!(!iterator.has_session());
+		let items: Vec<i64> = iterator.try_collect().await.unwrap();
+		assert_eq!(items, vec![1, 2]);
+		mock.assert_expectations_met().unwrap();
+		assert_eq!(mock.request_count("invokefunction"), 0);
+	}
+
+	#[tokio::test]
+	async fn test_iterator_in_session() {
+		let (contract, mock) = mocked_contract();
+		mock.expect(Expectation::new("getversion").returning(version(true)));
+		mock.expect(Expectation::new("invokefunction").returning(tokens(
+			json!({"type": "InteropInterface", "interface": "IIterator", "id": "iterator"}),
+			Some("session"),
+		)));
+
+		let mapper = Arc::new(|item: StackItem| item.as_int().unwrap_or_default());
+		let iterator = contract
+			.call_function_returning_iterator("tokens", vec![], mapper)
+			.await
+			.unwrap();
+		assert!(iterator.has_session());
+		mock.assert_expectations_met().unwrap();
+		assert_eq!(mock.request_count("invokescript"), 0);
+	}
+
+	#[tokio::test]
+	async fn test_iterator_without_session_id_is_unwrapped() {
+		// the node reports sessions but answers without one
+		let (contract, mock) = mocked_contract();
+		mock.expect(Expectation::new("getversion").returning(version(true)));
+		mock.expect(Expectation::new("invokefunction").returning(tokens(
+			json!({"type": "InteropInterface", "interface": "IIterator", "id": "iterator"}),
+			None,
+		)));
+		mock.expect(Expectation::new("invokescript").returning(tokens(
+			json!({"type": "Array", "value": [{"type": "Integer", "value": 3}]}),
+			None,
+		)));
+
+		let mapper = Arc::new(|item: StackItem| item.as_int().unwrap_or_default());
+		let iterator = contract
+			.call_function_returning_iterator("tokens", vec![], mapper)
+			.await
+			.unwrap();
+		assert!(!iterator.has_session());
+		let items: Vec<i64> = iterator.try_collect().await.unwrap();
+		assert_eq!(items, vec![3]);
+		mock.assert_expectations_met().unwrap();
+	}
+}
@@ -1,10 +1,7 @@
-use crate::{error::ContractError, traits::smart_contract::SmartContractTrait};
+use crate::{call::ContractCall, error::ContractError, traits::smart_contract::SmartContractTrait};
 use async_trait::async_trait;
 use futures::{FutureExt, TryFutureExt};
-use neo_providers::{
-	core::{account::AccountTrait, transaction::transaction_builder::TransactionBuilder},
-	JsonRpcClient, Middleware, Provider,
-};
+use neo_providers::{core::account::AccountTrait, Middleware};
 use neo_types::{
 	contract_parameter::ContractParameter,
 	contract_state::{ContractIdentifiers, ContractState},
@@ -14,26 +11,26 @@ use neo_types::{
 };
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use thiserror::__private::ThiserrorProvide;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ContractManagement<'a, P: JsonRpcClient> {
+pub struct ContractManagement<M: Middleware> {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	script_hash: ScriptHash,
 	#[serde(skip)]
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> ContractManagement<'a, P> {
-	pub fn new(script_hash: H160, provider: Option<&'a Provider<P>>) -> Self {
-		Self { script_hash, provider }
+impl<M: Middleware + 'static> ContractManagement<M> {
+	pub fn new(script_hash: H160, client: Option<Arc<M>>) -> Self {
+		Self { script_hash, client }
 	}
 
 	pub async fn get_minimum_deployment_fee(&self) -> Result<u64, ContractError> {
 		Ok(self
-			.provider
-			.unwrap()
+			.require_client()?
 			.invoke_function(&self.script_hash, "getMinimumDeploymentFee".to_string(), vec![], None)
 			.await
 			.unwrap()
@@ -44,8 +41,7 @@ impl<'a, P: JsonRpcClient> ContractManagement<'a, P> {
 
 	pub async fn set_minimum_deployment_fee(&self, fee: u64) -> Result<u64, ContractError> {
 		Ok(self
-			.provider
-			.unwrap()
+			.require_client()?
 			.invoke_function(
 				&self.script_hash,
 				"setMinimumDeploymentFee".to_string(),
@@ -60,8 +56,7 @@ impl<'a, P: JsonRpcClient> ContractManagement<'a, P> {
 	}
 
 	pub async fn get_contract(&self, hash: H160) -> Result<ContractState, ContractError> {
-		self.provider
-			.unwrap()
+		self.require_client()?
 			.get_contract_state(hash)
 			.await
 			.map_err(|e| ContractError::RuntimeError(e.to_string()))
@@ -74,8 +69,7 @@ impl<'a, P: JsonRpcClient> ContractManagement<'a, P> {
 
 	pub async fn get_contract_hash_by_id(&self, id: u32) -> Result<ScriptHash, ContractError> {
 		let result = self
-			.provider
-			.unwrap()
+			.require_client()?
 			.invoke_function(
 				&self.script_hash,
 				"getContractById".to_string(),
@@ -91,16 +85,11 @@ impl<'a, P: JsonRpcClient> ContractManagement<'a, P> {
 	}
 
 	pub async fn get_contract_hashes(&self) -> Result<ContractIdentifiers, ContractError> {
-		self.provider
-			.unwrap()
+		self.require_client()?
 			.invoke_function(&self.script_hash, "getContractHashes".to_string(), vec![], None)
 			.await
 			.map(|item| ContractIdentifiers::try_from(item).unwrap())
-			.map_err(|e| {
-				// Convert ProviderError to ContractError here
-				// This assumes you have a way to convert from ProviderError to ContractError
-				ContractError::from(e)
-			})
+			.map_err(ContractError::from_middleware_error)
 	}
 
 	pub async fn has_method(
@@ -109,8 +98,7 @@ impl<'a, P: JsonRpcClient> ContractManagement<'a, P> {
 		method: &str,
 		params: usize,
 	) -> Result<bool, ContractError> {
-		self.provider
-			.unwrap()
+		self.require_client()?
 			.invoke_function(
 				&self.script_hash,
 				"hasMethod".to_string(),
@@ -127,7 +115,7 @@ impl<'a, P: JsonRpcClient> ContractManagement<'a, P> {
 		nef: &NefFile,
 		manifest: &[u8],
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		let params = vec![nef.into(), manifest.into(), data.unwrap()];
 		let tx = self.invoke_function("deploy", params).await;
 		tx
@@ -136,8 +124,8 @@ impl<'a, P: JsonRpcClient> ContractManagement<'a, P> {
 
 // Other types and helpers
 #[async_trait]
-impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for ContractManagement<'a, P> {
-	type P = P;
+impl<M: Middleware + 'static> SmartContractTrait for ContractManagement<M> {
+	type M = M;
 
 	fn script_hash(&self) -> H160 {
 		self.script_hash.clone()
@@ -147,7 +135,7 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for ContractManagement<'a, P>
 		self.script_hash = script_hash;
 	}
 
-	fn provider(&self) -> Option<&Provider<P>> {
-		self.provider
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
 	}
 }
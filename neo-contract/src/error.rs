@@ -1,4 +1,5 @@
-use neo_providers::ProviderError;
+use neo_providers::{MiddlewareError, ProviderError};
+use neo_types::invocation_result::VmFault;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -23,4 +24,16 @@ pub enum ContractError {
 	InvalidArgError(String),
 	#[error(transparent)]
 	ProviderError(#[from] ProviderError),
+	#[error(transparent)]
+	VmFault(#[from] VmFault),
+	/// An error of the middleware stack the contract sends its requests through
+	#[error("Middleware error: {0}")]
+	MiddlewareError(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl ContractError {
+	/// Wraps an error of the middleware the contract is connected to.
+	pub fn from_middleware_error<E: MiddlewareError + 'static>(e: E) -> Self {
+		ContractError::MiddlewareError(Box::new(e))
+	}
 }
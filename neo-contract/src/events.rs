@@ -0,0 +1,385 @@
+//! Decoding of contract notifications into typed events.
+//!
+//! Notifications only carry the name of the event and its arguments as raw stack items. An
+//! [`EventDecoder`] knows the events declared in the ABI of contract manifests and uses their
+//! parameter types to decode the arguments into [`EventValue`]s, named after the parameters.
+use crate::error::ContractError;
+use neo_crypto::keys::Secp256r1PublicKey;
+use neo_providers::core::responses::neo_application_log::ApplicationLog;
+use neo_types::{
+	contract_manifest::{ContractEvent, ContractManifest},
+	contract_parameter_type::ContractParameterType,
+	invocation_result::Notification,
+	stack_item::StackItem,
+};
+use primitive_types::{H160, H256};
+use std::collections::HashMap;
+
+/// An argument of an event, decoded according to the declared parameter type
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventValue {
+	Any(StackItem),
+	Boolean(bool),
+	Integer(i64),
+	ByteArray(Vec<u8>),
+	String(String),
+	Hash160(H160),
+	Hash256(H256),
+	PublicKey(Secp256r1PublicKey),
+	Signature(Vec<u8>),
+	Array(Vec<StackItem>),
+	Map(HashMap<StackItem, StackItem>),
+	/// A null value, e.g. the `from` of a NEP-17 `Transfer` minting tokens
+	Null,
+}
+
+impl EventValue {
+	/// Decodes `item` as a value of type `typ`.
+	pub fn decode(typ: &ContractParameterType, item: &StackItem) -> Result<Self, ContractError> {
+		if matches!(item, StackItem::Any) {
+			return Ok(EventValue::Null)
+		}
+		let unexpected =
+			|| ContractError::UnexpectedReturnType(format!("{typ:?}, got {}", item.to_string()));
+		let value = match typ {
+			ContractParameterType::Boolean =>
+				EventValue::Boolean(item.as_bool().ok_or_else(unexpected)?),
+			ContractParameterType::Integer =>
+				EventValue::Integer(item.as_int().ok_or_else(unexpected)?),
+			ContractParameterType::ByteArray =>
+				EventValue::ByteArray(item.as_bytes().ok_or_else(unexpected)?),
+			ContractParameterType::String => EventValue::String(
+				item.as_bytes()
+					.and_then(|bytes| String::from_utf8(bytes).ok())
+					.ok_or_else(unexpected)?,
+			),
+			// hashes are little-endian on the stack
+			ContractParameterType::H160 => EventValue::Hash160(
+				item.as_bytes()
+					.filter(|bytes| bytes.len() == 20)
+					.map(|bytes| H160::from_slice(&reversed(bytes)))
+					.ok_or_else(unexpected)?,
+			),
+			ContractParameterType::H256 => EventValue::Hash256(
+				item.as_bytes()
+					.filter(|bytes| bytes.len() == 32)
+					.map(|bytes| H256::from_slice(&reversed(bytes)))
+					.ok_or_else(unexpected)?,
+			),
+			ContractParameterType::PublicKey =>
+				EventValue::PublicKey(item.as_public_key().ok_or_else(unexpected)?),
+			ContractParameterType::Signature =>
+				EventValue::Signature(item.as_bytes().ok_or_else(unexpected)?),
+			ContractParameterType::Array =>
+				EventValue::Array(item.as_array().ok_or_else(unexpected)?),
+			ContractParameterType::Map => EventValue::Map(item.as_map().ok_or_else(unexpected)?),
+			_ => EventValue::Any(item.clone()),
+		};
+		Ok(value)
+	}
+
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			EventValue::Boolean(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_int(&self) -> Option<i64> {
+		match self {
+			EventValue::Integer(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_bytes(&self) -> Option<&[u8]> {
+		match self {
+			EventValue::ByteArray(value) | EventValue::Signature(value) => Some(value),
+			_ => None,
+		}
+	}
+
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			EventValue::String(value) => Some(value),
+			_ => None,
+		}
+	}
+
+	pub fn as_hash160(&self) -> Option<H160> {
+		match self {
+			EventValue::Hash160(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_hash256(&self) -> Option<H256> {
+		match self {
+			EventValue::Hash256(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn is_null(&self) -> bool {
+		matches!(self, EventValue::Null)
+	}
+}
+
+fn reversed(mut bytes: Vec<u8>) -> Vec<u8> {
+	bytes.reverse();
+	bytes
+}
+
+/// A notification decoded with the ABI of the emitting contract
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedEvent {
+	pub contract: H160,
+	pub name: String,
+	/// The arguments in declaration order, with the names of the parameters
+	pub params: Vec<(String, EventValue)>,
+}
+
+impl DecodedEvent {
+	/// The argument of the parameter `name`
+	pub fn param(&self, name: &str) -> Option<&EventValue> {
+		self.params.iter().find(|(param, _)| param == name).map(|(_, value)| value)
+	}
+
+	/// Converts the event into the typed event `E`.
+	pub fn parse<E: ContractEventType>(&self) -> Result<E, ContractError> {
+		if self.name != E::NAME {
+			return Err(ContractError::InvalidArgError(format!(
+				"Expected event {}, got {}",
+				E::NAME,
+				self.name
+			)))
+		}
+		E::from_event(self)
+	}
+}
+
+/// A typed contract event, decoded from a [`DecodedEvent`] with the name [`Self::NAME`].
+pub trait ContractEventType: Sized {
+	const NAME: &'static str;
+
+	fn from_event(event: &DecodedEvent) -> Result<Self, ContractError>;
+}
+
+/// The `Transfer` event of NEP-17 tokens. `from` is `None` when tokens are minted, `to` when
+/// tokens are burned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nep17Transfer {
+	pub from: Option<H160>,
+	pub to: Option<H160>,
+	pub amount: i64,
+}
+
+impl ContractEventType for Nep17Transfer {
+	const NAME: &'static str = "Transfer";
+
+	fn from_event(event: &DecodedEvent) -> Result<Self, ContractError> {
+		let missing = |name: &str| {
+			ContractError::UnexpectedReturnType(format!("Transfer without valid {name} argument"))
+		};
+		let account = |name: &str| match event.param(name) {
+			Some(EventValue::Null) => Ok(None),
+			Some(value) => value.as_hash160().map(Some).ok_or_else(|| missing(name)),
+			None => Err(missing(name)),
+		};
+		Ok(Self {
+			from: account("from")?,
+			to: account("to")?,
+			amount: event
+				.param("amount")
+				.and_then(EventValue::as_int)
+				.ok_or_else(|| missing("amount"))?,
+		})
+	}
+}
+
+/// Decodes notifications using the events declared in contract manifests.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_contract::events::{EventDecoder, Nep17Transfer};
+/// # use neo_providers::core::responses::neo_application_log::ApplicationLog;
+/// # use neo_types::contract_manifest::ContractManifest;
+/// # use primitive_types::H160;
+///
+/// # fn foo(token: H160, manifest: ContractManifest, log: ApplicationLog) -> Result<(), Box<dyn std::error::Error>> {
+/// let decoder = EventDecoder::new().with_manifest(token, &manifest);
+/// for event in decoder.decode_log(&log)? {
+/// 	if event.name == "Transfer" {
+/// 		let transfer: Nep17Transfer = event.parse()?;
+/// 		println!("{:?} -> {:?}: {}", transfer.from, transfer.to, transfer.amount);
+/// 	}
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EventDecoder {
+	events: HashMap<H160, HashMap<String, ContractEvent>>,
+}
+
+impl EventDecoder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the events declared in the ABI of `manifest`, the manifest of `contract`.
+	pub fn register(&mut self, contract: H160, manifest: &ContractManifest) {
+		let events = manifest.abi.as_ref().map(|abi| abi.events.as_slice()).unwrap_or_default();
+		for event in events {
+			self.register_event(contract, event.clone());
+		}
+	}
+
+	/// Registers a single event of `contract`, replacing an event with the same name.
+	pub fn register_event(&mut self, contract: H160, event: ContractEvent) {
+		self.events.entry(contract).or_default().insert(event.name.clone(), event);
+	}
+
+	#[must_use]
+	pub fn with_manifest(mut self, contract: H160, manifest: &ContractManifest) -> Self {
+		self.register(contract, manifest);
+		self
+	}
+
+	/// Returns `true` if events of `contract` were registered.
+	pub fn is_registered(&self, contract: &H160) -> bool {
+		self.events.contains_key(contract)
+	}
+
+	/// Decodes `notification`. Returns `None` if the event is unknown.
+	pub fn decode(
+		&self,
+		notification: &Notification,
+	) -> Result<Option<DecodedEvent>, ContractError> {
+		let Some(event) = self
+			.events
+			.get(&notification.contract)
+			.and_then(|events| events.get(&notification.event_name))
+		else {
+			return Ok(None)
+		};
+
+		let args = notification.state.as_array().ok_or_else(|| {
+			ContractError::UnexpectedReturnType(format!(
+				"Arguments of {} are not an array",
+				notification.event_name
+			))
+		})?;
+		if args.len() != event.parameters.len() {
+			return Err(ContractError::UnexpectedReturnType(format!(
+				"{} expects {} arguments, got {}",
+				event.name,
+				event.parameters.len(),
+				args.len()
+			)))
+		}
+
+		let params = event
+			.parameters
+			.iter()
+			.zip(&args)
+			.enumerate()
+			.map(|(i, (param, arg))| {
+				let name =
+					param.get_name().map(str::to_string).unwrap_or_else(|| format!("arg{i}"));
+				Ok((name, EventValue::decode(&param.get_type(), arg)?))
+			})
+			.collect::<Result<_, ContractError>>()?;
+		Ok(Some(DecodedEvent { contract: notification.contract, name: event.name.clone(), params }))
+	}
+
+	/// Decodes the notifications of all executions in `log`, skipping unknown events.
+	pub fn decode_log(&self, log: &ApplicationLog) -> Result<Vec<DecodedEvent>, ContractError> {
+		let notifications = log.executions.iter().flat_map(|execution| &execution.notifications);
+		notifications
+			.filter_map(|notification| self.decode(notification).transpose())
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_types::natives;
+	use serde_json::json;
+
+	fn transfer_event() -> ContractEvent {
+		serde_json::from_value(json!({
+			"name": "Transfer",
+			"parameters": [
+				{"name": "from", "type": "Hash160"},
+				{"name": "to", "type": "Hash160"},
+				{"name": "amount", "type": "Integer"}
+			]
+		}))
+		.unwrap()
+	}
+
+	/// A GAS transfer to the NEO contract, with the hashes in their little-endian stack order
+	fn notification(from: serde_json::Value) -> Notification {
+		serde_json::from_value(json!({
+			"contract": "0xd2a4cff31913016155e38e474a2c06d08be276cf",
+			"eventname": "Transfer",
+			"state": {"type": "Array", "value": [
+				from,
+				{"type": "ByteString", "value": "f563ea40bc283d4d0e05c48ea305b3f2a07340ef"},
+				{"type": "Integer", "value": 100000000}
+			]}
+		}))
+		.unwrap()
+	}
+
+	fn decoder() -> EventDecoder {
+		let mut decoder = EventDecoder::new();
+		decoder.register_event(natives::GAS_TOKEN.hash, transfer_event());
+		decoder
+	}
+
+	#[test]
+	fn test_decode_transfer() {
+		let from =
+			json!({"type": "ByteString", "value": "cf76e28bd0062c4a478ee35561011319f3cfa4d2"});
+		let event = decoder().decode(&notification(from)).unwrap().unwrap();
+		assert_eq!(event.contract, natives::GAS_TOKEN.hash);
+		assert_eq!(event.param("from"), Some(&EventValue::Hash160(natives::GAS_TOKEN.hash)));
+
+		let transfer: Nep17Transfer = event.parse().unwrap();
+		assert_eq!(
+			transfer,
+			Nep17Transfer {
+				from: Some(natives::GAS_TOKEN.hash),
+				to: Some(natives::NEO_TOKEN.hash),
+				amount: 100_000_000,
+			}
+		);
+	}
+
+	#[test]
+	fn test_decode_mint() {
+		let event = decoder().decode(&notification(json!({"type": "Any"}))).unwrap().unwrap();
+		assert!(event.param("from").unwrap().is_null());
+
+		let transfer: Nep17Transfer = event.parse().unwrap();
+		assert_eq!(transfer.from, None);
+		assert_eq!(transfer.to, Some(natives::NEO_TOKEN.hash));
+	}
+
+	#[test]
+	fn test_decode_unknown_and_invalid_events() {
+		let mut unknown = notification(json!({"type": "Any"}));
+		unknown.event_name = "Approval".to_string();
+		assert_eq!(decoder().decode(&unknown).unwrap(), None);
+
+		let short_hash = json!({"type": "ByteString", "value": "cf76e28b"});
+		assert!(decoder().decode(&notification(short_hash)).is_err());
+
+		let mut missing_arg = notification(json!({"type": "Any"}));
+		missing_arg.state = StackItem::Array { value: vec![StackItem::Any] };
+		assert!(decoder().decode(&missing_arg).is_err());
+	}
+}
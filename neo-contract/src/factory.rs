@@ -0,0 +1,261 @@
+//! Deployment of contracts, see [`ContractFactory`].
+use crate::{
+	contract::Contract, contract_management::ContractManagement, error::ContractError,
+	traits::smart_contract::SmartContractTrait,
+};
+use neo_codec::encode::NeoSerializable;
+use neo_providers::{
+	core::{
+		account::{Account, AccountTrait},
+		script::script_builder::ScriptBuilder,
+		transaction::{
+			call_flags::CallFlags,
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction::Transaction,
+			transaction_template::TransactionTemplate,
+			witness::Witness,
+		},
+	},
+	Middleware, PendingTransaction,
+};
+use neo_types::{
+	contract_manifest::ContractManifest, contract_parameter::ContractParameter, encoding, gas::Gas,
+	natives, nef_file::NefFile, Bytes,
+};
+use primitive_types::H160;
+use std::{sync::Arc, time::Duration};
+
+/// Deploys a contract from its NEF file and manifest.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_contract::factory::ContractFactory;
+/// use neo_providers::{core::account::Account, Http, Provider};
+/// use neo_types::{contract_manifest::ContractManifest, nef_file::NefFile};
+/// use std::{convert::TryFrom, sync::Arc};
+///
+/// # async fn foo(
+/// # 	nef: NefFile,
+/// # 	manifest: ContractManifest,
+/// # 	sender: Account,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let factory = ContractFactory::new(nef, manifest, Arc::new(provider));
+/// let deployer = factory.deploy(None)?;
+/// println!("deploying for {}", deployer.estimate_fees(&sender).await?);
+/// let contract = deployer.send(&sender).await?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct ContractFactory<M: Middleware> {
+	nef: NefFile,
+	manifest: ContractManifest,
+	client: Arc<M>,
+}
+
+impl<M: Middleware + 'static> ContractFactory<M> {
+	pub fn new(nef: NefFile, manifest: ContractManifest, client: Arc<M>) -> Self {
+		Self { nef, manifest, client }
+	}
+
+	/// Creates a deployment of the contract, passing `data` to its `_deploy` method.
+	///
+	/// Fails if the manifest has no name, since the hash of the contract is derived from it.
+	pub fn deploy(&self, data: Option<ContractParameter>) -> Result<Deployer<M>, ContractError> {
+		let name = self.manifest.name.clone().ok_or_else(|| {
+			ContractError::InvalidArgError("The manifest has no name".to_string())
+		})?;
+		let manifest = serde_json::to_string(&self.manifest)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+
+		let mut params = vec![
+			ContractParameter::byte_array(self.nef.to_array()),
+			ContractParameter::string(manifest),
+		];
+		params.extend(data);
+		let script = ScriptBuilder::new()
+			.contract_call(&natives::CONTRACT_MANAGEMENT.hash, "deploy", &params, CallFlags::All)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
+			.to_bytes();
+
+		Ok(Deployer {
+			script,
+			name,
+			nef_checksum: self.nef.checksum(),
+			manifest: self.manifest.clone(),
+			client: self.client.clone(),
+			confirmations: 1,
+			interval: None,
+		})
+	}
+}
+
+/// A pending deployment created by [`ContractFactory::deploy`].
+#[derive(Debug)]
+pub struct Deployer<M: Middleware> {
+	script: Bytes,
+	name: String,
+	nef_checksum: u32,
+	manifest: ContractManifest,
+	client: Arc<M>,
+	confirmations: usize,
+	interval: Option<Duration>,
+}
+
+impl<M: Middleware + 'static> Deployer<M> {
+	/// Sets the number of blocks that must be persisted on top of the deployment before
+	/// [`Deployer::send`] returns.
+	#[must_use]
+	pub fn confirmations(mut self, confirmations: usize) -> Self {
+		self.confirmations = confirmations;
+		self
+	}
+
+	/// Sets the interval at which the node is polled for the deployment.
+	#[must_use]
+	pub fn interval(mut self, interval: Duration) -> Self {
+		self.interval = Some(interval);
+		self
+	}
+
+	/// The script calling `ContractManagement.deploy`
+	pub fn script(&self) -> &Bytes {
+		&self.script
+	}
+
+	/// The hash of the contract once deployed by `sender`.
+	pub fn contract_hash(&self, sender: &H160) -> Result<H160, ContractError> {
+		ContractManagement::<M>::calc_contract_hash(*sender, self.nef_checksum, &self.name)
+	}
+
+	/// Builds the unsigned deployment transaction sent by `sender`, with its fees.
+	///
+	/// The system fee includes the deployment fee charged by `ContractManagement`, which depends
+	/// on the size of the NEF file and manifest and is at least the minimum deployment fee.
+	pub async fn build_transaction(&self, sender: &Account) -> Result<Transaction, ContractError> {
+		let signer = AccountSigner::called_by_entry(sender)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let template =
+			TransactionTemplate::new(self.script.clone(), &[Signer::from(signer)], rand::random())
+				.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let mut tx = template
+			.instantiate_with(self.client.as_ref())
+			.await
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		tx.set_network_magic(self.network_magic().await?);
+		Ok(tx)
+	}
+
+	/// Estimates the GAS `sender` pays to deploy the contract, the system and network fee of the
+	/// deployment transaction.
+	pub async fn estimate_fees(&self, sender: &Account) -> Result<Gas, ContractError> {
+		let tx = self.build_transaction(sender).await?;
+		Ok(tx.sys_fee.saturating_add(tx.net_fee))
+	}
+
+	/// Signs and sends the deployment transaction and waits until it is persisted.
+	///
+	/// `sender` pays the fees and must hold its key pair. Fails if the transaction is dropped or
+	/// the deployment faults.
+	pub async fn send(self, sender: &Account) -> Result<Contract<M>, ContractError> {
+		let key_pair = sender.key_pair().as_ref().ok_or_else(|| {
+			ContractError::InvalidStateError(
+				"The sender does not hold a private key to sign the deployment".to_string(),
+			)
+		})?;
+		let mut tx = self.build_transaction(sender).await?;
+		let hash_data = tx.get_hash_data(self.network_magic().await?);
+		let witness = Witness::create(hash_data, key_pair)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		tx.add_witness(witness);
+
+		let sent = self
+			.client
+			.send_raw_transaction(encoding::base64_encode(tx.to_array()))
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		let mut pending = PendingTransaction::new(sent.hash, self.client.provider())
+			.confirmations(self.confirmations);
+		if let Some(interval) = self.interval {
+			pending = pending.interval(interval);
+		}
+		pending.await_success().await?;
+
+		let script_hash = self.contract_hash(&sender.get_script_hash())?;
+		Ok(Contract::new(script_hash, self.manifest, self.client))
+	}
+
+	async fn network_magic(&self) -> Result<u32, ContractError> {
+		self.client
+			.get_network_magic()
+			.await
+			.map_err(ContractError::from_middleware_error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_codec::Decoder;
+	use neo_providers::{
+		core::script::script_reader::{ScriptReader, ScriptValue},
+		MockProvider, Provider,
+	};
+	use serde_json::json;
+	use std::str::FromStr;
+
+	/// A NEF file in the layout written by nccs, with one method token and a checksum over the
+	/// double SHA-256 of the file
+	const NEF: &str = concat!(
+		"4e4546334e656f2e436f6d70696c65722e43536861727020332e362e3000000000000000000000000000",
+		"0000000000000000000000000000000000000000000000000000000001cf76e28bd0062c4a478ee35561",
+		"011319f3cfa4d20962616c616e63654f660100010f00001a0c140102030405060708090a0b0c0d0e0f10",
+		"11121314370000401ec390c0",
+	);
+
+	fn factory(manifest: serde_json::Value) -> ContractFactory<Provider<MockProvider>> {
+		let nef = NefFile::decode(&mut Decoder::new(&hex::decode(NEF).unwrap())).unwrap();
+		let (provider, _) = Provider::mocked();
+		ContractFactory::new(nef, serde_json::from_value(manifest).unwrap(), Arc::new(provider))
+	}
+
+	#[test]
+	fn test_deploy_script() {
+		let factory = factory(json!({"name": "Token", "extra": null}));
+		let manifest = serde_json::to_string(&factory.manifest).unwrap();
+
+		let deployer = factory.deploy(None).unwrap();
+		let calls = ScriptReader::contract_calls(deployer.script()).unwrap();
+		assert_eq!(calls.len(), 1);
+		assert_eq!(calls[0].contract, natives::CONTRACT_MANAGEMENT.hash);
+		assert_eq!(calls[0].method, "deploy");
+		assert_eq!(
+			calls[0].args,
+			vec![
+				ScriptValue::Bytes(hex::decode(NEF).unwrap()),
+				ScriptValue::Bytes(manifest.into_bytes())
+			]
+		);
+
+		let deployer = factory.deploy(Some(ContractParameter::integer(7))).unwrap();
+		let calls = ScriptReader::contract_calls(deployer.script()).unwrap();
+		assert_eq!(calls[0].args[2], ScriptValue::Integer(7));
+	}
+
+	#[test]
+	fn test_contract_hash() {
+		let factory = factory(json!({"name": "Token", "extra": null}));
+		assert_eq!(factory.nef.checksum(), 0xc090c31e);
+
+		let sender = H160::from_str("69ecca587293047be4c59159bf8bc399985c160d").unwrap();
+		let hash = factory.deploy(None).unwrap().contract_hash(&sender).unwrap();
+		assert_eq!(hash, H160::from_str("a079dacdbf6257aff98ce3eb25341cc99e7b9e09").unwrap());
+	}
+
+	#[test]
+	fn test_deploy_needs_a_name() {
+		let factory = factory(json!({"extra": null}));
+		assert!(matches!(factory.deploy(None), Err(ContractError::InvalidArgError(_))));
+	}
+}
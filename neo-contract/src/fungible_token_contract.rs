@@ -5,33 +5,34 @@ use crate::{
 	},
 };
 use async_trait::async_trait;
-use neo_providers::{JsonRpcClient, Provider};
+use neo_providers::Middleware;
 use neo_types::nns_name::NNSName;
 use primitive_types::H160;
+use std::sync::Arc;
 
 #[derive(Debug)]
-pub struct FungibleTokenContract<'a, P: JsonRpcClient> {
+pub struct FungibleTokenContract<M: Middleware> {
 	script_hash: H160,
 	total_supply: Option<u64>,
 	decimals: Option<u8>,
 	symbol: Option<String>,
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> FungibleTokenContract<'a, P> {
-	pub fn new(script_hash: &H160, provider: Option<&'a Provider<P>>) -> Self {
+impl<M: Middleware + 'static> FungibleTokenContract<M> {
+	pub fn new(script_hash: &H160, client: Option<Arc<M>>) -> Self {
 		Self {
 			script_hash: script_hash.clone(),
 			total_supply: None,
 			decimals: None,
 			symbol: None,
-			provider,
+			client,
 		}
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for FungibleTokenContract<'a, P> {
+impl<M: Middleware + 'static> TokenTrait for FungibleTokenContract<M> {
 	fn total_supply(&self) -> Option<u64> {
 		self.total_supply
 	}
@@ -62,8 +63,8 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for FungibleTokenContract<'a, P> {
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for FungibleTokenContract<'a, P> {
-	type P = P;
+impl<M: Middleware + 'static> SmartContractTrait for FungibleTokenContract<M> {
+	type M = M;
 
 	fn script_hash(&self) -> H160 {
 		self.script_hash
@@ -73,10 +74,10 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for FungibleTokenContract<'a,
 		self.script_hash = script_hash;
 	}
 
-	fn provider(&self) -> Option<&Provider<P>> {
-		self.provider
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> FungibleTokenTrait<'a, P> for FungibleTokenContract<'a, P> {}
+impl<M: Middleware + 'static> FungibleTokenTrait for FungibleTokenContract<M> {}
@@ -0,0 +1,180 @@
+use crate::{
+	error::ContractError, neo_token::NeoToken, traits::smart_contract::SmartContractTrait,
+};
+use neo_codec::encode::NeoSerializable;
+use neo_providers::{
+	core::{
+		account::{Account, AccountTrait},
+		script::script_builder::{ScriptBuilder, TransferIntent},
+		transaction::{
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction::Transaction,
+			transaction_template::TransactionTemplate,
+			witness::Witness,
+		},
+	},
+	Middleware, PendingTransaction,
+};
+use neo_types::{encoding, gas::Gas, Bytes};
+use std::sync::Arc;
+
+/// Claims the GAS generated by the NEO of an account.
+///
+/// Unclaimed GAS is distributed whenever the NEO balance of an account changes, so it is claimed
+/// by a transfer of 0 NEO from the account to itself. The fees of that transaction are paid from
+/// the GAS balance the account has before the claim, and the claim is refused if the unclaimed
+/// GAS does not exceed them.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_contract::gas_claim::GasClaim;
+/// use neo_providers::{core::account::Account, Http, Provider};
+/// use std::{convert::TryFrom, sync::Arc};
+///
+/// # async fn foo(account: Account) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let claim = GasClaim::new(Arc::new(provider), account);
+/// println!("claiming {}", claim.unclaimed_gas().await?);
+/// let result = claim.send().await?.await?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GasClaim<M: Middleware> {
+	client: Arc<M>,
+	account: Account,
+}
+
+impl<M: Middleware + 'static> GasClaim<M> {
+	/// Creates a claim for the GAS of `account`, which must hold its key pair to sign the
+	/// transaction.
+	pub fn new(client: Arc<M>, account: Account) -> Self {
+		Self { client, account }
+	}
+
+	/// The account claiming its GAS
+	pub fn account(&self) -> &Account {
+		&self.account
+	}
+
+	/// Gets the GAS the account can claim.
+	pub async fn unclaimed_gas(&self) -> Result<Gas, ContractError> {
+		let unclaimed = self
+			.client
+			.get_unclaimed_gas(self.account.get_script_hash())
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		Ok(unclaimed.unclaimed)
+	}
+
+	/// Builds the script transferring 0 NEO from the account to itself.
+	pub fn build_script(&self) -> Result<Bytes, ContractError> {
+		let account = self.account.get_script_hash();
+		let transfer = TransferIntent {
+			token: NeoToken::new(Some(self.client.clone())).script_hash(),
+			from: account,
+			to: account,
+			amount: 0,
+			data: None,
+		};
+		ScriptBuilder::build_multi_transfer(&[transfer])
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))
+	}
+
+	/// Builds the unsigned claim transaction with its fees.
+	///
+	/// Fails if the account has no GAS to claim or the unclaimed GAS does not exceed the fees.
+	pub async fn build_transaction(&self) -> Result<Transaction, ContractError> {
+		let unclaimed = self.unclaimed_gas().await?;
+		if unclaimed <= Gas::ZERO {
+			return Err(ContractError::InvalidStateError("There is no GAS to claim".to_string()))
+		}
+
+		let signer = AccountSigner::called_by_entry(&self.account)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let template =
+			TransactionTemplate::new(self.build_script()?, &[Signer::from(signer)], rand::random())
+				.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let mut tx = template
+			.instantiate_with(self.client.as_ref())
+			.await
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+
+		let fees = tx.sys_fee.saturating_add(tx.net_fee);
+		if unclaimed <= fees {
+			return Err(ContractError::InvalidStateError(format!(
+				"The unclaimed GAS {unclaimed} does not cover the fees {fees} of the claim"
+			)))
+		}
+		tx.set_network_magic(self.network_magic().await?);
+		Ok(tx)
+	}
+
+	/// Builds, signs and sends the claim transaction.
+	pub async fn send(&self) -> Result<PendingTransaction<'_, M::Provider>, ContractError> {
+		let key_pair = self.account.key_pair().as_ref().ok_or_else(|| {
+			ContractError::InvalidStateError(
+				"The account does not hold a private key to sign the claim".to_string(),
+			)
+		})?;
+		let mut tx = self.build_transaction().await?;
+		let hash_data = tx.get_hash_data(self.network_magic().await?);
+		let witness = Witness::create(hash_data, key_pair)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		tx.add_witness(witness);
+
+		let sent = self
+			.client
+			.send_raw_transaction(encoding::base64_encode(tx.to_array()))
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		Ok(PendingTransaction::new(sent.hash, self.client.provider()))
+	}
+
+	async fn network_magic(&self) -> Result<u32, ContractError> {
+		self.client
+			.get_network_magic()
+			.await
+			.map_err(ContractError::from_middleware_error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_providers::{core::script::script_reader::ScriptReader, Expectation, Provider};
+	use neo_types::{address::Address, natives};
+	use serde_json::json;
+
+	#[test]
+	fn test_claim_is_self_transfer_of_zero_neo() {
+		let (provider, _) = Provider::mocked();
+		let account = Account::create().unwrap();
+		let claim = GasClaim::new(Arc::new(provider), account.clone());
+
+		let calls = ScriptReader::contract_calls(&claim.build_script().unwrap()).unwrap();
+		assert_eq!(calls.len(), 1);
+		let script_hash = account.get_script_hash();
+		assert_eq!(
+			calls[0].as_nep17_transfer(),
+			Some((natives::NEO_TOKEN.hash, script_hash, script_hash, 0))
+		);
+	}
+
+	#[tokio::test]
+	async fn test_nothing_to_claim() {
+		let (provider, mock) = Provider::mocked();
+		let account = Account::create().unwrap();
+		mock.expect(Expectation::new("getunclaimedgas").returning(json!({
+			"unclaimed": "0",
+			"address": Address::from_script_hash(account.get_script_hash()).to_string(),
+		})));
+
+		let claim = GasClaim::new(Arc::new(provider), account);
+		assert!(matches!(
+			claim.build_transaction().await,
+			Err(ContractError::InvalidStateError(message)) if message.contains("no GAS")
+		));
+		mock.assert_expectations_met().unwrap();
+	}
+}
@@ -5,13 +5,14 @@ use crate::{
 	},
 };
 use async_trait::async_trait;
-use neo_providers::{JsonRpcClient, Provider};
+use neo_providers::Middleware;
 use neo_types::{nns_name::NNSName, script_hash::ScriptHash, *};
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GasToken<'a, P: JsonRpcClient> {
+pub struct GasToken<M: Middleware> {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	script_hash: ScriptHash,
@@ -22,27 +23,27 @@ pub struct GasToken<'a, P: JsonRpcClient> {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	symbol: Option<String>,
 	#[serde(skip)]
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> GasToken<'a, P> {
-	pub const NAME: &'static str = "GasToken";
+impl<M: Middleware + 'static> GasToken<M> {
+	pub const NAME: &'static str = natives::GAS_TOKEN.name;
 	pub const DECIMALS: u8 = 8;
 	pub const SYMBOL: &'static str = "GAS";
 
-	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
+	pub fn new(client: Option<Arc<M>>) -> Self {
 		Self {
-			script_hash: Self::calc_native_contract_hash(Self::NAME).unwrap(),
+			script_hash: natives::GAS_TOKEN.hash,
 			total_supply: None,
 			decimals: Some(Self::DECIMALS),
 			symbol: Some(Self::SYMBOL.to_string()),
-			provider,
+			client,
 		}
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for GasToken<'a, P> {
+impl<M: Middleware + 'static> TokenTrait for GasToken<M> {
 	fn total_supply(&self) -> Option<u64> {
 		self.total_supply
 	}
@@ -73,8 +74,8 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for GasToken<'a, P> {
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for GasToken<'a, P> {
-	type P = P;
+impl<M: Middleware + 'static> SmartContractTrait for GasToken<M> {
+	type M = M;
 
 	fn script_hash(&self) -> H160 {
 		self.script_hash
@@ -84,10 +85,10 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for GasToken<'a, P> {
 		self.script_hash = script_hash;
 	}
 
-	fn provider(&self) -> Option<&Provider<P>> {
-		self.provider
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> FungibleTokenTrait<'a, P> for GasToken<'a, P> {}
+impl<M: Middleware + 'static> FungibleTokenTrait for GasToken<M> {}
@@ -0,0 +1,365 @@
+use crate::{
+	call::ContractCall,
+	error::ContractError,
+	neo_token::{Candidate, NeoToken},
+	role_management::RoleManagement,
+};
+use neo_crypto::keys::Secp256r1PublicKey;
+use neo_providers::{
+	core::{
+		account::{Account, AccountTrait},
+		transaction::signers::{account_signer::AccountSigner, signer::Signer},
+	},
+	Middleware,
+};
+use neo_types::role::Role;
+use primitive_types::H160;
+use std::sync::Arc;
+
+/// The GAS a NEO holder can expect to receive, in GAS fractions, see
+/// [`Governance::expected_gas_rewards`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GasRewards {
+	/// The share of the GAS generated per block that is distributed to all NEO holders
+	pub holder: i64,
+	/// The share of the GAS distributed to the voters of the committee member voted for
+	pub voter: i64,
+}
+
+impl GasRewards {
+	pub fn total(&self) -> i64 {
+		self.holder + self.voter
+	}
+}
+
+/// Participation in the governance of the network: candidates for the committee, votes and the
+/// roles the committee designates.
+///
+/// Wraps [`NeoToken`] and [`RoleManagement`]. The methods changing state return contract calls
+/// that already carry the signer whose witness the `NeoToken` contract checks. They are sent
+/// through the client, which must sign them, e.g. a `SignerMiddleware` holding the key.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_contract::governance::Governance;
+/// use neo_providers::{core::account::Account, Http, Provider};
+/// use std::{convert::TryFrom, sync::Arc};
+///
+/// # async fn foo(voter: Account) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let governance = Governance::new(Arc::new(provider));
+/// let candidates = governance.candidates().await?;
+/// let vote = governance.vote(&voter, Some(&candidates[0].public_key)).await?;
+/// assert!(!vote.call().await?.has_state_fault());
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Governance<M: Middleware> {
+	client: Arc<M>,
+	neo: NeoToken<M>,
+	roles: RoleManagement<M>,
+}
+
+impl<M: Middleware + 'static> Governance<M> {
+	/// The share of the GAS per block distributed to all NEO holders, in percent
+	pub const HOLDER_REWARD_RATIO: i64 = 10;
+	/// The share of the GAS per block distributed to the voters of the committee, in percent
+	pub const VOTER_REWARD_RATIO: i64 = 80;
+
+	pub fn new(client: Arc<M>) -> Self {
+		Self {
+			neo: NeoToken::new(Some(client.clone())),
+			roles: RoleManagement::new(Some(client.clone())),
+			client,
+		}
+	}
+
+	/// The wrapped `NeoToken` contract
+	pub fn neo_token(&self) -> &NeoToken<M> {
+		&self.neo
+	}
+
+	/// The wrapped `RoleManagement` contract
+	pub fn role_management(&self) -> &RoleManagement<M> {
+		&self.roles
+	}
+
+	/// Gets the registered candidates, the ones with the most votes first.
+	pub async fn candidates(&self) -> Result<Vec<Candidate>, ContractError> {
+		let mut candidates = self.neo.get_candidates().await?;
+		candidates.sort_by(|a, b| b.votes.cmp(&a.votes));
+		Ok(candidates)
+	}
+
+	/// Gets the public keys of the members of the committee.
+	pub async fn committee(&self) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
+		self.neo.get_committee().await
+	}
+
+	/// Gets the public keys designated for `role` at the current block.
+	pub async fn designated(&self, role: Role) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
+		let block_count = self
+			.client
+			.get_block_count()
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		self.roles.get_designated_by_role(role, block_count).await
+	}
+
+	/// Creates a transaction registering the key of `candidate` as candidate for the committee.
+	///
+	/// The registration costs the register price of the `NeoToken` contract, paid in GAS by
+	/// `candidate`.
+	pub async fn register_candidate(
+		&self,
+		candidate: &Account,
+	) -> Result<ContractCall<M>, ContractError> {
+		let public_key = Self::public_key(candidate)?;
+		if self.neo.is_candidate(&public_key).await? {
+			return Err(ContractError::InvalidStateError(
+				"The account is already registered as candidate".to_string(),
+			))
+		}
+		let call = self.neo.register_candidate(&public_key).await?;
+		Self::signed_by(call, candidate)
+	}
+
+	/// Creates a transaction unregistering the key of `candidate`. The votes for it are kept
+	/// but no longer count until it registers again.
+	pub async fn unregister_candidate(
+		&self,
+		candidate: &Account,
+	) -> Result<ContractCall<M>, ContractError> {
+		let public_key = Self::public_key(candidate)?;
+		if !self.neo.is_candidate(&public_key).await? {
+			return Err(ContractError::InvalidStateError(
+				"The account is not registered as candidate".to_string(),
+			))
+		}
+		let call = self.neo.unregister_candidate(&public_key).await?;
+		Self::signed_by(call, candidate)
+	}
+
+	/// Creates a transaction voting with all NEO of `voter` for `candidate`, replacing a
+	/// previous vote, or cancelling the vote if `candidate` is `None`.
+	pub async fn vote(
+		&self,
+		voter: &Account,
+		candidate: Option<&Secp256r1PublicKey>,
+	) -> Result<ContractCall<M>, ContractError> {
+		if let Some(candidate) = candidate {
+			if !self.neo.is_candidate(candidate).await? {
+				return Err(ContractError::InvalidArgError(format!(
+					"{} is not a registered candidate",
+					candidate.get_encoded_compressed_hex()
+				)))
+			}
+		}
+		let call = self.neo.vote(&voter.get_script_hash(), candidate).await?;
+		Self::signed_by(call, voter)
+	}
+
+	/// Gets the GAS `account` can claim now, in GAS fractions.
+	pub async fn unclaimed_gas(&self, account: &H160) -> Result<i64, ContractError> {
+		let block_count = self
+			.client
+			.get_block_count()
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		self.neo.unclaimed_gas_contract(account, block_count as i32).await
+	}
+
+	/// Estimates the GAS `account` receives over the next `blocks` blocks with its current
+	/// NEO balance, vote, GAS per block and committee.
+	///
+	/// Every block, 10% of the GAS per block goes to the NEO holders in proportion to their
+	/// balance. 80% go to the voters of the committee members, split equally between the
+	/// members, with validators receiving twice the share of the other members, and split in
+	/// proportion to the votes between the voters of each member. Voters of candidates outside
+	/// the committee receive no voter rewards.
+	pub async fn expected_gas_rewards(
+		&self,
+		account: &H160,
+		blocks: u32,
+	) -> Result<GasRewards, ContractError> {
+		let state = self.neo.get_account_state(account).await?;
+		if state.balance == 0 {
+			return Ok(GasRewards::default())
+		}
+		let balance = state.balance as i128;
+		let gas = self.neo.get_gas_per_block().await? as i128 * blocks as i128;
+		let holder = gas * Self::HOLDER_REWARD_RATIO as i128 * balance
+			/ (100 * NeoToken::<M>::TOTAL_SUPPLY as i128);
+
+		let Some(vote) = state.public_key else {
+			return Ok(GasRewards { holder: holder as i64, voter: 0 })
+		};
+		let committee = self.neo.get_committee().await?;
+		let validators = self.neo.get_next_block_validators().await?;
+		let factor = if validators.contains(&vote) {
+			2
+		} else if committee.contains(&vote) {
+			1
+		} else {
+			return Ok(GasRewards { holder: holder as i64, voter: 0 })
+		};
+		let votes = self
+			.neo
+			.get_candidates()
+			.await?
+			.into_iter()
+			.find(|candidate| candidate.public_key == vote)
+			.map_or(0, |candidate| candidate.votes as i128);
+		if votes == 0 {
+			return Ok(GasRewards { holder: holder as i64, voter: 0 })
+		}
+		let members = (committee.len() + validators.len()) as i128;
+		let voter =
+			gas * Self::VOTER_REWARD_RATIO as i128 * factor * balance / (100 * members * votes);
+		Ok(GasRewards { holder: holder as i64, voter: voter as i64 })
+	}
+
+	fn public_key(account: &Account) -> Result<Secp256r1PublicKey, ContractError> {
+		account.key_pair().as_ref().map(|key_pair| key_pair.public_key).ok_or_else(|| {
+			ContractError::InvalidArgError("The account has no key pair".to_string())
+		})
+	}
+
+	fn signed_by(
+		mut call: ContractCall<M>,
+		account: &Account,
+	) -> Result<ContractCall<M>, ContractError> {
+		let signer = AccountSigner::called_by_entry(account)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		call.set_signers(vec![Signer::from(signer)]);
+		Ok(call)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_crypto::key_pair::KeyPair;
+	use neo_providers::{Expectation, MockProvider, Provider};
+	use serde_json::{json, Value};
+
+	const GAS_PER_BLOCK: i64 = 500_000_000;
+	const BALANCE: i64 = 1_000;
+
+	fn keys(count: usize) -> Vec<Secp256r1PublicKey> {
+		(0..count).map(|_| KeyPair::new_random().public_key()).collect()
+	}
+
+	fn key_item(key: &Secp256r1PublicKey) -> Value {
+		json!({"type": "ByteString", "value": hex::encode(key.get_encoded(true))})
+	}
+
+	fn keys_item(keys: &[Secp256r1PublicKey]) -> Value {
+		json!({"type": "Array", "value": keys.iter().map(key_item).collect::<Vec<_>>()})
+	}
+
+	fn expect_invocation(mock: &MockProvider, item: Value) {
+		mock.expect(Expectation::new("invokefunction").returning(json!({
+			"script": "",
+			"state": "HALT",
+			"gasconsumed": "0",
+			"stack": [item],
+		})));
+	}
+
+	/// Expects the invocations made for an account holding [`BALANCE`] NEO and voting for `vote`.
+	fn expect_account(mock: &MockProvider, vote: Option<&Secp256r1PublicKey>) {
+		let vote = vote.map_or(json!({"type": "Any"}), key_item);
+		expect_invocation(
+			mock,
+			json!({"type": "Struct", "value": [
+				{"type": "Integer", "value": BALANCE},
+				{"type": "Integer", "value": 10},
+				vote,
+			]}),
+		);
+		expect_invocation(mock, json!({"type": "Integer", "value": GAS_PER_BLOCK}));
+	}
+
+	fn expect_committee(
+		mock: &MockProvider,
+		committee: &[Secp256r1PublicKey],
+		validators: &[Secp256r1PublicKey],
+		votes: &[(Secp256r1PublicKey, i64)],
+	) {
+		expect_invocation(mock, keys_item(committee));
+		expect_invocation(mock, keys_item(validators));
+		let candidates = votes
+			.iter()
+			.map(|(key, votes)| {
+				json!({"type": "Struct", "value": [key_item(key), {"type": "Integer", "value": votes}]})
+			})
+			.collect::<Vec<_>>();
+		expect_invocation(mock, json!({"type": "Array", "value": candidates}));
+	}
+
+	async fn rewards(mock: MockProvider, provider: Provider<MockProvider>) -> GasRewards {
+		let governance = Governance::new(Arc::new(provider));
+		let rewards = governance.expected_gas_rewards(&H160::repeat_byte(1), 100).await.unwrap();
+		mock.assert_expectations_met().unwrap();
+		rewards
+	}
+
+	// 100 blocks of 5 GAS, 10% of which go to the holders of the 100 million NEO
+	const HOLDER_REWARD: i64 = 50_000;
+
+	#[tokio::test]
+	async fn test_rewards_of_non_voter() {
+		let (provider, mock) = Provider::mocked();
+		expect_account(&mock, None);
+		let rewards = rewards(mock, provider).await;
+		assert_eq!(rewards, GasRewards { holder: HOLDER_REWARD, voter: 0 });
+	}
+
+	#[tokio::test]
+	async fn test_rewards_of_committee_voter() {
+		let committee = keys(3);
+		let (provider, mock) = Provider::mocked();
+		expect_account(&mock, Some(&committee[2]));
+		expect_committee(&mock, &committee, &committee[..2], &[(committee[2], 10_000)]);
+		// 80% of 500 GAS, split between 3 members and 2 validators, for 1_000 of 10_000 votes
+		let rewards = rewards(mock, provider).await;
+		assert_eq!(rewards, GasRewards { holder: HOLDER_REWARD, voter: 800_000_000 });
+		assert_eq!(rewards.total(), 800_050_000);
+	}
+
+	#[tokio::test]
+	async fn test_rewards_of_validator_voter() {
+		let committee = keys(3);
+		let (provider, mock) = Provider::mocked();
+		expect_account(&mock, Some(&committee[0]));
+		expect_committee(&mock, &committee, &committee[..2], &[(committee[0], 10_000)]);
+		// validators receive twice the share of the other members
+		let rewards = rewards(mock, provider).await;
+		assert_eq!(rewards, GasRewards { holder: HOLDER_REWARD, voter: 1_600_000_000 });
+	}
+
+	#[tokio::test]
+	async fn test_rewards_of_non_committee_voter() {
+		let committee = keys(3);
+		let candidate = keys(1)[0];
+		let (provider, mock) = Provider::mocked();
+		expect_account(&mock, Some(&candidate));
+		expect_invocation(&mock, keys_item(&committee));
+		expect_invocation(&mock, keys_item(&committee[..2]));
+		let rewards = rewards(mock, provider).await;
+		assert_eq!(rewards, GasRewards { holder: HOLDER_REWARD, voter: 0 });
+	}
+
+	#[tokio::test]
+	async fn test_rewards_are_split_between_members_and_validators() {
+		// a single member, who is also the single validator, counts twice in the denominator
+		let committee = keys(1);
+		let (provider, mock) = Provider::mocked();
+		expect_account(&mock, Some(&committee[0]));
+		expect_committee(&mock, &committee, &committee, &[(committee[0], 10_000)]);
+		let rewards = rewards(mock, provider).await;
+		assert_eq!(rewards, GasRewards { holder: HOLDER_REWARD, voter: 4_000_000_000 });
+	}
+}
@@ -1,53 +1,323 @@
 // iterator
 use crate::error::ContractError;
-use neo_providers::{JsonRpcClient, Middleware, Provider};
-use neo_types::stack_item::StackItem;
-use std::{fmt, sync::Arc};
+use futures_util::{ready, Stream};
+use neo_providers::Middleware;
+use neo_types::{
+	error::TypeError,
+	stack_item::{FromStackItem, StackItem},
+};
+use std::{
+	collections::VecDeque,
+	fmt,
+	future::Future,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
 
-pub struct NeoIterator<'a, T, P: JsonRpcClient> {
+type PinBoxFut<T> = Pin<Box<dyn Future<Output = Result<T, ContractError>> + Send>>;
+
+/// How the items of an iterator are turned into values
+enum Decoder<T> {
+	Mapper(Arc<dyn Fn(StackItem) -> T + Send + Sync>),
+	FromStackItem(fn(StackItem) -> Result<T, TypeError>),
+}
+
+impl<T> Decoder<T> {
+	fn decode(&self, item: StackItem) -> Result<T, ContractError> {
+		match self {
+			Decoder::Mapper(mapper) => Ok(mapper(item)),
+			Decoder::FromStackItem(decode) =>
+				decode(item).map_err(|e| ContractError::UnexpectedReturnType(e.to_string())),
+		}
+	}
+}
+
+enum IteratorState {
+	/// Waiting for the next page to be requested
+	Idle,
+	/// Fetching the next page with `traverseiterator`
+	Traversing(PinBoxFut<Vec<StackItem>>),
+	/// Closing the session with `terminatesession`
+	Terminating(PinBoxFut<bool>),
+	/// The session is closed
+	Done,
+}
+
+/// An iterator returned by a contract invocation, backed by a session on the RPC node.
+///
+/// As a [`Stream`], the iterator fetches `page_size` items at a time with `traverseiterator` and
+/// decodes them. The session is terminated with `terminatesession` once the iterator is
+/// exhausted. Call [`NeoIterator::close`] to terminate it when stopping before that, otherwise
+/// the session stays open until it expires on the node.
+///
+/// Nodes with sessions disabled cannot traverse iterators. Their items are then unwrapped by the
+/// invocation itself, up to a bound, and the iterator only streams the loaded items, see
+/// [`NeoIterator::from_items`].
+pub struct NeoIterator<T, M: Middleware> {
 	session_id: String,
 	iterator_id: String,
-	mapper: Arc<dyn Fn(StackItem) -> T + Send + Sync>,
-	provider: Option<&'a Provider<P>>,
+	decoder: Decoder<T>,
+	client: Option<Arc<M>>,
+	page_size: u32,
+	buffer: VecDeque<Result<T, ContractError>>,
+	state: IteratorState,
 }
 
-impl<'a, T, P: JsonRpcClient> fmt::Debug for NeoIterator<'a, T, P> {
+impl<T, M: Middleware> fmt::Debug for NeoIterator<T, M> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("NeoIterator")
 			.field("session_id", &self.session_id)
 			.field("iterator_id", &self.iterator_id)
 			// For the mapper, you can decide what to print. Here, we just print a static string.
 			.field("mapper", &"<function>")
+			.field("page_size", &self.page_size)
 			.finish()
 	}
 }
 
-impl<'a, T, P: JsonRpcClient> NeoIterator<'a, T, P> {
+impl<T, M: Middleware + 'static> NeoIterator<T, M> {
+	/// Number of items fetched per `traverseiterator` call by default
+	pub const DEFAULT_PAGE_SIZE: u32 = 100;
+
 	pub fn new(
 		session_id: String,
 		iterator_id: String,
 		mapper: Arc<dyn Fn(StackItem) -> T + Send + Sync>,
-		provider: Option<&'a Provider<P>>,
+		client: Option<Arc<M>>,
+	) -> Self {
+		Self::with_decoder(session_id, iterator_id, Decoder::Mapper(mapper), client)
+	}
+
+	/// Creates an iterator over `items` that were already unwrapped from the iterator by the
+	/// invocation, e.g. with `ScriptBuilder::build_contract_call_and_unwrap_iterator`. It has no
+	/// session, so nothing is requested from the node.
+	pub fn from_items(
+		items: Vec<StackItem>,
+		mapper: Arc<dyn Fn(StackItem) -> T + Send + Sync>,
+	) -> Self {
+		let mut iterator =
+			Self::with_decoder(String::new(), String::new(), Decoder::Mapper(mapper), None);
+		let decoder = &iterator.decoder;
+		let items: VecDeque<_> = items.into_iter().map(|item| decoder.decode(item)).collect();
+		iterator.buffer = items;
+		iterator.state = IteratorState::Done;
+		iterator
+	}
+
+	/// Whether the items are traversed in a session on the node, rather than loaded by the
+	/// invocation
+	pub fn has_session(&self) -> bool {
+		!self.session_id.is_empty()
+	}
+
+	/// Creates an iterator decoding its items with [`FromStackItem`].
+	pub fn decoding(session_id: String, iterator_id: String, client: Option<Arc<M>>) -> Self
+	where
+		T: FromStackItem,
+	{
+		let decoder = Decoder::FromStackItem(T::from_stack_item);
+		Self::with_decoder(session_id, iterator_id, decoder, client)
+	}
+
+	fn with_decoder(
+		session_id: String,
+		iterator_id: String,
+		decoder: Decoder<T>,
+		client: Option<Arc<M>>,
 	) -> Self {
-		Self { session_id, iterator_id, mapper, provider }
+		Self {
+			session_id,
+			iterator_id,
+			decoder,
+			client,
+			page_size: Self::DEFAULT_PAGE_SIZE,
+			buffer: VecDeque::new(),
+			state: IteratorState::Idle,
+		}
+	}
+
+	/// Sets the number of items fetched per `traverseiterator` call.
+	#[must_use]
+	pub fn page_size(mut self, page_size: u32) -> Self {
+		self.page_size = page_size.max(1);
+		self
 	}
 
 	pub async fn traverse(&self, count: i32) -> Result<Vec<T>, ContractError> {
+		if !self.has_session() {
+			return Err(ContractError::InvalidStateError(
+				"iterator has no session, stream its loaded items instead".to_string(),
+			))
+		}
 		let result = self
-			.provider
-			.unwrap()
+			.client()?
 			.traverse_iterator(self.session_id.clone(), self.iterator_id.clone(), count as u32)
-			.await?;
-		let mapped = result.iter().map(|item| (self.mapper)(item.clone())).collect();
-		Ok(mapped)
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		result.into_iter().map(|item| self.decoder.decode(item)).collect()
 	}
 
-	pub async fn terminate_session(&self) -> Result<(), ContractError> {
-		self.provider
-			.unwrap()
+	/// Terminates the session on the node, unless the iterator is exhausted or has no session.
+	pub async fn close(mut self) -> Result<(), ContractError> {
+		self.terminate_session().await
+	}
+
+	pub async fn terminate_session(&mut self) -> Result<(), ContractError> {
+		if matches!(self.state, IteratorState::Done) {
+			return Ok(())
+		}
+		self.state = IteratorState::Done;
+		self.client()?
 			.terminate_session(&self.session_id)
 			.await
-			.expect("Could not terminate session");
+			.map_err(ContractError::from_middleware_error)?;
 		Ok(())
 	}
+
+	fn client(&self) -> Result<&Arc<M>, ContractError> {
+		self.client.as_ref().ok_or_else(no_client)
+	}
+}
+
+fn no_client() -> ContractError {
+	ContractError::InvalidStateError("iterator has no client".to_string())
+}
+
+fn traverse<M: Middleware + 'static>(
+	client: Arc<M>,
+	session_id: String,
+	iterator_id: String,
+	count: u32,
+) -> IteratorState {
+	IteratorState::Traversing(Box::pin(async move {
+		client
+			.traverse_iterator(session_id, iterator_id, count)
+			.await
+			.map_err(ContractError::from_middleware_error)
+	}))
+}
+
+fn terminate<M: Middleware + 'static>(client: Option<&Arc<M>>, session_id: &str) -> IteratorState {
+	match client {
+		Some(client) => {
+			let client = client.clone();
+			let session_id = session_id.to_string();
+			IteratorState::Terminating(Box::pin(async move {
+				client
+					.terminate_session(&session_id)
+					.await
+					.map_err(ContractError::from_middleware_error)
+			}))
+		},
+		None => IteratorState::Done,
+	}
+}
+
+impl<T: Unpin, M: Middleware + 'static> Stream for NeoIterator<T, M> {
+	type Item = Result<T, ContractError>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		loop {
+			if let Some(item) = this.buffer.pop_front() {
+				return Poll::Ready(Some(item))
+			}
+			this.state = match &mut this.state {
+				IteratorState::Idle => match &this.client {
+					Some(client) => traverse(
+						client.clone(),
+						this.session_id.clone(),
+						this.iterator_id.clone(),
+						this.page_size,
+					),
+					None => {
+						this.buffer.push_back(Err(no_client()));
+						IteratorState::Done
+					},
+				},
+				IteratorState::Traversing(fut) => match ready!(fut.as_mut().poll(cx)) {
+					Ok(items) => {
+						let exhausted = items.len() < this.page_size as usize;
+						let decoder = &this.decoder;
+						this.buffer.extend(items.into_iter().map(|item| decoder.decode(item)));
+						if exhausted {
+							terminate(this.client.as_ref(), &this.session_id)
+						} else {
+							IteratorState::Idle
+						}
+					},
+					Err(err) => {
+						this.buffer.push_back(Err(err));
+						terminate(this.client.as_ref(), &this.session_id)
+					},
+				},
+				IteratorState::Terminating(fut) => {
+					// the session expires on the node anyway, so a failure is not reported
+					let _ = ready!(fut.as_mut().poll(cx));
+					IteratorState::Done
+				},
+				IteratorState::Done => return Poll::Ready(None),
+			};
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures_util::TryStreamExt;
+	use neo_providers::{Expectation, MockProvider, Provider};
+
+	type Ints = NeoIterator<i64, Provider<MockProvider>>;
+
+	fn ints(values: impl IntoIterator<Item = i64>) -> Vec<StackItem> {
+		values.into_iter().map(|value| StackItem::Integer { value }).collect()
+	}
+
+	#[tokio::test]
+	async fn test_streams_pages_and_terminates_session() {
+		let (provider, mock) = Provider::mocked();
+		for page in [ints([1, 2]), ints([3])] {
+			mock.expect(
+				Expectation::new("traverseiterator")
+					.with_params(("session", "iterator", 2))
+					.returning(page),
+			);
+		}
+		mock.expect(Expectation::new("terminatesession").with_params(["session"]).returning(true));
+
+		let iterator =
+			Ints::decoding("session".to_string(), "iterator".to_string(), Some(Arc::new(provider)))
+				.page_size(2);
+		assert!(iterator.has_session());
+		let items: Vec<i64> = iterator.try_collect().await.unwrap();
+		assert_eq!(items, vec![1, 2, 3]);
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_close_terminates_session() {
+		let (provider, mock) = Provider::mocked();
+		mock.expect(Expectation::new("terminatesession").with_params(["session"]).returning(true));
+		let iterator =
+			Ints::decoding("session".to_string(), "iterator".to_string(), Some(Arc::new(provider)));
+		iterator.close().await.unwrap();
+		mock.assert_expectations_met().unwrap();
+		assert_eq!(mock.request_count("terminatesession"), 1);
+	}
+
+	#[tokio::test]
+	async fn test_loaded_items() {
+		let mapper = Arc::new(|item: StackItem| item.as_int().unwrap_or_default() * 10);
+		let iterator = Ints::from_items(ints([1, 2]), mapper);
+		assert!(!iterator.has_session());
+		assert!(iterator.traverse(1).await.is_err());
+		let items: Vec<i64> = iterator.try_collect().await.unwrap();
+		assert_eq!(items, vec![10, 20]);
+
+		// nothing to terminate without a session
+		let iterator = Ints::from_items(vec![], Arc::new(|_: StackItem| 0));
+		iterator.close().await.unwrap();
+	}
 }
@@ -1,14 +1,30 @@
-#![feature(const_trait_impl)]
-
+pub mod call;
+pub mod contract;
 pub mod contract_management;
 pub mod error;
+pub mod events;
+pub mod factory;
 pub mod fungible_token_contract;
+pub mod gas_claim;
 pub mod gas_token;
+pub mod governance;
 pub mod iterator;
 pub mod name_service;
 pub mod neo_token;
 pub mod neo_uri;
+pub mod nep17_multi_transfer;
 pub mod nft_contract;
+#[cfg(feature = "notary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "notary")))]
+pub mod notary;
+pub mod oracle_contract;
+pub mod oracle_node;
 pub mod policy_contract;
 pub mod role_management;
+pub mod simulation;
+pub mod standards;
 pub mod traits;
+
+pub use call::ContractCall;
+pub use contract::Contract;
+pub use factory::{ContractFactory, Deployer};
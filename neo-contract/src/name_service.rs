@@ -1,14 +1,12 @@
-#![feature(const_trait_impl)]
 use crate::{
+	call::ContractCall,
 	error::ContractError,
 	iterator::NeoIterator,
 	traits::{nft::NonFungibleTokenTrait, smart_contract::SmartContractTrait, token::TokenTrait},
 };
 use async_trait::async_trait;
 use futures::FutureExt;
-use neo_providers::{
-	core::transaction::transaction_builder::TransactionBuilder, JsonRpcClient, Middleware, Provider,
-};
+use neo_providers::Middleware;
 use neo_types::{
 	address_or_scripthash::AddressOrScriptHash, contract_parameter::ContractParameter,
 	nns_name::NNSName, script_hash::ScriptHash, stack_item::StackItem, *,
@@ -60,15 +58,15 @@ pub struct NameState {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct NeoNameService<'a, P: JsonRpcClient> {
+pub struct NeoNameService<M: Middleware> {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	script_hash: ScriptHash,
 	#[serde(skip)]
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
+impl<M: Middleware + 'static> NeoNameService<M> {
 	const ADD_ROOT: &'static str = "addRoot";
 	const ROOTS: &'static str = "roots";
 	const SET_PRICE: &'static str = "setPrice";
@@ -88,28 +86,25 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 	const EXPIRATION_PROPERTY: &'static str = "expiration";
 	const ADMIN_PROPERTY: &'static str = "admin";
 
-	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
-		Self { script_hash: provider.unwrap().nns_resolver().clone(), provider }
+	pub fn new(client: Option<Arc<M>>) -> Self {
+		Self { script_hash: client.as_ref().unwrap().nns_resolver(), client }
 	}
 
 	// Implementation
 
-	async fn add_root(&self, root: &str) -> Result<TransactionBuilder<P>, ContractError> {
+	async fn add_root(&self, root: &str) -> Result<ContractCall<M>, ContractError> {
 		let args = vec![root.to_string().into()];
 		self.invoke_function(Self::ADD_ROOT, args).await
 	}
 
-	async fn get_roots(&self) -> Result<NeoIterator<String, P>, ContractError> {
+	async fn get_roots(&self) -> Result<NeoIterator<String, M>, ContractError> {
 		let args = vec![];
-		let roots = self
-			.call_function_returning_iterator(
-				Self::ROOTS,
-				args,
-				Arc::new(|item: StackItem| item.to_string()),
-			)
-			.await;
-
-		Ok(roots)
+		self.call_function_returning_iterator(
+			Self::ROOTS,
+			args,
+			Arc::new(|item: StackItem| item.to_string()),
+		)
+		.await
 	}
 
 	async fn get_symbol(&self) -> Result<String, ContractError> {
@@ -126,7 +121,7 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 		&self,
 		name: &str,
 		owner: H160,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		self.check_domain_name_availability(name, true).await.unwrap();
 
 		let args = vec![name.into(), owner.into()];
@@ -139,7 +134,7 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 		&self,
 		name: &str,
 		admin: H160,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		self.check_domain_name_availability(name, true).await.unwrap();
 
 		let args = vec![name.into(), admin.into()];
@@ -153,7 +148,7 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 		name: &str,
 		record_type: RecordType,
 		data: &str,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		let args = vec![name.into(), (record_type as u8).into(), data.into()];
 
 		self.invoke_function(Self::SET_RECORD, args).await
@@ -165,7 +160,7 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 		&self,
 		name: &str,
 		record_type: RecordType,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		let args = vec![name.into(), (record_type as u8).into()];
 		self.invoke_function(Self::DELETE_RECORD, args).await
 	}
@@ -174,11 +169,7 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 		let args = vec![name.into()];
 		self.call_function_returning_bool(Self::IS_AVAILABLE, args).await
 	}
-	pub async fn renew(
-		&self,
-		name: &str,
-		years: u32,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	pub async fn renew(&self, name: &str, years: u32) -> Result<ContractCall<M>, ContractError> {
 		self.check_domain_name_availability(name, true).await.unwrap();
 
 		let args = vec![name.into(), years.into()];
@@ -188,8 +179,7 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 	async fn get_name_state(&self, name: &[u8]) -> Result<NameState, ContractError> {
 		let args = vec![name.into()];
 		let result = self
-			.provider
-			.unwrap()
+			.require_client()?
 			.invoke_function(&self.script_hash, Self::PROPERTIES.to_string(), args, None)
 			.await
 			.unwrap()
@@ -241,7 +231,7 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NeoNameService<'a, P> {
+impl<M: Middleware + 'static> TokenTrait for NeoNameService<M> {
 	fn total_supply(&self) -> Option<u64> {
 		todo!()
 	}
@@ -268,8 +258,7 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NeoNameService<'a, P> {
 
 	async fn resolve_nns_text_record(&self, name: &NNSName) -> Result<H160, ContractError> {
 		let req = {
-			self.provider()
-				.unwrap()
+			self.require_client()?
 				.invoke_function(
 					&self.script_hash(),
 					"resolve".to_string(),
@@ -288,8 +277,8 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NeoNameService<'a, P> {
 	}
 }
 
-impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NeoNameService<'a, P> {
-	type P = P;
+impl<M: Middleware + 'static> SmartContractTrait for NeoNameService<M> {
+	type M = M;
 
 	fn set_name(&mut self, name: String) {}
 
@@ -301,9 +290,9 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NeoNameService<'a, P> {
 		self.script_hash = script_hash;
 	}
 
-	fn provider(&self) -> Option<&Provider<P>> {
-		self.provider
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
 	}
 }
 
-impl<'a, P: JsonRpcClient> NonFungibleTokenTrait<'a, P> for NeoNameService<'a, P> {}
+impl<M: Middleware + 'static> NonFungibleTokenTrait for NeoNameService<M> {}
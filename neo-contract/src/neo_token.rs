@@ -1,4 +1,5 @@
 use crate::{
+	call::ContractCall,
 	error::ContractError,
 	traits::{
 		fungible_token::FungibleTokenTrait, smart_contract::SmartContractTrait, token::TokenTrait,
@@ -13,18 +14,16 @@ use neo_types::{
 
 use neo_crypto::keys::Secp256r1PublicKey;
 use neo_providers::{
-	core::{
-		account::{Account, AccountTrait},
-		transaction::transaction_builder::TransactionBuilder,
-	},
-	JsonRpcClient, Middleware, Provider,
+	core::account::{Account, AccountTrait},
+	Middleware,
 };
 use neo_types::{nns_name::NNSName, *};
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NeoToken<'a, P: JsonRpcClient> {
+pub struct NeoToken<M: Middleware> {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	script_hash: ScriptHash,
@@ -34,29 +33,28 @@ pub struct NeoToken<'a, P: JsonRpcClient> {
 	decimals: Option<u8>,
 	symbol: Option<String>,
 	#[serde(skip)]
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
-	pub const NAME: &'static str = "NeoToken";
-	// pub const SCRIPT_HASH: H160 = Self::calc_native_contract_hash(Self::NAME).unwrap();
+impl<M: Middleware + 'static> NeoToken<M> {
+	pub const NAME: &'static str = natives::NEO_TOKEN.name;
 	pub const DECIMALS: u8 = 0;
 	pub const SYMBOL: &'static str = "NEO";
 	pub const TOTAL_SUPPLY: u64 = 100_000_000;
 
-	pub(crate) fn new(provider: Option<&'a Provider<P>>) -> Self {
+	pub fn new(client: Option<Arc<M>>) -> Self {
 		Self {
-			script_hash: Self::calc_native_contract_hash(Self::NAME).unwrap(),
+			script_hash: natives::NEO_TOKEN.hash,
 			total_supply: Some(Self::TOTAL_SUPPLY),
 			decimals: Some(Self::DECIMALS),
 			symbol: Some(Self::SYMBOL.to_string()),
-			provider,
+			client,
 		}
 	}
 
 	// Unclaimed Gas
 
-	async fn unclaimed_gas(
+	pub async fn unclaimed_gas(
 		&self,
 		account: &Account,
 		block_height: i32,
@@ -64,7 +62,7 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 		self.unclaimed_gas_contract(&account.get_script_hash(), block_height).await
 	}
 
-	async fn unclaimed_gas_contract(
+	pub async fn unclaimed_gas_contract(
 		&self,
 		script_hash: &H160,
 		block_height: i32,
@@ -74,70 +72,75 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 				"unclaimedGas",
 				vec![script_hash.into(), block_height.into()],
 			)
-			.await
-			.unwrap() as i64)
+			.await? as i64)
 	}
 
 	// Candidate Registration
 
-	async fn register_candidate(
+	pub async fn register_candidate(
 		&self,
 		candidate_key: &Secp256r1PublicKey,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("registerCandidate", vec![candidate_key.into()]).await
 	}
 
-	async fn unregister_candidate(
+	pub async fn unregister_candidate(
 		&self,
 		candidate_key: &Secp256r1PublicKey,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("unregisterCandidate", vec![candidate_key.into()]).await
 	}
 
 	// Committee and Candidates Information
 
-	async fn get_committee(&self) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
+	pub async fn get_committee(&self) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
 		self.call_function_returning_list_of_public_keys("getCommittee")
 			.await
 			.map_err(|e| ContractError::UnexpectedReturnType(e.to_string()))
 	}
 
-	async fn get_candidates(&self) -> Result<Vec<Candidate>, ContractError> {
-		let candidates = self.call_invoke_function("getCandidates", vec![], vec![]).await.unwrap();
-		let item = candidates.stack.first().unwrap();
-		if let StackItem::Array { value: array } = item {
-			Ok(array
-				.to_vec()
-				.chunks(2)
-				.filter_map(|v| {
-					if v.len() == 2 {
-						Some(Candidate::from(v.to_vec()).unwrap())
-					} else {
-						None
-					}
-				})
-				.collect::<Vec<Candidate>>())
-		} else {
-			Err(ContractError::UnexpectedReturnType("Candidates".to_string()))
-		}
+	/// Gets the registered candidates with the NEO voted for them.
+	pub async fn get_candidates(&self) -> Result<Vec<Candidate>, ContractError> {
+		let invocation = self.call_invoke_function("getCandidates", vec![], vec![]).await?;
+		self.throw_if_fault_state(&invocation)?;
+		invocation
+			.stack
+			.first()
+			.and_then(StackItem::as_array)
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Candidates".to_string()))?
+			.iter()
+			.map(|item| {
+				let fields = item
+					.as_array()
+					.ok_or_else(|| ContractError::UnexpectedReturnType("Candidate".to_string()))?;
+				Candidate::from(fields)
+			})
+			.collect()
 	}
 
-	async fn is_candidate(&self, public_key: &Secp256r1PublicKey) -> Result<bool, ContractError> {
-		Ok(self
-			.get_candidates()
+	pub async fn is_candidate(
+		&self,
+		public_key: &Secp256r1PublicKey,
+	) -> Result<bool, ContractError> {
+		Ok(self.get_candidates().await?.into_iter().any(|c| c.public_key == *public_key))
+	}
+
+	/// Gets the validators of the next block, a subset of the committee.
+	pub async fn get_next_block_validators(
+		&self,
+	) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
+		self.call_function_returning_list_of_public_keys("getNextBlockValidators")
 			.await
-			.unwrap()
-			.into_iter()
-			.any(|c| c.public_key == *public_key))
+			.map_err(|e| ContractError::UnexpectedReturnType(e.to_string()))
 	}
 
 	// Voting
 
-	async fn vote(
+	pub async fn vote(
 		&self,
 		voter: &H160,
 		candidate: Option<&Secp256r1PublicKey>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		let params = match candidate {
 			Some(key) => vec![voter.into(), key.into()],
 			None => vec![voter.into(), ContractParameter::new(ContractParameterType::Any)],
@@ -146,11 +149,11 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 		self.invoke_function("vote", params).await
 	}
 
-	async fn cancel_vote(&self, voter: &H160) -> Result<TransactionBuilder<P>, ContractError> {
+	pub async fn cancel_vote(&self, voter: &H160) -> Result<ContractCall<M>, ContractError> {
 		self.vote(voter, None).await
 	}
 
-	async fn build_vote_script(
+	pub async fn build_vote_script(
 		&self,
 		voter: &H160,
 		candidate: Option<&Secp256r1PublicKey>,
@@ -165,41 +168,42 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 
 	// Network Settings
 
-	async fn get_gas_per_block(&self) -> Result<i32, ContractError> {
+	pub async fn get_gas_per_block(&self) -> Result<i32, ContractError> {
 		self.call_function_returning_int("getGasPerBlock", vec![]).await
 	}
 
-	async fn set_gas_per_block(
+	pub async fn set_gas_per_block(
 		&self,
 		gas_per_block: i32,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("setGasPerBlock", vec![gas_per_block.into()]).await
 	}
 
-	async fn get_register_price(&self) -> Result<i32, ContractError> {
+	pub async fn get_register_price(&self) -> Result<i32, ContractError> {
 		self.call_function_returning_int("getRegisterPrice", vec![]).await
 	}
 
-	async fn set_register_price(
+	pub async fn set_register_price(
 		&self,
 		register_price: i32,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("setRegisterPrice", vec![register_price.into()]).await
 	}
 
-	async fn get_account_state(&self, account: &H160) -> Result<AccountState, ContractError> {
+	pub async fn get_account_state(&self, account: &H160) -> Result<AccountState, ContractError> {
 		let result = self
 			.call_invoke_function("getAccountState", vec![account.into()], vec![])
-			.await
-			.unwrap()
+			.await?
 			.stack
 			.first()
-			.unwrap()
-			.clone();
+			.cloned()
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Account state".to_string()))?;
 
 		match result {
 			StackItem::Any => Ok(AccountState::with_no_balance()),
-			StackItem::Array { value: items } if items.len() >= 3 => {
+			StackItem::Array { value: items } | StackItem::Struct { value: items }
+				if items.len() >= 3 =>
+			{
 				let balance = items[0].as_int().unwrap();
 				let update_height = items[1].as_int();
 				let public_key = items[2].clone();
@@ -229,30 +233,22 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 		&self,
 		function: &str,
 	) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
-		let result = self.call_invoke_function(function, vec![], vec![]).await.unwrap();
-		let stack_item = result.stack.first().unwrap();
-
-		if let StackItem::Array { value: array } = stack_item {
-			let keys = array
-				.iter()
-				.map(|item| {
-					if let StackItem::ByteString { value: bytes } = item {
-						Secp256r1PublicKey::from_bytes(bytes.as_bytes()).unwrap()
-					} else {
-						panic!("Unexpected stack item type")
-					}
-				})
-				.collect::<Vec<Secp256r1PublicKey>>();
-
-			Ok(keys)
-		} else {
-			Err(ContractError::UnexpectedReturnType("UnexpectedReturnType".to_string()))
-		}
+		let output = self.call_invoke_function(function, vec![], vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+		let unexpected = || ContractError::UnexpectedReturnType(format!("{function}: public keys"));
+		output
+			.stack
+			.first()
+			.and_then(StackItem::as_array)
+			.ok_or_else(unexpected)?
+			.iter()
+			.map(|item| item.as_public_key().ok_or_else(unexpected))
+			.collect()
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NeoToken<'a, P> {
+impl<M: Middleware + 'static> TokenTrait for NeoToken<M> {
 	fn total_supply(&self) -> Option<u64> {
 		self.total_supply
 	}
@@ -283,8 +279,8 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NeoToken<'a, P> {
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NeoToken<'a, P> {
-	type P = P;
+impl<M: Middleware + 'static> SmartContractTrait for NeoToken<M> {
+	type M = M;
 
 	fn script_hash(&self) -> H160 {
 		self.script_hash
@@ -294,14 +290,16 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NeoToken<'a, P> {
 		self.script_hash = script_hash;
 	}
 
-	fn provider(&self) -> Option<&Provider<P>> {
-		self.provider
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> FungibleTokenTrait<'a, P> for NeoToken<'a, P> {}
+impl<M: Middleware + 'static> FungibleTokenTrait for NeoToken<M> {}
 
+/// A candidate for the committee and the NEO voted for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Candidate {
 	pub public_key: Secp256r1PublicKey,
 	pub votes: i32,
@@ -309,8 +307,16 @@ pub struct Candidate {
 
 impl Candidate {
 	fn from(items: Vec<StackItem>) -> Result<Self, ContractError> {
-		let key = items[0].as_public_key().unwrap();
-		let votes = items[1].as_int().unwrap() as i32;
-		Ok(Self { public_key: key, votes })
+		match items.as_slice() {
+			[key, votes] => Ok(Self {
+				public_key: key.as_public_key().ok_or_else(|| {
+					ContractError::UnexpectedReturnType("Candidate public key".to_string())
+				})?,
+				votes: votes.as_int().ok_or_else(|| {
+					ContractError::UnexpectedReturnType("Candidate votes".to_string())
+				})? as i32,
+			}),
+			_ => Err(ContractError::UnexpectedReturnType("Candidate".to_string())),
+		}
 	}
 }
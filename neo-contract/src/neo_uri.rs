@@ -1,4 +1,7 @@
+//! NEP-9 payment URIs, e.g.
+//! `neo:NZNovnsMvTujbhdpm9vV9ANZ5AzyBPQ1AW?asset=gas&amount=1.5&description=coffee`.
 use crate::{
+	call::ContractCall,
 	error::ContractError,
 	fungible_token_contract::FungibleTokenContract,
 	gas_token::GasToken,
@@ -8,28 +11,21 @@ use crate::{
 	},
 };
 use getset::{Getters, Setters};
-use neo_providers::{
-	core::{
-		account::{Account, AccountTrait},
-		transaction::transaction_builder::TransactionBuilder,
-	},
-	JsonRpcClient, Provider,
-};
+use neo_providers::{core::account::Account, Middleware};
 use neo_types::{
-	script_hash::{ScriptHash, ScriptHashExtension},
+	address::Address,
+	script_hash::{ScriptHash, ScriptHashExt},
+	token_amount::TokenAmount,
 	*,
 };
 use primitive_types::H160;
 use reqwest::Url;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::{
-	borrow::{Borrow, BorrowMut},
-	error::Error,
-	str::FromStr,
-};
+use std::{str::FromStr, sync::Arc};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Getters, Setters)]
-pub struct NeoURI<'a, P: JsonRpcClient> {
+pub struct NeoURI<M: Middleware> {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	#[serde(deserialize_with = "deserialize_url_option")]
 	#[serde(serialize_with = "serialize_url_option")]
@@ -45,58 +41,63 @@ pub struct NeoURI<'a, P: JsonRpcClient> {
 	#[serde(serialize_with = "serialize_script_hash_option")]
 	#[getset(get = "pub", set = "pub")]
 	token: Option<ScriptHash>,
+	/// The amount in token units, e.g. `1.5` GAS
 	#[serde(skip_serializing_if = "Option::is_none")]
 	#[getset(get = "pub", set = "pub")]
-	amount: Option<u64>,
+	amount: Option<Decimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[getset(get = "pub", set = "pub")]
+	description: Option<String>,
 	#[serde(skip)]
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> NeoURI<'a, P> {
+impl<M: Middleware + 'static> NeoURI<M> {
 	const NEO_SCHEME: &'static str = "neo";
 	const MIN_NEP9_URI_LENGTH: usize = 38;
 	const NEO_TOKEN_STRING: &'static str = "neo";
 	const GAS_TOKEN_STRING: &'static str = "gas";
 
-	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
-		Self { uri: None, recipient: None, token: None, amount: None, provider }
+	pub fn new(client: Option<Arc<M>>) -> Self {
+		Self { uri: None, recipient: None, token: None, amount: None, description: None, client }
 	}
 
+	/// Parses a NEP-9 URI.
+	///
+	/// The recipient must be a valid address, `asset` either `neo`, `gas` or the hash of a token
+	/// contract, and `amount` a decimal number. Unknown parameters are ignored, unless they are
+	/// prefixed with `req-` and thus required to be understood.
 	pub fn from_uri(uri_string: &str) -> Result<Self, ContractError> {
-		let parts: Vec<&str> = uri_string.split(".unwrap()").collect();
-		let base = parts[0];
-		let query = if parts.len() > 1 { Some(parts[1]) } else { None };
-
-		let base_parts: Vec<&str> = base.split(":").collect();
-		if base_parts.len() != 2
-			|| base_parts[0] != Self::NEO_SCHEME
-			|| uri_string.len() < Self::MIN_NEP9_URI_LENGTH
-		{
-			return Err(ContractError::InvalidNeoName("Invalid NEP-9 URI".to_string()))
+		let invalid =
+			|reason: &str| ContractError::InvalidArgError(format!("Invalid NEP-9 URI: {reason}"));
+		if uri_string.len() < Self::MIN_NEP9_URI_LENGTH {
+			return Err(invalid("too short"))
 		}
-
-		let mut neo_uri = Self::new(None);
-		neo_uri.set_recipient(ScriptHash::from_address(base_parts[1]).ok());
-
-		if let Some(query_str) = query {
-			for part in query_str.split("&") {
-				let kv: Vec<&str> = part.split("=").collect();
-				if kv.len() != 2 {
-					return Err(ContractError::InvalidNeoName("Invalid query".to_string()))
-				}
-
-				match kv[0] {
-					"asset" if neo_uri.token().is_none() => {
-						&neo_uri.set_token(H160::from_str(kv[1].clone()).ok());
-					},
-					"amount" if neo_uri.amount.is_none() => {
-						neo_uri.amount = Some(kv[1].parse().unwrap());
-					},
-					_ => {},
-				}
+		let uri = Url::parse(uri_string).map_err(|e| invalid(&e.to_string()))?;
+		if uri.scheme() != Self::NEO_SCHEME {
+			return Err(invalid("scheme must be neo"))
+		}
+		let address = Address::from_str(uri.path()).map_err(|e| invalid(&e.to_string()))?;
+
+		let mut neo_uri = Self::new(None).with_recipient(address);
+		for (key, value) in uri.query_pairs() {
+			match key.as_ref() {
+				"asset" if neo_uri.token.is_none() => neo_uri.token_str(&value)?,
+				"amount" if neo_uri.amount.is_none() => {
+					let amount = Decimal::from_str(&value)
+						.ok()
+						.filter(|amount| amount.is_sign_positive() && !amount.is_zero())
+						.ok_or_else(|| invalid(&format!("invalid amount {value}")))?;
+					neo_uri.amount = Some(amount);
+				},
+				"description" if neo_uri.description.is_none() =>
+					neo_uri.description = Some(value.into_owned()),
+				key if key.starts_with("req-") =>
+					return Err(invalid(&format!("unsupported required parameter {key}"))),
+				_ => {},
 			}
 		}
-
+		neo_uri.uri = Some(uri);
 		Ok(neo_uri)
 	}
 
@@ -107,127 +108,235 @@ impl<'a, P: JsonRpcClient> NeoURI<'a, P> {
 	}
 
 	pub fn recipient_address(&self) -> Option<String> {
-		self.recipient.as_ref().map(H160::to_address)
+		self.recipient.map(|recipient| Address::from_script_hash(recipient).to_string())
 	}
 
 	pub fn token_string(&self) -> Option<String> {
-		self.token.as_ref().map(|token| match token {
-			token if *token == NeoToken::<P>::new(None).script_hash() =>
-				Self::NEO_TOKEN_STRING.to_owned(),
-			token if *token == GasToken::<P>::new(None).script_hash() =>
-				Self::GAS_TOKEN_STRING.to_owned(),
-			_ => ScriptHashExtension::to_string(token),
-		})
+		self.token.as_ref().map(Self::asset_string)
 	}
 
 	// Builders
 
+	/// Sets the recipient.
+	#[must_use]
+	pub fn with_recipient(mut self, recipient: Address) -> Self {
+		self.recipient = Some(recipient.script_hash());
+		self
+	}
+
+	/// Sets the token to transfer.
+	#[must_use]
+	pub fn with_token(mut self, token: ScriptHash) -> Self {
+		self.token = Some(token);
+		self
+	}
+
+	/// Sets the amount in token units, e.g. `1.5` GAS.
+	#[must_use]
+	pub fn with_amount(mut self, amount: Decimal) -> Self {
+		self.amount = Some(amount);
+		self
+	}
+
+	/// Sets a description of the payment.
+	#[must_use]
+	pub fn with_description(mut self, description: impl Into<String>) -> Self {
+		self.description = Some(description.into());
+		self
+	}
+
+	/// Builds a transfer of the amount from `sender` to the recipient, using the client set with
+	/// [`NeoURI::new`].
 	pub async fn build_transfer_from(
 		&self,
 		sender: &Account,
-	) -> Result<TransactionBuilder<P>, ContractError> {
-		let recipient = self
-			.recipient
-			.ok_or(ContractError::InvalidStateError("Recipient not set".to_string()))
-			.unwrap();
-		let amount = self
-			.amount
-			.ok_or(ContractError::InvalidStateError("Amount not set".to_string()))
-			.unwrap();
-		let token_hash = self
-			.token
-			.ok_or(ContractError::InvalidStateError("Token not set".to_string()))
-			.unwrap();
-
-		let mut token = &mut FungibleTokenContract::new(&token_hash, self.provider);
-
-		// Validate amount precision
-		let amount_scale = (amount as f64).log10().floor() as u32 + 1; //amount.scale() as u8; //.scale();
-
-		if Self::is_neo_token(&token_hash) && amount_scale > 0 {
-			return Err(ContractError::from(ContractError::InvalidArgError(
-				"NEO does not support decimals".to_string(),
-			)))
-		}
-
-		if Self::is_gas_token(&token_hash)
-			&& amount_scale > GasToken::<P>::new(None).decimals().unwrap() as u32
-		{
-			return Err(ContractError::from(ContractError::InvalidArgError(
-				"Too many decimal places for GAS".to_string(),
-			)))
-		}
-
-		let decimals = token.get_decimals().await.unwrap();
-		if amount_scale > decimals as u32 {
-			return Err(ContractError::from(ContractError::InvalidArgError(
-				"Too many decimal places for token".to_string(),
-			)))
-		}
+	) -> Result<ContractCall<M>, ContractError> {
+		let missing = |field: &str| ContractError::InvalidStateError(format!("{field} not set"));
+		let recipient = self.recipient.ok_or_else(|| missing("Recipient"))?;
+		let amount = self.amount.ok_or_else(|| missing("Amount"))?.normalize();
+		let token_hash = self.token.ok_or_else(|| missing("Token"))?;
+
+		let mut token = FungibleTokenContract::new(&token_hash, self.client.clone());
+		let decimals = if Self::is_neo_token(&token_hash) {
+			NeoToken::<M>::DECIMALS
+		} else if Self::is_gas_token(&token_hash) {
+			GasToken::<M>::DECIMALS
+		} else {
+			token.get_decimals().await?
+		};
+		let amount = TokenAmount::parse(&amount.to_string(), decimals, token_hash)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		let value =
+			amount.value_i64().map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		token.transfer_from_account(sender, &recipient, value, None).await
+	}
 
-		let amt = token.to_fractions(amount, 0).unwrap();
-		token
-			.transfer_from_account(sender, &recipient, amt, None)
-			.await
-			.map_err(|e| ContractError::from(e))
+	/// Builds the transfer described by the URI from `sender`, sent through `client`.
+	pub async fn into_contract_call(
+		mut self,
+		client: Arc<M>,
+		sender: &Account,
+	) -> Result<ContractCall<M>, ContractError> {
+		self.client = Some(client);
+		self.build_transfer_from(sender).await
 	}
 
 	// Helpers
 
 	fn is_neo_token(token: &H160) -> bool {
-		token == &NeoToken::<P>::new(None).script_hash()
+		token == &NeoToken::<M>::new(None).script_hash()
 	}
 
 	fn is_gas_token(token: &H160) -> bool {
-		token == &GasToken::<P>::new(None).script_hash()
+		token == &GasToken::<M>::new(None).script_hash()
+	}
+
+	fn asset_string(token: &H160) -> String {
+		if Self::is_neo_token(token) {
+			Self::NEO_TOKEN_STRING.to_owned()
+		} else if Self::is_gas_token(token) {
+			Self::GAS_TOKEN_STRING.to_owned()
+		} else {
+			format!("{token:x}")
+		}
 	}
 
 	// Setters
 
-	pub fn token_str(&mut self, token_str: &str) {
-		self.token = match token_str {
-			Self::NEO_TOKEN_STRING => Some(NeoToken::new(self.provider).script_hash()),
-			Self::GAS_TOKEN_STRING => Some(GasToken::new(self.provider).script_hash()),
-			_ => Some(token_str.parse().unwrap()),
-		};
+	/// Sets the token from `neo`, `gas` or the hex encoded hash of a token contract.
+	pub fn token_str(&mut self, token_str: &str) -> Result<(), ContractError> {
+		self.token = Some(match token_str {
+			Self::NEO_TOKEN_STRING => NeoToken::<M>::new(None).script_hash(),
+			Self::GAS_TOKEN_STRING => GasToken::<M>::new(None).script_hash(),
+			_ => {
+				let hex = token_str.strip_prefix("0x").unwrap_or(token_str);
+				if hex.len() != 40 {
+					return Err(ContractError::InvalidArgError(format!("Invalid asset {token_str}")))
+				}
+				H160::from_str(hex).map_err(|_| {
+					ContractError::InvalidArgError(format!("Invalid asset {token_str}"))
+				})?
+			},
+		});
+		Ok(())
 	}
 
 	// URI builder
 
-	fn build_query(&self) -> String {
-		let mut parts = Vec::new();
-
-		if let Some(token) = &self.token {
-			let token_str = match token {
-				token if *token == NeoToken::new(self.provider).script_hash() =>
-					Self::NEO_TOKEN_STRING.to_owned(),
-				token if *token == GasToken::new(self.provider).script_hash() =>
-					Self::GAS_TOKEN_STRING.to_owned(),
-				_ => ScriptHashExtension::to_string(token),
-			};
-
-			parts.push(format!("asset={}", token_str));
+	fn to_url(&self) -> Result<Url, ContractError> {
+		let recipient = self
+			.recipient
+			.ok_or(ContractError::InvalidStateError("No recipient set".to_string()))?;
+
+		let address = Address::from_script_hash(recipient);
+		let mut uri = Url::parse(&format!("{}:{address}", Self::NEO_SCHEME))
+			.map_err(|e| ContractError::InvalidArgError(format!("Invalid NEP-9 URI: {e}")))?;
+		if self.token.is_some() || self.amount.is_some() || self.description.is_some() {
+			let mut query = uri.query_pairs_mut();
+			if let Some(token) = &self.token {
+				query.append_pair("asset", &Self::asset_string(token));
+			}
+			if let Some(amount) = &self.amount {
+				query.append_pair("amount", &amount.normalize().to_string());
+			}
+			if let Some(description) = &self.description {
+				query.append_pair("description", description);
+			}
 		}
+		Ok(uri)
+	}
 
-		if let Some(amount) = &self.amount {
-			parts.push(format!("amount={}", amount));
-		}
+	pub fn build_uri(&mut self) -> Result<Url, ContractError> {
+		let uri = self.to_url()?;
+		self.uri = Some(uri.clone());
+		Ok(uri)
+	}
+}
+
+impl<M: Middleware + 'static> FromStr for NeoURI<M> {
+	type Err = ContractError;
 
-		parts.join("&")
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::from_uri(s)
 	}
+}
 
-	pub fn build_uri(&mut self) -> Result<Url, ContractError> {
-		let recipient = self
-			.recipient
-			.ok_or(ContractError::InvalidStateError("No recipient set".to_string()))
-			.unwrap();
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_providers::{MockProvider, Provider};
+	use neo_types::natives;
+	use rust_decimal_macros::dec;
+
+	type Uri = NeoURI<Provider<MockProvider>>;
+
+	#[test]
+	fn test_parse_address_only() {
+		let address = Address::random();
+		let uri = Uri::from_uri(&format!("neo:{address}")).unwrap();
+		assert_eq!(uri.recipient(), &Some(address.script_hash()));
+		assert_eq!(uri.recipient_address(), Some(address.to_string()));
+		assert_eq!(uri.token(), &None);
+		assert_eq!(uri.amount(), &None);
+		assert_eq!(uri.uri_string(), Some(format!("neo:{address}")));
+	}
 
-		let base = format!("{}:{}", Self::NEO_SCHEME, recipient.to_address());
-		let query = self.build_query();
-		let uri_str = if query.is_empty() { base } else { format!("{}.unwrap(){}", base, query) };
+	#[test]
+	fn test_parse_asset_and_amount() {
+		let address = Address::random();
+		let uri =
+			Uri::from_uri(&format!("neo:{address}?asset=gas&amount=1.5&description=coffee&x=1"))
+				.unwrap();
+		assert_eq!(uri.token(), &Some(natives::GAS_TOKEN.hash));
+		assert_eq!(uri.token_string(), Some("gas".to_string()));
+		assert_eq!(uri.amount(), &Some(dec!(1.5)));
+		assert_eq!(uri.description(), &Some("coffee".to_string()));
+
+		let token = H160::repeat_byte(0xab);
+		let uri = Uri::from_uri(&format!("neo:{address}?asset=0x{token:x}&amount=3")).unwrap();
+		assert_eq!(uri.token(), &Some(token));
+		assert_eq!(uri.amount(), &Some(dec!(3)));
+	}
 
-		self.uri = Some(uri_str.parse().unwrap());
+	#[test]
+	fn test_build_uri_round_trip() {
+		let address = Address::random();
+		let mut uri = Uri::new(None)
+			.with_recipient(address)
+			.with_token(natives::NEO_TOKEN.hash)
+			.with_amount(dec!(2.50));
+		let built = uri.build_uri().unwrap();
+		assert_eq!(built.as_str(), format!("neo:{address}?asset=neo&amount=2.5"));
+
+		let parsed = Uri::from_uri(built.as_str()).unwrap();
+		assert_eq!(parsed.token(), &Some(natives::NEO_TOKEN.hash));
+		assert_eq!(parsed.amount(), &Some(dec!(2.5)));
+		assert!(Uri::new(None).build_uri().is_err());
+	}
 
-		Ok(self.uri.clone().unwrap())
+	#[test]
+	fn test_invalid_uris() {
+		let address = Address::random();
+		// changing the last character breaks the checksum
+		let mut bad_checksum = address.to_string();
+		let last = if bad_checksum.pop() == Some('a') { 'b' } else { 'a' };
+		bad_checksum.push(last);
+		let invalid = [
+			"neo:short".to_string(),
+			format!("bitcoin:{address}"),
+			format!("neo:{bad_checksum}"),
+			format!("neo:{address}?asset=btc"),
+			format!("neo:{address}?asset=0x1234"),
+			format!("neo:{address}?amount=abc"),
+			format!("neo:{address}?amount=-1"),
+			format!("neo:{address}?amount=0"),
+			format!("neo:{address}?req-fee=1"),
+		];
+		for uri in invalid {
+			assert!(
+				matches!(Uri::from_uri(&uri), Err(ContractError::InvalidArgError(_))),
+				"{uri} should be rejected"
+			);
+		}
 	}
 }
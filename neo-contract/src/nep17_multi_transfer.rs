@@ -0,0 +1,109 @@
+use crate::error::ContractError;
+use neo_providers::{
+	core::{
+		script::script_builder::{ScriptBuilder, TransferIntent},
+		transaction::{
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction_builder::TransactionBuilder,
+		},
+	},
+	JsonRpcClient,
+};
+use neo_types::{contract_parameter::ContractParameter, Bytes};
+use primitive_types::H160;
+
+/// Packs NEP-17 transfers of any number of tokens, senders and recipients into one transaction,
+/// e.g. for payouts of exchanges or payrolls.
+///
+/// The transfers are executed in the order they were added and either all of them succeed or
+/// the transaction faults. Every sender signs with a witness that is only valid in the tokens
+/// it sends, and the first sender pays the fees.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_contract::nep17_multi_transfer::Nep17MultiTransfer;
+/// use neo_providers::{core::transaction::transaction_builder::TransactionBuilder, Http};
+/// use primitive_types::H160;
+///
+/// # fn foo(gas: H160, exchange: H160, alice: H160, bob: H160) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut payout = Nep17MultiTransfer::new();
+/// payout.add(gas, exchange, alice, 1_0000_0000, None)?;
+/// payout.add(gas, exchange, bob, 2_5000_0000, None)?;
+/// let builder: TransactionBuilder<Http> = payout.to_builder()?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Nep17MultiTransfer {
+	transfers: Vec<TransferIntent>,
+}
+
+impl Nep17MultiTransfer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds the transfer of `amount` of `token` from `from` to `to`.
+	pub fn add(
+		&mut self,
+		token: H160,
+		from: H160,
+		to: H160,
+		amount: i64,
+		data: Option<ContractParameter>,
+	) -> Result<&mut Self, ContractError> {
+		if amount < 0 {
+			return Err(ContractError::InvalidArgError(
+				"The amount must be greater than or equal to 0.".to_string(),
+			))
+		}
+		self.transfers.push(TransferIntent { token, from, to, amount, data });
+		Ok(self)
+	}
+
+	/// The added transfers
+	pub fn transfers(&self) -> &[TransferIntent] {
+		&self.transfers
+	}
+
+	/// Builds the script performing all transfers.
+	pub fn build_script(&self) -> Result<Bytes, ContractError> {
+		ScriptBuilder::build_multi_transfer(&self.transfers)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))
+	}
+
+	/// The signers of the transaction, one per sender in the order the senders were first
+	/// added, each allowed only in the tokens it sends.
+	pub fn signers(&self) -> Result<Vec<Signer>, ContractError> {
+		let mut senders: Vec<(H160, Vec<H160>)> = vec![];
+		for transfer in &self.transfers {
+			match senders.iter_mut().find(|(sender, _)| *sender == transfer.from) {
+				Some((_, tokens)) =>
+					if !tokens.contains(&transfer.token) {
+						tokens.push(transfer.token);
+					},
+				None => senders.push((transfer.from, vec![transfer.token])),
+			}
+		}
+
+		let invalid = |e: &dyn std::fmt::Display| ContractError::InvalidArgError(e.to_string());
+		senders
+			.into_iter()
+			.map(|(sender, tokens)| {
+				let mut signer = AccountSigner::none_hash160(sender).map_err(|e| invalid(&e))?;
+				for token in tokens {
+					signer = signer.allow_contract(token).map_err(|e| invalid(&e))?;
+				}
+				Ok(signer.into())
+			})
+			.collect()
+	}
+
+	/// Creates a transaction builder with the script and the signers of the transfers.
+	pub fn to_builder<P: JsonRpcClient>(&self) -> Result<TransactionBuilder<P>, ContractError> {
+		let mut builder = TransactionBuilder::new();
+		builder.set_script(self.build_script()?);
+		builder.set_signers(self.signers()?);
+		Ok(builder)
+	}
+}
@@ -3,33 +3,34 @@ use crate::{
 	traits::{nft::NonFungibleTokenTrait, smart_contract::SmartContractTrait, token::TokenTrait},
 };
 use async_trait::async_trait;
-use neo_providers::{JsonRpcClient, Provider};
+use neo_providers::Middleware;
 use neo_types::nns_name::NNSName;
 use primitive_types::H160;
+use std::sync::Arc;
 
 #[derive(Debug)]
-pub struct NftContract<'a, P: JsonRpcClient> {
+pub struct NftContract<M: Middleware> {
 	script_hash: H160,
 	total_supply: Option<u64>,
 	decimals: Option<u8>,
 	symbol: Option<String>,
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> NftContract<'a, P> {
-	pub fn new(script_hash: &H160, provider: Option<&'a Provider<P>>) -> Self {
+impl<M: Middleware + 'static> NftContract<M> {
+	pub fn new(script_hash: &H160, client: Option<Arc<M>>) -> Self {
 		Self {
 			script_hash: script_hash.clone(),
 			total_supply: None,
 			decimals: None,
 			symbol: None,
-			provider,
+			client,
 		}
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NftContract<'a, P> {
+impl<M: Middleware + 'static> TokenTrait for NftContract<M> {
 	fn total_supply(&self) -> Option<u64> {
 		self.total_supply
 	}
@@ -60,8 +61,8 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NftContract<'a, P> {
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NftContract<'a, P> {
-	type P = P;
+impl<M: Middleware + 'static> SmartContractTrait for NftContract<M> {
+	type M = M;
 
 	fn script_hash(&self) -> H160 {
 		self.script_hash
@@ -71,10 +72,10 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NftContract<'a, P> {
 		self.script_hash = script_hash;
 	}
 
-	fn provider(&self) -> Option<&Provider<P>> {
-		self.provider
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> NonFungibleTokenTrait<'a, P> for NftContract<'a, P> {}
+impl<M: Middleware + 'static> NonFungibleTokenTrait for NftContract<M> {}
@@ -0,0 +1,448 @@
+//! Notary-assisted transactions of networks running the notary service of neo-go, see
+//! [`NotaryContract`] and [`NotaryRequest`].
+use crate::{call::ContractCall, error::ContractError, traits::smart_contract::SmartContractTrait};
+use async_trait::async_trait;
+use neo_codec::{encode::NeoSerializable, Decoder, Encoder};
+use neo_crypto::{hash::HashableForVec, key_pair::KeyPair};
+use neo_providers::{
+	core::{
+		script::script_builder::ScriptBuilder,
+		transaction::{
+			call_flags::CallFlags,
+			invocation_script::InvocationScript,
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction::Transaction,
+			transaction_attribute::TransactionAttribute,
+			transaction_error::TransactionError,
+			verification_script::VerificationScript,
+			witness::Witness,
+		},
+	},
+	Middleware,
+};
+use neo_types::{
+	contract_parameter::ContractParameter, contract_parameter_type::ContractParameterType,
+	encoding, gas::Gas, op_code::OpCode, script_hash::ScriptHash, *,
+};
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Client for the native `Notary` contract of neo-go networks.
+///
+/// Transactions signed by several parties can be completed by the notary service instead of
+/// passing them around: every party sends a [`NotaryRequest`] holding the main transaction with
+/// its signature, and a fallback transaction that is persisted instead if the main transaction
+/// does not get all signatures before the fallback becomes valid. The fees of the requests are
+/// paid from GAS deposited to the contract, see [`deposit`](Self::deposit).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotaryContract<M: Middleware> {
+	#[serde(deserialize_with = "deserialize_script_hash")]
+	#[serde(serialize_with = "serialize_script_hash")]
+	script_hash: ScriptHash,
+	#[serde(skip)]
+	client: Option<Arc<M>>,
+}
+
+impl<M: Middleware + 'static> NotaryContract<M> {
+	pub const NAME: &'static str = natives::NOTARY.name;
+
+	pub fn new(client: Option<Arc<M>>) -> Self {
+		Self { script_hash: natives::NOTARY.hash, client }
+	}
+
+	/// Gets the GAS fractions deposited by `account`.
+	pub async fn balance_of(&self, account: H160) -> Result<i64, ContractError> {
+		self.call_function_returning_i64("balanceOf", vec![account.into()]).await
+	}
+
+	/// Gets the block until which the deposit of `account` is locked.
+	pub async fn expiration_of(&self, account: H160) -> Result<u32, ContractError> {
+		self.call_function_returning_i64("expirationOf", vec![account.into()])
+			.await
+			.map(|height| height as u32)
+	}
+
+	/// Gets the maximum number of blocks the `NotValidBefore` height of a fallback transaction
+	/// may be ahead of the current height.
+	pub async fn get_max_not_valid_before_delta(&self) -> Result<u32, ContractError> {
+		self.call_function_returning_i64("getMaxNotValidBeforeDelta", vec![])
+			.await
+			.map(|delta| delta as u32)
+	}
+
+	/// Creates a transaction depositing `amount` GAS fractions of `from` for `to`, or `from` if
+	/// `to` is `None`, locked until the block `till`.
+	///
+	/// The deposit is a GAS transfer to the contract, signed by `from`.
+	pub fn deposit(
+		&self,
+		from: &H160,
+		amount: i64,
+		to: Option<H160>,
+		till: u32,
+	) -> Result<ContractCall<M>, ContractError> {
+		if amount <= 0 {
+			return Err(ContractError::InvalidArgError("The deposit must be positive".to_string()))
+		}
+		let to = match to {
+			Some(to) => to.into(),
+			None => ContractParameter::new(ContractParameterType::Any),
+		};
+		let data = ContractParameter::array(vec![to, ContractParameter::integer(till as i64)]);
+		let params =
+			[from.into(), self.script_hash.into(), ContractParameter::integer(amount), data];
+		self.contract_call(&natives::GAS_TOKEN.hash, "transfer", &params)
+	}
+
+	/// Creates a transaction extending the lock of the deposit of `account` until the block
+	/// `till`, signed by `account`.
+	pub fn lock_deposit_until(
+		&self,
+		account: &H160,
+		till: u32,
+	) -> Result<ContractCall<M>, ContractError> {
+		let params = [account.into(), ContractParameter::integer(till as i64)];
+		self.contract_call(&self.script_hash, "lockDepositUntil", &params)
+	}
+
+	/// Creates a transaction withdrawing the expired deposit of `from` to `to`, signed by `from`.
+	pub fn withdraw(&self, from: &H160, to: &H160) -> Result<ContractCall<M>, ContractError> {
+		self.contract_call(&self.script_hash, "withdraw", &[from.into(), to.into()])
+	}
+
+	/// Builds the main transaction of a notary request, without the signatures.
+	///
+	/// `signers` are the accounts signing the transaction with their witness holding the
+	/// verification script, or the arguments of `verify` for a contract. The notary contract is
+	/// added as the last signer, and the number of keys the notary service waits for is counted
+	/// from the verification scripts. The fees are calculated by the node.
+	pub async fn build_main_transaction(
+		&self,
+		script: Bytes,
+		signers: Vec<(Signer, Witness)>,
+		valid_until_block: u32,
+	) -> Result<Transaction, ContractError> {
+		let client = self.require_client()?;
+		if signers.is_empty() {
+			return Err(ContractError::InvalidArgError("The transaction has no signers".to_string()))
+		}
+		let mut n_keys = 0u8;
+		for (signer, witness) in &signers {
+			let verification = &witness.verification;
+			// a multi-sig account needs the signatures of all its keys, contracts none
+			if verification.is_single_sig() || verification.is_multi_sig() {
+				n_keys += verification
+					.get_nr_of_accounts()
+					.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
+					as u8;
+			}
+			if !verification.script().is_empty() && verification.hash() != *signer.get_signer_hash()
+			{
+				return Err(ContractError::InvalidArgError(format!(
+					"The verification script does not match the signer {:#x}",
+					signer.get_signer_hash()
+				)))
+			}
+		}
+		let (mut signers, mut witnesses): (Vec<_>, Vec<_>) = signers.into_iter().unzip();
+		signers.push(self.notary_signer()?);
+		witnesses.push(Self::notary_witness()?);
+
+		let mut tx = Transaction {
+			nonce: rand::random(),
+			valid_until_block: valid_until_block as i32,
+			signers,
+			attributes: vec![TransactionAttribute::NotaryAssisted { n_keys }],
+			script,
+			witnesses,
+			..Default::default()
+		};
+		let result = client
+			.invoke_script(encoding::base64_encode(&tx.script), tx.signers.clone())
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		if result.has_state_fault() {
+			return Err(ContractError::RuntimeError(format!(
+				"The script faults: {}",
+				result.exception.unwrap_or_default()
+			)))
+		}
+		tx.sys_fee = result.gas_consumed.parse::<Gas>().map_err(|e| {
+			ContractError::UnexpectedReturnType(format!("Invalid GAS consumed: {e}"))
+		})?;
+		tx.net_fee = client
+			.calculate_network_fee(encoding::base64_encode(tx.to_array()))
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		Ok(tx)
+	}
+
+	/// Creates the notary request of `key_pair` for `main`, built by
+	/// [`build_main_transaction`](Self::build_main_transaction).
+	///
+	/// Signs `main` with `key_pair`, which has to be one of the keys of its signers, and builds
+	/// the fallback transaction paid from the deposit of `key_pair`. The fallback becomes valid
+	/// half of the maximum delta after the current height.
+	pub async fn build_request(
+		&self,
+		mut main: Transaction,
+		key_pair: &KeyPair,
+	) -> Result<NotaryRequest, ContractError> {
+		let client = self.require_client()?;
+		let network_magic =
+			client.get_network_magic().await.map_err(ContractError::from_middleware_error)?;
+		let sign = |data: Bytes| {
+			key_pair
+				.private_key
+				.sign_tx(&data)
+				.map_err(|e| ContractError::RuntimeError(e.to_string()))
+		};
+
+		let signature = sign(main.get_hash_data(network_magic))?;
+		let mut signed = false;
+		for witness in &mut main.witnesses {
+			if witness.verification.script().is_empty() {
+				continue
+			}
+			let keys = witness.verification.get_public_keys().unwrap_or_default();
+			if keys.contains(&key_pair.public_key) {
+				witness.invocation = InvocationScript::from_signature(&signature)
+					.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+				signed = true;
+			}
+		}
+		if !signed {
+			return Err(ContractError::InvalidArgError(
+				"The key is not one of the signers of the main transaction".to_string(),
+			))
+		}
+
+		let height =
+			client.get_block_count().await.map_err(ContractError::from_middleware_error)?;
+		let not_valid_before = height + self.get_max_not_valid_before_delta().await? / 2;
+		if not_valid_before >= main.valid_until_block as u32 {
+			return Err(ContractError::InvalidArgError(format!(
+				"The main transaction expires before the fallback becomes valid at {not_valid_before}"
+			)))
+		}
+		let verification = VerificationScript::from_public_key(&key_pair.public_key)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let sender = AccountSigner::none_hash160(verification.hash())
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let mut fallback = Transaction {
+			nonce: rand::random(),
+			valid_until_block: main.valid_until_block,
+			signers: vec![self.notary_signer()?, sender.into()],
+			attributes: vec![
+				TransactionAttribute::NotValidBefore { height: not_valid_before },
+				TransactionAttribute::Conflicts { hash: main.hash() },
+				TransactionAttribute::NotaryAssisted { n_keys: 0 },
+			],
+			script: vec![OpCode::Ret as u8],
+			witnesses: vec![
+				Self::notary_witness()?,
+				Witness::from_scripts_obj(InvocationScript::new(), verification),
+			],
+			..Default::default()
+		};
+		fallback.net_fee = client
+			.calculate_network_fee(encoding::base64_encode(fallback.to_array()))
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		fallback.witnesses[1].invocation =
+			InvocationScript::from_signature(&sign(fallback.get_hash_data(network_magic))?)
+				.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+
+		let mut request = NotaryRequest {
+			main_transaction: main,
+			fallback_transaction: fallback,
+			witness: Witness::from_scripts_obj(InvocationScript::new(), VerificationScript::new()),
+		};
+		request.witness = Witness::create(request.get_hash_data(network_magic), key_pair)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		Ok(request)
+	}
+
+	/// Sends `request` to the notary service of the node.
+	pub async fn submit_request(&self, request: &NotaryRequest) -> Result<(), ContractError> {
+		self.require_client()?
+			.provider()
+			.request::<_, serde_json::Value>(
+				"submitnotaryrequest",
+				[encoding::base64_encode(request.to_array())],
+			)
+			.await?;
+		Ok(())
+	}
+
+	/// Gets which of the transactions of `request` was persisted.
+	pub async fn outcome(&self, request: &NotaryRequest) -> Result<NotaryOutcome, ContractError> {
+		let client = self.require_client()?;
+		let main = request.main_transaction.hash();
+		if self.is_persisted(main).await? {
+			return Ok(NotaryOutcome::Main(main))
+		}
+		let fallback = request.fallback_transaction.hash();
+		if self.is_persisted(fallback).await? {
+			return Ok(NotaryOutcome::Fallback(fallback))
+		}
+		let height =
+			client.get_block_count().await.map_err(ContractError::from_middleware_error)?;
+		if height > request.main_transaction.valid_until_block as u32 {
+			return Ok(NotaryOutcome::Expired)
+		}
+		Ok(NotaryOutcome::Pending)
+	}
+
+	async fn is_persisted(&self, hash: H256) -> Result<bool, ContractError> {
+		let tx = self
+			.require_client()?
+			.get_transaction(hash)
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		Ok(tx.map_or(false, |tx| tx.block_hash.is_some()))
+	}
+
+	fn notary_signer(&self) -> Result<Signer, ContractError> {
+		AccountSigner::none_hash160(self.script_hash)
+			.map(Signer::from)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))
+	}
+
+	/// The witness of the notary contract, whose signature is filled in by the notary service
+	fn notary_witness() -> Result<Witness, ContractError> {
+		let invocation = ScriptBuilder::new()
+			.push_data(vec![0; 64])
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?
+			.to_bytes();
+		Ok(Witness::from_scripts(invocation, vec![]))
+	}
+
+	fn contract_call(
+		&self,
+		contract: &H160,
+		method: &str,
+		params: &[ContractParameter],
+	) -> Result<ContractCall<M>, ContractError> {
+		// deposits and withdrawals transfer GAS, which `CallFlags::None` forbids
+		let script = ScriptBuilder::new()
+			.contract_call(contract, method, params, CallFlags::All)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
+			.to_bytes();
+		Ok(ContractCall::new(self.require_client()?.clone(), script))
+	}
+
+	async fn call_function_returning_i64(
+		&self,
+		function: &str,
+		params: Vec<ContractParameter>,
+	) -> Result<i64, ContractError> {
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+		output
+			.stack
+			.first()
+			.and_then(|item| item.as_int())
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Int".to_string()))
+	}
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> SmartContractTrait for NotaryContract<M> {
+	type M = M;
+
+	fn script_hash(&self) -> H160 {
+		self.script_hash
+	}
+
+	fn set_script_hash(&mut self, script_hash: H160) {
+		self.script_hash = script_hash;
+	}
+
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
+	}
+}
+
+/// A request to the notary service: the main transaction signed by the sender of the request,
+/// and the fallback transaction persisted if the main transaction is not completed in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotaryRequest {
+	pub main_transaction: Transaction,
+	pub fallback_transaction: Transaction,
+	/// The witness of the sender of the request, the second signer of the fallback transaction
+	pub witness: Witness,
+}
+
+impl NotaryRequest {
+	/// The hash of the request, the SHA-256 of the main and the fallback transaction in the
+	/// byte order used by the RPC interface.
+	pub fn hash(&self) -> H256 {
+		let mut hash = self.digest();
+		hash.reverse();
+		H256::from_slice(&hash)
+	}
+
+	/// The data signed by the witness of the request on the network with the magic
+	/// `network_magic`.
+	pub fn get_hash_data(&self, network_magic: u32) -> Bytes {
+		let mut data = network_magic.to_le_bytes().to_vec();
+		data.extend(self.digest());
+		data
+	}
+
+	/// The account sending the request and paying for the fallback transaction.
+	pub fn sender(&self) -> Option<H160> {
+		self.fallback_transaction.signers.get(1).map(|signer| *signer.get_signer_hash())
+	}
+
+	fn digest(&self) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		self.main_transaction.encode(&mut writer);
+		self.fallback_transaction.encode(&mut writer);
+		writer.to_bytes().hash256()
+	}
+}
+
+impl NeoSerializable for NotaryRequest {
+	type Error = TransactionError;
+
+	fn size(&self) -> usize {
+		self.main_transaction.size() + self.fallback_transaction.size() + self.witness.size()
+	}
+
+	fn encode(&self, writer: &mut Encoder) {
+		self.main_transaction.encode(writer);
+		self.fallback_transaction.encode(writer);
+		self.witness.encode(writer);
+	}
+
+	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
+		Ok(Self {
+			main_transaction: Transaction::decode(reader)?,
+			fallback_transaction: Transaction::decode(reader)?,
+			witness: Witness::decode(reader)
+				.map_err(|e| TransactionError::IllegalState(e.to_string()))?,
+		})
+	}
+
+	fn to_array(&self) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		self.encode(&mut writer);
+		writer.to_bytes()
+	}
+}
+
+/// Which transaction of a [`NotaryRequest`] was persisted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotaryOutcome {
+	/// Neither transaction is persisted yet
+	Pending,
+	/// The main transaction with the given hash was completed and persisted
+	Main(H256),
+	/// The fallback transaction with the given hash was persisted
+	Fallback(H256),
+	/// Both transactions expired
+	Expired,
+}
@@ -0,0 +1,272 @@
+use crate::{
+	call::ContractCall,
+	error::ContractError,
+	events::{ContractEventType, DecodedEvent, EventValue},
+	traits::smart_contract::SmartContractTrait,
+};
+use async_trait::async_trait;
+use neo_providers::{
+	core::{
+		responses::neo_transaction_result::TransactionResult,
+		script::script_builder::ScriptBuilder,
+		transaction::{call_flags::CallFlags, transaction_attribute::OracleResponse},
+	},
+	Middleware,
+};
+use neo_types::{
+	contract_parameter::ContractParameter, contract_parameter_type::ContractParameterType,
+	script_hash::ScriptHash, *,
+};
+use primitive_types::H160;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Client for the native `OracleContract`.
+///
+/// Contracts fetch data from outside the chain with an oracle request. Once the designated
+/// oracle nodes agreed on the data, they send a transaction with an [`OracleResponse`]
+/// attribute that calls the callback method of the requesting contract.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleContract<M: Middleware> {
+	#[serde(deserialize_with = "deserialize_script_hash")]
+	#[serde(serialize_with = "serialize_script_hash")]
+	script_hash: ScriptHash,
+	#[serde(skip)]
+	client: Option<Arc<M>>,
+}
+
+impl<M: Middleware + 'static> OracleContract<M> {
+	pub const NAME: &'static str = natives::ORACLE_CONTRACT.name;
+	/// The maximum length of the URL in bytes
+	pub const MAX_URL_LENGTH: usize = 256;
+	/// The maximum length of the filter in bytes
+	pub const MAX_FILTER_LENGTH: usize = 128;
+	/// The maximum length of the name of the callback method in bytes
+	pub const MAX_CALLBACK_LENGTH: usize = 32;
+	/// The minimum GAS, in fractions, paid for the response transaction
+	pub const MIN_GAS_FOR_RESPONSE: i64 = 10_000_000;
+
+	pub fn new(client: Option<Arc<M>>) -> Self {
+		Self { script_hash: natives::ORACLE_CONTRACT.hash, client }
+	}
+
+	/// Gets the price of a request in GAS fractions, paid in addition to the GAS for the
+	/// response.
+	pub async fn get_price(&self) -> Result<i32, ContractError> {
+		self.call_function_returning_int("getPrice", vec![]).await
+	}
+
+	/// Creates a transaction setting the price of a request. Requires the signature of the
+	/// committee.
+	pub async fn set_price(&self, price: i32) -> Result<ContractCall<M>, ContractError> {
+		self.invoke_function("setPrice", vec![price.into()]).await
+	}
+
+	/// Creates a transaction requesting the data at `url`.
+	///
+	/// `filter` is a JSONPath expression selecting the part of the response that is returned,
+	/// `callback` the method called with the response and `user_data` is passed to it
+	/// unchanged. `gas_for_response` GAS fractions are paid for the response transaction.
+	///
+	/// The node calls back the contract making the request and only accepts requests made by
+	/// a deployed contract, so the script is meant to be executed by a contract on behalf of
+	/// the sender.
+	pub async fn request(
+		&self,
+		url: &str,
+		filter: Option<&str>,
+		callback: &str,
+		user_data: ContractParameter,
+		gas_for_response: i64,
+	) -> Result<ContractCall<M>, ContractError> {
+		Self::check_request(url, filter, callback, gas_for_response)?;
+		let filter = match filter {
+			Some(filter) => filter.into(),
+			None => ContractParameter::new(ContractParameterType::Any),
+		};
+		let params = [
+			url.into(),
+			filter,
+			callback.into(),
+			user_data,
+			ContractParameter::integer(gas_for_response),
+		];
+		// the request mints GAS and emits a notification, which `CallFlags::None` forbids
+		let script = ScriptBuilder::new()
+			.contract_call(&self.script_hash, "request", &params, CallFlags::All)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
+			.to_bytes();
+		Ok(ContractCall::new(self.require_client()?.clone(), script))
+	}
+
+	/// The oracle response carried by `tx`, if `tx` answers an oracle request.
+	pub fn oracle_response(tx: &TransactionResult) -> Option<&OracleResponse> {
+		tx.attributes.iter().find_map(|attribute| attribute.oracle_response())
+	}
+
+	/// Checks the arguments of a request against the limits enforced by the node.
+	fn check_request(
+		url: &str,
+		filter: Option<&str>,
+		callback: &str,
+		gas_for_response: i64,
+	) -> Result<(), ContractError> {
+		if url.len() > Self::MAX_URL_LENGTH {
+			return Err(ContractError::InvalidArgError(format!(
+				"The URL must not be longer than {} bytes",
+				Self::MAX_URL_LENGTH
+			)))
+		}
+		if filter.map_or(false, |filter| filter.len() > Self::MAX_FILTER_LENGTH) {
+			return Err(ContractError::InvalidArgError(format!(
+				"The filter must not be longer than {} bytes",
+				Self::MAX_FILTER_LENGTH
+			)))
+		}
+		if callback.len() > Self::MAX_CALLBACK_LENGTH || callback.starts_with('_') {
+			return Err(ContractError::InvalidArgError(format!("Invalid callback {callback}")))
+		}
+		if gas_for_response < Self::MIN_GAS_FOR_RESPONSE {
+			return Err(ContractError::InvalidArgError(format!(
+				"The GAS for the response must be at least {}",
+				Self::MIN_GAS_FOR_RESPONSE
+			)))
+		}
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> SmartContractTrait for OracleContract<M> {
+	type M = M;
+
+	fn script_hash(&self) -> H160 {
+		self.script_hash
+	}
+
+	fn set_script_hash(&mut self, script_hash: H160) {
+		self.script_hash = script_hash;
+	}
+
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
+	}
+}
+
+/// The `OracleRequest` event of the oracle contract, emitted for every new request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleRequestEvent {
+	/// The ID of the request, referenced by the [`OracleResponse`]
+	pub id: i64,
+	/// The contract that made the request and receives the callback
+	pub request_contract: H160,
+	pub url: String,
+	pub filter: Option<String>,
+}
+
+impl ContractEventType for OracleRequestEvent {
+	const NAME: &'static str = "OracleRequest";
+
+	fn from_event(event: &DecodedEvent) -> Result<Self, ContractError> {
+		let missing = |name: &str| {
+			ContractError::UnexpectedReturnType(format!("OracleRequest without valid {name}"))
+		};
+		let filter = match event.param("Filter") {
+			Some(EventValue::Null) => None,
+			Some(value) => Some(value.as_str().ok_or_else(|| missing("Filter"))?.to_string()),
+			None => return Err(missing("Filter")),
+		};
+		Ok(Self {
+			id: event.param("Id").and_then(EventValue::as_int).ok_or_else(|| missing("Id"))?,
+			request_contract: event
+				.param("RequestContract")
+				.and_then(EventValue::as_hash160)
+				.ok_or_else(|| missing("RequestContract"))?,
+			url: event
+				.param("Url")
+				.and_then(EventValue::as_str)
+				.ok_or_else(|| missing("Url"))?
+				.to_string(),
+			filter,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_providers::{
+		core::transaction::{oracle_response_code::OracleResponseCode, transaction::Transaction},
+		MockProvider, Provider,
+	};
+	use serde_json::json;
+
+	type Oracle = OracleContract<Provider<MockProvider>>;
+
+	#[test]
+	fn test_oracle_response() {
+		let mut tx = TransactionResult::from(&Transaction::default());
+		assert_eq!(Oracle::oracle_response(&tx), None);
+
+		tx.attributes = serde_json::from_value(json!([
+			{"type": "HighPriority"},
+			{"type": "OracleResponse", "id": 5, "code": "Success", "result": "eyJwcmljZSI6MX0="}
+		]))
+		.unwrap();
+		let response = Oracle::oracle_response(&tx).unwrap();
+		assert_eq!(response.id, 5);
+		assert_eq!(response.response_code, OracleResponseCode::Success);
+		assert_eq!(response.result_bytes().unwrap(), br#"{"price":1}"#);
+
+		tx.attributes = serde_json::from_value(json!([
+			{"type": "OracleResponse", "id": 6, "code": "NotFound", "result": ""}
+		]))
+		.unwrap();
+		let response = Oracle::oracle_response(&tx).unwrap();
+		assert_eq!(response.response_code, OracleResponseCode::NotFound);
+		assert_eq!(response.result_bytes().unwrap(), Vec::<u8>::new());
+	}
+
+	#[test]
+	fn test_request_event() {
+		let contract = H160::repeat_byte(1);
+		let mut event = DecodedEvent {
+			contract: natives::ORACLE_CONTRACT.hash,
+			name: "OracleRequest".to_string(),
+			params: vec![
+				("Id".to_string(), EventValue::Integer(7)),
+				("RequestContract".to_string(), EventValue::Hash160(contract)),
+				("Url".to_string(), EventValue::String("https://example.com".to_string())),
+				("Filter".to_string(), EventValue::Null),
+			],
+		};
+		let request: OracleRequestEvent = event.parse().unwrap();
+		assert_eq!(
+			request,
+			OracleRequestEvent {
+				id: 7,
+				request_contract: contract,
+				url: "https://example.com".to_string(),
+				filter: None,
+			}
+		);
+
+		event.params[3].1 = EventValue::String("$.price".to_string());
+		assert_eq!(event.parse::<OracleRequestEvent>().unwrap().filter.as_deref(), Some("$.price"));
+		event.params.pop();
+		assert!(event.parse::<OracleRequestEvent>().is_err());
+	}
+
+	#[test]
+	fn test_check_request() {
+		let gas = Oracle::MIN_GAS_FOR_RESPONSE;
+		assert!(
+			Oracle::check_request("https://example.com", Some("$.price"), "callback", gas).is_ok()
+		);
+		assert!(Oracle::check_request(&"a".repeat(257), None, "callback", gas).is_err());
+		assert!(Oracle::check_request("https://example.com", Some(&"a".repeat(129)), "cb", gas)
+			.is_err());
+		assert!(Oracle::check_request("https://example.com", None, "_callback", gas).is_err());
+		assert!(Oracle::check_request("https://example.com", None, "callback", gas - 1).is_err());
+	}
+}
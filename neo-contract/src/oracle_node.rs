@@ -0,0 +1,443 @@
+//! Tools for oracle node operators answering oracle requests, see [`OracleNode`].
+use crate::{error::ContractError, role_management::RoleManagement};
+use neo_codec::{encode::NeoSerializable, CodecError, Decoder};
+use neo_crypto::{
+	key_pair::KeyPair,
+	keys::{Secp256r1PublicKey, Secp256r1Signature},
+};
+use neo_providers::{
+	core::{
+		script::script_builder::ScriptBuilder,
+		transaction::{
+			call_flags::CallFlags,
+			invocation_script::InvocationScript,
+			oracle_response_code::OracleResponseCode,
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction::Transaction,
+			transaction_attribute::{OracleResponse, TransactionAttribute},
+			verification_script::VerificationScript,
+			witness::Witness,
+		},
+	},
+	JsonRpcClient, Middleware, PendingTransaction, Provider,
+};
+use neo_types::{encoding, gas::Gas, natives, role::Role, Bytes};
+use primitive_types::{H160, H256};
+use std::sync::Arc;
+
+/// An oracle request waiting for its response, as stored by the native `OracleContract`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleRequest {
+	pub id: u64,
+	/// The transaction that made the request
+	pub original_tx_id: H256,
+	/// The GAS fractions paying the fees of the response transaction
+	pub gas_for_response: i64,
+	pub url: String,
+	/// The JSONPath expression selecting the part of the response that is returned
+	pub filter: Option<String>,
+	/// The contract that made the request and is called back with the response
+	pub callback_contract: H160,
+	pub callback_method: String,
+	/// The user data passed to the callback, in the binary serialization of the VM
+	pub user_data: Bytes,
+}
+
+impl OracleRequest {
+	/// Decodes a request from an entry of the storage of the `OracleContract`.
+	pub fn from_storage(key: &[u8], value: &[u8]) -> Result<Self, ContractError> {
+		Self::decode(key, value).map_err(|e| {
+			ContractError::UnexpectedReturnType(format!("Invalid oracle request: {e}"))
+		})
+	}
+
+	fn decode(key: &[u8], value: &[u8]) -> Result<Self, CodecError> {
+		let mut reader = Decoder::new(key);
+		if reader.read_u8()? != PREFIX_REQUEST {
+			return Err(reader.invalid_data("not the key of a request"))
+		}
+		let id = u64::from_be_bytes(reader.read_array()?);
+
+		let mut reader = Decoder::new(value);
+		if reader.read_u8()? != ARRAY || reader.read_var_int()? != 7 {
+			return Err(reader.invalid_data("expected an array of 7 items"))
+		}
+		let mut original_tx_id = read_bytes(&mut reader)?.unwrap_or_default();
+		let gas_for_response = read_integer(&mut reader)?;
+		let url = read_string(&mut reader)?.ok_or_else(|| reader.invalid_data("missing URL"))?;
+		let filter = read_string(&mut reader)?;
+		let mut callback_contract = read_bytes(&mut reader)?.unwrap_or_default();
+		let callback_method = read_string(&mut reader)?
+			.ok_or_else(|| reader.invalid_data("missing callback method"))?;
+		let user_data = read_bytes(&mut reader)?.unwrap_or_default();
+		if original_tx_id.len() != 32 || callback_contract.len() != 20 {
+			return Err(reader.invalid_data("invalid transaction hash or callback contract"))
+		}
+		// both are stored in little-endian order
+		original_tx_id.reverse();
+		callback_contract.reverse();
+		Ok(Self {
+			id,
+			original_tx_id: H256::from_slice(&original_tx_id),
+			gas_for_response,
+			url,
+			filter,
+			callback_contract: H160::from_slice(&callback_contract),
+			callback_method,
+			user_data,
+		})
+	}
+}
+
+/// The storage prefix of the requests, followed by the ID in big-endian order
+const PREFIX_REQUEST: u8 = 7;
+
+// The types of stack items in the binary serialization of the VM
+const ANY: u8 = 0x00;
+const INTEGER: u8 = 0x21;
+const BYTE_STRING: u8 = 0x28;
+const BUFFER: u8 = 0x30;
+const ARRAY: u8 = 0x40;
+
+/// Reads a byte string, `None` for `Any`
+fn read_bytes(reader: &mut Decoder) -> Result<Option<Bytes>, CodecError> {
+	match reader.read_u8()? {
+		ANY => Ok(None),
+		BYTE_STRING | BUFFER => reader.read_var_bytes().map(Some),
+		other => Err(reader.invalid_data(format!("expected a byte string, got type {other:#04x}"))),
+	}
+}
+
+fn read_string(reader: &mut Decoder) -> Result<Option<String>, CodecError> {
+	match read_bytes(reader)? {
+		Some(bytes) => String::from_utf8(bytes)
+			.map(Some)
+			.map_err(|_| reader.invalid_data("invalid UTF-8")),
+		None => Ok(None),
+	}
+}
+
+/// Reads an integer of at most 8 bytes, stored in little-endian two's complement
+fn read_integer(reader: &mut Decoder) -> Result<i64, CodecError> {
+	if reader.read_u8()? != INTEGER {
+		return Err(reader.invalid_data("expected an integer"))
+	}
+	let bytes = reader.read_var_bytes()?;
+	if bytes.len() > 8 {
+		return Err(reader.invalid_data("integer out of range"))
+	}
+	let fill = if bytes.last().map_or(false, |byte| byte & 0x80 != 0) { 0xff } else { 0 };
+	let mut buffer = [fill; 8];
+	buffer[..bytes.len()].copy_from_slice(&bytes);
+	Ok(i64::from_le_bytes(buffer))
+}
+
+/// Answers oracle requests as one of the oracle nodes designated by the `RoleManagement`
+/// contract.
+///
+/// Every designated node builds the same response transaction for a request with
+/// [`build_response`](Self::build_response), signs it with [`sign_response`](Self::sign_response)
+/// and sends its signature to the other nodes with
+/// [`submit_signature`](Self::submit_signature). Once enough signatures are collected, the
+/// transaction is completed with [`complete_response`](Self::complete_response) and sent with
+/// [`send_response`](Self::send_response). A request is removed from the storage of the
+/// `OracleContract` when its response is persisted, see [`is_finished`](Self::is_finished).
+///
+/// The response has to be built from the same data by every node, otherwise the signatures do
+/// not match.
+#[derive(Debug, Clone)]
+pub struct OracleNode<M: Middleware> {
+	client: Arc<M>,
+	key_pair: KeyPair,
+	oracle_hash: H160,
+}
+
+impl<M: Middleware + 'static> OracleNode<M> {
+	/// The maximum size of the result in bytes
+	pub const MAX_RESULT_SIZE: usize = 0xffff;
+
+	/// Creates a node signing with `key_pair`, which must be designated as an oracle node.
+	pub fn new(client: Arc<M>, key_pair: KeyPair) -> Self {
+		Self { client, key_pair, oracle_hash: natives::ORACLE_CONTRACT.hash }
+	}
+
+	pub fn public_key(&self) -> &Secp256r1PublicKey {
+		&self.key_pair.public_key
+	}
+
+	/// The number of signatures required for a response of `nodes` oracle nodes.
+	pub fn signing_threshold(nodes: usize) -> usize {
+		nodes - (nodes - 1) / 3
+	}
+
+	/// The public keys of the oracle nodes designated at the current height, in the order of
+	/// their multi-sig account.
+	pub async fn oracle_nodes(&self) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
+		let block_count = self
+			.client
+			.get_block_count()
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		let mut nodes = RoleManagement::new(Some(self.client.clone()))
+			.get_designated_by_role(Role::Oracle, block_count)
+			.await?;
+		nodes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		Ok(nodes)
+	}
+
+	/// Whether the key of this node is designated as an oracle node at the current height.
+	pub async fn is_designated(&self) -> Result<bool, ContractError> {
+		Ok(self.oracle_nodes().await?.contains(self.public_key()))
+	}
+
+	/// The requests waiting for a response.
+	pub async fn pending_requests(&self) -> Result<Vec<OracleRequest>, ContractError> {
+		let mut requests = Vec::new();
+		let mut start = 0;
+		loop {
+			let page = self
+				.client
+				.find_storage(self.oracle_hash, &[PREFIX_REQUEST], start)
+				.await
+				.map_err(ContractError::from_middleware_error)?;
+			for entry in &page.results {
+				requests.push(OracleRequest::from_storage(&entry.key, &entry.value)?);
+			}
+			if !page.truncated {
+				return Ok(requests)
+			}
+			start = page.next;
+		}
+	}
+
+	/// The request with the ID `id`, `None` if it does not exist or was answered.
+	pub async fn request(&self, id: u64) -> Result<Option<OracleRequest>, ContractError> {
+		let mut key = vec![PREFIX_REQUEST];
+		key.extend(id.to_be_bytes());
+		let page = self
+			.client
+			.find_storage(self.oracle_hash, &key, 0)
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		page.results
+			.iter()
+			.find(|entry| entry.key == key)
+			.map(|entry| OracleRequest::from_storage(&entry.key, &entry.value))
+			.transpose()
+	}
+
+	/// Whether the response to the request `id` is persisted, i.e. the request is removed.
+	pub async fn is_finished(&self, id: u64) -> Result<bool, ContractError> {
+		Ok(self.request(id).await?.is_none())
+	}
+
+	/// Builds the transaction answering `request` with `code` and `result`, without witnesses.
+	///
+	/// The transaction is built the same way as by the oracle service of the reference node,
+	/// so it gets the same hash on every node. The result is dropped if `code` is not
+	/// [`OracleResponseCode::Success`], and replaced by [`OracleResponseCode::ResponseTooLarge`]
+	/// or [`OracleResponseCode::InsufficientFunds`] if it is too large or the GAS for the
+	/// response does not cover the network fee.
+	pub async fn build_response(
+		&self,
+		request: &OracleRequest,
+		code: OracleResponseCode,
+		result: &[u8],
+	) -> Result<Transaction, ContractError> {
+		let nodes = self.oracle_nodes().await?;
+		if nodes.is_empty() {
+			return Err(ContractError::InvalidStateError(
+				"No oracle nodes are designated".to_string(),
+			))
+		}
+		let threshold = Self::signing_threshold(nodes.len());
+		let verification = VerificationScript::from_multi_sig(&nodes, threshold as u8)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+
+		let request_height = self
+			.client
+			.get_transaction_height(request.original_tx_id)
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		let height = self
+			.client
+			.get_block_count()
+			.await
+			.map_err(ContractError::from_middleware_error)?
+			.saturating_sub(1);
+		let increment = self
+			.client
+			.get_version()
+			.await
+			.map_err(ContractError::from_middleware_error)?
+			.protocol
+			.ok_or_else(|| {
+				ContractError::InvalidStateError(
+					"The node did not send its protocol settings".to_string(),
+				)
+			})?
+			.max_valid_until_block_increment;
+		let mut valid_until_block = request_height + increment;
+		while valid_until_block <= height {
+			valid_until_block += increment;
+		}
+
+		let script = ScriptBuilder::new()
+			.contract_call(&self.oracle_hash, "finish", &[], CallFlags::All)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?
+			.to_bytes();
+		let signers = [self.oracle_hash, verification.hash()]
+			.into_iter()
+			.map(|hash| AccountSigner::none_hash160(hash).map(Signer::from))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		let network_magic = self
+			.client
+			.get_network_magic()
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		let mut tx = Transaction {
+			version: 0,
+			// the nonce is the ID, which makes the hash of the response unique
+			nonce: request.id as u32 as i32,
+			valid_until_block: valid_until_block as i32,
+			signers,
+			script,
+			witnesses: vec![
+				Witness::new(),
+				Witness::from_scripts_obj(InvocationScript::new(), verification),
+			],
+			network_magic: Some(network_magic),
+			..Default::default()
+		};
+
+		let (mut code, mut result) = match code {
+			OracleResponseCode::Success if result.len() > Self::MAX_RESULT_SIZE =>
+				(OracleResponseCode::ResponseTooLarge, &[][..]),
+			OracleResponseCode::Success => (code, result),
+			_ => (code, &[][..]),
+		};
+		let gas_for_response = Gas::from_datoms(request.gas_for_response);
+		loop {
+			tx.attributes = vec![TransactionAttribute::OracleResponse(OracleResponse {
+				id: request.id,
+				response_code: code,
+				result: encoding::base64_encode(result),
+			})];
+			tx.net_fee = self
+				.client
+				.calculate_network_fee(encoding::base64_encode(tx.to_array()))
+				.await
+				.map_err(ContractError::from_middleware_error)?;
+			if tx.net_fee <= gas_for_response || code == OracleResponseCode::InsufficientFunds {
+				break
+			}
+			code = OracleResponseCode::InsufficientFunds;
+			result = &[];
+		}
+		tx.sys_fee = gas_for_response.saturating_sub(tx.net_fee);
+		Ok(tx)
+	}
+
+	/// Signs the response `tx` built by [`build_response`](Self::build_response).
+	pub fn sign_response(&self, tx: &Transaction) -> Result<Secp256r1Signature, ContractError> {
+		let network_magic = tx.network_magic.ok_or_else(|| {
+			ContractError::InvalidArgError("The network of the response is unknown".to_string())
+		})?;
+		self.key_pair
+			.private_key
+			.sign_tx(&tx.get_hash_data(network_magic))
+			.map_err(|e| ContractError::RuntimeError(e.to_string()))
+	}
+
+	/// Sends the signature of the response to the request `request_id` to the oracle service of
+	/// the oracle node `peer`, which completes the response once it has enough signatures.
+	pub async fn submit_signature<P: JsonRpcClient>(
+		&self,
+		peer: &Provider<P>,
+		request_id: u64,
+		tx_signature: &Secp256r1Signature,
+	) -> Result<(), ContractError> {
+		let public_key = self.public_key().get_encoded(true);
+		let tx_signature = tx_signature.to_bytes();
+		let mut message = public_key.clone();
+		message.extend(request_id.to_le_bytes());
+		message.extend(tx_signature);
+		let message_signature = self
+			.key_pair
+			.private_key
+			.sign_tx(&message)
+			.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+		peer.request::<_, serde_json::Value>(
+			"submitoracleresponse",
+			(
+				encoding::base64_encode(public_key),
+				request_id,
+				encoding::base64_encode(tx_signature),
+				encoding::base64_encode(message_signature.to_bytes()),
+			),
+		)
+		.await?;
+		Ok(())
+	}
+
+	/// Adds the witness of the oracle nodes to the response `tx`, made of the valid ones of
+	/// `signatures` of the designated nodes.
+	///
+	/// Fails if fewer signatures than the signing threshold are valid.
+	pub fn complete_response(
+		&self,
+		tx: &mut Transaction,
+		signatures: &[(Secp256r1PublicKey, Secp256r1Signature)],
+	) -> Result<(), ContractError> {
+		let (Some(network_magic), Some(witness)) = (tx.network_magic, tx.witnesses.get(1)) else {
+			return Err(ContractError::InvalidArgError(
+				"The transaction was not built by build_response".to_string(),
+			))
+		};
+		let verification = witness.verification.clone();
+		let nodes = verification
+			.get_public_keys()
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		let threshold = Self::signing_threshold(nodes.len());
+
+		// the signatures are checked in the order of the keys of the multi-sig account
+		let hash_data = tx.get_hash_data(network_magic);
+		let valid = nodes
+			.iter()
+			.filter_map(|node| {
+				signatures.iter().find(|(key, signature)| {
+					key == node && key.verify(&hash_data, signature).is_ok()
+				})
+			})
+			.map(|(_, signature)| signature.clone())
+			.take(threshold)
+			.collect::<Vec<_>>();
+		if valid.len() < threshold {
+			return Err(ContractError::InvalidArgError(format!(
+				"{threshold} valid signatures are required, got {}",
+				valid.len()
+			)))
+		}
+		let invocation = InvocationScript::from_signatures(&valid)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		tx.witnesses[1] = Witness::from_scripts_obj(invocation, verification);
+		Ok(())
+	}
+
+	/// Sends the response completed by [`complete_response`](Self::complete_response).
+	///
+	/// Every oracle node may send the response, use [`is_finished`](Self::is_finished) to track
+	/// the request rather than the returned transaction.
+	pub async fn send_response(
+		&self,
+		tx: &Transaction,
+	) -> Result<PendingTransaction<'_, M::Provider>, ContractError> {
+		let sent = self
+			.client
+			.send_raw_transaction(encoding::base64_encode(tx.to_array()))
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		Ok(PendingTransaction::new(sent.hash, self.client.provider()))
+	}
+}
@@ -1,30 +1,27 @@
-use crate::{error::ContractError, traits::smart_contract::SmartContractTrait};
+use crate::{call::ContractCall, error::ContractError, traits::smart_contract::SmartContractTrait};
 use async_trait::async_trait;
-use neo_providers::{
-	core::{account::AccountTrait, transaction::transaction_builder::TransactionBuilder},
-	JsonRpcClient, Middleware, Provider,
-};
+use neo_providers::{core::account::AccountTrait, Middleware};
 use neo_types::{
-	script_hash::{ScriptHash, ScriptHashExtension},
+	script_hash::{ScriptHash, ScriptHashExt},
 	*,
 };
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PolicyContract<'a, P: JsonRpcClient> {
+pub struct PolicyContract<M: Middleware> {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	script_hash: ScriptHash,
 	#[serde(skip)]
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> PolicyContract<'a, P> {
-	pub const NAME: &'static str = "PolicyContract";
-	// pub const SCRIPT_HASH: H160 = Self::calc_native_contract_hash(Self::NAME).unwrap();
+impl<M: Middleware + 'static> PolicyContract<M> {
+	pub const NAME: &'static str = natives::POLICY_CONTRACT.name;
 
-	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
-		Self { script_hash: Self::calc_native_contract_hash(Self::NAME).unwrap(), provider }
+	pub fn new(client: Option<Arc<M>>) -> Self {
+		Self { script_hash: natives::POLICY_CONTRACT.hash, client }
 	}
 
 	pub async fn get_fee_per_byte(&self) -> Result<i32, ContractError> {
@@ -45,58 +42,46 @@ impl<'a, P: JsonRpcClient> PolicyContract<'a, P> {
 
 	// State modifying methods
 
-	pub async fn set_fee_per_byte(&self, fee: i32) -> Result<TransactionBuilder<P>, ContractError> {
+	pub async fn set_fee_per_byte(&self, fee: i32) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("setFeePerByte", vec![fee.into()]).await
 	}
 
-	pub async fn set_exec_fee_factor(
-		&self,
-		fee: i32,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	pub async fn set_exec_fee_factor(&self, fee: i32) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("setExecFeeFactor", vec![fee.into()]).await
 	}
 
-	pub async fn set_storage_price(
-		&self,
-		price: i32,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	pub async fn set_storage_price(&self, price: i32) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("setStoragePrice", vec![price.into()]).await
 	}
 
-	pub async fn block_account(
-		&self,
-		account: &H160,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	pub async fn block_account(&self, account: &H160) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("blockAccount", vec![account.into()]).await
 	}
 
 	pub async fn block_account_address(
 		&self,
 		address: &str,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		let account = ScriptHash::from_address(address).unwrap();
 		self.block_account(&account).await
 	}
 
-	pub async fn unblock_account(
-		&self,
-		account: &H160,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	pub async fn unblock_account(&self, account: &H160) -> Result<ContractCall<M>, ContractError> {
 		self.invoke_function("unblockAccount", vec![account.into()]).await
 	}
 
 	pub async fn unblock_account_address(
 		&self,
 		address: &str,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<M>, ContractError> {
 		let account = ScriptHash::from_address(address).unwrap();
 		self.unblock_account(&account).await
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for PolicyContract<'a, P> {
-	type P = P;
+impl<M: Middleware + 'static> SmartContractTrait for PolicyContract<M> {
+	type M = M;
 
 	fn script_hash(&self) -> H160 {
 		self.script_hash
@@ -106,7 +91,7 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for PolicyContract<'a, P> {
 		self.script_hash = script_hash;
 	}
 
-	fn provider(&self) -> Option<&Provider<P>> {
-		self.provider
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
 	}
 }
@@ -1,72 +1,83 @@
-use crate::{error::ContractError, traits::smart_contract::SmartContractTrait};
+use crate::{call::ContractCall, error::ContractError, traits::smart_contract::SmartContractTrait};
 use async_trait::async_trait;
-use neo_types::{script_hash::ScriptHash, serde_value::ValueExtension, stack_item::StackItem};
-use num_enum::TryFromPrimitive;
-
 use neo_crypto::keys::Secp256r1PublicKey;
 use neo_providers::{
-	core::{account::AccountTrait, transaction::transaction_builder::TransactionBuilder},
-	JsonRpcClient, Middleware, Provider,
+	core::{
+		account::{Account, AccountTrait},
+		transaction::signers::{account_signer::AccountSigner, signer::Signer},
+	},
+	Middleware,
 };
-use neo_types::{contract_parameter::ContractParameter, *};
+use neo_types::{contract_parameter::ContractParameter, role::Role, script_hash::ScriptHash, *};
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
+/// Client for the native `RoleManagement` contract.
+///
+/// The contract keeps track of the public keys that are designated for a [`Role`]
+/// (oracle nodes, state validators and NeoFS alphabet nodes) starting at a given block.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RoleManagement<'a, P: JsonRpcClient> {
+pub struct RoleManagement<M: Middleware> {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	script_hash: ScriptHash,
 	#[serde(skip)]
-	provider: Option<&'a Provider<P>>,
+	client: Option<Arc<M>>,
 }
 
-impl<'a, P: JsonRpcClient> RoleManagement<'a, P> {
-	const NAME: &'static str = "RoleManagement";
-	// const SCRIPT_HASH: H160 = Self::calc_native_contract_hash(Self::NAME).unwrap(); // compute hash
+impl<M: Middleware + 'static> RoleManagement<M> {
+	pub const NAME: &'static str = natives::ROLE_MANAGEMENT.name;
 
-	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
-		Self { script_hash: Self::calc_native_contract_hash(Self::NAME).unwrap(), provider }
+	pub fn new(client: Option<Arc<M>>) -> Self {
+		Self { script_hash: natives::ROLE_MANAGEMENT.hash, client }
 	}
 
+	/// Gets the public keys designated for `role` at the block with index `block_index`.
+	///
+	/// The block index must be below the current block count of the connected node, i.e. the
+	/// block must exist.
 	pub async fn get_designated_by_role(
 		&self,
 		role: Role,
-		block_index: i32,
+		block_index: u32,
 	) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
-		self.check_block_index_validity(block_index).await.unwrap();
+		self.check_block_index_validity(block_index).await?;
 
 		let invocation = self
 			.call_invoke_function(
 				"getDesignatedByRole",
-				vec![role.into(), block_index.into()],
+				vec![(&role).into(), block_index.into()],
 				vec![],
 			)
-			.await
-			.unwrap();
+			.await?;
+		self.throw_if_fault_state(&invocation)?;
+
+		let items = invocation
+			.stack
+			.first()
+			.and_then(|item| item.as_array())
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Array".to_string()))?;
 
-		let designated = invocation.stack[0]
-			.as_array()
-			.unwrap()
-			.into_iter()
+		items
+			.iter()
 			.map(|item| {
-				Secp256r1PublicKey::from_bytes(item.as_bytes().unwrap().as_slice()).unwrap()
+				item.as_public_key()
+					.ok_or_else(|| ContractError::UnexpectedReturnType("PublicKey".to_string()))
 			})
-			.collect();
-
-		Ok(designated)
+			.collect()
 	}
 
-	async fn check_block_index_validity(&self, block_index: i32) -> Result<(), ContractError> {
-		if block_index < 0 {
-			return Err(ContractError::InvalidNeoName("Block index must be positive".to_string()))
-		}
-
-		let current_block_count = self.provider.unwrap().get_block_count().await.unwrap();
+	async fn check_block_index_validity(&self, block_index: u32) -> Result<(), ContractError> {
+		let current_block_count = self
+			.require_client()?
+			.get_block_count()
+			.await
+			.map_err(ContractError::from_middleware_error)?;
 
-		if block_index > current_block_count as i32 {
-			return Err(ContractError::InvalidNeoName(format!(
-				"Block index {} exceeds current block count {}",
+		if block_index >= current_block_count {
+			return Err(ContractError::InvalidArgError(format!(
+				"Block index {} is not below the current block count {}",
 				block_index, current_block_count
 			)))
 		}
@@ -74,66 +85,100 @@ impl<'a, P: JsonRpcClient> RoleManagement<'a, P> {
 		Ok(())
 	}
 
+	/// Builds a transaction that designates `pub_keys` for `role`.
+	///
+	/// `designateAsRole` can only be called by the committee, so `committee` must be the
+	/// committee multi-sig account. It is added as a `CalledByEntry` signer of the transaction.
 	pub async fn designate_as_role(
 		&self,
 		role: Role,
 		pub_keys: Vec<Secp256r1PublicKey>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+		committee: &Account,
+	) -> Result<ContractCall<M>, ContractError> {
 		if pub_keys.is_empty() {
-			return Err(ContractError::InvalidNeoName(
+			return Err(ContractError::InvalidArgError(
 				"At least 1 public key is required".to_string(),
 			))
 		}
+		if !committee.is_multi_sig() {
+			return Err(ContractError::InvalidArgError(
+				"The committee account must be a multi-sig account".to_string(),
+			))
+		}
 
-		let params: Vec<_> = pub_keys.into_iter().map(|key| key.to_value()).collect();
+		let params = pub_keys.iter().map(ContractParameter::public_key).collect();
+		let signer = AccountSigner::called_by_entry(committee)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
 
-		self.invoke_function("designateAsRole", vec![role.into(), params.into()]).await
+		let mut call = self
+			.invoke_function(
+				"designateAsRole",
+				vec![(&role).into(), ContractParameter::array(params)],
+			)
+			.await?;
+		call.set_signers(vec![Signer::from(signer)]);
+		Ok(call)
 	}
 }
 
 #[async_trait]
-impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for RoleManagement<'a, P> {
-	type P = P;
+impl<M: Middleware + 'static> SmartContractTrait for RoleManagement<M> {
+	type M = M;
 
 	fn script_hash(&self) -> H160 {
-		self.script_hash.clone()
+		self.script_hash
 	}
 
 	fn set_script_hash(&mut self, script_hash: H160) {
 		self.script_hash = script_hash;
 	}
 
-	fn provider(&self) -> Option<&Provider<P>> {
-		self.provider
+	fn client(&self) -> Option<&Arc<M>> {
+		self.client.as_ref()
 	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u8)]
-pub enum Role {
-	Oracle,
-	Policy,
-	Validator,
-	StateRootValidator,
-	PriceFeedOracle,
-	FeeCollector,
-	ComplianceOfficer,
-}
-
-impl Role {
-	pub const fn byte(self) -> u8 {
-		self as u8
-	}
-}
-
-impl From<Role> for StackItem {
-	fn from(role: Role) -> Self {
-		StackItem::Integer { value: role.byte() as i64 }
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_crypto::key_pair::KeyPair;
+	use neo_providers::{Expectation, Provider};
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_get_designated_by_role() {
+		let (provider, mock) = Provider::mocked();
+		let keys: Vec<_> = (0..2).map(|_| KeyPair::new_random().public_key()).collect();
+		let items: Vec<_> = keys
+			.iter()
+			.map(|key| json!({"type": "ByteString", "value": hex::encode(key.get_encoded(true))}))
+			.collect();
+		mock.expect(Expectation::new("getblockcount").returning(10));
+		mock.expect(Expectation::new("invokefunction").returning(json!({
+			"script": "",
+			"state": "HALT",
+			"gasconsumed": "0",
+			"stack": [{"type": "Array", "value": items}],
+		})));
+
+		let role_management = RoleManagement::new(Some(Arc::new(provider)));
+		let designated = role_management.get_designated_by_role(Role::Oracle, 9).await.unwrap();
+		assert_eq!(designated, keys);
+		mock.assert_expectations_met().unwrap();
 	}
-}
 
-impl Into<ContractParameter> for Role {
-	fn into(self) -> ContractParameter {
-		ContractParameter::integer(self.byte() as i64)
+	#[tokio::test]
+	async fn test_block_index_must_exist() {
+		let (provider, mock) = Provider::mocked();
+		mock.expect(Expectation::new("getblockcount").returning(10).times(2));
+
+		let role_management = RoleManagement::new(Some(Arc::new(provider)));
+		for block_index in [10, 11] {
+			assert!(matches!(
+				role_management.get_designated_by_role(Role::Oracle, block_index).await,
+				Err(ContractError::InvalidArgError(_))
+			));
+		}
+		assert_eq!(mock.request_count("invokefunction"), 0);
 	}
 }
@@ -0,0 +1,191 @@
+//! Test invocations with diagnostics, see [`SmartContractTrait::simulate`].
+//!
+//! [`SmartContractTrait::simulate`]: crate::traits::smart_contract::SmartContractTrait::simulate
+use crate::error::ContractError;
+use neo_types::{
+	diagnostics::{Diagnostics, InvokedContract, StorageChange},
+	invocation_result::{InvocationResult, NeoVMStateType, Notification, VmFault},
+	stack_item::StackItem,
+};
+use primitive_types::H160;
+use std::collections::BTreeMap;
+
+/// The outcome of a test invocation run with diagnostics: the result stack, the emitted
+/// notifications, the tree of contract calls and the storage entries the invocation would write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationResult {
+	result: InvocationResult,
+}
+
+impl SimulationResult {
+	pub fn new(result: InvocationResult) -> Self {
+		Self { result }
+	}
+
+	/// The raw result of the invocation
+	pub fn invocation_result(&self) -> &InvocationResult {
+		&self.result
+	}
+
+	pub fn into_invocation_result(self) -> InvocationResult {
+		self.result
+	}
+
+	pub fn state(&self) -> &NeoVMStateType {
+		&self.result.state
+	}
+
+	pub fn is_fault(&self) -> bool {
+		self.result.has_state_fault()
+	}
+
+	/// The fault of the invocation, with its parsed cause, if it faulted
+	pub fn fault(&self) -> Option<VmFault> {
+		self.result.clone().into_result().err()
+	}
+
+	/// The exception that faulted the invocation
+	pub fn exception(&self) -> Option<&str> {
+		self.result.exception.as_deref()
+	}
+
+	/// The GAS consumed by the invocation, in fractions of GAS
+	pub fn gas_consumed(&self) -> Result<u64, ContractError> {
+		self.result.gas_consumed.parse().map_err(|_| {
+			ContractError::UnexpectedReturnType(format!(
+				"Invalid gas consumed {}",
+				self.result.gas_consumed
+			))
+		})
+	}
+
+	pub fn stack(&self) -> &[StackItem] {
+		&self.result.stack
+	}
+
+	pub fn notifications(&self) -> &[Notification] {
+		self.result.notifications.as_deref().unwrap_or_default()
+	}
+
+	pub fn diagnostics(&self) -> Option<&Diagnostics> {
+		self.result.diagnostics.as_ref()
+	}
+
+	/// The root of the invocation tree. `None` if the node did not return diagnostics.
+	pub fn call_tree(&self) -> Option<&InvokedContract> {
+		self.diagnostics().map(|diagnostics| &diagnostics.invoked_contracts)
+	}
+
+	/// The hashes of the contracts called by the invocation, in call order
+	pub fn called_contracts(&self) -> Vec<H160> {
+		self.diagnostics().map(Diagnostics::called_contracts).unwrap_or_default()
+	}
+
+	pub fn storage_changes(&self) -> &[StorageChange] {
+		self.diagnostics()
+			.map(|diagnostics| diagnostics.storage_changes.as_slice())
+			.unwrap_or_default()
+	}
+
+	/// The storage changes grouped by the id of the contract owning the entries
+	pub fn storage_changes_by_contract(&self) -> BTreeMap<i32, Vec<&StorageChange>> {
+		let mut changes = BTreeMap::<i32, Vec<&StorageChange>>::new();
+		for change in self.storage_changes() {
+			if let Some(id) = change.contract_id() {
+				changes.entry(id).or_default().push(change);
+			}
+		}
+		changes
+	}
+}
+
+impl From<InvocationResult> for SimulationResult {
+	fn from(result: InvocationResult) -> Self {
+		Self::new(result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{gas_token::GasToken, traits::smart_contract::SmartContractTrait};
+	use neo_providers::{Expectation, Provider};
+	use neo_types::{
+		contract_parameter::ContractParameter, contract_parameter_type::ContractParameterType,
+		invocation_result::FaultKind, natives,
+	};
+	use primitive_types::H160;
+	use serde_json::json;
+	use std::sync::Arc;
+
+	fn transfer_params() -> Vec<ContractParameter> {
+		vec![
+			ContractParameter::hash160(&H160::repeat_byte(1)),
+			ContractParameter::hash160(&H160::repeat_byte(2)),
+			ContractParameter::integer(5),
+			ContractParameter::new(ContractParameterType::Any),
+		]
+	}
+
+	#[tokio::test]
+	async fn test_simulate_halt() {
+		let (provider, mock) = Provider::mocked();
+		let gas = GasToken::new(Some(Arc::new(provider)));
+		mock.expect(Expectation::new("invokefunction").returning(json!({
+			"script": "",
+			"state": "HALT",
+			"gasconsumed": "1007390",
+			"stack": [{"type": "Boolean", "value": true}],
+			"notifications": [{
+				"contract": "0xd2a4cff31913016155e38e474a2c06d08be276cf",
+				"eventname": "Transfer",
+				"state": {"type": "Array", "value": []}
+			}],
+			"diagnostics": {
+				"invokedcontracts": {
+					"hash": "0x8d13e4ae5ae4bf9f6da2e70c6ea4bb6c15c0bcd0",
+					"call": [{"hash": "0xd2a4cff31913016155e38e474a2c06d08be276cf"}]
+				},
+				"storagechanges": [{
+					"state": "Changed",
+					"key": "+v///xQKr3tjjGH0U2Di3lwl8wYh9DhsHQ==",
+					"value": "QQEhBQDodkgX"
+				}]
+			}
+		})));
+
+		let simulation = gas.simulate("transfer", transfer_params(), vec![]).await.unwrap();
+		assert!(!simulation.is_fault());
+		assert_eq!(simulation.fault(), None);
+		assert_eq!(simulation.gas_consumed().unwrap(), 1_007_390);
+		assert_eq!(simulation.stack().len(), 1);
+		assert_eq!(simulation.notifications()[0].event_name, "Transfer");
+		assert_eq!(simulation.called_contracts(), vec![natives::GAS_TOKEN.hash]);
+		let by_contract = simulation.storage_changes_by_contract();
+		assert_eq!(by_contract.keys().copied().collect::<Vec<_>>(), vec![-6]);
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_simulate_fault() {
+		let (provider, mock) = Provider::mocked();
+		let gas = GasToken::new(Some(Arc::new(provider)));
+		mock.expect(Expectation::new("invokefunction").returning(json!({
+			"script": "",
+			"state": "FAULT",
+			"gasconsumed": "2028330",
+			"exception": "ASSERT is executed with false result.",
+			"stack": []
+		})));
+
+		// a fault is reported in the result, not as an error
+		let simulation = gas.simulate("transfer", transfer_params(), vec![]).await.unwrap();
+		assert!(simulation.is_fault());
+		assert_eq!(simulation.exception(), Some("ASSERT is executed with false result."));
+		assert_eq!(simulation.fault().unwrap().kind(), FaultKind::AssertFailed(None));
+		assert_eq!(simulation.call_tree(), None);
+		assert!(simulation.storage_changes().is_empty());
+		assert!(simulation.notifications().is_empty());
+
+		assert!(gas.simulate("", vec![], vec![]).await.is_err());
+	}
+}
@@ -0,0 +1,380 @@
+//! Checks that a deployed contract implements a token standard, see [`StandardsChecker`].
+use crate::{contract::Contract, error::ContractError};
+use neo_providers::Middleware;
+use neo_types::{
+	contract_manifest::ManifestError, contract_parameter::ContractParameter,
+	stack_item::FromStackItem, standard::Standard,
+};
+use primitive_types::H160;
+use std::{fmt, sync::Arc};
+
+/// Checks that a deployed contract conforms to a [`Standard`] before it is used as such, e.g.
+/// before listing a token in a wallet.
+///
+/// The manifest is fetched from the node and its ABI checked against the standard, see
+/// [`ContractManifest::standard_violations`]. [`check`](Self::check) then test invokes the safe
+/// methods of the standard to see that they return sensible values. Nothing is persisted.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_contract::standards::StandardsChecker;
+/// use neo_providers::{Http, Provider};
+/// use neo_types::standard::Standard;
+/// use primitive_types::H160;
+/// use std::{convert::TryFrom, sync::Arc};
+///
+/// # async fn foo(token: H160) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Arc::new(Provider::<Http>::try_from("http://localhost:10332")?);
+/// let report = StandardsChecker::check(provider, token, Standard::Nep17).await?;
+/// if !report.is_conformant() {
+/// 	println!("{report}");
+/// }
+/// # Ok(()) }
+/// ```
+///
+/// [`ContractManifest::standard_violations`]: neo_types::contract_manifest::ContractManifest::standard_violations
+#[derive(Debug, Clone, Copy)]
+pub struct StandardsChecker;
+
+impl StandardsChecker {
+	/// Checks the manifest of the contract `contract_hash` and test invokes the safe methods of
+	/// `standard`.
+	///
+	/// Fails only if the contract state cannot be fetched or a request fails, violations of the
+	/// standard are listed in the report.
+	pub async fn check<M: Middleware + 'static>(
+		client: Arc<M>,
+		contract_hash: H160,
+		standard: Standard,
+	) -> Result<ConformanceReport, ContractError> {
+		Self::run(client, contract_hash, standard, true).await
+	}
+
+	/// Checks the manifest of the contract `contract_hash` against `standard`, without invoking
+	/// the contract.
+	pub async fn check_abi<M: Middleware + 'static>(
+		client: Arc<M>,
+		contract_hash: H160,
+		standard: Standard,
+	) -> Result<ConformanceReport, ContractError> {
+		Self::run(client, contract_hash, standard, false).await
+	}
+
+	async fn run<M: Middleware + 'static>(
+		client: Arc<M>,
+		contract_hash: H160,
+		standard: Standard,
+		invoke: bool,
+	) -> Result<ConformanceReport, ContractError> {
+		let manifest = client
+			.get_contract_state(contract_hash)
+			.await
+			.map_err(ContractError::from_middleware_error)?
+			.manifest;
+		let mut report = ConformanceReport {
+			contract: contract_hash,
+			name: manifest.name.clone(),
+			standard,
+			declared: manifest
+				.supported_standards
+				.iter()
+				.any(|name| name.parse::<Standard>() == Ok(standard)),
+			abi_violations: manifest.standard_violations(standard),
+			invocations: Vec::new(),
+		};
+		if !invoke {
+			return Ok(report)
+		}
+
+		let contract = Contract::new(contract_hash, manifest, client);
+		if let Standard::Nep17 | Standard::Nep11 = standard {
+			report.invocations.extend(
+				[
+					invoke_check(&contract, "symbol", vec![], |symbol: &String| {
+						if symbol.is_empty()
+							|| symbol.chars().any(|c| c.is_whitespace() || c.is_control())
+						{
+							return Err("the symbol must be a non-empty word".to_string())
+						}
+						Ok(symbol.clone())
+					})
+					.await?,
+					invoke_check(&contract, "decimals", vec![], |decimals: &i64| match decimals {
+						0..=255 => Ok(decimals.to_string()),
+						_ => Err(format!("{decimals} decimals are out of range")),
+					})
+					.await?,
+					invoke_check(&contract, "totalSupply", vec![], non_negative).await?,
+					invoke_check(&contract, "balanceOf", vec![H160::zero().into()], non_negative)
+						.await?,
+				]
+				.into_iter()
+				.flatten(),
+			);
+		}
+		Ok(report)
+	}
+}
+
+fn non_negative(amount: &i64) -> Result<String, String> {
+	if *amount < 0 {
+		return Err(format!("{amount} is negative"))
+	}
+	Ok(amount.to_string())
+}
+
+/// Test invokes `method` with `params` and checks the result with `check`. `None` if the ABI
+/// lacks the method, which is reported as an ABI violation already.
+async fn invoke_check<M, R, F>(
+	contract: &Contract<M>,
+	method: &str,
+	params: Vec<ContractParameter>,
+	check: F,
+) -> Result<Option<InvocationCheck>, ContractError>
+where
+	M: Middleware + 'static,
+	R: FromStackItem,
+	F: FnOnce(&R) -> Result<String, String>,
+{
+	if contract.method(method, &params).is_err() {
+		return Ok(None)
+	}
+	let call = format!(
+		"{method}({})",
+		params
+			.iter()
+			.map(|param| param.get_type().to_string())
+			.collect::<Vec<_>>()
+			.join(", ")
+	);
+	let result = match contract.call::<R>(method, params).await {
+		Ok(value) => check(&value),
+		Err(err @ (ContractError::VmFault(_) | ContractError::UnexpectedReturnType(_))) =>
+			Err(err.to_string()),
+		Err(err) => return Err(err),
+	};
+	Ok(Some(InvocationCheck { call, result }))
+}
+
+/// The conformance of a contract to a standard, see [`StandardsChecker`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceReport {
+	pub contract: H160,
+	/// The name in the manifest
+	pub name: Option<String>,
+	pub standard: Standard,
+	/// Whether the manifest lists the standard in its `supportedstandards`
+	pub declared: bool,
+	/// The methods and events of the standard the ABI lacks or declares with the wrong safe flag
+	pub abi_violations: Vec<ManifestError>,
+	/// The test invokes of the safe methods, empty for [`StandardsChecker::check_abi`]
+	pub invocations: Vec<InvocationCheck>,
+}
+
+impl ConformanceReport {
+	/// Whether the contract declares the standard, has its ABI and all test invokes passed
+	pub fn is_conformant(&self) -> bool {
+		self.declared
+			&& self.abi_violations.is_empty()
+			&& self.invocations.iter().all(|check| check.result.is_ok())
+	}
+}
+
+impl fmt::Display for ConformanceReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let verdict = if self.is_conformant() { "conforms to" } else { "violates" };
+		let name = self.name.as_deref().unwrap_or("The contract");
+		writeln!(f, "{name} ({:#x}) {verdict} {}", self.contract, self.standard)?;
+		if !self.declared {
+			writeln!(f, "- the manifest does not list {}", self.standard)?;
+		}
+		for violation in &self.abi_violations {
+			writeln!(f, "- {violation}")?;
+		}
+		for check in &self.invocations {
+			match &check.result {
+				Ok(value) => writeln!(f, "- {} returned {value}", check.call)?,
+				Err(reason) => writeln!(f, "- {} failed: {reason}", check.call)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A test invoke of a safe method of a standard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvocationCheck {
+	/// The method and the types of the arguments, e.g. `balanceOf(H160)`
+	pub call: String,
+	/// The returned value, or why it violates the standard
+	pub result: Result<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_providers::{Expectation, Provider};
+	use serde_json::{json, Value};
+
+	const TOKEN: H160 = H160::repeat_byte(1);
+
+	fn method(name: &str, parameters: &[&str], return_type: &str, safe: bool) -> Value {
+		let parameters: Vec<_> = parameters
+			.iter()
+			.enumerate()
+			.map(|(index, typ)| json!({"name": format!("arg{index}"), "type": typ}))
+			.collect();
+		json!({
+			"name": name,
+			"parameters": parameters,
+			"returntype": return_type,
+			"offset": 0,
+			"safe": safe
+		})
+	}
+
+	fn nep17_manifest() -> Value {
+		json!({
+			"name": "Token",
+			"supportedstandards": ["NEP-17"],
+			"abi": {
+				"methods": [
+					method("symbol", &[], "String", true),
+					method("decimals", &[], "Integer", true),
+					method("totalSupply", &[], "Integer", true),
+					method("balanceOf", &["Hash160"], "Integer", true),
+					method("transfer", &["Hash160", "Hash160", "Integer", "Any"], "Boolean", false),
+				],
+				"events": [{
+					"name": "Transfer",
+					"parameters": [
+						{"name": "from", "type": "Hash160"},
+						{"name": "to", "type": "Hash160"},
+						{"name": "amount", "type": "Integer"}
+					]
+				}]
+			},
+			"permissions": [],
+			"trusts": [],
+			"extra": null
+		})
+	}
+
+	fn contract_state(manifest: Value) -> Value {
+		json!({
+			"id": 1,
+			"updatecounter": 0,
+			"hash": format!("{TOKEN:#x}"),
+			"nef": {
+				"magic": 860243278,
+				"compiler": "neon-test",
+				"source": "",
+				"tokens": [],
+				"script": "QA==",
+				"checksum": 0
+			},
+			"manifest": manifest
+		})
+	}
+
+	fn returning(item: Value) -> Expectation {
+		Expectation::new("invokefunction").returning(json!({
+			"script": "",
+			"state": "HALT",
+			"gasconsumed": "0",
+			"stack": [item]
+		}))
+	}
+
+	#[tokio::test]
+	async fn test_conforming_token() {
+		let (provider, mock) = Provider::mocked();
+		mock.expect(
+			Expectation::new("getcontractstate").returning(contract_state(nep17_manifest())),
+		);
+		// "TOK"
+		mock.expect(returning(json!({"type": "ByteString", "value": "544f4b"})));
+		mock.expect(returning(json!({"type": "Integer", "value": 8})));
+		mock.expect(returning(json!({"type": "Integer", "value": 1000})));
+		mock.expect(returning(json!({"type": "Integer", "value": 0})));
+
+		let report = StandardsChecker::check(Arc::new(provider), TOKEN, Standard::Nep17)
+			.await
+			.unwrap();
+		assert!(report.is_conformant(), "{report}");
+		assert_eq!(report.name.as_deref(), Some("Token"));
+		let results: Vec<_> = report.invocations.iter().map(|check| check.result.clone()).collect();
+		assert_eq!(
+			results,
+			vec![
+				Ok("TOK".to_string()),
+				Ok("8".to_string()),
+				Ok("1000".to_string()),
+				Ok("0".to_string())
+			]
+		);
+		assert_eq!(report.invocations[3].call, "balanceOf(H160)");
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_non_conforming_token() {
+		let mut manifest = nep17_manifest();
+		manifest["supportedstandards"] = json!([]);
+		manifest["abi"]["events"] = json!([]);
+		let methods = manifest["abi"]["methods"].as_array_mut().unwrap();
+		methods.pop();
+		methods[3]["safe"] = json!(false);
+
+		let (provider, mock) = Provider::mocked();
+		mock.expect(Expectation::new("getcontractstate").returning(contract_state(manifest)));
+		mock.expect(returning(json!({"type": "ByteString", "value": ""})));
+		mock.expect(returning(json!({"type": "Integer", "value": 8})));
+		mock.expect(returning(json!({"type": "Integer", "value": -5})));
+		mock.expect(returning(json!({"type": "Integer", "value": 0})));
+
+		let report = StandardsChecker::check(Arc::new(provider), TOKEN, Standard::Nep17)
+			.await
+			.unwrap();
+		assert!(!report.is_conformant());
+		assert!(!report.declared);
+		assert_eq!(report.abi_violations.len(), 3);
+		assert!(matches!(
+			&report.abi_violations[0],
+			ManifestError::Safety { method, safe: true, .. } if method.starts_with("balanceOf")
+		));
+		assert!(matches!(
+			&report.abi_violations[1],
+			ManifestError::MissingMethod { method, .. } if method.starts_with("transfer")
+		));
+		assert!(matches!(
+			&report.abi_violations[2],
+			ManifestError::MissingEvent { event, .. } if event == "Transfer"
+		));
+		let failed: Vec<_> = report
+			.invocations
+			.iter()
+			.filter(|check| check.result.is_err())
+			.map(|check| check.call.as_str())
+			.collect();
+		assert_eq!(failed, vec!["symbol()", "totalSupply()"]);
+		assert!(report.to_string().contains("violates NEP-17"));
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_check_abi_does_not_invoke() {
+		let (provider, mock) = Provider::mocked();
+		mock.expect(
+			Expectation::new("getcontractstate").returning(contract_state(nep17_manifest())),
+		);
+
+		let report = StandardsChecker::check_abi(Arc::new(provider), TOKEN, Standard::Nep17)
+			.await
+			.unwrap();
+		assert!(report.is_conformant());
+		assert!(report.invocations.is_empty());
+		assert_eq!(mock.request_count("invokefunction"), 0);
+	}
+}
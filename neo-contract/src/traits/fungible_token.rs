@@ -1,25 +1,22 @@
-use crate::{
-	error::ContractError, fungible_token_contract::FungibleTokenContract, traits::token::TokenTrait,
-};
+use crate::{call::ContractCall, error::ContractError, traits::token::TokenTrait};
 use async_trait::async_trait;
 use neo_providers::{
 	core::{
 		account::{Account, AccountTrait},
-		transaction::{
-			signers::account_signer::AccountSigner, transaction_builder::TransactionBuilder,
-		},
+		transaction::signers::account_signer::AccountSigner,
 	},
-	JsonRpcClient,
+	Middleware,
 };
 use neo_signers::Wallet;
 use neo_types::{
-	address::Address, contract_parameter::ContractParameter, nns_name::NNSName,
-	script_hash::ScriptHash, Bytes,
+	address::Address, contract_parameter::ContractParameter,
+	contract_parameter_type::ContractParameterType, nns_name::NNSName, script_hash::ScriptHash,
+	token_amount::TokenAmount, Bytes,
 };
 use primitive_types::H160;
 
 #[async_trait]
-pub trait FungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> {
+pub trait FungibleTokenTrait: TokenTrait {
 	const BALANCE_OF: &'static str = "balanceOf";
 	const TRANSFER: &'static str = "transfer";
 
@@ -47,47 +44,91 @@ pub trait FungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> {
 		&self,
 		from: &Account,
 		to: &ScriptHash,
-		amount: i32,
+		amount: i64,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
-		let mut builder = self
+	) -> Result<ContractCall<Self::M>, ContractError> {
+		let mut call = self
 			.transfer_from_hash160(&from.address_or_scripthash().script_hash(), to, amount, data)
-			.await
-			.unwrap();
-		builder.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
+			.await?;
+		let signer = AccountSigner::called_by_entry(from)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		call.set_signers(vec![signer.into()]);
 
-		Ok(builder)
+		Ok(call)
 	}
 
 	async fn transfer_from_hash160(
 		&self,
 		from: &ScriptHash,
 		to: &ScriptHash,
-		amount: i32,
+		amount: i64,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<Self::M>, ContractError> {
 		if amount < 0 {
 			return Err(ContractError::InvalidArgError(
 				"The amount must be greater than or equal to 0.".to_string(),
 			))
 		}
 
-		let transfer_script = self.build_transfer_script(from, to, amount, data).await.unwrap();
-		let mut builder = TransactionBuilder::new();
-		builder.set_script(transfer_script);
-		Ok(builder)
+		let transfer_script = self.build_transfer_script(from, to, amount, data).await?;
+		Ok(ContractCall::new(self.require_client()?.clone(), transfer_script))
+	}
+
+	/// Creates a transfer of `amount` from `from` to `to`.
+	///
+	/// Unlike the transfers taking the value in the smallest unit of the token, this checks that
+	/// `amount` is an amount of this token with its decimals, so amounts parsed for another token
+	/// are refused instead of being sent scaled wrongly.
+	async fn transfer_amount(
+		&self,
+		from: &Account,
+		to: &ScriptHash,
+		amount: &TokenAmount,
+		data: Option<ContractParameter>,
+	) -> Result<ContractCall<Self::M>, ContractError> {
+		self.check_amount(amount).await?;
+		let value =
+			amount.value_i64().map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		self.transfer_from_account(from, to, value, data).await
+	}
+
+	/// Checks that `amount` is an amount of this token with its decimals.
+	async fn check_amount(&self, amount: &TokenAmount) -> Result<(), ContractError> {
+		if amount.asset() != self.script_hash() {
+			return Err(ContractError::InvalidArgError(format!(
+				"The amount is an amount of {:?}, not of the token {:?}",
+				amount.asset(),
+				self.script_hash()
+			)))
+		}
+		let decimals = match self.decimals() {
+			Some(decimals) => decimals,
+			None => self.call_function_returning_int(Self::DECIMALS, vec![]).await? as u8,
+		};
+		if amount.decimals() != decimals {
+			return Err(ContractError::InvalidArgError(format!(
+				"The amount has {} decimals, the token {decimals}",
+				amount.decimals()
+			)))
+		}
+		Ok(())
 	}
 
 	async fn build_transfer_script(
 		&self,
 		from: &ScriptHash,
 		to: &ScriptHash,
-		amount: i32,
+		amount: i64,
 		data: Option<ContractParameter>,
 	) -> Result<Bytes, ContractError> {
 		self.build_invoke_function_script(
-			<FungibleTokenContract<P> as FungibleTokenTrait<P>>::TRANSFER,
-			vec![from.into(), to.into(), amount.into(), data.unwrap()],
+			Self::TRANSFER,
+			vec![
+				from.into(),
+				to.into(),
+				amount.into(),
+				data.unwrap_or_else(|| ContractParameter::new(ContractParameterType::Any)),
+			],
 		)
 		.await
 	}
@@ -98,25 +139,25 @@ pub trait FungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> {
 		&self,
 		from: &Account,
 		to: &NNSName,
-		amount: i32,
+		amount: i64,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
-		let mut builder = self
+	) -> Result<ContractCall<Self::M>, ContractError> {
+		let mut call = self
 			.transfer_from_hash160_to_nns(&from.get_script_hash(), to, amount, data)
 			.await
 			.unwrap();
-		builder.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
+		call.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
 
-		Ok(builder)
+		Ok(call)
 	}
 
 	async fn transfer_from_hash160_to_nns(
 		&self,
 		from: &ScriptHash,
 		to: &NNSName,
-		amount: i32,
+		amount: i64,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<Self::M>, ContractError> {
 		let script_hash = self.resolve_nns_text_record(to).await.unwrap();
 		self.transfer_from_hash160(from, &script_hash, amount, data).await
 	}
@@ -1,24 +1,22 @@
 use crate::{
+	call::ContractCall,
 	error::ContractError,
 	iterator::NeoIterator,
-	nft_contract::NftContract,
 	traits::{smart_contract::SmartContractTrait, token::TokenTrait},
 };
 use async_trait::async_trait;
 use neo_providers::{
 	core::{
 		account::{Account, AccountTrait},
-		transaction::{
-			signers::account_signer::AccountSigner, transaction_builder::TransactionBuilder,
-		},
+		transaction::signers::account_signer::AccountSigner,
 	},
-	JsonRpcClient,
+	Middleware,
 };
 use neo_types::{
 	address::Address,
 	contract_parameter::ContractParameter,
 	nns_name::NNSName,
-	script_hash::{ScriptHash, ScriptHashExtension},
+	script_hash::{ScriptHash, ScriptHashExt},
 	stack_item::StackItem,
 	Bytes,
 };
@@ -26,7 +24,7 @@ use primitive_types::H160;
 use std::{collections::HashMap, sync::Arc};
 
 #[async_trait]
-pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send {
+pub trait NonFungibleTokenTrait: TokenTrait + Send {
 	const OWNER_OF: &'static str = "ownerOf";
 	const TOKENS_OF: &'static str = "tokensOf";
 	const BALANCE_OF: &'static str = "balanceOf";
@@ -37,24 +35,18 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 	// Token methods
 
 	async fn balance_of(&mut self, owner: H160) -> Result<i32, ContractError> {
-		self.call_function_returning_int(
-			<NftContract<P> as NonFungibleTokenTrait<P>>::BALANCE_OF,
-			vec![owner.into()],
-		)
-		.await
+		self.call_function_returning_int(Self::BALANCE_OF, vec![owner.into()]).await
 	}
 
 	// NFT methods
 
-	async fn tokens_of(&mut self, owner: H160) -> Result<NeoIterator<Bytes, P>, ContractError> {
+	async fn tokens_of(
+		&mut self,
+		owner: H160,
+	) -> Result<NeoIterator<Bytes, Self::M>, ContractError> {
 		let mapper_fn = Arc::new(|item: StackItem| item.as_bytes().unwrap());
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS_OF,
-				vec![owner.into()],
-				mapper_fn,
-			)
-			.await)
+		self.call_function_returning_iterator(Self::TOKENS_OF, vec![owner.into()], mapper_fn)
+			.await
 	}
 
 	// Non-divisible NFT methods
@@ -65,11 +57,11 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		to: ScriptHash,
 		token_id: Bytes,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
-		let mut builder = self.transfer_inner(to, token_id, data).await.unwrap();
-		&builder.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
+	) -> Result<ContractCall<Self::M>, ContractError> {
+		let mut call = self.transfer_inner(to, token_id, data).await.unwrap();
+		call.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
 
-		Ok(builder)
+		Ok(call)
 	}
 
 	async fn transfer_inner(
@@ -77,13 +69,10 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		to: ScriptHash,
 		token_id: Bytes,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<Self::P>, ContractError> {
+	) -> Result<ContractCall<Self::M>, ContractError> {
 		self.throw_if_divisible_nft().await.unwrap();
-		self.invoke_function(
-			<NftContract<P> as NonFungibleTokenTrait<P>>::TRANSFER,
-			vec![to.into(), token_id.into(), data.unwrap()],
-		)
-		.await
+		self.invoke_function(Self::TRANSFER, vec![to.into(), token_id.into(), data.unwrap()])
+			.await
 	}
 
 	async fn transfer_from_name(
@@ -92,7 +81,7 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		to: &str,
 		token_id: Bytes,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<Self::M>, ContractError> {
 		self.throw_if_sender_is_not_owner(&from.get_script_hash(), &token_id)
 			.await
 			.unwrap();
@@ -111,7 +100,7 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		to: &str,
 		token_id: Bytes,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<Self::M>, ContractError> {
 		self.throw_if_divisible_nft().await.unwrap();
 
 		self.transfer_inner(
@@ -130,21 +119,15 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 	) -> Result<Bytes, ContractError> {
 		self.throw_if_divisible_nft().await.unwrap();
 
-		self.build_invoke_function_script(
-			<NftContract<P> as NonFungibleTokenTrait<P>>::TRANSFER,
-			vec![to.into(), token_id.into(), data],
-		)
-		.await
+		self.build_invoke_function_script(Self::TRANSFER, vec![to.into(), token_id.into(), data])
+			.await
 	}
 
 	async fn owner_of(&mut self, token_id: Bytes) -> Result<H160, ContractError> {
 		self.throw_if_divisible_nft().await.unwrap();
 
-		self.call_function_returning_script_hash(
-			<NftContract<P> as NonFungibleTokenTrait<P>>::OWNER_OF,
-			vec![token_id.into()],
-		)
-		.await
+		self.call_function_returning_script_hash(Self::OWNER_OF, vec![token_id.into()])
+			.await
 	}
 
 	async fn throw_if_divisible_nft(&mut self) -> Result<(), ContractError> {
@@ -181,13 +164,13 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		amount: i32,
 		token_id: Bytes,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
-		let mut builder = self
+	) -> Result<ContractCall<Self::M>, ContractError> {
+		let mut call = self
 			.transfer_divisible_from_hashes(&from.get_script_hash(), to, amount, token_id, data)
 			.await
 			.unwrap();
-		builder.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
-		Ok(builder)
+		call.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
+		Ok(call)
 	}
 
 	async fn transfer_divisible_from_hashes(
@@ -197,11 +180,11 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		amount: i32,
 		token_id: Bytes,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<Self::M>, ContractError> {
 		self.throw_if_non_divisible_nft().await.unwrap();
 
 		self.invoke_function(
-			<NftContract<P> as NonFungibleTokenTrait<P>>::TRANSFER,
+			Self::TRANSFER,
 			vec![from.into(), to.into(), amount.into(), token_id.into(), data.unwrap()],
 		)
 		.await
@@ -214,8 +197,8 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		amount: i32,
 		token_id: Bytes,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
-		let mut builder = self
+	) -> Result<ContractCall<Self::M>, ContractError> {
+		let mut call = self
 			.transfer_divisible_from_hashes(
 				&from.get_script_hash(),
 				&self.resolve_nns_text_record(&NNSName::new(to).unwrap()).await.unwrap(),
@@ -225,8 +208,8 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 			)
 			.await
 			.unwrap();
-		builder.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
-		Ok(builder)
+		call.set_signers(vec![AccountSigner::called_by_entry(from).unwrap().into()]);
+		Ok(call)
 	}
 
 	async fn transfer_divisible_to_name(
@@ -236,7 +219,7 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		amount: i32,
 		token_id: Bytes,
 		data: Option<ContractParameter>,
-	) -> Result<TransactionBuilder<P>, ContractError> {
+	) -> Result<ContractCall<Self::M>, ContractError> {
 		self.throw_if_non_divisible_nft().await.unwrap();
 
 		self.transfer_divisible_from_hashes(
@@ -258,7 +241,7 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		data: Option<ContractParameter>,
 	) -> Result<Bytes, ContractError> {
 		self.build_invoke_function_script(
-			<NftContract<P> as NonFungibleTokenTrait<P>>::TRANSFER,
+			Self::TRANSFER,
 			vec![from.into(), to.into(), amount.into(), token_id.into(), data.unwrap()],
 		)
 		.await
@@ -267,16 +250,15 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 	async fn owners_of(
 		&mut self,
 		token_id: Bytes,
-	) -> Result<NeoIterator<Address, P>, ContractError> {
+	) -> Result<NeoIterator<Address, Self::M>, ContractError> {
 		self.throw_if_non_divisible_nft().await.unwrap();
 
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::OWNER_OF,
-				vec![token_id.into()],
-				Arc::new(|item: StackItem| item.as_address().unwrap()),
-			)
-			.await)
+		self.call_function_returning_iterator(
+			Self::OWNER_OF,
+			vec![token_id.into()],
+			Arc::new(|item: StackItem| item.as_address().unwrap()),
+		)
+		.await
 	}
 
 	async fn throw_if_non_divisible_nft(&mut self) -> Result<(), ContractError> {
@@ -296,23 +278,19 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 	) -> Result<i32, ContractError> {
 		self.throw_if_non_divisible_nft().await.unwrap();
 
-		self.call_function_returning_int(
-			<NftContract<P> as NonFungibleTokenTrait<P>>::BALANCE_OF,
-			vec![owner.into(), token_id.into()],
-		)
-		.await
+		self.call_function_returning_int(Self::BALANCE_OF, vec![owner.into(), token_id.into()])
+			.await
 	}
 
 	// Optional methods
 
-	async fn tokens(&mut self) -> Result<NeoIterator<Bytes, P>, ContractError> {
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS,
-				vec![],
-				Arc::new(|item: StackItem| item.as_bytes().unwrap()),
-			)
-			.await)
+	async fn tokens(&mut self) -> Result<NeoIterator<Bytes, Self::M>, ContractError> {
+		self.call_function_returning_iterator(
+			Self::TOKENS,
+			vec![],
+			Arc::new(|item: StackItem| item.as_bytes().unwrap()),
+		)
+		.await
 	}
 
 	async fn properties(
@@ -320,11 +298,7 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		token_id: Bytes,
 	) -> Result<HashMap<String, String>, ContractError> {
 		let invocation_result = self
-			.call_invoke_function(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::PROPERTIES,
-				vec![token_id.into()],
-				vec![],
-			)
+			.call_invoke_function(Self::PROPERTIES, vec![token_id.into()], vec![])
 			.await
 			.unwrap();
 
@@ -350,11 +324,7 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 		token_id: Bytes,
 	) -> Result<HashMap<String, StackItem>, ContractError> {
 		let invocation_result = self
-			.call_invoke_function(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::PROPERTIES,
-				vec![token_id.into()],
-				vec![],
-			)
+			.call_invoke_function(Self::PROPERTIES, vec![token_id.into()], vec![])
 			.await
 			.unwrap();
 
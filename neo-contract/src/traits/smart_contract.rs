@@ -1,21 +1,26 @@
-use crate::{error::ContractError, iterator::NeoIterator};
+use crate::{
+	call::ContractCall,
+	error::ContractError,
+	events::{DecodedEvent, EventDecoder},
+	iterator::NeoIterator,
+	simulation::SimulationResult,
+};
 use async_trait::async_trait;
 use neo_providers::{
 	core::{
 		account::AccountTrait,
+		responses::neo_application_log::ApplicationLog,
 		script::script_builder::ScriptBuilder,
-		transaction::{
-			call_flags::CallFlags, signers::signer::Signer, transaction_builder::TransactionBuilder,
-		},
+		transaction::{call_flags::CallFlags, signers::signer::Signer},
 	},
-	JsonRpcClient, Middleware, Provider,
+	Capability, Middleware,
 };
 use neo_types::{
 	contract_manifest::ContractManifest,
 	contract_parameter::ContractParameter,
 	invocation_result::InvocationResult,
 	op_code::OpCode,
-	script_hash::{ScriptHash, ScriptHashExtension},
+	script_hash::{ScriptHash, ScriptHashExt},
 	stack_item::StackItem,
 	Bytes,
 };
@@ -26,9 +31,10 @@ use serde::Serialize;
 use std::sync::Arc;
 
 #[async_trait]
-pub trait SmartContractTrait<'a>: Send + Sync {
+pub trait SmartContractTrait: Send + Sync {
 	const DEFAULT_ITERATOR_COUNT: usize = 100;
-	type P: JsonRpcClient;
+	/// The middleware stack the contract sends its requests through
+	type M: Middleware + 'static;
 
 	async fn name(&self) -> String {
 		self.get_manifest().await.name.clone().unwrap()
@@ -43,17 +49,22 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		panic!("Cannot set script hash for NNS")
 	}
 
-	fn provider(&self) -> Option<&Provider<Self::P>>;
+	fn client(&self) -> Option<&Arc<Self::M>>;
+
+	/// The client of the contract, or an error if the contract was created without one.
+	fn require_client(&self) -> Result<&Arc<Self::M>, ContractError> {
+		self.client()
+			.ok_or_else(|| ContractError::InvalidStateError("No client set".to_string()))
+	}
 
+	/// Creates a call of `function`, sent through the client of the contract.
 	async fn invoke_function(
 		&self,
 		function: &str,
 		params: Vec<ContractParameter>,
-	) -> Result<TransactionBuilder<Self::P>, ContractError> {
-		let script = self.build_invoke_function_script(function, params).await.unwrap();
-		let mut builder = TransactionBuilder::new();
-		builder.set_script(script);
-		Ok(builder)
+	) -> Result<ContractCall<Self::M>, ContractError> {
+		let script = self.build_invoke_function_script(function, params).await?;
+		Ok(ContractCall::new(self.require_client()?.clone(), script))
 	}
 
 	async fn build_invoke_function_script(
@@ -65,9 +76,11 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 			return Err(ContractError::InvalidNeoName("Function name cannot be empty".to_string()))
 		}
 
+		// the calls are sent as transactions, which `CallFlags::None` would keep from changing
+		// state
 		let script = ScriptBuilder::new()
-			.contract_call(&self.script_hash(), function, params.as_slice(), CallFlags::None)
-			.unwrap()
+			.contract_call(&self.script_hash(), function, params.as_slice(), CallFlags::All)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
 			.to_bytes();
 
 		Ok(script)
@@ -78,8 +91,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<String, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
 		let item = output.stack[0].clone();
 		match item.as_string() {
@@ -93,8 +106,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<i32, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
 		let item = output.stack[0].clone();
 		match item.as_int() {
@@ -108,8 +121,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<bool, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
 		let item = output.stack[0].clone();
 		match item.as_bool() {
@@ -132,22 +145,35 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 			)))
 		}
 
-		let res = self
-			.provider()
-			.unwrap()
-			.invoke_function(&self.script_hash().clone(), function.into(), params, Some(signers))
-			.await?
-			.clone();
+		self.require_client()?
+			.invoke_function(&self.script_hash(), function.into(), params, Some(signers))
+			.await
+			.map_err(ContractError::from_middleware_error)
+	}
 
-		Ok(res)
+	/// Test invokes `function` with diagnostics, returning the call tree, notifications and
+	/// storage changes of the invocation besides its result. A faulting invocation is not an
+	/// error, see [`SimulationResult::is_fault`].
+	async fn simulate(
+		&self,
+		function: &str,
+		params: Vec<ContractParameter>,
+		signers: Vec<Signer>,
+	) -> Result<SimulationResult, ContractError> {
+		if function.is_empty() {
+			return Err(ContractError::InvalidNeoName("Function cannot be empty".to_string()))
+		}
+		let result = self
+			.require_client()?
+			.invoke_function_diagnostics(self.script_hash(), function.to_string(), params, signers)
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+		Ok(SimulationResult::new(result))
 	}
 
 	fn throw_if_fault_state(&self, output: &InvocationResult) -> Result<(), ContractError> {
-		if output.has_state_fault() {
-			Err(ContractError::UnexpectedReturnType(output.exception.clone().unwrap()))
-		} else {
-			Ok(())
-		}
+		output.clone().into_result()?;
+		Ok(())
 	}
 
 	// Other methods like `call_function_returning_xxx`, iterators, etc.
@@ -156,8 +182,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<H160, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
 		let item = &output.stack[0];
 		item.as_bytes()
@@ -166,29 +192,46 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 			.ok_or_else(|| ContractError::UnexpectedReturnType("Script hash".to_string()))
 	}
 
+	/// Test invokes `function`, which returns an iterator, and iterates its items mapped with
+	/// `mapper`.
+	///
+	/// The items are traversed in a session if the node supports it, see
+	/// [`Capability::IteratorSessions`]. Otherwise the invocation unwraps at most
+	/// [`SmartContractTrait::DEFAULT_ITERATOR_COUNT`] items, which the iterator then streams.
 	async fn call_function_returning_iterator<U>(
 		&self,
 		function: &str,
 		params: Vec<ContractParameter>,
 		mapper: Arc<dyn Fn(StackItem) -> U + Send + Sync>,
-	) -> NeoIterator<U, Self::P>
+	) -> Result<NeoIterator<U, Self::M>, ContractError>
 	where
-		U: Send + Sync, // Adding this bound if necessary
+		U: Send + Sync,
 	{
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
-
-		let item = &output.stack[0];
-		let StackItem::InteropInterface { id, interface } = item else { panic!("") };
-
-		let session_id = output
-			.session_id
-			.ok_or(ContractError::InvalidNeoNameServiceRoot("No session ID".to_string()))
-			.unwrap();
+		let client = self.require_client()?;
+		if client.provider().supports(Capability::IteratorSessions).await? {
+			let output = self.call_invoke_function(function, params.clone(), vec![]).await?;
+			self.throw_if_fault_state(&output)?;
+			// the node may still run with sessions disabled if it does not report its settings
+			if let (Some(StackItem::InteropInterface { id, .. }), Some(session_id)) =
+				(output.stack.first(), output.session_id.clone())
+			{
+				return Ok(NeoIterator::new(session_id, id.clone(), mapper, Some(client.clone())))
+			}
+		}
 
-		NeoIterator::new(session_id, id.clone(), mapper, None)
+		let items = self
+			.call_function_and_unwrap_iterator(
+				function,
+				params,
+				Self::DEFAULT_ITERATOR_COUNT,
+				|item| item,
+			)
+			.await?;
+		Ok(NeoIterator::from_items(items, mapper))
 	}
 
+	/// Test invokes `function`, which returns an iterator, with a script unwrapping at most
+	/// `max_items` of its items, so no session is needed on the node.
 	async fn call_function_and_unwrap_iterator<U>(
 		&self,
 		function: &str,
@@ -200,20 +243,25 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 			&self.script_hash(),
 			function,
 			&params,
-			255, //TODO
+			max_items as u32,
 			CallFlags::All,
 		)
-		.unwrap();
-
-		let output = { self.provider().unwrap().invoke_script(script.to_hex(), vec![]) };
-
-		let output = output.await.unwrap();
-
-		self.throw_if_fault_state(&output).unwrap();
-
-		let items = output.stack[0].as_array().unwrap().into_iter().map(mapper).collect();
-
-		Ok(items)
+		.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+
+		let output = self
+			.require_client()?
+			.invoke_script(script.to_hex(), vec![])
+			.await
+			.map_err(ContractError::from_middleware_error)?;
+
+		self.throw_if_fault_state(&output)?;
+
+		let items = output
+			.stack
+			.first()
+			.and_then(|item| item.as_array())
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Array".to_string()))?;
+		Ok(items.into_iter().map(mapper).collect())
 	}
 
 	fn calc_native_contract_hash(contract_name: &str) -> Result<H160, ContractError> {
@@ -226,17 +274,30 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		contract_name: &str,
 	) -> Result<H160, ContractError> {
 		let mut script = ScriptBuilder::new();
-		script.op_code(&[OpCode::Abort]);
-		script.push_data(sender.to_vec()).unwrap();
-		script.push_integer(BigInt::from(nef_checksum)).unwrap();
-		script.push_data(contract_name.as_bytes().to_vec()).unwrap();
+		script
+			.op_code(&[OpCode::Abort])
+			.push_data(sender.to_le_vec())
+			.and_then(|script| script.push_integer(BigInt::from(nef_checksum)))
+			.and_then(|script| script.push_data(contract_name.as_bytes().to_vec()))
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+
+		Ok(H160::from_script(&script.to_bytes()))
+	}
 
-		Ok(H160::from_slice(&script.to_bytes()))
+	/// Decodes the notifications emitted by this contract in `log` into events, using the ABI
+	/// of its manifest. Notifications of other contracts are skipped.
+	async fn decode_logs(&self, log: &ApplicationLog) -> Result<Vec<DecodedEvent>, ContractError> {
+		let manifest = self
+			.require_client()?
+			.get_contract_state(self.script_hash())
+			.await
+			.map_err(ContractError::from_middleware_error)?
+			.manifest;
+		EventDecoder::new().with_manifest(self.script_hash(), &manifest).decode_log(log)
 	}
 
 	async fn get_manifest(&self) -> ContractManifest {
-		let req =
-			{ self.provider().unwrap().get_contract_state(self.script_hash()).await.unwrap() };
+		let req = { self.client().unwrap().get_contract_state(self.script_hash()).await.unwrap() };
 
 		req.manifest.clone()
 	}
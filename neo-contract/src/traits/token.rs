@@ -2,7 +2,7 @@ use crate::{
 	error::ContractError, name_service::NeoNameService, traits::smart_contract::SmartContractTrait,
 };
 use async_trait::async_trait;
-use neo_providers::{JsonRpcClient, Middleware};
+use neo_providers::Middleware;
 use neo_types::{
 	contract_parameter::ContractParameter, nns_name::NNSName, record_type::RecordType,
 };
@@ -12,7 +12,7 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
 #[async_trait]
-pub trait TokenTrait<'a, P: JsonRpcClient>: SmartContractTrait<'a, P = P> {
+pub trait TokenTrait: SmartContractTrait {
 	const TOTAL_SUPPLY: &'static str = "totalSupply";
 	const SYMBOL: &'static str = "symbol";
 	const DECIMALS: &'static str = "decimals";
@@ -1,3 +1,7 @@
+//! Base58Check for the keys of this crate, e.g. WIF.
+//!
+//! Other crates use `neo_types::encoding`, which this crate cannot depend on.
+
 use sha2::{Digest, Sha256};
 
 /// Encodes a byte slice into a base58check string.
@@ -0,0 +1,107 @@
+//! Verification of many signatures at once, e.g. the witnesses of a chain of block headers.
+//!
+//! ECDSA has no batch verification that is cheaper than verifying every signature, so the
+//! signatures are verified one by one, in parallel on all cores with the `parallel` feature.
+
+use crate::{
+	error::CryptoError,
+	keys::{Secp256r1PublicKey, Secp256r1Signature},
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A message with its signature and the key it should be signed with.
+#[derive(Debug, Clone, Copy)]
+pub struct VerificationItem<'a> {
+	pub message: &'a [u8],
+	pub signature: &'a Secp256r1Signature,
+	pub public_key: &'a Secp256r1PublicKey,
+}
+
+impl<'a> VerificationItem<'a> {
+	pub fn new(
+		message: &'a [u8],
+		signature: &'a Secp256r1Signature,
+		public_key: &'a Secp256r1PublicKey,
+	) -> Self {
+		Self { message, signature, public_key }
+	}
+
+	/// Verifies the signature, see [`Secp256r1PublicKey::verify`].
+	pub fn verify(&self) -> Result<(), CryptoError> {
+		self.public_key.verify(self.message, self.signature)
+	}
+}
+
+impl<'a> From<(&'a [u8], &'a Secp256r1Signature, &'a Secp256r1PublicKey)> for VerificationItem<'a> {
+	fn from(
+		(message, signature, public_key): (
+			&'a [u8],
+			&'a Secp256r1Signature,
+			&'a Secp256r1PublicKey,
+		),
+	) -> Self {
+		Self::new(message, signature, public_key)
+	}
+}
+
+/// Verifies every item and returns the results in the order of `items`.
+///
+/// With the `parallel` feature the items are spread over the rayon thread pool.
+pub fn verify_batch(items: &[VerificationItem<'_>]) -> Vec<Result<(), CryptoError>> {
+	#[cfg(feature = "parallel")]
+	{
+		items.par_iter().map(VerificationItem::verify).collect()
+	}
+	#[cfg(not(feature = "parallel"))]
+	{
+		items.iter().map(VerificationItem::verify).collect()
+	}
+}
+
+/// Verifies every item and returns whether all signatures are valid.
+///
+/// Stops at the first invalid signature, so use [`verify_batch`] to find out which ones failed.
+pub fn verify_all(items: &[VerificationItem<'_>]) -> bool {
+	#[cfg(feature = "parallel")]
+	{
+		items.par_iter().all(|item| item.verify().is_ok())
+	}
+	#[cfg(not(feature = "parallel"))]
+	{
+		items.iter().all(|item| item.verify().is_ok())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::keys::Secp256r1PrivateKey;
+	use rand_core::OsRng;
+
+	#[test]
+	fn test_verify_batch() {
+		let keys: Vec<_> = (0..8).map(|_| Secp256r1PrivateKey::random(&mut OsRng)).collect();
+		let public_keys: Vec<_> = keys.iter().map(Secp256r1PrivateKey::to_public_key).collect();
+		let messages: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i; 32]).collect();
+		let signatures: Vec<_> = keys
+			.iter()
+			.zip(&messages)
+			.map(|(key, message)| key.sign_tx(message).unwrap())
+			.collect();
+
+		let mut items: Vec<_> = (0..8)
+			.map(|i| VerificationItem::new(&messages[i], &signatures[i], &public_keys[i]))
+			.collect();
+		assert!(verify_batch(&items).iter().all(Result::is_ok));
+		assert!(verify_all(&items));
+
+		// the second message with the signature of the first one
+		items[1].signature = &signatures[0];
+		let results = verify_batch(&items);
+		assert!(results[0].is_ok());
+		assert!(results[1].is_err());
+		assert!(results[2..].iter().all(Result::is_ok));
+		assert!(!verify_all(&items));
+	}
+}
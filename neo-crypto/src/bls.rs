@@ -0,0 +1,209 @@
+//! # BLS12-381
+//!
+//! The points and scalars taken by the BLS12-381 methods of the native `CryptoLib` contract, in
+//! the encoding `bls12381Deserialize` accepts.
+//!
+//! Points of G1 and G2 are compressed and checked to be on the curve and in the subgroup, so a
+//! script is not sent with a point the contract rejects. Elements of Gt are only checked for
+//! their length.
+
+use crate::error::CryptoError;
+use bls12_381::{G1Affine, G2Affine, Scalar};
+use std::fmt;
+
+/// A point of the G1 group, compressed to 48 bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bls12381G1([u8; Self::SIZE]);
+
+impl Bls12381G1 {
+	pub const SIZE: usize = 48;
+
+	/// The generator of the group
+	pub fn generator() -> Self {
+		Self(G1Affine::generator().to_compressed())
+	}
+
+	/// Decodes a compressed point, failing if it is not a valid point of the group.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+		let bytes: [u8; Self::SIZE] = bytes.try_into().map_err(|_| invalid_length("G1", bytes))?;
+		Option::<G1Affine>::from(G1Affine::from_compressed(&bytes))
+			.ok_or_else(|| CryptoError::InvalidFormat("Invalid BLS12-381 G1 point".to_string()))?;
+		Ok(Self(bytes))
+	}
+
+	pub fn from_hex(hex: &str) -> Result<Self, CryptoError> {
+		Self::from_bytes(&hex::decode(hex.trim_start_matches("0x"))?)
+	}
+
+	pub fn to_bytes(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+/// A point of the G2 group, compressed to 96 bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bls12381G2([u8; Self::SIZE]);
+
+impl Bls12381G2 {
+	pub const SIZE: usize = 96;
+
+	/// The generator of the group
+	pub fn generator() -> Self {
+		Self(G2Affine::generator().to_compressed())
+	}
+
+	/// Decodes a compressed point, failing if it is not a valid point of the group.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+		let bytes: [u8; Self::SIZE] = bytes.try_into().map_err(|_| invalid_length("G2", bytes))?;
+		Option::<G2Affine>::from(G2Affine::from_compressed(&bytes))
+			.ok_or_else(|| CryptoError::InvalidFormat("Invalid BLS12-381 G2 point".to_string()))?;
+		Ok(Self(bytes))
+	}
+
+	pub fn from_hex(hex: &str) -> Result<Self, CryptoError> {
+		Self::from_bytes(&hex::decode(hex.trim_start_matches("0x"))?)
+	}
+
+	pub fn to_bytes(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+/// An element of the Gt group, the result of a pairing, encoded in 576 bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bls12381Gt(Vec<u8>);
+
+impl Bls12381Gt {
+	pub const SIZE: usize = 576;
+
+	/// Wraps an encoded element, e.g. one returned by `bls12381Serialize`. Only the length is
+	/// checked.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+		if bytes.len() != Self::SIZE {
+			return Err(invalid_length("Gt", bytes))
+		}
+		Ok(Self(bytes.to_vec()))
+	}
+
+	pub fn from_hex(hex: &str) -> Result<Self, CryptoError> {
+		Self::from_bytes(&hex::decode(hex.trim_start_matches("0x"))?)
+	}
+
+	pub fn to_bytes(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+/// A point of any of the groups, as taken by `bls12381Add` and `bls12381Mul`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Bls12381Point {
+	G1(Bls12381G1),
+	G2(Bls12381G2),
+	Gt(Bls12381Gt),
+}
+
+impl Bls12381Point {
+	/// Decodes a point of the group given by the length of `bytes`, like `bls12381Deserialize`.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+		match bytes.len() {
+			Bls12381G1::SIZE => Bls12381G1::from_bytes(bytes).map(Self::G1),
+			Bls12381G2::SIZE => Bls12381G2::from_bytes(bytes).map(Self::G2),
+			Bls12381Gt::SIZE => Bls12381Gt::from_bytes(bytes).map(Self::Gt),
+			_ => Err(invalid_length("point", bytes)),
+		}
+	}
+
+	pub fn to_bytes(&self) -> &[u8] {
+		match self {
+			Self::G1(point) => point.to_bytes(),
+			Self::G2(point) => point.to_bytes(),
+			Self::Gt(point) => point.to_bytes(),
+		}
+	}
+}
+
+impl From<Bls12381G1> for Bls12381Point {
+	fn from(point: Bls12381G1) -> Self {
+		Self::G1(point)
+	}
+}
+
+impl From<Bls12381G2> for Bls12381Point {
+	fn from(point: Bls12381G2) -> Self {
+		Self::G2(point)
+	}
+}
+
+impl From<Bls12381Gt> for Bls12381Point {
+	fn from(point: Bls12381Gt) -> Self {
+		Self::Gt(point)
+	}
+}
+
+impl fmt::Display for Bls12381Point {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", hex::encode(self.to_bytes()))
+	}
+}
+
+/// A scalar of the BLS12-381 curve, the multiplier of `bls12381Mul`, encoded in 32 bytes in
+/// little-endian order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bls12381Scalar([u8; Self::SIZE]);
+
+impl Bls12381Scalar {
+	pub const SIZE: usize = 32;
+
+	/// Decodes a scalar in little-endian order, failing if it is not smaller than the order of
+	/// the groups.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+		let bytes: [u8; Self::SIZE] =
+			bytes.try_into().map_err(|_| invalid_length("scalar", bytes))?;
+		Option::<Scalar>::from(Scalar::from_bytes(&bytes))
+			.ok_or_else(|| CryptoError::InvalidFormat("Invalid BLS12-381 scalar".to_string()))?;
+		Ok(Self(bytes))
+	}
+
+	pub fn to_bytes(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl From<u64> for Bls12381Scalar {
+	fn from(value: u64) -> Self {
+		Self(Scalar::from(value).to_bytes())
+	}
+}
+
+fn invalid_length(kind: &str, bytes: &[u8]) -> CryptoError {
+	CryptoError::InvalidFormat(format!("Invalid length {} of a BLS12-381 {kind}", bytes.len()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_points() {
+		let g1 = Bls12381G1::generator();
+		assert_eq!(Bls12381G1::from_bytes(g1.to_bytes()).unwrap(), g1);
+		assert_eq!(Bls12381Point::from_bytes(g1.to_bytes()).unwrap(), g1.into());
+		let g2 = Bls12381G2::generator();
+		assert_eq!(Bls12381Point::from_bytes(g2.to_bytes()).unwrap(), g2.into());
+		assert!(matches!(Bls12381Point::from_bytes(&[0; 576]).unwrap(), Bls12381Point::Gt(_)));
+
+		// the x coordinate of the generator without the flag of a compressed point
+		let mut invalid = g1.to_bytes().to_vec();
+		invalid[0] &= 0x7f;
+		assert!(Bls12381G1::from_bytes(&invalid).is_err());
+		assert!(Bls12381Point::from_bytes(&[0; 47]).is_err());
+	}
+
+	#[test]
+	fn test_scalar() {
+		let mut one = [0; 32];
+		one[0] = 1;
+		assert_eq!(Bls12381Scalar::from(1).to_bytes(), one);
+		assert!(Bls12381Scalar::from_bytes(&[0xff; 32]).is_err());
+	}
+}
@@ -22,6 +22,26 @@ pub enum CryptoError {
 	SignatureVerificationError,
 	#[error(transparent)]
 	FromHexError(#[from] hex::FromHexError),
+	#[error(transparent)]
+	WifError(#[from] WifError),
+	#[error(transparent)]
+	CodecError(#[from] neo_codec::CodecError),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WifError {
+	#[error("WIF is not valid Base58")]
+	InvalidBase58,
+	#[error("Invalid WIF length {0}, expected 38 bytes")]
+	InvalidLength(usize),
+	#[error("Invalid WIF prefix 0x{0:02x}, expected 0x80")]
+	InvalidPrefix(u8),
+	#[error("WIF does not mark a compressed public key")]
+	NotCompressed,
+	#[error("Invalid WIF checksum")]
+	InvalidChecksum,
+	#[error("WIF does not contain a valid private key")]
+	InvalidPrivateKey,
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -13,10 +13,13 @@ use crate::{
 	wif::wif_from_private_key,
 };
 use rand::rngs::OsRng;
+use std::fmt;
 
 /// Represents an Elliptic Curve Key Pair containing both a private and a public key.
-
-#[derive(Debug, Clone)]
+///
+/// The private key is wiped from memory when the key pair is dropped, and `Debug` output only
+/// shows the public key.
+#[derive(Clone)]
 pub struct KeyPair {
 	/// The private key component of the key pair.
 	pub private_key: Secp256r1PrivateKey,
@@ -25,6 +28,15 @@ pub struct KeyPair {
 	pub public_key: Secp256r1PublicKey,
 }
 
+impl fmt::Debug for KeyPair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("KeyPair")
+			.field("private_key", &self.private_key)
+			.field("public_key", &self.public_key)
+			.finish()
+	}
+}
+
 impl KeyPair {
 	/// Creates a new `KeyPair` instance given a private key and its corresponding public key.
 	///
@@ -55,7 +67,8 @@ impl KeyPair {
 		Self::new(private_key.clone(), public_key)
 	}
 
-	/// Returns the 32-byte representation of the private key.
+	/// Returns the 32-byte representation of the private key. The returned copy is not wiped
+	/// from memory.
 	pub fn private_key_bytes(&self) -> [u8; 32] {
 		self.private_key.to_raw_bytes()
 	}
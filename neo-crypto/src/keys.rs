@@ -52,9 +52,12 @@
 //! Note: Error handling is crucial for cryptographic operations. Ensure proper error handling
 //! in real-world applications.
 
-use crate::error::CryptoError;
+use crate::{error::CryptoError, secret::SecretBytes};
 use core::fmt;
-use neo_codec::{encode::NeoSerializable, Decoder, Encoder};
+use neo_codec::{
+	encode::{var_size, NeoSerializable},
+	Decoder, Encoder,
+};
 use num_integer::Integer;
 use num_traits::cast::ToPrimitive;
 use p256::{
@@ -72,6 +75,7 @@ use serde::{Deserialize, Serialize};
 use signature::{Keypair, SignerMut, Verifier};
 use std::hash::{Hash, Hasher};
 use typenum::Unsigned;
+use zeroize::ZeroizeOnDrop;
 
 #[cfg_attr(feature = "substrate", serde(crate = "serde_substrate"))]
 #[derive(Debug, Copy, Clone)]
@@ -79,7 +83,9 @@ pub struct Secp256r1PublicKey {
 	inner: PublicKey,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// A private key, wiped from memory when dropped. Comparisons run in constant time and the key
+/// never appears in `Debug` or `Display` output.
+#[derive(PartialEq, Eq, Clone)]
 pub struct Secp256r1PrivateKey {
 	inner: SecretKey,
 }
@@ -172,6 +178,22 @@ impl Secp256r1PublicKey {
 			.map_err(|_| CryptoError::SignatureVerificationError)
 	}
 
+	/// Verifies a signature like [`Secp256r1PublicKey::verify`], but also rejects signatures
+	/// whose `s` is not normalized, see [`Secp256r1Signature::normalize_s`].
+	///
+	/// Neo nodes accept both forms, so use this only where a signature has to be unique, e.g.
+	/// when signatures are used as identifiers.
+	pub fn verify_strict(
+		&self,
+		message: &[u8],
+		signature: &Secp256r1Signature,
+	) -> Result<(), CryptoError> {
+		if !signature.is_normalized() {
+			return Err(CryptoError::SignatureVerificationError)
+		}
+		self.verify(message, signature)
+	}
+
 	/// Gets this public key's elliptic curve point encoded as defined in section 2.3.3 of [SEC1](http://www.secg.org/sec1-v2.pdf).
 	///
 	/// - Parameter compressed: If the EC point should be encoded in compressed or uncompressed format
@@ -256,9 +278,17 @@ impl Secp256r1PrivateKey {
 
 	/// Returns the raw byte representation of the private key.
 	///
+	/// The returned copy is not wiped from memory, prefer
+	/// [`Secp256r1PrivateKey::to_secret_bytes`].
+	///
 	/// - Returns: A 32-byte array representing the private key.
 	pub fn to_raw_bytes(&self) -> [u8; 32] {
-		self.inner.clone().to_bytes().as_slice().try_into().unwrap()
+		self.inner.to_bytes().into()
+	}
+
+	/// Returns the raw bytes of the private key in a buffer that is wiped when dropped.
+	pub fn to_secret_bytes(&self) -> SecretBytes {
+		SecretBytes::from(self.inner.to_bytes().as_slice())
 	}
 
 	/// Converts the private key to its corresponding public key.
@@ -277,8 +307,7 @@ impl Secp256r1PrivateKey {
 	///
 	/// - Returns: A `Result` with the `Secp256r1Signature` or a `CryptoError`.
 	pub fn sign_tx(&self, message: &[u8]) -> Result<Secp256r1Signature, CryptoError> {
-		let signing_key = SigningKey::from_slice(&self.inner.to_bytes().as_slice())
-			.map_err(|_| CryptoError::InvalidPrivateKey)?;
+		let signing_key = SigningKey::from(&self.inner);
 		let (signature, _) =
 			signing_key.try_sign(message).map_err(|_| CryptoError::SigningError)?;
 
@@ -339,7 +368,9 @@ impl Secp256r1Signature {
 		if bytes.len() != 64 {
 			return Err(CryptoError::InvalidFormat("Invalid signature length".to_string()))
 		}
-		Ok(Secp256r1Signature { inner: Signature::from_slice(bytes).unwrap() })
+		Signature::from_slice(bytes)
+			.map(|inner| Secp256r1Signature { inner })
+			.map_err(|_| CryptoError::InvalidFormat("Invalid signature".to_string()))
 	}
 
 	/// Converts the signature into a 64-byte array.
@@ -358,11 +389,48 @@ impl Secp256r1Signature {
 
 		bytes
 	}
+
+	/// Parses an ASN.1 DER encoded signature, the format returned by most HSMs and key
+	/// management services.
+	///
+	/// The signature is kept as is, call [`Secp256r1Signature::normalize_s`] to get the low-S
+	/// form.
+	pub fn from_der(bytes: &[u8]) -> Result<Self, CryptoError> {
+		Signature::from_der(bytes)
+			.map(|inner| Secp256r1Signature { inner })
+			.map_err(|_| CryptoError::InvalidFormat("Invalid DER signature".to_string()))
+	}
+
+	/// Encodes the signature in ASN.1 DER.
+	pub fn to_der(&self) -> Vec<u8> {
+		self.inner.to_der().as_bytes().to_vec()
+	}
+
+	/// Returns the signature with `s` replaced by `n - s` if `s` is greater than half the order
+	/// `n` of the curve. Both forms are valid for the same message and key, the normalized one
+	/// is the canonical form.
+	pub fn normalize_s(&self) -> Self {
+		self.inner.normalize_s().map_or(*self, |inner| Secp256r1Signature { inner })
+	}
+
+	/// Whether `s` is at most half the order of the curve.
+	pub fn is_normalized(&self) -> bool {
+		self.inner.normalize_s().is_none()
+	}
+}
+
+// `SecretKey` zeroizes itself when dropped
+impl ZeroizeOnDrop for Secp256r1PrivateKey {}
+
+impl fmt::Debug for Secp256r1PrivateKey {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("Secp256r1PrivateKey([REDACTED])")
+	}
 }
 
 impl fmt::Display for Secp256r1PrivateKey {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "Secp256r1PrivateKey: {}\n", hex::encode(self.inner.to_bytes().as_slice()))
+		f.write_str("Secp256r1PrivateKey([REDACTED])")
 	}
 }
 
@@ -433,7 +501,8 @@ impl NeoSerializable for Secp256r1PublicKey {
 	type Error = CryptoError;
 
 	fn size(&self) -> usize {
-		64
+		let len = self.to_vec().len();
+		var_size(len) + len
 	}
 
 	fn encode(&self, writer: &mut Encoder) {
@@ -441,8 +510,8 @@ impl NeoSerializable for Secp256r1PublicKey {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let bytes = reader.read_var_bytes().unwrap();
-		Secp256r1PublicKey::from_bytes(&bytes).map_err(|_| CryptoError::InvalidPublicKey)
+		let bytes = reader.read_var_slice()?;
+		Secp256r1PublicKey::from_bytes(bytes).map_err(|_| CryptoError::InvalidPublicKey)
 	}
 
 	fn to_array(&self) -> Vec<u8> {
@@ -452,8 +521,12 @@ impl NeoSerializable for Secp256r1PublicKey {
 
 #[cfg(test)]
 mod tests {
-	use crate::keys::{PublicKeyExtension, Secp256r1PublicKey};
+	use crate::keys::{
+		PublicKeyExtension, Secp256r1PrivateKey, Secp256r1PublicKey, Secp256r1Signature,
+	};
 	use neo_codec::encode::NeoSerializable;
+	use p256::{ecdsa::Signature, FieldBytes};
+	use rand_core::OsRng;
 	use rustc_serialize::hex::{FromHex, ToHex};
 
 	const ENCODED_POINT: &str =
@@ -504,6 +577,41 @@ mod tests {
 		assert_eq!(public_key.get_encoded(true).to_hex(), data);
 	}
 
+	#[test]
+	fn test_normalize_s() {
+		let private_key = Secp256r1PrivateKey::random(&mut OsRng);
+		let public_key = private_key.to_public_key();
+		let message = b"message";
+		let signature = private_key.sign_tx(message).unwrap().normalize_s();
+		assert!(signature.is_normalized());
+
+		let (r, s) = signature.inner.split_scalars();
+		let high_s = Secp256r1Signature {
+			inner: Signature::from_scalars(r, FieldBytes::from(-*s)).unwrap(),
+		};
+		assert!(!high_s.is_normalized());
+		assert!(public_key.verify(message, &high_s).is_ok());
+		assert!(public_key.verify_strict(message, &high_s).is_err());
+		assert!(public_key.verify_strict(message, &signature).is_ok());
+		assert_eq!(high_s.normalize_s(), signature);
+	}
+
+	#[test]
+	fn test_der() {
+		let r = [0x80; 32];
+		let mut s = [0u8; 32];
+		s[31] = 0x01;
+		let signature = Secp256r1Signature::from_scalars(r, s).unwrap();
+
+		// r needs a leading zero to stay positive, leading zeros of s are dropped
+		let mut der = vec![0x30, 0x26, 0x02, 0x21, 0x00];
+		der.extend_from_slice(&r);
+		der.extend_from_slice(&[0x02, 0x01, 0x01]);
+		assert_eq!(signature.to_der(), der);
+		assert_eq!(Secp256r1Signature::from_der(&der).unwrap(), signature);
+		assert!(Secp256r1Signature::from_der(&signature.to_bytes()).is_err());
+	}
+
 	#[test]
 	fn test_public_key_size() {
 		let key = Secp256r1PublicKey::from_encoded(
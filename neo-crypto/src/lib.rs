@@ -1,8 +1,12 @@
 pub mod base58_helper;
+pub mod batch;
+pub mod bls;
 pub mod error;
 pub mod hash;
 pub mod key_pair;
 pub mod keys;
+pub mod secret;
+pub mod signed_message;
 pub mod utils;
 pub mod wif;
 
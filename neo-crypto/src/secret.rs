@@ -0,0 +1,103 @@
+//! # SecretBytes
+//!
+//! Buffers for key material and passwords that are wiped when they are dropped.
+
+use std::{fmt, ops::Deref};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Bytes that are overwritten with zeros when dropped, e.g. raw private keys, passwords and keys
+/// derived from them.
+///
+/// The contents never appear in `Debug` output and comparisons take the same time regardless of
+/// where the contents differ. Copying the contents out of the buffer, e.g. with `to_vec`, creates
+/// a copy that is not wiped.
+#[derive(Clone, Default)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+	pub fn new(bytes: Vec<u8>) -> Self {
+		Self(bytes)
+	}
+
+	/// Creates a buffer of `len` zeros, to be filled in place.
+	pub fn zeroed(len: usize) -> Self {
+		Self(vec![0; len])
+	}
+
+	pub fn as_mut_slice(&mut self) -> &mut [u8] {
+		&mut self.0
+	}
+}
+
+impl Drop for SecretBytes {
+	fn drop(&mut self) {
+		self.0.zeroize();
+	}
+}
+
+impl ZeroizeOnDrop for SecretBytes {}
+
+impl Deref for SecretBytes {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl AsRef<[u8]> for SecretBytes {
+	fn as_ref(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl From<Vec<u8>> for SecretBytes {
+	fn from(bytes: Vec<u8>) -> Self {
+		Self(bytes)
+	}
+}
+
+impl From<&[u8]> for SecretBytes {
+	fn from(bytes: &[u8]) -> Self {
+		Self(bytes.to_vec())
+	}
+}
+
+impl From<String> for SecretBytes {
+	fn from(string: String) -> Self {
+		Self(string.into_bytes())
+	}
+}
+
+impl PartialEq for SecretBytes {
+	fn eq(&self, other: &Self) -> bool {
+		self.0.ct_eq(&other.0).into()
+	}
+}
+
+impl Eq for SecretBytes {}
+
+impl fmt::Debug for SecretBytes {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "SecretBytes([REDACTED; {}])", self.0.len())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_debug_is_redacted() {
+		let secret = SecretBytes::from(vec![0xab; 32]);
+		assert_eq!(format!("{secret:?}"), "SecretBytes([REDACTED; 32])");
+	}
+
+	#[test]
+	fn test_eq() {
+		assert_eq!(SecretBytes::from(&[1u8, 2][..]), SecretBytes::new(vec![1, 2]));
+		assert_ne!(SecretBytes::new(vec![1, 2]), SecretBytes::new(vec![1, 3]));
+		assert_ne!(SecretBytes::new(vec![1, 2]), SecretBytes::new(vec![1]));
+	}
+}
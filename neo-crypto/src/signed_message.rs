@@ -0,0 +1,157 @@
+//! # Signed messages
+//!
+//! Off-chain message signing compatible with NeoLine, O3 and neon-js.
+//!
+//! The message is prefixed with a random salt and wrapped into the payload of a dummy
+//! transaction (`010001f0`, the var-length salted message, `0000`), so a signed message can never
+//! be mistaken for a signed transaction. The payload is signed with ECDSA over secp256r1 and
+//! SHA-256, just like a transaction.
+
+use crate::{
+	error::CryptoError,
+	key_pair::KeyPair,
+	keys::{Secp256r1PublicKey, Secp256r1Signature},
+};
+use serde::{Deserialize, Serialize};
+
+const PAYLOAD_PREFIX: [u8; 4] = [0x01, 0x00, 0x01, 0xf0];
+const PAYLOAD_SUFFIX: [u8; 2] = [0x00, 0x00];
+
+/// A message signed by a Neo account, serialized the way JS wallets return it from
+/// `signMessage`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SignedMessage {
+	/// The compressed public key of the signer, as hex
+	#[serde(rename = "publicKey")]
+	pub pubkey: String,
+	/// The salt prefixed to the message, as hex. Empty if the message was signed without salt.
+	pub salt: String,
+	/// The signature, `r` and `s` as hex
+	#[serde(rename = "data")]
+	pub signature: String,
+	/// The signed message
+	pub message: String,
+}
+
+impl SignedMessage {
+	/// The public key of the signer
+	pub fn public_key(&self) -> Result<Secp256r1PublicKey, CryptoError> {
+		Secp256r1PublicKey::from_bytes(&hex::decode(&self.pubkey)?)
+	}
+
+	/// Verifies that the message was signed by the private key of [`SignedMessage::public_key`].
+	pub fn verify(&self) -> Result<(), CryptoError> {
+		let signature = Secp256r1Signature::from_bytes(&hex::decode(&self.signature)?)?;
+		let payload = message_payload(&self.salt, &self.message);
+		self.public_key()?.verify(&payload, &signature)
+	}
+}
+
+/// Signs `message` with a random 16 byte salt.
+pub fn sign_message(key_pair: &KeyPair, message: &str) -> Result<SignedMessage, CryptoError> {
+	let salt = hex::encode(rand::random::<[u8; 16]>());
+	sign_message_with_salt(key_pair, message, &salt)
+}
+
+/// Signs `message` prefixed with `salt`. An empty salt matches `signMessageWithoutSalt` of
+/// NeoLine.
+pub fn sign_message_with_salt(
+	key_pair: &KeyPair,
+	message: &str,
+	salt: &str,
+) -> Result<SignedMessage, CryptoError> {
+	let signature = key_pair.private_key.sign_tx(&message_payload(salt, message))?;
+	Ok(SignedMessage {
+		pubkey: hex::encode(key_pair.public_key.get_encoded(true)),
+		salt: salt.to_string(),
+		signature: hex::encode(signature.to_bytes()),
+		message: message.to_string(),
+	})
+}
+
+/// Verifies a message signed by a Neo account.
+pub fn verify_message(signed: &SignedMessage) -> Result<(), CryptoError> {
+	signed.verify()
+}
+
+/// Returns the bytes that are signed for `message` prefixed with `salt`.
+pub fn message_payload(salt: &str, message: &str) -> Vec<u8> {
+	let salted = [salt.as_bytes(), message.as_bytes()].concat();
+	let mut payload = PAYLOAD_PREFIX.to_vec();
+	payload.extend(var_int(salted.len()));
+	payload.extend(salted);
+	payload.extend(PAYLOAD_SUFFIX);
+	payload
+}
+
+fn var_int(n: usize) -> Vec<u8> {
+	match n {
+		0..=0xfc => vec![n as u8],
+		0xfd..=0xffff => [&[0xfd][..], &(n as u16).to_le_bytes()].concat(),
+		0x1_0000..=0xffff_ffff => [&[0xfe][..], &(n as u32).to_le_bytes()].concat(),
+		_ => [&[0xff][..], &(n as u64).to_le_bytes()].concat(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::keys::Secp256r1PrivateKey;
+	use neo_config::TestConstants;
+
+	fn key_pair() -> KeyPair {
+		let private_key = Secp256r1PrivateKey::from_bytes(
+			&hex::decode(TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY).unwrap(),
+		)
+		.unwrap();
+		KeyPair::from_secret_key(&private_key)
+	}
+
+	#[test]
+	fn test_message_payload() {
+		assert_eq!(hex::encode(message_payload("abc", "hi")), "010001f00561626368690000");
+		assert_eq!(&message_payload("", &"a".repeat(300))[4..7], &[0xfd, 0x2c, 0x01]);
+	}
+
+	#[test]
+	fn test_sign_and_verify() {
+		let signed = sign_message(&key_pair(), "Hello Neo").unwrap();
+		assert_eq!(signed.pubkey, TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY);
+		assert_eq!(signed.salt.len(), 32);
+		assert!(verify_message(&signed).is_ok());
+
+		let json = serde_json::to_value(&signed).unwrap();
+		assert_eq!(json["publicKey"], TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY);
+		assert_eq!(json["data"], signed.signature);
+		let restored: SignedMessage = serde_json::from_value(json).unwrap();
+		assert!(restored.verify().is_ok());
+
+		let tampered = SignedMessage { message: "Hello Eth".to_string(), ..signed.clone() };
+		assert!(matches!(tampered.verify(), Err(CryptoError::SignatureVerificationError)));
+		let tampered = SignedMessage { salt: String::new(), ..signed };
+		assert!(tampered.verify().is_err());
+	}
+
+	#[test]
+	fn test_verify_wallet_json() {
+		// A `signMessage` result in the JSON shape returned by NeoLine, signed over the neon-js
+		// payload by an ECDSA implementation other than the one of this crate.
+		let signed: SignedMessage = serde_json::from_value(serde_json::json!({
+			"publicKey": "033a4d051b04b7fc0230d2b1aaedfd5a84be279a5361a7358db665ad7857787f1b",
+			"data": "5fb6d3f793463bf21e92db73c1f526eabe9024967aca788a6e0de068dbc922f6\
+				5d5b44ba76c59c1b9923198a72f5770ba9488e3b0ef1f88661320bb7711dfc27",
+			"salt": "058b9e03e7154e4db1e489c1256b7f4e",
+			"message": "Hello World!"
+		}))
+		.unwrap();
+		assert_eq!(
+			hex::encode(message_payload(&signed.salt, &signed.message)),
+			"010001f02c303538623965303365373135346534646231653438396331323536623766346548656c6c6f\
+			 20576f726c64210000"
+		);
+		assert!(signed.verify().is_ok());
+
+		let tampered = SignedMessage { message: "Hello World?".to_string(), ..signed };
+		assert!(tampered.verify().is_err());
+	}
+}
@@ -1,4 +1,8 @@
-use crate::{error::CryptoError, hash::HashableForVec, keys::Secp256r1PrivateKey};
+use crate::{
+	error::{CryptoError, WifError},
+	keys::Secp256r1PrivateKey,
+	secret::SecretBytes,
+};
 use sha2::{Digest, Sha256};
 
 /// Converts a WIF (Wallet Import Format) string into a `Secp256r1PrivateKey`.
@@ -14,24 +18,29 @@ use sha2::{Digest, Sha256};
 /// or an `Err` with `CryptoError` if the WIF string is invalid.
 ///
 /// # Errors
-/// This function returns an error if:
+/// This function returns a [`WifError`] if:
 /// * The WIF string is not properly base58 encoded.
-/// * The decoded data does not have the correct length, prefix, or suffix expected for a WIF.
+/// * The decoded data is not 38 bytes long.
+/// * The version byte is not `0x80`, or the compression flag is not `0x01`.
 /// * The checksum of the WIF does not match the expected value.
 pub fn private_key_from_wif(wif: &str) -> Result<Secp256r1PrivateKey, CryptoError> {
-	let data = bs58::decode(wif)
-		.into_vec()
-		.map_err(|_| CryptoError::InvalidFormat("Incorrect WIF format.".to_string()))?;
-	if data.len() != 38 || data[0] != 0x80 || data[33] != 0x01 {
-		return Err(CryptoError::InvalidFormat("Incorrect WIF format.".to_string()))
+	let data = SecretBytes::new(bs58::decode(wif).into_vec().map_err(|_| WifError::InvalidBase58)?);
+	if data.len() != 38 {
+		return Err(WifError::InvalidLength(data.len()).into())
+	}
+	if data[0] != 0x80 {
+		return Err(WifError::InvalidPrefix(data[0]).into())
+	}
+	if data[33] != 0x01 {
+		return Err(WifError::NotCompressed.into())
 	}
 
-	let checksum_calculated = Sha256::digest(&Sha256::digest(&data[..34]));
+	let checksum_calculated = Sha256::digest(Sha256::digest(&data[..34]));
 	if checksum_calculated[..4] != data[34..] {
-		return Err(CryptoError::InvalidFormat("Incorrect WIF checksum.".to_string()))
+		return Err(WifError::InvalidChecksum.into())
 	}
 
-	Secp256r1PrivateKey::from_bytes(&data[1..33].to_vec())
+	Secp256r1PrivateKey::from_bytes(&data[1..33]).map_err(|_| WifError::InvalidPrivateKey.into())
 }
 
 /// Converts a `Secp256r1PrivateKey` into a WIF (Wallet Import Format) string.
@@ -45,20 +54,22 @@ pub fn private_key_from_wif(wif: &str) -> Result<Secp256r1PrivateKey, CryptoErro
 /// # Returns
 /// A `String` containing the WIF representation of the provided private key.
 pub fn wif_from_private_key(private_key: &Secp256r1PrivateKey) -> String {
-	let mut extended_key: Vec<u8> = vec![0x80];
-	extended_key.extend(private_key.to_raw_bytes());
-	extended_key.push(0x01);
+	let mut extended_key = SecretBytes::zeroed(38);
+	let buffer = extended_key.as_mut_slice();
+	buffer[0] = 0x80;
+	buffer[1..33].copy_from_slice(&private_key.to_secret_bytes());
+	buffer[33] = 0x01;
 
-	let hash = Sha256::digest(&Sha256::digest(&extended_key));
-	let checksum = &hash[0..4];
-	extended_key.extend_from_slice(checksum);
+	let hash = Sha256::digest(&Sha256::digest(&buffer[..34]));
+	buffer[34..].copy_from_slice(&hash[0..4]);
 
-	bs58::encode(extended_key).into_string()
+	bs58::encode(&*extended_key).into_string()
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::{
+		error::{CryptoError, WifError},
 		keys::{PrivateKeyExtension, Secp256r1PrivateKey},
 		wif::{private_key_from_wif, wif_from_private_key},
 	};
@@ -102,6 +113,26 @@ mod tests {
 		assert!(private_key_from_wif(invalid_33rd.as_str()).is_err());
 	}
 
+	#[test]
+	fn test_wif_errors() {
+		let wif = "L25kgAQJXNHnhc7Sx9bomxxwVSMsZdkaNQ3m2VfHrnLzKWMLP13A";
+		let wif_error = |wif: &str| match private_key_from_wif(wif) {
+			Err(CryptoError::WifError(err)) => err,
+			other => panic!("expected a WIF error, got {other:?}"),
+		};
+		let reencode = |change: fn(&mut Vec<u8>)| {
+			let mut decoded = bs58::decode(wif).into_vec().unwrap();
+			change(&mut decoded);
+			bs58::encode(decoded).into_string()
+		};
+
+		assert_eq!(wif_error("0OIl"), WifError::InvalidBase58);
+		assert_eq!(wif_error(&reencode(|d| d.truncate(30))), WifError::InvalidLength(30));
+		assert_eq!(wif_error(&reencode(|d| d[0] = 0x81)), WifError::InvalidPrefix(0x81));
+		assert_eq!(wif_error(&reencode(|d| d[33] = 0)), WifError::NotCompressed);
+		assert_eq!(wif_error(&reencode(|d| d[37] ^= 1)), WifError::InvalidChecksum);
+	}
+
 	#[test]
 	fn test_valid_private_key_to_wif() {
 		let pk = hex::decode("9117f4bf9be717c9a90994326897f4243503accd06712162267e77f18b49c3a3")
@@ -0,0 +1,163 @@
+use crate::{NeoError, SignedTransaction, UnsignedTransaction};
+use neo_codec::{encode::NeoSerializable, Decoder};
+use neo_providers::core::{
+	account::{Account, AccountTrait},
+	transaction::{transaction::Transaction, witness::Witness},
+};
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+/// A single-signature account, holding a private key unless it was created from an encrypted key
+/// and not decrypted.
+#[derive(Debug, uniffi::Object)]
+pub struct NeoAccount {
+	pub(crate) account: Account,
+}
+
+#[uniffi::export]
+impl NeoAccount {
+	/// Creates an account with a new random private key.
+	#[uniffi::constructor]
+	pub fn create() -> Result<Arc<Self>, NeoError> {
+		let account = Account::create().map_err(|e| NeoError::InvalidKey(e.to_string()))?;
+		Ok(Arc::new(Self { account }))
+	}
+
+	/// Imports the private key `wif` in Wallet Import Format.
+	#[uniffi::constructor]
+	pub fn from_wif(wif: String) -> Result<Arc<Self>, NeoError> {
+		let account = Account::from_wif(&wif).map_err(|e| NeoError::InvalidKey(e.to_string()))?;
+		Ok(Arc::new(Self { account }))
+	}
+
+	/// Decrypts the NEP-2 encrypted private key `nep2` with `password`.
+	#[uniffi::constructor]
+	pub fn from_nep2(nep2: String, password: String) -> Result<Arc<Self>, NeoError> {
+		let password = Zeroizing::new(password);
+		let mut encrypted = Account { encrypted_private_key: Some(nep2), ..Default::default() };
+		encrypted
+			.decrypt_private_key(&password)
+			.map_err(|e| NeoError::InvalidKey(e.to_string()))?;
+		let key_pair = encrypted.key_pair.ok_or_else(|| {
+			NeoError::InvalidKey("the NEP-2 string holds no private key".to_string())
+		})?;
+		let account = Account::from_key_pair(key_pair, None, None)
+			.map_err(|e| NeoError::InvalidKey(e.to_string()))?;
+		Ok(Arc::new(Self { account }))
+	}
+
+	/// The Neo address, e.g. `NiHURyS83nX2mpxtA7xq84cGxVbHojj5Wc`
+	pub fn address(&self) -> String {
+		self.account.address_or_scripthash().address().to_string()
+	}
+
+	/// The script hash as hex
+	pub fn script_hash(&self) -> String {
+		hex::encode(self.account.get_script_hash())
+	}
+
+	/// The compressed public key as hex, if the account holds a key pair
+	pub fn public_key(&self) -> Option<String> {
+		self.account
+			.key_pair()
+			.as_ref()
+			.map(|key_pair| key_pair.public_key().get_encoded_compressed_hex())
+	}
+
+	/// Exports the private key in Wallet Import Format.
+	pub fn export_wif(&self) -> Result<String, NeoError> {
+		self.account.export_wif().map_err(|e| NeoError::InvalidKey(e.to_string()))
+	}
+
+	/// Encrypts the private key with `password` according to NEP-2.
+	pub fn export_nep2(&self, password: String) -> Result<String, NeoError> {
+		let password = Zeroizing::new(password);
+		let mut account = self.account.clone();
+		account
+			.encrypt_private_key(&password)
+			.map_err(|e| NeoError::InvalidKey(e.to_string()))?;
+		account
+			.encrypted_private_key
+			.ok_or_else(|| NeoError::InvalidKey("the account holds no private key".to_string()))
+	}
+
+	/// Signs `tx`, which must have been prepared for this account, e.g. with
+	/// [`NeoClient::prepare_transfer`](crate::NeoClient::prepare_transfer).
+	pub fn sign(&self, tx: UnsignedTransaction) -> Result<SignedTransaction, NeoError> {
+		let key_pair =
+			self.account.key_pair().as_ref().ok_or_else(|| {
+				NeoError::InvalidKey("the account holds no private key".to_string())
+			})?;
+		let mut transaction = Transaction::decode(&mut Decoder::new(&tx.raw))
+			.map_err(|e| NeoError::InvalidTransaction(e.to_string()))?;
+		let signer = self.account.get_script_hash();
+		if !transaction.signers.iter().any(|s| *s.get_signer_hash() == signer) {
+			return Err(NeoError::InvalidTransaction(format!(
+				"{} is not a signer of the transaction",
+				self.address()
+			)))
+		}
+		transaction.set_network_magic(tx.network_magic);
+		let hash_data = transaction.get_hash_data(tx.network_magic);
+		let witness = Witness::create(hash_data, key_pair)
+			.map_err(|e| NeoError::InvalidTransaction(e.to_string()))?;
+		transaction.add_witness(witness);
+		Ok(SignedTransaction { raw: transaction.to_array() })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::transaction::transfer_script;
+	use neo_providers::core::transaction::{
+		signers::{account_signer::AccountSigner, signer::Signer},
+		transaction_template::TransactionTemplate,
+	};
+	use primitive_types::H160;
+
+	#[test]
+	fn test_wif_round_trip() {
+		let account = NeoAccount::create().unwrap();
+		let wif = account.export_wif().unwrap();
+		let imported = NeoAccount::from_wif(wif).unwrap();
+		assert_eq!(imported.address(), account.address());
+		assert_eq!(imported.public_key(), account.public_key());
+	}
+
+	#[test]
+	fn test_invalid_wif() {
+		assert!(matches!(
+			NeoAccount::from_wif("invalid".to_string()),
+			Err(NeoError::InvalidKey(_))
+		));
+	}
+
+	#[test]
+	fn test_sign() {
+		let account = NeoAccount::create().unwrap();
+		let from = account.account.get_script_hash();
+		let script =
+			transfer_script(&H160::repeat_byte(1), &from, &H160::repeat_byte(2), 100).unwrap();
+		let signer: Signer = AccountSigner::called_by_entry(&account.account).unwrap().into();
+		let tx = TransactionTemplate::new(script, &[signer], 1)
+			.unwrap()
+			.instantiate(100, 0, 0)
+			.unwrap();
+		let unsigned = UnsignedTransaction {
+			raw: tx.to_array(),
+			network_magic: 860833102,
+			system_fee: 0,
+			network_fee: 0,
+			valid_until_block: 100,
+		};
+
+		let signed = account.sign(unsigned.clone()).unwrap();
+		let decoded = Transaction::decode(&mut Decoder::new(&signed.raw)).unwrap();
+		assert_eq!(decoded.witnesses.len(), 1);
+		assert!(matches!(
+			NeoAccount::create().unwrap().sign(unsigned),
+			Err(NeoError::InvalidTransaction(_))
+		));
+	}
+}
@@ -0,0 +1,159 @@
+use crate::{
+	transaction::{parse_address, parse_script_hash, transfer_script},
+	NeoAccount, NeoError, SignedTransaction, UnsignedTransaction,
+};
+use neo_codec::encode::NeoSerializable;
+use neo_providers::{
+	core::{
+		account::AccountTrait,
+		transaction::{
+			signers::{account_signer::AccountSigner, signer::Signer},
+			transaction_template::TransactionTemplate,
+		},
+	},
+	Http, Middleware, Provider,
+};
+use neo_types::encoding;
+use std::{convert::TryFrom, sync::Arc};
+
+/// A connection to a Neo node over HTTP(S).
+#[derive(Debug, uniffi::Object)]
+pub struct NeoClient {
+	provider: Provider<Http>,
+}
+
+#[uniffi::export]
+impl NeoClient {
+	/// Connects to the JSON-RPC endpoint at `url`, e.g. `https://mainnet1.neo.coz.io:443`.
+	#[uniffi::constructor]
+	pub fn new(url: String) -> Result<Arc<Self>, NeoError> {
+		let provider = Provider::<Http>::try_from(url.as_str())
+			.map_err(|e| NeoError::Rpc(format!("invalid url {url}: {e}")))?;
+		Ok(Arc::new(Self { provider }))
+	}
+}
+
+#[uniffi::export(async_runtime = "tokio")]
+impl NeoClient {
+	/// The number of blocks in the chain
+	pub async fn block_count(&self) -> Result<u32, NeoError> {
+		self.provider.get_block_count().await.map_err(|e| NeoError::Rpc(e.to_string()))
+	}
+
+	/// The magic number of the network of the node
+	pub async fn network_magic(&self) -> Result<u32, NeoError> {
+		network_magic(&self.provider).await
+	}
+
+	/// Prepares the transfer of `amount` fractions of the NEP-17 token with the script hash
+	/// `token` from `from` to the address `to`.
+	///
+	/// The fees are calculated by the node and paid by `from`. The transaction is valid for the
+	/// maximum number of blocks and is signed with [`NeoAccount::sign`].
+	pub async fn prepare_transfer(
+		&self,
+		from: Arc<NeoAccount>,
+		token: String,
+		to: String,
+		amount: u64,
+	) -> Result<UnsignedTransaction, NeoError> {
+		prepare_transfer(&self.provider, &from, &token, &to, amount).await
+	}
+
+	/// Sends `tx` to the node and returns its hash. Resending a transaction the node already
+	/// knows succeeds, so the call can be retried after network errors.
+	pub async fn broadcast(&self, tx: SignedTransaction) -> Result<String, NeoError> {
+		let sent = self
+			.provider
+			.send_raw_transaction_idempotent(encoding::base64_encode(tx.raw))
+			.await
+			.map_err(|e| NeoError::Rpc(e.to_string()))?;
+		Ok(format!("{:#x}", sent.hash))
+	}
+}
+
+async fn network_magic<M: Middleware>(provider: &M) -> Result<u32, NeoError> {
+	provider.get_network_magic().await.map_err(|e| NeoError::Rpc(e.to_string()))
+}
+
+async fn prepare_transfer<M: Middleware>(
+	provider: &M,
+	from: &NeoAccount,
+	token: &str,
+	to: &str,
+	amount: u64,
+) -> Result<UnsignedTransaction, NeoError> {
+	let token = parse_script_hash(token)?;
+	let to = parse_address(to)?;
+	let script = transfer_script(&token, &from.account.get_script_hash(), &to, amount)?;
+	let signer: Signer = AccountSigner::called_by_entry(&from.account)
+		.map_err(|e| NeoError::InvalidTransaction(e.to_string()))?
+		.into();
+
+	let network_magic = network_magic(provider).await?;
+	let tx = TransactionTemplate::new(script, &[signer], rand::random())
+		.map_err(|e| NeoError::InvalidTransaction(e.to_string()))?
+		.instantiate_with(provider)
+		.await
+		.map_err(|e| NeoError::Rpc(e.to_string()))?;
+	Ok(UnsignedTransaction {
+		raw: tx.to_array(),
+		network_magic,
+		system_fee: tx.sys_fee.datoms() as u64,
+		network_fee: tx.net_fee.datoms() as u64,
+		valid_until_block: tx.valid_until_block as u32,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_codec::Decoder;
+	use neo_providers::{core::transaction::transaction::Transaction, Expectation, Provider};
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_prepare_transfer() {
+		let (provider, mock) = Provider::mocked();
+		provider.shared_config().set_network_magic(860833102);
+		let from = NeoAccount::create().unwrap();
+		let gas = "0xd2a4cff31913016155e38e474a2c06d08be276cf";
+		let to = "NTrezR3C4X8aMLVg7vozt5wguyNfFhwuFx";
+		let script = transfer_script(
+			&parse_script_hash(gas).unwrap(),
+			&from.account.get_script_hash(),
+			&parse_address(to).unwrap(),
+			100,
+		)
+		.unwrap();
+
+		mock.expect(Expectation::new("getblockcount").returning(1000));
+		mock.expect(Expectation::new("invokescript").returning(json!({
+			"script": encoding::base64_encode(&script),
+			"state": "HALT",
+			"gasconsumed": "997775",
+			"stack": [{ "type": "Boolean", "value": true }]
+		})));
+		mock.expect(
+			Expectation::new("calculatenetworkfee").returning(json!({ "networkfee": "123000" })),
+		);
+
+		let unsigned = prepare_transfer(&provider, &from, gas, to, 100).await.unwrap();
+		mock.assert_expectations_met().unwrap();
+		assert_eq!(unsigned.network_magic, 860833102);
+		assert_eq!(unsigned.system_fee, 997_775);
+		assert_eq!(unsigned.network_fee, 123_000);
+		assert_eq!(
+			unsigned.valid_until_block,
+			1000 + provider.max_valid_until_block_increment() - 1
+		);
+		let tx = Transaction::decode(&mut Decoder::new(&unsigned.raw)).unwrap();
+		assert_eq!(tx.script, script);
+		assert!(tx.witnesses.is_empty());
+
+		assert!(matches!(
+			prepare_transfer(&provider, &from, gas, "not an address", 100).await,
+			Err(NeoError::InvalidAddress(_))
+		));
+	}
+}
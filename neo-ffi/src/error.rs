@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+/// Errors surfaced to Kotlin and Swift. The foreign side receives the variant and its message.
+#[derive(Debug, Error, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum NeoError {
+	/// An address or script hash could not be parsed
+	#[error("invalid address: {0}")]
+	InvalidAddress(String),
+	/// A private key, WIF or NEP-2 string is invalid, or the password is wrong
+	#[error("invalid key: {0}")]
+	InvalidKey(String),
+	/// A NEP-6 wallet could not be read or written
+	#[error("invalid wallet: {0}")]
+	InvalidWallet(String),
+	/// A transaction could not be built, decoded or signed
+	#[error("invalid transaction: {0}")]
+	InvalidTransaction(String),
+	/// The node could not be reached or answered with an error
+	#[error("rpc error: {0}")]
+	Rpc(String),
+}
@@ -0,0 +1,20 @@
+#![doc = include_str!("../README.md")]
+#![deny(unsafe_code, rustdoc::broken_intra_doc_links)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+uniffi::setup_scaffolding!();
+
+mod account;
+pub use account::NeoAccount;
+
+mod client;
+pub use client::NeoClient;
+
+mod error;
+pub use error::NeoError;
+
+mod transaction;
+pub use transaction::{SignedTransaction, UnsignedTransaction};
+
+mod wallet;
+pub use wallet::NeoWallet;
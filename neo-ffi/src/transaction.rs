@@ -0,0 +1,117 @@
+use crate::NeoError;
+use neo_providers::core::{
+	script::script_builder::ScriptBuilder, transaction::call_flags::CallFlags,
+};
+use neo_types::{
+	address::Address, contract_parameter::ContractParameter,
+	contract_parameter_type::ContractParameterType, script_hash::ScriptHashExt, Bytes,
+};
+use primitive_types::H160;
+
+/// A transaction with fees and validity window, waiting to be signed with
+/// [`NeoAccount::sign`](crate::NeoAccount::sign).
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct UnsignedTransaction {
+	/// The serialized transaction without witnesses
+	pub raw: Vec<u8>,
+	/// The magic number of the network the transaction is valid on
+	pub network_magic: u32,
+	/// The system fee in GAS fractions
+	pub system_fee: u64,
+	/// The network fee in GAS fractions
+	pub network_fee: u64,
+	/// The last block the transaction can be included in
+	pub valid_until_block: u32,
+}
+
+/// A signed transaction, ready to be broadcast with
+/// [`NeoClient::broadcast`](crate::NeoClient::broadcast).
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct SignedTransaction {
+	/// The serialized transaction including witnesses
+	pub raw: Vec<u8>,
+}
+
+/// Parses a Neo address into its script hash.
+pub(crate) fn parse_address(address: &str) -> Result<H160, NeoError> {
+	let parsed: Address =
+		address.parse().map_err(|_| NeoError::InvalidAddress(address.to_string()))?;
+	Ok(parsed.script_hash())
+}
+
+/// Parses a script hash in hex, with or without `0x` prefix.
+pub(crate) fn parse_script_hash(hash: &str) -> Result<H160, NeoError> {
+	H160::from_hex(hash.trim_start_matches("0x"))
+		.map_err(|_| NeoError::InvalidAddress(format!("invalid script hash {hash}")))
+}
+
+/// The script calling `transfer` on the NEP-17 token `token`.
+pub(crate) fn transfer_script(
+	token: &H160,
+	from: &H160,
+	to: &H160,
+	amount: u64,
+) -> Result<Bytes, NeoError> {
+	let amount = i64::try_from(amount)
+		.map_err(|_| NeoError::InvalidTransaction(format!("amount {amount} is too large")))?;
+	let params = [
+		ContractParameter::hash160(from),
+		ContractParameter::hash160(to),
+		ContractParameter::integer(amount),
+		ContractParameter::new(ContractParameterType::Any),
+	];
+	let mut builder = ScriptBuilder::new();
+	builder
+		.contract_call(token, "transfer", &params, CallFlags::All)
+		.map_err(|e| NeoError::InvalidTransaction(e.to_string()))?;
+	Ok(builder.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_config::TestConstants;
+
+	#[test]
+	fn test_parse_address() {
+		let script_hash = H160::from_hex(TestConstants::DEFAULT_ACCOUNT_SCRIPT_HASH).unwrap();
+		assert_eq!(parse_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap(), script_hash);
+		assert!(matches!(parse_address(""), Err(NeoError::InvalidAddress(_))));
+		// a script hash is not an address
+		assert!(parse_address(TestConstants::DEFAULT_ACCOUNT_SCRIPT_HASH).is_err());
+	}
+
+	#[test]
+	fn test_parse_script_hash() {
+		let hash = "d2a4cff31913016155e38e474a2c06d08be276cf";
+		let expected = H160::from_hex(hash).unwrap();
+		assert_eq!(parse_script_hash(hash).unwrap(), expected);
+		assert_eq!(parse_script_hash(&format!("0x{hash}")).unwrap(), expected);
+		assert!(matches!(parse_script_hash(&hash[2..]), Err(NeoError::InvalidAddress(_))));
+		assert!(parse_script_hash(TestConstants::DEFAULT_ACCOUNT_ADDRESS).is_err());
+	}
+
+	#[test]
+	fn test_transfer_script() {
+		let (token, from, to) = (H160::repeat_byte(1), H160::repeat_byte(2), H160::repeat_byte(3));
+		let expected = |amount: i64| {
+			let params = [
+				ContractParameter::hash160(&from),
+				ContractParameter::hash160(&to),
+				ContractParameter::integer(amount),
+				ContractParameter::new(ContractParameterType::Any),
+			];
+			let mut builder = ScriptBuilder::new();
+			builder.contract_call(&token, "transfer", &params, CallFlags::All).unwrap();
+			builder.to_bytes()
+		};
+
+		assert_eq!(transfer_script(&token, &from, &to, 100).unwrap(), expected(100));
+		let max = i64::MAX as u64;
+		assert_eq!(transfer_script(&token, &from, &to, max).unwrap(), expected(i64::MAX));
+		assert!(matches!(
+			transfer_script(&token, &from, &to, max + 1),
+			Err(NeoError::InvalidTransaction(_))
+		));
+	}
+}
@@ -0,0 +1,136 @@
+use crate::{transaction::parse_address, NeoAccount, NeoError};
+use neo_providers::core::{account::AccountTrait, wallet::WalletTrait};
+use neo_signers::{NEP6Wallet, Wallet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use zeroize::Zeroizing;
+
+/// A NEP-6 wallet holding several accounts, one of them the default account.
+#[derive(Debug, uniffi::Object)]
+pub struct NeoWallet {
+	wallet: Mutex<Wallet>,
+}
+
+#[uniffi::export]
+impl NeoWallet {
+	/// Creates an empty wallet named `name`.
+	#[uniffi::constructor]
+	pub fn new(name: String) -> Arc<Self> {
+		let mut wallet = Wallet::new();
+		wallet.set_name(name);
+		Arc::new(Self { wallet: Mutex::new(wallet) })
+	}
+
+	/// Reads a wallet in the NEP-6 JSON format. Encrypted accounts stay encrypted until
+	/// [`NeoWallet::decrypt_accounts`] is called.
+	#[uniffi::constructor]
+	pub fn from_nep6_json(json: String) -> Result<Arc<Self>, NeoError> {
+		let nep6: NEP6Wallet =
+			serde_json::from_str(&json).map_err(|e| NeoError::InvalidWallet(e.to_string()))?;
+		if !nep6.accounts().is_empty() && !nep6.accounts().iter().any(|a| a.is_default) {
+			return Err(NeoError::InvalidWallet("the wallet has no default account".to_string()))
+		}
+		let wallet = Wallet::from_nep6(nep6).map_err(|e| NeoError::InvalidWallet(e.to_string()))?;
+		Ok(Arc::new(Self { wallet: Mutex::new(wallet) }))
+	}
+
+	/// Writes the wallet in the NEP-6 JSON format. Call [`NeoWallet::encrypt_accounts`] first,
+	/// NEP-6 only stores encrypted private keys.
+	pub fn to_nep6_json(&self) -> Result<String, NeoError> {
+		let nep6 = self.lock().to_nep6().map_err(|e| NeoError::InvalidWallet(e.to_string()))?;
+		serde_json::to_string(&nep6).map_err(|e| NeoError::InvalidWallet(e.to_string()))
+	}
+
+	/// The name of the wallet
+	pub fn name(&self) -> String {
+		self.lock().name().clone()
+	}
+
+	/// Adds `account`. The first account added to an empty wallet becomes the default account.
+	pub fn add_account(&self, account: Arc<NeoAccount>) {
+		let mut wallet = self.lock();
+		let script_hash = account.account.get_script_hash();
+		if wallet.accounts().is_empty() {
+			wallet.set_default_account(script_hash);
+		}
+		wallet.add_account(account.account.clone());
+	}
+
+	/// Creates an account with a new random private key and adds it.
+	pub fn create_account(&self) -> Result<Arc<NeoAccount>, NeoError> {
+		let account = NeoAccount::create()?;
+		self.add_account(account.clone());
+		Ok(account)
+	}
+
+	/// The accounts of the wallet
+	pub fn accounts(&self) -> Vec<Arc<NeoAccount>> {
+		self.lock()
+			.accounts()
+			.values()
+			.map(|account| Arc::new(NeoAccount { account: account.clone() }))
+			.collect()
+	}
+
+	/// The default account, or `None` if the wallet is empty
+	pub fn default_account(&self) -> Option<Arc<NeoAccount>> {
+		let wallet = self.lock();
+		if wallet.accounts().is_empty() {
+			return None
+		}
+		Some(Arc::new(NeoAccount { account: wallet.default_account().clone() }))
+	}
+
+	/// Makes the account with the address `address` the default account.
+	pub fn set_default_account(&self, address: String) -> Result<(), NeoError> {
+		let script_hash = parse_address(&address)?;
+		let mut wallet = self.lock();
+		if !wallet.accounts().contains_key(&script_hash) {
+			return Err(NeoError::InvalidAddress(format!("{address} is not in the wallet")))
+		}
+		wallet.set_default_account(script_hash);
+		Ok(())
+	}
+
+	/// Encrypts the private keys of all accounts with `password` according to NEP-2.
+	pub fn encrypt_accounts(&self, password: String) {
+		let password = Zeroizing::new(password);
+		self.lock().encrypt_accounts(&password);
+	}
+
+	/// Decrypts the NEP-2 encrypted private keys of all accounts with `password`.
+	pub fn decrypt_accounts(&self, password: String) -> Result<(), NeoError> {
+		let password = Zeroizing::new(password);
+		for account in self.lock().accounts.values_mut() {
+			account
+				.decrypt_private_key(&password)
+				.map_err(|e| NeoError::InvalidKey(e.to_string()))?;
+		}
+		Ok(())
+	}
+}
+
+impl NeoWallet {
+	fn lock(&self) -> MutexGuard<'_, Wallet> {
+		self.wallet.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_default_account() {
+		let wallet = NeoWallet::new("mobile".to_string());
+		assert!(wallet.default_account().is_none());
+
+		let first = wallet.create_account().unwrap();
+		let second = wallet.create_account().unwrap();
+		assert_eq!(wallet.accounts().len(), 2);
+		assert_eq!(wallet.default_account().unwrap().address(), first.address());
+
+		wallet.set_default_account(second.address()).unwrap();
+		assert_eq!(wallet.default_account().unwrap().address(), second.address());
+		assert!(wallet.set_default_account(NeoAccount::create().unwrap().address()).is_err());
+	}
+}
@@ -0,0 +1,380 @@
+use async_trait::async_trait;
+use neo_codec::{encode::NeoSerializable, Decoder};
+use neo_providers::{
+	core::{
+		responses::neo_send_raw_transaction::RawTransaction, script::script_reader::ScriptReader,
+		transaction::transaction::Transaction,
+	},
+	metrics, Middleware, MiddlewareError,
+};
+use neo_types::{encoding, gas::Gas};
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	convert::Infallible,
+	fmt::Debug,
+	fs::{File, OpenOptions},
+	io::{self, BufRead, BufReader, Write},
+	path::Path,
+	sync::Mutex,
+	time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+use tracing::warn;
+
+/// The outcome of the broadcast of an audited transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum AuditResult {
+	/// The transaction is about to be broadcast. A pending transaction without later record was
+	/// interrupted, e.g. by a crash, and may have reached the node.
+	Pending,
+	/// The node accepted the transaction
+	Sent,
+	/// The broadcast failed, the transaction may be sent again
+	Failed { error: String },
+}
+
+/// A record of a signed transaction broadcast through an [`AuditMiddleware`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+	pub hash: H256,
+	/// Unix time of the record in milliseconds
+	pub timestamp: u64,
+	pub signers: Vec<H160>,
+	pub system_fee: Gas,
+	pub network_fee: Gas,
+	pub valid_until_block: u32,
+	/// The disassembly of the script
+	pub script: String,
+	/// The signed transaction, base64 encoded
+	pub transaction: String,
+	#[serde(flatten)]
+	pub result: AuditResult,
+}
+
+impl AuditRecord {
+	/// A pending record of `tx`, whose serialization is `transaction`
+	pub fn pending(tx: &Transaction, transaction: String) -> Self {
+		Self {
+			hash: tx.hash(),
+			timestamp: now(),
+			signers: tx.signers.iter().map(|signer| *signer.get_signer_hash()).collect(),
+			system_fee: tx.sys_fee,
+			network_fee: tx.net_fee,
+			valid_until_block: tx.valid_until_block as u32,
+			script: ScriptReader::convert_to_op_code_string(&tx.script),
+			transaction,
+			result: AuditResult::Pending,
+		}
+	}
+}
+
+fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |since| since.as_millis() as u64)
+}
+
+/// Persists the records of an [`AuditMiddleware`].
+///
+/// Unlike a [`CacheStore`](crate::CacheStore), a sink must be reliable: a transaction is only
+/// broadcast once its pending record is written.
+pub trait AuditSink: Send + Sync + Debug {
+	type Error: std::error::Error + Send + Sync + 'static;
+
+	/// Appends `record` to the trail.
+	fn append(&self, record: &AuditRecord) -> Result<(), Self::Error>;
+
+	/// The result of the latest record of the transaction `hash`, `None` if it has none.
+	fn status(&self, hash: &H256) -> Result<Option<AuditResult>, Self::Error>;
+}
+
+/// An in-memory [`AuditSink`], which does not survive restarts.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+	records: Mutex<Vec<AuditRecord>>,
+}
+
+impl MemorySink {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The records in the order they were appended
+	pub fn records(&self) -> Vec<AuditRecord> {
+		self.records.lock().unwrap().clone()
+	}
+}
+
+impl AuditSink for MemorySink {
+	type Error = Infallible;
+
+	fn append(&self, record: &AuditRecord) -> Result<(), Self::Error> {
+		self.records.lock().unwrap().push(record.clone());
+		Ok(())
+	}
+
+	fn status(&self, hash: &H256) -> Result<Option<AuditResult>, Self::Error> {
+		let records = self.records.lock().unwrap();
+		Ok(records
+			.iter()
+			.rev()
+			.find(|record| record.hash == *hash)
+			.map(|r| r.result.clone()))
+	}
+}
+
+/// An [`AuditSink`] appending the records as JSON lines to a file, synced to disk after every
+/// record.
+#[derive(Debug)]
+pub struct JsonlSink {
+	inner: Mutex<JsonlFile>,
+}
+
+#[derive(Debug)]
+struct JsonlFile {
+	file: File,
+	/// The result of the latest record by transaction
+	results: HashMap<H256, AuditResult>,
+}
+
+impl JsonlSink {
+	/// Opens the trail at `path`, creating it if it does not exist. The results of the existing
+	/// records are loaded to refuse sending their transactions again.
+	pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+		let path = path.as_ref();
+		let mut results = HashMap::new();
+		if path.exists() {
+			for line in BufReader::new(File::open(path)?).lines() {
+				let line = line?;
+				if line.trim().is_empty() {
+					continue
+				}
+				let record: AuditRecord = serde_json::from_str(&line)?;
+				results.insert(record.hash, record.result);
+			}
+		}
+		let file = OpenOptions::new().create(true).append(true).open(path)?;
+		Ok(Self { inner: Mutex::new(JsonlFile { file, results }) })
+	}
+}
+
+impl AuditSink for JsonlSink {
+	type Error = io::Error;
+
+	fn append(&self, record: &AuditRecord) -> Result<(), Self::Error> {
+		let mut line = serde_json::to_vec(record)?;
+		line.push(b'\n');
+		let mut inner = self.inner.lock().unwrap();
+		inner.file.write_all(&line)?;
+		inner.file.sync_data()?;
+		inner.results.insert(record.hash, record.result.clone());
+		Ok(())
+	}
+
+	fn status(&self, hash: &H256) -> Result<Option<AuditResult>, Self::Error> {
+		Ok(self.inner.lock().unwrap().results.get(hash).cloned())
+	}
+}
+
+/// An [`AuditSink`] storing the records in a SQLite table `audit_records`.
+#[cfg(feature = "rusqlite")]
+#[derive(Debug)]
+pub struct SqliteSink {
+	connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "rusqlite")]
+impl SqliteSink {
+	/// Opens the database at `path`, creating it and the table if they do not exist.
+	pub fn open(path: impl AsRef<Path>) -> Result<Self, rusqlite::Error> {
+		Self::new(rusqlite::Connection::open(path)?)
+	}
+
+	/// Stores the records in the database of `connection`, creating the table if it does not
+	/// exist.
+	pub fn new(connection: rusqlite::Connection) -> Result<Self, rusqlite::Error> {
+		connection.execute(
+			"CREATE TABLE IF NOT EXISTS audit_records (
+				id INTEGER PRIMARY KEY AUTOINCREMENT,
+				hash TEXT NOT NULL,
+				timestamp INTEGER NOT NULL,
+				record TEXT NOT NULL
+			)",
+			[],
+		)?;
+		connection
+			.execute("CREATE INDEX IF NOT EXISTS audit_records_hash ON audit_records (hash)", [])?;
+		Ok(Self { connection: Mutex::new(connection) })
+	}
+}
+
+#[cfg(feature = "rusqlite")]
+impl AuditSink for SqliteSink {
+	type Error = rusqlite::Error;
+
+	fn append(&self, record: &AuditRecord) -> Result<(), Self::Error> {
+		let json = serde_json::to_string(record)
+			.map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+		self.connection.lock().unwrap().execute(
+			"INSERT INTO audit_records (hash, timestamp, record) VALUES (?1, ?2, ?3)",
+			rusqlite::params![format!("{:x}", record.hash), record.timestamp as i64, json],
+		)?;
+		Ok(())
+	}
+
+	fn status(&self, hash: &H256) -> Result<Option<AuditResult>, Self::Error> {
+		use rusqlite::OptionalExtension;
+
+		let connection = self.connection.lock().unwrap();
+		let json: Option<String> = connection
+			.query_row(
+				"SELECT record FROM audit_records WHERE hash = ?1 ORDER BY id DESC LIMIT 1",
+				[format!("{hash:x}")],
+				|row| row.get(0),
+			)
+			.optional()?;
+		json.map(|json| {
+			serde_json::from_str::<AuditRecord>(&json)
+				.map(|record| record.result)
+				.map_err(|e| {
+					rusqlite::Error::FromSqlConversionFailure(
+						0,
+						rusqlite::types::Type::Text,
+						Box::new(e),
+					)
+				})
+		})
+		.transpose()
+	}
+}
+
+/// Middleware recording every signed transaction it broadcasts in an [`AuditSink`].
+///
+/// A pending record is written before the transaction is sent, and a second record with the
+/// outcome afterwards. Transactions whose latest record is pending or sent are refused with
+/// [`AuditMiddlewareError::AlreadySent`], so a persistent sink keeps payloads from being
+/// replayed after a restart. Failed transactions may be sent again.
+///
+/// The records are written on [`Middleware::send_raw_transaction`], so the middleware goes
+/// below the [`SignerMiddleware`](crate::SignerMiddleware) to see the signed payloads.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_middleware::{
+/// 	audit::{AuditMiddleware, JsonlSink},
+/// 	SignerMiddleware,
+/// };
+/// use neo_providers::{Http, Provider};
+/// use neo_signers::Wallet;
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(wallet: Wallet) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let provider = AuditMiddleware::new(provider, JsonlSink::open("audit.jsonl")?);
+/// let client = SignerMiddleware::new(provider, wallet);
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct AuditMiddleware<M, S> {
+	inner: M,
+	sink: S,
+}
+
+impl<M, S> AuditMiddleware<M, S>
+where
+	M: Middleware,
+	S: AuditSink,
+{
+	/// Records the transactions sent through `inner` in `sink`.
+	pub fn new(inner: M, sink: S) -> Self {
+		Self { inner, sink }
+	}
+
+	/// The sink the records are written to
+	pub fn sink(&self) -> &S {
+		&self.sink
+	}
+}
+
+#[derive(Error, Debug)]
+/// Error thrown when the client interacts with the audit middleware.
+pub enum AuditMiddlewareError<M: Middleware, S: AuditSink> {
+	/// Thrown when a record cannot be written or read
+	#[error("audit trail: {0}")]
+	SinkError(S::Error),
+	/// Thrown when a transaction was already sent, or its pending record was interrupted
+	#[error("transaction {0:?} was already sent")]
+	AlreadySent(H256),
+	/// Thrown when the payload is not a base64 encoded transaction
+	#[error("invalid transaction: {0}")]
+	InvalidTransaction(String),
+	/// Thrown when an internal middleware errors
+	#[error(transparent)]
+	MiddlewareError(M::Error),
+}
+
+impl<M: Middleware, S: AuditSink> MiddlewareError for AuditMiddlewareError<M, S> {
+	type Inner = M::Error;
+
+	fn from_err(src: M::Error) -> Self {
+		AuditMiddlewareError::MiddlewareError(src)
+	}
+
+	fn as_inner(&self) -> Option<&Self::Inner> {
+		match self {
+			AuditMiddlewareError::MiddlewareError(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<M, S> Middleware for AuditMiddleware<M, S>
+where
+	M: Middleware,
+	S: AuditSink,
+{
+	type Error = AuditMiddlewareError<M, S>;
+	type Provider = M::Provider;
+	type Inner = M;
+
+	fn inner(&self) -> &M {
+		&self.inner
+	}
+
+	/// Records the transaction before and after sending it.
+	async fn send_raw_transaction(&self, hex: String) -> Result<RawTransaction, Self::Error> {
+		metrics::instrument("audit", "send_raw_transaction", async move {
+			let bytes = encoding::base64_decode(&hex)
+				.map_err(|e| AuditMiddlewareError::InvalidTransaction(e.to_string()))?;
+			let tx = Transaction::decode(&mut Decoder::new(&bytes))
+				.map_err(|e| AuditMiddlewareError::InvalidTransaction(e.to_string()))?;
+
+			let mut record = AuditRecord::pending(&tx, hex.clone());
+			match self.sink.status(&record.hash).map_err(AuditMiddlewareError::SinkError)? {
+				Some(AuditResult::Pending | AuditResult::Sent) =>
+					return Err(AuditMiddlewareError::AlreadySent(record.hash)),
+				_ => {},
+			}
+			self.sink.append(&record).map_err(AuditMiddlewareError::SinkError)?;
+
+			let result = self.inner.send_raw_transaction(hex).await;
+			record.timestamp = now();
+			record.result = match &result {
+				Ok(_) => AuditResult::Sent,
+				Err(e) => AuditResult::Failed { error: e.to_string() },
+			};
+			// the pending record keeps the transaction from being sent again
+			if let Err(err) = self.sink.append(&record) {
+				warn!(hash = ?record.hash, err = %err, "could not record the broadcast");
+			}
+			result.map_err(AuditMiddlewareError::MiddlewareError)
+		})
+		.await
+	}
+}
@@ -1,6 +1,9 @@
-use crate::SignerMiddleware;
+use crate::{
+	cache::CacheStore, CacheMiddleware, SignerMiddleware, TimeoutMiddleware, TracingMiddleware,
+};
 use neo_providers::Middleware;
 use neo_signers::Signer;
+use std::time::Duration;
 
 /// A builder trait to compose different [`Middleware`] layers and then build a composed
 /// [`Provider`](neo_providers::Provider) architecture.
@@ -55,6 +58,24 @@ pub trait MiddlewareBuilder: Middleware + Sized + 'static {
 	{
 		SignerMiddleware::new(self, s)
 	}
+
+	/// Wraps `self` inside a [`CacheMiddleware`] caching responses in `store`.
+	fn with_cache<S>(self, store: S) -> CacheMiddleware<Self, S>
+	where
+		S: CacheStore,
+	{
+		CacheMiddleware::new(self, store)
+	}
+
+	/// Wraps `self` inside a [`TracingMiddleware`].
+	fn with_tracing(self) -> TracingMiddleware<Self> {
+		TracingMiddleware::new(self)
+	}
+
+	/// Wraps `self` inside a [`TimeoutMiddleware`] cancelling operations after `timeout`.
+	fn with_operation_timeout(self, timeout: Duration) -> TimeoutMiddleware<Self> {
+		TimeoutMiddleware::new(self, timeout)
+	}
 }
 
 impl<M> MiddlewareBuilder for M where M: Middleware + Sized + 'static {}
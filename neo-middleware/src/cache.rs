@@ -0,0 +1,326 @@
+use async_trait::async_trait;
+use neo_providers::{
+	core::responses::{
+		neo_application_log::ApplicationLog, neo_block::NeoBlock,
+		neo_send_raw_transaction::RawTransaction, neo_transaction_result::TransactionResult,
+	},
+	metrics, Middleware, MiddlewareError,
+};
+use primitive_types::{H160, H256};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt::Debug,
+	future::Future,
+	sync::Mutex,
+};
+use thiserror::Error;
+use tracing::{trace, warn};
+
+/// A key-value store holding the responses cached by a [`CacheMiddleware`].
+///
+/// Stores are best effort: a value may be evicted at any time, and failing to persist a value
+/// must not fail the request that produced it.
+pub trait CacheStore: Send + Sync + Debug {
+	/// Returns the value stored at `key`.
+	fn get(&self, key: &str) -> Option<Vec<u8>>;
+
+	/// Stores `value` at `key`, replacing any previous value.
+	fn insert(&self, key: String, value: Vec<u8>);
+}
+
+/// An in-memory [`CacheStore`] evicting the least recently used entries once it holds
+/// `capacity` entries.
+#[derive(Debug)]
+pub struct MemoryStore {
+	capacity: usize,
+	inner: Mutex<LruEntries>,
+}
+
+#[derive(Debug, Default)]
+struct LruEntries {
+	/// The values with the tick they were last used at
+	values: HashMap<String, (Vec<u8>, u64)>,
+	/// The keys by the tick they were last used at, oldest first
+	by_use: BTreeMap<u64, String>,
+	tick: u64,
+}
+
+impl LruEntries {
+	fn touch(&mut self, key: &str) -> Option<Vec<u8>> {
+		self.tick += 1;
+		let (value, used) = self.values.get_mut(key)?;
+		self.by_use.remove(used);
+		*used = self.tick;
+		self.by_use.insert(self.tick, key.to_string());
+		Some(value.clone())
+	}
+}
+
+impl MemoryStore {
+	/// The capacity of [`MemoryStore::default`]
+	pub const DEFAULT_CAPACITY: usize = 10_000;
+
+	/// Creates a store holding up to `capacity` entries.
+	pub fn new(capacity: usize) -> Self {
+		Self { capacity: capacity.max(1), inner: Mutex::new(LruEntries::default()) }
+	}
+
+	/// Number of cached entries
+	pub fn len(&self) -> usize {
+		self.inner.lock().unwrap().values.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl Default for MemoryStore {
+	fn default() -> Self {
+		Self::new(Self::DEFAULT_CAPACITY)
+	}
+}
+
+impl CacheStore for MemoryStore {
+	fn get(&self, key: &str) -> Option<Vec<u8>> {
+		self.inner.lock().unwrap().touch(key)
+	}
+
+	fn insert(&self, key: String, value: Vec<u8>) {
+		let mut entries = self.inner.lock().unwrap();
+		entries.tick += 1;
+		let tick = entries.tick;
+		if let Some((_, used)) = entries.values.insert(key.clone(), (value, tick)) {
+			entries.by_use.remove(&used);
+		}
+		entries.by_use.insert(tick, key);
+		while entries.values.len() > self.capacity {
+			let Some((_, oldest)) = entries.by_use.pop_first() else { break };
+			entries.values.remove(&oldest);
+		}
+	}
+}
+
+/// A [`CacheStore`] persisting the responses in a [sled](https://docs.rs/sled) tree, so they
+/// survive restarts.
+#[cfg(feature = "sled")]
+#[derive(Debug, Clone)]
+pub struct SledStore {
+	tree: sled::Tree,
+}
+
+#[cfg(feature = "sled")]
+impl SledStore {
+	/// Opens the database at `path`, creating it if it does not exist.
+	pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, sled::Error> {
+		Ok(Self::new(sled::open(path)?.open_tree("neo-cache")?))
+	}
+
+	/// Stores the responses in `tree`.
+	pub fn new(tree: sled::Tree) -> Self {
+		Self { tree }
+	}
+}
+
+#[cfg(feature = "sled")]
+impl CacheStore for SledStore {
+	fn get(&self, key: &str) -> Option<Vec<u8>> {
+		match self.tree.get(key) {
+			Ok(value) => value.map(|value| value.to_vec()),
+			Err(err) => {
+				warn!(key, err = ?err, "could not read from the cache");
+				None
+			},
+		}
+	}
+
+	fn insert(&self, key: String, value: Vec<u8>) {
+		if let Err(err) = self.tree.insert(key.as_bytes(), value) {
+			warn!(key, err = ?err, "could not write to the cache");
+		}
+	}
+}
+
+/// Middleware caching the responses to requests for immutable chain data: blocks and block
+/// headers by hash, transactions and application logs by hash, and contract storage at a fixed
+/// state root. Every other request is passed through.
+///
+/// Blocks are final once they are accepted by the consensus nodes, so cached blocks never go
+/// stale. Note that volatile fields like `confirmations` keep the value of the first response.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_middleware::cache::{CacheMiddleware, MemoryStore};
+/// use neo_providers::{Http, Middleware, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let provider = CacheMiddleware::new(provider, MemoryStore::new(1_000));
+///
+/// let hash = provider.get_block_hash(1_000).await?;
+/// let block = provider.get_block(hash, true).await?;
+/// // served from the cache
+/// let block = provider.get_block(hash, true).await?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct CacheMiddleware<M, S = MemoryStore> {
+	inner: M,
+	store: S,
+}
+
+impl<M, S> CacheMiddleware<M, S>
+where
+	M: Middleware,
+	S: CacheStore,
+{
+	/// Caches the responses of `inner` in `store`.
+	pub fn new(inner: M, store: S) -> Self {
+		Self { inner, store }
+	}
+
+	/// The store holding the cached responses
+	pub fn store(&self) -> &S {
+		&self.store
+	}
+
+	/// Returns the value cached at `key`, or caches the result of `fetch`, a call of `method`.
+	async fn cached<T, F>(
+		&self,
+		method: &str,
+		key: String,
+		fetch: F,
+	) -> Result<T, CacheMiddlewareError<M>>
+	where
+		T: Serialize + DeserializeOwned,
+		F: Future<Output = Result<T, M::Error>>,
+	{
+		metrics::instrument("cache", method, async move {
+			if let Some(value) = self.lookup(&key) {
+				return Ok(value)
+			}
+			let value = fetch.await.map_err(CacheMiddlewareError::MiddlewareError)?;
+			self.store_value(key, &value);
+			Ok(value)
+		})
+		.await
+	}
+
+	fn lookup<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+		let bytes = self.store.get(key)?;
+		match serde_json::from_slice(&bytes) {
+			Ok(value) => {
+				trace!(key, "cache hit");
+				Some(value)
+			},
+			Err(err) => {
+				warn!(key, err = ?err, "could not decode cached value");
+				None
+			},
+		}
+	}
+
+	fn store_value<T: Serialize>(&self, key: String, value: &T) {
+		match serde_json::to_vec(value) {
+			Ok(bytes) => self.store.insert(key, bytes),
+			Err(err) => warn!(key, err = ?err, "could not encode value to cache"),
+		}
+	}
+}
+
+#[derive(Error, Debug)]
+/// Error thrown when the client interacts with the cache middleware.
+pub enum CacheMiddlewareError<M: Middleware> {
+	/// Thrown when an internal middleware errors
+	#[error(transparent)]
+	MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> MiddlewareError for CacheMiddlewareError<M> {
+	type Inner = M::Error;
+
+	fn from_err(src: M::Error) -> Self {
+		CacheMiddlewareError::MiddlewareError(src)
+	}
+
+	fn as_inner(&self) -> Option<&Self::Inner> {
+		match self {
+			CacheMiddlewareError::MiddlewareError(e) => Some(e),
+		}
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<M, S> Middleware for CacheMiddleware<M, S>
+where
+	M: Middleware,
+	S: CacheStore,
+{
+	type Error = CacheMiddlewareError<M>;
+	type Provider = M::Provider;
+	type Inner = M;
+
+	fn inner(&self) -> &M {
+		&self.inner
+	}
+
+	async fn get_block(&self, block_hash: H256, full_tx: bool) -> Result<NeoBlock, Self::Error> {
+		let key = format!("block:{block_hash:x}:{full_tx}");
+		self.cached("get_block", key, self.inner.get_block(block_hash, full_tx)).await
+	}
+
+	async fn get_block_header(&self, block_hash: H256) -> Result<NeoBlock, Self::Error> {
+		let key = format!("header:{block_hash:x}");
+		self.cached("get_block_header", key, self.inner.get_block_header(block_hash))
+			.await
+	}
+
+	async fn get_transaction(&self, hash: H256) -> Result<Option<TransactionResult>, Self::Error> {
+		let key = format!("tx:{hash:x}");
+		metrics::instrument("cache", "get_transaction", async move {
+			if let Some(transaction) = self.lookup(&key) {
+				return Ok(Some(transaction))
+			}
+			let transaction = self
+				.inner
+				.get_transaction(hash)
+				.await
+				.map_err(CacheMiddlewareError::MiddlewareError)?;
+			// unknown and pending transactions may still be included in a later block
+			if let Some(transaction) =
+				transaction.as_ref().filter(|transaction| transaction.block_hash.is_some())
+			{
+				self.store_value(key, transaction);
+			}
+			Ok(transaction)
+		})
+		.await
+	}
+
+	async fn get_raw_transaction(&self, tx_hash: H256) -> Result<RawTransaction, Self::Error> {
+		let key = format!("rawtx:{tx_hash:x}");
+		self.cached("get_raw_transaction", key, self.inner.get_raw_transaction(tx_hash))
+			.await
+	}
+
+	async fn get_application_log(&self, tx_hash: H256) -> Result<ApplicationLog, Self::Error> {
+		let key = format!("applog:{tx_hash:x}");
+		self.cached("get_application_log", key, self.inner.get_application_log(tx_hash))
+			.await
+	}
+
+	async fn get_state(
+		&self,
+		root_hash: H256,
+		contract_hash: H160,
+		key: &str,
+	) -> Result<String, Self::Error> {
+		let cache_key = format!("state:{root_hash:x}:{contract_hash:x}:{key}");
+		self.cached("get_state", cache_key, self.inner.get_state(root_hash, contract_hash, key))
+			.await
+	}
+}
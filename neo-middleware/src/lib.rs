@@ -4,7 +4,7 @@
 
 /// The [TransformerMiddleware] is used to intercept transactions
 /// and transform them to be sent via various supported transformers, e.g.,
-/// [DSProxy](crate::transformer::DsProxy).
+/// [relay contracts](crate::transformer::ContractProxyTransformer).
 pub mod transformer;
 pub use transformer::TransformerMiddleware;
 
@@ -17,9 +17,28 @@ pub use signer::SignerMiddleware;
 /// [`PolicyMiddleware`] before sending them.
 pub mod policy;
 pub use policy::{
-	AllowEverything, Policy, PolicyMiddleware, PolicyMiddlewareError, RejectEverything,
+	AllowEverything, And, ContractCallAllowList, DestinationAllowList, MaxFee, Or, Policy,
+	PolicyMiddleware, PolicyMiddlewareError, PolicyViolation, RejectEverything, SpendLimit,
 };
 
+/// The [CacheMiddleware] caches responses for immutable chain data, like blocks by hash, in a
+/// [`CacheStore`].
+pub mod cache;
+pub use cache::{CacheMiddleware, CacheStore, MemoryStore};
+
+/// The [TracingMiddleware] traces logical operations in spans carrying a correlation id, with
+/// secrets redacted from the logged requests.
+pub mod tracer;
+pub use tracer::TracingMiddleware;
+
+/// The [TimeoutMiddleware] cancels logical operations that do not complete within a deadline.
+pub mod timeout;
+pub use timeout::TimeoutMiddleware;
+
+/// The [AuditMiddleware] records every signed transaction it broadcasts in an [`AuditSink`].
+pub mod audit;
+pub use audit::{AuditMiddleware, AuditSink, JsonlSink};
+
 /// [MiddlewareBuilder] provides a way to compose many [`Middleware`]s in a concise way.
 pub mod builder;
 pub use builder::MiddlewareBuilder;
@@ -1,8 +1,19 @@
 use async_trait::async_trait;
 use neo_providers::{
-	core::transaction::transaction::Transaction, Middleware, MiddlewareError, PendingTransaction,
+	core::{
+		script::script_reader::{ContractCall, ScriptReader},
+		transaction::transaction::Transaction,
+	},
+	metrics, Middleware, MiddlewareError, PendingTransaction,
+};
+use neo_types::gas::Gas;
+use primitive_types::H160;
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::Debug,
+	sync::Mutex,
+	time::{SystemTime, UNIX_EPOCH},
 };
-use std::fmt::Debug;
 use thiserror::Error;
 
 /// Basic trait to ensure that transactions about to be sent follow certain rules.
@@ -15,6 +26,28 @@ pub trait Policy: Sync + Send + Debug {
 	///
 	/// Returns Ok with the `tx` or an Err otherwise.
 	async fn ensure_can_send(&self, tx: Transaction) -> Result<Transaction, Self::Error>;
+
+	/// Records that `tx`, which this policy allows, was sent, e.g. to count it towards a limit.
+	///
+	/// Called by [`PolicyMiddleware`] once the inner middleware sent the transaction, and not if
+	/// sending fails. Does nothing by default.
+	async fn on_sent(&self, _tx: &Transaction) {}
+
+	/// Combines the policies so that a transaction must comply with both, `self` first.
+	fn and<P: Policy>(self, other: P) -> And<Self, P>
+	where
+		Self: Sized,
+	{
+		And(self, other)
+	}
+
+	/// Combines the policies so that a transaction must comply with either, `self` first.
+	fn or<P: Policy>(self, other: P) -> Or<Self, P>
+	where
+		Self: Sized,
+	{
+		Or(self, other)
+	}
 }
 
 /// A policy that does not restrict anything.
@@ -45,7 +78,384 @@ impl Policy for RejectEverything {
 	}
 }
 
+/// The reason a transaction is rejected by one of the built-in policies
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+	/// The contract calls of the script could not be decoded, so they cannot be checked
+	#[error("the script cannot be checked: {0}")]
+	UncheckableScript(String),
+	/// The transaction transfers tokens to a destination that is not allowed
+	#[error("transfer of {token:?} to {to:?}, which is not an allowed destination")]
+	DestinationNotAllowed { token: H160, to: H160 },
+	/// The transaction transfers a token without a spend limit
+	#[error("transfer of {token:?}, which is not an allowed token")]
+	TokenNotAllowed { token: H160 },
+	/// The transaction transfers more than what remains of the daily limit of a token
+	#[error("transfer of {amount} {token:?} exceeds the {remaining} remaining of the daily limit")]
+	SpendLimitExceeded { token: H160, amount: i128, remaining: i128 },
+	/// The network fee of the transaction is too high
+	#[error("network fee of {fee} GAS exceeds the maximum of {max} GAS")]
+	NetworkFeeTooHigh { fee: Gas, max: Gas },
+	/// The system fee of the transaction is too high
+	#[error("system fee of {fee} GAS exceeds the maximum of {max} GAS")]
+	SystemFeeTooHigh { fee: Gas, max: Gas },
+	/// The transaction calls a contract method that is not allowed
+	#[error("call of {method} of {contract:?}, which is not allowed")]
+	CallNotAllowed { contract: H160, method: String },
+	/// None of the policies combined by [`Or`] allows the transaction
+	#[error("no policy allows the transaction: {0:?}")]
+	NoneSatisfied(Vec<PolicyViolation>),
+}
+
+fn contract_calls(tx: &Transaction) -> Result<Vec<ContractCall>, PolicyViolation> {
+	ScriptReader::contract_calls(&tx.script)
+		.map_err(|e| PolicyViolation::UncheckableScript(e.to_string()))
+}
+
+/// The recipient of a NEP-17 or NEP-11 `transfer`, `None` for other calls
+fn transfer_destination(call: &ContractCall) -> Result<Option<H160>, PolicyViolation> {
+	let to = match (call.method.as_str(), call.args.as_slice()) {
+		// NEP-17 transfer(from, to, amount, data)
+		("transfer", [_, to, _, _]) => to,
+		// NEP-11 transfer(to, tokenId, data)
+		("transfer", [to, _, _]) => to,
+		("transfer", _) =>
+			return Err(PolicyViolation::UncheckableScript(format!(
+				"unknown transfer of {:?}",
+				call.contract
+			))),
+		_ => return Ok(None),
+	};
+	to.as_hash160().map(Some).ok_or_else(|| {
+		PolicyViolation::UncheckableScript(format!("invalid recipient of {:?}", call.contract))
+	})
+}
+
+/// Transactions compliant with both policies, checked by `A` first.
+///
+/// Created by [`Policy::and`].
+#[derive(Debug, Clone)]
+pub struct And<A, B>(pub A, pub B);
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<A, B> Policy for And<A, B>
+where
+	A: Policy<Error = PolicyViolation>,
+	B: Policy<Error = PolicyViolation>,
+{
+	type Error = PolicyViolation;
+
+	async fn ensure_can_send(&self, tx: Transaction) -> Result<Transaction, Self::Error> {
+		let tx = self.0.ensure_can_send(tx).await?;
+		self.1.ensure_can_send(tx).await
+	}
+
+	async fn on_sent(&self, tx: &Transaction) {
+		self.0.on_sent(tx).await;
+		self.1.on_sent(tx).await;
+	}
+}
+
+/// Transactions compliant with either policy, checked by `A` first.
+///
+/// Created by [`Policy::or`]. Rejects with the violations of both policies.
+#[derive(Debug, Clone)]
+pub struct Or<A, B>(pub A, pub B);
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<A, B> Policy for Or<A, B>
+where
+	A: Policy<Error = PolicyViolation>,
+	B: Policy<Error = PolicyViolation>,
+{
+	type Error = PolicyViolation;
+
+	async fn ensure_can_send(&self, tx: Transaction) -> Result<Transaction, Self::Error> {
+		let first = match self.0.ensure_can_send(tx.clone()).await {
+			Ok(tx) => return Ok(tx),
+			Err(e) => e,
+		};
+		self.1.ensure_can_send(tx).await.map_err(|second| {
+			let mut violations = Vec::new();
+			for violation in [first, second] {
+				match violation {
+					PolicyViolation::NoneSatisfied(nested) => violations.extend(nested),
+					violation => violations.push(violation),
+				}
+			}
+			PolicyViolation::NoneSatisfied(violations)
+		})
+	}
+
+	/// Records the transaction with the policy which allowed it, `A` if both do.
+	async fn on_sent(&self, tx: &Transaction) {
+		if self.0.ensure_can_send(tx.clone()).await.is_ok() {
+			self.0.on_sent(tx).await
+		} else {
+			self.1.on_sent(tx).await
+		}
+	}
+}
+
+/// Rejects transactions whose fees exceed a maximum.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaxFee {
+	network_fee: Option<Gas>,
+	system_fee: Option<Gas>,
+}
+
+impl MaxFee {
+	/// A policy without maximum, see [`MaxFee::network_fee`] and [`MaxFee::system_fee`].
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the maximum network fee
+	#[must_use]
+	pub fn network_fee(mut self, max: Gas) -> Self {
+		self.network_fee = Some(max);
+		self
+	}
+
+	/// Sets the maximum system fee
+	#[must_use]
+	pub fn system_fee(mut self, max: Gas) -> Self {
+		self.system_fee = Some(max);
+		self
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Policy for MaxFee {
+	type Error = PolicyViolation;
+
+	async fn ensure_can_send(&self, tx: Transaction) -> Result<Transaction, Self::Error> {
+		match self.network_fee {
+			Some(max) if tx.net_fee > max =>
+				return Err(PolicyViolation::NetworkFeeTooHigh { fee: tx.net_fee, max }),
+			_ => {},
+		}
+		match self.system_fee {
+			Some(max) if tx.sys_fee > max =>
+				return Err(PolicyViolation::SystemFeeTooHigh { fee: tx.sys_fee, max }),
+			_ => {},
+		}
+		Ok(tx)
+	}
+}
+
+/// Rejects transactions transferring NEP-17 or NEP-11 tokens to other destinations than the
+/// allowed ones.
+///
+/// Transactions whose script cannot be decoded are rejected, see
+/// [`ScriptReader::contract_calls`].
+#[derive(Debug, Clone, Default)]
+pub struct DestinationAllowList {
+	destinations: HashSet<H160>,
+}
+
+impl DestinationAllowList {
+	pub fn new(destinations: impl IntoIterator<Item = H160>) -> Self {
+		Self { destinations: destinations.into_iter().collect() }
+	}
+
+	/// Allows transfers to `destination`
+	#[must_use]
+	pub fn allow(mut self, destination: H160) -> Self {
+		self.destinations.insert(destination);
+		self
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Policy for DestinationAllowList {
+	type Error = PolicyViolation;
+
+	async fn ensure_can_send(&self, tx: Transaction) -> Result<Transaction, Self::Error> {
+		for call in contract_calls(&tx)? {
+			match transfer_destination(&call)? {
+				Some(to) if !self.destinations.contains(&to) =>
+					return Err(PolicyViolation::DestinationNotAllowed { token: call.contract, to }),
+				_ => {},
+			}
+		}
+		Ok(tx)
+	}
+}
+
+/// Rejects transactions calling other contract methods than the allowed ones.
+///
+/// Transactions whose script cannot be decoded are rejected, see
+/// [`ScriptReader::contract_calls`].
+#[derive(Debug, Clone, Default)]
+pub struct ContractCallAllowList {
+	/// The allowed methods by contract, `None` if all are allowed
+	contracts: HashMap<H160, Option<HashSet<String>>>,
+}
+
+impl ContractCallAllowList {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Allows calls of all methods of `contract`
+	#[must_use]
+	pub fn allow_contract(mut self, contract: H160) -> Self {
+		self.contracts.insert(contract, None);
+		self
+	}
+
+	/// Allows calls of `method` of `contract`
+	#[must_use]
+	pub fn allow_method(mut self, contract: H160, method: impl Into<String>) -> Self {
+		if let Some(methods) =
+			self.contracts.entry(contract).or_insert_with(|| Some(HashSet::new()))
+		{
+			methods.insert(method.into());
+		}
+		self
+	}
+
+	fn is_allowed(&self, call: &ContractCall) -> bool {
+		match self.contracts.get(&call.contract) {
+			Some(None) => true,
+			Some(Some(methods)) => methods.contains(&call.method),
+			None => false,
+		}
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Policy for ContractCallAllowList {
+	type Error = PolicyViolation;
+
+	async fn ensure_can_send(&self, tx: Transaction) -> Result<Transaction, Self::Error> {
+		if let Some(call) = contract_calls(&tx)?.into_iter().find(|call| !self.is_allowed(call)) {
+			return Err(PolicyViolation::CallNotAllowed {
+				contract: call.contract,
+				method: call.method,
+			})
+		}
+		Ok(tx)
+	}
+}
+
+/// Limits the amount of each token transferred per day with NEP-17 `transfer`s. Transfers of
+/// tokens without a limit are rejected.
+///
+/// Days start at midnight UTC. The transfers of a transaction count towards the limits once it is
+/// sent by [`PolicyMiddleware`], see [`Policy::on_sent`], so transactions rejected by another
+/// policy or by the node do not. Transactions whose script cannot be decoded are rejected, see
+/// [`ScriptReader::contract_calls`].
+#[derive(Debug, Default)]
+pub struct SpendLimit {
+	limits: HashMap<H160, i128>,
+	/// The day and the amount transferred on it by token
+	spent: Mutex<HashMap<H160, (u64, i128)>>,
+}
+
+impl SpendLimit {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Allows transfers of up to `amount` of `token` per day, in its smallest unit
+	#[must_use]
+	pub fn limit(mut self, token: H160, amount: i128) -> Self {
+		self.limits.insert(token, amount);
+		self
+	}
+
+	/// The amount of `token` transferred today
+	pub fn spent_today(&self, token: &H160) -> i128 {
+		match self.spent.lock().unwrap().get(token) {
+			Some((day, spent)) if *day == today() => *spent,
+			_ => 0,
+		}
+	}
+
+	/// The amount transferred by `tx` by token
+	fn amounts(&self, tx: &Transaction) -> Result<HashMap<H160, i128>, PolicyViolation> {
+		let mut amounts = HashMap::<H160, i128>::new();
+		for call in contract_calls(tx)? {
+			if call.method != "transfer" {
+				continue
+			}
+			if !self.limits.contains_key(&call.contract) {
+				return Err(PolicyViolation::TokenNotAllowed { token: call.contract })
+			}
+			let (token, _, _, amount) = call.as_nep17_transfer().ok_or_else(|| {
+				PolicyViolation::UncheckableScript(format!(
+					"unknown transfer of {:?}",
+					call.contract
+				))
+			})?;
+			let total = amounts.entry(token).or_default();
+			*total = total.saturating_add(amount.max(0));
+		}
+		Ok(amounts)
+	}
+}
+
+fn today() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |since| since.as_secs() / 86_400)
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Policy for SpendLimit {
+	type Error = PolicyViolation;
+
+	async fn ensure_can_send(&self, tx: Transaction) -> Result<Transaction, Self::Error> {
+		for (token, amount) in self.amounts(&tx)? {
+			let remaining = self.limits[&token].saturating_sub(self.spent_today(&token));
+			if amount > remaining {
+				return Err(PolicyViolation::SpendLimitExceeded { token, amount, remaining })
+			}
+		}
+		Ok(tx)
+	}
+
+	async fn on_sent(&self, tx: &Transaction) {
+		let Ok(amounts) = self.amounts(tx) else { return };
+		let today = today();
+		let mut spent = self.spent.lock().unwrap();
+		for (token, amount) in amounts {
+			let entry = spent.entry(token).or_insert((today, 0));
+			if entry.0 != today {
+				*entry = (today, 0);
+			}
+			entry.1 = entry.1.saturating_add(amount);
+		}
+	}
+}
+
 /// Middleware used to enforce certain policies for transactions.
+///
+/// # Example
+///
+/// ```
+/// use neo_middleware::policy::{ContractCallAllowList, DestinationAllowList, MaxFee, Policy};
+/// use neo_types::gas::Gas;
+/// use primitive_types::H160;
+///
+/// # fn foo(gas: H160, oracle: H160, treasury: H160) {
+/// // GAS transfers to the treasury or calls of the oracle, for a network fee of at most 0.01 GAS
+/// let payments = ContractCallAllowList::new()
+/// 	.allow_method(gas, "transfer")
+/// 	.and(DestinationAllowList::new([treasury]));
+/// let oracle_calls = ContractCallAllowList::new().allow_contract(oracle);
+/// let policy =
+/// 	MaxFee::new().network_fee(Gas::from_datoms(1_000_000)).and(payments.or(oracle_calls));
+/// # }
+/// ```
 #[derive(Clone, Debug)]
 pub struct PolicyMiddleware<M, P> {
 	pub(crate) inner: M,
@@ -61,6 +471,11 @@ where
 	pub fn new(inner: M, policy: P) -> Self {
 		Self { inner, policy }
 	}
+
+	/// The policy checking the transactions
+	pub fn policy(&self) -> &P {
+		&self.policy
+	}
 }
 
 #[derive(Error, Debug)]
@@ -105,19 +520,27 @@ where
 	}
 
 	/// This ensures the tx complies with the registered policy.
-	/// If so then this simply delegates the transaction to the inner middleware
+	/// If so then this simply delegates the transaction to the inner middleware, and records it
+	/// with the policy once sent, see [`Policy::on_sent`].
 	async fn send_transaction<T: Into<Transaction> + Send + Sync>(
 		&self,
 		tx: T,
 	) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
-		let tx = self
-			.policy
-			.ensure_can_send(tx.into())
-			.await
-			.map_err(PolicyMiddlewareError::PolicyError)?;
-		self.inner
-			.send_transaction(tx)
-			.await
-			.map_err(PolicyMiddlewareError::MiddlewareError)
+		let tx = tx.into();
+		metrics::instrument("policy", "send_transaction", async move {
+			let tx = self
+				.policy
+				.ensure_can_send(tx)
+				.await
+				.map_err(PolicyMiddlewareError::PolicyError)?;
+			let pending = self
+				.inner
+				.send_transaction(tx.clone())
+				.await
+				.map_err(PolicyMiddlewareError::MiddlewareError)?;
+			self.policy.on_sent(&tx).await;
+			Ok(pending)
+		})
+		.await
 	}
 }
@@ -1,19 +1,39 @@
 use async_trait::async_trait;
 use neo_codec::encode::NeoSerializable;
-use neo_crypto::keys::Secp256r1Signature;
 use neo_providers::{
-	core::transaction::transaction::Transaction, Middleware, MiddlewareError, PendingTransaction,
+	core::{
+		account::AccountTrait,
+		transaction::{
+			fee_sponsor::FeeSponsor,
+			signers::{
+				account_signer::AccountSigner,
+				signer::{Signer as TxSigner, SignerTrait},
+			},
+			transaction::Transaction,
+			transaction_error::TransactionError,
+			transaction_template::TransactionTemplate,
+			witness_scope::WitnessScope,
+		},
+	},
+	metrics, Middleware, MiddlewareError, PendingTransaction,
 };
 use neo_signers::Signer;
-use neo_types::{address::Address, block::BlockId, Bytes};
-use rustc_serialize::hex::ToHex;
-use std::convert::TryFrom;
+use neo_types::{address::Address, block::BlockId, encoding, Bytes};
 use thiserror::Error;
 
 #[derive(Clone, Debug)]
 /// Middleware used for locally signing transactions, compatible with any implementer
 /// of the [`Signer`] trait.
 ///
+/// [`Middleware::send_transaction`] accepts unsigned transactions. The account of the signer is
+/// added as first signer, with the scope set by [`SignerMiddleware::with_scope`], unless it
+/// already signs the transaction. Transactions without validity window, i.e. whose
+/// `valid_until_block` is 0, get their validity window and fees from the node. The witness is
+/// then created for the network magic of the node and attached before broadcasting.
+///
+/// With a [`FeeSponsor`], see [`SignerMiddleware::with_fee_sponsor`], the sponsor is made the
+/// first signer and pays the fees, and its witness is attached along with the one of the signer.
+///
 /// # Example
 ///
 /// ```no_run
@@ -62,6 +82,8 @@ pub struct SignerMiddleware<M, S> {
 	pub(crate) inner: M,
 	pub(crate) signer: S,
 	pub(crate) address: Address,
+	pub(crate) scope: WitnessScope,
+	pub(crate) fee_sponsor: Option<FeeSponsor>,
 }
 
 #[derive(Error, Debug)]
@@ -90,6 +112,17 @@ pub enum SignerMiddlewareError<M: Middleware, S: Signer> {
 	/// Thrown if the signer's network_magic is different than the network_magic of the transaction
 	#[error("specified network_magic is different than the signer's network_magic")]
 	DifferentChainID,
+	/// Thrown if the transaction could not be filled
+	#[error(transparent)]
+	TransactionError(#[from] TransactionError),
+	/// Thrown if the witnesses of the other signers are not attached
+	#[error("the transaction has {witnesses} witnesses for {signers} signers besides the signer")]
+	MissingWitnesses {
+		/// Number of attached witnesses
+		witnesses: usize,
+		/// Number of signers besides the signer
+		signers: usize,
+	},
 }
 
 impl<M: Middleware, S: Signer> MiddlewareError for SignerMiddlewareError<M, S> {
@@ -124,42 +157,87 @@ where
 	/// [`Signer`] neo_signers::Signer
 	pub fn new(inner: M, signer: S) -> Self {
 		let address = signer.address();
-		SignerMiddleware { inner, signer, address }
+		SignerMiddleware {
+			inner,
+			signer,
+			address,
+			scope: WitnessScope::CalledByEntry,
+			fee_sponsor: None,
+		}
+	}
+
+	/// Sets the scope of the signer when it is added to a transaction, `CalledByEntry` by
+	/// default.
+	///
+	/// Custom scopes are only valid with the allowed contracts, groups or rules they refer to, so
+	/// add the signer to the transaction yourself for them.
+	#[must_use]
+	pub fn with_scope(mut self, scope: WitnessScope) -> Self {
+		self.scope = scope;
+		self
+	}
+
+	/// The scope of the signer when it is added to a transaction
+	pub fn scope(&self) -> WitnessScope {
+		self.scope
 	}
 
-	/// Signs and returns the RLP encoding of the signed transaction.
-	/// If the transaction does not have a network magic set, it sets it to the signer's network magic.
-	/// Returns an error if the transaction's existing network magic does not match the signer's chain
-	/// id.
-	// async fn sign_transaction(
-	// 	&self,
-	// 	mut tx: Transaction,
-	// ) -> Result<Bytes, SignerMiddlewareError<M, S>> {
-	// 	// compare network_magic and use signer's network_magic if the tranasaction's network_magic is None,
-	// 	// return an error if they are not consistent
-	// 	let network_magic = self.signer.network_magic();
-	// 	match tx.network_magic() {
-	// 		Some(id) if id != network_magic => return Err(SignerMiddlewareError::DifferentChainID),
-	// 		None => {
-	// 			tx.set_network_magic(network_magic);
-	// 		},
-	// 		_ => {},
-	// 	}
-	//
-	// 	let witness = self
-	// 		.signer
-	// 		.get_witness(&tx)
-	// 		.await
-	// 		.map_err(SignerMiddlewareError::SignerError)?;
-	//
-	// 	// Return the raw encoded signed transaction
-	// 	let witness = Witness::create(tx.get_hash_data());
-	// 	Ok(tx.add_witness(&signature))
-	// }
+	/// Lets `sponsor` pay the fees of the transactions sent through this middleware.
+	///
+	/// The sponsor is made the first signer, with the `None` scope unless it already signs the
+	/// transaction, and its witness is created by the sponsor, locally or remotely, when the
+	/// transaction is signed.
+	#[must_use]
+	pub fn with_fee_sponsor(mut self, sponsor: FeeSponsor) -> Self {
+		self.fee_sponsor = Some(sponsor);
+		self
+	}
+
+	/// The sponsor paying the fees of the transactions, if any
+	pub fn fee_sponsor(&self) -> Option<&FeeSponsor> {
+		self.fee_sponsor.as_ref()
+	}
+
+	/// Fills `tx`, see [`Middleware::fill_transaction`], and attaches the witness of the signer.
+	///
+	/// The witness is inserted at the position of the signer among the signers of `tx`, so the
+	/// witnesses of the other signers must already be attached in the order of their signers.
+	/// The witness of the fee sponsor is created as well unless it is already attached.
+	pub async fn sign_transaction(
+		&self,
+		mut tx: Transaction,
+	) -> Result<Transaction, SignerMiddlewareError<M, S>> {
+		self.fill_transaction(&mut tx).await?;
+		let hash = self.signer.account().get_script_hash();
+		if let Some(sponsor) = &self.fee_sponsor {
+			// the sponsor and the signer are the only signers without witness
+			if sponsor.script_hash() != hash && tx.witnesses.len() + 2 == tx.signers.len() {
+				let network_magic = tx.network_magic().unwrap_or_default();
+				let witness = sponsor.witness(&tx, network_magic).await?;
+				tx.witnesses.insert(0, witness);
+			}
+		}
+		let index = tx
+			.signers
+			.iter()
+			.position(|signer| *signer.get_signer_hash() == hash)
+			.ok_or(SignerMiddlewareError::WrongSigner)?;
+		if tx.witnesses.len() + 1 != tx.signers.len() {
+			return Err(SignerMiddlewareError::MissingWitnesses {
+				witnesses: tx.witnesses.len(),
+				signers: tx.signers.len() - 1,
+			})
+		}
+
+		let witness =
+			self.signer.get_witness(&tx).await.map_err(SignerMiddlewareError::SignerError)?;
+		tx.witnesses.insert(index, witness);
+		Ok(tx)
+	}
 
 	/// Returns the client's address
 	pub fn address(&self) -> Address {
-		self.address.clone()
+		self.address
 	}
 
 	/// Returns a reference to the client's signer
@@ -197,11 +275,18 @@ where
 			.await
 			.map_err(|e| SignerMiddlewareError::MiddlewareError(e))?;
 		let signer = signer.with_network_magic(network_magic);
-		Ok(SignerMiddleware { inner, signer, address })
+		Ok(SignerMiddleware {
+			inner,
+			signer,
+			address,
+			scope: WitnessScope::CalledByEntry,
+			fee_sponsor: None,
+		})
 	}
 }
 
-#[async_trait]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl<M, S> Middleware for SignerMiddleware<M, S>
 where
 	M: Middleware,
@@ -217,45 +302,62 @@ where
 
 	/// Returns the client's address
 	fn default_sender(&self) -> Option<Address> {
-		Some(self.address.clone())
+		Some(self.address)
 	}
 
-	/// Helper for filling a transaction's nonce using the wallet
+	/// Adds the signer to `tx` unless it already signs it, and the fee sponsor in front of the
+	/// signers if any. Then sets the network magic of the node and, if `tx` has no validity
+	/// window, its validity window and fees.
 	async fn fill_transaction(&self, tx: &mut Transaction) -> Result<(), Self::Error> {
-		// get the signer's network_magic if the transaction does not set it
-		let network_magic = self.signer.network_magic();
+		let account = self.signer.account();
+		let hash = account.get_script_hash();
+		if !tx.signers.iter().any(|signer| *signer.get_signer_hash() == hash) {
+			let mut signer = AccountSigner::called_by_entry(account)?;
+			signer.set_scopes(vec![self.scope]);
+			// the first signer pays the fees
+			tx.signers.insert(0, TxSigner::from(signer));
+		}
+		if let Some(sponsor) = &self.fee_sponsor {
+			sponsor.apply(&mut tx.signers)?;
+		}
+
 		if tx.network_magic().is_none() {
+			let network_magic = self
+				.inner
+				.get_network_magic()
+				.await
+				.map_err(SignerMiddlewareError::MiddlewareError)?;
 			tx.set_network_magic(network_magic);
 		}
 
-		tx.nonce = 0;
-		self.inner()
-			.fill_transaction(tx)
-			.await
-			.map_err(SignerMiddlewareError::MiddlewareError)?;
+		if tx.valid_until_block == 0 {
+			let template =
+				TransactionTemplate::new(tx.script.clone(), &tx.signers, tx.nonce as u32)?
+					.with_attributes(tx.attributes.clone());
+			let network_magic = tx.network_magic();
+			let witnesses = std::mem::take(&mut tx.witnesses);
+			*tx = template.instantiate_with(&self.inner).await?;
+			tx.network_magic = network_magic;
+			tx.witnesses = witnesses;
+		}
 		Ok(())
 	}
 
-	/// Signs and broadcasts the transaction.
+	/// Signs and broadcasts the transaction, see [`SignerMiddleware::sign_transaction`].
 	async fn send_transaction<T: Into<Transaction> + Send + Sync>(
 		&self,
 		tx: T,
 	) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
-		let mut tx = tx.into();
-
-		// fill any missing fields
-		self.fill_transaction(&mut tx).await?;
-
-		// if we have a nonce manager set, we should try handling the result in
-		// case there was a nonce mismatch
-		let signed_tx = tx.to_array(); // self.sign_transaction(tx).await?;
-
-		// Submit the raw transaction
-		self.inner
-			.send_raw_transaction(signed_tx.to_hex())
-			.await
-			.map(|tx| PendingTransaction::new(tx.hash, self.provider()))
-			.map_err(SignerMiddlewareError::MiddlewareError)
+		let tx = tx.into();
+		metrics::instrument("signer", "send_transaction", async move {
+			let tx = self.sign_transaction(tx).await?;
+			self.inner
+				.send_raw_transaction(encoding::base64_encode(tx.to_array()))
+				.await
+				.map(|sent| PendingTransaction::new(sent.hash, self.provider()))
+				.map_err(SignerMiddlewareError::MiddlewareError)
+		})
+		.await
 	}
 
 	async fn call(&self, tx: &Transaction, block: Option<BlockId>) -> Result<Bytes, Self::Error> {
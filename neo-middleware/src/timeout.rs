@@ -0,0 +1,188 @@
+use async_trait::async_trait;
+use neo_providers::{
+	core::{
+		responses::{
+			neo_application_log::ApplicationLog, neo_balances::Nep17Balances, neo_block::NeoBlock,
+			neo_send_raw_transaction::RawTransaction, neo_transaction_result::TransactionResult,
+		},
+		transaction::{signers::signer::Signer, transaction::Transaction},
+	},
+	Middleware, MiddlewareError, PendingTransaction, ProviderError,
+};
+use neo_types::{
+	block::BlockId, contract_parameter::ContractParameter, invocation_result::InvocationResult,
+	Bytes,
+};
+use primitive_types::{H160, H256};
+use std::{future::Future, time::Duration};
+use thiserror::Error;
+use tracing::warn;
+
+/// Middleware cancelling logical operations, like sending a transaction, that do not complete
+/// within a deadline.
+///
+/// An operation that times out is dropped, cancelling its pending requests, and fails with
+/// [`ProviderError::Timeout`], accessible through
+/// [`MiddlewareError::as_provider_error`]. Use
+/// [`Provider::with_timeout`](neo_providers::Provider::with_timeout) to bound every single RPC
+/// request instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_middleware::TimeoutMiddleware;
+/// use neo_providers::{Http, Middleware, MiddlewareError, Provider, ProviderError};
+/// use std::{convert::TryFrom, time::Duration};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let provider = TimeoutMiddleware::new(provider, Duration::from_secs(5));
+///
+/// match provider.get_block_by_index(1_000, true).await {
+/// 	Err(err) if matches!(err.as_provider_error(), Some(ProviderError::Timeout(_))) => {
+/// 		println!("node too slow")
+/// 	},
+/// 	result => println!("{:?}", result?),
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TimeoutMiddleware<M> {
+	inner: M,
+	timeout: Duration,
+}
+
+impl<M: Middleware> TimeoutMiddleware<M> {
+	/// Cancels the operations sent through `inner` after `timeout`.
+	pub fn new(inner: M, timeout: Duration) -> Self {
+		Self { inner, timeout }
+	}
+
+	/// The deadline of every operation
+	pub fn timeout(&self) -> Duration {
+		self.timeout
+	}
+
+	/// Runs `operation`, failing with [`ProviderError::Timeout`] once the deadline passed.
+	async fn timed<T, F>(
+		&self,
+		name: &'static str,
+		operation: F,
+	) -> Result<T, TimeoutMiddlewareError<M>>
+	where
+		F: Future<Output = Result<T, M::Error>>,
+	{
+		match neo_providers::timeout(self.timeout, operation).await {
+			Some(result) => result.map_err(TimeoutMiddlewareError::MiddlewareError),
+			None => {
+				warn!(operation = name, timeout = ?self.timeout, "operation timed out");
+				Err(TimeoutMiddlewareError::from_provider_err(ProviderError::Timeout(self.timeout)))
+			},
+		}
+	}
+}
+
+#[derive(Error, Debug)]
+/// Error thrown when the client interacts with the timeout middleware.
+pub enum TimeoutMiddlewareError<M: Middleware> {
+	/// Thrown when an internal middleware errors, or with [`ProviderError::Timeout`] when an
+	/// operation timed out
+	#[error(transparent)]
+	MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> TimeoutMiddlewareError<M> {
+	/// Returns `true` if the operation, or one of its requests, timed out
+	pub fn is_timeout(&self) -> bool {
+		matches!(self.as_provider_error(), Some(ProviderError::Timeout(_)))
+	}
+}
+
+impl<M: Middleware> MiddlewareError for TimeoutMiddlewareError<M> {
+	type Inner = M::Error;
+
+	fn from_err(src: M::Error) -> Self {
+		TimeoutMiddlewareError::MiddlewareError(src)
+	}
+
+	fn as_inner(&self) -> Option<&Self::Inner> {
+		match self {
+			TimeoutMiddlewareError::MiddlewareError(e) => Some(e),
+		}
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<M> Middleware for TimeoutMiddleware<M>
+where
+	M: Middleware,
+{
+	type Error = TimeoutMiddlewareError<M>;
+	type Provider = M::Provider;
+	type Inner = M;
+
+	fn inner(&self) -> &M {
+		&self.inner
+	}
+
+	async fn fill_transaction(&self, tx: &mut Transaction) -> Result<(), Self::Error> {
+		self.timed("fill_transaction", self.inner.fill_transaction(tx)).await
+	}
+
+	async fn send_transaction<T: Into<Transaction> + Send + Sync>(
+		&self,
+		tx: T,
+	) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+		self.timed("send_transaction", self.inner.send_transaction(tx)).await
+	}
+
+	async fn send_raw_transaction(&self, hex: String) -> Result<RawTransaction, Self::Error> {
+		self.timed("send_raw_transaction", self.inner.send_raw_transaction(hex)).await
+	}
+
+	async fn call(&self, tx: &Transaction, block: Option<BlockId>) -> Result<Bytes, Self::Error> {
+		self.timed("call", self.inner.call(tx, block)).await
+	}
+
+	async fn invoke_function(
+		&self,
+		contract_hash: &H160,
+		method: String,
+		params: Vec<ContractParameter>,
+		signers: Option<Vec<Signer>>,
+	) -> Result<InvocationResult, Self::Error> {
+		let invoke = self.inner.invoke_function(contract_hash, method, params, signers);
+		self.timed("invoke_function", invoke).await
+	}
+
+	async fn invoke_script(
+		&self,
+		hex: String,
+		signers: Vec<Signer>,
+	) -> Result<InvocationResult, Self::Error> {
+		self.timed("invoke_script", self.inner.invoke_script(hex, signers)).await
+	}
+
+	async fn get_block(&self, block_hash: H256, full_tx: bool) -> Result<NeoBlock, Self::Error> {
+		self.timed("get_block", self.inner.get_block(block_hash, full_tx)).await
+	}
+
+	async fn get_block_by_index(&self, index: u32, full_tx: bool) -> Result<NeoBlock, Self::Error> {
+		self.timed("get_block_by_index", self.inner.get_block_by_index(index, full_tx))
+			.await
+	}
+
+	async fn get_transaction(&self, hash: H256) -> Result<Option<TransactionResult>, Self::Error> {
+		self.timed("get_transaction", self.inner.get_transaction(hash)).await
+	}
+
+	async fn get_application_log(&self, tx_hash: H256) -> Result<ApplicationLog, Self::Error> {
+		self.timed("get_application_log", self.inner.get_application_log(tx_hash)).await
+	}
+
+	async fn get_nep17_balances(&self, script_hash: H160) -> Result<Nep17Balances, Self::Error> {
+		self.timed("get_nep17_balances", self.inner.get_nep17_balances(script_hash))
+			.await
+	}
+}
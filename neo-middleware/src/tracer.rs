@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use instant::Instant;
+use neo_providers::{
+	core::{
+		responses::{
+			neo_address::NeoAddress, neo_application_log::ApplicationLog,
+			neo_balances::Nep17Balances, neo_block::NeoBlock,
+			neo_send_raw_transaction::RawTransaction, neo_transaction_result::TransactionResult,
+		},
+		transaction::{signers::signer::Signer, transaction::Transaction},
+	},
+	CorrelationId, Middleware, MiddlewareError, PendingTransaction,
+};
+use neo_types::{
+	block::BlockId, contract_parameter::ContractParameter, invocation_result::InvocationResult,
+	Bytes,
+};
+use primitive_types::{H160, H256};
+use std::future::Future;
+use thiserror::Error;
+use tracing::{debug, debug_span, warn};
+use tracing_futures::Instrument;
+
+/// Middleware tracing logical operations, like sending a transaction, rather than single RPC
+/// requests.
+///
+/// Every operation runs in a `neo` span with the name of the `operation` and a
+/// `correlation_id`. The id is taken from the enclosing [`CorrelationId::scope`], or generated
+/// if there is none, and propagated to every layer below, so the `rpc` spans of the requests
+/// sent by the [`Provider`](neo_providers::Provider) carry the same id. Secret parameters, like
+/// the key passed to `importprivkey` or the password passed to `openwallet`, are redacted from
+/// the logged payloads, see [`redact`](neo_providers::redact).
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_middleware::TracingMiddleware;
+/// use neo_providers::{CorrelationId, Http, Middleware, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let provider = TracingMiddleware::new(provider);
+///
+/// // both requests are logged with the correlation id `job-7`
+/// CorrelationId::new("job-7")
+/// 	.scope(async {
+/// 		let hash = provider.get_block_hash(1_000).await?;
+/// 		provider.get_block(hash, true).await
+/// 	})
+/// 	.await?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TracingMiddleware<M> {
+	inner: M,
+}
+
+impl<M: Middleware> TracingMiddleware<M> {
+	/// Traces the operations sent through `inner`.
+	pub fn new(inner: M) -> Self {
+		Self { inner }
+	}
+
+	/// Runs `operation` in its span, with the current correlation id or a new one.
+	async fn traced<T, F>(
+		&self,
+		name: &'static str,
+		operation: F,
+	) -> Result<T, TracingMiddlewareError<M>>
+	where
+		F: Future<Output = Result<T, M::Error>>,
+	{
+		let id = CorrelationId::current_or_generate();
+		let span = debug_span!("neo", operation = name, correlation_id = %id);
+		let start = Instant::now();
+		let result = id.scope(operation).instrument(span.clone()).await;
+
+		let _enter = span.enter();
+		match &result {
+			Ok(_) => debug!(elapsed = ?start.elapsed(), "operation succeeded"),
+			Err(err) => warn!(elapsed = ?start.elapsed(), err = %err, "operation failed"),
+		}
+		result.map_err(TracingMiddlewareError::MiddlewareError)
+	}
+}
+
+#[derive(Error, Debug)]
+/// Error thrown when the client interacts with the tracing middleware.
+pub enum TracingMiddlewareError<M: Middleware> {
+	/// Thrown when an internal middleware errors
+	#[error(transparent)]
+	MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> MiddlewareError for TracingMiddlewareError<M> {
+	type Inner = M::Error;
+
+	fn from_err(src: M::Error) -> Self {
+		TracingMiddlewareError::MiddlewareError(src)
+	}
+
+	fn as_inner(&self) -> Option<&Self::Inner> {
+		match self {
+			TracingMiddlewareError::MiddlewareError(e) => Some(e),
+		}
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<M> Middleware for TracingMiddleware<M>
+where
+	M: Middleware,
+{
+	type Error = TracingMiddlewareError<M>;
+	type Provider = M::Provider;
+	type Inner = M;
+
+	fn inner(&self) -> &M {
+		&self.inner
+	}
+
+	async fn fill_transaction(&self, tx: &mut Transaction) -> Result<(), Self::Error> {
+		self.traced("fill_transaction", self.inner.fill_transaction(tx)).await
+	}
+
+	async fn send_transaction<T: Into<Transaction> + Send + Sync>(
+		&self,
+		tx: T,
+	) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+		self.traced("send_transaction", self.inner.send_transaction(tx)).await
+	}
+
+	async fn send_raw_transaction(&self, hex: String) -> Result<RawTransaction, Self::Error> {
+		self.traced("send_raw_transaction", self.inner.send_raw_transaction(hex)).await
+	}
+
+	async fn call(&self, tx: &Transaction, block: Option<BlockId>) -> Result<Bytes, Self::Error> {
+		self.traced("call", self.inner.call(tx, block)).await
+	}
+
+	async fn invoke_function(
+		&self,
+		contract_hash: &H160,
+		method: String,
+		params: Vec<ContractParameter>,
+		signers: Option<Vec<Signer>>,
+	) -> Result<InvocationResult, Self::Error> {
+		let invoke = self.inner.invoke_function(contract_hash, method, params, signers);
+		self.traced("invoke_function", invoke).await
+	}
+
+	async fn invoke_script(
+		&self,
+		hex: String,
+		signers: Vec<Signer>,
+	) -> Result<InvocationResult, Self::Error> {
+		self.traced("invoke_script", self.inner.invoke_script(hex, signers)).await
+	}
+
+	async fn get_block(&self, block_hash: H256, full_tx: bool) -> Result<NeoBlock, Self::Error> {
+		self.traced("get_block", self.inner.get_block(block_hash, full_tx)).await
+	}
+
+	async fn get_block_by_index(&self, index: u32, full_tx: bool) -> Result<NeoBlock, Self::Error> {
+		self.traced("get_block_by_index", self.inner.get_block_by_index(index, full_tx))
+			.await
+	}
+
+	async fn get_transaction(&self, hash: H256) -> Result<Option<TransactionResult>, Self::Error> {
+		self.traced("get_transaction", self.inner.get_transaction(hash)).await
+	}
+
+	async fn get_application_log(&self, tx_hash: H256) -> Result<ApplicationLog, Self::Error> {
+		self.traced("get_application_log", self.inner.get_application_log(tx_hash))
+			.await
+	}
+
+	async fn get_nep17_balances(&self, script_hash: H160) -> Result<Nep17Balances, Self::Error> {
+		self.traced("get_nep17_balances", self.inner.get_nep17_balances(script_hash))
+			.await
+	}
+
+	async fn open_wallet(&self, path: String, password: String) -> Result<bool, Self::Error> {
+		self.traced("open_wallet", self.inner.open_wallet(path, password)).await
+	}
+
+	async fn import_private_key(&self, wif: String) -> Result<NeoAddress, Self::Error> {
+		self.traced("import_private_key", self.inner.import_private_key(wif)).await
+	}
+
+	async fn dump_private_key(&self, script_hash: H160) -> Result<String, Self::Error> {
+		self.traced("dump_private_key", self.inner.dump_private_key(script_hash)).await
+	}
+}
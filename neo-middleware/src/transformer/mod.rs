@@ -1,7 +1,9 @@
 use std::string::ParseError;
 mod middleware;
 pub use middleware::TransformerMiddleware;
+mod proxy;
 use neo_providers::core::transaction::transaction::Transaction;
+pub use proxy::ContractProxyTransformer;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,11 +14,17 @@ pub enum TransformerError {
 
 	#[error(transparent)]
 	AbiParseError(#[from] ParseError),
+
+	#[error("The script cannot be transformed: {0}")]
+	InvalidScript(String),
+
+	#[error("The signers cannot be transformed: {0}")]
+	InvalidSigner(String),
 }
 
-/// `Transformer` is a trait to be implemented by a proxy wallet, eg. [`DsProxy`], that intends to
-/// intercept a transaction request and transform it into one that is instead sent via the proxy
-/// contract.
+/// `Transformer` is a trait to be implemented by a proxy wallet, eg. [`ContractProxyTransformer`],
+/// that intends to intercept a transaction request and transform it into one that is instead sent
+/// via the proxy contract.
 pub trait Transformer: Send + Sync + std::fmt::Debug {
 	/// Transforms a [`transaction request`] into one that can be broadcasted and execute via the
 	/// proxy contract.
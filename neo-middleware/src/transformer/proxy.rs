@@ -0,0 +1,206 @@
+use super::{Transformer, TransformerError};
+use neo_providers::core::{
+	error::BuilderError,
+	script::{
+		interop_service::InteropService,
+		script_builder::ScriptBuilder,
+		script_reader::{ContractCall, ScriptReader, ScriptValue},
+	},
+	transaction::{
+		call_flags::CallFlags,
+		signers::{
+			account_signer::AccountSigner,
+			signer::{Signer, SignerTrait},
+		},
+		transaction::Transaction,
+		transaction_attribute::TransactionAttribute,
+		witness_scope::WitnessScope,
+	},
+};
+use neo_types::{gas::Gas, op_code::OpCode, script_hash::ScriptHashExt, Bytes};
+use num_bigint::BigInt;
+use primitive_types::H160;
+
+/// A [`Transformer`] rewriting the contract calls of a transaction into calls through a relay
+/// contract, for meta-transaction style relays.
+///
+/// Every call of `method` of `target` with `args` becomes a call of the entry method of the
+/// proxy, `execute` by default, taking the target, the method and the arguments:
+/// `execute(target: Hash160, method: String, args: Array)`. The proxy must call the target with
+/// the arguments itself.
+///
+/// Since the targets are then called by the proxy instead of the entry script, signers with the
+/// `CalledByEntry` scope are also allowed in the targets. A relayer may be set to pay the fees,
+/// with a witness only valid for that. The fees and validity window of the transaction are reset
+/// so that they are calculated for the new script.
+///
+/// # Example
+///
+/// ```
+/// use neo_middleware::transformer::{ContractProxyTransformer, Transformer};
+/// use neo_providers::core::transaction::transaction::Transaction;
+/// use primitive_types::H160;
+/// # use std::error::Error;
+///
+/// # fn foo(relay: H160, relayer: H160, tx: &mut Transaction) -> Result<(), Box<dyn Error>> {
+/// let transformer = ContractProxyTransformer::new(relay).method("forward").relayer(relayer);
+/// transformer.transform(tx)?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContractProxyTransformer {
+	proxy: H160,
+	method: String,
+	relayer: Option<H160>,
+	attributes: Vec<TransactionAttribute>,
+}
+
+impl ContractProxyTransformer {
+	/// The entry method of the proxy unless set with [`ContractProxyTransformer::method`]
+	pub const DEFAULT_METHOD: &'static str = "execute";
+
+	/// Sends the calls through the contract `proxy`.
+	pub fn new(proxy: H160) -> Self {
+		Self { proxy, method: Self::DEFAULT_METHOD.to_string(), relayer: None, attributes: vec![] }
+	}
+
+	/// Sets the entry method of the proxy
+	#[must_use]
+	pub fn method(mut self, method: impl Into<String>) -> Self {
+		self.method = method.into();
+		self
+	}
+
+	/// Adds `relayer` as first signer, paying the fees, with the `None` scope.
+	#[must_use]
+	pub fn relayer(mut self, relayer: H160) -> Self {
+		self.relayer = Some(relayer);
+		self
+	}
+
+	/// Adds `attribute` to the transformed transactions, e.g. one required by the relay.
+	#[must_use]
+	pub fn attribute(mut self, attribute: TransactionAttribute) -> Self {
+		self.attributes.push(attribute);
+		self
+	}
+
+	/// The script hash of the proxy
+	pub fn proxy(&self) -> H160 {
+		self.proxy
+	}
+
+	/// The script calling the proxy once per call of `calls`
+	fn proxied_script(&self, calls: &[ContractCall]) -> Result<Bytes, BuilderError> {
+		let mut builder = ScriptBuilder::new();
+		for call in calls {
+			let params = [
+				ScriptValue::Bytes(call.contract.to_le_vec()),
+				ScriptValue::Bytes(call.method.as_bytes().to_vec()),
+				ScriptValue::Array(call.args.clone()),
+			];
+			push_array(&mut builder, &params)?;
+			builder
+				.push_integer(BigInt::from(CallFlags::All.value()))?
+				.push_data(self.method.as_bytes().to_vec())?
+				.push_data(self.proxy.to_le_vec())?
+				.sys_call(InteropService::SystemContractCall);
+		}
+		Ok(builder.to_bytes())
+	}
+}
+
+/// Pushes `items` as an array whose first item is `items[0]`.
+fn push_array(builder: &mut ScriptBuilder, items: &[ScriptValue]) -> Result<(), BuilderError> {
+	if items.is_empty() {
+		builder.op_code(&[OpCode::NewArray0]);
+		return Ok(())
+	}
+	// `Pack` takes the top of the stack as first item
+	for item in items.iter().rev() {
+		match item {
+			ScriptValue::Null => {
+				builder.op_code(&[OpCode::PushNull]);
+			},
+			ScriptValue::Boolean(value) => {
+				builder.push_bool(*value);
+			},
+			ScriptValue::Integer(value) => {
+				builder.push_integer(BigInt::from(*value))?;
+			},
+			ScriptValue::Bytes(value) => {
+				builder.push_data(value.clone())?;
+			},
+			ScriptValue::Array(items) => push_array(builder, items)?,
+		}
+	}
+	builder.push_integer(BigInt::from(items.len()))?.op_code(&[OpCode::Pack]);
+	Ok(())
+}
+
+/// Also allows a `CalledByEntry` witness in the contracts `targets`.
+fn allow_targets(signer: &mut impl SignerTrait, targets: &[H160]) -> Result<(), BuilderError> {
+	if !signer.get_scopes().contains(&WitnessScope::CalledByEntry) {
+		return Ok(())
+	}
+	let missing: Vec<H160> = targets
+		.iter()
+		.filter(|target| !signer.get_allowed_contracts().contains(target))
+		.copied()
+		.collect();
+	if missing.is_empty() {
+		return Ok(())
+	}
+	signer.set_allowed_contracts(missing)
+}
+
+impl Transformer for ContractProxyTransformer {
+	fn transform(&self, tx: &mut Transaction) -> Result<(), TransformerError> {
+		let calls = ScriptReader::contract_calls(&tx.script)
+			.map_err(|e| TransformerError::InvalidScript(e.to_string()))?;
+		if calls.is_empty() {
+			return Err(TransformerError::InvalidScript("the script calls no contract".to_string()))
+		}
+		// already sent through the proxy
+		if calls.iter().all(|call| call.contract == self.proxy) {
+			return Ok(())
+		}
+		tx.script = self
+			.proxied_script(&calls)
+			.map_err(|e| TransformerError::InvalidScript(e.to_string()))?;
+
+		let mut targets: Vec<H160> = Vec::new();
+		for call in &calls {
+			if !targets.contains(&call.contract) {
+				targets.push(call.contract);
+			}
+		}
+		for signer in tx.signers.iter_mut() {
+			let allowed = match signer {
+				Signer::Account(signer) => allow_targets(signer, &targets),
+				Signer::Contract(signer) => allow_targets(signer, &targets),
+				Signer::Transaction(signer) => allow_targets(signer, &targets),
+			};
+			allowed.map_err(|e| TransformerError::InvalidSigner(e.to_string()))?;
+		}
+
+		if let Some(relayer) = self.relayer {
+			let relayer = match tx.signers.iter().position(|s| *s.get_signer_hash() == relayer) {
+				Some(index) => tx.signers.remove(index),
+				None => AccountSigner::none_hash160(relayer)
+					.map(Signer::from)
+					.map_err(|e| TransformerError::InvalidSigner(e.to_string()))?,
+			};
+			// the first signer pays the fees
+			tx.signers.insert(0, relayer);
+		}
+		tx.attributes.extend(self.attributes.iter().cloned());
+
+		// the fees, validity window and witnesses were for the old script
+		tx.sys_fee = Gas::ZERO;
+		tx.net_fee = Gas::ZERO;
+		tx.valid_until_block = 0;
+		tx.witnesses.clear();
+		Ok(())
+	}
+}
@@ -0,0 +1,66 @@
+use neo_codec::encode::NeoSerializable;
+use neo_middleware::audit::{
+	AuditMiddleware, AuditMiddlewareError, AuditResult, AuditSink, JsonlSink, MemorySink,
+};
+use neo_providers::{
+	core::{
+		responses::neo_send_raw_transaction::RawTransaction, transaction::transaction::Transaction,
+	},
+	Middleware, Provider,
+};
+use neo_types::encoding;
+
+fn signed_tx(nonce: i32) -> (Transaction, String) {
+	let tx = Transaction { nonce, script: vec![0x40], valid_until_block: 10, ..Default::default() };
+	let raw = encoding::base64_encode(tx.to_array());
+	(tx, raw)
+}
+
+#[tokio::test]
+async fn records_and_refuses_replays() {
+	let (provider, mock) = Provider::mocked();
+	let provider = AuditMiddleware::new(provider, MemorySink::new());
+	let (tx, raw) = signed_tx(1);
+
+	mock.push(RawTransaction { hash: tx.hash() }).unwrap();
+	provider.send_raw_transaction(raw.clone()).await.unwrap();
+	let records = provider.sink().records();
+	assert_eq!(records.len(), 2);
+	assert_eq!(records[0].result, AuditResult::Pending);
+	assert_eq!(records[1].result, AuditResult::Sent);
+	assert_eq!(records[1].hash, tx.hash());
+	assert_eq!(records[1].transaction, raw);
+
+	assert!(matches!(
+		provider.send_raw_transaction(raw).await,
+		Err(AuditMiddlewareError::AlreadySent(hash)) if hash == tx.hash()
+	));
+}
+
+#[tokio::test]
+async fn jsonl_sink_survives_restarts() {
+	let dir = tempfile::tempdir().unwrap();
+	let path = dir.path().join("audit.jsonl");
+	let (tx, raw) = signed_tx(2);
+
+	let (provider, mock) = Provider::mocked();
+	let provider = AuditMiddleware::new(provider, JsonlSink::open(&path).unwrap());
+	// the failed broadcast can be retried
+	assert!(provider.send_raw_transaction(raw.clone()).await.is_err());
+	assert!(matches!(
+		provider.sink().status(&tx.hash()).unwrap(),
+		Some(AuditResult::Failed { .. })
+	));
+	mock.push(RawTransaction { hash: tx.hash() }).unwrap();
+	provider.send_raw_transaction(raw.clone()).await.unwrap();
+	drop(provider);
+
+	let sink = JsonlSink::open(&path).unwrap();
+	assert_eq!(sink.status(&tx.hash()).unwrap(), Some(AuditResult::Sent));
+	let (provider, _mock) = Provider::mocked();
+	let provider = AuditMiddleware::new(provider, sink);
+	assert!(matches!(
+		provider.send_raw_transaction(raw).await,
+		Err(AuditMiddlewareError::AlreadySent(_))
+	));
+}
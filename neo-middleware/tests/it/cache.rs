@@ -0,0 +1,59 @@
+use neo_middleware::{CacheStore, MemoryStore, MiddlewareBuilder};
+use neo_providers::{
+	core::{
+		responses::neo_transaction_result::TransactionResult, transaction::transaction::Transaction,
+	},
+	Middleware, Provider,
+};
+use primitive_types::{H160, H256};
+
+#[tokio::test]
+async fn serves_immutable_data_from_cache() {
+	let (provider, mock) = Provider::mocked();
+	let provider = provider.with_cache(MemoryStore::default());
+
+	let root = H256::repeat_byte(1);
+	let contract = H160::repeat_byte(2);
+	mock.push("AQ==").unwrap();
+	assert_eq!(provider.get_state(root, contract, "00").await.unwrap(), "AQ==");
+	// the mock has no responses left, so this is answered by the cache
+	assert_eq!(provider.get_state(root, contract, "00").await.unwrap(), "AQ==");
+	assert_eq!(provider.store().len(), 1);
+
+	// a different key is not cached
+	provider.get_state(root, contract, "01").await.unwrap_err();
+}
+
+#[tokio::test]
+async fn caches_only_included_transactions() {
+	let (provider, mock) = Provider::mocked();
+	let provider = provider.with_cache(MemoryStore::default());
+
+	let hash = H256::repeat_byte(1);
+	let pending = TransactionResult::from(&Transaction { hash, ..Default::default() });
+	mock.push(pending.clone()).unwrap();
+	assert_eq!(provider.get_transaction(hash).await.unwrap().unwrap().block_hash, None);
+	assert_eq!(provider.store().len(), 0);
+
+	let included = TransactionResult { block_hash: Some(H256::repeat_byte(2)), ..pending };
+	mock.push(included).unwrap();
+	assert!(provider.get_transaction(hash).await.unwrap().unwrap().block_hash.is_some());
+	// the mock has no responses left, so this is answered by the cache
+	let cached = provider.get_transaction(hash).await.unwrap().unwrap();
+	assert_eq!(cached.block_hash, Some(H256::repeat_byte(2)));
+	assert_eq!(provider.store().len(), 1);
+}
+
+#[test]
+fn memory_store_evicts_least_recently_used() {
+	let store = MemoryStore::new(2);
+	store.insert("a".to_string(), vec![1]);
+	store.insert("b".to_string(), vec![2]);
+	assert_eq!(store.get("a"), Some(vec![1]));
+	store.insert("c".to_string(), vec![3]);
+
+	assert_eq!(store.get("b"), None);
+	assert_eq!(store.get("a"), Some(vec![1]));
+	assert_eq!(store.get("c"), Some(vec![3]));
+	assert_eq!(store.len(), 2);
+}
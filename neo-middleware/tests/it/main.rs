@@ -5,8 +5,14 @@ use neo_providers::{Http, Provider, Ws};
 use neo_signers::{LocalWallet, Signer};
 use std::time::Duration;
 
+mod audit;
+
 mod builder;
 
+mod cache;
+
+mod policy;
+
 mod signer;
 
 mod stack;
@@ -0,0 +1,141 @@
+use neo_middleware::{
+	policy::{
+		ContractCallAllowList, DestinationAllowList, MaxFee, Policy, PolicyMiddleware,
+		PolicyViolation, SpendLimit,
+	},
+	SignerMiddleware,
+};
+use neo_providers::{
+	core::{
+		account::{Account, AccountTrait},
+		responses::neo_send_raw_transaction::RawTransaction,
+		transaction::transaction::Transaction,
+	},
+	Expectation, JsonRpcError, Middleware, Provider,
+};
+use neo_signers::Wallet;
+use neo_types::{gas::Gas, script_hash::ScriptHashExt};
+use primitive_types::H160;
+
+/// A script calling `transfer(from, to, amount, null)` of `token`
+fn transfer(token: H160, to: H160, amount: u8) -> Transaction {
+	let mut script = vec![0x0b, 0x00, amount, 0x0c, 0x14];
+	script.extend(to.to_le_vec());
+	script.extend([0x0c, 0x14]);
+	script.extend(H160::zero().to_le_vec());
+	script.extend([0x14, 0xc0, 0x1f, 0x0c, 0x08]);
+	script.extend(b"transfer");
+	script.extend([0x0c, 0x14]);
+	script.extend(token.to_le_vec());
+	script.extend([0x41, 0x62, 0x7d, 0x5b, 0x52]);
+	Transaction { script, ..Default::default() }
+}
+
+#[tokio::test]
+async fn destination_allow_list() {
+	let (token, treasury, other) =
+		(H160::repeat_byte(1), H160::repeat_byte(2), H160::repeat_byte(3));
+	let policy = DestinationAllowList::new([treasury]);
+
+	assert!(policy.ensure_can_send(transfer(token, treasury, 5)).await.is_ok());
+	assert_eq!(
+		policy.ensure_can_send(transfer(token, other, 5)).await.unwrap_err(),
+		PolicyViolation::DestinationNotAllowed { token, to: other }
+	);
+	let unreadable = Transaction { script: vec![0x22, 0x02, 0x40], ..Default::default() };
+	assert!(matches!(
+		policy.ensure_can_send(unreadable).await,
+		Err(PolicyViolation::UncheckableScript(_))
+	));
+}
+
+#[tokio::test]
+async fn spend_limit_per_token() {
+	let (token, to) = (H160::repeat_byte(1), H160::repeat_byte(2));
+	let policy = SpendLimit::new().limit(token, 100);
+
+	let tx = policy.ensure_can_send(transfer(token, to, 60)).await.unwrap();
+	// only sent transactions count
+	assert_eq!(policy.spent_today(&token), 0);
+	policy.on_sent(&tx).await;
+	assert_eq!(policy.spent_today(&token), 60);
+	assert_eq!(
+		policy.ensure_can_send(transfer(token, to, 50)).await.unwrap_err(),
+		PolicyViolation::SpendLimitExceeded { token, amount: 50, remaining: 40 }
+	);
+	assert_eq!(
+		policy.ensure_can_send(transfer(to, to, 1)).await.unwrap_err(),
+		PolicyViolation::TokenNotAllowed { token: to }
+	);
+	assert_eq!(policy.spent_today(&token), 60);
+}
+
+#[tokio::test]
+async fn combined_policies() {
+	let (token, to, oracle) = (H160::repeat_byte(1), H160::repeat_byte(2), H160::repeat_byte(3));
+	let policy = MaxFee::new().system_fee(Gas::from_datoms(10)).and(
+		ContractCallAllowList::new()
+			.allow_method(token, "transfer")
+			.or(ContractCallAllowList::new().allow_contract(oracle)),
+	);
+
+	assert!(policy.ensure_can_send(transfer(token, to, 1)).await.is_ok());
+	assert!(policy.ensure_can_send(transfer(oracle, to, 1)).await.is_ok());
+	assert_eq!(
+		policy.ensure_can_send(transfer(to, to, 1)).await.unwrap_err(),
+		PolicyViolation::NoneSatisfied(vec![
+			PolicyViolation::CallNotAllowed { contract: to, method: "transfer".to_string() },
+			PolicyViolation::CallNotAllowed { contract: to, method: "transfer".to_string() },
+		])
+	);
+
+	let expensive = Transaction { sys_fee: Gas::from_datoms(11), ..transfer(token, to, 1) };
+	assert_eq!(
+		policy.ensure_can_send(expensive).await.unwrap_err(),
+		PolicyViolation::SystemFeeTooHigh { fee: Gas::from_datoms(11), max: Gas::from_datoms(10) }
+	);
+}
+
+#[tokio::test]
+async fn spend_limit_counts_sent_transactions() {
+	let (token, to) = (H160::repeat_byte(1), H160::repeat_byte(2));
+	let account = Account::create().unwrap();
+	let mut wallet = Wallet::new();
+	wallet.set_default_account(account.get_script_hash());
+	wallet.add_account(account);
+	let (provider, mock) = Provider::mocked();
+	let client = PolicyMiddleware::new(
+		SignerMiddleware::new(provider, wallet),
+		SpendLimit::new()
+			.limit(token, 100)
+			.and(MaxFee::new().system_fee(Gas::from_datoms(10))),
+	);
+	let tx = |amount| {
+		let mut tx = Transaction { valid_until_block: 100, ..transfer(token, to, amount) };
+		tx.set_network_magic(860833102);
+		tx
+	};
+
+	// rejected by the node
+	mock.expect(Expectation::new("sendrawtransaction").returning_error(JsonRpcError {
+		code: -500,
+		message: "Insufficient funds".to_string(),
+		data: None,
+	}));
+	assert!(client.send_transaction(tx(60)).await.is_err());
+	assert_eq!(client.policy().0.spent_today(&token), 0);
+
+	// rejected by the policy checked after the spend limit
+	let expensive = Transaction { sys_fee: Gas::from_datoms(11), ..tx(60) };
+	assert!(client.send_transaction(expensive).await.is_err());
+	assert_eq!(client.policy().0.spent_today(&token), 0);
+
+	mock.expect(
+		Expectation::new("sendrawtransaction")
+			.returning(RawTransaction { hash: Default::default() }),
+	);
+	client.send_transaction(tx(60)).await.unwrap();
+	assert_eq!(client.policy().0.spent_today(&token), 60);
+	assert!(client.send_transaction(tx(50)).await.is_err());
+	mock.assert_expectations_met().unwrap();
+}
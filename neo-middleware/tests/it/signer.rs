@@ -123,3 +123,71 @@ async fn check_tx<P: JsonRpcClient + Clone>(
 		}
 	}
 }
+
+#[tokio::test]
+async fn sign_transaction_adds_signer_and_witness() {
+	use neo_crypto::keys::Secp256r1Signature;
+	use neo_providers::{
+		core::account::{Account, AccountTrait},
+		Provider,
+	};
+	use neo_signers::Wallet;
+
+	let account = Account::create().unwrap();
+	let hash = account.get_script_hash();
+	let public_key = account.key_pair().as_ref().unwrap().public_key();
+	let mut wallet = Wallet::new();
+	wallet.add_account(account);
+	wallet.set_default_account(hash);
+
+	// the validity window and network magic are set, so nothing is requested from the node
+	let (provider, _mock) = Provider::mocked();
+	let client = SignerMiddleware::new(provider, wallet);
+	let mut tx = Transaction { script: vec![0x40], valid_until_block: 100, ..Default::default() };
+	tx.set_network_magic(860833102);
+
+	let tx = client.sign_transaction(tx).await.unwrap();
+	assert_eq!(tx.signers.len(), 1);
+	assert_eq!(*tx.signers[0].get_signer_hash(), hash);
+	assert_eq!(tx.witnesses.len(), 1);
+
+	// the invocation script pushes the signature of the sign data, as CheckSig verifies it
+	let invocation: &[u8] = &tx.witnesses[0].invocation;
+	let signature = Secp256r1Signature::from_bytes(&invocation[2..]).unwrap();
+	public_key.verify(&tx.get_hash_data(860833102), &signature).unwrap();
+}
+
+#[tokio::test]
+async fn sign_transaction_adds_fee_sponsor_first() {
+	use neo_providers::{
+		core::{
+			account::{Account, AccountTrait},
+			transaction::{fee_sponsor::FeeSponsor, witness_scope::WitnessScope},
+		},
+		Provider,
+	};
+	use neo_signers::Wallet;
+
+	let account = Account::create().unwrap();
+	let hash = account.get_script_hash();
+	let mut wallet = Wallet::new();
+	wallet.add_account(account);
+	wallet.set_default_account(hash);
+	let sponsor = FeeSponsor::new(Account::create().unwrap());
+
+	let (provider, _mock) = Provider::mocked();
+	let client = SignerMiddleware::new(provider, wallet).with_fee_sponsor(sponsor.clone());
+	let mut tx = Transaction { script: vec![0x40], valid_until_block: 100, ..Default::default() };
+	tx.set_network_magic(860833102);
+
+	let tx = client.sign_transaction(tx).await.unwrap();
+	assert_eq!(tx.signers.len(), 2);
+	assert_eq!(*tx.signers[0].get_signer_hash(), sponsor.script_hash());
+	assert_eq!(tx.signers[0].get_scopes(), &vec![WitnessScope::None]);
+	assert_eq!(*tx.signers[1].get_signer_hash(), hash);
+	assert_eq!(tx.witnesses.len(), 2);
+	assert_eq!(
+		tx.witnesses[0].verification,
+		sponsor.witness(&tx, 860833102).await.unwrap().verification
+	);
+}
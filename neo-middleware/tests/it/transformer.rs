@@ -120,3 +120,59 @@ async fn ds_proxy_code() {
 	assert_eq!(last_sender, address.into());
 	assert_eq!(last_value, H256::from_low_u64_be(expected_value));
 }
+
+#[test]
+fn contract_proxy_transformer() {
+	use neo_middleware::transformer::{ContractProxyTransformer, Transformer};
+	use neo_providers::core::{
+		script::script_reader::{ScriptReader, ScriptValue},
+		transaction::{
+			signers::{account_signer::AccountSigner, signer::SignerTrait},
+			transaction::Transaction,
+		},
+	};
+	use neo_types::script_hash::ScriptHashExt;
+	use primitive_types::H160;
+
+	let (token, proxy, relayer, sender) =
+		(H160::repeat_byte(1), H160::repeat_byte(2), H160::repeat_byte(3), H160::repeat_byte(4));
+	// token.transfer(sender, relayer, 5, null)
+	let mut script = vec![0x0b, 0x15, 0x0c, 0x14];
+	script.extend(relayer.to_le_vec());
+	script.extend([0x0c, 0x14]);
+	script.extend(sender.to_le_vec());
+	script.extend([0x14, 0xc0, 0x1f, 0x0c, 0x08]);
+	script.extend(b"transfer");
+	script.extend([0x0c, 0x14]);
+	script.extend(token.to_le_vec());
+	script.extend([0x41, 0x62, 0x7d, 0x5b, 0x52]);
+	let signer = AccountSigner::called_by_entry_hash160(sender).unwrap();
+	let mut tx = Transaction {
+		script,
+		signers: vec![signer.into()],
+		valid_until_block: 100,
+		..Default::default()
+	};
+	let original = ScriptReader::contract_calls(&tx.script).unwrap().remove(0);
+
+	ContractProxyTransformer::new(proxy)
+		.relayer(relayer)
+		.transform(&mut tx)
+		.unwrap();
+	let calls = ScriptReader::contract_calls(&tx.script).unwrap();
+	assert_eq!(calls.len(), 1);
+	assert_eq!((calls[0].contract, calls[0].method.as_str()), (proxy, "execute"));
+	assert_eq!(
+		calls[0].args,
+		vec![
+			ScriptValue::Bytes(token.to_le_vec()),
+			ScriptValue::Bytes(b"transfer".to_vec()),
+			ScriptValue::Array(original.args),
+		]
+	);
+
+	assert_eq!(tx.valid_until_block, 0);
+	assert_eq!(*tx.signers[0].get_signer_hash(), relayer);
+	let sender = tx.signers[1].as_account_signer().unwrap();
+	assert_eq!(sender.get_allowed_contracts(), &vec![token]);
+}
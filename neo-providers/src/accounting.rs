@@ -0,0 +1,1117 @@
+//! Fee, transfer and balance accounting for a set of accounts.
+//!
+//! The [`LedgerExporter`] walks a range of blocks and records, for the tracked accounts, every fee
+//! paid, every token transfer and every GAS generation event (GAS minted to the account, e.g. when
+//! NEO is transferred or when committee rewards are distributed). The result is a normalized
+//! [`Ledger`] that can be exported to CSV or JSON for tax and accounting tools.
+//!
+//! Amounts are kept as integers in the smallest unit of the token, so no precision is lost, and
+//! are formatted using the decimals of the token. Every entry has an ID derived from its position
+//! on the chain, so exporting the same blocks twice yields the same IDs and entries can be
+//! reconciled against previous exports.
+//!
+//! The [`BalanceTracker`] is lighter: it reads the NEP-17 and NEP-11 transfers of the accounts
+//! over a range of blocks from the `TokensTracker` plugin of the node and turns them into
+//! balance deltas and running balances, without fetching every block.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use neo_providers::{accounting::LedgerExporter, Http, Provider};
+//! use primitive_types::H160;
+//! use std::convert::TryFrom;
+//!
+//! # async fn foo(account: H160) -> Result<(), Box<dyn std::error::Error>> {
+//! let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+//! let ledger = LedgerExporter::new(&provider, [account]).export(1_000, 2_000).await?;
+//! std::fs::write("ledger.csv", ledger.to_csv())?;
+//! # Ok(()) }
+//! ```
+
+use crate::Middleware;
+use neo_types::{
+	address::Address, deserialize_h256, deserialize_script_hash, deserialize_script_hash_option,
+	encoding, natives, script_hash::ScriptHashExt, serde_value::ValueExtension, serialize_h256,
+	serialize_script_hash, serialize_script_hash_option,
+};
+use num_bigint::{BigInt, Sign};
+use num_traits::Zero;
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::{
+	collections::{HashMap, HashSet},
+	fmt,
+	str::FromStr,
+};
+use thiserror::Error;
+
+/// Decimals of the native GAS token, in which fees are paid
+pub const GAS_DECIMALS: u8 = 8;
+
+/// Errors that can occur while exporting a ledger.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum AccountingError {
+	/// The RPC node returned an error
+	#[error("RPC error: {0}")]
+	Rpc(String),
+	/// The RPC node returned data that could not be interpreted
+	#[error("Invalid response: {0}")]
+	InvalidResponse(String),
+}
+
+/// The kind of a [`LedgerEntry`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerEntryKind {
+	/// Network fee paid by the sender of a transaction
+	NetworkFee,
+	/// System fee paid by the sender of a transaction
+	SystemFee,
+	/// Tokens sent or received
+	Transfer,
+	/// GAS minted to the account
+	GasGeneration,
+}
+
+impl fmt::Display for LedgerEntryKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			LedgerEntryKind::NetworkFee => "network_fee",
+			LedgerEntryKind::SystemFee => "system_fee",
+			LedgerEntryKind::Transfer => "transfer",
+			LedgerEntryKind::GasGeneration => "gas_generation",
+		})
+	}
+}
+
+/// Symbol and decimals of a token.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenInfo {
+	/// Token symbol
+	pub symbol: String,
+	/// Number of decimals of the token
+	pub decimals: u8,
+}
+
+/// A single balance change of a tracked account.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LedgerEntry {
+	/// Stable identifier of the entry, derived from its position on the chain
+	pub id: String,
+	/// Index of the block containing the event
+	pub block_index: u32,
+	/// Timestamp of the block in milliseconds
+	pub block_time: u64,
+	/// Hash of the transaction, or of the block for events raised while persisting a block
+	#[serde(serialize_with = "serialize_h256", deserialize_with = "deserialize_h256")]
+	pub container: H256,
+	/// Kind of the entry
+	pub kind: LedgerEntryKind,
+	/// The tracked account whose balance changed
+	#[serde(
+		serialize_with = "serialize_script_hash",
+		deserialize_with = "deserialize_script_hash"
+	)]
+	pub account: H160,
+	/// The other side of a transfer, `None` for fees, mints and burns
+	#[serde(
+		serialize_with = "serialize_script_hash_option",
+		deserialize_with = "deserialize_script_hash_option"
+	)]
+	pub counterparty: Option<H160>,
+	/// Script hash of the token
+	#[serde(
+		serialize_with = "serialize_script_hash",
+		deserialize_with = "deserialize_script_hash"
+	)]
+	pub token: H160,
+	/// Token symbol and decimals
+	pub token_info: TokenInfo,
+	/// Balance change in the smallest unit of the token, negative when the balance decreases
+	#[serde(serialize_with = "serialize_big_int", deserialize_with = "deserialize_big_int")]
+	pub amount: BigInt,
+}
+
+impl LedgerEntry {
+	/// The amount formatted with the decimals of the token, e.g. `-0.0112` GAS.
+	pub fn formatted_amount(&self) -> String {
+		format_amount(&self.amount, self.token_info.decimals)
+	}
+}
+
+/// The entries exported for a range of blocks, in chain order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ledger {
+	/// The exported entries
+	pub entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+	/// Header line of [`Ledger::to_csv`]
+	pub const CSV_HEADER: &'static str = "id,block_index,block_time,container,kind,account,\
+	                                      counterparty,token,symbol,decimals,amount,raw_amount";
+
+	/// Sum of the balance changes of `account` in `token`, in the smallest unit of the token.
+	pub fn balance_change(&self, account: &H160, token: &H160) -> BigInt {
+		self.entries
+			.iter()
+			.filter(|entry| entry.account == *account && entry.token == *token)
+			.map(|entry| &entry.amount)
+			.sum()
+	}
+
+	/// Exports the ledger as CSV, one line per entry after [`Ledger::CSV_HEADER`].
+	pub fn to_csv(&self) -> String {
+		let mut csv = format!("{}\n", Self::CSV_HEADER);
+		for entry in &self.entries {
+			let fields = [
+				entry.id.clone(),
+				entry.block_index.to_string(),
+				entry.block_time.to_string(),
+				format!("{:#x}", entry.container),
+				entry.kind.to_string(),
+				format!("{:#x}", entry.account),
+				entry.counterparty.map(|hash| format!("{hash:#x}")).unwrap_or_default(),
+				format!("{:#x}", entry.token),
+				entry.token_info.symbol.clone(),
+				entry.token_info.decimals.to_string(),
+				entry.formatted_amount(),
+				entry.amount.to_string(),
+			];
+			let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+			csv.push_str(&line.join(","));
+			csv.push('\n');
+		}
+		csv
+	}
+
+	/// Exports the ledger as a JSON array of entries.
+	pub fn to_json(&self) -> Result<String, serde_json::Error> {
+		serde_json::to_string_pretty(&self.entries)
+	}
+}
+
+/// Collects the [`Ledger`] of a set of accounts from an RPC node.
+///
+/// The node must run the `ApplicationLogs` plugin. Token metadata is fetched once per token and
+/// cached across exports.
+#[derive(Debug)]
+pub struct LedgerExporter<'a, M> {
+	client: &'a M,
+	accounts: HashSet<H160>,
+	tokens: HashMap<H160, TokenInfo>,
+}
+
+impl<'a, M: Middleware> LedgerExporter<'a, M> {
+	/// Creates an exporter tracking `accounts`.
+	pub fn new(client: &'a M, accounts: impl IntoIterator<Item = H160>) -> Self {
+		let gas = natives::GAS_TOKEN.hash;
+		let tokens =
+			HashMap::from([(gas, TokenInfo { symbol: "GAS".to_string(), decimals: GAS_DECIMALS })]);
+		Self { client, accounts: accounts.into_iter().collect(), tokens }
+	}
+
+	/// Exports the entries of the blocks `from..=to`.
+	pub async fn export(&mut self, from: u32, to: u32) -> Result<Ledger, AccountingError> {
+		let mut ledger = Ledger::default();
+		for index in from..=to {
+			let block: Value = self
+				.client
+				.provider()
+				.request("getblock", vec![index.to_value(), 1.to_value()])
+				.await
+				.map_err(rpc_error)?;
+			let mut logs = vec![self.application_log(hash_field(&block, "hash")?).await?];
+			for tx in transactions(&block)? {
+				logs.push(self.application_log(hash_field(tx, "hash")?).await?);
+			}
+
+			for token in self.unknown_tokens(&logs) {
+				let info = self.token_info(token).await?;
+				self.tokens.insert(token, info);
+			}
+			ledger.entries.extend(self.block_entries(&block, &logs)?);
+		}
+		Ok(ledger)
+	}
+
+	async fn application_log(&self, hash: H256) -> Result<Value, AccountingError> {
+		self.client
+			.provider()
+			.request("getapplicationlog", vec![hash.to_value()])
+			.await
+			.map_err(rpc_error)
+	}
+
+	/// The tokens transferred from or to a tracked account whose metadata is not cached yet.
+	fn unknown_tokens(&self, logs: &[Value]) -> HashSet<H160> {
+		logs.iter()
+			.flat_map(transfers)
+			.filter(|transfer| {
+				transfer.from.map_or(false, |from| self.accounts.contains(&from))
+					|| transfer.to.map_or(false, |to| self.accounts.contains(&to))
+			})
+			.map(|transfer| transfer.token)
+			.filter(|token| !self.tokens.contains_key(token))
+			.collect()
+	}
+
+	async fn token_info(&self, token: H160) -> Result<TokenInfo, AccountingError> {
+		token_info(self.client, token).await
+	}
+
+	/// Turns a verbose block and the application logs of the block and of its transactions, in
+	/// that order, into ledger entries. All transferred tokens must be cached.
+	fn block_entries(
+		&self,
+		block: &Value,
+		logs: &[Value],
+	) -> Result<Vec<LedgerEntry>, AccountingError> {
+		let block_index = block["index"]
+			.as_u64()
+			.and_then(|index| u32::try_from(index).ok())
+			.ok_or_else(|| invalid("block has no index"))?;
+		let block_time = block["time"].as_u64().ok_or_else(|| invalid("block has no time"))?;
+		let gas = natives::GAS_TOKEN.hash;
+
+		let mut entries = vec![];
+		let mut entry = |id: String, container, kind, account, counterparty, token, amount| {
+			let token_info = self.tokens[&token].clone();
+			entries.push(LedgerEntry {
+				id,
+				block_index,
+				block_time,
+				container,
+				kind,
+				account,
+				counterparty,
+				token,
+				token_info,
+				amount,
+			});
+		};
+
+		for tx in transactions(block)? {
+			let hash = hash_field(tx, "hash")?;
+			let sender = tx["sender"]
+				.as_str()
+				.and_then(|address| H160::from_address(address).ok())
+				.ok_or_else(|| invalid(format!("transaction {hash:#x} has no sender")))?;
+			if !self.accounts.contains(&sender) {
+				continue
+			}
+			for (kind, field) in
+				[(LedgerEntryKind::NetworkFee, "netfee"), (LedgerEntryKind::SystemFee, "sysfee")]
+			{
+				let fee = tx[field]
+					.as_str()
+					.and_then(|fee| BigInt::from_str(fee).ok())
+					.ok_or_else(|| invalid(format!("transaction {hash:#x} has no {field}")))?;
+				if !fee.is_zero() {
+					entry(format!("{hash:#x}:{kind}"), hash, kind, sender, None, gas, -fee);
+				}
+			}
+		}
+
+		for log in logs {
+			let is_block = log.get("txid").is_none();
+			let container = hash_field(log, if is_block { "blockhash" } else { "txid" })?;
+			for transfer in transfers(log) {
+				let id = format!("{container:#x}:{}:{}", transfer.execution, transfer.notification);
+				let minted_gas = transfer.token == gas && transfer.from.is_none();
+				if let Some(from) = transfer.from.filter(|from| self.accounts.contains(from)) {
+					// GAS burnt while persisting a block pays the fees recorded above
+					if is_block && transfer.token == gas && transfer.to.is_none() {
+						continue
+					}
+					let kind = LedgerEntryKind::Transfer;
+					let amount = -transfer.amount.clone();
+					entry(
+						format!("{id}:out"),
+						container,
+						kind,
+						from,
+						transfer.to,
+						transfer.token,
+						amount,
+					);
+				}
+				if let Some(to) = transfer.to.filter(|to| self.accounts.contains(to)) {
+					let kind = if minted_gas {
+						LedgerEntryKind::GasGeneration
+					} else {
+						LedgerEntryKind::Transfer
+					};
+					let amount = transfer.amount.clone();
+					entry(
+						format!("{id}:in"),
+						container,
+						kind,
+						to,
+						transfer.from,
+						transfer.token,
+						amount,
+					);
+				}
+			}
+		}
+		Ok(entries)
+	}
+}
+
+/// The token standard of the transfers read by a [`BalanceTracker`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenStandard {
+	/// Fungible tokens
+	Nep17,
+	/// Non-fungible tokens
+	Nep11,
+}
+
+impl TokenStandard {
+	/// The `TokensTracker` RPC method listing the transfers of an address
+	pub fn transfers_method(&self) -> &'static str {
+		match self {
+			TokenStandard::Nep17 => "getnep17transfers",
+			TokenStandard::Nep11 => "getnep11transfers",
+		}
+	}
+}
+
+impl fmt::Display for TokenStandard {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			TokenStandard::Nep17 => "nep17",
+			TokenStandard::Nep11 => "nep11",
+		})
+	}
+}
+
+/// A transfer from or to a tracked address, with the balance of the address after it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BalanceChange {
+	/// Index of the block containing the transfer
+	pub block_index: u32,
+	/// Timestamp of the block in milliseconds
+	pub block_time: u64,
+	/// Hash of the transaction, or of the block for transfers raised while persisting a block
+	#[serde(serialize_with = "serialize_h256", deserialize_with = "deserialize_h256")]
+	pub tx_hash: H256,
+	/// Index of the `Transfer` notification among the notifications of the transaction
+	pub transfer_notify_index: u32,
+	/// The tracked address whose balance changed
+	#[serde(
+		serialize_with = "serialize_script_hash",
+		deserialize_with = "deserialize_script_hash"
+	)]
+	pub address: H160,
+	/// Standard of the token
+	pub standard: TokenStandard,
+	/// Script hash of the token
+	#[serde(
+		serialize_with = "serialize_script_hash",
+		deserialize_with = "deserialize_script_hash"
+	)]
+	pub token: H160,
+	/// Token symbol and decimals
+	pub token_info: TokenInfo,
+	/// Hex encoded id of the transferred NEP-11 token, `None` for NEP-17 tokens
+	pub token_id: Option<String>,
+	/// Balance change in the smallest unit of the token, negative when the balance decreases
+	#[serde(serialize_with = "serialize_big_int", deserialize_with = "deserialize_big_int")]
+	pub delta: BigInt,
+	/// Balance of the address in the token after the transfer
+	#[serde(serialize_with = "serialize_big_int", deserialize_with = "deserialize_big_int")]
+	pub balance: BigInt,
+}
+
+impl BalanceChange {
+	/// The delta formatted with the decimals of the token, e.g. `-0.0112` GAS.
+	pub fn formatted_delta(&self) -> String {
+		format_amount(&self.delta, self.token_info.decimals)
+	}
+
+	/// The balance formatted with the decimals of the token.
+	pub fn formatted_balance(&self) -> String {
+		format_amount(&self.balance, self.token_info.decimals)
+	}
+}
+
+/// The balance changes tracked over a range of blocks, in block order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BalanceHistory {
+	/// The tracked changes
+	pub changes: Vec<BalanceChange>,
+}
+
+impl BalanceHistory {
+	/// Header line of [`BalanceHistory::to_csv`]
+	pub const CSV_HEADER: &'static str = "block_index,block_time,tx_hash,transfer_notify_index,\
+	                                      address,standard,token,symbol,decimals,token_id,delta,\
+	                                      balance,raw_delta,raw_balance";
+
+	/// Sum of the balance changes of `address` in `token`, in the smallest unit of the token.
+	pub fn delta(&self, address: &H160, token: &H160) -> BigInt {
+		self.changes_of(address, token).map(|change| &change.delta).sum()
+	}
+
+	/// Balance of `address` in `token` after the last tracked change, `None` without changes.
+	pub fn closing_balance(&self, address: &H160, token: &H160) -> Option<&BigInt> {
+		self.changes_of(address, token).last().map(|change| &change.balance)
+	}
+
+	fn changes_of<'a>(
+		&'a self,
+		address: &'a H160,
+		token: &'a H160,
+	) -> impl Iterator<Item = &'a BalanceChange> {
+		self.changes
+			.iter()
+			.filter(move |change| change.address == *address && change.token == *token)
+	}
+
+	/// Exports the history as CSV, one line per change after [`BalanceHistory::CSV_HEADER`].
+	pub fn to_csv(&self) -> String {
+		let mut csv = format!("{}\n", Self::CSV_HEADER);
+		for change in &self.changes {
+			let fields = [
+				change.block_index.to_string(),
+				change.block_time.to_string(),
+				format!("{:#x}", change.tx_hash),
+				change.transfer_notify_index.to_string(),
+				format!("{:#x}", change.address),
+				change.standard.to_string(),
+				format!("{:#x}", change.token),
+				change.token_info.symbol.clone(),
+				change.token_info.decimals.to_string(),
+				change.token_id.clone().unwrap_or_default(),
+				change.formatted_delta(),
+				change.formatted_balance(),
+				change.delta.to_string(),
+				change.balance.to_string(),
+			];
+			let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+			csv.push_str(&line.join(","));
+			csv.push('\n');
+		}
+		csv
+	}
+}
+
+/// Tracks the NEP-17 and NEP-11 balances of a set of addresses over a range of blocks.
+///
+/// The node must run the `TokensTracker` plugin, which lists transfers by timestamp: the range
+/// of blocks is turned into the range of their timestamps, and windows returning as many
+/// transfers as the node allows are split until each fits in a response. Running balances start
+/// from the opening balances, zero unless set with [`BalanceTracker::opening_balance`]. NEP-11
+/// balances count the tokens of a contract held by the address, across token ids.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_providers::{accounting::BalanceTracker, Http, Provider};
+/// use primitive_types::H160;
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(account: H160) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let history = BalanceTracker::new(&provider, [account]).track(1_000, 2_000).await?;
+/// std::fs::write("balances.csv", history.to_csv())?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct BalanceTracker<'a, M> {
+	client: &'a M,
+	addresses: Vec<H160>,
+	standards: Vec<TokenStandard>,
+	max_results: usize,
+	opening_balances: HashMap<(H160, H160), BigInt>,
+	tokens: HashMap<H160, TokenInfo>,
+}
+
+impl<'a, M: Middleware> BalanceTracker<'a, M> {
+	/// The default `MaxResults` of the `TokensTracker` plugin
+	pub const DEFAULT_MAX_RESULTS: usize = 1000;
+
+	/// Creates a tracker of the NEP-17 and NEP-11 balances of `addresses`.
+	pub fn new(client: &'a M, addresses: impl IntoIterator<Item = H160>) -> Self {
+		let mut tracked: Vec<H160> = vec![];
+		for address in addresses {
+			if !tracked.contains(&address) {
+				tracked.push(address);
+			}
+		}
+		Self {
+			client,
+			addresses: tracked,
+			standards: vec![TokenStandard::Nep17, TokenStandard::Nep11],
+			max_results: Self::DEFAULT_MAX_RESULTS,
+			opening_balances: HashMap::new(),
+			tokens: HashMap::new(),
+		}
+	}
+
+	/// Only tracks the tokens of `standards`.
+	#[must_use]
+	pub fn standards(mut self, standards: impl IntoIterator<Item = TokenStandard>) -> Self {
+		self.standards = standards.into_iter().collect();
+		self
+	}
+
+	/// Sets the `MaxResults` the node is configured with, so full responses are detected.
+	#[must_use]
+	pub fn max_results(mut self, max_results: usize) -> Self {
+		self.max_results = max_results.max(1);
+		self
+	}
+
+	/// Sets the balance of `address` in `token` before the first tracked block, in the smallest
+	/// unit of the token.
+	#[must_use]
+	pub fn opening_balance(mut self, address: H160, token: H160, balance: BigInt) -> Self {
+		self.opening_balances.insert((address, token), balance);
+		self
+	}
+
+	/// Tracks the balance changes of the blocks `from..=to`.
+	///
+	/// Changes are ordered by block, then by transaction hash and notification index, since the
+	/// node does not report the position of transactions in their block.
+	pub async fn track(&mut self, from: u32, to: u32) -> Result<BalanceHistory, AccountingError> {
+		let start = self.block_time(from).await?;
+		let end = self.block_time(to).await?;
+		let mut transfers = vec![];
+		for address in &self.addresses {
+			for standard in &self.standards {
+				transfers.extend(self.transfers(*address, *standard, start, end).await?);
+			}
+		}
+		// blocks sharing a timestamp with the ends of the range are listed too
+		transfers.retain(|transfer| (from..=to).contains(&transfer.block_index));
+
+		let unknown: HashSet<H160> = transfers
+			.iter()
+			.map(|transfer| transfer.token)
+			.filter(|token| !self.tokens.contains_key(token))
+			.collect();
+		for token in unknown {
+			let info = token_info(self.client, token).await?;
+			self.tokens.insert(token, info);
+		}
+
+		transfers.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+		let mut balances = self.opening_balances.clone();
+		let changes = transfers
+			.into_iter()
+			.map(|transfer| {
+				let balance = balances.entry((transfer.address, transfer.token)).or_default();
+				*balance += &transfer.delta;
+				BalanceChange {
+					block_index: transfer.block_index,
+					block_time: transfer.block_time,
+					tx_hash: transfer.tx_hash,
+					transfer_notify_index: transfer.transfer_notify_index,
+					address: transfer.address,
+					standard: transfer.standard,
+					token: transfer.token,
+					token_info: self.tokens[&transfer.token].clone(),
+					token_id: transfer.token_id,
+					delta: transfer.delta,
+					balance: balance.clone(),
+				}
+			})
+			.collect();
+		Ok(BalanceHistory { changes })
+	}
+
+	async fn block_time(&self, index: u32) -> Result<u64, AccountingError> {
+		let header: Value = self
+			.client
+			.provider()
+			.request("getblockheader", vec![index.to_value(), true.to_value()])
+			.await
+			.map_err(rpc_error)?;
+		header["time"]
+			.as_u64()
+			.ok_or_else(|| invalid(format!("block {index} has no time")))
+	}
+
+	/// The transfers of `address` with timestamps in `start..=end`.
+	async fn transfers(
+		&self,
+		address: H160,
+		standard: TokenStandard,
+		start: u64,
+		end: u64,
+	) -> Result<Vec<TrackedTransfer>, AccountingError> {
+		let mut windows = vec![(start, end)];
+		let mut transfers = vec![];
+		while let Some((start, end)) = windows.pop() {
+			let response: Value = self
+				.client
+				.provider()
+				.request(
+					standard.transfers_method(),
+					vec![
+						Address::from_script_hash(address).to_value(),
+						start.to_value(),
+						end.to_value(),
+					],
+				)
+				.await
+				.map_err(rpc_error)?;
+			let sent = tracked_transfers(&response, "sent", address, standard)?;
+			let received = tracked_transfers(&response, "received", address, standard)?;
+			if sent.len().max(received.len()) >= self.max_results {
+				if start == end {
+					return Err(invalid(format!(
+						"more than {} transfers of {address:#x} at timestamp {start}",
+						self.max_results
+					)))
+				}
+				// the earlier half is popped first
+				let mid = start + (end - start) / 2;
+				windows.push((mid + 1, end));
+				windows.push((start, mid));
+				continue
+			}
+			transfers.extend(sent);
+			transfers.extend(received);
+		}
+		Ok(transfers)
+	}
+}
+
+/// A transfer listed by the `TokensTracker` plugin, signed from the side of the tracked address.
+struct TrackedTransfer {
+	address: H160,
+	standard: TokenStandard,
+	block_index: u32,
+	block_time: u64,
+	tx_hash: H256,
+	transfer_notify_index: u32,
+	token: H160,
+	token_id: Option<String>,
+	delta: BigInt,
+}
+
+impl TrackedTransfer {
+	/// Orders transfers by block, transaction and notification, the sent side of a transfer
+	/// to oneself first
+	fn sort_key(&self) -> (u32, H256, u32, &BigInt) {
+		(self.block_index, self.tx_hash, self.transfer_notify_index, &self.delta)
+	}
+}
+
+/// Parses the `sent` or `received` transfers of a `TokensTracker` response.
+fn tracked_transfers(
+	response: &Value,
+	field: &str,
+	address: H160,
+	standard: TokenStandard,
+) -> Result<Vec<TrackedTransfer>, AccountingError> {
+	let transfers = response[field]
+		.as_array()
+		.ok_or_else(|| invalid(format!("response has no {field} transfers")))?;
+	transfers
+		.iter()
+		.map(|transfer| {
+			let index = |name: &str| {
+				transfer[name]
+					.as_u64()
+					.and_then(|index| u32::try_from(index).ok())
+					.ok_or_else(|| invalid(format!("missing or invalid {name}")))
+			};
+			let amount = match &transfer["amount"] {
+				Value::String(amount) => BigInt::from_str(amount).ok(),
+				Value::Number(amount) => amount.as_u64().map(BigInt::from),
+				_ => None,
+			}
+			.ok_or_else(|| invalid("missing or invalid amount"))?;
+			let token = transfer["assethash"]
+				.as_str()
+				.and_then(|hash| H160::from_str(hash.trim_start_matches("0x")).ok())
+				.ok_or_else(|| invalid("missing or invalid assethash"))?;
+			Ok(TrackedTransfer {
+				address,
+				standard,
+				block_index: index("blockindex")?,
+				block_time: transfer["timestamp"]
+					.as_u64()
+					.ok_or_else(|| invalid("missing or invalid timestamp"))?,
+				tx_hash: hash_field(transfer, "txhash")?,
+				transfer_notify_index: index("transfernotifyindex")?,
+				token,
+				token_id: transfer["tokenid"].as_str().map(str::to_string),
+				delta: if field == "sent" { -amount } else { amount },
+			})
+		})
+		.collect()
+}
+
+/// Fetches the symbol and decimals of `token` with test invocations.
+async fn token_info<M: Middleware>(client: &M, token: H160) -> Result<TokenInfo, AccountingError> {
+	let symbol = invoke(client, token, "symbol").await?;
+	let decimals = invoke(client, token, "decimals").await?;
+	let symbol = match symbol["type"].as_str() {
+		Some("ByteString") => symbol["value"]
+			.as_str()
+			.and_then(|value| encoding::base64_decode(value).ok())
+			.and_then(|bytes| String::from_utf8(bytes).ok()),
+		_ => None,
+	}
+	.ok_or_else(|| invalid(format!("invalid symbol of token {token:#x}")))?;
+	let decimals = integer(&decimals)
+		.and_then(|decimals| u8::try_from(decimals).ok())
+		.ok_or_else(|| invalid(format!("invalid decimals of token {token:#x}")))?;
+	Ok(TokenInfo { symbol, decimals })
+}
+
+async fn invoke<M: Middleware>(
+	client: &M,
+	token: H160,
+	method: &str,
+) -> Result<Value, AccountingError> {
+	let result: Value = client
+		.provider()
+		.request("invokefunction", vec![token.to_value(), method.to_value(), Value::Array(vec![])])
+		.await
+		.map_err(rpc_error)?;
+	if result["state"].as_str() != Some("HALT") {
+		return Err(invalid(format!("{method} of token {token:#x} faulted")))
+	}
+	Ok(result["stack"][0].clone())
+}
+
+/// A NEP-17 `Transfer` notification.
+struct Transfer {
+	execution: usize,
+	notification: usize,
+	token: H160,
+	from: Option<H160>,
+	to: Option<H160>,
+	amount: BigInt,
+}
+
+/// Extracts the well-formed `Transfer` notifications of the successful executions of an
+/// application log.
+fn transfers(log: &Value) -> Vec<Transfer> {
+	let empty = vec![];
+	let executions = log["executions"].as_array().unwrap_or(&empty);
+	let mut transfers = vec![];
+	for (execution, exec) in executions.iter().enumerate() {
+		if exec["vmstate"].as_str() != Some("HALT") {
+			continue
+		}
+		let notifications = exec["notifications"].as_array().unwrap_or(&empty);
+		for (notification, event) in notifications.iter().enumerate() {
+			if event["eventname"].as_str() != Some("Transfer") {
+				continue
+			}
+			let Some(token) = event["contract"]
+				.as_str()
+				.and_then(|hash| H160::from_str(hash.trim_start_matches("0x")).ok())
+			else {
+				continue
+			};
+			let Some([from, to, amount]) = event["state"]["value"].as_array().map(Vec::as_slice)
+			else {
+				continue
+			};
+			let (Some(from), Some(to), Some(amount)) =
+				(address_item(from), address_item(to), integer(amount))
+			else {
+				continue
+			};
+			transfers.push(Transfer { execution, notification, token, from, to, amount });
+		}
+	}
+	transfers
+}
+
+/// Parses an address argument of a transfer, `Some(None)` standing for the null address.
+fn address_item(item: &Value) -> Option<Option<H160>> {
+	match item["type"].as_str()? {
+		"Any" => Some(None),
+		"ByteString" | "Buffer" => {
+			let mut bytes = encoding::base64_decode(item["value"].as_str()?).ok()?;
+			if bytes.len() != 20 {
+				return None
+			}
+			bytes.reverse();
+			Some(Some(H160::from_slice(&bytes)))
+		},
+		_ => None,
+	}
+}
+
+fn integer(item: &Value) -> Option<BigInt> {
+	if item["type"].as_str()? != "Integer" {
+		return None
+	}
+	match &item["value"] {
+		Value::String(value) => BigInt::from_str(value).ok(),
+		Value::Number(value) => value.as_i64().map(BigInt::from),
+		_ => None,
+	}
+}
+
+fn transactions(block: &Value) -> Result<&Vec<Value>, AccountingError> {
+	block["tx"].as_array().ok_or_else(|| invalid("block has no transaction list"))
+}
+
+fn hash_field(value: &Value, field: &str) -> Result<H256, AccountingError> {
+	value[field]
+		.as_str()
+		.and_then(|hash| H256::from_str(hash.trim_start_matches("0x")).ok())
+		.ok_or_else(|| invalid(format!("missing or invalid {field}")))
+}
+
+/// Formats `amount` with `decimals` decimals, without trailing zeros.
+pub fn format_amount(amount: &BigInt, decimals: u8) -> String {
+	let digits = amount.magnitude().to_string();
+	let decimals = decimals as usize;
+	let digits = format!("{digits:0>width$}", width = decimals + 1);
+	let (integer, fraction) = digits.split_at(digits.len() - decimals);
+	let fraction = fraction.trim_end_matches('0');
+	let sign = if amount.sign() == Sign::Minus { "-" } else { "" };
+	if fraction.is_empty() {
+		format!("{sign}{integer}")
+	} else {
+		format!("{sign}{integer}.{fraction}")
+	}
+}
+
+fn csv_field(field: &str) -> String {
+	if field.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_string()
+	}
+}
+
+fn serialize_big_int<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&value.to_string())
+}
+
+fn deserialize_big_int<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+	let value = String::deserialize(deserializer)?;
+	BigInt::from_str(&value).map_err(serde::de::Error::custom)
+}
+
+fn rpc_error(err: impl fmt::Display) -> AccountingError {
+	AccountingError::Rpc(err.to_string())
+}
+
+fn invalid(message: impl Into<String>) -> AccountingError {
+	AccountingError::InvalidResponse(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Expectation, MockProvider, Provider};
+	use neo_config::DEFAULT_ADDRESS_VERSION;
+	use serde_json::json;
+
+	fn item(hash: &H160) -> Value {
+		let mut bytes = hash.as_bytes().to_vec();
+		bytes.reverse();
+		json!({ "type": "ByteString", "value": encoding::base64_encode(bytes) })
+	}
+
+	fn transfer(from: Option<&H160>, to: Option<&H160>, amount: u64) -> Value {
+		let null = json!({ "type": "Any" });
+		json!({
+			"contract": format!("{:#x}", natives::GAS_TOKEN.hash),
+			"eventname": "Transfer",
+			"state": { "type": "Array", "value": [
+				from.map(item).unwrap_or(null.clone()),
+				to.map(item).unwrap_or(null),
+				{ "type": "Integer", "value": amount.to_string() },
+			]},
+		})
+	}
+
+	#[test]
+	fn test_format_amount() {
+		assert_eq!(format_amount(&BigInt::from(0), 8), "0");
+		assert_eq!(format_amount(&BigInt::from(1_120_000), 8), "0.0112");
+		assert_eq!(format_amount(&BigInt::from(-250_000_000), 8), "-2.5");
+		assert_eq!(format_amount(&BigInt::from(42), 0), "42");
+		let huge = BigInt::from_str("123456789012345678901234567890").unwrap();
+		assert_eq!(format_amount(&huge, 18), "123456789012.34567890123456789");
+	}
+
+	#[test]
+	fn test_block_entries() {
+		let provider = Provider::new(MockProvider::new());
+		let alice = H160::repeat_byte(1);
+		let bob = H160::repeat_byte(2);
+		let exporter = LedgerExporter::new(&provider, [alice]);
+		let tx_hash = H256::repeat_byte(0xaa);
+		let block_hash = H256::repeat_byte(0xbb);
+
+		let block = json!({
+			"hash": format!("{block_hash:#x}"),
+			"index": 10,
+			"time": 1_700_000_000_000u64,
+			"tx": [{
+				"hash": format!("{tx_hash:#x}"),
+				"sender": alice.to_address(DEFAULT_ADDRESS_VERSION),
+				"sysfee": "997750",
+				"netfee": "1230610",
+			}],
+		});
+		let block_log = json!({
+			"blockhash": format!("{block_hash:#x}"),
+			"executions": [{
+				"trigger": "OnPersist",
+				"vmstate": "HALT",
+				"notifications": [transfer(Some(&alice), None, 2_228_360)],
+			}],
+		});
+		let tx_log = json!({
+			"txid": format!("{tx_hash:#x}"),
+			"executions": [{
+				"trigger": "Application",
+				"vmstate": "HALT",
+				"notifications": [
+					transfer(Some(&alice), Some(&bob), 100_000_000),
+					transfer(None, Some(&alice), 5_000),
+				],
+			}],
+		});
+
+		let ledger =
+			Ledger { entries: exporter.block_entries(&block, &[block_log, tx_log]).unwrap() };
+		let kinds: Vec<_> = ledger.entries.iter().map(|entry| entry.kind).collect();
+		assert_eq!(
+			kinds,
+			vec![
+				LedgerEntryKind::NetworkFee,
+				LedgerEntryKind::SystemFee,
+				LedgerEntryKind::Transfer,
+				LedgerEntryKind::GasGeneration
+			]
+		);
+		let gas = natives::GAS_TOKEN.hash;
+		assert_eq!(
+			ledger.balance_change(&alice, &gas),
+			BigInt::from(-997_750 - 1_230_610 - 100_000_000 + 5_000)
+		);
+		assert_eq!(ledger.entries[2].id, format!("{tx_hash:#x}:0:0:out"));
+		assert_eq!(ledger.entries[2].counterparty, Some(bob));
+
+		let csv = ledger.to_csv();
+		assert_eq!(csv.lines().count(), 5);
+		assert!(csv.lines().nth(3).unwrap().ends_with(",GAS,8,-1,-100000000"));
+
+		let json = ledger.to_json().unwrap();
+		let entries: Vec<LedgerEntry> = serde_json::from_str(&json).unwrap();
+		assert_eq!(entries, ledger.entries);
+	}
+
+	fn tracked(block_index: u32, tx: u8, token: &H160, amount: &str) -> Value {
+		json!({
+			"timestamp": 1_000 * u64::from(block_index),
+			"assethash": format!("{token:#x}"),
+			"transferaddress": null,
+			"amount": amount,
+			"blockindex": block_index,
+			"transfernotifyindex": 0,
+			"txhash": format!("{:#x}", H256::repeat_byte(tx)),
+		})
+	}
+
+	#[tokio::test]
+	async fn test_balance_tracker() {
+		let (provider, mock) = Provider::mocked();
+		let alice = H160::repeat_byte(1);
+		let token = H160::repeat_byte(7);
+		let address = alice.to_address(DEFAULT_ADDRESS_VERSION);
+		let header = |index: u32| {
+			Expectation::new("getblockheader")
+				.with_params((index, true))
+				.returning(json!({ "time": 1_000 * index }))
+		};
+		let transfers = |method, start: u32, end: u32, sent: Vec<Value>, received: Vec<Value>| {
+			Expectation::new(method)
+				.with_params((&address, start, end))
+				.returning(json!({ "sent": sent, "received": received, "address": &address }))
+		};
+		let invocation = |method: &str, item: Value| {
+			Expectation::new("invokefunction")
+				.with_params((format!("{token:#x}"), method, json!([])))
+				.returning(json!({ "state": "HALT", "stack": [item] }))
+		};
+
+		mock.expect(header(10));
+		mock.expect(header(12));
+		mock.expect(transfers(
+			"getnep17transfers",
+			10_000,
+			12_000,
+			vec![tracked(11, 0xbb, &token, "150")],
+			// block 9 is outside the range
+			vec![tracked(10, 0xaa, &token, "500"), tracked(9, 0xcc, &token, "1")],
+		));
+		mock.expect(transfers("getnep11transfers", 10_000, 12_000, vec![], vec![]));
+		mock.expect(invocation(
+			"symbol",
+			json!({ "type": "ByteString", "value": encoding::base64_encode(b"TOK") }),
+		));
+		mock.expect(invocation("decimals", json!({ "type": "Integer", "value": "2" })));
+
+		let mut tracker = BalanceTracker::new(&provider, [alice, alice]).opening_balance(
+			alice,
+			token,
+			BigInt::from(50),
+		);
+		let history = tracker.track(10, 12).await.unwrap();
+		mock.assert_expectations_met().unwrap();
+
+		let balances: Vec<_> =
+			history.changes.iter().map(BalanceChange::formatted_balance).collect();
+		assert_eq!(balances, vec!["5.5", "4"]);
+		assert_eq!(history.changes[1].formatted_delta(), "-1.5");
+		assert_eq!(history.changes[1].token_info.symbol, "TOK");
+		assert_eq!(history.delta(&alice, &token), BigInt::from(350));
+		assert_eq!(history.closing_balance(&alice, &token), Some(&BigInt::from(400)));
+		assert_eq!(history.closing_balance(&alice, &H160::zero()), None);
+		assert!(history.to_csv().lines().nth(2).unwrap().ends_with(",-1.5,4,-150,400"));
+
+		// full responses are split in two windows
+		mock.expect(header(10));
+		mock.expect(header(12));
+		let full = vec![tracked(10, 0xaa, &token, "1"), tracked(11, 0xbb, &token, "1")];
+		mock.expect(transfers("getnep17transfers", 10_000, 12_000, vec![], full));
+		mock.expect(transfers(
+			"getnep17transfers",
+			10_000,
+			11_000,
+			vec![],
+			vec![tracked(10, 0xaa, &token, "1")],
+		));
+		mock.expect(transfers(
+			"getnep17transfers",
+			11_001,
+			12_000,
+			vec![tracked(12, 0xdd, &token, "3")],
+			vec![],
+		));
+		let mut tracker = BalanceTracker::new(&provider, [alice])
+			.standards([TokenStandard::Nep17])
+			.max_results(2);
+		mock.expect(invocation(
+			"symbol",
+			json!({ "type": "ByteString", "value": encoding::base64_encode(b"TOK") }),
+		));
+		mock.expect(invocation("decimals", json!({ "type": "Integer", "value": "2" })));
+		let history = tracker.track(10, 12).await.unwrap();
+		mock.assert_expectations_met().unwrap();
+		assert_eq!(history.delta(&alice, &token), BigInt::from(-2));
+	}
+}
@@ -1,14 +1,18 @@
 use crate::{
-	core::{transaction::verification_script::VerificationScript, wallet::WalletTrait},
-	utils::{private_key_from_wif, public_key_to_address},
+	core::{
+		error::BuilderError, transaction::verification_script::VerificationScript,
+		wallet::WalletTrait,
+	},
 	ProviderError,
 };
-use neo_crypto::{key_pair::KeyPair, keys::Secp256r1PublicKey, nep2::NEP2};
+use neo_crypto::{
+	key_pair::KeyPair, keys::Secp256r1PublicKey, nep2::NEP2, wif::private_key_from_wif,
+};
 use neo_types::{
 	address::Address,
 	address_or_scripthash::AddressOrScriptHash,
-	script_hash::{ScriptHash, ScriptHashExtension},
-	Base64Encode, *,
+	script_hash::{ScriptHash, ScriptHashExt},
+	*,
 };
 use primitive_types::H160;
 use serde_derive::{Deserialize, Serialize};
@@ -68,6 +72,10 @@ pub trait AccountTrait: Sized + PartialEq + Send + Sync + Debug + Clone {
 
 	fn from_wif(wif: &str) -> Result<Self, Self::Error>;
 
+	/// Exports the private key of the account in Wallet Import Format. Fails if the account has no
+	/// decrypted key pair.
+	fn export_wif(&self) -> Result<String, Self::Error>;
+
 	fn decrypt_private_key(&mut self, password: &str) -> Result<(), Self::Error>;
 
 	fn encrypt_private_key(&mut self, password: &str) -> Result<(), Self::Error>;
@@ -229,14 +237,15 @@ impl AccountTrait for Account {
 		signing_threshold: Option<u32>,
 		nr_of_participants: Option<u32>,
 	) -> Result<Self, Self::Error> {
-		let address = public_key_to_address(&key_pair.public_key);
+		let address = Address::from_script_hash(ScriptHash::from_public_key(&key_pair.public_key));
 		Ok(Self {
 			key_pair: Some(key_pair.clone()),
-			address_or_scripthash: AddressOrScriptHash::Address(address.clone()),
-			label: Some(address),
-			verification_script: Some(VerificationScript::from_public_key(
-				&key_pair.clone().public_key(),
-			)),
+			address_or_scripthash: AddressOrScriptHash::Address(address),
+			label: Some(address.to_string()),
+			verification_script: Some(
+				VerificationScript::from_public_key(&key_pair.clone().public_key())
+					.map_err(|e| ProviderError::IllegalState(e.to_string()))?,
+			),
 			is_locked: false,
 			encrypted_private_key: None,
 			signing_threshold,
@@ -267,10 +276,17 @@ impl AccountTrait for Account {
 	}
 
 	fn from_wif(wif: &str) -> Result<Self, Self::Error> {
-		let key_pair = KeyPair::from_secret_key(&private_key_from_wif(wif).unwrap());
+		let key_pair = KeyPair::from_secret_key(&private_key_from_wif(wif)?);
 		Self::from_key_pair(key_pair, None, None)
 	}
 
+	fn export_wif(&self) -> Result<String, Self::Error> {
+		self.key_pair
+			.as_ref()
+			.map(KeyPair::export_as_wif)
+			.ok_or_else(|| Self::Error::IllegalState("No decrypted key pair present".to_string()))
+	}
+
 	fn decrypt_private_key(&mut self, password: &str) -> Result<(), Self::Error> {
 		if self.key_pair.is_some() {
 			return Ok(())
@@ -279,9 +295,10 @@ impl AccountTrait for Account {
 		let encrypted_private_key = self
 			.encrypted_private_key
 			.as_ref()
-			.ok_or(Self::Error::IllegalState("No encrypted private key present".to_string()))
-			.unwrap();
-		let key_pair = NEP2::decrypt(password, encrypted_private_key).unwrap();
+			.ok_or(Self::Error::IllegalState("No encrypted private key present".to_string()))?;
+		let key_pair = NEP2::decrypt(password, encrypted_private_key).map_err(|e| {
+			Self::Error::IllegalState(format!("Could not decrypt the private key: {e}"))
+		})?;
 		self.key_pair = Some(KeyPair::from_secret_key(&key_pair.private_key().clone()));
 		Ok(())
 	}
@@ -290,9 +307,10 @@ impl AccountTrait for Account {
 		let key_pair = self
 			.key_pair
 			.as_ref()
-			.ok_or(Self::Error::IllegalState("No decrypted key pair present".to_string()))
-			.unwrap();
-		let encrypted_private_key = NEP2::encrypt(password, key_pair).unwrap();
+			.ok_or(Self::Error::IllegalState("No decrypted key pair present".to_string()))?;
+		let encrypted_private_key = NEP2::encrypt(password, key_pair).map_err(|e| {
+			Self::Error::IllegalState(format!("Could not encrypt the private key: {e}"))
+		})?;
 		self.encrypted_private_key = Some(encrypted_private_key);
 		self.key_pair = None;
 		Ok(())
@@ -318,9 +336,10 @@ impl AccountTrait for Account {
 		let address = ScriptHash::from_script(&script.script());
 
 		let (signing_threshold, nr_of_participants) = if script.is_multi_sig() {
+			let invalid = |e: BuilderError| ProviderError::IllegalState(e.to_string());
 			(
-				Some(script.get_signing_threshold().unwrap()),
-				Some(script.get_nr_of_accounts().unwrap()),
+				Some(script.get_signing_threshold().map_err(invalid)?),
+				Some(script.get_nr_of_accounts().map_err(invalid)?),
 			)
 		} else {
 			(None, None)
@@ -328,7 +347,7 @@ impl AccountTrait for Account {
 
 		Ok(Self {
 			address_or_scripthash: AddressOrScriptHash::ScriptHash(address),
-			label: Some(ScriptHashExtension::to_string(&address)),
+			label: Some(Address::from_script_hash(address).to_string()),
 			verification_script: Some(script.clone()),
 			signing_threshold: signing_threshold.map(|x| x as u32),
 			nr_of_participants: nr_of_participants.map(|x| x as u32),
@@ -337,12 +356,13 @@ impl AccountTrait for Account {
 	}
 
 	fn from_public_key(public_key: &Secp256r1PublicKey) -> Result<Self, Self::Error> {
-		let script = VerificationScript::from_public_key(public_key);
+		let script = VerificationScript::from_public_key(public_key)
+			.map_err(|e| ProviderError::IllegalState(e.to_string()))?;
 		let address = ScriptHash::from_script(&script.script());
 
 		Ok(Self {
 			address_or_scripthash: AddressOrScriptHash::ScriptHash(address),
-			label: Some(ScriptHashExtension::to_string(&address)),
+			label: Some(Address::from_script_hash(address).to_string()),
 			verification_script: Some(script),
 			..Default::default()
 		})
@@ -352,10 +372,11 @@ impl AccountTrait for Account {
 		public_keys: &[Secp256r1PublicKey],
 		signing_threshold: u32,
 	) -> Result<Self, Self::Error> {
-		let script = VerificationScript::from_multi_sig(public_keys, signing_threshold as u8);
+		let script = VerificationScript::from_multi_sig(public_keys, signing_threshold as u8)
+			.map_err(|e| ProviderError::IllegalState(e.to_string()))?;
 
 		Ok(Self {
-			label: Some(script.script().to_base64()),
+			label: Some(encoding::base64_encode(script.script())),
 			verification_script: Some(script),
 			signing_threshold: Some(signing_threshold),
 			nr_of_participants: Some(public_keys.len() as u32),
@@ -364,17 +385,21 @@ impl AccountTrait for Account {
 	}
 
 	fn from_address(address: &str) -> Result<Self, Self::Error> {
-		let address = Address::from_str(address).unwrap();
+		let address = Address::from_str(address)?;
 		Ok(Self {
-			address_or_scripthash: AddressOrScriptHash::Address(address.clone()),
-			label: Some(address),
+			address_or_scripthash: AddressOrScriptHash::Address(address),
+			label: Some(address.to_string()),
 			..Default::default()
 		})
 	}
 
 	fn from_script_hash(script_hash: &H160) -> Result<Self, Self::Error> {
-		let address = script_hash.to_address();
-		Self::from_address(&address)
+		let address = Address::from_script_hash(*script_hash);
+		Ok(Self {
+			address_or_scripthash: AddressOrScriptHash::Address(address),
+			label: Some(address.to_string()),
+			..Default::default()
+		})
 	}
 
 	fn create() -> Result<Self, Self::Error> {
@@ -386,3 +411,23 @@ impl AccountTrait for Account {
 		self.signing_threshold.is_some() && self.nr_of_participants.is_some()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_config::TestConstants;
+
+	#[test]
+	fn test_wif_round_trip() {
+		let account = Account::from_wif(TestConstants::DEFAULT_ACCOUNT_WIF).unwrap();
+		assert_eq!(
+			hex::encode(account.get_script_hash()),
+			TestConstants::DEFAULT_ACCOUNT_SCRIPT_HASH
+		);
+		assert_eq!(account.export_wif().unwrap(), TestConstants::DEFAULT_ACCOUNT_WIF);
+
+		assert!(Account::from_wif("L25kgAQJXNHnhc7Sx9bomxxwVSMsZdkaNQ3m2VfHrnLzKWML").is_err());
+		let watch_only = Account::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
+		assert!(watch_only.export_wif().is_err());
+	}
+}
@@ -1,142 +1,161 @@
-use lazy_static::lazy_static;
-use neo_crypto::hash::HashableForVec;
-use std::{
-	collections::HashMap,
-	hash::Hash,
-	sync::{Arc, Mutex},
-};
-use strum::IntoEnumIterator;
-use strum_macros::{Display, EnumIter, EnumString};
-
-lazy_static! {
-	static ref INTEROP_SERVICE_HASHES: Arc<Mutex<HashMap<String, String>>> =
-		Arc::new(Mutex::new(HashMap::new()));
+//! The interop services of the NeoVM, called with the `Syscall` opcode.
+//!
+//! A syscall names its service by the first 4 bytes of the SHA-256 of the service name, which
+//! are computed at compile time. The methods of native contracts like CryptoLib and StdLib are
+//! not syscalls, they are called through `System.Contract.Call`.
+
+use neo_crypto::hash::sha256;
+use std::{fmt, str::FromStr};
+use strum::ParseError;
+
+/// The 4 byte id of the interop service `name`
+const fn interop_hash(name: &str) -> [u8; 4] {
+	let hash = sha256(name.as_bytes());
+	[hash[0], hash[1], hash[2], hash[3]]
+}
+
+macro_rules! interop_services {
+	($($variant:ident => $name:literal, $price:expr;)*) => {
+		#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+		pub enum InteropService {
+			$(
+				#[doc = concat!("`", $name, "`")]
+				$variant,
+			)*
+		}
+
+		impl InteropService {
+			/// All interop services, in declaration order
+			pub const ALL: &'static [InteropService] = &[$(InteropService::$variant),*];
+
+			/// The ids of [`InteropService::ALL`], in the same order
+			const HASHES: &'static [[u8; 4]] = &[$(interop_hash($name)),*];
+
+			/// The name of the service, e.g. `System.Contract.Call`
+			pub const fn name(&self) -> &'static str {
+				match self {
+					$(InteropService::$variant => $name,)*
+				}
+			}
+
+			/// The fixed price of calling the service, in units of the execution fee factor.
+			/// Services whose price depends on their arguments have a price of 0.
+			pub const fn price(&self) -> u64 {
+				match self {
+					$(InteropService::$variant => $price,)*
+				}
+			}
+		}
+	};
 }
 
-#[derive(EnumString, EnumIter, Display, Copy, Clone, PartialEq, Eq)]
-pub enum InteropService {
-	#[strum(serialize = "System.Crypto.CheckSig")]
-	SystemCryptoCheckSig,
-	#[strum(serialize = "System.Crypto.CheckMultiSig")]
-	SystemCryptoCheckMultiSig,
-	#[strum(serialize = "System.Contract.Call")]
-	SystemContractCall,
-	#[strum(serialize = "System.Contract.CallNative")]
-	SystemContractCallNative,
-	#[strum(serialize = "System.Contract.GetCallFlags")]
-	SystemContractGetCallFlags,
-	#[strum(serialize = "System.Contract.CreateStandardAccount")]
-	SystemContractCreateStandardAccount,
-	#[strum(serialize = "System.Contract.CreateMultiSigAccount")]
-	SystemContractCreateMultiSigAccount,
-	#[strum(serialize = "System.Contract.NativeOnPersist")]
-	SystemContractNativeOnPersist,
-	#[strum(serialize = "System.Contract.NativePostPersist")]
-	SystemContractNativePostPersist,
-	#[strum(serialize = "System.Iterator.Next")]
-	SystemIteratorNext,
-	#[strum(serialize = "System.Iterator.Value")]
-	SystemIteratorValue,
-	#[strum(serialize = "System.Runtime.Platform")]
-	SystemRuntimePlatform,
-	#[strum(serialize = "System.Runtime.GetTrigger")]
-	SystemRuntimeGetTrigger,
-	#[strum(serialize = "System.Runtime.GetTime")]
-	SystemRuntimeGetTime,
-	#[strum(serialize = "System.Runtime.GetScriptContainer")]
-	SystemRuntimeGetScriptContainer,
-	#[strum(serialize = "System.Runtime.GetExecutingScriptHash")]
-	SystemRuntimeGetExecutingScriptHash,
-	#[strum(serialize = "System.Runtime.GetCallingScriptHash")]
-	SystemRuntimeGetCallingScriptHash,
-	#[strum(serialize = "System.Runtime.GetEntryScriptHash")]
-	SystemRuntimeGetEntryScriptHash,
-	#[strum(serialize = "System.Runtime.CheckWitness")]
-	SystemRuntimeCheckWitness,
-	#[strum(serialize = "System.Runtime.GetInvocationCounter")]
-	SystemRuntimeGetInvocationCounter,
-	#[strum(serialize = "System.Runtime.Log")]
-	SystemRuntimeLog,
-	#[strum(serialize = "System.Runtime.Notify")]
-	SystemRuntimeNotify,
-	#[strum(serialize = "System.Runtime.GetNotifications")]
-	SystemRuntimeGetNotifications,
-	#[strum(serialize = "System.Runtime.GasLeft")]
-	SystemRuntimeGasLeft,
-	#[strum(serialize = "System.Runtime.BurnGas")]
-	SystemRuntimeBurnGas,
-	#[strum(serialize = "System.Runtime.GetNetwork")]
-	SystemRuntimeGetNetwork,
-	#[strum(serialize = "System.Runtime.GetRandom")]
-	SystemRuntimeGetRandom,
-	#[strum(serialize = "System.Storage.GetContext")]
-	SystemStorageGetContext,
-	#[strum(serialize = "System.Storage.GetReadOnlyContext")]
-	SystemStorageGetReadOnlyContext,
-	#[strum(serialize = "System.Storage.AsReadOnly")]
-	SystemStorageAsReadOnly,
-	#[strum(serialize = "System.Storage.Get")]
-	SystemStorageGet,
-	#[strum(serialize = "System.Storage.Find")]
-	SystemStorageFind,
-	#[strum(serialize = "System.Storage.Put")]
-	SystemStoragePut,
-	#[strum(serialize = "System.Storage.Delete")]
-	SystemStorageDelete,
+interop_services! {
+	SystemContractCall => "System.Contract.Call", 1 << 15;
+	SystemContractCallNative => "System.Contract.CallNative", 0;
+	SystemContractGetCallFlags => "System.Contract.GetCallFlags", 1 << 10;
+	SystemContractCreateStandardAccount => "System.Contract.CreateStandardAccount", 0;
+	SystemContractCreateMultiSigAccount => "System.Contract.CreateMultisigAccount", 0;
+	SystemContractNativeOnPersist => "System.Contract.NativeOnPersist", 0;
+	SystemContractNativePostPersist => "System.Contract.NativePostPersist", 0;
+	SystemCryptoCheckSig => "System.Crypto.CheckSig", 1 << 15;
+	SystemCryptoCheckMultiSig => "System.Crypto.CheckMultisig", 0;
+	SystemIteratorNext => "System.Iterator.Next", 1 << 15;
+	SystemIteratorValue => "System.Iterator.Value", 1 << 4;
+	SystemRuntimePlatform => "System.Runtime.Platform", 1 << 3;
+	SystemRuntimeGetNetwork => "System.Runtime.GetNetwork", 1 << 3;
+	SystemRuntimeGetAddressVersion => "System.Runtime.GetAddressVersion", 1 << 3;
+	SystemRuntimeGetTrigger => "System.Runtime.GetTrigger", 1 << 3;
+	SystemRuntimeGetTime => "System.Runtime.GetTime", 1 << 3;
+	SystemRuntimeGetScriptContainer => "System.Runtime.GetScriptContainer", 1 << 3;
+	SystemRuntimeGetExecutingScriptHash => "System.Runtime.GetExecutingScriptHash", 1 << 4;
+	SystemRuntimeGetCallingScriptHash => "System.Runtime.GetCallingScriptHash", 1 << 4;
+	SystemRuntimeGetEntryScriptHash => "System.Runtime.GetEntryScriptHash", 1 << 4;
+	SystemRuntimeLoadScript => "System.Runtime.LoadScript", 1 << 15;
+	SystemRuntimeCheckWitness => "System.Runtime.CheckWitness", 1 << 10;
+	SystemRuntimeGetInvocationCounter => "System.Runtime.GetInvocationCounter", 1 << 4;
+	SystemRuntimeGetRandom => "System.Runtime.GetRandom", 1 << 4;
+	SystemRuntimeLog => "System.Runtime.Log", 1 << 15;
+	SystemRuntimeNotify => "System.Runtime.Notify", 1 << 15;
+	SystemRuntimeGetNotifications => "System.Runtime.GetNotifications", 1 << 12;
+	SystemRuntimeGasLeft => "System.Runtime.GasLeft", 1 << 4;
+	SystemRuntimeBurnGas => "System.Runtime.BurnGas", 1 << 4;
+	SystemRuntimeCurrentSigners => "System.Runtime.CurrentSigners", 1 << 4;
+	SystemStorageGetContext => "System.Storage.GetContext", 1 << 4;
+	SystemStorageGetReadOnlyContext => "System.Storage.GetReadOnlyContext", 1 << 4;
+	SystemStorageAsReadOnly => "System.Storage.AsReadOnly", 1 << 4;
+	SystemStorageGet => "System.Storage.Get", 1 << 15;
+	SystemStorageFind => "System.Storage.Find", 1 << 15;
+	SystemStoragePut => "System.Storage.Put", 1 << 15;
+	SystemStorageDelete => "System.Storage.Delete", 1 << 15;
 }
 
 impl InteropService {
-	pub fn hash(&self) -> String {
-		let mut hashes = INTEROP_SERVICE_HASHES.lock().unwrap();
-		return if let Some(hash) = hashes.get(self.to_string().as_str()) {
-			hash.clone()
-		} else {
-			let sha = self.to_string().as_bytes().to_vec().hash256();
-			let hash = hex::encode(sha)[..4].to_string();
-			hashes.insert(self.to_string(), hash.clone());
-			hash
-		}
+	/// The 4 byte id of the service, the operand of its `Syscall`
+	pub const fn hash(&self) -> [u8; 4] {
+		Self::HASHES[*self as usize]
+	}
+
+	/// The id of the service as the little-endian `u32` the node uses
+	pub const fn id(&self) -> u32 {
+		u32::from_le_bytes(self.hash())
+	}
+
+	/// The service whose id is `hash`, e.g. the operand of a `Syscall`
+	pub fn from_hash(hash: &[u8]) -> Option<InteropService> {
+		let index = Self::HASHES.iter().position(|service| service[..] == *hash)?;
+		Some(Self::ALL[index])
+	}
+}
+
+impl fmt::Display for InteropService {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for InteropService {
+	type Err = ParseError;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.find(|service| service.name() == name)
+			.copied()
+			.ok_or(ParseError::VariantNotFound)
 	}
+}
 
-	pub fn from_hash(hash: String) -> Option<InteropService> {
-		InteropService::iter().find(|service| service.hash() == hash)
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_crypto::hash::HashableForVec;
+
+	#[test]
+	fn test_hashes() {
+		assert_eq!(InteropService::SystemContractCall.hash(), [0x62, 0x7d, 0x5b, 0x52]);
+		assert_eq!(InteropService::SystemCryptoCheckSig.hash(), [0x56, 0xe7, 0xb3, 0x27]);
+		assert_eq!(InteropService::SystemCryptoCheckMultiSig.hash(), [0x9e, 0xd0, 0xdc, 0x3a]);
+		for service in InteropService::ALL {
+			assert_eq!(service.hash()[..], service.name().as_bytes().hash256()[..4]);
+		}
 	}
-	pub fn price(&self) -> u64 {
-		match self {
-			InteropService::SystemRuntimePlatform
-			| InteropService::SystemRuntimeGetTrigger
-			| InteropService::SystemRuntimeGetTime
-			| InteropService::SystemRuntimeGetScriptContainer
-			| InteropService::SystemRuntimeGetNetwork => 1 << 3,
-
-			InteropService::SystemIteratorValue
-			| InteropService::SystemRuntimeGetExecutingScriptHash
-			| InteropService::SystemRuntimeGetCallingScriptHash
-			| InteropService::SystemRuntimeGetEntryScriptHash
-			| InteropService::SystemRuntimeGetInvocationCounter
-			| InteropService::SystemRuntimeGasLeft
-			| InteropService::SystemRuntimeBurnGas
-			| InteropService::SystemRuntimeGetRandom
-			| InteropService::SystemStorageGetContext
-			| InteropService::SystemStorageGetReadOnlyContext
-			| InteropService::SystemStorageAsReadOnly => 1 << 4,
-
-			InteropService::SystemContractGetCallFlags
-			| InteropService::SystemRuntimeCheckWitness => 1 << 10,
-
-			InteropService::SystemRuntimeGetNotifications => 1 << 12,
-
-			InteropService::SystemCryptoCheckSig
-			| InteropService::SystemContractCall
-			| InteropService::SystemContractCreateStandardAccount
-			| InteropService::SystemIteratorNext
-			| InteropService::SystemRuntimeLog
-			| InteropService::SystemRuntimeNotify
-			| InteropService::SystemStorageGet
-			| InteropService::SystemStorageFind
-			| InteropService::SystemStoragePut
-			| InteropService::SystemStorageDelete => 1 << 15,
-			_ => 0,
+
+	#[test]
+	fn test_reverse_lookup() {
+		for service in InteropService::ALL {
+			assert_eq!(InteropService::from_hash(&service.hash()), Some(*service));
+			assert_eq!(service.name().parse::<InteropService>(), Ok(*service));
 		}
+		assert_eq!(InteropService::from_hash(&[0, 0, 0, 0]), None);
+		assert_eq!(InteropService::from_hash(&[0x62, 0x7d, 0x5b]), None);
+		assert!("System.Unknown".parse::<InteropService>().is_err());
+	}
+
+	#[test]
+	fn test_prices() {
+		assert_eq!(InteropService::SystemCryptoCheckSig.price(), 1 << 15);
+		// charged by the service itself, like the multi-sig variant
+		assert_eq!(InteropService::SystemContractCreateStandardAccount.price(), 0);
+		assert_eq!(InteropService::SystemContractCreateMultiSigAccount.price(), 0);
 	}
 }
@@ -4,19 +4,37 @@ use crate::core::{
 };
 use getset::{Getters, Setters};
 use neo_codec::Encoder;
-use neo_crypto::keys::Secp256r1PublicKey;
+use neo_crypto::{
+	bls::{Bls12381G1, Bls12381G2, Bls12381Point, Bls12381Scalar},
+	keys::Secp256r1PublicKey,
+};
 use neo_types::{
 	contract_parameter::{ContractParameter, ParameterValue},
 	contract_parameter_type::ContractParameterType,
+	encoding, natives,
 	op_code::OpCode,
-	script_hash::ScriptHashExtension,
+	script_hash::{ScriptHashBE, ScriptHashExt},
 	Bytes,
 };
 use num_bigint::{BigInt, Sign};
 use num_traits::ToPrimitive;
 use primitive_types::H160;
 use std::collections::HashMap;
-use tokio::io::AsyncWriteExt;
+
+/// A NEP-17 transfer, see [`ScriptBuilder::build_multi_transfer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransferIntent {
+	/// The script hash of the token
+	pub token: H160,
+	/// The sender, which has to sign the transaction
+	pub from: H160,
+	/// The recipient
+	pub to: H160,
+	/// The amount in the smallest unit of the token
+	pub amount: i64,
+	/// The data passed to `onNEP17Payment` of the recipient, if it is a contract
+	pub data: Option<ContractParameter>,
+}
 
 #[derive(Debug, PartialEq, Eq, Hash, Getters, Setters)]
 pub struct ScriptBuilder {
@@ -49,66 +67,86 @@ impl ScriptBuilder {
 		call_flags: CallFlags,
 	) -> Result<&mut Self, BuilderError> {
 		if params.is_empty() {
-			self.op_code(&[OpCode::NewArray]);
+			self.op_code(&[OpCode::NewArray0]);
 		} else {
-			self.push_params(params);
+			self.push_params(params)?;
 		}
 
 		Ok(self
-			.push_integer(BigInt::from(call_flags.value()))
-			.unwrap()
-			.push_data(method.as_bytes().to_vec())
-			.unwrap()
-			.push_data(hash160.to_vec())
-			.unwrap()
+			.push_integer(BigInt::from(call_flags.value()))?
+			.push_data(method.as_bytes().to_vec())?
+			.push_data(hash160.to_le_vec())?
 			.sys_call(InteropService::SystemContractCall))
 	}
 
 	pub fn sys_call(&mut self, operation: InteropService) -> &mut Self {
-		self.op_code(&[OpCode::Syscall])
-			.push_data(operation.hash().into_bytes())
-			.unwrap()
+		self.op_code_with_arg(OpCode::Syscall, operation.hash().to_vec())
 	}
 
-	pub fn push_params(&mut self, params: &[ContractParameter]) -> &mut Self {
-		for param in params {
-			self.push_param(param).unwrap();
+	/// Pushes `params` packed into an array. They are pushed in reverse, so the first parameter
+	/// ends up on top of the stack and first in the array, like the node emits them.
+	pub fn push_params(&mut self, params: &[ContractParameter]) -> Result<&mut Self, BuilderError> {
+		for param in params.iter().rev() {
+			self.push_param(param)?;
 		}
 
-		self.push_integer(BigInt::from(params.len())).unwrap().op_code(&[OpCode::Pack])
+		Ok(self.push_integer(BigInt::from(params.len()))?.op_code(&[OpCode::Pack]))
 	}
 
 	pub fn push_param(&mut self, param: &ContractParameter) -> Result<&mut Self, BuilderError> {
-		if param.get_type() == ContractParameterType::Any {
-			self.op_code(&[OpCode::PushNull]);
-		}
-		match &param.value.clone().unwrap() {
+		let value = match &param.value {
+			None | Some(ParameterValue::Any) => return Ok(self.op_code(&[OpCode::PushNull])),
+			Some(value) => value.clone(),
+		};
+		match &value {
 			ParameterValue::Boolean(b) => self.push_bool(*b),
-			ParameterValue::Integer(i) => self.push_integer(BigInt::from(i.clone())).unwrap(),
-			ParameterValue::ByteArray(b)
-			| ParameterValue::Signature(b)
-			| ParameterValue::PublicKey(b) => self.push_data(b.as_bytes().to_vec()).unwrap(),
-			ParameterValue::Hash160(h) => self.push_data(h.as_bytes().to_vec()).unwrap(),
-			ParameterValue::Hash256(h) => self.push_data(h.as_bytes().to_vec()).unwrap(),
-			ParameterValue::String(s) => self.push_data(s.as_bytes().to_vec()).unwrap(),
-			ParameterValue::Array(arr) => self.push_array(arr).unwrap(),
+			ParameterValue::Integer(i) => self.push_integer(BigInt::from(i.clone()))?,
+			// encoded as in RPC requests, byte arrays and signatures in base64 and keys in hex
+			ParameterValue::ByteArray(b) | ParameterValue::Signature(b) => {
+				let bytes = encoding::base64_decode(b).map_err(|e| {
+					BuilderError::IllegalArgument(format!(
+						"Invalid {} parameter: {e}",
+						param.get_type()
+					))
+				})?;
+				self.push_data(bytes)?
+			},
+			ParameterValue::PublicKey(key) => {
+				let bytes = hex::decode(key).map_err(|e| {
+					BuilderError::IllegalArgument(format!("Invalid PublicKey parameter: {e}"))
+				})?;
+				self.push_data(bytes)?
+			},
+			ParameterValue::Hash160(h) => {
+				let hash = h.parse::<ScriptHashBE>().map_err(|e| {
+					BuilderError::IllegalArgument(format!("Invalid Hash160 parameter: {e}"))
+				})?;
+				self.push_data(hash.0.to_le_vec())?
+			},
+			ParameterValue::Hash256(h) => self.push_data(h.as_bytes().to_vec())?,
+			ParameterValue::String(s) => self.push_data(s.as_bytes().to_vec())?,
+			ParameterValue::Array(arr) => self.push_array(arr)?,
 			ParameterValue::Map(map) => {
-				// Create an empty HashMap to hold your ContractParameter key-value pairs
+				// the map is stored as a flat list of JSON encoded keys and values
+				if map.len() % 2 != 0 {
+					return Err(BuilderError::IllegalArgument(
+						"Map parameter has a key without value".to_string(),
+					))
+				}
+				let parse = |entry: &serde_json::Value| -> Result<ContractParameter, BuilderError> {
+					let json = entry.as_str().ok_or_else(|| {
+						BuilderError::IllegalArgument(format!("Invalid map entry {entry}"))
+					})?;
+					serde_json::from_str(json).map_err(|e| {
+						BuilderError::IllegalArgument(format!("Invalid map entry {json}: {e}"))
+					})
+				};
 				let mut map_value: HashMap<ContractParameter, ContractParameter> = HashMap::new();
-
-				// Iterate over pairs of elements in the vector
-				// (assuming the vector has an even number of elements)
-				for i in (0..map.len()).step_by(2) {
-					let key: ContractParameter =
-						serde_json::from_str(map[i].as_str().unwrap()).unwrap();
-					let value: ContractParameter =
-						serde_json::from_str(map[i + 1].as_str().unwrap()).unwrap();
-
-					// Insert the key-value pair into the HashMap
-					map_value.insert(key, value);
+				for pair in map.chunks(2) {
+					map_value.insert(parse(&pair[0])?, parse(&pair[1])?);
 				}
 
-				self.push_map(&map_value).unwrap()
+				self.push_map(&map_value)?
 			},
 			_ =>
 				return Err(BuilderError::IllegalArgument("Unsupported parameter type".to_string())),
@@ -119,8 +157,10 @@ impl ScriptBuilder {
 
 	// Additional push_* methods
 	pub fn push_integer(&mut self, value: BigInt) -> Result<&mut Self, BuilderError> {
-		if value >= BigInt::from(-1) && value <= BigInt::from(16) {
-			self.op_code(vec![OpCode::try_from(value.to_i32().unwrap() as u8 + OpCode::Push0 as u8).unwrap()].as_slice());
+		if let Some(small) = value.to_i32().filter(|value| (-1..=16).contains(value)) {
+			let opcode = OpCode::try_from((OpCode::Push0 as i32 + small) as u8)
+				.map_err(|_| BuilderError::InvalidOperation)?;
+			self.op_code(&[opcode]);
 		} else {
 			let bytes = value.to_signed_bytes_le();
 
@@ -160,25 +200,28 @@ impl ScriptBuilder {
 	// Push data handling
 
 	pub fn push_data(&mut self, data: Vec<u8>) -> Result<&mut Self, BuilderError> {
+		Ok(self.emit_push_data(&data))
+	}
+
+	fn emit_push_data(&mut self, data: &[u8]) -> &mut Self {
 		match data.len() {
 			0..=0xff => {
 				self.op_code(&[OpCode::PushData1]);
 				self.script.write_u8(data.len() as u8);
-				let _ = self.script.write_bytes(&data);
+				let _ = self.script.write_bytes(data);
 			},
 			0x100..=0xffff => {
 				self.op_code(&[OpCode::PushData2]);
 				self.script.write_u16(data.len() as u16);
-				let _ = self.script.write_bytes(&data);
+				let _ = self.script.write_bytes(data);
 			},
 			_ => {
 				self.op_code(&[OpCode::PushData4]);
 				self.script.write_u32(data.len() as u32);
-				let _ = self.script.write_bytes(&data);
+				let _ = self.script.write_bytes(data);
 			},
-			// _ => return Err(BuilderError::IllegalArgument("Data too long".to_string())),
 		}
-		Ok(self)
+		self
 	}
 
 	pub fn push_bool(&mut self, b: bool) -> &mut Self {
@@ -194,7 +237,7 @@ impl ScriptBuilder {
 		if arr.is_empty() {
 			self.op_code(&[OpCode::NewArray0]);
 		} else {
-			self.push_params(arr);
+			self.push_params(arr)?;
 		};
 		Ok(self)
 	}
@@ -206,11 +249,11 @@ impl ScriptBuilder {
 		for (k, v) in map {
 			let kk: ContractParameter = k.clone().into();
 			let vv: ContractParameter = v.clone().into();
-			self.push_param(&vv).unwrap();
-			self.push_param(&kk).unwrap();
+			self.push_param(&vv)?;
+			self.push_param(&kk)?;
 		}
 
-		Ok(self.push_integer(BigInt::from(map.len())).unwrap().op_code(&[OpCode::PackMap]))
+		Ok(self.push_integer(BigInt::from(map.len()))?.op_code(&[OpCode::PackMap]))
 	}
 
 	// Additional helper methods
@@ -225,8 +268,7 @@ impl ScriptBuilder {
 
 	pub fn build_verification_script(pub_key: &Secp256r1PublicKey) -> Bytes {
 		let mut sb = ScriptBuilder::new();
-		sb.push_data(pub_key.to_raw_bytes().to_vec())
-			.unwrap()
+		sb.emit_push_data(&pub_key.get_encoded(true))
 			.sys_call(InteropService::SystemCryptoCheckSig);
 		sb.to_bytes()
 	}
@@ -236,12 +278,12 @@ impl ScriptBuilder {
 		threshold: u8,
 	) -> Result<Bytes, BuilderError> {
 		let mut sb = ScriptBuilder::new();
-		sb.push_integer(BigInt::from(threshold)).unwrap();
+		sb.push_integer(BigInt::from(threshold))?;
 		pubkeys.sort_by(|a, b| a.to_raw_bytes().cmp(&b.to_raw_bytes()));
 		for pk in pubkeys.iter() {
-			sb.push_data(pk.to_raw_bytes().to_vec()).unwrap();
+			sb.push_data(pk.to_raw_bytes().to_vec())?;
 		}
-		sb.push_integer(BigInt::from(pubkeys.len())).unwrap();
+		sb.push_integer(BigInt::from(pubkeys.len()))?;
 		sb.sys_call(InteropService::SystemCryptoCheckMultiSig);
 		Ok(sb.to_bytes())
 	}
@@ -253,12 +295,9 @@ impl ScriptBuilder {
 	) -> Result<Bytes, BuilderError> {
 		let mut sb = ScriptBuilder::new();
 		sb.op_code(&[OpCode::Abort])
-			.push_data(sender.to_vec())
-			.unwrap()
-			.push_integer(BigInt::from(nef_checksum))
-			.unwrap()
-			.push_data(name.as_bytes().to_vec())
-			.unwrap();
+			.push_data(sender.to_le_vec())?
+			.push_integer(BigInt::from(nef_checksum))?
+			.push_data(name.as_bytes().to_vec())?;
 		Ok(sb.to_bytes())
 	}
 	pub fn build_contract_call_and_unwrap_iterator(
@@ -269,9 +308,9 @@ impl ScriptBuilder {
 		call_flags: CallFlags,
 	) -> Result<Bytes, BuilderError> {
 		let mut sb = Self::new();
-		sb.push_integer(BigInt::from(max_items)).unwrap();
+		sb.push_integer(BigInt::from(max_items))?;
 
-		sb.contract_call(contract_hash, method, params, call_flags).unwrap();
+		sb.contract_call(contract_hash, method, params, call_flags)?;
 
 		sb.op_code(&[OpCode::NewArray]);
 
@@ -313,6 +352,102 @@ impl ScriptBuilder {
 		Ok(script)
 	}
 
+	/// Builds a script performing all `transfers` in one transaction.
+	///
+	/// The result of every `transfer` call is asserted, so the script faults and no tokens are
+	/// moved if any of the transfers fails.
+	pub fn build_multi_transfer(transfers: &[TransferIntent]) -> Result<Bytes, BuilderError> {
+		if transfers.is_empty() {
+			return Err(BuilderError::IllegalArgument("No transfers to build".to_string()))
+		}
+		let mut sb = Self::new();
+		for transfer in transfers {
+			if transfer.amount < 0 {
+				return Err(BuilderError::IllegalArgument(format!(
+					"The amount must not be negative, got {}",
+					transfer.amount
+				)))
+			}
+			let params = [
+				ContractParameter::hash160(&transfer.from),
+				ContractParameter::hash160(&transfer.to),
+				ContractParameter::integer(transfer.amount),
+				transfer
+					.data
+					.clone()
+					.unwrap_or_else(|| ContractParameter::new(ContractParameterType::Any)),
+			];
+			sb.contract_call(&transfer.token, "transfer", &params, CallFlags::All)?
+				.op_code(&[OpCode::Assert]);
+		}
+		Ok(sb.to_bytes())
+	}
+
+	/// Pushes `point` decoded by `bls12381Deserialize` of `CryptoLib`, the form the other
+	/// BLS12-381 methods take their points in.
+	pub fn bls12381_deserialize(
+		&mut self,
+		point: &Bls12381Point,
+	) -> Result<&mut Self, BuilderError> {
+		self.push_data(point.to_bytes().to_vec())?;
+		self.call_crypto_lib("bls12381Deserialize", 1)
+	}
+
+	/// Replaces the point on top of the stack, e.g. the result of [`Self::bls12381_add`], with
+	/// its encoding returned by `bls12381Serialize`, so it can be returned by the script.
+	pub fn bls12381_serialize(&mut self) -> Result<&mut Self, BuilderError> {
+		self.call_crypto_lib("bls12381Serialize", 1)
+	}
+
+	/// Pushes the sum of the points `a` and `b`, which have to be of the same group.
+	pub fn bls12381_add(
+		&mut self,
+		a: &Bls12381Point,
+		b: &Bls12381Point,
+	) -> Result<&mut Self, BuilderError> {
+		// the arguments are pushed in reverse order, `PACK` takes the top item first
+		self.bls12381_deserialize(b)?.bls12381_deserialize(a)?;
+		self.call_crypto_lib("bls12381Add", 2)
+	}
+
+	/// Pushes `point` multiplied by `scalar`, or by `-scalar` if `negative` is true.
+	pub fn bls12381_mul(
+		&mut self,
+		point: &Bls12381Point,
+		scalar: &Bls12381Scalar,
+		negative: bool,
+	) -> Result<&mut Self, BuilderError> {
+		self.push_bool(negative).push_data(scalar.to_bytes().to_vec())?;
+		self.bls12381_deserialize(point)?;
+		self.call_crypto_lib("bls12381Mul", 3)
+	}
+
+	/// Pushes the pairing of `g1` and `g2`, an element of Gt.
+	pub fn bls12381_pairing(
+		&mut self,
+		g1: &Bls12381G1,
+		g2: &Bls12381G2,
+	) -> Result<&mut Self, BuilderError> {
+		self.bls12381_deserialize(&(*g2).into())?.bls12381_deserialize(&(*g1).into())?;
+		self.call_crypto_lib("bls12381Pairing", 2)
+	}
+
+	/// Calls `method` of `CryptoLib` with the `arg_count` items on top of the stack as
+	/// arguments, the first argument on top.
+	fn call_crypto_lib(
+		&mut self,
+		method: &str,
+		arg_count: usize,
+	) -> Result<&mut Self, BuilderError> {
+		let crypto_lib = natives::CRYPTO_LIB.hash;
+		self.push_integer(BigInt::from(arg_count))?
+			.op_code(&[OpCode::Pack])
+			.push_integer(BigInt::from(CallFlags::None.value()))?
+			.push_data(method.as_bytes().to_vec())?
+			.push_data(crypto_lib.to_le_vec())?;
+		Ok(self.sys_call(InteropService::SystemContractCall))
+	}
+
 	pub fn len(&self) -> usize {
 		self.script().size()
 	}
@@ -322,10 +457,13 @@ impl ScriptBuilder {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::core::script::script_reader::ScriptReader;
 	use hex_literal::hex;
+	use neo_config::TestConstants;
+	use neo_types::encoding;
 	use num_bigint::BigInt;
 	use num_traits::FromPrimitive;
-	use std::vec;
+	use std::{str::FromStr, vec};
 
 	#[test]
 	fn test_push_empty_array() {
@@ -354,6 +492,26 @@ mod tests {
 		assert_eq!(builder.to_bytes()[..5], hex!("0e00000100"));
 	}
 
+	#[test]
+	fn test_push_encoded_params() {
+		// byte arrays and keys are pushed as their bytes, not as their base64 or hex text
+		let mut builder = ScriptBuilder::new();
+		builder.push_param(&ContractParameter::byte_array(vec![0xAA, 0xBB])).unwrap();
+		assert_eq!(builder.to_bytes(), hex!("0c02aabb"));
+
+		let encoded = hex::decode(TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY).unwrap();
+		let key = Secp256r1PublicKey::from_bytes(&encoded).unwrap();
+		let mut builder = ScriptBuilder::new();
+		builder.push_param(&ContractParameter::public_key(&key)).unwrap();
+		assert_eq!(builder.to_bytes()[2..], encoded[..]);
+
+		let invalid = ContractParameter::with_value(
+			ContractParameterType::ByteArray,
+			ParameterValue::ByteArray("not base64!".to_string()),
+		);
+		assert!(ScriptBuilder::new().push_param(&invalid).is_err());
+	}
+
 	#[test]
 	fn test_push_string() {
 		let mut builder = ScriptBuilder::new();
@@ -374,6 +532,10 @@ mod tests {
 		builder.push_integer(BigInt::from(0)).unwrap();
 		assert_eq!(builder.to_bytes()[..1], vec![OpCode::Push0 as u8]);
 
+		let mut builder = ScriptBuilder::new();
+		builder.push_integer(BigInt::from(-1)).unwrap();
+		assert_eq!(builder.to_bytes()[..1], vec![OpCode::PushM1 as u8]);
+
 		let mut builder = ScriptBuilder::new();
 		builder.push_integer(BigInt::from(1)).unwrap();
 		assert_eq!(builder.to_bytes()[..1], vec![OpCode::Push1 as u8]);
@@ -400,18 +562,106 @@ mod tests {
 	}
 
 	#[test]
-	fn test_verification_script() {
-		// let pubkey1 = hex!("035fdb1d1f06759547020891ae97c729327853aeb1256b6fe0473bc2e9fa42ff50");
-		// let pubkey2 = hex!("03eda286d19f7ee0b472afd1163d803d620a961e1581a8f2704b52c0285f6e022d");
-		// let pubkey3 = hex!("03ac81ec17f2f15fd6d193182f927c5971559c2a32b9408a06fec9e711fb7ca02e");
-		//
-		// let script = ScriptBuilder::build_multisig_script(&[pubkey1, pubkey2, pubkey3], 2).unwrap();
-		//
-		// let expected = hex!("5221035fdb1d1f06759547020891ae97c729327853aeb1256b6fe0473bc2e9fa42ff50210"
-		//     "03ac81ec17f2f15fd6d193182f927c5971559c2a32b9408a06fec9e711fb7ca02e210"
-		//     "03eda286d19f7ee0b472afd1163d803d620a961e1581a8f2704b52c0285f6e022d53ae");
+	fn test_multi_transfer() {
+		let transfer = |token: u8, to: u8, amount| TransferIntent {
+			token: H160::repeat_byte(token),
+			from: H160::repeat_byte(0xaa),
+			to: H160::repeat_byte(to),
+			amount,
+			data: None,
+		};
+		let transfers = [transfer(1, 2, 100), transfer(3, 4, 5)];
+
+		let mut expected = ScriptBuilder::new();
+		for transfer in &transfers {
+			let params = [
+				ContractParameter::hash160(&transfer.from),
+				ContractParameter::hash160(&transfer.to),
+				ContractParameter::integer(transfer.amount),
+				ContractParameter::new(ContractParameterType::Any),
+			];
+			expected
+				.contract_call(&transfer.token, "transfer", &params, CallFlags::All)
+				.unwrap()
+				.op_code(&[OpCode::Assert]);
+		}
+		assert_eq!(ScriptBuilder::build_multi_transfer(&transfers).unwrap(), expected.to_bytes());
+		assert!(ScriptBuilder::build_multi_transfer(&[]).is_err());
+		assert!(ScriptBuilder::build_multi_transfer(&[transfer(1, 2, -1)]).is_err());
+	}
+
+	#[test]
+	fn test_transfer_script() {
+		let gas = natives::GAS_TOKEN.hash;
+		let from = H160::from_address("NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke").unwrap();
+		let to = H160::from_str("23ba2703c53263e8d6e522dc32203339dcd8eee9").unwrap();
+		let params = [
+			ContractParameter::hash160(&from),
+			ContractParameter::hash160(&to),
+			ContractParameter::integer(1),
+			ContractParameter::new(ContractParameterType::Any),
+		];
+		let mut builder = ScriptBuilder::new();
+		builder.contract_call(&gas, "transfer", &params, CallFlags::All).unwrap();
+
+		// the script the C# `EmitDynamicCall` emits for the same transfer, the arguments in
+		// reverse and the hashes little-endian
+		let expected = concat!(
+			"0b110c14e9eed8dc39332032dc22e5d6e86332c50327ba230c1409a55874c2da4b86e5d49ff530a1b1",
+			"53eb12c7d614c01f0c087472616e736665720c14cf76e28bd0062c4a478ee35561011319f3cfa4d241",
+			"627d5b52",
+		);
+		assert_eq!(hex::encode(builder.to_bytes()), expected);
+
+		let calls = ScriptReader::contract_calls(&builder.to_bytes()).unwrap();
+		assert_eq!(calls[0].as_nep17_transfer(), Some((gas, from, to, 1)));
+	}
+
+	#[test]
+	fn test_contract_call_without_params() {
+		let oracle = natives::ORACLE_CONTRACT.hash;
+		let mut builder = ScriptBuilder::new();
+		builder.contract_call(&oracle, "finish", &[], CallFlags::All).unwrap();
+		// the fixed script of oracle responses
+		assert_eq!(
+			encoding::base64_encode(builder.to_bytes()),
+			"wh8MBmZpbmlzaAwUWIcXEX4KqBByr6tx0t2J/nxLkv5BYn1bUg=="
+		);
+	}
 
-		// assert_eq!(script, expected);
+	#[test]
+	fn test_bls12381_add() {
+		let g1: Bls12381Point = Bls12381G1::generator().into();
+		let mut builder = ScriptBuilder::new();
+		builder.bls12381_add(&g1, &g1).unwrap().bls12381_serialize().unwrap();
+
+		let mut deserialize = ScriptBuilder::new();
+		deserialize
+			.contract_call(
+				&natives::CRYPTO_LIB.hash,
+				"bls12381Deserialize",
+				&[ContractParameter::byte_array(g1.to_bytes().to_vec())],
+				CallFlags::None,
+			)
+			.unwrap();
+		let deserialize = deserialize.to_bytes();
+		let script = builder.to_bytes();
+		assert_eq!(script[..deserialize.len()], deserialize[..]);
+		assert_eq!(script[deserialize.len()..2 * deserialize.len()], deserialize[..]);
+		// the call of `bls12381Add` starts with `PUSH2 PACK`
+		assert_eq!(
+			script[2 * deserialize.len()..][..2],
+			[OpCode::Push2.opcode(), OpCode::Pack.opcode()]
+		);
+	}
+
+	#[test]
+	fn test_verification_script() {
+		let encoded = hex::decode(TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY).unwrap();
+		let pubkey = Secp256r1PublicKey::from_bytes(&encoded).unwrap();
+		let script = ScriptBuilder::build_verification_script(&pubkey);
+		assert_eq!(hex::encode(&script), TestConstants::DEFAULT_ACCOUNT_VERIFICATION_SCRIPT);
+		assert_eq!(script[script.len() - 4..], InteropService::SystemCryptoCheckSig.hash());
 	}
 
 	#[test]
@@ -6,35 +6,95 @@ use neo_types::{
 	op_code::{OpCode, OperandSize},
 	Bytes,
 };
+use primitive_types::H160;
 use rustc_serialize::hex::ToHex;
 use std::hash::Hash;
-use tokio::io::AsyncReadExt;
+
+/// A constant pushed by a script, see [`ScriptReader::contract_calls`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptValue {
+	Null,
+	Boolean(bool),
+	/// Integers that do not fit into an `i128` are not decoded
+	Integer(i128),
+	Bytes(Bytes),
+	Array(Vec<ScriptValue>),
+}
+
+impl ScriptValue {
+	/// The script hash pushed as little-endian bytes, the order of hashes in scripts
+	pub fn as_hash160(&self) -> Option<H160> {
+		match self {
+			ScriptValue::Bytes(bytes) if bytes.len() == 20 => {
+				let mut bytes = bytes.clone();
+				bytes.reverse();
+				Some(H160::from_slice(&bytes))
+			},
+			_ => None,
+		}
+	}
+
+	pub fn as_int(&self) -> Option<i128> {
+		match self {
+			ScriptValue::Integer(value) => Some(*value),
+			_ => None,
+		}
+	}
+}
+
+/// A `System.Contract.Call` of a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractCall {
+	pub contract: H160,
+	pub method: String,
+	pub call_flags: u8,
+	/// The arguments in the order the called method receives them
+	pub args: Vec<ScriptValue>,
+}
+
+impl ContractCall {
+	/// The token, sender, recipient and amount if this is a NEP-17 `transfer`.
+	pub fn as_nep17_transfer(&self) -> Option<(H160, H160, H160, i128)> {
+		match (self.method.as_str(), self.args.as_slice()) {
+			("transfer", [from, to, amount, _data]) =>
+				Some((self.contract, from.as_hash160()?, to.as_hash160()?, amount.as_int()?)),
+			_ => None,
+		}
+	}
+}
 
 pub struct ScriptReader;
 
 impl ScriptReader {
-	pub fn get_interop_service_code(_hash: String) -> Option<InteropService> {
-		InteropService::from_hash(_hash)
+	/// The interop service called by a `Syscall` with the operand `hash`
+	pub fn get_interop_service_code(hash: &[u8]) -> Option<InteropService> {
+		InteropService::from_hash(hash)
 	}
+
 	pub fn convert_to_op_code_string(script: &Bytes) -> String {
 		let mut reader = Decoder::new(script);
 		let mut result = String::new();
 		while reader.pointer().clone() < script.len() {
-			if let Ok(op_code) = OpCode::try_from(reader.read_u8()) {
+			let Ok(byte) = reader.read_u8() else { break };
+			if let Ok(op_code) = OpCode::try_from(byte) {
 				result.push_str(&format!("{:?}", op_code).to_uppercase());
 				if let Some(size) = op_code.operand_size() {
+					// a truncated operand ends the script
 					if size.size().clone() > 0 {
-						result.push_str(&format!(
-							" {}",
-							reader.read_bytes(size.size().clone() as usize).unwrap().to_hex()
-						));
+						let Ok(operand) = reader.read_slice(size.size().clone() as usize) else {
+							break
+						};
+						match Self::get_interop_service_code(operand) {
+							Some(service) if op_code == OpCode::Syscall =>
+								result.push_str(&format!(" {service}")),
+							_ => result.push_str(&format!(" {}", operand.to_hex())),
+						}
 					} else if size.prefix_size().clone() > 0 {
-						let prefix_size = Self::get_prefix_size(&mut reader, size).unwrap();
-						result.push_str(&format!(
-							" {} {}",
-							prefix_size,
-							reader.read_bytes(prefix_size).unwrap().to_hex()
-						));
+						let Ok(prefix_size) = Self::get_prefix_size(&mut reader, size) else {
+							break
+						};
+						let Ok(operand) = reader.read_slice(prefix_size) else { break };
+						result.push_str(&format!(" {} {}", prefix_size, operand.to_hex()));
 					}
 				}
 				result.push('\n');
@@ -43,14 +103,190 @@ impl ScriptReader {
 		result
 	}
 
+	/// Decodes the contract calls of `script` whose contract, method, call flags and arguments are
+	/// pushed as constants, as in the scripts of [`ScriptBuilder::contract_call`].
+	///
+	/// The pushes are evaluated like the VM does, so the arguments are in the order the called
+	/// method receives them. Fails if a contract call takes a value computed by the script, or the
+	/// script jumps, since the calls can then not be known without running it.
+	///
+	/// [`ScriptBuilder::contract_call`]: super::script_builder::ScriptBuilder::contract_call
+	pub fn contract_calls(script: &[u8]) -> Result<Vec<ContractCall>, BuilderError> {
+		let mut reader = Decoder::new(script);
+		// `None` for a value computed by the script
+		let mut stack: Vec<Option<ScriptValue>> = Vec::new();
+		let mut calls = Vec::new();
+
+		while reader.available() > 0 {
+			let offset = script.len() - reader.available();
+			let byte = reader.read_u8()?;
+			let op_code = OpCode::try_from(byte).map_err(|_| {
+				BuilderError::InvalidScript(format!("invalid opcode {byte:#04x} at {offset}"))
+			})?;
+			let operand = match op_code.operand_size() {
+				Some(size) if *size.prefix_size() > 0 => {
+					let length = Self::get_prefix_size(&mut reader, size)?;
+					reader.read_slice(length)?
+				},
+				Some(size) => reader.read_slice(*size.size() as usize)?,
+				None => &[],
+			};
+
+			match op_code {
+				OpCode::PushInt8
+				| OpCode::PushInt16
+				| OpCode::PushInt32
+				| OpCode::PushInt64
+				| OpCode::PushInt128
+				| OpCode::PushInt256 => stack.push(Self::decode_int(operand).map(ScriptValue::Integer)),
+				OpCode::PushTrue => stack.push(Some(ScriptValue::Boolean(true))),
+				OpCode::PushFalse => stack.push(Some(ScriptValue::Boolean(false))),
+				OpCode::PushNull => stack.push(Some(ScriptValue::Null)),
+				OpCode::PushData1 | OpCode::PushData2 | OpCode::PushData4 =>
+					stack.push(Some(ScriptValue::Bytes(operand.to_vec()))),
+				OpCode::PushM1 => stack.push(Some(ScriptValue::Integer(-1))),
+				op_code
+					if (OpCode::Push0 as u8..=OpCode::Push16 as u8).contains(&(op_code as u8)) =>
+					stack.push(Some(ScriptValue::Integer(
+						(op_code as u8 - OpCode::Push0 as u8).into(),
+					))),
+				OpCode::NewArray0 => stack.push(Some(ScriptValue::Array(vec![]))),
+				OpCode::Pack => {
+					let count = stack.pop().flatten().and_then(|count| count.as_int());
+					let items = count
+						.and_then(|count| usize::try_from(count).ok())
+						.filter(|count| *count <= stack.len())
+						.map(|count| stack.split_off(stack.len() - count));
+					if items.is_none() {
+						// the number of packed items is unknown
+						stack = vec![None; stack.len()];
+					}
+					// the first item of the array is the top of the stack
+					let array = items.and_then(|items| items.into_iter().rev().collect());
+					stack.push(array.map(ScriptValue::Array));
+				},
+				OpCode::Drop => {
+					stack.pop();
+				},
+				OpCode::Syscall if operand == InteropService::SystemContractCall.hash() => {
+					let mut pop = || stack.pop().flatten();
+					let contract = pop().and_then(|contract| contract.as_hash160());
+					let method = pop().and_then(|method| match method {
+						ScriptValue::Bytes(method) => String::from_utf8(method).ok(),
+						_ => None,
+					});
+					let call_flags = pop()
+						.and_then(|flags| flags.as_int())
+						.and_then(|flags| u8::try_from(flags).ok());
+					let args = pop().and_then(|args| match args {
+						ScriptValue::Array(args) => Some(args),
+						_ => None,
+					});
+					let (Some(contract), Some(method), Some(call_flags), Some(args)) =
+						(contract, method, call_flags, args)
+					else {
+						return Err(BuilderError::InvalidScript(format!(
+							"undecodable contract call at {offset}"
+						)))
+					};
+					calls.push(ContractCall { contract, method, call_flags, args });
+					// the return value of the call
+					stack.push(None);
+				},
+				OpCode::Ret => break,
+				op_code
+					if (OpCode::Jmp as u8..=OpCode::CallT as u8).contains(&(op_code as u8))
+						|| (OpCode::Try as u8..=OpCode::EndFinally as u8)
+							.contains(&(op_code as u8)) =>
+					return Err(BuilderError::InvalidScript(format!(
+						"unsupported control flow {op_code:?} at {offset}"
+					))),
+				// anything else may rearrange the stack
+				_ => stack = vec![None; stack.len()],
+			}
+		}
+		Ok(calls)
+	}
+
+	/// Decodes a little endian two's complement integer that fits into an `i128`.
+	fn decode_int(operand: &[u8]) -> Option<i128> {
+		let negative = operand.last().map_or(false, |byte| byte & 0x80 != 0);
+		let extension = if negative { 0xff } else { 0x00 };
+		let (low, high) = operand.split_at(operand.len().min(16));
+		// the high bytes must only extend the sign of the low ones
+		if high.iter().any(|byte| *byte != extension) {
+			return None
+		}
+		let mut bytes = [extension; 16];
+		bytes[..low.len()].copy_from_slice(low);
+		let value = i128::from_le_bytes(bytes);
+		(value.is_negative() == negative).then_some(value)
+	}
+
 	fn get_prefix_size(reader: &mut Decoder, size: OperandSize) -> Result<usize, BuilderError> {
 		match size.prefix_size() {
-			1 => Ok(reader.read_u8() as usize),
-			2 => Ok(reader.read_i16() as usize),
-			4 => Ok(reader.read_i32() as usize),
+			1 => Ok(reader.read_u8()? as usize),
+			2 => Ok(reader.read_i16()? as usize),
+			4 => Ok(reader.read_i32()? as usize),
 			_ => Err(BuilderError::UnsupportedOperation(
 				"Only operand prefix sizes 1, 2, and 4 are supported".to_string(),
 			)),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_types::script_hash::ScriptHashExt;
+
+	#[test]
+	fn test_contract_calls() {
+		let token = H160::repeat_byte(0x01);
+		let to = H160::repeat_byte(0x02);
+		let from = H160::repeat_byte(0x03);
+		let mut script = vec![0x0b]; // data: PushNull
+		script.extend([0x01, 0xe8, 0x03]); // amount: PushInt16 1000
+		script.extend([0x0c, 0x14]); // to
+		script.extend(to.to_le_vec());
+		script.extend([0x0c, 0x14]); // from
+		script.extend(from.to_le_vec());
+		script.extend([0x14, 0xc0, 0x1f]); // Push4, Pack, Push15 (call flags All)
+		script.extend([0x0c, 0x08]);
+		script.extend(b"transfer");
+		script.extend([0x0c, 0x14]);
+		script.extend(token.to_le_vec());
+		script.extend([0x41, 0x62, 0x7d, 0x5b, 0x52]); // Syscall System.Contract.Call
+
+		let calls = ScriptReader::contract_calls(&script).unwrap();
+		assert_eq!(calls.len(), 1);
+		assert_eq!(calls[0].method, "transfer");
+		assert_eq!(calls[0].call_flags, 0x0f);
+		assert_eq!(calls[0].as_nep17_transfer(), Some((token, from, to, 1000)));
+
+		// the sender and recipient are swapped by the script
+		script.insert(48, 0x50); // Swap
+		assert!(ScriptReader::contract_calls(&script).is_err());
+	}
+
+	#[test]
+	fn test_disassemble_syscall() {
+		let script = vec![0x41, 0x62, 0x7d, 0x5b, 0x52, 0x41, 0x01, 0x02, 0x03, 0x04];
+		assert_eq!(
+			ScriptReader::convert_to_op_code_string(&script),
+			"SYSCALL System.Contract.Call\nSYSCALL 01020304\n"
+		);
+	}
+
+	#[test]
+	fn test_decode_int() {
+		assert_eq!(ScriptReader::decode_int(&[0xff]), Some(-1));
+		assert_eq!(ScriptReader::decode_int(&[0x00, 0x80]), Some(-32768));
+		let mut max = [0u8; 32];
+		max[..15].fill(0xff);
+		max[15] = 0x7f;
+		assert_eq!(ScriptReader::decode_int(&max), Some(i128::MAX));
+		max[16] = 0x01;
+		assert_eq!(ScriptReader::decode_int(&max), None);
+	}
+}
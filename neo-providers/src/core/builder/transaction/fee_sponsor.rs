@@ -0,0 +1,220 @@
+//! Fee sponsorship, a distinct account paying the fees of a transaction.
+//!
+//! The first signer of a transaction is its sender, which pays the system and network fee. A
+//! [`FeeSponsor`] is placed first with the `None` scope, so its witness only pays the fees and
+//! cannot be used by the contracts the transaction calls.
+use crate::core::{
+	account::{Account, AccountTrait},
+	transaction::{
+		signers::{account_signer::AccountSigner, signer::Signer},
+		transaction::Transaction,
+		transaction_error::TransactionError,
+		witness::Witness,
+		witness_scope::WitnessScope,
+	},
+};
+use async_trait::async_trait;
+use neo_types::Bytes;
+use primitive_types::H160;
+use std::{fmt::Debug, sync::Arc};
+
+/// Creates the witness of a fee sponsor whose key is held elsewhere, e.g. by a remote signing
+/// service.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait SponsorSigner: Send + Sync + Debug {
+	/// Creates the witness of the sponsor for `tx`, whose hash data on the target network, see
+	/// [`Transaction::get_hash_data`], is `hash_data`.
+	async fn sponsor_witness(
+		&self,
+		tx: &Transaction,
+		hash_data: Bytes,
+	) -> Result<Witness, TransactionError>;
+}
+
+/// An account paying the fees of transactions signed by others.
+///
+/// # Example
+///
+/// ```
+/// use neo_providers::core::{
+/// 	account::{Account, AccountTrait},
+/// 	transaction::{
+/// 		fee_sponsor::FeeSponsor,
+/// 		signers::{account_signer::AccountSigner, signer::Signer},
+/// 	},
+/// };
+///
+/// let sponsor = FeeSponsor::new(Account::create().unwrap());
+/// let sender = Account::create().unwrap();
+/// let mut signers = vec![Signer::from(AccountSigner::called_by_entry(&sender).unwrap())];
+/// sponsor.apply(&mut signers).unwrap();
+/// assert_eq!(*signers[0].get_signer_hash(), sponsor.script_hash());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FeeSponsor {
+	account: Account,
+	signer: Option<Arc<dyn SponsorSigner>>,
+}
+
+impl FeeSponsor {
+	/// A sponsor signing with the key pair of `account`
+	pub fn new(account: Account) -> Self {
+		Self { account, signer: None }
+	}
+
+	/// A sponsor whose witness is created by `signer`. `account` only needs its verification
+	/// script, which is used to calculate the network fee.
+	pub fn remote(account: Account, signer: impl SponsorSigner + 'static) -> Self {
+		Self { account, signer: Some(Arc::new(signer)) }
+	}
+
+	/// The account of the sponsor
+	pub fn account(&self) -> &Account {
+		&self.account
+	}
+
+	/// The script hash of the account of the sponsor
+	pub fn script_hash(&self) -> H160 {
+		self.account.get_script_hash()
+	}
+
+	/// The signer of the sponsor, with the `None` scope
+	pub fn signer(&self) -> Result<Signer, TransactionError> {
+		Ok(AccountSigner::none(&self.account)?.into())
+	}
+
+	/// Makes the sponsor the first signer of `signers`.
+	///
+	/// A signer of the sponsor already among `signers` is moved to the front and keeps its
+	/// scopes, since the transaction may need its witness besides paying the fees.
+	pub fn apply(&self, signers: &mut Vec<Signer>) -> Result<(), TransactionError> {
+		let hash = self.script_hash();
+		match signers.iter().position(|signer| *signer.get_signer_hash() == hash) {
+			Some(index) => {
+				let signer = signers.remove(index);
+				signers.insert(0, signer);
+			},
+			None => signers.insert(0, self.signer()?),
+		}
+		self.validate(signers)
+	}
+
+	/// Checks that the sponsor is the first of `signers`, with scopes the node accepts.
+	///
+	/// `None` and `Global` cannot be combined with other scopes.
+	pub fn validate(&self, signers: &[Signer]) -> Result<(), TransactionError> {
+		let first = signers.first().ok_or(TransactionError::NoSigners)?;
+		if *first.get_signer_hash() != self.script_hash() {
+			return Err(TransactionError::SignerConfiguration(format!(
+				"The fee sponsor {:?} must be the first signer, not {:?}",
+				self.script_hash(),
+				first.get_signer_hash()
+			)))
+		}
+
+		let scopes = first.get_scopes();
+		if scopes.is_empty() {
+			return Err(TransactionError::SignerConfiguration(
+				"The fee sponsor has no witness scope".to_string(),
+			))
+		}
+		for exclusive in [WitnessScope::None, WitnessScope::Global] {
+			if scopes.contains(&exclusive) && scopes.len() > 1 {
+				return Err(TransactionError::SignerConfiguration(format!(
+					"The {exclusive} scope of the fee sponsor cannot be combined with other scopes"
+				)))
+			}
+		}
+		Ok(())
+	}
+
+	/// Creates the witness of the sponsor for `tx` on the network `network_magic`, with the
+	/// remote signer if any, and with the key pair of the account otherwise.
+	pub async fn witness(
+		&self,
+		tx: &Transaction,
+		network_magic: u32,
+	) -> Result<Witness, TransactionError> {
+		let hash_data = tx.get_hash_data(network_magic);
+		if let Some(signer) = &self.signer {
+			return signer.sponsor_witness(tx, hash_data).await
+		}
+
+		let key_pair = self.account.key_pair().as_ref().ok_or_else(|| {
+			TransactionError::SignerConfiguration(
+				"The fee sponsor holds no private key and has no remote signer".to_string(),
+			)
+		})?;
+		Witness::create(hash_data, key_pair)
+			.map_err(|e| TransactionError::SignerConfiguration(e.to_string()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::transaction::signers::signer::SignerTrait;
+
+	#[derive(Debug)]
+	struct Remote(Account);
+
+	#[async_trait]
+	impl SponsorSigner for Remote {
+		async fn sponsor_witness(
+			&self,
+			_tx: &Transaction,
+			hash_data: Bytes,
+		) -> Result<Witness, TransactionError> {
+			Witness::create(hash_data, self.0.key_pair().as_ref().unwrap())
+				.map_err(|e| TransactionError::SignerConfiguration(e.to_string()))
+		}
+	}
+
+	#[test]
+	fn test_apply_moves_sponsor_first() {
+		let sponsor = FeeSponsor::new(Account::create().unwrap());
+		let sender = Account::create().unwrap();
+		let mut signers = vec![
+			Signer::from(AccountSigner::called_by_entry(&sender).unwrap()),
+			Signer::from(AccountSigner::called_by_entry(sponsor.account()).unwrap()),
+		];
+		sponsor.apply(&mut signers).unwrap();
+		assert_eq!(signers.len(), 2);
+		assert_eq!(*signers[0].get_signer_hash(), sponsor.script_hash());
+		assert_eq!(signers[0].get_scopes(), &vec![WitnessScope::CalledByEntry]);
+
+		let mut signers = vec![Signer::from(AccountSigner::called_by_entry(&sender).unwrap())];
+		sponsor.apply(&mut signers).unwrap();
+		assert_eq!(signers[0].get_scopes(), &vec![WitnessScope::None]);
+		assert_eq!(*signers[1].get_signer_hash(), sender.get_script_hash());
+	}
+
+	#[test]
+	fn test_validate_scopes() {
+		let sponsor = FeeSponsor::new(Account::create().unwrap());
+		let mut signer = AccountSigner::global(sponsor.account()).unwrap();
+		signer.set_scopes(vec![WitnessScope::Global, WitnessScope::CalledByEntry]);
+		assert!(matches!(
+			sponsor.validate(&[signer.into()]),
+			Err(TransactionError::SignerConfiguration(_))
+		));
+
+		let sender = AccountSigner::called_by_entry(&Account::create().unwrap()).unwrap();
+		assert!(sponsor.validate(&[sender.into(), sponsor.signer().unwrap()]).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_remote_witness() {
+		let account = Account::create().unwrap();
+		let public_key = account.key_pair().as_ref().unwrap().public_key();
+		let watch_only = Account::from_public_key(&public_key).unwrap();
+		let sponsor = FeeSponsor::remote(watch_only, Remote(account.clone()));
+		let tx = Transaction { script: vec![0x40], ..Default::default() };
+
+		let witness = sponsor.witness(&tx, 860833102).await.unwrap();
+		let local = FeeSponsor::new(account).witness(&tx, 860833102).await.unwrap();
+		assert_eq!(witness.verification, local.verification);
+		assert!(FeeSponsor::new(sponsor.account().clone()).witness(&tx, 1).await.is_err());
+	}
+}
@@ -1,7 +1,10 @@
 use crate::core::{error::BuilderError, script::script_builder::ScriptBuilder};
 use getset::{Getters, Setters};
-use neo_codec::{encode::NeoSerializable, Decoder, Encoder};
-use neo_crypto::{hash::HashableForVec, key_pair::KeyPair, keys::Secp256r1Signature};
+use neo_codec::{
+	encode::{var_size, NeoSerializable},
+	Decoder, Encoder,
+};
+use neo_crypto::{key_pair::KeyPair, keys::Secp256r1Signature};
 use neo_types::Bytes;
 use serde_derive::{Deserialize, Serialize};
 
@@ -24,36 +27,31 @@ pub struct InvocationScript {
 }
 
 impl InvocationScript {
-	pub fn from_signature(signature: &Secp256r1Signature) -> Self {
+	pub fn from_signature(signature: &Secp256r1Signature) -> Result<Self, BuilderError> {
 		let mut builder = ScriptBuilder::new();
-		builder
-			.push_data(signature.to_raw_bytes().to_vec())
-			.expect("TODO: panic message");
-		Self { script: builder.to_bytes() }
+		builder.push_data(signature.to_raw_bytes().to_vec())?;
+		Ok(Self { script: builder.to_bytes() })
 	}
 
 	pub fn from_message_and_key_pair(
 		message: Bytes,
 		key_pair: &KeyPair,
 	) -> Result<Self, BuilderError> {
-		let message_hash = message.hash256();
-		let signature = key_pair.private_key.sign_tx(&message_hash)?;
+		// signing hashes the message with SHA-256, as the CheckSig of the node does
+		let signature = key_pair.private_key.sign_tx(&message)?;
 		let mut builder = ScriptBuilder::new();
 		// Convert signature to bytes
 		let signature_bytes = signature.to_raw_bytes();
-		builder.push_data(signature_bytes.to_vec()).expect("Incorrect signature length");
+		builder.push_data(signature_bytes.to_vec())?;
 		Ok(Self { script: builder.to_bytes() })
 	}
 
-	pub fn from_signatures(signatures: &[Secp256r1Signature]) -> Self {
+	pub fn from_signatures(signatures: &[Secp256r1Signature]) -> Result<Self, BuilderError> {
 		let mut builder = ScriptBuilder::new();
 		for signature in signatures {
-			let mut signature_bytes = signature.to_raw_bytes();
-			// signature.write_scalars(&mut signature_bytes).unwrap();
-
-			builder.push_data(signature_bytes.to_vec()).expect("Incorrect signature length");
+			builder.push_data(signature.to_raw_bytes().to_vec())?;
 		}
-		Self { script: builder.to_bytes() }
+		Ok(Self { script: builder.to_bytes() })
 	}
 }
 
@@ -61,7 +59,7 @@ impl NeoSerializable for InvocationScript {
 	type Error = BuilderError;
 
 	fn size(&self) -> usize {
-		self.script.len()
+		var_size(self.script.len()) + self.script.len()
 	}
 
 	fn encode(&self, writer: &mut Encoder) {
@@ -1,5 +1,6 @@
 pub mod call_flags;
 pub mod contract_parameters_context;
+pub mod fee_sponsor;
 pub mod invocation_script;
 pub mod oracle_response_code;
 pub mod signers;
@@ -8,6 +9,8 @@ pub mod transaction_attribute;
 pub mod transaction_builder;
 pub mod transaction_error;
 pub mod transaction_send_token;
+pub mod transaction_summary;
+pub mod transaction_template;
 pub mod verification_script;
 pub mod witness;
 pub mod witness_rule;
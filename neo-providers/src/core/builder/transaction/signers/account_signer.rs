@@ -1,9 +1,10 @@
 use getset::{Getters, Setters};
 use neo_crypto::keys::{PublicKeyExtension, Secp256r1PublicKey};
-use neo_types::{script_hash::ScriptHashExtension, *};
+use neo_types::*;
 
 use crate::core::{
 	account::{Account, AccountTrait},
+	error::BuilderError,
 	transaction::{
 		signers::signer::{SignerTrait, SignerType},
 		transaction_error::TransactionError,
@@ -48,7 +49,8 @@ impl NeoSerializable for AccountSigner {
 	type Error = TransactionError;
 
 	fn size(&self) -> usize {
-		let mut size: usize = NeoConstants::HASH160_SIZE as usize;
+		// the signer hash and the scope byte
+		let mut size = NeoConstants::HASH160_SIZE as usize + 1;
 		if self.scopes.contains(&WitnessScope::CustomContracts) {
 			size += self.allowed_contracts.var_size();
 		}
@@ -65,13 +67,13 @@ impl NeoSerializable for AccountSigner {
 		writer.write_serializable_fixed(&self.signer_hash);
 		writer.write_u8(WitnessScope::combine(&self.scopes));
 		if self.scopes.contains(&WitnessScope::CustomContracts) {
-			writer.write_serializable_variable_list(&self.allowed_contracts);
+			writer.write_serializable_list(&self.allowed_contracts);
 		}
 		if self.scopes.contains(&WitnessScope::CustomGroups) {
-			writer.write_serializable_variable_list(&self.allowed_groups);
+			writer.write_serializable_list(&self.allowed_groups);
 		}
 		if self.scopes.contains(&WitnessScope::WitnessRules) {
-			writer.write_serializable_variable_list(&self.rules);
+			writer.write_serializable_list(&self.rules);
 		}
 	}
 
@@ -79,19 +81,19 @@ impl NeoSerializable for AccountSigner {
 	where
 		Self: Sized,
 	{
-		let signer_hash = reader.read_serializable::<H160>().unwrap();
-		let scopes = WitnessScope::split(reader.read_u8());
+		let signer_hash = reader.read_serializable::<H160>()?;
+		let scopes = WitnessScope::split(reader.read_u8()?);
 		let mut allowed_contracts = vec![];
 		let mut allowed_groups = vec![];
 		let mut rules = vec![];
 		if scopes.contains(&WitnessScope::CustomContracts) {
-			allowed_contracts = reader.read_serializable_list::<H160>().unwrap();
+			allowed_contracts = reader.read_serializable_list::<H160>()?;
 		}
 		if scopes.contains(&WitnessScope::CustomGroups) {
-			allowed_groups = reader.read_serializable_list::<Secp256r1PublicKey>().unwrap();
+			allowed_groups = reader.read_serializable_list::<Secp256r1PublicKey>()?;
 		}
 		if scopes.contains(&WitnessScope::WitnessRules) {
-			rules = reader.read_serializable_list::<WitnessRule>().unwrap();
+			rules = reader.read_serializable_list::<WitnessRule>()?;
 		}
 		Ok(Self {
 			signer_hash,
@@ -99,7 +101,7 @@ impl NeoSerializable for AccountSigner {
 			allowed_contracts,
 			allowed_groups,
 			rules,
-			account: Account::from_address(signer_hash.to_address().as_str()).unwrap(),
+			account: Account::from_script_hash(&signer_hash)?,
 			scope: WitnessScope::None,
 		})
 	}
@@ -192,7 +194,7 @@ impl AccountSigner {
 	fn new(account: &Account, scope: WitnessScope) -> Self {
 		Self {
 			signer_hash: account.get_script_hash().clone(),
-			scopes: vec![],
+			scopes: vec![scope],
 			allowed_contracts: vec![],
 			allowed_groups: vec![],
 			rules: vec![],
@@ -201,34 +203,119 @@ impl AccountSigner {
 		}
 	}
 
+	/// A signer whose witness is only valid for paying the transaction fees.
 	pub fn none(account: &Account) -> Result<Self, TransactionError> {
 		Ok(Self::new(account, WitnessScope::None))
 	}
 
+	/// Like [`AccountSigner::none`], for the account with the script hash `account_hash`.
 	pub fn none_hash160(account_hash: H160) -> Result<Self, TransactionError> {
-		let account = Account::from_address(account_hash.to_address().as_str()).unwrap();
+		let account = Account::from_script_hash(&account_hash)?;
 		Ok(Self::new(&account, WitnessScope::None))
 	}
 
+	/// A signer whose witness is only valid in contracts called by the entry script, the most
+	/// common scope.
 	pub fn called_by_entry(account: &Account) -> Result<Self, TransactionError> {
 		Ok(Self::new(account, WitnessScope::CalledByEntry))
 	}
 
+	/// Like [`AccountSigner::called_by_entry`], for the account with the script hash
+	/// `account_hash`.
 	pub fn called_by_entry_hash160(account_hash: H160) -> Result<Self, TransactionError> {
-		let account = Account::from_address(account_hash.to_address().as_str()).unwrap();
+		let account = Account::from_script_hash(&account_hash)?;
 		Ok(Self::new(&account, WitnessScope::CalledByEntry))
 	}
 
-	pub fn global(account: Account) -> Result<Self, TransactionError> {
-		Ok(Self::new(&account, WitnessScope::Global))
+	/// A signer whose witness is valid everywhere. Only use it with trusted scripts.
+	pub fn global(account: &Account) -> Result<Self, TransactionError> {
+		Ok(Self::new(account, WitnessScope::Global))
 	}
 
+	/// Like [`AccountSigner::global`], for the account with the script hash `account_hash`.
 	pub fn global_hash160(account_hash: H160) -> Result<Self, TransactionError> {
-		let account = Account::from_address(account_hash.to_address().as_str()).unwrap();
+		let account = Account::from_script_hash(&account_hash)?;
 		Ok(Self::new(&account, WitnessScope::Global))
 	}
 
+	/// Also allows the witness in the contract `hash`.
+	///
+	/// ```
+	/// # use neo_providers::core::{
+	/// # 	account::Account, transaction::signers::account_signer::AccountSigner,
+	/// # };
+	/// # use primitive_types::H160;
+	/// # fn foo(account: &Account, token: H160) -> Result<(), Box<dyn std::error::Error>> {
+	/// let signer = AccountSigner::called_by_entry(account)?.allow_contract(token)?;
+	/// # Ok(()) }
+	/// ```
+	pub fn allow_contract(mut self, hash: H160) -> Result<Self, BuilderError> {
+		self.set_allowed_contracts(vec![hash])?;
+		Ok(self)
+	}
+
+	/// Also allows the witness in the contracts of the group `group`.
+	pub fn allow_group(mut self, group: Secp256r1PublicKey) -> Result<Self, BuilderError> {
+		self.set_allowed_groups(vec![group])?;
+		Ok(self)
+	}
+
+	/// Adds the witness rule `rule`, e.g. built with
+	/// [`WitnessRuleBuilder`](crate::core::transaction::witness_rule::witness_rule_builder::WitnessRuleBuilder).
+	pub fn with_rule(mut self, rule: WitnessRule) -> Result<Self, BuilderError> {
+		self.set_rules(vec![rule])?;
+		Ok(self)
+	}
+
 	pub fn is_multi_sig(&self) -> bool {
 		matches!(&self.account.verification_script(), Some(script) if script.is_multi_sig())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_crypto::key_pair::KeyPair;
+
+	fn account() -> Account {
+		Account::from_key_pair(KeyPair::new_random(), None, None).unwrap()
+	}
+
+	#[test]
+	fn test_scopes() {
+		let account = account();
+		let signer = AccountSigner::called_by_entry(&account).unwrap();
+		assert_eq!(signer.get_scopes(), &vec![WitnessScope::CalledByEntry]);
+		assert_eq!(signer.to_array()[20], 0x01);
+
+		let contract = H160::repeat_byte(0x01);
+		let signer = AccountSigner::none(&account).unwrap().allow_contract(contract).unwrap();
+		assert_eq!(signer.get_scopes(), &vec![WitnessScope::CustomContracts]);
+		assert_eq!(signer.get_allowed_contracts(), &vec![contract]);
+
+		let group = KeyPair::new_random().public_key();
+		let signer = AccountSigner::called_by_entry(&account)
+			.unwrap()
+			.allow_contract(contract)
+			.unwrap()
+			.allow_group(group)
+			.unwrap();
+		assert_eq!(
+			signer.get_scopes(),
+			&vec![
+				WitnessScope::CalledByEntry,
+				WitnessScope::CustomContracts,
+				WitnessScope::CustomGroups
+			]
+		);
+	}
+
+	#[test]
+	fn test_global_rejects_restrictions() {
+		let signer = AccountSigner::global(&account()).unwrap();
+		assert!(matches!(
+			signer.allow_contract(H160::zero()),
+			Err(BuilderError::TransactionConfiguration(_))
+		));
+	}
+}
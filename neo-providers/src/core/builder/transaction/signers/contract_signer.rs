@@ -1,11 +1,13 @@
 use crate::core::{
 	account::AccountTrait,
+	error::BuilderError,
 	transaction::{
 		signers::{
 			account_signer::AccountSigner,
 			signer::{SignerTrait, SignerType},
 		},
 		transaction_error::TransactionError,
+		witness::Witness,
 		witness_rule::witness_rule::WitnessRule,
 		witness_scope::WitnessScope,
 	},
@@ -21,6 +23,74 @@ use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
+/// A deployed contract as signer of a transaction, e.g. to withdraw the assets it holds.
+///
+/// The node verifies the witness of a contract by calling its `verify` method with the arguments
+/// pushed by the invocation script of the witness. These are the verification parameters of the
+/// signer, see [`ContractSigner::with_verify_params`], and [`ContractSigner::witness`] builds the
+/// witness from them. The network fee includes the GAS consumed by `verify`, which the node
+/// calculates when it runs the method, see `TransactionBuilder::get_network_fee`.
+///
+/// # Withdrawing from a contract
+///
+/// A vault contract holding GAS lets its owner withdraw, with a `verify(owner)` method checking
+/// the witness of `owner`. The transfer is signed by the owner, who pays the fees, and by the
+/// vault, from whose address the GAS is sent:
+///
+/// ```no_run
+/// use neo_providers::{
+/// 	core::{
+/// 		account::{Account, AccountTrait},
+/// 		script::script_builder::ScriptBuilder,
+/// 		transaction::{
+/// 			call_flags::CallFlags,
+/// 			signers::{account_signer::AccountSigner, contract_signer::ContractSigner},
+/// 			transaction_builder::TransactionBuilder,
+/// 		},
+/// 	},
+/// 	Http, Middleware, Provider,
+/// };
+/// use neo_codec::encode::NeoSerializable;
+/// use neo_types::{
+/// 	contract_parameter::ContractParameter, contract_parameter_type::ContractParameterType,
+/// 	encoding,
+/// };
+/// use primitive_types::H160;
+/// use std::sync::Arc;
+///
+/// # async fn foo(
+/// # 	provider: Arc<Provider<Http>>,
+/// # 	owner: Account,
+/// # 	vault: H160,
+/// # 	gas: H160,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// let owner_hash = owner.get_script_hash();
+/// let script = ScriptBuilder::new()
+/// 	.contract_call(
+/// 		&gas,
+/// 		"transfer",
+/// 		&[
+/// 			ContractParameter::hash160(&vault),
+/// 			ContractParameter::hash160(&owner_hash),
+/// 			ContractParameter::integer(100_000_000),
+/// 			ContractParameter::new(ContractParameterType::Any),
+/// 		],
+/// 		CallFlags::All,
+/// 	)?
+/// 	.to_bytes();
+/// let vault_signer = ContractSigner::called_by_entry(vault, &[])
+/// 	.with_verify_params(&[ContractParameter::hash160(&owner_hash)]);
+///
+/// let mut builder = TransactionBuilder::new();
+/// builder.with_provider(provider.clone()).set_script(script).set_signers(vec![
+/// 	AccountSigner::called_by_entry(&owner)?.into(),
+/// 	vault_signer.into(),
+/// ]);
+/// let tx = builder.sign().await?;
+/// println!("network fee: {} GAS", tx.net_fee.to_decimal_string());
+/// provider.send_raw_transaction(encoding::base64_encode(tx.to_array())).await?;
+/// # Ok(()) }
+/// ```
 #[derive(Debug, Clone, Serialize, PartialEq, Deserialize)]
 pub struct ContractSigner {
 	#[serde(
@@ -120,8 +190,8 @@ impl ContractSigner {
 		verify_params: Vec<ContractParameter>,
 	) -> Self {
 		Self {
-			signer_hash: Default::default(),
-			scopes: vec![],
+			signer_hash: contract_hash,
+			scopes: vec![scope],
 			allowed_contracts: vec![],
 			allowed_groups: vec![],
 			rules: vec![],
@@ -138,13 +208,27 @@ impl ContractSigner {
 	pub fn global(contract_hash: H160, verify_params: &[ContractParameter]) -> Self {
 		Self::new(contract_hash, WitnessScope::Global, verify_params.to_vec())
 	}
+
+	/// Sets the arguments the `verify` method of the contract is called with.
+	#[must_use]
+	pub fn with_verify_params(mut self, params: &[ContractParameter]) -> Self {
+		self.verify_params = params.to_vec();
+		self
+	}
+
+	/// The witness of the contract, an invocation script pushing the verification parameters
+	/// and no verification script, see [`Witness::create_contract_witness`].
+	pub fn witness(&self) -> Result<Witness, BuilderError> {
+		Witness::create_contract_witness(self.verify_params.clone())
+	}
 }
 
 impl NeoSerializable for ContractSigner {
 	type Error = TransactionError;
 
 	fn size(&self) -> usize {
-		let mut size: usize = NeoConstants::HASH160_SIZE as usize;
+		// the signer hash and the scope byte
+		let mut size = NeoConstants::HASH160_SIZE as usize + 1;
 		if self.scopes.contains(&WitnessScope::CustomContracts) {
 			size += self.allowed_contracts.var_size();
 		}
@@ -161,13 +245,13 @@ impl NeoSerializable for ContractSigner {
 		writer.write_serializable_fixed(&self.signer_hash);
 		writer.write_u8(WitnessScope::combine(&self.scopes));
 		if self.scopes.contains(&WitnessScope::CustomContracts) {
-			writer.write_serializable_variable_list(&self.allowed_contracts);
+			writer.write_serializable_list(&self.allowed_contracts);
 		}
 		if self.scopes.contains(&WitnessScope::CustomGroups) {
-			writer.write_serializable_variable_list(&self.allowed_groups);
+			writer.write_serializable_list(&self.allowed_groups);
 		}
 		if self.scopes.contains(&WitnessScope::WitnessRules) {
-			writer.write_serializable_variable_list(&self.rules);
+			writer.write_serializable_list(&self.rules);
 		}
 	}
 
@@ -175,19 +259,19 @@ impl NeoSerializable for ContractSigner {
 	where
 		Self: Sized,
 	{
-		let signer_hash = reader.read_serializable::<H160>().unwrap();
-		let scopes = WitnessScope::split(reader.read_u8());
+		let signer_hash = reader.read_serializable::<H160>()?;
+		let scopes = WitnessScope::split(reader.read_u8()?);
 		let mut allowed_contracts = vec![];
 		let mut allowed_groups = vec![];
 		let mut rules = vec![];
 		if scopes.contains(&WitnessScope::CustomContracts) {
-			allowed_contracts = reader.read_serializable_list::<H160>().unwrap();
+			allowed_contracts = reader.read_serializable_list::<H160>()?;
 		}
 		if scopes.contains(&WitnessScope::CustomGroups) {
-			allowed_groups = reader.read_serializable_list::<Secp256r1PublicKey>().unwrap();
+			allowed_groups = reader.read_serializable_list::<Secp256r1PublicKey>()?;
 		}
 		if scopes.contains(&WitnessScope::WitnessRules) {
-			rules = reader.read_serializable_list::<WitnessRule>().unwrap();
+			rules = reader.read_serializable_list::<WitnessRule>()?;
 		}
 		Ok(Self {
 			signer_hash,
@@ -67,6 +67,8 @@ pub trait SignerTrait {
 		}
 
 		// Update state
+		// `None` cannot be combined with other scopes
+		self.get_scopes_mut().retain(|scope| *scope != WitnessScope::None);
 		if !self.get_scopes().contains(&WitnessScope::CustomContracts) {
 			self.get_scopes_mut().push(WitnessScope::CustomContracts);
 		}
@@ -92,6 +94,7 @@ pub trait SignerTrait {
 			))
 		}
 
+		self.get_scopes_mut().retain(|scope| *scope != WitnessScope::None);
 		if !self.get_scopes().contains(&WitnessScope::CustomGroups) {
 			self.get_scopes_mut().push(WitnessScope::CustomGroups);
 		}
@@ -115,9 +118,10 @@ pub trait SignerTrait {
 
 		// Validate nesting depth
 		for rule in &rules {
-			self.validate_depth(&rule.condition, NeoConstants::MAX_NESTING_DEPTH).unwrap();
+			self.validate_depth(&rule.condition, NeoConstants::MAX_NESTING_DEPTH)?;
 		}
 
+		self.get_scopes_mut().retain(|scope| *scope != WitnessScope::None);
 		if !self.get_scopes().contains(&WitnessScope::WitnessRules) {
 			self.get_scopes_mut().push(WitnessScope::WitnessRules);
 		}
@@ -127,22 +131,21 @@ pub trait SignerTrait {
 		Ok(())
 	}
 
-	// Check depth recursively
+	// Check depth recursively, like the node every `And`, `Or` and `Not` takes one level
 	fn validate_depth(&self, rule: &WitnessCondition, depth: u8) -> Result<(), BuilderError> {
+		let inner: &[WitnessCondition] = match rule {
+			WitnessCondition::And(conditions) | WitnessCondition::Or(conditions) => conditions,
+			WitnessCondition::Not(condition) => std::slice::from_ref(condition.as_ref()),
+			_ => return Ok(()),
+		};
 		// Depth exceeded
 		if depth == 0 {
 			return Err(BuilderError::TransactionConfiguration(
 				"Max nesting depth exceeded".to_string(),
 			))
 		}
-
-		match &rule {
-			WitnessCondition::And(conditions) | WitnessCondition::Or(conditions) => {
-				for inner_rule in conditions {
-					self.validate_depth(inner_rule, depth - 1).unwrap();
-				}
-			},
-			_ => (),
+		for inner_rule in inner {
+			self.validate_depth(inner_rule, depth - 1)?;
 		}
 
 		Ok(())
@@ -178,6 +181,14 @@ impl Signer {
 		}
 	}
 
+	pub fn get_scopes(&self) -> &Vec<WitnessScope> {
+		match self {
+			Signer::Account(account_signer) => account_signer.get_scopes(),
+			Signer::Contract(contract_signer) => contract_signer.get_scopes(),
+			Signer::Transaction(transaction_signer) => transaction_signer.get_scopes(),
+		}
+	}
+
 	pub fn as_account_signer(&self) -> Option<&AccountSigner> {
 		match self {
 			Signer::Account(account_signer) => Some(account_signer),
@@ -222,22 +233,75 @@ impl From<ContractSigner> for Signer {
 	}
 }
 
-impl Into<AccountSigner> for Signer {
-	fn into(self) -> AccountSigner {
-		match self {
-			Signer::Account(account_signer) => account_signer,
-			_ => panic!("Cannot convert ContractSigner into AccountSigner"),
+impl From<TransactionSigner> for Signer {
+	fn from(transaction_signer: TransactionSigner) -> Self {
+		Signer::Transaction(transaction_signer)
+	}
+}
+
+impl TryFrom<Signer> for AccountSigner {
+	type Error = BuilderError;
+
+	fn try_from(signer: Signer) -> Result<Self, Self::Error> {
+		match signer {
+			Signer::Account(account_signer) => Ok(account_signer),
+			signer => Err(wrong_signer_type(&signer, SignerType::Account)),
 		}
 	}
 }
 
+impl From<&Signer> for TransactionSigner {
+	/// The signer as described in the transactions returned by the node, e.g. of a decoded
+	/// transaction
+	fn from(signer: &Signer) -> Self {
+		match signer {
+			Signer::Account(account_signer) => describe(account_signer),
+			Signer::Contract(contract_signer) => describe(contract_signer),
+			Signer::Transaction(transaction_signer) => transaction_signer.clone(),
+		}
+	}
+}
+
+fn describe<S: SignerTrait>(signer: &S) -> TransactionSigner {
+	fn non_empty<T: Clone>(values: &[T]) -> Option<Vec<T>> {
+		Some(values.to_vec()).filter(|values| !values.is_empty())
+	}
+	TransactionSigner {
+		account: *signer.get_signer_hash(),
+		scopes: signer.get_scopes().clone(),
+		allowed_contracts: non_empty(signer.get_allowed_contracts()),
+		allowed_groups: non_empty(signer.get_allowed_groups()),
+		rules: non_empty(signer.get_rules()),
+	}
+}
+
+fn wrong_signer_type(signer: &Signer, expected: SignerType) -> BuilderError {
+	BuilderError::SignerConfiguration(format!(
+		"Cannot convert the {:?} signer {} into a {expected:?} signer",
+		signer.get_type(),
+		signer.get_signer_hash()
+	))
+}
+
+/// Copies the hash, scopes and restrictions of `signer` into a [`TransactionSigner`].
+fn to_transaction_signer(signer: &impl SignerTrait) -> TransactionSigner {
+	fn non_empty<T: Clone>(items: &[T]) -> Option<Vec<T>> {
+		(!items.is_empty()).then(|| items.to_vec())
+	}
+	TransactionSigner {
+		account: *signer.get_signer_hash(),
+		scopes: signer.get_scopes().clone(),
+		allowed_contracts: non_empty(signer.get_allowed_contracts()),
+		allowed_groups: non_empty(signer.get_allowed_groups()),
+		rules: non_empty(signer.get_rules()),
+	}
+}
+
 impl Into<TransactionSigner> for Signer {
 	fn into(self) -> TransactionSigner {
 		match self {
-			Signer::Account(account_signer) =>
-				panic!("Cannot convert AccountSigner into TransactionSigner"),
-			Signer::Contract(contract_signer) =>
-				panic!("Cannot convert ContractSigner into AccountSigner"),
+			Signer::Account(account_signer) => to_transaction_signer(&account_signer),
+			Signer::Contract(contract_signer) => to_transaction_signer(&contract_signer),
 			Signer::Transaction(transaction_signer) => transaction_signer,
 		}
 	}
@@ -246,10 +310,8 @@ impl Into<TransactionSigner> for Signer {
 impl Into<TransactionSigner> for &Signer {
 	fn into(self) -> TransactionSigner {
 		match self {
-			Signer::Account(account_signer) =>
-				panic!("Cannot convert AccountSigner into TransactionSigner"),
-			Signer::Contract(contract_signer) =>
-				panic!("Cannot convert ContractSigner into AccountSigner"),
+			Signer::Account(account_signer) => to_transaction_signer(account_signer),
+			Signer::Contract(contract_signer) => to_transaction_signer(contract_signer),
 			Signer::Transaction(transaction_signer) => transaction_signer.clone(),
 		}
 	}
@@ -258,36 +320,32 @@ impl Into<TransactionSigner> for &Signer {
 impl Into<TransactionSigner> for &mut Signer {
 	fn into(self) -> TransactionSigner {
 		match self {
-			Signer::Account(account_signer) =>
-				panic!("Cannot convert AccountSigner into TransactionSigner"),
-			Signer::Contract(contract_signer) =>
-				panic!("Cannot convert ContractSigner into AccountSigner"),
+			Signer::Account(account_signer) => to_transaction_signer(account_signer),
+			Signer::Contract(contract_signer) => to_transaction_signer(contract_signer),
 			Signer::Transaction(transaction_signer) => transaction_signer.clone(),
 		}
 	}
 }
 
-impl Into<AccountSigner> for &mut Signer {
-	fn into(self) -> AccountSigner {
-		match self {
-			Signer::Account(account_signer) => account_signer.clone(),
-			Signer::Contract(contract_signer) =>
-				panic!("Cannot convert ContractSigner into AccountSigner"),
-			Signer::Transaction(transaction_signer) =>
-				panic!("Cannot convert TransactionSigner into AccountSigner"),
-		}
+impl TryFrom<&mut Signer> for AccountSigner {
+	type Error = BuilderError;
+
+	fn try_from(signer: &mut Signer) -> Result<Self, Self::Error> {
+		signer
+			.as_account_signer()
+			.cloned()
+			.ok_or_else(|| wrong_signer_type(signer, SignerType::Account))
 	}
 }
 
-impl Into<ContractSigner> for &mut Signer {
-	fn into(self) -> ContractSigner {
-		match self {
-			Signer::Account(account_signer) =>
-				panic!("Cannot convert AccountSigner into ContractSigner"),
-			Signer::Contract(contract_signer) => contract_signer.clone(),
-			Signer::Transaction(transaction_signer) =>
-				panic!("Cannot convert TransactionSigner into ContractSigner"),
-		}
+impl TryFrom<&mut Signer> for ContractSigner {
+	type Error = BuilderError;
+
+	fn try_from(signer: &mut Signer) -> Result<Self, Self::Error> {
+		signer
+			.as_contract_signer()
+			.cloned()
+			.ok_or_else(|| wrong_signer_type(signer, SignerType::Contract))
 	}
 }
 
@@ -323,8 +381,7 @@ impl NeoSerializable for Signer {
 		match self {
 			Signer::Account(account_signer) => account_signer.size(),
 			Signer::Contract(contract_signer) => contract_signer.size(),
-			// Signer::Transaction(transaction_signer) => transaction_signer.size(),
-			_ => panic!("Unsupported signer type"),
+			Signer::Transaction(transaction_signer) => transaction_signer.size(),
 		}
 	}
 
@@ -332,8 +389,7 @@ impl NeoSerializable for Signer {
 		match self {
 			Signer::Account(account_signer) => account_signer.encode(writer),
 			Signer::Contract(contract_signer) => contract_signer.encode(writer),
-			// Signer::Transaction(transaction_signer) => transaction_signer.encode(writer),
-			_ => panic!("Unsupported signer type"),
+			Signer::Transaction(transaction_signer) => transaction_signer.encode(writer),
 		}
 	}
 
@@ -341,20 +397,15 @@ impl NeoSerializable for Signer {
 	where
 		Self: Sized,
 	{
-		match reader.read_u8() {
-			0 => Ok(Signer::Account(AccountSigner::decode(reader)?)),
-			1 => Ok(Signer::Contract(ContractSigner::decode(reader)?)),
-			// 2 => Ok(Signer::Transaction(TransactionSigner::decode(reader)?)),
-			_ => Err(TransactionError::InvalidTransaction),
-		}
+		// the serialization does not tell account and contract signers apart
+		Ok(Signer::Account(AccountSigner::decode(reader)?))
 	}
 
 	fn to_array(&self) -> Vec<u8> {
 		match self {
 			Signer::Account(account_signer) => account_signer.to_array(),
 			Signer::Contract(contract_signer) => contract_signer.to_array(),
-			// Signer::Transaction(transaction_signer) => transaction_signer.to_array(),
-			_ => panic!("Unsupported signer type"),
+			Signer::Transaction(transaction_signer) => transaction_signer.to_array(),
 		}
 	}
 }
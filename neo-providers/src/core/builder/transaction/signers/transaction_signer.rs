@@ -122,14 +122,14 @@ impl NeoSerializable for TransactionSigner {
 	fn size(&self) -> usize {
 		let mut size = (NeoConstants::HASH160_SIZE + 1) as usize;
 		if self.scopes.contains(&WitnessScope::CustomContracts) {
-			size += &self.allowed_contracts.clone().unwrap().var_size();
+			size += self.allowed_contracts.as_deref().unwrap_or_default().var_size();
 		}
 		if self.scopes.contains(&WitnessScope::CustomGroups) {
-			size += &self.allowed_groups.clone().unwrap().var_size();
+			size += self.allowed_groups.as_deref().unwrap_or_default().var_size();
 		}
 
 		if self.scopes.contains(&WitnessScope::WitnessRules) {
-			size += &self.rules.clone().unwrap().var_size();
+			size += self.rules.as_deref().unwrap_or_default().var_size();
 		}
 
 		size
@@ -139,13 +139,13 @@ impl NeoSerializable for TransactionSigner {
 		writer.write_serializable_fixed(self.get_signer_hash());
 		writer.write_u8(WitnessScope::combine(self.scopes.as_slice()));
 		if self.scopes.contains(&WitnessScope::CustomContracts) {
-			writer.write_serializable_variable_list(self.allowed_contracts.as_ref().unwrap());
+			writer.write_serializable_list(self.allowed_contracts.as_deref().unwrap_or_default());
 		}
 		if self.scopes.contains(&WitnessScope::CustomGroups) {
-			writer.write_serializable_variable_list(self.allowed_groups.as_ref().unwrap());
+			writer.write_serializable_list(self.allowed_groups.as_deref().unwrap_or_default());
 		}
 		if self.scopes.contains(&WitnessScope::WitnessRules) {
-			writer.write_serializable_variable_list(self.rules.as_ref().unwrap());
+			writer.write_serializable_list(self.rules.as_deref().unwrap_or_default());
 		}
 	}
 
@@ -154,17 +154,17 @@ impl NeoSerializable for TransactionSigner {
 		Self: Sized,
 	{
 		let mut signer = TransactionSigner::default();
-		signer.set_signer_hash(reader.read_serializable().unwrap());
-		let scopes = WitnessScope::split(reader.read_u8());
+		signer.set_signer_hash(reader.read_serializable()?);
+		let scopes = WitnessScope::split(reader.read_u8()?);
 		signer.set_scopes(scopes);
 		if signer.get_scopes().contains(&WitnessScope::CustomContracts) {
-			signer.allowed_contracts = Some(reader.read_serializable_list().unwrap());
+			signer.allowed_contracts = Some(reader.read_serializable_list()?);
 		}
 		if signer.get_scopes().contains(&WitnessScope::CustomGroups) {
-			signer.allowed_groups = Some(reader.read_serializable_list().unwrap());
+			signer.allowed_groups = Some(reader.read_serializable_list()?);
 		}
 		if signer.get_scopes().contains(&WitnessScope::WitnessRules) {
-			signer.rules = Some(reader.read_serializable_list().unwrap());
+			signer.rules = Some(reader.read_serializable_list()?);
 		}
 		Ok(signer)
 	}
@@ -5,17 +5,19 @@ use crate::{
 			signers::{signer::Signer, transaction_signer::TransactionSigner},
 			transaction_attribute::TransactionAttribute,
 			transaction_error::TransactionError,
+			transaction_summary::{ManifestRegistry, TransactionSummary},
 			witness::Witness,
 		},
 	},
 	JsonRpcClient,
 };
+use neo_addressbook::AddressBook;
 use neo_codec::{
 	encode::{NeoSerializable, VarSizeTrait},
 	Decoder, Encoder,
 };
 use neo_crypto::hash::HashableForVec;
-use neo_types::{address::NameOrAddress, vm_state::VMState, *};
+use neo_types::{address::NameOrAddress, gas::Gas, vm_state::VMState, *};
 use primitive_types::{H160, H256, U256};
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
@@ -45,10 +47,10 @@ pub struct Transaction {
 	pub sender: H160,
 
 	#[serde(rename = "sysfee")]
-	pub sys_fee: i64,
+	pub sys_fee: Gas,
 
 	#[serde(rename = "netfee")]
-	pub net_fee: i64,
+	pub net_fee: Gas,
 
 	#[serde(rename = "signers")]
 	pub signers: Vec<Signer>,
@@ -91,6 +93,34 @@ impl Transaction {
 		Transaction { ..Default::default() }
 	}
 
+	/// Decodes a serialized transaction, in Base64 as returned by `getrawtransaction` without
+	/// `verbose` or in hex, with its signers, attributes, script and witnesses.
+	///
+	/// The hash, size and sender are computed from the transaction, the fields describing the
+	/// block that includes it are unknown.
+	pub fn from_raw(raw: &str) -> Result<Self, TransactionError> {
+		let bytes = encoding::raw_decode(raw)?;
+		let mut reader = Decoder::new(&bytes);
+		let mut tx = Transaction::decode(&mut reader)?;
+		if reader.available() > 0 {
+			return Err(TransactionError::IllegalState(format!(
+				"{} bytes after the transaction",
+				reader.available()
+			)))
+		}
+		tx.set_computed_fields();
+		Ok(tx)
+	}
+
+	/// Sets the fields the node computes for decoded transactions: the hash, the size and the
+	/// sender, the first signer.
+	pub(crate) fn set_computed_fields(&mut self) {
+		self.hash = self.hash();
+		self.size = self.size() as i32;
+		self.sender =
+			self.signers.first().map(|signer| *signer.get_signer_hash()).unwrap_or_default();
+	}
+
 	pub fn network_magic(&self) -> Option<u32> {
 		self.network_magic
 	}
@@ -103,26 +133,80 @@ impl Transaction {
 		self.witnesses.push(witness);
 	}
 
-	pub fn get_hash_data(&self) -> Result<Bytes, TransactionError> {
-		if self.network_magic().is_none() {
-			panic!("Transaction network magic is not set");
+	/// Checks that the transaction has one witness per signer, in the order of the signers, as
+	/// the node verifies the witness at each index against the signer at the same index.
+	///
+	/// A witness with a verification script must be the one of its signer, whose script hash is
+	/// the hash of the script. Witnesses of deployed contracts have no verification script.
+	pub fn validate_witnesses(&self) -> Result<(), TransactionError> {
+		if self.witnesses.len() != self.signers.len() {
+			return Err(TransactionError::WitnessConfiguration(format!(
+				"{} witnesses for {} signers",
+				self.witnesses.len(),
+				self.signers.len()
+			)))
+		}
+		for (index, (signer, witness)) in self.signers.iter().zip(&self.witnesses).enumerate() {
+			let verification = &witness.verification;
+			if !verification.script().is_empty() && verification.hash() != *signer.get_signer_hash()
+			{
+				return Err(TransactionError::WitnessConfiguration(format!(
+					"The witness at index {index} verifies {:?}, not the signer {:?}",
+					verification.hash(),
+					signer.get_signer_hash()
+				)))
+			}
 		}
+		Ok(())
+	}
+
+	/// Summarizes what the transaction does, e.g. for a confirmation screen: the NEP-17
+	/// transfers, contract deployments, votes and other contract calls of its script, naming
+	/// contracts and accounts with `address_book` and `manifests`.
+	///
+	/// Use [`ManifestRegistry::fetch_for`] to fetch the manifests of the called contracts.
+	pub fn explain(
+		&self,
+		address_book: &AddressBook,
+		manifests: &ManifestRegistry,
+	) -> TransactionSummary {
+		TransactionSummary::new(self, address_book, manifests)
+	}
+
+	/// The data signed by the witnesses of the transaction on the network with the magic
+	/// `network_magic`: the magic in little-endian order followed by the SHA-256 of the
+	/// serialization of the transaction without witnesses.
+	pub fn get_hash_data(&self, network_magic: u32) -> Bytes {
+		let mut data = network_magic.to_le_bytes().to_vec();
+		data.extend(self.unsigned_hash());
+		data
+	}
+
+	/// The hash identifying the transaction: the SHA-256 of its serialization without witnesses,
+	/// in the byte order used by the RPC interface.
+	///
+	/// Computed locally, unlike the `hash` field which is only set for transactions received
+	/// from a node.
+	pub fn hash(&self) -> H256 {
+		let mut hash = self.unsigned_hash();
+		hash.reverse();
+		H256::from_slice(&hash)
+	}
+
+	fn unsigned_hash(&self) -> Vec<u8> {
 		let mut encoder = Encoder::new();
 		self.serialize_without_witnesses(&mut encoder);
-		let mut data = encoder.to_bytes().hash256();
-		data.splice(0..0, self.network_magic.unwrap().to_be_bytes());
-
-		Ok(data)
+		encoder.to_bytes().hash256()
 	}
 
 	fn serialize_without_witnesses(&self, writer: &mut Encoder) {
 		writer.write_u8(self.version);
 		writer.write_u32(self.nonce as u32);
-		writer.write_i64(self.sys_fee);
-		writer.write_i64(self.net_fee);
+		writer.write_i64(self.sys_fee.datoms());
+		writer.write_i64(self.net_fee.datoms());
 		writer.write_u32(self.valid_until_block as u32);
-		writer.write_serializable_variable_list(&self.signers);
-		writer.write_serializable_variable_list(&self.attributes);
+		writer.write_serializable_list(&self.signers);
+		writer.write_serializable_list(&self.attributes);
 		writer.write_var_bytes(&self.script);
 	}
 }
@@ -148,31 +232,34 @@ impl NeoSerializable for Transaction {
 
 	fn encode(&self, writer: &mut Encoder) {
 		self.serialize_without_witnesses(writer);
-		writer.write_serializable_variable_list(&self.witnesses);
+		writer.write_serializable_list(&self.witnesses);
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error>
 	where
 		Self: Sized,
 	{
-		let version = reader.read_u8();
-		let nonce = reader.read_u32();
-		let system_fee = reader.read_i64();
-		let network_fee = reader.read_i64();
-		let valid_until_block = reader.read_u32();
+		let version = reader.read_u8()?;
+		let nonce = reader.read_u32()?;
+		let system_fee = reader.read_i64()?;
+		let network_fee = reader.read_i64()?;
+		let valid_until_block = reader.read_u32()?;
 
 		// Read signers
-		let signers: Vec<Signer> = reader.read_serializable_list::<Signer>().unwrap();
+		let signers: Vec<Signer> = reader.read_serializable_list::<Signer>()?;
 
 		// Read attributes
 		let attributes: Vec<TransactionAttribute> =
-			reader.read_serializable_list::<TransactionAttribute>().unwrap();
+			reader.read_serializable_list::<TransactionAttribute>()?;
 
-		let script = reader.read_var_bytes().unwrap().to_vec();
+		let script = reader.read_var_bytes()?;
 
+		// the witnesses are missing if only the unsigned part was serialized
 		let mut witnesses = vec![];
-		if (reader.available() > 0) {
-			witnesses.append(&mut reader.read_serializable_list::<Witness>().unwrap());
+		if reader.available() > 0 {
+			witnesses = reader
+				.read_serializable_list::<Witness>()
+				.map_err(|e| TransactionError::IllegalState(e.to_string()))?;
 		}
 
 		Ok(Self {
@@ -182,8 +269,8 @@ impl NeoSerializable for Transaction {
 			hash: Default::default(),
 			size: 0,
 			sender: Default::default(),
-			sys_fee: system_fee,
-			net_fee: network_fee,
+			sys_fee: Gas::from_datoms(system_fee),
+			net_fee: Gas::from_datoms(network_fee),
 			signers,
 			attributes,
 			script,
@@ -202,3 +289,103 @@ impl NeoSerializable for Transaction {
 		writer.to_bytes()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::transaction::signers::account_signer::AccountSigner;
+	use neo_config::NeoNetwork;
+	use neo_types::script_hash::ScriptHashExt;
+
+	// The expected values were computed independently from the canonical serialization.
+	const RAW: &str = "000403020140420f000000000087d612000000000000093d0001e9eed8dc39332032dc22e5d6e86332c50327ba23010002114000";
+	const HASH: &str = "1fc0af54c5f7ec7e04a46c02842eae806cee9bc4d0b23a49db6bd4070137f26c";
+	const MAINNET_HASH_DATA: &str =
+		"4e454f336cf2370107d46bdb493ab2d0c49bee6c80ae2e84026ca4047eecf7c554afc01f";
+
+	fn transaction() -> Transaction {
+		let sender = H160::from_hex("23ba2703c53263e8d6e522dc32203339dcd8eee9").unwrap();
+		Transaction {
+			nonce: 0x01020304,
+			sys_fee: Gas::from_datoms(1_000_000),
+			net_fee: Gas::from_datoms(1_234_567),
+			valid_until_block: 4_000_000,
+			signers: vec![AccountSigner::called_by_entry_hash160(sender).unwrap().into()],
+			script: vec![0x11, 0x40],
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_serialization() {
+		assert_eq!(hex::encode(transaction().to_array()), RAW);
+	}
+
+	#[test]
+	fn test_decode() {
+		let raw = hex::decode(RAW).unwrap();
+		let tx = Transaction::decode(&mut Decoder::new(&raw)).unwrap();
+		assert_eq!(tx.nonce, 0x01020304);
+		assert_eq!(tx.signers.len(), 1);
+		assert_eq!(tx.script, vec![0x11, 0x40]);
+		assert_eq!(tx.hash(), transaction().hash());
+
+		// cut off in the middle of the script
+		let err = Transaction::decode(&mut Decoder::new(&raw[..raw.len() - 2])).unwrap_err();
+		match err {
+			TransactionError::CodecError(err) => assert_eq!(err.offset(), Some(raw.len() - 4)),
+			err => panic!("unexpected error {err}"),
+		}
+	}
+
+	#[test]
+	fn test_from_raw() {
+		let expected = transaction();
+		let tx = Transaction::from_raw(RAW).unwrap();
+		assert_eq!(tx.hash, expected.hash());
+		assert_eq!(tx.size as usize, RAW.len() / 2);
+		assert_eq!(tx.sender, *expected.signers[0].get_signer_hash());
+		assert_eq!(tx.net_fee, expected.net_fee);
+
+		let mut signed = expected;
+		signed.add_witness(Witness::from_scripts(vec![0x0c; 66], vec![0x41; 40]));
+		let base64 = encoding::base64_encode(signed.to_array());
+		let tx = Transaction::from_raw(&base64).unwrap();
+		assert_eq!(tx.witnesses, signed.witnesses);
+		assert_eq!(tx.to_array(), signed.to_array());
+
+		assert!(matches!(
+			Transaction::from_raw(&format!("0x{RAW}00")),
+			Err(TransactionError::IllegalState(_))
+		));
+		assert!(matches!(
+			Transaction::from_raw("not a tx"),
+			Err(TransactionError::EncodingError(_))
+		));
+	}
+
+	#[test]
+	fn test_size() {
+		let mut tx = transaction();
+		assert_eq!(tx.size(), tx.to_array().len());
+		tx.add_witness(Witness::from_scripts(vec![0x0c; 66], vec![0x41; 300]));
+		tx.attributes.push(TransactionAttribute::HighPriority);
+		assert_eq!(tx.size(), tx.to_array().len());
+	}
+
+	#[test]
+	fn test_hash() {
+		let tx = transaction();
+		assert_eq!(tx.hash(), H256::from_slice(&hex::decode(HASH).unwrap()));
+		// witnesses are not part of the hash
+		let mut signed = tx.clone();
+		signed.add_witness(Witness::from_scripts(vec![0x0c], vec![0x41]));
+		assert_eq!(signed.hash(), tx.hash());
+	}
+
+	#[test]
+	fn test_hash_data() {
+		let hash_data = transaction().get_hash_data(NeoNetwork::MainNet.to_magic());
+		assert_eq!(hex::encode(hash_data), MAINNET_HASH_DATA);
+	}
+}
@@ -1,8 +1,11 @@
 use crate::core::transaction::transaction_error::TransactionError;
-use neo_codec::{encode::NeoSerializable, CodecError, Decoder, Encoder};
-use neo_types::{Base64Encode, ExternBase64};
+use neo_codec::{
+	encode::{var_size, NeoSerializable},
+	CodecError, Decoder, Encoder,
+};
+use neo_types::{deserialize_h256, encoding, serialize_h256};
 use num_bigint::BigInt;
-use rustc_serialize::base64::FromBase64;
+use primitive_types::H256;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::hash::Hasher;
 
@@ -16,18 +19,58 @@ pub enum TransactionAttribute {
 
 	#[serde(rename = "OracleResponse")]
 	OracleResponse(OracleResponse),
+
+	/// The transaction is invalid before the block `height`
+	#[serde(rename = "NotValidBefore")]
+	NotValidBefore { height: u32 },
+
+	/// The transaction conflicts with the transaction `hash`, only one of them is persisted
+	#[serde(rename = "Conflicts")]
+	Conflicts {
+		#[serde(serialize_with = "serialize_h256")]
+		#[serde(deserialize_with = "deserialize_h256")]
+		hash: H256,
+	},
+
+	/// The transaction is completed by the notary service, after collecting `n_keys` signatures
+	#[serde(rename = "NotaryAssisted")]
+	NotaryAssisted {
+		#[serde(rename = "nkeys")]
+		n_keys: u8,
+	},
 }
 
+/// The response of the oracle nodes to an oracle request, attached to the transaction calling
+/// the callback of the requesting contract.
 #[derive(Serialize, Deserialize, PartialEq, Hash, Debug, Clone)]
-struct OracleResponse {
-	pub id: u32,
+pub struct OracleResponse {
+	/// The ID of the answered request
+	pub id: u64,
+	/// Whether the data could be fetched
+	#[serde(rename = "code")]
 	pub response_code: OracleResponseCode,
+	/// The fetched data, base64 encoded
 	pub result: String,
 }
 
+impl OracleResponse {
+	/// The fetched data, or `None` if the result is not valid base64
+	pub fn result_bytes(&self) -> Option<Vec<u8>> {
+		encoding::base64_decode(&self.result).ok()
+	}
+}
+
 impl TransactionAttribute {
 	pub const MAX_RESULT_SIZE: usize = 0xffff;
 
+	/// The oracle response, if this is an `OracleResponse` attribute
+	pub fn oracle_response(&self) -> Option<&OracleResponse> {
+		match self {
+			TransactionAttribute::OracleResponse(response) => Some(response),
+			_ => None,
+		}
+	}
+
 	pub fn to_bytes(&self) -> Vec<u8> {
 		let mut bytes = vec![];
 
@@ -41,28 +84,31 @@ impl TransactionAttribute {
 				bytes.push(response_code.clone() as u8);
 				bytes.extend(result.as_bytes());
 			},
+			// the other attributes have no legacy encoding
+			_ => return self.to_array(),
 		}
 
 		bytes
 	}
 
 	pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
-		match bytes[0] {
-			0x01 => Ok(TransactionAttribute::HighPriority),
-			0x11 => {
-				if bytes.len() < 9 {
+		match bytes.first() {
+			Some(0x01) => Ok(TransactionAttribute::HighPriority),
+			Some(0x11) => {
+				if bytes.len() < 10 {
 					return Err("Not enough bytes for OracleResponse")
 				}
 				let mut array = [0; 8];
 				let slice_len = bytes[1..9].len();
 				array[8 - slice_len..].copy_from_slice(&bytes[1..9]);
 				let id = u64::from_be_bytes(array);
-				let response_code = OracleResponseCode::try_from(bytes[9]).unwrap();
+				let response_code = OracleResponseCode::try_from(bytes[9])
+					.map_err(|_| "Invalid OracleResponse code")?;
 				let result =
-					String::from_utf8(bytes[10..].to_vec()).map_err(|_| "Invalid UTF-8").unwrap();
+					String::from_utf8(bytes[10..].to_vec()).map_err(|_| "Invalid UTF-8")?;
 
 				Ok(TransactionAttribute::OracleResponse(OracleResponse {
-					id: id as u32,
+					id,
 					response_code,
 					result,
 				}))
@@ -72,7 +118,7 @@ impl TransactionAttribute {
 	}
 
 	pub fn to_json(&self) -> String {
-		serde_json::to_string(self).unwrap()
+		serde_json::to_string(self).expect("serializing to JSON cannot fail")
 	}
 }
 
@@ -82,8 +128,14 @@ impl NeoSerializable for TransactionAttribute {
 	fn size(&self) -> usize {
 		match self {
 			TransactionAttribute::HighPriority => 1,
-			TransactionAttribute::OracleResponse(OracleResponse { id, response_code, result }) =>
-				1 + 9 + result.len(),
+			TransactionAttribute::NotValidBefore { .. } => 1 + 4,
+			TransactionAttribute::Conflicts { .. } => 1 + 32,
+			TransactionAttribute::NotaryAssisted { .. } => 1 + 1,
+			TransactionAttribute::OracleResponse(OracleResponse { result, .. }) => {
+				let result_len = encoding::base64_decode(result).map_or(0, |result| result.len());
+				// the type, the id and the response code precede the result
+				1 + 8 + 1 + var_size(result_len) + result_len
+			},
 		}
 	}
 
@@ -94,22 +146,36 @@ impl NeoSerializable for TransactionAttribute {
 			},
 			TransactionAttribute::OracleResponse(OracleResponse { id, response_code, result }) => {
 				writer.write_u8(0x11);
-				let mut v = id.to_be_bytes();
-				v.reverse();
-				writer.write(&v);
+				writer.write_u64(*id);
 				writer.write_u8(response_code.clone() as u8);
-				writer.write_var_bytes(result.from_base64().unwrap().as_slice());
+				// `size` counts an invalid result as empty as well
+				writer.write_var_bytes(
+					encoding::base64_decode(result).unwrap_or_default().as_slice(),
+				);
+			},
+			TransactionAttribute::NotValidBefore { height } => {
+				writer.write_u8(0x20);
+				writer.write_u32(*height);
+			},
+			TransactionAttribute::Conflicts { hash } => {
+				writer.write_u8(0x21);
+				writer.write_serializable_fixed(hash);
+			},
+			TransactionAttribute::NotaryAssisted { n_keys } => {
+				writer.write_u8(0x22);
+				writer.write_u8(*n_keys);
 			},
 		}
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		match reader.read_u8() {
+		match reader.read_u8()? {
 			0x01 => Ok(TransactionAttribute::HighPriority),
 			0x11 => {
-				let id = reader.read_u32();
-				let response_code = OracleResponseCode::try_from(reader.read_u8()).unwrap();
-				let result = reader.read_var_bytes().unwrap().to_base64();
+				let id = reader.read_u64()?;
+				let response_code = OracleResponseCode::try_from(reader.read_u8()?)
+					.map_err(|_| reader.invalid_data("invalid oracle response code"))?;
+				let result = encoding::base64_encode(reader.read_var_slice()?);
 
 				Ok(TransactionAttribute::OracleResponse(OracleResponse {
 					id,
@@ -117,6 +183,9 @@ impl NeoSerializable for TransactionAttribute {
 					result,
 				}))
 			},
+			0x20 => Ok(TransactionAttribute::NotValidBefore { height: reader.read_u32()? }),
+			0x21 => Ok(TransactionAttribute::Conflicts { hash: H256::decode(reader)? }),
+			0x22 => Ok(TransactionAttribute::NotaryAssisted { n_keys: reader.read_u8()? }),
 			_ => Err(TransactionError::InvalidTransaction),
 		}
 	}
@@ -127,3 +196,53 @@ impl NeoSerializable for TransactionAttribute {
 		writer.to_bytes()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::str::FromStr;
+
+	#[test]
+	fn test_oracle_response() {
+		let attribute = TransactionAttribute::OracleResponse(OracleResponse {
+			id: 0x0102030405,
+			response_code: OracleResponseCode::Success,
+			result: encoding::base64_encode(b"{}"),
+		});
+		let bytes = attribute.to_array();
+		assert_eq!(hex::encode(&bytes), "11050403020100000000027b7d");
+		assert_eq!(attribute.size(), bytes.len());
+		let decoded = TransactionAttribute::decode(&mut Decoder::new(&bytes)).unwrap();
+		assert_eq!(decoded, attribute);
+	}
+
+	#[test]
+	fn test_notary_attributes() {
+		let hash =
+			H256::from_str("0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20")
+				.unwrap();
+		for (attribute, expected) in [
+			(TransactionAttribute::NotValidBefore { height: 0x01020304 }, "2004030201".to_string()),
+			(
+				TransactionAttribute::Conflicts { hash },
+				format!(
+					"21{}",
+					hex::encode(hash.as_bytes().iter().rev().copied().collect::<Vec<_>>())
+				),
+			),
+			(TransactionAttribute::NotaryAssisted { n_keys: 3 }, "2203".to_string()),
+		] {
+			let bytes = attribute.to_array();
+			assert_eq!(hex::encode(&bytes), expected);
+			assert_eq!(attribute.size(), bytes.len());
+			assert_eq!(TransactionAttribute::decode(&mut Decoder::new(&bytes)).unwrap(), attribute);
+		}
+
+		let json = format!(r#"{{"type":"Conflicts","hash":"{hash:#x}"}}"#);
+		let attribute: TransactionAttribute = serde_json::from_str(&json).unwrap();
+		assert_eq!(attribute, TransactionAttribute::Conflicts { hash });
+		let attribute: TransactionAttribute =
+			serde_json::from_str(r#"{"type":"NotaryAssisted","nkeys":2}"#).unwrap();
+		assert_eq!(attribute, TransactionAttribute::NotaryAssisted { n_keys: 2 });
+	}
+}
@@ -20,19 +20,21 @@ use getset::{CopyGetters, Getters, MutGetters, Setters};
 use neo_codec::encode::NeoSerializable;
 use neo_config::NeoConstants;
 use neo_types::{
-	contract_parameter::ContractParameter, public_key_to_script_hash, script_hash::ScriptHash,
+	contract_parameter::ContractParameter,
+	encoding,
+	gas::Gas,
+	natives,
+	script_hash::{ScriptHash, ScriptHashExt},
 	Bytes,
 };
-use once_cell::sync::Lazy;
 use primitive_types::H160;
-use rustc_serialize::hex::ToHex;
 use serde::Serialize;
 use std::{
 	collections::HashSet,
 	fmt::Debug,
 	hash::{Hash, Hasher},
 	iter::Iterator,
-	str::FromStr,
+	sync::Arc,
 };
 
 use crate::{
@@ -40,7 +42,9 @@ use crate::{
 		account::{Account, AccountTrait},
 		builder::{
 			error::BuilderError,
-			transaction::{transaction_error::TransactionError, witness::Witness},
+			transaction::{
+				fee_sponsor::FeeSponsor, transaction_error::TransactionError, witness::Witness,
+			},
 		},
 		transaction::{
 			signers::{
@@ -56,7 +60,7 @@ use crate::{
 
 #[derive(Getters, Setters, MutGetters, CopyGetters, Default)]
 pub struct TransactionBuilder<P: JsonRpcClient + 'static> {
-	provider: Option<&'static Provider<P>>,
+	provider: Option<Arc<Provider<P>>>,
 	version: u8,
 	nonce: u32,
 	valid_until_block: Option<u32>,
@@ -69,6 +73,8 @@ pub struct TransactionBuilder<P: JsonRpcClient + 'static> {
 	script: Option<Bytes>,
 	fee_consumer: Option<Box<dyn Fn(u64, u64)>>,
 	fee_error: Option<TransactionError>,
+	fee_sponsor: Option<FeeSponsor>,
+	witnesses: Vec<(H160, Witness)>,
 }
 
 impl<P: JsonRpcClient> Debug for TransactionBuilder<P> {
@@ -84,6 +90,8 @@ impl<P: JsonRpcClient> Debug for TransactionBuilder<P> {
 			.field("script", &self.script)
 			// .field("fee_consumer", &self.fee_consumer)
 			.field("fee_error", &self.fee_error)
+			.field("fee_sponsor", &self.fee_sponsor)
+			.field("witnesses", &self.witnesses)
 			.finish()
 	}
 }
@@ -91,7 +99,7 @@ impl<P: JsonRpcClient> Debug for TransactionBuilder<P> {
 impl<P: JsonRpcClient> Clone for TransactionBuilder<P> {
 	fn clone(&self) -> Self {
 		Self {
-			provider: self.provider,
+			provider: self.provider.clone(),
 			version: self.version,
 			nonce: self.nonce,
 			valid_until_block: self.valid_until_block,
@@ -103,6 +111,8 @@ impl<P: JsonRpcClient> Clone for TransactionBuilder<P> {
 			// fee_consumer: self.fee_consumer.clone(),
 			fee_consumer: None,
 			fee_error: None,
+			fee_sponsor: self.fee_sponsor.clone(),
+			witnesses: self.witnesses.clone(),
 		}
 	}
 }
@@ -119,6 +129,7 @@ impl<P: JsonRpcClient> PartialEq for TransactionBuilder<P> {
 			&& self.additional_system_fee == other.additional_system_fee
 			&& self.attributes == other.attributes
 			&& self.script == other.script
+			&& self.witnesses == other.witnesses
 	}
 }
 
@@ -132,14 +143,11 @@ impl<P: JsonRpcClient> Hash for TransactionBuilder<P> {
 		self.additional_system_fee.hash(state);
 		self.attributes.hash(state);
 		self.script.hash(state);
+		self.witnesses.hash(state);
 	}
 }
 
-static GAS_TOKEN_HASH: Lazy<ScriptHash> =
-	Lazy::new(|| ScriptHash::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap());
-
 impl<P: JsonRpcClient> TransactionBuilder<P> {
-	// const GAS_TOKEN_HASH: ScriptHash = ScriptHash::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap();
 	pub const BALANCE_OF_FUNCTION: &'static str = "balanceOf";
 	pub const DUMMY_PUB_KEY: &'static str =
 		"02ec143f00b88524caf36a0121c2de09eef0519ddbe1c710a00f0e2663201ee4c0";
@@ -158,6 +166,8 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 			script: None,
 			fee_consumer: None,
 			fee_error: None,
+			fee_sponsor: None,
+			witnesses: Vec::new(),
 		}
 	}
 
@@ -196,13 +206,74 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 		self
 	}
 
-	// Get unsigned transaction
-	pub async fn get_unsigned_tx(&mut self) -> Result<Transaction, TransactionError> {
+	/// Sets the provider used to query the node, e.g. for the network fee and the network magic.
+	pub fn with_provider(&mut self, provider: Arc<Provider<P>>) -> &mut Self {
+		self.provider = Some(provider);
+		self
+	}
+
+	/// Lets `sponsor` pay the fees of the transaction instead of the first of the signers.
+	///
+	/// The sponsor is made the first signer when the transaction is built, with the `None` scope
+	/// unless it is already a signer, see [`FeeSponsor::apply`], and its witness is created by
+	/// [`TransactionBuilder::sign`].
+	pub fn with_fee_sponsor(&mut self, sponsor: FeeSponsor) -> &mut Self {
+		self.fee_sponsor = Some(sponsor);
+		self
+	}
+
+	/// Attaches `witness` for the signer with the script hash `signer_hash`, e.g. a witness
+	/// created by an external signer, replacing any witness attached for it before.
+	///
+	/// Attached witnesses are placed in the order of the signers by [`TransactionBuilder::sign`]
+	/// and [`TransactionBuilder::finalize`], whatever the order they are attached in.
+	pub fn witness(&mut self, signer_hash: H160, witness: Witness) -> &mut Self {
+		match self.witnesses.iter_mut().find(|(hash, _)| *hash == signer_hash) {
+			Some((_, attached)) => *attached = witness,
+			None => self.witnesses.push((signer_hash, witness)),
+		}
+		self
+	}
+
+	/// Builds the unsigned transaction with its fees.
+	///
+	/// The system fee is the GAS consumed by a test invocation of the script, and the network fee
+	/// is calculated by the node, see [`TransactionBuilder::get_network_fee`]. Both are queried
+	/// with the provider set by [`TransactionBuilder::with_provider`], and increased by the
+	/// additional fees.
+	pub async fn get_unsigned_tx(&mut self) -> Result<Transaction, BuilderError> {
+		let mut tx = self.build_tx()?;
+		let system_fee = Self::add_fee(self.get_system_fee().await?, self.additional_system_fee)?;
+		let network_fee = Self::add_fee(
+			self.calculate_network_fee(tx.clone()).await?,
+			self.additional_network_fee,
+		)?;
+
+		// Check sender balance if needed
+		if let Some(fee_consumer) = &self.fee_consumer {
+			let fees = system_fee.saturating_add(network_fee).datoms() as u64;
+			let sender_balance = self.get_sender_balance().await?;
+			if fees > sender_balance {
+				fee_consumer(fees, sender_balance);
+			}
+		}
+
+		tx.sys_fee = system_fee;
+		tx.net_fee = network_fee;
+		Ok(tx)
+	}
+
+	/// Validates the configuration and builds the transaction without fees.
+	fn build_tx(&mut self) -> Result<Transaction, TransactionError> {
 		// Validate configuration
 		if self.signers.is_empty() {
 			return Err(TransactionError::NoSigners)
 		}
 
+		if let Some(sponsor) = &self.fee_sponsor {
+			sponsor.apply(&mut self.signers)?;
+		}
+
 		if self.script.is_none() {
 			return Err(TransactionError::NoScript)
 		}
@@ -228,72 +299,111 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 			return Err(TransactionError::NoScript)
 		}
 
-		let mut tx = Transaction::new();
-		// 	self.version,
-		// 	self.nonce,
-		// 	self.valid_until_block.unwrap(),
-		// 	self.clone().signers,
-		// 	0,
-		// 	0,
-		// 	self.clone().attributes,
-		// 	self.clone().script.unwrap(),
-		// 	vec![],
-		// );
-
-		// Get fees
-		let system_fee = 0; //self.get_system_fee().await.unwrap();
-		let network_fee = 0; //self.get_network_fee(&tx).await.unwrap();
+		Ok(Transaction {
+			version: self.version,
+			nonce: self.nonce as i32,
+			valid_until_block: self.valid_until_block.unwrap_or_default() as i32,
+			sender: *self.signers[0].get_signer_hash(),
+			signers: self.signers.clone(),
+			attributes: self.attributes.clone(),
+			script: self.script.clone().unwrap_or_default(),
+			..Transaction::new()
+		})
+	}
 
-		// Check sender balance if needed
-		if let Some(fee_consumer) = &self.fee_consumer {
-			let sender_balance = 0; // self.get_sender_balance().await.unwrap();
-			if network_fee + system_fee > sender_balance {
-				fee_consumer(network_fee + system_fee, sender_balance);
-			}
+	/// The GAS consumed by a test invocation of the script with the signers.
+	async fn get_system_fee(&self) -> Result<Gas, TransactionError> {
+		let script = self.script.as_ref().ok_or(TransactionError::NoScript)?;
+		let result = self
+			.provider()?
+			.invoke_script(encoding::base64_encode(script), self.signers.clone())
+			.await?;
+		if result.has_state_fault() {
+			return Err(TransactionError::TransactionConfiguration(format!(
+				"test invocation faulted: {}",
+				result.exception.unwrap_or_default()
+			)))
 		}
+		let gas_consumed = result.gas_consumed.parse::<i64>().map_err(|e| {
+			TransactionError::TransactionConfiguration(format!("invalid gas consumed: {e}"))
+		})?;
+		Ok(Gas::from_datoms(gas_consumed))
+	}
 
-		// tx.set_network_fee(network_fee as i64);
-		// tx.set_system_fee(system_fee as i64);
-		// Build transaction
-		Ok(tx)
+	fn add_fee(fee: Gas, additional: u64) -> Result<Gas, TransactionError> {
+		i64::try_from(additional)
+			.ok()
+			.and_then(|additional| fee.checked_add(Gas::from_datoms(additional)))
+			.ok_or_else(|| TransactionError::TransactionConfiguration("fee overflow".to_string()))
 	}
 
-	// async fn get_system_fee(&self) -> Result<u64, TransactionError> {
-	// 	let script = self.script.as_ref().unwrap();
-	//
-	// 	let response = NEO_INSTANCE
-	// 		.read()
-	// 		.unwrap()
-	// 		.invoke_script(script.to_hex(), vec![self.signers[0].clone()])
-	// 		.request()
-	// 		.await
-	// 		.unwrap();
-	// 	Ok(u64::from_str(response.gas_consumed.as_str()).unwrap()) // example
-	// }
+	fn provider(&self) -> Result<&Provider<P>, TransactionError> {
+		self.provider.as_deref().ok_or_else(|| {
+			TransactionError::TransactionConfiguration(
+				"The builder has no provider to query the node.".to_string(),
+			)
+		})
+	}
+
+	/// Calculates the network fee of the transaction with the node.
+	///
+	/// The node needs the verification script of every signer, and the invocation script of
+	/// contract signers, as it runs their `verify` method to charge the GAS it consumes. Attached
+	/// witnesses are used as is, account signers get their verification script, and contract
+	/// signers get the witness built from their verification parameters, see
+	/// [`Witness::create_contract_witness`].
+	pub async fn get_network_fee(&mut self) -> Result<Gas, BuilderError> {
+		let tx = self.build_tx()?;
+		self.calculate_network_fee(tx).await
+	}
 
-	async fn get_network_fee(&mut self, tx: &Transaction) -> Result<u64, TransactionError> {
-		let fee = self.provider.unwrap().calculate_network_fee(tx.to_array().to_hex()).await?;
+	async fn calculate_network_fee(&self, mut tx: Transaction) -> Result<Gas, BuilderError> {
+		for signer in &tx.signers {
+			let witness = if let Some(witness) = self.attached_witness(signer) {
+				witness.clone()
+			} else if let Some(contract_signer) = signer.as_contract_signer() {
+				contract_signer.witness()?
+			} else {
+				let script = signer
+					.as_account_signer()
+					.and_then(|account_signer| {
+						account_signer.account().verification_script().clone()
+					})
+					.map(|script| script.script().clone())
+					.unwrap_or_default();
+				Witness::from_scripts(vec![], script)
+			};
+			tx.witnesses.push(witness);
+		}
+		let fee = self
+			.provider()?
+			.calculate_network_fee(encoding::base64_encode(tx.to_array()))
+			.await?;
 		Ok(fee)
 	}
 
 	async fn get_sender_balance(&self) -> Result<u64, TransactionError> {
 		// Call network
-		let sender = &self.signers[0];
+		let sender = self.signers.first().ok_or(TransactionError::NoSigners)?;
 
 		if Self::is_account_signer(sender) {
-			let balance = self
-				.provider
-				.unwrap()
+			let result = self
+				.provider()?
 				.invoke_function(
-					&GAS_TOKEN_HASH,
+					&natives::GAS_TOKEN.hash,
 					Self::BALANCE_OF_FUNCTION.to_string(),
 					vec![ContractParameter::hash160(sender.get_signer_hash())],
 					None,
 				)
-				.await?
-				.stack[0]
-				.clone();
-			return Ok(balance.as_int().unwrap() as u64)
+				.await?;
+			let balance = result.stack.first().and_then(|item| item.as_int()).ok_or_else(|| {
+				TransactionError::IllegalState(format!(
+					"Unexpected result of {}: {:?}",
+					Self::BALANCE_OF_FUNCTION,
+					result.stack
+				))
+			})?;
+			return Ok(balance as u64)
 		}
 		Err(TransactionError::InvalidSender)
 	}
@@ -306,16 +416,33 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 		return false
 	}
 
-	// Sign transaction
+	/// Builds and signs the transaction, with one witness per signer in the order of the
+	/// signers. Witnesses attached with [`TransactionBuilder::witness`] are used as is, the
+	/// others are created from the accounts of the signers.
 	pub async fn sign(&mut self) -> Result<Transaction, BuilderError> {
-		let mut transaction = self.get_unsigned_tx().await.unwrap();
-		let tx_bytes = transaction.get_hash_data()?;
+		self.check_attached_witnesses()?;
+		let mut transaction = self.get_unsigned_tx().await?;
+		let network_magic = match transaction.network_magic() {
+			Some(magic) => magic,
+			None =>
+				self.provider
+					.as_ref()
+					.ok_or_else(|| {
+						BuilderError::IllegalState("The network magic is unknown.".to_string())
+					})?
+					.get_network_magic()
+					.await?,
+		};
+		let tx_bytes = transaction.get_hash_data(network_magic);
 
 		let mut witnesses_to_add = Vec::new();
 
-		for signer in &mut transaction.signers {
-			if Self::is_account_signer(signer) {
-				let account_signer = signer.as_account_signer().unwrap();
+		for (index, signer) in transaction.signers.iter().enumerate() {
+			if let Some(witness) = self.attached_witness(signer) {
+				witnesses_to_add.push(witness.clone());
+			} else if let Some(sponsor) = self.fee_sponsor.as_ref().filter(|_| index == 0) {
+				witnesses_to_add.push(sponsor.witness(&transaction, network_magic).await?);
+			} else if let Some(account_signer) = signer.as_account_signer() {
 				let acc = &account_signer.account;
 				if acc.is_multi_sig() {
 					return Err(BuilderError::IllegalState(
@@ -331,27 +458,75 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 				})?;
 
 				witnesses_to_add.push(Witness::create(tx_bytes.clone(), key_pair)?);
+			} else if let Some(contract_signer) = signer.as_contract_signer() {
+				witnesses_to_add.push(contract_signer.witness()?);
 			} else {
-				let contract_signer = signer.as_contract_signer().unwrap();
-				witnesses_to_add
-					.push(Witness::create_contract_witness(contract_signer.verify_params.clone())?);
+				return Err(BuilderError::IllegalState(format!(
+					"Cannot sign for the {:?} signer {}",
+					signer.get_type(),
+					signer.get_signer_hash()
+				)))
 			}
 		}
 
 		for witness in witnesses_to_add {
 			transaction.add_witness(witness);
 		}
+		transaction.validate_witnesses()?;
+
+		Ok(transaction)
+	}
 
+	/// Builds the transaction with the witnesses attached with [`TransactionBuilder::witness`],
+	/// without signing anything.
+	///
+	/// Fails unless every signer has an attached witness, and every attached witness belongs to
+	/// a signer, see [`Transaction::validate_witnesses`].
+	pub async fn finalize(&mut self) -> Result<Transaction, BuilderError> {
+		self.check_attached_witnesses()?;
+		let mut transaction = self.get_unsigned_tx().await?;
+		for signer in &transaction.signers {
+			let witness = self.attached_witness(signer).ok_or_else(|| {
+				TransactionError::WitnessConfiguration(format!(
+					"No witness attached for the signer {:?}",
+					signer.get_signer_hash()
+				))
+			})?;
+			transaction.witnesses.push(witness.clone());
+		}
+		transaction.validate_witnesses()?;
 		Ok(transaction)
 	}
 
+	fn attached_witness(&self, signer: &Signer) -> Option<&Witness> {
+		self.witnesses
+			.iter()
+			.find(|(hash, _)| hash == signer.get_signer_hash())
+			.map(|(_, witness)| witness)
+	}
+
+	/// Fails if a witness is attached for a script hash which is not a signer.
+	fn check_attached_witnesses(&self) -> Result<(), TransactionError> {
+		let sponsor = self.fee_sponsor.as_ref().map(FeeSponsor::script_hash);
+		for (hash, _) in &self.witnesses {
+			if Some(*hash) != sponsor
+				&& !self.signers.iter().any(|signer| signer.get_signer_hash() == hash)
+			{
+				return Err(TransactionError::WitnessConfiguration(format!(
+					"A witness is attached for {hash:?}, which is not a signer"
+				)))
+			}
+		}
+		Ok(())
+	}
+
 	fn signers_contain_multi_sig_with_committee_member(&self, committee: &HashSet<H160>) -> bool {
 		for signer in &self.signers {
 			if let Some(account_signer) = signer.as_account_signer() {
 				if account_signer.is_multi_sig() {
 					if let Some(script) = &account_signer.account().verification_script() {
-						for pubkey in script.get_public_keys().unwrap() {
-							let hash = public_key_to_script_hash(&pubkey);
+						for pubkey in script.get_public_keys().unwrap_or_default() {
+							let hash = ScriptHash::from_public_key(&pubkey);
 							if committee.contains(&hash) {
 								return true
 							}
@@ -392,3 +567,134 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 	// 	Ok(result.stack[0].as_int().unwrap() as u64)
 	// }
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		core::transaction::signers::{
+			account_signer::AccountSigner, contract_signer::ContractSigner, signer::SignerTrait,
+		},
+		Expectation, MockProvider,
+	};
+	use neo_types::op_code::OpCode;
+	use serde_json::json;
+
+	const MAGIC: u32 = 860833102;
+
+	/// A provider answering every test invocation and network fee calculation with fixed fees.
+	fn fee_provider() -> (Arc<Provider<MockProvider>>, MockProvider) {
+		let (provider, mock) = Provider::mocked();
+		provider.shared_config().set_network_magic(MAGIC);
+		mock.expect(
+			Expectation::new("invokescript")
+				.returning(json!({
+					"script": "EUA=",
+					"state": "HALT",
+					"gasconsumed": "997775",
+					"stack": [],
+				}))
+				.always(),
+		);
+		mock.expect(
+			Expectation::new("calculatenetworkfee")
+				.returning(json!({ "networkfee": "1234567" }))
+				.always(),
+		);
+		(Arc::new(provider), mock)
+	}
+
+	#[tokio::test]
+	async fn test_sign_sets_fees() {
+		let (provider, _mock) = fee_provider();
+		let alice = Account::create().unwrap();
+		let mut builder = TransactionBuilder::new();
+		builder
+			.with_provider(provider)
+			.set_script(vec![0x11, 0x40])
+			.set_signers(vec![AccountSigner::called_by_entry(&alice).unwrap().into()]);
+
+		let tx = builder.sign().await.unwrap();
+		assert_eq!(tx.sys_fee, Gas::from_datoms(997_775));
+		assert_eq!(tx.net_fee, Gas::from_datoms(1_234_567));
+		assert_eq!(tx.witnesses.len(), 1);
+		let unsigned = builder.get_unsigned_tx().await.unwrap();
+		assert_eq!(unsigned.get_hash_data(MAGIC), tx.get_hash_data(MAGIC));
+
+		// without a provider the fees are unknown
+		let mut builder = TransactionBuilder::<MockProvider>::new();
+		builder
+			.set_script(vec![0x11, 0x40])
+			.set_signers(vec![AccountSigner::called_by_entry(&alice).unwrap().into()]);
+		assert!(builder.get_unsigned_tx().await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_finalize_orders_witnesses() {
+		let (provider, _mock) = fee_provider();
+		let alice = Account::create().unwrap();
+		let bob = Account::create().unwrap();
+		let mut builder = TransactionBuilder::new();
+		builder.with_provider(provider).set_script(vec![0x11, 0x40]).set_signers(vec![
+			AccountSigner::called_by_entry(&alice).unwrap().into(),
+			AccountSigner::called_by_entry(&bob).unwrap().into(),
+		]);
+		let hash_data = builder.get_unsigned_tx().await.unwrap().get_hash_data(MAGIC);
+		let witness = |account: &Account| {
+			Witness::create(hash_data.clone(), account.key_pair().as_ref().unwrap()).unwrap()
+		};
+
+		builder.witness(bob.get_script_hash(), witness(&bob));
+		assert!(matches!(
+			builder.finalize().await,
+			Err(BuilderError::TransactionError(TransactionError::WitnessConfiguration(_)))
+		));
+
+		// attached out of order, placed in the order of the signers
+		builder.witness(alice.get_script_hash(), witness(&alice));
+		let tx = builder.finalize().await.unwrap();
+		let verified: Vec<_> = tx.witnesses.iter().map(|w| w.verification.hash()).collect();
+		assert_eq!(verified, vec![alice.get_script_hash(), bob.get_script_hash()]);
+
+		let mut swapped = tx.clone();
+		swapped.witnesses.reverse();
+		assert!(swapped.validate_witnesses().is_err());
+
+		let carol = Account::create().unwrap();
+		builder.witness(carol.get_script_hash(), witness(&carol));
+		assert!(builder.finalize().await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_network_fee_with_contract_signer() {
+		let (provider, mock) = Provider::mocked();
+		let provider = Arc::new(provider);
+		let owner = Account::create().unwrap();
+		let vault = H160::repeat_byte(0x42);
+		let vault_signer = ContractSigner::called_by_entry(vault, &[]).with_verify_params(&[
+			ContractParameter::integer(1),
+			ContractParameter::hash160(&owner.get_script_hash()),
+		]);
+		assert_eq!(*vault_signer.get_signer_hash(), vault);
+		let witness = vault_signer.witness().unwrap();
+		// the first argument of `verify` is pushed last
+		assert_eq!(witness.invocation.last(), Some(&(OpCode::Push1 as u8)));
+		assert!(witness.verification.script().is_empty());
+
+		let mut builder = TransactionBuilder::new();
+		builder.with_provider(provider).set_script(vec![0x11, 0x40]).set_signers(vec![
+			AccountSigner::called_by_entry(&owner).unwrap().into(),
+			vault_signer.into(),
+		]);
+		let mut tx = builder.build_tx().unwrap();
+		let verification = owner.verification_script().clone().unwrap();
+		tx.witnesses = vec![Witness::from_scripts(vec![], verification.script().clone()), witness];
+		mock.expect(
+			Expectation::new("calculatenetworkfee")
+				.with_params([encoding::base64_encode(tx.to_array())])
+				.returning(json!({ "networkfee": "1234567" })),
+		);
+		assert_eq!(builder.get_network_fee().await.unwrap(), Gas::from_datoms(1_234_567));
+		mock.assert_expectations_met().unwrap();
+	}
+}
@@ -1,6 +1,7 @@
 use crate::ProviderError;
 use neo_codec::CodecError;
 use neo_crypto::error::CryptoError;
+use neo_types::encoding::EncodingError;
 use std::error;
 use thiserror::Error;
 
@@ -18,6 +19,8 @@ pub enum TransactionError {
 	InvalidTransaction,
 	#[error("Invalid witness condition")]
 	InvalidWitnessCondition,
+	#[error("Witness configuration error: {0}")]
+	WitnessConfiguration(String),
 	#[error("Too many signers")]
 	TooManySigners,
 	#[error("Duplicate signer")]
@@ -38,6 +41,8 @@ pub enum TransactionError {
 	TransactionConfiguration(String),
 	#[error("Codec error: {0}")]
 	CodecError(#[from] CodecError),
+	#[error("Encoding error: {0}")]
+	EncodingError(#[from] EncodingError),
 	#[error("Crypto error: {0}")]
 	CryptoError(#[from] CryptoError),
 	#[error(transparent)]
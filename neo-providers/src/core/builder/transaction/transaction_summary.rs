@@ -0,0 +1,505 @@
+//! Human-readable summaries of transactions, see [`Transaction::explain`].
+//!
+//! The script of the transaction is decoded with [`ScriptReader::contract_calls`], and common
+//! calls like NEP-17 transfers, contract deployments and votes are recognized. Contracts and
+//! accounts are named with an [`AddressBook`] and the manifests of a [`ManifestRegistry`].
+
+use crate::{
+	core::{
+		script::{
+			script_builder::ScriptBuilder,
+			script_reader::{ContractCall, ScriptReader, ScriptValue},
+		},
+		transaction::{transaction::Transaction, witness_scope::WitnessScope},
+	},
+	Middleware,
+};
+use neo_addressbook::AddressBook;
+use neo_crypto::keys::Secp256r1PublicKey;
+use neo_types::{
+	address::Address,
+	contract_manifest::ContractManifest,
+	contract_parameter_type::ContractParameterType,
+	gas::Gas,
+	natives,
+	script_hash::{ScriptHashBE, ScriptHashExt},
+};
+use primitive_types::{H160, H256};
+use std::{collections::HashMap, fmt};
+
+/// The manifests of deployed contracts by script hash, used by [`Transaction::explain`] to name
+/// contracts and the arguments of their methods.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestRegistry {
+	manifests: HashMap<H160, ContractManifest>,
+}
+
+impl ManifestRegistry {
+	/// An empty registry
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the manifest of the contract `hash`, replacing a registered one.
+	pub fn insert(&mut self, hash: H160, manifest: ContractManifest) {
+		self.manifests.insert(hash, manifest);
+	}
+
+	/// The manifest of the contract `hash`
+	pub fn get(&self, hash: &H160) -> Option<&ContractManifest> {
+		self.manifests.get(hash)
+	}
+
+	/// Fetches the manifests of the contracts called by `tx` that are not registered yet.
+	///
+	/// Nothing is fetched if the calls of the script cannot be decoded.
+	pub async fn fetch_for<M: Middleware>(
+		&mut self,
+		client: &M,
+		tx: &Transaction,
+	) -> Result<(), M::Error> {
+		let calls = ScriptReader::contract_calls(&tx.script).unwrap_or_default();
+		for call in calls {
+			if !self.manifests.contains_key(&call.contract) {
+				let state = client.get_contract_state(call.contract).await?;
+				self.manifests.insert(call.contract, state.manifest);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// An account, with its name in the address book if it has one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedAccount {
+	pub script_hash: H160,
+	pub name: Option<String>,
+}
+
+impl NamedAccount {
+	/// The address of the account
+	pub fn address(&self) -> Address {
+		Address::from_script_hash(self.script_hash)
+	}
+}
+
+impl fmt::Display for NamedAccount {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.name {
+			Some(name) => write!(f, "{name} ({})", self.address()),
+			None => write!(f, "{}", self.address()),
+		}
+	}
+}
+
+/// A contract, with its name in the address book or its manifest if either is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedContract {
+	pub script_hash: H160,
+	pub name: Option<String>,
+}
+
+impl fmt::Display for NamedContract {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.name {
+			Some(name) => f.write_str(name),
+			None => write!(f, "{}", ScriptHashBE(self.script_hash)),
+		}
+	}
+}
+
+/// An argument of a [`Action::Call`], named after the parameter of the method in the manifest
+/// of the contract if it is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedArgument {
+	pub name: Option<String>,
+	pub typ: Option<ContractParameterType>,
+	pub value: ScriptValue,
+}
+
+/// What a contract call of a transaction does
+#[derive(Debug, Clone)]
+pub enum Action {
+	/// A `transfer` of a NEP-17 token, the amount in the smallest unit of the token
+	Nep17Transfer { token: NamedContract, from: NamedAccount, to: NamedAccount, amount: i128 },
+	/// The deployment of a contract with `ContractManagement.deploy`. The name is taken from
+	/// the manifest, the script hash is derived from the name, the NEF file and the sender.
+	Deploy { name: Option<String>, script_hash: Option<H160> },
+	/// A vote of `account` for `candidate` with `NeoToken.vote`, or the cancellation of its
+	/// vote if there is no candidate
+	Vote { account: NamedAccount, candidate: Option<Secp256r1PublicKey> },
+	/// Any other contract call
+	Call { contract: NamedContract, method: String, args: Vec<NamedArgument> },
+}
+
+/// A structured summary of a transaction, e.g. for confirmation screens, see
+/// [`Transaction::explain`].
+///
+/// The `Display` implementation renders the summary as text, one action per line.
+#[derive(Debug, Clone)]
+pub struct TransactionSummary {
+	pub hash: H256,
+	/// The first signer, which pays the fees
+	pub sender: Option<NamedAccount>,
+	pub signers: Vec<(NamedAccount, Vec<WitnessScope>)>,
+	pub system_fee: Gas,
+	pub network_fee: Gas,
+	pub valid_until_block: i32,
+	/// The contract calls of the script, in order. Empty if they could not be decoded, see
+	/// `decoded`.
+	pub actions: Vec<Action>,
+	/// Whether the contract calls of the script were decoded. Scripts computing the arguments
+	/// of their calls or jumping cannot be decoded and are only described by `disassembly`.
+	pub decoded: bool,
+	/// The opcodes of the script, one per line
+	pub disassembly: String,
+}
+
+impl TransactionSummary {
+	/// Summarizes `tx`, naming contracts and accounts with `address_book` and `manifests`.
+	pub fn new(tx: &Transaction, address_book: &AddressBook, manifests: &ManifestRegistry) -> Self {
+		let namer = Namer { address_book, manifests };
+		let sender_hash = tx.signers.first().map(|signer| *signer.get_signer_hash());
+		let calls = ScriptReader::contract_calls(&tx.script);
+		let decoded = calls.is_ok();
+		let actions = calls
+			.unwrap_or_default()
+			.into_iter()
+			.map(|call| namer.action(call, sender_hash))
+			.collect();
+
+		Self {
+			hash: tx.hash(),
+			sender: sender_hash.map(|hash| namer.account(hash)),
+			signers: tx
+				.signers
+				.iter()
+				.map(|signer| {
+					(namer.account(*signer.get_signer_hash()), signer.get_scopes().clone())
+				})
+				.collect(),
+			system_fee: tx.sys_fee,
+			network_fee: tx.net_fee,
+			valid_until_block: tx.valid_until_block,
+			actions,
+			decoded,
+			disassembly: ScriptReader::convert_to_op_code_string(&tx.script),
+		}
+	}
+}
+
+impl fmt::Display for TransactionSummary {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Transaction {:?}", self.hash)?;
+		if let Some(sender) = &self.sender {
+			writeln!(f, "Sender: {sender}")?;
+		}
+		for (signer, scopes) in &self.signers {
+			let scopes: Vec<String> = scopes.iter().map(ToString::to_string).collect();
+			writeln!(f, "Signer: {signer} [{}]", scopes.join(", "))?;
+		}
+		writeln!(f, "System fee: {}", self.system_fee)?;
+		writeln!(f, "Network fee: {}", self.network_fee)?;
+		writeln!(f, "Valid until block: {}", self.valid_until_block)?;
+		if !self.decoded {
+			return write!(f, "The script could not be decoded:\n{}", self.disassembly)
+		}
+		for (index, action) in self.actions.iter().enumerate() {
+			writeln!(f, "{}. {action}", index + 1)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for Action {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Action::Nep17Transfer { token, from, to, amount } =>
+				write!(f, "Transfer {amount} units of {token} from {from} to {to}"),
+			Action::Deploy { name, script_hash } => {
+				write!(f, "Deploy the contract {}", name.as_deref().unwrap_or("without name"))?;
+				match script_hash {
+					Some(hash) => write!(f, " at {}", ScriptHashBE(*hash)),
+					None => Ok(()),
+				}
+			},
+			Action::Vote { account, candidate: Some(candidate) } =>
+				write!(f, "Vote for {} with {account}", candidate.get_encoded_compressed_hex()),
+			Action::Vote { account, candidate: None } => write!(f, "Cancel the vote of {account}"),
+			Action::Call { contract, method, args } => {
+				let args: Vec<String> = args
+					.iter()
+					.map(|arg| {
+						let value = render_value(&arg.value, arg.typ);
+						match &arg.name {
+							Some(name) => format!("{name}: {value}"),
+							None => value,
+						}
+					})
+					.collect();
+				write!(f, "Call {contract}.{method}({})", args.join(", "))
+			},
+		}
+	}
+}
+
+/// Names contracts and accounts for a [`TransactionSummary`]
+struct Namer<'a> {
+	address_book: &'a AddressBook,
+	manifests: &'a ManifestRegistry,
+}
+
+impl Namer<'_> {
+	fn account(&self, script_hash: H160) -> NamedAccount {
+		NamedAccount { script_hash, name: self.address_book.name_of(&script_hash).map(Into::into) }
+	}
+
+	fn contract(&self, script_hash: H160) -> NamedContract {
+		let name = self.address_book.name_of(&script_hash).map(Into::into).or_else(|| {
+			self.manifests.get(&script_hash).and_then(|manifest| manifest.name.clone())
+		});
+		NamedContract { script_hash, name }
+	}
+
+	fn action(&self, call: ContractCall, sender: Option<H160>) -> Action {
+		if let Some((token, from, to, amount)) = call.as_nep17_transfer() {
+			return Action::Nep17Transfer {
+				token: self.contract(token),
+				from: self.account(from),
+				to: self.account(to),
+				amount,
+			}
+		}
+		if let Some(action) = self.deploy(&call, sender).or_else(|| self.vote(&call)) {
+			return action
+		}
+
+		let parameters = self
+			.manifests
+			.get(&call.contract)
+			.and_then(|manifest| manifest.abi.as_ref())
+			.and_then(|abi| {
+				abi.methods.iter().find(|method| {
+					method.name == call.method && method.parameters.len() == call.args.len()
+				})
+			})
+			.map(|method| method.parameters.as_slice())
+			.unwrap_or_default();
+		let args = call
+			.args
+			.into_iter()
+			.enumerate()
+			.map(|(index, value)| {
+				let parameter = parameters.get(index);
+				NamedArgument {
+					name: parameter.and_then(|p| p.get_name()).map(Into::into),
+					typ: parameter.map(|p| p.get_type()),
+					value,
+				}
+			})
+			.collect();
+		Action::Call { contract: self.contract(call.contract), method: call.method, args }
+	}
+
+	fn deploy(&self, call: &ContractCall, sender: Option<H160>) -> Option<Action> {
+		if call.contract != natives::CONTRACT_MANAGEMENT.hash || call.method != "deploy" {
+			return None
+		}
+		let (nef, manifest) = match call.args.as_slice() {
+			[ScriptValue::Bytes(nef), ScriptValue::Bytes(manifest), ..] if nef.len() >= 4 =>
+				(nef, manifest),
+			_ => return None,
+		};
+		let name = serde_json::from_slice::<serde_json::Value>(manifest)
+			.ok()
+			.and_then(|manifest| manifest["name"].as_str().map(String::from));
+		// the checksum ends the NEF file
+		let checksum = u32::from_le_bytes(nef[nef.len() - 4..].try_into().ok()?);
+		let script_hash = match (sender, &name) {
+			(Some(sender), Some(name)) =>
+				ScriptBuilder::build_contract_script(&sender, checksum, name)
+					.ok()
+					.map(|script| H160::from_script(&script)),
+			_ => None,
+		};
+		Some(Action::Deploy { name, script_hash })
+	}
+
+	fn vote(&self, call: &ContractCall) -> Option<Action> {
+		if call.contract != natives::NEO_TOKEN.hash || call.method != "vote" {
+			return None
+		}
+		let (account, candidate) = match call.args.as_slice() {
+			[account, ScriptValue::Null] => (account.as_hash160()?, None),
+			[account, ScriptValue::Bytes(key)] =>
+				(account.as_hash160()?, Some(Secp256r1PublicKey::from_bytes(key).ok()?)),
+			_ => return None,
+		};
+		Some(Action::Vote { account: self.account(account), candidate })
+	}
+}
+
+/// Renders an argument, as an address if the method takes a `Hash160`
+fn render_value(value: &ScriptValue, typ: Option<ContractParameterType>) -> String {
+	match value {
+		ScriptValue::Null => "null".to_string(),
+		ScriptValue::Boolean(value) => value.to_string(),
+		ScriptValue::Integer(value) => value.to_string(),
+		ScriptValue::Bytes(_) if typ == Some(ContractParameterType::H160) =>
+			match value.as_hash160() {
+				Some(hash) => Address::from_script_hash(hash).to_string(),
+				None => "invalid Hash160".to_string(),
+			},
+		ScriptValue::Bytes(bytes) => match std::str::from_utf8(bytes) {
+			Ok(text) if !text.is_empty() && !text.chars().any(char::is_control) =>
+				format!("{text:?}"),
+			_ => format!("0x{}", hex::encode(bytes)),
+		},
+		ScriptValue::Array(items) => {
+			let items: Vec<String> = items.iter().map(|item| render_value(item, None)).collect();
+			format!("[{}]", items.join(", "))
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::transaction::{call_flags::CallFlags, signers::account_signer::AccountSigner};
+	use neo_config::{NeoNetwork, TestConstants};
+	use neo_crypto::keys::Secp256r1PublicKey;
+	use neo_types::{
+		contract_parameter::ContractParameter, contract_parameter_type::ContractParameterType,
+	};
+
+	const VAULT_MANIFEST: &str = r#"{
+		"name": "Vault",
+		"abi": {
+			"methods": [{
+				"name": "withdraw",
+				"parameters": [{"name": "to", "type": "Hash160"}, {"name": "amount", "type": "Integer"}],
+				"offset": 0,
+				"returntype": "Boolean",
+				"safe": false
+			}]
+		},
+		"trusts": "*"
+	}"#;
+
+	#[test]
+	fn test_explain() {
+		let sender = H160::from_address("NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke").unwrap();
+		let treasury = H160::repeat_byte(0x02);
+		let gas = natives::GAS_TOKEN.hash;
+		let neo = natives::NEO_TOKEN.hash;
+		let vault = H160::repeat_byte(0x07);
+		let candidate = hex::decode(TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY).unwrap();
+		let nef = b"NEF3\x00\x00\x00\x00\x01\x02\x03\x04".to_vec();
+		let any = ContractParameter::new(ContractParameterType::Any);
+		let account = ContractParameter::hash160(&sender);
+
+		let mut builder = ScriptBuilder::new();
+		let calls = [
+			(
+				gas,
+				"transfer",
+				vec![
+					account.clone(),
+					ContractParameter::hash160(&treasury),
+					ContractParameter::integer(100),
+					any.clone(),
+				],
+			),
+			(
+				neo,
+				"vote",
+				vec![
+					account.clone(),
+					ContractParameter::public_key(
+						&Secp256r1PublicKey::from_bytes(&candidate).unwrap(),
+					),
+				],
+			),
+			(neo, "vote", vec![account, any.clone()]),
+			(
+				natives::CONTRACT_MANAGEMENT.hash,
+				"deploy",
+				vec![
+					ContractParameter::byte_array(nef),
+					ContractParameter::string(r#"{"name":"Vault"}"#.to_string()),
+					any,
+				],
+			),
+			(
+				vault,
+				"withdraw",
+				vec![ContractParameter::hash160(&treasury), ContractParameter::integer(5)],
+			),
+		];
+		for (contract, method, params) in &calls {
+			builder.contract_call(contract, method, params, CallFlags::All).unwrap();
+		}
+
+		let tx = Transaction {
+			signers: vec![AccountSigner::called_by_entry_hash160(sender).unwrap().into()],
+			sys_fee: Gas::from_datoms(1_000_000),
+			valid_until_block: 100,
+			script: builder.to_bytes(),
+			..Default::default()
+		};
+		let mut address_book = AddressBook::bundled();
+		address_book.insert("treasury", NeoNetwork::MainNet, Address::from_script_hash(treasury));
+		let mut manifests = ManifestRegistry::new();
+		manifests.insert(vault, serde_json::from_str(VAULT_MANIFEST).unwrap());
+
+		let summary = tx.explain(&address_book, &manifests);
+		assert!(summary.decoded);
+		assert_eq!(summary.hash, tx.hash());
+		assert_eq!(summary.sender.as_ref().unwrap().script_hash, sender);
+		assert_eq!(summary.actions.len(), 5);
+		assert!(matches!(
+			&summary.actions[1],
+			Action::Vote { candidate: Some(key), .. } if key.get_encoded(true) == candidate
+		));
+		let deployed = ScriptBuilder::build_contract_script(&sender, 0x04030201, "Vault").unwrap();
+		assert!(matches!(
+			&summary.actions[3],
+			Action::Deploy { name: Some(name), script_hash: Some(hash) }
+				if name == "Vault" && *hash == H160::from_script(&deployed)
+		));
+
+		let treasury = Address::from_script_hash(treasury);
+		let text = summary.to_string();
+		let lines: Vec<&str> = text.lines().collect();
+		assert_eq!(lines[1], "Sender: NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke");
+		assert_eq!(lines[2], "Signer: NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke [CalledByEntry]");
+		assert_eq!(lines[3], "System fee: 0.01 GAS");
+		assert_eq!(
+			lines[6],
+			format!(
+				"1. Transfer 100 units of gas from NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke to treasury \
+				 ({treasury})"
+			)
+		);
+		assert_eq!(
+			lines[7],
+			format!(
+				"2. Vote for {} with NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke",
+				TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY
+			)
+		);
+		assert_eq!(lines[8], "3. Cancel the vote of NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke");
+		assert!(lines[9].starts_with("4. Deploy the contract Vault at 0x"));
+		assert_eq!(lines[10], format!("5. Call Vault.withdraw(to: {treasury}, amount: 5)"));
+	}
+
+	#[test]
+	fn test_explain_undecodable_script() {
+		// JMP 2, RET
+		let tx = Transaction { script: vec![0x22, 0x02, 0x40], ..Default::default() };
+		let summary = tx.explain(&AddressBook::new(), &ManifestRegistry::new());
+		assert!(!summary.decoded);
+		assert!(summary.actions.is_empty());
+		assert!(summary.to_string().ends_with("The script could not be decoded:\nJMP 02\nRET\n"));
+	}
+}
@@ -0,0 +1,279 @@
+//! Witness-less transaction templates for cold signing.
+//!
+//! A [`TransactionTemplate`] captures the decisions made when a transaction is constructed: the
+//! script, signers, attributes and nonce. It leaves out everything that depends on the state of
+//! the chain at signing time, the validity window and the fees. Templates can be serialized,
+//! stored and handed to another service, which instantiates them into an unsigned
+//! [`Transaction`] right before passing it to a (cold) signer.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use neo_providers::{
+//! 	core::transaction::{signers::signer::Signer, transaction_template::TransactionTemplate},
+//! 	Http, Provider,
+//! };
+//! use std::convert::TryFrom;
+//!
+//! # async fn foo(signers: Vec<Signer>, nonce: u32) -> Result<(), Box<dyn std::error::Error>> {
+//! // construction time
+//! let template = TransactionTemplate::new(vec![0x11, 0x40], &signers, nonce)?;
+//! let stored = serde_json::to_string(&template)?;
+//!
+//! // signing time
+//! let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+//! let template: TransactionTemplate = serde_json::from_str(&stored)?;
+//! let unsigned = template.instantiate_with(&provider).await?;
+//! # Ok(()) }
+//! ```
+
+use crate::{
+	core::transaction::{
+		signers::{signer::Signer, transaction_signer::TransactionSigner},
+		transaction::Transaction,
+		transaction_attribute::TransactionAttribute,
+		transaction_error::TransactionError,
+		witness::Witness,
+	},
+	Middleware,
+};
+use neo_codec::encode::NeoSerializable;
+use neo_config::NeoConstants;
+use neo_types::{encoding, gas::Gas, Bytes};
+use serde::{Deserialize, Serialize};
+
+/// A transaction without witnesses, fees and validity window.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash)]
+pub struct TransactionTemplate {
+	/// Transaction version
+	pub version: u8,
+	/// Random number making the transaction hash unique
+	pub nonce: u32,
+	/// The signers of the transaction, the first one pays the fees
+	pub signers: Vec<TransactionSigner>,
+	/// The verification scripts of the signers, in the same order. Empty for signers whose script
+	/// is not known, e.g. contracts. Only used to calculate the network fee.
+	pub verification_scripts: Vec<Bytes>,
+	/// Transaction attributes
+	pub attributes: Vec<TransactionAttribute>,
+	/// The script to execute
+	pub script: Bytes,
+	/// Network fee added on top of the calculated network fee
+	pub additional_network_fee: u64,
+	/// System fee added on top of the calculated system fee
+	pub additional_system_fee: u64,
+}
+
+impl TransactionTemplate {
+	/// Creates a template executing `script` signed by `signers`.
+	///
+	/// The nonce makes the hash of the transaction unique and should be random. The verification
+	/// scripts of account signers are kept so the network fee can be calculated later.
+	pub fn new(script: Bytes, signers: &[Signer], nonce: u32) -> Result<Self, TransactionError> {
+		let verification_scripts = signers
+			.iter()
+			.map(|signer| {
+				signer
+					.as_account_signer()
+					.and_then(|account_signer| {
+						account_signer.account().verification_script().clone()
+					})
+					.map(|script| script.script().clone())
+					.unwrap_or_default()
+			})
+			.collect();
+		let template = Self {
+			version: 0,
+			nonce,
+			signers: signers.iter().map(Into::into).collect(),
+			verification_scripts,
+			attributes: vec![],
+			script,
+			additional_network_fee: 0,
+			additional_system_fee: 0,
+		};
+		template.validate()?;
+		Ok(template)
+	}
+
+	/// Sets the attributes.
+	#[must_use]
+	pub fn with_attributes(mut self, attributes: Vec<TransactionAttribute>) -> Self {
+		self.attributes = attributes;
+		self
+	}
+
+	/// Sets the fees added on top of the calculated fees when the template is instantiated.
+	#[must_use]
+	pub fn with_additional_fees(mut self, network_fee: u64, system_fee: u64) -> Self {
+		self.additional_network_fee = network_fee;
+		self.additional_system_fee = system_fee;
+		self
+	}
+
+	/// Checks that the template can be turned into a valid transaction.
+	pub fn validate(&self) -> Result<(), TransactionError> {
+		if self.script.is_empty() {
+			return Err(TransactionError::EmptyScript)
+		}
+		if self.signers.is_empty() {
+			return Err(TransactionError::NoSigners)
+		}
+		if self.signers.len() > NeoConstants::MAX_SIGNER_SUBITEMS as usize {
+			return Err(TransactionError::TooManySigners)
+		}
+		if self.verification_scripts.len() != self.signers.len() {
+			return Err(TransactionError::TransactionConfiguration(
+				"one verification script is needed per signer".to_string(),
+			))
+		}
+		let hashes: Vec<_> = self.signers.iter().map(|signer| &signer.account).collect();
+		if hashes.iter().enumerate().any(|(i, hash)| hashes[..i].contains(hash)) {
+			return Err(TransactionError::DuplicateSigner)
+		}
+		Ok(())
+	}
+
+	/// Instantiates an unsigned transaction with the given validity window and fees.
+	///
+	/// The additional fees of the template are added to `system_fee` and `network_fee`.
+	pub fn instantiate(
+		&self,
+		valid_until_block: u32,
+		system_fee: u64,
+		network_fee: u64,
+	) -> Result<Transaction, TransactionError> {
+		self.validate()?;
+		if valid_until_block == 0 {
+			return Err(TransactionError::InvalidBlock)
+		}
+		let fee = |fee: u64, additional: u64| {
+			fee.checked_add(additional)
+				.and_then(|fee| i64::try_from(fee).ok())
+				.map(Gas::from_datoms)
+				.ok_or_else(|| {
+					TransactionError::TransactionConfiguration("fee overflow".to_string())
+				})
+		};
+
+		Ok(Transaction {
+			version: self.version,
+			nonce: self.nonce as i32,
+			valid_until_block: valid_until_block as i32,
+			sys_fee: fee(system_fee, self.additional_system_fee)?,
+			net_fee: fee(network_fee, self.additional_network_fee)?,
+			signers: self.signers.iter().cloned().map(Signer::from).collect(),
+			attributes: self.attributes.clone(),
+			script: self.script.clone(),
+			..Default::default()
+		})
+	}
+
+	/// Instantiates an unsigned transaction using the current state of the chain.
+	///
+	/// The transaction is valid for the maximum number of blocks allowed by the config of
+	/// `client`. The system fee is the GAS consumed by a test invocation of the script, and the
+	/// network fee is calculated by the node from the verification scripts of the signers.
+	/// Signers without a verification script, e.g. contracts, do not add to the network fee, which
+	/// can be compensated with [`TransactionTemplate::with_additional_fees`].
+	pub async fn instantiate_with<M: Middleware>(
+		&self,
+		client: &M,
+	) -> Result<Transaction, TransactionError> {
+		self.validate()?;
+		let block_count = client.get_block_count().await.map_err(rpc_error)?;
+		let valid_until_block = block_count + client.max_valid_until_block_increment() - 1;
+
+		let signers = self.signers.iter().cloned().map(Signer::from).collect();
+		let invocation = client
+			.invoke_script(encoding::base64_encode(&self.script), signers)
+			.await
+			.map_err(rpc_error)?;
+		if invocation.has_state_fault() {
+			return Err(TransactionError::TransactionConfiguration(format!(
+				"test invocation faulted: {}",
+				invocation.exception.unwrap_or_default()
+			)))
+		}
+		let system_fee = invocation.gas_consumed.parse::<u64>().map_err(|e| {
+			TransactionError::TransactionConfiguration(format!("invalid gas consumed: {e}"))
+		})?;
+
+		let mut tx = self.instantiate(valid_until_block, system_fee, 0)?;
+		// The node needs the verification scripts of the signers to calculate the network fee.
+		tx.witnesses = self
+			.verification_scripts
+			.iter()
+			.map(|script| Witness::from_scripts(vec![], script.clone()))
+			.collect();
+		let network_fee = client
+			.calculate_network_fee(encoding::base64_encode(tx.to_array()))
+			.await
+			.map_err(rpc_error)?;
+		let network_fee = u64::try_from(network_fee.datoms()).map_err(|_| {
+			TransactionError::TransactionConfiguration(format!("invalid network fee {network_fee}"))
+		})?;
+		tx.witnesses.clear();
+
+		self.instantiate(valid_until_block, system_fee, network_fee)
+	}
+}
+
+fn rpc_error(err: impl std::fmt::Display) -> TransactionError {
+	TransactionError::IllegalState(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::{
+		account::{Account, AccountTrait},
+		transaction::signers::account_signer::AccountSigner,
+	};
+	use neo_crypto::key_pair::KeyPair;
+
+	fn signer() -> Signer {
+		let account = Account::from_key_pair(KeyPair::new_random(), None, None).unwrap();
+		Signer::from(AccountSigner::called_by_entry(&account).unwrap())
+	}
+
+	#[test]
+	fn test_validate() {
+		let signer = signer();
+		assert!(matches!(
+			TransactionTemplate::new(vec![], &[signer.clone()], 1),
+			Err(TransactionError::EmptyScript)
+		));
+		assert!(matches!(
+			TransactionTemplate::new(vec![0x40], &[], 1),
+			Err(TransactionError::NoSigners)
+		));
+		assert!(matches!(
+			TransactionTemplate::new(vec![0x40], &[signer.clone(), signer], 1),
+			Err(TransactionError::DuplicateSigner)
+		));
+	}
+
+	#[test]
+	fn test_instantiate() {
+		let signer = signer();
+		let template = TransactionTemplate::new(vec![0x11, 0x40], &[signer.clone()], 7)
+			.unwrap()
+			.with_additional_fees(10, 20);
+		assert!(!template.verification_scripts[0].is_empty());
+
+		let json = serde_json::to_string(&template).unwrap();
+		let restored: TransactionTemplate = serde_json::from_str(&json).unwrap();
+		assert_eq!(restored, template);
+
+		let tx = restored.instantiate(1_000, 100, 200).unwrap();
+		assert_eq!(tx.nonce, 7);
+		assert_eq!(tx.valid_until_block, 1_000);
+		assert_eq!(tx.sys_fee, Gas::from_datoms(120));
+		assert_eq!(tx.net_fee, Gas::from_datoms(210));
+		assert_eq!(tx.script, vec![0x11, 0x40]);
+		assert_eq!(tx.signers[0].get_signer_hash(), signer.get_signer_hash());
+		assert!(tx.witnesses.is_empty());
+		assert!(matches!(restored.instantiate(0, 0, 0), Err(TransactionError::InvalidBlock)));
+	}
+}
@@ -3,9 +3,12 @@ use crate::core::{
 	script::{interop_service::InteropService, script_builder::ScriptBuilder},
 };
 use getset::{Getters, Setters};
-use neo_codec::{encode::NeoSerializable, Decoder, Encoder};
+use neo_codec::{
+	encode::{var_size, NeoSerializable},
+	Decoder, Encoder,
+};
 use neo_crypto::keys::{PublicKeyExtension, Secp256r1PublicKey, Secp256r1Signature};
-use neo_types::{op_code::OpCode, Bytes};
+use neo_types::{op_code::OpCode, script_hash::ScriptHashExt, Bytes};
 use num_bigint::BigInt;
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
@@ -26,33 +29,35 @@ impl VerificationScript {
 		Self { script: script.to_vec() }
 	}
 
-	pub fn from_public_key(public_key: &Secp256r1PublicKey) -> Self {
+	pub fn from_public_key(public_key: &Secp256r1PublicKey) -> Result<Self, BuilderError> {
 		let mut builder = ScriptBuilder::new();
 		builder
-			.push_data(public_key.to_raw_bytes().to_vec())
-			.unwrap()
-			.op_code(&vec![OpCode::Syscall])
-			.push_data(InteropService::SystemCryptoCheckSig.hash().into_bytes())
-			.unwrap();
-		Self::from(builder.to_bytes())
+			.push_data(public_key.get_encoded(true))?
+			.sys_call(InteropService::SystemCryptoCheckSig);
+		Ok(Self::from(builder.to_bytes()))
 	}
 
-	pub fn from_multi_sig(public_keys: &[Secp256r1PublicKey], threshold: u8) -> Self {
-		// Build multi-sig script
+	/// Builds the script of a multi-sig account. Fails if `threshold` is not between 1 and the
+	/// number of `public_keys`.
+	pub fn from_multi_sig(
+		public_keys: &[Secp256r1PublicKey],
+		threshold: u8,
+	) -> Result<Self, BuilderError> {
+		if threshold == 0 || threshold as usize > public_keys.len() {
+			return Err(BuilderError::IllegalArgument(format!(
+				"The signing threshold must be between 1 and {}, got {threshold}",
+				public_keys.len()
+			)))
+		}
 		let mut builder = ScriptBuilder::new();
-		builder
-			.push_integer(BigInt::from(threshold))
-			.expect("Threshold must be between 1 and 16");
+		builder.push_integer(BigInt::from(threshold))?;
 		for key in public_keys {
-			builder.push_data(key.to_vec()).unwrap();
+			builder.push_data(key.to_vec())?;
 		}
 		builder
-			.push_integer(BigInt::from(public_keys.len()))
-			.unwrap()
-			.op_code(vec![OpCode::Syscall].as_slice())
-			.push_data(InteropService::SystemCryptoCheckMultiSig.hash().into_bytes())
-			.unwrap();
-		Self::from(builder.to_bytes())
+			.push_integer(BigInt::from(public_keys.len()))?
+			.sys_call(InteropService::SystemCryptoCheckMultiSig);
+		Ok(Self::from(builder.to_bytes()))
 	}
 
 	pub fn is_single_sig(&self) -> bool {
@@ -68,18 +73,19 @@ impl VerificationScript {
 
 		let mut reader = Decoder::new(&self.script);
 
-		let n = reader.by_ref().read_var_int().unwrap();
+		let Ok(n) = reader.read_var_int() else { return false };
 		if !(1..16).contains(&n) {
 			return false
 		}
 
 		let mut m = 0;
-		while reader.by_ref().read_u8() == OpCode::PushData1 as u8 {
-			let len = reader.by_ref().read_u8();
-			if len != 33 {
+		while reader.read_u8() == Ok(OpCode::PushData1 as u8) {
+			if reader.read_u8() != Ok(33) {
+				return false
+			}
+			if reader.read_slice(33).is_err() {
 				return false
 			}
-			let _ = reader.by_ref().skip(33);
 			m += 1;
 		}
 
@@ -89,36 +95,35 @@ impl VerificationScript {
 
 		// additional checks
 		let service_bytes = &self.script[self.script.len() - 4..];
-		if service_bytes != &InteropService::SystemCryptoCheckMultiSig.hash().into_bytes() {
+		if service_bytes != InteropService::SystemCryptoCheckMultiSig.hash() {
 			return false
 		}
 
-		if m != reader.by_ref().read_var_int().unwrap() {
+		if reader.read_var_int() != Ok(m) {
 			return false
 		}
 
-		if reader.by_ref().read_u8() != OpCode::Syscall as u8 {
+		if reader.read_u8() != Ok(OpCode::Syscall as u8) {
 			return false
 		}
 
 		true
 	}
 
-	// other methods
+	/// The script hash of the account verified by the script
 	pub fn hash(&self) -> H160 {
-		H160::from_slice(&self.script)
+		H160::from_script(&self.script)
 	}
 
 	pub fn get_signatures(&self) -> Vec<Secp256r1Signature> {
 		let mut reader = Decoder::new(&self.script);
 		let mut signatures = vec![];
 
-		while reader.by_ref().read_u8() == OpCode::PushData1 as u8 {
-			let len = reader.by_ref().read_u8();
-			let sig =
-				Secp256r1Signature::from_bytes(&reader.by_ref().read_bytes(len as usize).unwrap())
-					.unwrap();
-			signatures.push(sig);
+		while reader.read_u8() == Ok(OpCode::PushData1 as u8) {
+			let Ok(len) = reader.read_u8() else { break };
+			let Ok(bytes) = reader.read_slice(len as usize) else { break };
+			let Ok(signature) = Secp256r1Signature::from_bytes(bytes) else { break };
+			signatures.push(signature);
 		}
 
 		signatures
@@ -127,26 +132,21 @@ impl VerificationScript {
 	pub fn get_public_keys(&self) -> Result<Vec<Secp256r1PublicKey>, BuilderError> {
 		if self.is_single_sig() {
 			let mut reader = Decoder::new(&self.script);
-			reader.by_ref().read_u8(); // skip pushdata1
-			reader.by_ref().read_u8(); // skip length
-
-			let mut point = [0; 33];
-			point.copy_from_slice(&reader.by_ref().read_bytes(33).unwrap());
+			reader.read_u8()?; // skip pushdata1
+			reader.read_u8()?; // skip length
 
-			let key = Secp256r1PublicKey::from_bytes(&point).unwrap();
+			let key = Secp256r1PublicKey::from_bytes(reader.read_slice(33)?)?;
 			return Ok(vec![key])
 		}
 
 		if self.is_multi_sig() {
 			let mut reader = Decoder::new(&self.script);
-			reader.by_ref().read_var_int().unwrap(); // skip threshold
+			reader.read_var_int()?; // skip threshold
 
 			let mut keys = vec![];
-			while reader.by_ref().read_u8() == OpCode::PushData1 as u8 {
-				reader.by_ref().read_u8(); // skip length
-				let mut point = [0; 33];
-				point.copy_from_slice(&reader.by_ref().read_bytes(33).unwrap());
-				keys.push(Secp256r1PublicKey::from_bytes(&point).unwrap());
+			while reader.read_u8() == Ok(OpCode::PushData1 as u8) {
+				reader.read_u8()?; // skip length
+				keys.push(Secp256r1PublicKey::from_bytes(reader.read_slice(33)?)?);
 			}
 
 			return Ok(keys)
@@ -160,7 +160,7 @@ impl VerificationScript {
 			Ok(1)
 		} else if self.is_multi_sig() {
 			let reader = &mut Decoder::new(&self.script);
-			Ok(reader.by_ref().read_var_int()? as usize)
+			Ok(reader.read_var_int()? as usize)
 		} else {
 			Err(BuilderError::InvalidScript("Invalid verification script".to_string()))
 		}
@@ -177,7 +177,7 @@ impl NeoSerializable for VerificationScript {
 	type Error = BuilderError;
 
 	fn size(&self) -> usize {
-		self.script.len()
+		var_size(self.script.len()) + self.script.len()
 	}
 
 	fn encode(&self, writer: &mut Encoder) {
@@ -38,9 +38,8 @@ impl Witness {
 
 	pub fn create(message_to_sign: Bytes, key_pair: &KeyPair) -> Result<Self, BuilderError> {
 		let invocation_script =
-			InvocationScript::from_message_and_key_pair(message_to_sign, key_pair).unwrap();
-		let verification_script =
-			VerificationScript::from(key_pair.public_key().to_raw_bytes().to_vec());
+			InvocationScript::from_message_and_key_pair(message_to_sign, key_pair)?;
+		let verification_script = VerificationScript::from_public_key(&key_pair.public_key())?;
 		Ok(Self { invocation: invocation_script, verification: verification_script })
 	}
 
@@ -50,7 +49,7 @@ impl Witness {
 		public_keys: Vec<Secp256r1PublicKey>,
 	) -> Result<Self, BuilderError> {
 		let verification_script =
-			VerificationScript::from_multi_sig(&public_keys, signing_threshold);
+			VerificationScript::from_multi_sig(&public_keys, signing_threshold)?;
 		Self::create_multi_sig_witness_script(signatures, verification_script)
 	}
 
@@ -58,7 +57,7 @@ impl Witness {
 		signatures: Vec<Secp256r1Signature>,
 		verification_script: VerificationScript,
 	) -> Result<Self, BuilderError> {
-		let threshold = verification_script.get_signing_threshold().unwrap();
+		let threshold = verification_script.get_signing_threshold()?;
 		if signatures.len() < threshold {
 			return Err(BuilderError::SignerConfiguration(
 				"Not enough signatures provided for the required signing threshold.".to_string(),
@@ -66,18 +65,22 @@ impl Witness {
 		}
 
 		let invocation_script =
-			InvocationScript::from_signatures(&signatures[..threshold as usize]);
+			InvocationScript::from_signatures(&signatures[..threshold as usize])?;
 		Ok(Self { invocation: invocation_script, verification: verification_script })
 	}
 
+	/// The witness of a deployed contract, verified by calling its `verify` method with `params`.
+	///
+	/// The invocation script pushes the arguments of `verify`, the last one first so the first
+	/// argument is on top of the stack, and the verification script is empty.
 	pub fn create_contract_witness(params: Vec<ContractParameter>) -> Result<Self, BuilderError> {
 		if params.is_empty() {
 			return Ok(Self::new())
 		}
 
 		let mut builder = ScriptBuilder::new();
-		for param in params {
-			builder.push_param(&param).expect("Failed to push param");
+		for param in params.iter().rev() {
+			builder.push_param(param)?;
 		}
 		let invocation_script = builder.to_bytes();
 
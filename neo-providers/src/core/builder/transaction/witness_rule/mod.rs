@@ -1,3 +1,4 @@
 pub mod witness_action;
 pub mod witness_condition;
 pub mod witness_rule;
+pub mod witness_rule_builder;
@@ -4,7 +4,10 @@ use crate::core::transaction::{
 	signers::signer::SignerType::Transaction, transaction_error::TransactionError,
 	witness_scope::WitnessScope::WitnessRules,
 };
-use neo_codec::{encode::NeoSerializable, Decoder, Encoder};
+use neo_codec::{
+	encode::{NeoSerializable, VarSizeTrait},
+	Decoder, Encoder,
+};
 use neo_crypto::keys::Secp256r1PublicKey;
 use primitive_types::H160;
 use serde::{Deserialize, Deserializer, Serialize};
@@ -172,11 +175,8 @@ impl NeoSerializable for WitnessCondition {
 	fn size(&self) -> usize {
 		match self {
 			WitnessCondition::Boolean(_) => 2,
-			WitnessCondition::Not(_) => 1 + self.expression().unwrap().size(),
-			WitnessCondition::And(_) | WitnessCondition::Or(_) => {
-				let exp = self.expression_list().unwrap();
-				1 + 1 + exp.len() + exp.iter().map(|e| e.size()).sum::<usize>()
-			},
+			WitnessCondition::Not(exp) => 1 + exp.size(),
+			WitnessCondition::And(exp) | WitnessCondition::Or(exp) => 1 + exp.var_size(),
 			WitnessCondition::ScriptHash(_) | WitnessCondition::CalledByContract(_) => 1 + 20,
 			WitnessCondition::Group(_) | WitnessCondition::CalledByGroup(_) => 1 + 33,
 			WitnessCondition::CalledByEntry => 1,
@@ -191,16 +191,15 @@ impl NeoSerializable for WitnessCondition {
 			},
 			WitnessCondition::Not(exp) => {
 				writer.write_u8(WitnessCondition::NOT_BYTE);
-				writer.write_serializable_fixed(exp.expression().unwrap());
-				// exp.writeSerializableFixed(writer);
+				writer.write_serializable_fixed(exp.as_ref());
 			},
 			WitnessCondition::And(exp) => {
 				writer.write_u8(WitnessCondition::AND_BYTE);
-				writer.write_serializable_variable_list(exp);
+				writer.write_serializable_list(exp);
 			},
 			WitnessCondition::Or(exp) => {
 				writer.write_u8(WitnessCondition::OR_BYTE);
-				writer.write_serializable_variable_list(exp)
+				writer.write_serializable_list(exp)
 			},
 			WitnessCondition::ScriptHash(hash) => {
 				writer.write_u8(WitnessCondition::SCRIPT_HASH_BYTE);
@@ -225,10 +224,10 @@ impl NeoSerializable for WitnessCondition {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let byte = reader.read_u8();
+		let byte = reader.read_u8()?;
 		match byte {
 			WitnessCondition::BOOLEAN_BYTE => {
-				let b = reader.read_bool();
+				let b = reader.read_bool()?;
 				Ok(WitnessCondition::Boolean(b))
 			},
 			WitnessCondition::NOT_BYTE => {
@@ -237,14 +236,16 @@ impl NeoSerializable for WitnessCondition {
 			},
 			WitnessCondition::OR_BYTE | WitnessCondition::AND_BYTE => {
 				let len = reader.read_var_int()?;
-				if len > WitnessCondition::MAX_SUBITEMS as i64 {
+				if len > WitnessCondition::MAX_SUBITEMS as u64 {
 					return Err(TransactionError::InvalidWitnessCondition)
 				}
-				let exp = WitnessCondition::decode(reader)?;
+				let exp = (0..len)
+					.map(|_| WitnessCondition::decode(reader))
+					.collect::<Result<Vec<_>, _>>()?;
 				if byte == WitnessCondition::OR_BYTE {
-					Ok(WitnessCondition::Or(vec![exp]))
+					Ok(WitnessCondition::Or(exp))
 				} else {
-					Ok(WitnessCondition::And(vec![exp]))
+					Ok(WitnessCondition::And(exp))
 				}
 			},
 			WitnessCondition::SCRIPT_HASH_BYTE | WitnessCondition::CALLED_BY_CONTRACT_BYTE => {
@@ -274,3 +275,17 @@ impl NeoSerializable for WitnessCondition {
 		writer.to_bytes()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_not_round_trip() {
+		let condition = WitnessCondition::Not(Box::new(WitnessCondition::Boolean(true)));
+		let bytes = condition.to_array();
+		assert_eq!(bytes, vec![WitnessCondition::NOT_BYTE, WitnessCondition::BOOLEAN_BYTE, 1]);
+		assert_eq!(condition.size(), bytes.len());
+		assert_eq!(WitnessCondition::decode(&mut Decoder::new(&bytes)).unwrap(), condition);
+	}
+}
@@ -0,0 +1,344 @@
+//! A fluent builder for [`WitnessRule`]s.
+//!
+//! Conditions are built with the functions of this module and combined with
+//! [`Condition::and`], [`Condition::or`], [`all`], [`any`] and [`not`]. The nesting depth of a
+//! condition is part of its type, so conditions nested deeper than
+//! [`NeoConstants::MAX_NESTING_DEPTH`] do not compile, and neither do `all` or `any` with more
+//! than [`NeoConstants::MAX_SUBITEMS`] conditions.
+//!
+//! # Example
+//!
+//! ```
+//! use neo_providers::core::transaction::witness_rule::witness_rule_builder::{
+//! 	all, called_by_contract, called_by_entry, not, script_hash, WitnessRuleBuilder,
+//! };
+//! use primitive_types::H160;
+//!
+//! let token = H160::repeat_byte(0x01);
+//! let dex = H160::repeat_byte(0x02);
+//! // allow the witness in the token contract when called directly or by the dex
+//! let rule = WitnessRuleBuilder::allow()
+//! 	.when(script_hash(token).and(called_by_entry().or(called_by_contract(dex))));
+//! // deny it everywhere else
+//! let fallback = WitnessRuleBuilder::deny().when(not(all([script_hash(token)])));
+//! ```
+//!
+//! Nesting three `And`, `Or` or `Not` conditions is rejected by the node, and fails to compile:
+//!
+//! ```compile_fail
+//! use neo_providers::core::transaction::witness_rule::witness_rule_builder::{
+//! 	called_by_entry, not, WitnessRuleBuilder,
+//! };
+//!
+//! let rule = WitnessRuleBuilder::allow().when(not(not(not(called_by_entry()))));
+//! ```
+
+use crate::core::transaction::witness_rule::{
+	witness_action::WitnessAction, witness_condition::WitnessCondition, witness_rule::WitnessRule,
+};
+use neo_config::NeoConstants;
+use neo_crypto::keys::Secp256r1PublicKey;
+use primitive_types::H160;
+use std::marker::PhantomData;
+
+// The depth types below cover exactly the nesting depth allowed by the protocol.
+const _: () = assert!(NeoConstants::MAX_NESTING_DEPTH == 2);
+
+mod sealed {
+	pub trait Sealed {}
+}
+
+/// The number of nested `And`, `Or` and `Not` conditions of a [`Condition`].
+pub trait Depth: sealed::Sealed {}
+
+/// A depth that can be nested in one more `And`, `Or` or `Not` condition.
+pub trait Nest: Depth {
+	/// The depth after nesting
+	type Next: Depth;
+}
+
+/// The larger of two depths.
+pub trait Max<D: Depth>: Depth {
+	/// The larger depth
+	type Output: Depth;
+}
+
+/// A depth not larger than `D`.
+pub trait AtMost<D: Depth>: Depth {}
+
+/// A condition without `And`, `Or` or `Not`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Depth0 {}
+
+/// A condition nesting one `And`, `Or` or `Not`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Depth1 {}
+
+/// A condition nesting the maximum number of `And`, `Or` and `Not` conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Depth2 {}
+
+macro_rules! impl_depths {
+	($($depth:ident),*) => {
+		$(
+			impl sealed::Sealed for $depth {}
+			impl Depth for $depth {}
+		)*
+	};
+}
+
+macro_rules! impl_max {
+	($(($a:ident, $b:ident) => $max:ident),* $(,)?) => {
+		$(
+			impl Max<$b> for $a {
+				type Output = $max;
+			}
+		)*
+	};
+}
+
+macro_rules! impl_at_most {
+	($(($a:ident, $b:ident)),* $(,)?) => {
+		$(impl AtMost<$b> for $a {})*
+	};
+}
+
+impl_depths!(Depth0, Depth1, Depth2);
+
+impl Nest for Depth0 {
+	type Next = Depth1;
+}
+
+impl Nest for Depth1 {
+	type Next = Depth2;
+}
+
+impl_max!(
+	(Depth0, Depth0) => Depth0,
+	(Depth0, Depth1) => Depth1,
+	(Depth0, Depth2) => Depth2,
+	(Depth1, Depth0) => Depth1,
+	(Depth1, Depth1) => Depth1,
+	(Depth1, Depth2) => Depth2,
+	(Depth2, Depth0) => Depth2,
+	(Depth2, Depth1) => Depth2,
+	(Depth2, Depth2) => Depth2,
+);
+
+impl_at_most!(
+	(Depth0, Depth0),
+	(Depth0, Depth1),
+	(Depth0, Depth2),
+	(Depth1, Depth1),
+	(Depth1, Depth2),
+	(Depth2, Depth2),
+);
+
+/// A [`WitnessCondition`] whose nesting depth `D` is checked at compile time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition<D: Depth> {
+	condition: WitnessCondition,
+	depth: PhantomData<D>,
+}
+
+impl<D: Depth> Condition<D> {
+	fn new(condition: WitnessCondition) -> Self {
+		Self { condition, depth: PhantomData }
+	}
+
+	/// The condition that both `self` and `other` are true. Use [`all`] to combine more
+	/// conditions without nesting them.
+	pub fn and<E: Depth>(self, other: Condition<E>) -> Condition<<D::Output as Nest>::Next>
+	where
+		D: Max<E>,
+		D::Output: Nest,
+	{
+		Condition::new(WitnessCondition::And(vec![self.condition, other.condition]))
+	}
+
+	/// The condition that `self` or `other` is true. Use [`any`] to combine more conditions
+	/// without nesting them.
+	pub fn or<E: Depth>(self, other: Condition<E>) -> Condition<<D::Output as Nest>::Next>
+	where
+		D: Max<E>,
+		D::Output: Nest,
+	{
+		Condition::new(WitnessCondition::Or(vec![self.condition, other.condition]))
+	}
+
+	/// The same condition typed with the larger depth `E`, so it can be combined in [`all`] or
+	/// [`any`] with conditions of depth `E`.
+	pub fn lift<E: Depth>(self) -> Condition<E>
+	where
+		D: AtMost<E>,
+	{
+		Condition::new(self.condition)
+	}
+
+	/// The built condition
+	pub fn into_inner(self) -> WitnessCondition {
+		self.condition
+	}
+}
+
+impl<D: Depth> From<Condition<D>> for WitnessCondition {
+	fn from(condition: Condition<D>) -> Self {
+		condition.condition
+	}
+}
+
+/// The condition that is always `value`.
+pub fn boolean(value: bool) -> Condition<Depth0> {
+	Condition::new(WitnessCondition::Boolean(value))
+}
+
+/// The condition that the witness is checked in the contract `hash`.
+pub fn script_hash(hash: H160) -> Condition<Depth0> {
+	Condition::new(WitnessCondition::ScriptHash(hash))
+}
+
+/// The condition that the witness is checked in a contract of the group `group`.
+pub fn group(group: Secp256r1PublicKey) -> Condition<Depth0> {
+	Condition::new(WitnessCondition::Group(group))
+}
+
+/// The condition that the contract checking the witness is called by the entry script.
+pub fn called_by_entry() -> Condition<Depth0> {
+	Condition::new(WitnessCondition::CalledByEntry)
+}
+
+/// The condition that the contract checking the witness is called by the contract `hash`.
+pub fn called_by_contract(hash: H160) -> Condition<Depth0> {
+	Condition::new(WitnessCondition::CalledByContract(hash))
+}
+
+/// The condition that the contract checking the witness is called by a contract of the group
+/// `group`.
+pub fn called_by_group(group: Secp256r1PublicKey) -> Condition<Depth0> {
+	Condition::new(WitnessCondition::CalledByGroup(group))
+}
+
+/// The condition that `condition` is false.
+pub fn not<D: Nest>(condition: Condition<D>) -> Condition<D::Next> {
+	Condition::new(WitnessCondition::Not(Box::new(condition.condition)))
+}
+
+/// The condition that all `conditions` are true. Conditions of smaller depths are combined with
+/// [`Condition::lift`].
+pub fn all<D: Nest, const N: usize>(conditions: [Condition<D>; N]) -> Condition<D::Next> {
+	#[allow(clippy::let_unit_value)]
+	let () = SubItems::<N>::CHECK;
+	Condition::new(WitnessCondition::And(conditions.into_iter().map(|c| c.condition).collect()))
+}
+
+/// The condition that at least one of `conditions` is true. Conditions of smaller depths are
+/// combined with [`Condition::lift`].
+pub fn any<D: Nest, const N: usize>(conditions: [Condition<D>; N]) -> Condition<D::Next> {
+	#[allow(clippy::let_unit_value)]
+	let () = SubItems::<N>::CHECK;
+	Condition::new(WitnessCondition::Or(conditions.into_iter().map(|c| c.condition).collect()))
+}
+
+/// Fails the build if `And` or `Or` conditions have no or too many sub conditions.
+struct SubItems<const N: usize>;
+
+impl<const N: usize> SubItems<N> {
+	const CHECK: () = assert!(
+		N > 0 && N <= NeoConstants::MAX_SUBITEMS as usize,
+		"`all` and `any` take between one and `MAX_SUBITEMS` conditions"
+	);
+}
+
+/// Builds a [`WitnessRule`] from its action and condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WitnessRuleBuilder {
+	action: WitnessAction,
+}
+
+impl WitnessRuleBuilder {
+	/// A rule allowing the witness if its condition is true
+	pub fn allow() -> Self {
+		Self { action: WitnessAction::Allow }
+	}
+
+	/// A rule denying the witness if its condition is true
+	pub fn deny() -> Self {
+		Self { action: WitnessAction::Deny }
+	}
+
+	/// Completes the rule with `condition`.
+	pub fn when<D: Depth>(self, condition: Condition<D>) -> WitnessRule {
+		WitnessRule::new(self.action, condition.condition)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::{
+		account::{Account, AccountTrait},
+		transaction::signers::{account_signer::AccountSigner, signer::SignerTrait},
+	};
+	use neo_crypto::key_pair::KeyPair;
+
+	#[test]
+	fn test_builds_condition_tree() {
+		let token = H160::repeat_byte(0x01);
+		let dex = H160::repeat_byte(0x02);
+		let rule = WitnessRuleBuilder::allow()
+			.when(script_hash(token).and(called_by_entry().or(called_by_contract(dex))));
+
+		assert_eq!(
+			rule,
+			WitnessRule::new(
+				WitnessAction::Allow,
+				WitnessCondition::And(vec![
+					WitnessCondition::ScriptHash(token),
+					WitnessCondition::Or(vec![
+						WitnessCondition::CalledByEntry,
+						WitnessCondition::CalledByContract(dex),
+					]),
+				]),
+			)
+		);
+	}
+
+	#[test]
+	fn test_all_and_any() {
+		let condition = any([
+			all([boolean(true), called_by_entry()]),
+			not(script_hash(H160::zero())),
+			boolean(false).lift(),
+		]);
+
+		assert_eq!(
+			condition.into_inner(),
+			WitnessCondition::Or(vec![
+				WitnessCondition::And(vec![
+					WitnessCondition::Boolean(true),
+					WitnessCondition::CalledByEntry,
+				]),
+				WitnessCondition::Not(Box::new(WitnessCondition::ScriptHash(H160::zero()))),
+				WitnessCondition::Boolean(false),
+			])
+		);
+	}
+
+	#[test]
+	fn test_signer_accepts_maximum_depth() {
+		let account = Account::from_key_pair(KeyPair::new_random(), None, None).unwrap();
+		let mut signer = AccountSigner::called_by_entry(&account).unwrap();
+		let rule = WitnessRuleBuilder::deny().when(not(called_by_entry().or(boolean(false))));
+
+		signer.set_rules(vec![rule.clone()]).unwrap();
+		assert_eq!(signer.get_rules(), &vec![rule]);
+
+		let too_deep = WitnessRule::new(
+			WitnessAction::Deny,
+			WitnessCondition::Not(Box::new(WitnessCondition::Not(Box::new(
+				WitnessCondition::Not(Box::new(WitnessCondition::CalledByEntry)),
+			)))),
+		);
+		assert!(signer.set_rules(vec![too_deep]).is_err());
+	}
+}
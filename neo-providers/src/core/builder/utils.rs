@@ -1,5 +1,6 @@
 use crate::core::{
 	account::AccountTrait,
+	error::BuilderError,
 	script::script_builder::ScriptBuilder,
 	transaction::{
 		signers::{signer::Signer, transaction_signer::TransactionSigner},
@@ -9,7 +10,7 @@ use crate::core::{
 };
 use neo_crypto::keys::Secp256r1PublicKey;
 use neo_types::{
-	script_hash::{ScriptHash, ScriptHashExtension},
+	script_hash::{ScriptHash, ScriptHashExt},
 	serde_value::ValueExtension,
 };
 use serde::{Deserialize, Serialize};
@@ -30,24 +31,9 @@ use serde_json::Value;
 pub fn public_keys_to_scripthash(
 	public_keys: &mut [Secp256r1PublicKey],
 	threshold: usize,
-) -> ScriptHash {
-	let mut script = ScriptBuilder::build_multi_sig_script(public_keys, threshold as u8).unwrap();
-	// Self::from_script(&script)
-	ScriptHash::from_slice(&script)
-}
-
-/// Converts a public key to a script hash.
-///
-/// # Arguments
-///
-/// * `public_key` - A `Secp256r1PublicKey` instance.
-///
-/// # Returns
-///
-/// A `ScriptHash` instance representing the script hash of the verification script.
-pub fn pubkey_to_scripthash(public_key: &Secp256r1PublicKey) -> ScriptHash {
-	let script = ScriptBuilder::build_verification_script(public_key);
-	ScriptHash::from_script(&script)
+) -> Result<ScriptHash, BuilderError> {
+	let script = ScriptBuilder::build_multi_sig_script(public_keys, threshold as u8)?;
+	Ok(ScriptHash::from_script(&script))
 }
 
 pub trait VecValueExtension {
@@ -62,7 +48,7 @@ impl ValueExtension for TransactionAttribute {
 
 impl ValueExtension for TransactionSendToken {
 	fn to_value(&self) -> Value {
-		Value::String(serde_json::to_string(self).unwrap())
+		Value::String(serde_json::to_string(self).expect("serializing to JSON cannot fail"))
 	}
 }
 
@@ -79,7 +65,7 @@ impl VecValueExtension for Vec<TransactionAttribute> {
 }
 impl ValueExtension for Signer {
 	fn to_value(&self) -> Value {
-		Value::String(serde_json::to_string(self).unwrap())
+		Value::String(serde_json::to_string(self).expect("serializing to JSON cannot fail"))
 	}
 }
 
@@ -91,7 +77,7 @@ impl VecValueExtension for Vec<Signer> {
 
 impl ValueExtension for TransactionSigner {
 	fn to_value(&self) -> Value {
-		Value::String(serde_json::to_string(self).unwrap())
+		Value::String(serde_json::to_string(self).expect("serializing to JSON cannot fail"))
 	}
 }
 
@@ -6,6 +6,8 @@ pub mod neo_address;
 pub mod neo_application_log;
 pub mod neo_balances;
 pub mod neo_find_states;
+pub mod neo_find_storage;
+pub mod neo_get_candidates;
 pub mod neo_get_claimable;
 pub mod neo_get_mem_pool;
 pub mod neo_transfers;
@@ -22,6 +24,7 @@ pub mod neo_get_unspents;
 pub mod neo_get_version;
 pub mod neo_get_wallet_balance;
 pub mod neo_list_plugins;
+pub mod neo_network_fee;
 pub mod neo_send_raw_transaction;
 pub mod neo_transaction_result;
 pub mod neo_validate_address;
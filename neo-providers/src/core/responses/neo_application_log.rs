@@ -1,10 +1,19 @@
-use neo_types::{invocation_result::Notification, stack_item::StackItem, vm_state::VMState, *};
-use primitive_types::H256;
+use neo_types::{
+	invocation_result::{Notification, VmFault},
+	notification_filter::NotificationFilter,
+	stack_item::StackItem,
+	vm_state::VMState,
+	*,
+};
+use primitive_types::{H160, H256};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(from = "ApplicationLogRepr")]
 pub struct ApplicationLog {
-	#[serde(rename = "txid")]
+	/// The hash of the transaction, or of the block for the executions of a block
+	#[serde(rename = "txid", alias = "blockhash")]
 	#[serde(serialize_with = "serialize_h256")]
 	#[serde(deserialize_with = "deserialize_h256")]
 	pub transaction_id: H256,
@@ -22,3 +31,223 @@ pub struct Execution {
 	pub stack: Vec<StackItem>,
 	pub notifications: Vec<Notification>,
 }
+
+/// The shapes an [`ApplicationLog`] is sent in: the response of `getapplicationlog`, or a
+/// single execution tagged with its container as sent by `transaction_executed` subscriptions.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ApplicationLogRepr {
+	Log {
+		#[serde(rename = "txid", alias = "blockhash")]
+		#[serde(deserialize_with = "deserialize_h256")]
+		transaction_id: H256,
+		executions: Vec<Execution>,
+	},
+	Executed {
+		#[serde(deserialize_with = "deserialize_h256")]
+		container: H256,
+		#[serde(flatten)]
+		execution: Execution,
+	},
+}
+
+impl From<ApplicationLogRepr> for ApplicationLog {
+	fn from(repr: ApplicationLogRepr) -> Self {
+		match repr {
+			ApplicationLogRepr::Log { transaction_id, executions } =>
+				Self { transaction_id, executions },
+			ApplicationLogRepr::Executed { container, execution } =>
+				Self { transaction_id: container, executions: vec![execution] },
+		}
+	}
+}
+
+impl ApplicationLog {
+	/// Iterates over the notifications of all executions that pass `filter`.
+	pub fn notifications_matching<'a>(
+		&'a self,
+		filter: &'a NotificationFilter,
+	) -> impl Iterator<Item = &'a Notification> + 'a {
+		self.executions
+			.iter()
+			.flat_map(|execution| execution.notifications_matching(filter))
+	}
+
+	/// Returns the log if all of its executions halted, or the fault of the first execution that
+	/// did not. Inclusion in a block alone does not mean that a transaction succeeded.
+	pub fn into_result(self) -> Result<Self, VmFault> {
+		match self.executions.iter().find(|execution| !execution.is_halt()) {
+			Some(execution) => Err(execution.fault()),
+			None => Ok(self),
+		}
+	}
+}
+
+impl Execution {
+	/// Returns `true` if the execution halted, i.e. succeeded
+	pub fn is_halt(&self) -> bool {
+		self.state == VMState::Halt
+	}
+
+	/// The exception, notifications and GAS consumption of the execution as a [`VmFault`]
+	pub fn fault(&self) -> VmFault {
+		VmFault {
+			exception: self.exception.clone(),
+			notifications: self.notifications.clone(),
+			gas_consumed: self.gas_consumed.clone(),
+		}
+	}
+
+	/// Iterates over the notifications of this execution that pass `filter`.
+	pub fn notifications_matching<'a>(
+		&'a self,
+		filter: &'a NotificationFilter,
+	) -> impl Iterator<Item = &'a Notification> + 'a {
+		self.notifications
+			.iter()
+			.filter(|notification| filter.matches_notification(notification))
+	}
+}
+
+/// Selects application logs by the state of their executions and the notifications they emit,
+/// see [`Middleware::subscribe_application_logs`](crate::Middleware::subscribe_application_logs).
+///
+/// The state is filtered by the node, the notifications are filtered locally since nodes only
+/// filter executed transactions by state. Both are checked again locally, since the node sends an
+/// event once to a connection, whichever of its subscriptions the event matched.
+///
+/// # Example
+///
+/// ```
+/// use neo_providers::core::responses::neo_application_log::ApplicationLogFilter;
+/// use neo_types::vm_state::VMState;
+/// use primitive_types::H160;
+///
+/// let gas = H160::repeat_byte(0xcf);
+/// let filter = ApplicationLogFilter::new().state(VMState::Halt).contract(gas).event("Transfer");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApplicationLogFilter {
+	state: Option<VMState>,
+	notifications: NotificationFilter,
+}
+
+impl ApplicationLogFilter {
+	/// A filter matching every application log.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Restricts the filter to executions ending in `state`.
+	#[must_use]
+	pub fn state(mut self, state: VMState) -> Self {
+		self.state = Some(state);
+		self
+	}
+
+	/// Restricts the filter to logs with a notification emitted by `contract`. Can be called
+	/// several times to accept several contracts.
+	#[must_use]
+	pub fn contract(mut self, contract: H160) -> Self {
+		self.notifications = self.notifications.contract(contract);
+		self
+	}
+
+	/// Restricts the filter to logs with a notification matching the event name `pattern`, see
+	/// [`NotificationFilter::event`].
+	#[must_use]
+	pub fn event(mut self, pattern: &str) -> Self {
+		self.notifications = self.notifications.event(pattern);
+		self
+	}
+
+	/// The notification filter applied to the executions
+	pub fn notification_filter(&self) -> &NotificationFilter {
+		&self.notifications
+	}
+
+	/// Returns `true` if one of the executions of `log` passes the filter.
+	pub fn matches(&self, log: &ApplicationLog) -> bool {
+		log.executions.iter().any(|execution| {
+			self.state.map_or(true, |state| execution.state == state)
+				&& (self.notifications.is_any()
+					|| execution.notifications_matching(&self.notifications).next().is_some())
+		})
+	}
+
+	/// The parameters of the `transaction_executed` subscription
+	pub fn subscription_params(&self) -> serde_json::Value {
+		match self.state {
+			Some(state) => json!(["transaction_executed", { "state": state.to_string() }]),
+			None => json!(["transaction_executed"]),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn log(state: VMState, contract: H160, event_name: &str) -> ApplicationLog {
+		ApplicationLog {
+			transaction_id: H256::zero(),
+			executions: vec![Execution {
+				trigger: "Application".to_string(),
+				state,
+				exception: None,
+				gas_consumed: "0".to_string(),
+				stack: vec![],
+				notifications: vec![Notification {
+					contract,
+					event_name: event_name.to_string(),
+					state: StackItem::Any,
+				}],
+			}],
+		}
+	}
+
+	#[test]
+	fn test_application_log_filter() {
+		let gas = H160::repeat_byte(0xcf);
+		let neo = H160::repeat_byte(0xef);
+		let transfer = log(VMState::Halt, gas, "Transfer");
+
+		assert!(ApplicationLogFilter::new().matches(&transfer));
+		assert!(ApplicationLogFilter::new().state(VMState::Halt).matches(&transfer));
+		assert!(!ApplicationLogFilter::new().state(VMState::Fault).matches(&transfer));
+
+		let filter = ApplicationLogFilter::new().contract(gas).event("Transfer");
+		assert!(filter.matches(&transfer));
+		assert!(!filter.matches(&log(VMState::Halt, neo, "Transfer")));
+		assert!(!filter.matches(&log(VMState::Halt, gas, "Vote")));
+	}
+
+	#[test]
+	fn test_into_result() {
+		let gas = H160::repeat_byte(0xcf);
+		let transfer = log(VMState::Halt, gas, "Transfer");
+		assert_eq!(transfer.clone().into_result(), Ok(transfer.clone()));
+
+		let mut faulted = transfer.clone();
+		faulted.executions.push(Execution {
+			state: VMState::Fault,
+			exception: Some("ASSERT is executed with false result.".to_string()),
+			..transfer.executions[0].clone()
+		});
+		let fault = faulted.into_result().unwrap_err();
+		assert_eq!(fault.exception.as_deref(), Some("ASSERT is executed with false result."));
+		assert_eq!(fault.notifications[0].event_name, "Transfer");
+	}
+
+	#[test]
+	fn test_application_log_filter_params() {
+		assert_eq!(
+			ApplicationLogFilter::new().subscription_params(),
+			json!(["transaction_executed"])
+		);
+		assert_eq!(
+			ApplicationLogFilter::new().state(VMState::Fault).subscription_params(),
+			json!(["transaction_executed", { "state": "FAULT" }])
+		);
+	}
+}
@@ -1,5 +1,12 @@
-use crate::core::responses::{neo_transaction_result::TransactionResult, neo_witness::NeoWitness};
-use neo_types::*;
+use crate::{
+	core::{
+		responses::{neo_transaction_result::TransactionResult, neo_witness::NeoWitness},
+		transaction::{transaction::Transaction, transaction_error::TransactionError},
+	},
+	light_client::{merkle_root, Header},
+};
+use neo_codec::{encode::NeoSerializable, Decoder};
+use neo_types::{address::Address, *};
 use primitive_types::H256;
 use serde::{Deserialize, Serialize};
 
@@ -16,7 +23,7 @@ pub struct NeoBlock {
 	#[serde(serialize_with = "serialize_h256")]
 	#[serde(deserialize_with = "deserialize_h256")]
 	pub merkle_root_hash: H256,
-	pub time: i32,
+	pub time: u64,
 	pub index: i32,
 	pub primary: Option<i32>,
 	pub next_consensus: String,
@@ -27,3 +34,132 @@ pub struct NeoBlock {
 	#[serde(deserialize_with = "deserialize_h256_option")]
 	pub next_block_hash: Option<H256>,
 }
+
+impl NeoBlock {
+	/// Decodes a serialized block, in Base64 as returned by `getblock` without `verbose` or in
+	/// hex: the header with the witness of the validators, and the transactions.
+	///
+	/// Fails if the merkle root of the header does not match the transactions. The witness is
+	/// not verified, see [`Header::verify_witness`]. The number of confirmations and the hash of
+	/// the next block are unknown.
+	pub fn from_raw(raw: &str) -> Result<Self, TransactionError> {
+		let bytes = encoding::raw_decode(raw)?;
+		let mut reader = Decoder::new(&bytes);
+		let header = Header::decode(&mut reader)
+			.map_err(|e| TransactionError::IllegalState(e.to_string()))?;
+		let count = reader.read_var_int()?;
+		let mut transactions = Vec::new();
+		for _ in 0..count {
+			let mut tx = Transaction::decode(&mut reader)?;
+			tx.set_computed_fields();
+			transactions.push(tx);
+		}
+		if reader.available() > 0 {
+			return Err(TransactionError::IllegalState(format!(
+				"{} bytes after the block",
+				reader.available()
+			)))
+		}
+
+		let hashes: Vec<H256> = transactions.iter().map(|tx| tx.hash).collect();
+		if merkle_root(&hashes).unwrap_or_default() != header.merkle_root {
+			return Err(TransactionError::IllegalState(format!(
+				"The merkle root of block {} does not match its transactions",
+				header.index
+			)))
+		}
+
+		Ok(Self {
+			hash: header.hash(),
+			size: bytes.len() as i32,
+			version: header.version as i32,
+			prev_block_hash: header.prev_hash,
+			merkle_root_hash: header.merkle_root,
+			time: header.timestamp,
+			index: header.index as i32,
+			primary: Some(header.primary_index as i32),
+			next_consensus: Address::from_script_hash(header.next_consensus).to_string(),
+			witnesses: Some(vec![NeoWitness::new(
+				encoding::base64_encode(&header.invocation),
+				encoding::base64_encode(&header.verification),
+			)]),
+			transactions: Some(transactions.iter().map(TransactionResult::from).collect()),
+			confirmations: 0,
+			next_block_hash: None,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::transaction::{signers::account_signer::AccountSigner, witness::Witness};
+	use neo_codec::Encoder;
+	use neo_types::gas::Gas;
+	use primitive_types::H160;
+
+	fn transaction(nonce: i32) -> Transaction {
+		let sender = H160::repeat_byte(nonce as u8);
+		let mut tx = Transaction {
+			nonce,
+			sys_fee: Gas::from_datoms(1_000_000),
+			valid_until_block: 100,
+			signers: vec![AccountSigner::called_by_entry_hash160(sender).unwrap().into()],
+			script: vec![0x11, 0x40],
+			..Default::default()
+		};
+		tx.add_witness(Witness::from_scripts(vec![0x0c; 66], vec![0x41; 40]));
+		tx
+	}
+
+	fn raw_block(transactions: &[Transaction], merkle_root: H256) -> Vec<u8> {
+		let mut next_consensus = H160::repeat_byte(2);
+		next_consensus.0[0] = 1;
+		let header = Header {
+			version: 0,
+			prev_hash: H256::repeat_byte(1),
+			merkle_root,
+			timestamp: 1_700_000_000_000,
+			nonce: 42,
+			index: 7,
+			primary_index: 3,
+			next_consensus,
+			invocation: vec![0x0c; 66],
+			verification: vec![0x41; 40],
+		};
+		let mut writer = Encoder::new();
+		writer.write_bytes(&header.to_bytes());
+		writer.write_var_int(transactions.len() as u64);
+		for tx in transactions {
+			writer.write_bytes(&tx.to_array());
+		}
+		writer.to_bytes()
+	}
+
+	#[test]
+	fn test_from_raw() {
+		let transactions = [transaction(1), transaction(2)];
+		let root = merkle_root(&[transactions[0].hash(), transactions[1].hash()]).unwrap();
+		let raw = raw_block(&transactions, root);
+
+		let block = NeoBlock::from_raw(&encoding::base64_encode(&raw)).unwrap();
+		assert_eq!(block.index, 7);
+		assert_eq!(block.primary, Some(3));
+		assert_eq!(block.size as usize, raw.len());
+		assert_eq!(block.merkle_root_hash, root);
+		let mut next_consensus = H160::repeat_byte(2);
+		next_consensus.0[0] = 1;
+		assert_eq!(block.next_consensus, Address::from_script_hash(next_consensus).to_string());
+		assert_eq!(block.witnesses.unwrap()[0].verification, encoding::base64_encode([0x41; 40]));
+		let decoded = block.transactions.unwrap();
+		assert_eq!(decoded.len(), 2);
+		assert_eq!(decoded[1].hash, transactions[1].hash());
+		assert_eq!(decoded[1].signers[0].account, H160::repeat_byte(2));
+		assert_eq!(decoded[1].script, "EUA=");
+
+		let empty = NeoBlock::from_raw(&hex::encode(raw_block(&[], H256::zero()))).unwrap();
+		assert!(empty.transactions.unwrap().is_empty());
+		// the merkle root does not commit to the transactions
+		assert!(NeoBlock::from_raw(&hex::encode(raw_block(&transactions, H256::zero()))).is_err());
+	}
+}
@@ -0,0 +1,22 @@
+use neo_types::{deserialize_base64, serialize_base64, Bytes};
+use serde::{Deserialize, Serialize};
+
+/// A page of the storage entries of a contract, see
+/// [`Middleware::find_storage`](crate::Middleware::find_storage).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FindStorage {
+	/// Whether more entries follow, starting at [`FindStorage::next`]
+	pub truncated: bool,
+	/// The position of the first entry of the next page
+	pub next: u32,
+	pub results: Vec<ContractStorageEntry>,
+}
+
+/// An entry of the storage of a contract.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ContractStorageEntry {
+	#[serde(serialize_with = "serialize_base64", deserialize_with = "deserialize_base64")]
+	pub key: Bytes,
+	#[serde(serialize_with = "serialize_base64", deserialize_with = "deserialize_base64")]
+	pub value: Bytes,
+}
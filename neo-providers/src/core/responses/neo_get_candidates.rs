@@ -0,0 +1,17 @@
+use neo_crypto::keys::Secp256r1PublicKey;
+use neo_types::{deserialize_public_key, deserialize_u64, serialize_public_key, serialize_u64};
+use serde::{Deserialize, Serialize};
+
+/// A candidate for the committee, as returned by `getcandidates`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Candidate {
+	/// The public key the candidate registered with
+	#[serde(rename = "publickey")]
+	#[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+	pub pubkey: Secp256r1PublicKey,
+	/// The NEO voted for the candidate
+	#[serde(serialize_with = "serialize_u64", deserialize_with = "deserialize_u64")]
+	pub votes: u64,
+	/// Whether the candidate is a member of the current committee
+	pub active: bool,
+}
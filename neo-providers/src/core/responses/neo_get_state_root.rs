@@ -1,4 +1,6 @@
-use crate::core::transaction::witness::Witness;
+use crate::core::responses::neo_witness::NeoWitness;
+use neo_codec::Encoder;
+use neo_crypto::hash::HashableForVec;
 use neo_types::*;
 use primitive_types::H256;
 use serde::{Deserialize, Serialize};
@@ -11,5 +13,24 @@ pub struct StateRoot {
 	#[serde(serialize_with = "serialize_h256")]
 	#[serde(deserialize_with = "deserialize_h256")]
 	pub root_hash: H256,
-	pub witnesses: Vec<Witness>,
+	pub witnesses: Vec<NeoWitness>,
+}
+
+impl StateRoot {
+	/// Serializes the state root without its witness, the data its hash is computed from.
+	pub fn unsigned_bytes(&self) -> Bytes {
+		let mut writer = Encoder::new();
+		writer.write_u8(self.version as u8);
+		writer.write_u32(self.index);
+		let mut root_hash = self.root_hash.to_fixed_bytes();
+		root_hash.reverse();
+		writer.write_bytes(&root_hash);
+		writer.to_bytes()
+	}
+
+	/// The data signed by the state validators: the network magic followed by the hash of the
+	/// unsigned state root.
+	pub fn sign_data(&self, network: u32) -> Bytes {
+		[network.to_le_bytes().as_slice(), &self.unsigned_bytes().hash256()].concat()
+	}
 }
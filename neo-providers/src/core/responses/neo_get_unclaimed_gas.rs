@@ -1,7 +1,8 @@
+use neo_types::gas::Gas;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct UnclaimedGas {
-	pub unclaimed: String,
+	pub unclaimed: Gas,
 	pub address: String,
 }
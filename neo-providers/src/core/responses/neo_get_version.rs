@@ -1,3 +1,4 @@
+use crate::{Capability, NodeClient, ProviderError};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash)]
@@ -10,6 +11,27 @@ pub struct NeoVersion {
 	#[serde(rename = "useragent")]
 	pub user_agent: String,
 	pub protocol: Option<NeoProtocol>,
+	/// The settings of the RPC server, reported by neo-cli 3.6 and neo-go
+	pub rpc: Option<NeoRpcSettings>,
+}
+
+impl NeoVersion {
+	/// The node implementation, parsed from the user agent
+	pub fn node_client(&self) -> Result<NodeClient, ProviderError> {
+		self.user_agent.parse()
+	}
+
+	/// Whether the node supports `capability`, judging by its implementation and version and,
+	/// for iterators, by whether its RPC server has sessions enabled. Unknown nodes support no
+	/// capability.
+	pub fn supports(&self, capability: Capability) -> bool {
+		let Ok(client) = self.node_client() else { return false };
+		match (capability, &self.rpc) {
+			(Capability::IteratorSessions, Some(rpc)) => rpc.session_enabled,
+			(Capability::SessionlessIterators, Some(rpc)) if rpc.session_enabled => false,
+			_ => client.supports(capability),
+		}
+	}
 }
 
 impl PartialEq for NeoVersion {
@@ -19,6 +41,7 @@ impl PartialEq for NeoVersion {
 			&& self.nonce == other.nonce
 			&& self.user_agent == other.user_agent
 			&& self.protocol == other.protocol
+			&& self.rpc == other.rpc
 	}
 }
 
@@ -42,3 +65,13 @@ pub struct NeoProtocol {
 	#[serde(rename = "initialgasdistribution")]
 	pub initial_gas_distribution: u64,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NeoRpcSettings {
+	/// The number of items of an iterator returned inline when sessions are disabled
+	#[serde(rename = "maxiteratorresultitems")]
+	pub max_iterator_result_items: u32,
+	/// Whether iterators of test invocations can be traversed with `traverseiterator`
+	#[serde(rename = "sessionenabled")]
+	pub session_enabled: bool,
+}
@@ -0,0 +1,8 @@
+use neo_types::gas::Gas;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NeoNetworkFee {
+	#[serde(rename = "networkfee")]
+	pub network_fee: Gas,
+}
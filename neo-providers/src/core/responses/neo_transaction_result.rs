@@ -1,12 +1,12 @@
 use crate::core::{
 	responses::neo_witness::NeoWitness,
 	transaction::{
-		signers::transaction_signer::TransactionSigner,
+		signers::transaction_signer::TransactionSigner, transaction::Transaction,
 		transaction_attribute::TransactionAttribute, witness_rule::witness_rule::WitnessRule,
 		witness_scope::WitnessScope,
 	},
 };
-use neo_types::invocation_result::NeoVMStateType;
+use neo_types::{address::Address, encoding, gas::Gas, invocation_result::NeoVMStateType};
 use primitive_types::{H160, H256};
 use serde::{Deserialize, Serialize};
 
@@ -18,9 +18,9 @@ pub struct TransactionResult {
 	pub nonce: i32,
 	pub sender: String,
 	#[serde(rename = "sysfee")]
-	pub sys_fee: String,
+	pub sys_fee: Gas,
 	#[serde(rename = "netfee")]
-	pub net_fee: String,
+	pub net_fee: Gas,
 	#[serde(rename = "validuntilblock")]
 	pub valid_until_block: i32,
 	pub signers: Vec<TransactionSigner>,
@@ -36,6 +36,31 @@ pub struct TransactionResult {
 	pub vm_state: Option<NeoVMStateType>,
 }
 
+impl From<&Transaction> for TransactionResult {
+	/// Describes `tx` the way the node does, without the fields of the block including it.
+	/// The hash, size and sender must have been computed, as for decoded transactions.
+	fn from(tx: &Transaction) -> Self {
+		Self {
+			hash: tx.hash,
+			size: tx.size,
+			version: tx.version as i32,
+			nonce: tx.nonce,
+			sender: Address::from_script_hash(tx.sender).to_string(),
+			sys_fee: tx.sys_fee,
+			net_fee: tx.net_fee,
+			valid_until_block: tx.valid_until_block,
+			signers: tx.signers.iter().map(TransactionSigner::from).collect(),
+			attributes: tx.attributes.clone(),
+			script: encoding::base64_encode(&tx.script),
+			witnesses: tx.witnesses.iter().map(NeoWitness::from).collect(),
+			block_hash: None,
+			confirmations: None,
+			block_time: None,
+			vm_state: None,
+		}
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct NeoTransactionSigner {
 	account: H160,
@@ -1,3 +1,5 @@
+use crate::core::transaction::witness::Witness;
+use neo_types::encoding;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
@@ -11,3 +13,12 @@ impl NeoWitness {
 		Self { invocation, verification }
 	}
 }
+
+impl From<&Witness> for NeoWitness {
+	fn from(witness: &Witness) -> Self {
+		Self::new(
+			encoding::base64_encode(witness.invocation.as_slice()),
+			encoding::base64_encode(witness.verification.script()),
+		)
+	}
+}
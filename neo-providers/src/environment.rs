@@ -0,0 +1,224 @@
+//! Configuration of the node to connect to, read from environment variables.
+//!
+//! Applications and tests usually take the RPC endpoint from the environment. [`NeoEnvironment`]
+//! reads it together with the network and the credentials of the node and reports missing or
+//! invalid variables as an [`EnvironmentError`] instead of panicking:
+//!
+//! ```no_run
+//! use neo_providers::{environment::NeoEnvironment, Provider};
+//!
+//! # fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! // NEO_ENDPOINT=https://testnet1.neo.coz.io:443 NEO_NETWORK=testnet
+//! let provider = Provider::from_env()?;
+//!
+//! // or inspect the environment first
+//! let env = NeoEnvironment::from_env()?;
+//! println!("connecting to {}", env.endpoint);
+//! let provider = env.provider()?;
+//! # Ok(()) }
+//! ```
+use crate::{Authorization, Http, HttpBuildError, Provider, ProviderExt};
+use neo_config::NeoNetwork;
+use std::fmt;
+use thiserror::Error;
+use url::Url;
+
+/// The URL of the RPC server of the node
+pub const ENDPOINT_VAR: &str = "NEO_ENDPOINT";
+/// The URL of the RPC server, read if [`ENDPOINT_VAR`] is not set
+pub const LEGACY_ENDPOINT_VAR: &str = "ENDPOINT";
+/// The network of the node: `mainnet`, `testnet`, `private` or its magic number
+pub const NETWORK_VAR: &str = "NEO_NETWORK";
+/// The user name for HTTP basic authentication, requires [`PASSWORD_VAR`]
+pub const USER_VAR: &str = "NEO_RPC_USER";
+/// The password for HTTP basic authentication, requires [`USER_VAR`]
+pub const PASSWORD_VAR: &str = "NEO_RPC_PASSWORD";
+/// A token for HTTP bearer authentication
+pub const TOKEN_VAR: &str = "NEO_RPC_TOKEN";
+
+/// The public RPC endpoint of the N3 MainNet run by COZ
+pub const MAINNET_ENDPOINT: &str = "https://mainnet1.neo.coz.io:443";
+
+/// An error in the environment variables of [`NeoEnvironment`]
+#[derive(Debug, Error)]
+pub enum EnvironmentError {
+	/// A required variable is not set
+	#[error("the environment variable {0} is not set")]
+	Missing(&'static str),
+	/// A variable is set to an invalid value
+	#[error("the environment variable {var} is invalid: {reason}")]
+	Invalid {
+		/// The name of the variable
+		var: &'static str,
+		/// Why the value is invalid
+		reason: String,
+	},
+	/// The HTTP client could not be created with the credentials
+	#[error(transparent)]
+	Http(#[from] HttpBuildError),
+}
+
+/// The node to connect to, see the [module docs](self).
+#[derive(Clone)]
+pub struct NeoEnvironment {
+	/// The URL of the RPC server
+	pub endpoint: Url,
+	/// The magic number of the network, if configured. Without it the provider asks the node.
+	pub network_magic: Option<u32>,
+	/// The credentials sent with every request
+	pub auth: Option<Authorization>,
+}
+
+impl NeoEnvironment {
+	/// Reads the environment variables of the process.
+	pub fn from_env() -> Result<Self, EnvironmentError> {
+		Self::from_lookup(|name| std::env::var(name).ok())
+	}
+
+	/// Reads the variables from `lookup`, which returns the value of the variable passed to it,
+	/// e.g. from a `.env` file or a map in tests.
+	pub fn from_lookup<F>(lookup: F) -> Result<Self, EnvironmentError>
+	where
+		F: Fn(&str) -> Option<String>,
+	{
+		let lookup = |name: &str| lookup(name).filter(|value| !value.trim().is_empty());
+
+		let (var, endpoint) = match lookup(ENDPOINT_VAR) {
+			Some(endpoint) => (ENDPOINT_VAR, endpoint),
+			None => (
+				LEGACY_ENDPOINT_VAR,
+				lookup(LEGACY_ENDPOINT_VAR).ok_or(EnvironmentError::Missing(ENDPOINT_VAR))?,
+			),
+		};
+		let endpoint = Url::parse(endpoint.trim())
+			.map_err(|e| EnvironmentError::Invalid { var, reason: e.to_string() })?;
+
+		let network_magic =
+			lookup(NETWORK_VAR).map(|network| parse_network(&network)).transpose()?;
+
+		let auth = match (lookup(USER_VAR), lookup(PASSWORD_VAR), lookup(TOKEN_VAR)) {
+			(None, None, None) => None,
+			(None, None, Some(token)) => Some(Authorization::bearer(token)),
+			(Some(user), Some(password), None) => Some(Authorization::basic(user, password)),
+			(_, _, Some(_)) =>
+				return Err(EnvironmentError::Invalid {
+					var: TOKEN_VAR,
+					reason: format!("cannot be combined with {USER_VAR} and {PASSWORD_VAR}"),
+				}),
+			(None, Some(_), None) => return Err(EnvironmentError::Missing(USER_VAR)),
+			(Some(_), None, None) => return Err(EnvironmentError::Missing(PASSWORD_VAR)),
+		};
+
+		Ok(Self { endpoint, network_magic, auth })
+	}
+
+	/// Creates an HTTP provider for the endpoint, sending the credentials with every request.
+	pub fn provider(&self) -> Result<Provider<Http>, EnvironmentError> {
+		let http = match &self.auth {
+			Some(auth) => Http::new_with_auth(self.endpoint.clone(), auth.clone())?,
+			None => Http::new(self.endpoint.clone()),
+		};
+		let mut provider = Provider::new(http);
+		if let Some(magic) = self.network_magic {
+			provider.set_network(magic);
+		}
+		Ok(provider)
+	}
+}
+
+/// The credentials are left out, so the environment can be logged.
+impl fmt::Debug for NeoEnvironment {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("NeoEnvironment")
+			.field("endpoint", &self.endpoint.as_str())
+			.field("network_magic", &self.network_magic)
+			.field("auth", &self.auth.as_ref().map(|_| "<redacted>"))
+			.finish()
+	}
+}
+
+/// Parses the name of a network or its magic number in decimal or `0x` prefixed hex.
+fn parse_network(network: &str) -> Result<u32, EnvironmentError> {
+	let network = network.trim();
+	let magic = match network.to_lowercase().as_str() {
+		"mainnet" => Some(NeoNetwork::MainNet.to_magic()),
+		"testnet" => Some(NeoNetwork::TestNet.to_magic()),
+		"private" | "privatenet" => Some(NeoNetwork::PrivateNet.to_magic()),
+		hex if hex.starts_with("0x") => u32::from_str_radix(&hex[2..], 16).ok(),
+		decimal => decimal.parse().ok(),
+	};
+	magic.ok_or_else(|| EnvironmentError::Invalid {
+		var: NETWORK_VAR,
+		reason: format!("unknown network {network}"),
+	})
+}
+
+impl Provider<Http> {
+	/// Creates a provider for the node configured in the environment, see
+	/// [`NeoEnvironment::from_env`].
+	pub fn from_env() -> Result<Self, EnvironmentError> {
+		NeoEnvironment::from_env()?.provider()
+	}
+
+	/// Creates a provider for the public MainNet endpoint [`MAINNET_ENDPOINT`]. The network is
+	/// known, so no request is sent to the node.
+	pub fn default_mainnet() -> Self {
+		let url = Url::parse(MAINNET_ENDPOINT).expect("valid MainNet url");
+		let mut provider = Provider::new(Http::new(url));
+		provider.set_network(NeoNetwork::MainNet.to_magic());
+		provider
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	fn env(vars: &[(&str, &str)]) -> Result<NeoEnvironment, EnvironmentError> {
+		let vars: HashMap<String, String> =
+			vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+		NeoEnvironment::from_lookup(|name| vars.get(name).cloned())
+	}
+
+	#[test]
+	fn test_endpoint() {
+		assert!(matches!(env(&[]), Err(EnvironmentError::Missing(ENDPOINT_VAR))));
+		let env1 = env(&[(LEGACY_ENDPOINT_VAR, "http://localhost:10332")]).unwrap();
+		assert_eq!(env1.endpoint.as_str(), "http://localhost:10332/");
+		assert_eq!(env1.network_magic, None);
+		assert!(env1.auth.is_none());
+		assert!(matches!(
+			env(&[(ENDPOINT_VAR, "localhost")]),
+			Err(EnvironmentError::Invalid { var: ENDPOINT_VAR, .. })
+		));
+	}
+
+	#[test]
+	fn test_network() {
+		let magic = |network| {
+			env(&[(ENDPOINT_VAR, "http://localhost:10332"), (NETWORK_VAR, network)])
+				.map(|env| env.network_magic)
+		};
+		assert_eq!(magic("MainNet").unwrap(), Some(0x334f454e));
+		assert_eq!(magic("testnet").unwrap(), Some(0x3554334e));
+		assert_eq!(magic("0x4e454e").unwrap(), Some(0x4e454e));
+		assert_eq!(magic("1234").unwrap(), Some(1234));
+		assert!(magic("moonnet").is_err());
+	}
+
+	#[test]
+	fn test_credentials() {
+		let endpoint = (ENDPOINT_VAR, "https://node.example");
+		let basic = env(&[endpoint, (USER_VAR, "neo"), (PASSWORD_VAR, "secret")]).unwrap();
+		assert!(matches!(basic.auth, Some(Authorization::Basic(_))));
+		assert!(!format!("{basic:?}").contains("secret"));
+		let bearer = env(&[endpoint, (TOKEN_VAR, "token")]).unwrap();
+		assert!(matches!(bearer.auth, Some(Authorization::Bearer(_))));
+		assert!(matches!(
+			env(&[endpoint, (USER_VAR, "neo")]),
+			Err(EnvironmentError::Missing(PASSWORD_VAR))
+		));
+		assert!(env(&[endpoint, (USER_VAR, "neo"), (TOKEN_VAR, "token")]).is_err());
+	}
+}
@@ -1,10 +1,11 @@
 use neo_crypto::error::CryptoError;
-use neo_types::error::TypeError;
+use neo_types::{error::TypeError, invocation_result::VmFault};
+use primitive_types::H256;
 use serde::{Deserialize, Serialize};
 use std::{error::Error, fmt::Debug};
 use thiserror::Error;
 
-use crate::JsonRpcError;
+use crate::{state::StateProofError, JsonRpcError};
 
 /// An `RpcError` is an abstraction over error types returned by a
 /// [`crate::JsonRpcClient`].
@@ -151,12 +152,25 @@ pub enum ProviderError {
 	/// Error in underlying lib `serde_json`
 	#[error(transparent)]
 	SerdeJson(#[from] serde_json::Error),
+	/// The result of a request did not match the response type
+	#[error("invalid result of {method} at `{path}`: {source}")]
+	Decode {
+		/// The JSON-RPC method of the request
+		method: String,
+		/// The JSON path of the offending field, `.` for the whole result
+		path: String,
+		/// The underlying error
+		source: serde_json::Error,
+	},
 	/// Error in underlying lib `hex`
 	#[error(transparent)]
 	HexError(#[from] hex::FromHexError),
 	/// Error in underlying lib `reqwest`
 	#[error(transparent)]
 	HTTPError(#[from] reqwest::Error),
+	/// The URL of the node is invalid
+	#[error(transparent)]
+	UrlError(#[from] url::ParseError),
 	/// Custom error from unknown source
 	#[error("custom error: {0}")]
 	CustomError(String),
@@ -177,6 +191,30 @@ pub enum ProviderError {
 	CryptoError(#[from] CryptoError),
 	#[error(transparent)]
 	TypeError(#[from] TypeError),
+	/// A test invocation faulted
+	#[error(transparent)]
+	VmFault(#[from] VmFault),
+	/// A transaction was included in a block but one of its executions faulted, see
+	/// [`PendingTransaction::await_success`](crate::PendingTransaction::await_success)
+	#[error("transaction {tx_hash:?} faulted: {fault}")]
+	TransactionFaulted {
+		/// The hash of the transaction
+		tx_hash: H256,
+		/// The exception and notifications of the faulted execution
+		fault: VmFault,
+	},
+	/// A transaction was dropped from the mempool without being included in a block
+	#[error("transaction {0:?} was dropped")]
+	TransactionDropped(H256),
+	/// A state proof of the node failed verification
+	#[error(transparent)]
+	StateProof(#[from] StateProofError),
+	/// The shutdown of the provider was triggered, see [`Shutdown`](crate::Shutdown)
+	#[error("provider is shutting down")]
+	Shutdown,
+	/// The request did not complete within the timeout and was cancelled
+	#[error("request timed out after {0:?}")]
+	Timeout(std::time::Duration),
 }
 
 impl RpcError for ProviderError {
@@ -192,6 +230,7 @@ impl RpcError for ProviderError {
 		match self {
 			ProviderError::JsonRpcClientError(e) => e.as_serde_error(),
 			ProviderError::SerdeJson(e) => Some(e),
+			ProviderError::Decode { source, .. } => Some(source),
 			_ => None,
 		}
 	}
@@ -0,0 +1,54 @@
+//! Deterministic control over the chain of a local test node.
+//!
+//! Time-dependent contract logic (vesting schedules, auctions, expirations) is hard to test against
+//! a node that produces blocks on its own schedule. [`ChainController`] lets tests produce blocks
+//! on demand, pin the timestamp of the next block and snapshot/restore the chain state.
+//!
+//! It is implemented by [`NeoExpressInstance`](crate::neo_express::NeoExpressInstance) for
+//! neo-express networks spawned by the test harness, and by [`MockNeoNode`](crate::MockNeoNode)
+//! for unit tests that do not need a real node. Both produce the same blocks for the same calls.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use neo_providers::{neo_express::NeoExpress, ChainController};
+//!
+//! # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! let node = NeoExpress::new().spawn().await?;
+//!
+//! let checkpoint = node.create_checkpoint("before-auction").await?;
+//! // let the auction run for one day
+//! node.set_next_block_time(1_700_000_000_000 + 86_400_000).await?;
+//! node.advance_blocks(1).await?;
+//! node.reset(Some(&checkpoint)).await?;
+//! # Ok(()) }
+//! ```
+
+use async_trait::async_trait;
+use std::fmt::Debug;
+
+/// Manipulates block production and chain state of a development node.
+///
+/// Timestamps are Unix timestamps in milliseconds, like the `time` field of a block.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait ChainController: Sync + Send + Debug {
+	/// Error type returned by the controller
+	type Error: std::error::Error + Send + Sync;
+
+	/// Produces `count` new blocks. The first one has the timestamp set with
+	/// [`set_next_block_time`](Self::set_next_block_time), if any.
+	async fn advance_blocks(&self, count: u32) -> Result<(), Self::Error>;
+
+	/// Sets the timestamp of the next block produced by
+	/// [`advance_blocks`](Self::advance_blocks). It must be later than the timestamp of the
+	/// current block. No block is produced.
+	async fn set_next_block_time(&self, timestamp: u64) -> Result<(), Self::Error>;
+
+	/// Saves the current chain state under `name` and returns the identifier to pass to
+	/// [`reset`](Self::reset).
+	async fn create_checkpoint(&self, name: &str) -> Result<String, Self::Error>;
+
+	/// Restores the chain state saved in `checkpoint`, or the genesis state if `None`.
+	async fn reset(&self, checkpoint: Option<&str>) -> Result<(), Self::Error>;
+}
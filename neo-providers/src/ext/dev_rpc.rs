@@ -59,7 +59,8 @@ pub enum DevRpcMiddlewareError<M: Middleware> {
 	NoSnapshot,
 }
 
-#[async_trait]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl<M: Middleware> Middleware for DevRpcMiddleware<M> {
 	type Error = DevRpcMiddlewareError<M>;
 	type Provider = M::Provider;
@@ -2,6 +2,9 @@
 pub mod admin;
 pub use admin::{NodeInfo, PeerInfo};
 
+pub mod chain_controller;
+pub use chain_controller::ChainController;
+
 pub mod nns;
 pub use nns::*;
 
@@ -0,0 +1,472 @@
+//! Ingestion of blocks and application logs into a database or any other [`Sink`].
+//!
+//! The [`Indexer`] downloads blocks together with the application logs of the block and of its
+//! transactions, with several blocks in flight at once, and hands them to the sink in height
+//! order and in batches. The sink stores the index of the last block it wrote as checkpoint, so
+//! an indexer created with the same sink after a restart continues where the previous one
+//! stopped. Blocks are final once they are persisted by the node, so written blocks are never
+//! revisited.
+//!
+//! [`MemorySink`] keeps the blocks in memory and [`JsonLinesSink`] appends them to a file.
+//! Databases like Postgres or SQLite are supported by implementing [`Sink`], writing each batch
+//! and its checkpoint in one database transaction.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use neo_providers::{
+//! 	indexer::{Indexer, JsonLinesSink},
+//! 	Http, Provider,
+//! };
+//! use std::convert::TryFrom;
+//!
+//! # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+//! let sink = JsonLinesSink::open("blocks.jsonl")?;
+//! // indexes the chain and follows new blocks until the provider is shut down
+//! Indexer::new(&provider, sink).concurrency(16).run().await?;
+//! # Ok(()) }
+//! ```
+
+use crate::{
+	block_range,
+	core::responses::{neo_application_log::ApplicationLog, neo_block::NeoBlock},
+	Middleware, ProviderError,
+};
+use async_trait::async_trait;
+use futures_timer::Delay;
+use futures_util::{
+	future,
+	stream::{self, StreamExt, TryStreamExt},
+};
+use instant::Duration;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, mem};
+use thiserror::Error;
+use tracing::debug;
+
+/// A block with the application logs of its executions.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexedBlock {
+	/// The block, including its transactions
+	pub block: NeoBlock,
+	/// The application log of the block followed by the logs of its transactions, in the order
+	/// of the transactions
+	pub application_logs: Vec<ApplicationLog>,
+}
+
+impl IndexedBlock {
+	/// The index of the block
+	pub fn index(&self) -> u32 {
+		self.block.index as u32
+	}
+}
+
+/// Storage for indexed blocks.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait Sink {
+	/// The error of the storage
+	type Error: std::error::Error + Send + Sync + 'static;
+
+	/// The index of the last written block, or `None` if no block was written yet.
+	async fn checkpoint(&self) -> Result<Option<u32>, Self::Error>;
+
+	/// Writes `blocks`, which follow the checkpoint without gaps and are in height order, and
+	/// moves the checkpoint to the last of them. Either all blocks and the checkpoint are
+	/// written or none of them.
+	async fn write(&mut self, blocks: Vec<IndexedBlock>) -> Result<(), Self::Error>;
+}
+
+/// Errors that can occur while indexing.
+#[derive(Error, Debug)]
+pub enum IndexerError<E: std::error::Error + 'static> {
+	/// A block or application log could not be downloaded
+	#[error(transparent)]
+	Provider(#[from] ProviderError),
+	/// The sink failed to read its checkpoint or to write blocks
+	#[error("sink error: {0}")]
+	Sink(#[source] E),
+}
+
+/// Downloads blocks and their application logs into a [`Sink`], see the [module
+/// documentation](self).
+#[derive(Debug)]
+pub struct Indexer<'a, M, S> {
+	client: &'a M,
+	sink: S,
+	start: u32,
+	concurrency: usize,
+	batch_size: usize,
+	poll_interval: Duration,
+}
+
+impl<'a, M: Middleware, S: Sink> Indexer<'a, M, S> {
+	/// Creates an indexer writing to `sink`, starting at the genesis block unless the sink has a
+	/// checkpoint.
+	pub fn new(client: &'a M, sink: S) -> Self {
+		Self {
+			client,
+			sink,
+			start: 0,
+			concurrency: 8,
+			batch_size: 100,
+			poll_interval: Duration::from_secs(5),
+		}
+	}
+
+	/// Starts at the block `index` if the sink has no checkpoint.
+	#[must_use]
+	pub fn start_at(mut self, index: u32) -> Self {
+		self.start = index;
+		self
+	}
+
+	/// Sets the number of blocks downloaded at once, 8 by default.
+	#[must_use]
+	pub fn concurrency(mut self, concurrency: usize) -> Self {
+		self.concurrency = concurrency.max(1);
+		self
+	}
+
+	/// Sets the number of blocks written to the sink at once, 100 by default.
+	#[must_use]
+	pub fn batch_size(mut self, batch_size: usize) -> Self {
+		self.batch_size = batch_size.max(1);
+		self
+	}
+
+	/// Sets how often [`Indexer::run`] checks for new blocks once it caught up with the chain,
+	/// every 5 seconds by default.
+	#[must_use]
+	pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+		self.poll_interval = poll_interval;
+		self
+	}
+
+	/// The sink
+	pub fn sink(&self) -> &S {
+		&self.sink
+	}
+
+	/// Consumes the indexer, returning the sink
+	pub fn into_sink(self) -> S {
+		self.sink
+	}
+
+	/// The index of the next block to download, following the checkpoint of the sink.
+	pub async fn next_index(&self) -> Result<u32, IndexerError<S::Error>> {
+		let checkpoint = self.sink.checkpoint().await.map_err(IndexerError::Sink)?;
+		Ok(checkpoint.map_or(self.start, |index| index + 1))
+	}
+
+	/// Indexes the blocks from the checkpoint up to, but excluding, the block `end` and returns
+	/// the index of the next block to download.
+	///
+	/// Blocks downloaded before an error or the shutdown of the provider are written before
+	/// returning, so no work is lost.
+	pub async fn sync_to(&mut self, end: u32) -> Result<u32, IndexerError<S::Error>> {
+		let mut next = self.next_index().await?;
+		let client = self.client;
+		let provider = client.provider();
+		let concurrency = self.concurrency;
+		let fetch = move |index| async move {
+			let block = provider.get_block_by_index(index, true).await?;
+			let hashes: Vec<_> = std::iter::once(block.hash)
+				.chain(block.transactions.iter().flatten().map(|tx| tx.hash))
+				.collect();
+			let application_logs = stream::iter(hashes)
+				.map(|hash| provider.get_application_log(hash))
+				.buffered(concurrency)
+				.try_collect()
+				.await?;
+			Ok::<_, ProviderError>(IndexedBlock { block, application_logs })
+		};
+		let mut blocks = Box::pin(
+			block_range::fetch_ordered(next..end, concurrency, fetch)
+				.take_until(provider.shutdown().signal()),
+		);
+
+		let mut batch = Vec::with_capacity(self.batch_size);
+		while let Some(block) = blocks.next().await {
+			let block = match block {
+				Ok(block) => block,
+				Err(err) => {
+					self.write(&mut batch, &mut next).await?;
+					return Err(err.into())
+				},
+			};
+			batch.push(block);
+			if batch.len() == self.batch_size {
+				self.write(&mut batch, &mut next).await?;
+			}
+		}
+		self.write(&mut batch, &mut next).await?;
+		Ok(next)
+	}
+
+	/// Indexes the chain and follows it, polling for new blocks, until the provider is shut
+	/// down.
+	pub async fn run(&mut self) -> Result<(), IndexerError<S::Error>> {
+		let client = self.client;
+		let provider = client.provider();
+		while !provider.shutdown().is_triggered() {
+			let count = provider.get_block_count().await?;
+			if self.next_index().await? < count {
+				self.sync_to(count).await?;
+			} else {
+				future::select(Delay::new(self.poll_interval), provider.shutdown().signal()).await;
+			}
+		}
+		Ok(())
+	}
+
+	async fn write(
+		&mut self,
+		batch: &mut Vec<IndexedBlock>,
+		next: &mut u32,
+	) -> Result<(), IndexerError<S::Error>> {
+		if batch.is_empty() {
+			return Ok(())
+		}
+		let from = *next;
+		*next += batch.len() as u32;
+		self.sink.write(mem::take(batch)).await.map_err(IndexerError::Sink)?;
+		debug!(from, to = *next - 1, "indexed blocks");
+		Ok(())
+	}
+}
+
+/// A sink keeping the blocks in memory, e.g. for tests or short-lived analyses.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+	blocks: Vec<IndexedBlock>,
+}
+
+impl MemorySink {
+	/// Creates an empty sink
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The written blocks, in height order
+	pub fn blocks(&self) -> &[IndexedBlock] {
+		&self.blocks
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Sink for MemorySink {
+	type Error = Infallible;
+
+	async fn checkpoint(&self) -> Result<Option<u32>, Self::Error> {
+		Ok(self.blocks.last().map(IndexedBlock::index))
+	}
+
+	async fn write(&mut self, blocks: Vec<IndexedBlock>) -> Result<(), Self::Error> {
+		self.blocks.extend(blocks);
+		Ok(())
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use json_lines::JsonLinesSink;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod json_lines {
+	use super::{IndexedBlock, Sink};
+	use async_trait::async_trait;
+	use serde_json::Value;
+	use std::{
+		fs::{File, OpenOptions},
+		io::{self, BufRead, BufReader, Write},
+		path::Path,
+	};
+
+	/// A sink appending the blocks to a file, one JSON object per line.
+	///
+	/// Every batch is synced to disk before the checkpoint moves. A line cut off by a crash while
+	/// writing is removed when the file is opened again.
+	#[derive(Debug)]
+	pub struct JsonLinesSink {
+		file: File,
+		checkpoint: Option<u32>,
+	}
+
+	impl JsonLinesSink {
+		/// Opens the file at `path`, creating it if it does not exist, and reads the checkpoint
+		/// from its last line.
+		pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+			let file = OpenOptions::new().read(true).append(true).create(true).open(path)?;
+			let mut reader = BufReader::new(&file);
+			let mut line = String::new();
+			let mut valid_len = 0;
+			let mut checkpoint = None;
+			while reader.read_line(&mut line)? > 0 {
+				// a line without newline was not completely written
+				let index = line
+					.strip_suffix('\n')
+					.and_then(|line| serde_json::from_str::<Value>(line).ok())
+					.and_then(|block| block["block"]["index"].as_u64())
+					.and_then(|index| u32::try_from(index).ok());
+				match index {
+					Some(index) => checkpoint = Some(index),
+					None => break,
+				}
+				valid_len += line.len() as u64;
+				line.clear();
+			}
+			file.set_len(valid_len)?;
+			Ok(Self { file, checkpoint })
+		}
+	}
+
+	#[async_trait]
+	impl Sink for JsonLinesSink {
+		type Error = io::Error;
+
+		async fn checkpoint(&self) -> Result<Option<u32>, Self::Error> {
+			Ok(self.checkpoint)
+		}
+
+		async fn write(&mut self, blocks: Vec<IndexedBlock>) -> Result<(), Self::Error> {
+			let mut buf = Vec::new();
+			for block in &blocks {
+				serde_json::to_writer(&mut buf, block)?;
+				buf.push(b'\n');
+			}
+			self.file.write_all(&buf)?;
+			self.file.sync_data()?;
+			self.checkpoint = blocks.last().map(IndexedBlock::index).or(self.checkpoint);
+			Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::{Expectation, ExpectationMode, MockProvider, Provider};
+	use neo_types::serde_value::ValueExtension;
+	use primitive_types::H256;
+	use serde_json::json;
+	use std::{fs::OpenOptions, io::Write};
+
+	fn block(index: u32) -> NeoBlock {
+		NeoBlock {
+			hash: H256::from_low_u64_be(u64::from(index) + 1),
+			size: 0,
+			version: 0,
+			prev_block_hash: H256::from_low_u64_be(u64::from(index)),
+			merkle_root_hash: H256::zero(),
+			time: 1_000 + u64::from(index),
+			index: index as i32,
+			primary: None,
+			next_consensus: String::new(),
+			witnesses: None,
+			transactions: None,
+			confirmations: 0,
+			next_block_hash: None,
+		}
+	}
+
+	fn expect_block(mock: &MockProvider, index: u32) {
+		let block = block(index);
+		let log = ApplicationLog { transaction_id: block.hash, executions: vec![] };
+		mock.expect(
+			Expectation::new("getblock")
+				.with_params(vec![index.to_value(), 1.to_value()])
+				.returning(&block),
+		);
+		mock.expect(
+			Expectation::new("getapplicationlog")
+				.with_params(vec![block.hash.to_value()])
+				.returning(&log),
+		);
+	}
+
+	fn mocked() -> (Provider<MockProvider>, MockProvider) {
+		let mock = MockProvider::with_mode(ExpectationMode::Unordered);
+		(Provider::new(mock.clone()), mock)
+	}
+
+	#[tokio::test]
+	async fn indexes_in_order_and_resumes() {
+		let (provider, mock) = mocked();
+		(0..5).for_each(|index| expect_block(&mock, index));
+
+		let mut indexer = Indexer::new(&provider, MemorySink::new()).concurrency(3).batch_size(2);
+		assert_eq!(indexer.sync_to(5).await.unwrap(), 5);
+		let sink = indexer.into_sink();
+		let indices: Vec<_> = sink.blocks().iter().map(IndexedBlock::index).collect();
+		assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+		assert_eq!(sink.blocks()[3].application_logs[0].transaction_id, block(3).hash);
+		mock.assert_expectations_met().unwrap();
+
+		// a new indexer with the same sink continues after the checkpoint
+		(5..7).for_each(|index| expect_block(&mock, index));
+		let mut indexer = Indexer::new(&provider, sink);
+		assert_eq!(indexer.next_index().await.unwrap(), 5);
+		assert_eq!(indexer.sync_to(7).await.unwrap(), 7);
+		assert_eq!(indexer.sink().blocks().len(), 7);
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn writes_blocks_before_an_error() {
+		let (provider, mock) = mocked();
+		(3..5).for_each(|index| expect_block(&mock, index));
+		mock.expect(
+			Expectation::new("getblock")
+				.with_params(vec![5.to_value(), 1.to_value()])
+				.returning_error(crate::JsonRpcError {
+					code: -100,
+					message: "Unknown block".to_string(),
+					data: None,
+				}),
+		);
+
+		let mut indexer = Indexer::new(&provider, MemorySink::new()).start_at(3).concurrency(1);
+		assert!(matches!(indexer.sync_to(8).await, Err(IndexerError::Provider(_))));
+		assert_eq!(indexer.next_index().await.unwrap(), 5);
+	}
+
+	#[tokio::test]
+	async fn json_lines_sink_drops_incomplete_lines() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("blocks.jsonl");
+		let mut sink = JsonLinesSink::open(&path).unwrap();
+		assert_eq!(sink.checkpoint().await.unwrap(), None);
+		let blocks = (0..2)
+			.map(|index| IndexedBlock { block: block(index), application_logs: vec![] })
+			.collect();
+		sink.write(blocks).await.unwrap();
+		drop(sink);
+
+		// a crash while writing the next block
+		let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+		file.write_all(b"{\"block\":{\"hash\"").unwrap();
+		drop(file);
+
+		let mut sink = JsonLinesSink::open(&path).unwrap();
+		assert_eq!(sink.checkpoint().await.unwrap(), Some(1));
+		sink.write(vec![IndexedBlock { block: block(2), application_logs: vec![] }])
+			.await
+			.unwrap();
+		let contents = std::fs::read_to_string(&path).unwrap();
+		assert_eq!(contents.lines().count(), 3);
+		assert_eq!(JsonLinesSink::open(&path).unwrap().checkpoint().await.unwrap(), Some(2));
+	}
+
+	#[test]
+	fn block_application_log_uses_block_hash() {
+		let log: ApplicationLog = serde_json::from_value(json!({
+			"blockhash": format!("{:#x}", H256::repeat_byte(1)),
+			"executions": []
+		}))
+		.unwrap();
+		assert_eq!(log.transaction_id, H256::repeat_byte(1));
+	}
+}
@@ -0,0 +1,346 @@
+use crate::light_client::LightClientError;
+use neo_codec::{Decoder, Encoder};
+use neo_crypto::{
+	hash::HashableForVec,
+	keys::{Secp256r1PublicKey, Secp256r1Signature},
+};
+use neo_types::{encoding, op_code::OpCode, script_hash::ScriptHashExt, Bytes};
+use primitive_types::{H160, H256};
+
+/// A block header as serialized on the Neo N3 network, including its witness.
+///
+/// Hashes, including `next_consensus`, are kept in the byte order used by the RPC API.
+/// `next_consensus` is the script hash of the multi-sig account of the validators that must sign
+/// the next block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+	/// Block version
+	pub version: u32,
+	/// Hash of the previous block
+	pub prev_hash: H256,
+	/// Merkle root of the transactions of the block
+	pub merkle_root: H256,
+	/// Block timestamp in milliseconds
+	pub timestamp: u64,
+	/// Random number chosen by the primary node
+	pub nonce: u64,
+	/// Block height
+	pub index: u32,
+	/// Index of the validator that proposed the block
+	pub primary_index: u8,
+	/// Script hash of the validators that must sign the next block
+	pub next_consensus: H160,
+	/// Invocation script of the witness, pushing the signatures
+	pub invocation: Bytes,
+	/// Verification script of the witness, the validators multi-sig script
+	pub verification: Bytes,
+}
+
+impl Header {
+	/// Size of the unsigned part of a header
+	const UNSIGNED_SIZE: usize = 4 + 32 + 32 + 8 + 8 + 4 + 1 + 20;
+
+	/// Decodes a header as returned by `getblockheader` with `verbose` set to false.
+	pub fn from_base64(header: &str) -> Result<Self, LightClientError> {
+		let bytes = encoding::base64_decode(header)
+			.map_err(|e| LightClientError::InvalidHeader(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// Decodes a serialized header.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, LightClientError> {
+		if bytes.len() <= Self::UNSIGNED_SIZE {
+			return Err(LightClientError::InvalidHeader(format!(
+				"header is too short: {} bytes",
+				bytes.len()
+			)))
+		}
+		let mut reader = Decoder::new(bytes);
+		let header = Self::decode(&mut reader)?;
+		if reader.available() != 0 {
+			return Err(LightClientError::InvalidHeader("trailing bytes after header".to_string()))
+		}
+		Ok(header)
+	}
+
+	/// Decodes a header at the position of `reader`, e.g. the header of a serialized block.
+	pub(crate) fn decode(reader: &mut Decoder) -> Result<Self, LightClientError> {
+		let version = reader.read_u32()?;
+		let prev_hash = read_uint256(reader)?;
+		let merkle_root = read_uint256(reader)?;
+		let timestamp = reader.read_u64()?;
+		let nonce = reader.read_u64()?;
+		let index = reader.read_u32()?;
+		let primary_index = reader.read_u8()?;
+		let mut next_consensus = reader.read_array::<20>()?;
+		next_consensus.reverse();
+		let next_consensus = H160(next_consensus);
+
+		if reader.read_var_int()? != 1 {
+			return Err(LightClientError::InvalidHeader("expected exactly one witness".to_string()))
+		}
+		let invocation = reader.read_var_bytes()?;
+		let verification = reader.read_var_bytes()?;
+
+		Ok(Self {
+			version,
+			prev_hash,
+			merkle_root,
+			timestamp,
+			nonce,
+			index,
+			primary_index,
+			next_consensus,
+			invocation,
+			verification,
+		})
+	}
+
+	/// Serializes the header without its witness, the data its hash is computed from.
+	pub fn unsigned_bytes(&self) -> Bytes {
+		let mut writer = Encoder::new();
+		writer.write_u32(self.version);
+		writer.write_bytes(&to_internal(&self.prev_hash));
+		writer.write_bytes(&to_internal(&self.merkle_root));
+		writer.write_bytes(&self.timestamp.to_le_bytes());
+		writer.write_bytes(&self.nonce.to_le_bytes());
+		writer.write_u32(self.index);
+		writer.write_u8(self.primary_index);
+		let mut next_consensus = self.next_consensus.to_fixed_bytes();
+		next_consensus.reverse();
+		writer.write_bytes(&next_consensus);
+		writer.to_bytes()
+	}
+
+	/// Serializes the header including its witness.
+	pub fn to_bytes(&self) -> Bytes {
+		let mut writer = Encoder::new();
+		writer.write_bytes(&self.unsigned_bytes());
+		writer.write_var_int(1);
+		writer.write_var_bytes(&self.invocation);
+		writer.write_var_bytes(&self.verification);
+		writer.to_bytes()
+	}
+
+	/// The block hash.
+	pub fn hash(&self) -> H256 {
+		let mut hash = [0u8; 32];
+		hash.copy_from_slice(&self.unsigned_bytes().hash256());
+		hash.reverse();
+		H256::from(hash)
+	}
+
+	/// The data signed by the validators: the network magic followed by the block hash.
+	pub fn sign_data(&self, network: u32) -> Bytes {
+		[network.to_le_bytes().as_slice(), &to_internal(&self.hash())].concat()
+	}
+
+	/// Checks that the witness of this header is a valid multi-sig of the validators whose
+	/// account has the script hash `next_consensus`, i.e. the `next_consensus` of the previous
+	/// header.
+	pub fn verify_witness(
+		&self,
+		network: u32,
+		next_consensus: &H160,
+	) -> Result<(), LightClientError> {
+		if H160::from_script(&self.verification) != *next_consensus {
+			return Err(LightClientError::InvalidWitness(format!(
+				"verification script of block {} does not match the expected validators",
+				self.index
+			)))
+		}
+
+		verify_multi_sig(&self.sign_data(network), &self.invocation, &self.verification).map_err(
+			|reason| LightClientError::InvalidWitness(format!("block {}: {reason}", self.index)),
+		)
+	}
+}
+
+/// Checks that `invocation` pushes enough valid signatures of `message` for the single-sig or
+/// multi-sig `verification` script. Returns why the witness is invalid otherwise.
+pub(crate) fn verify_multi_sig(
+	message: &[u8],
+	invocation: &[u8],
+	verification: &[u8],
+) -> Result<(), String> {
+	let (threshold, keys) = parse_verification_script(verification)
+		.ok_or_else(|| "unsupported verification script".to_string())?;
+	let signatures = parse_invocation_script(invocation)
+		.ok_or_else(|| "invalid invocation script".to_string())?;
+	if signatures.len() != threshold {
+		return Err(format!("expected {} signatures, got {}", threshold, signatures.len()))
+	}
+
+	// Signatures must appear in the same order as the keys, as in `CheckMultisig`.
+	let (mut sig_index, mut key_index) = (0, 0);
+	while sig_index < signatures.len() && key_index < keys.len() {
+		if keys[key_index].verify(message, &signatures[sig_index]).is_ok() {
+			sig_index += 1;
+		}
+		key_index += 1;
+		if signatures.len() - sig_index > keys.len() - key_index {
+			break
+		}
+	}
+	if sig_index != signatures.len() {
+		return Err("signatures are not valid".to_string())
+	}
+	Ok(())
+}
+
+fn read_uint256(reader: &mut Decoder) -> Result<H256, LightClientError> {
+	let mut bytes = reader.read_array::<32>()?;
+	bytes.reverse();
+	Ok(H256(bytes))
+}
+
+fn to_internal(hash: &H256) -> [u8; 32] {
+	let mut bytes = hash.to_fixed_bytes();
+	bytes.reverse();
+	bytes
+}
+
+/// Reads an integer pushed with `PUSH1`..`PUSH16` or `PUSHINT8`.
+fn read_small_int(script: &[u8], pos: &mut usize) -> Option<usize> {
+	let op = *script.get(*pos)?;
+	*pos += 1;
+	match op {
+		op if op >= OpCode::Push1 as u8 && op <= OpCode::Push16 as u8 =>
+			Some((op - OpCode::Push0 as u8) as usize),
+		op if op == OpCode::PushInt8 as u8 => {
+			let value = *script.get(*pos)?;
+			*pos += 1;
+			Some(value as usize)
+		},
+		_ => None,
+	}
+}
+
+/// Reads `PUSHDATA1 <len> <data>` where the data must be `len` bytes long.
+fn read_push_data<'a>(script: &'a [u8], pos: &mut usize, len: usize) -> Option<&'a [u8]> {
+	if script.get(*pos) != Some(&(OpCode::PushData1 as u8))
+		|| script.get(*pos + 1) != Some(&(len as u8))
+	{
+		return None
+	}
+	let data = script.get(*pos + 2..*pos + 2 + len)?;
+	*pos += 2 + len;
+	Some(data)
+}
+
+/// Parses a single-sig or multi-sig verification script into its threshold and public keys.
+fn parse_verification_script(script: &[u8]) -> Option<(usize, Vec<Secp256r1PublicKey>)> {
+	let mut pos = 0;
+	let (threshold, keys) = if script.first() == Some(&(OpCode::PushData1 as u8)) {
+		let key = read_push_data(script, &mut pos, 33)?;
+		(1, vec![Secp256r1PublicKey::from_bytes(key).ok()?])
+	} else {
+		let threshold = read_small_int(script, &mut pos)?;
+		let mut keys = vec![];
+		while script.get(pos) == Some(&(OpCode::PushData1 as u8)) {
+			keys.push(Secp256r1PublicKey::from_bytes(read_push_data(script, &mut pos, 33)?).ok()?);
+		}
+		if read_small_int(script, &mut pos)? != keys.len() {
+			return None
+		}
+		(threshold, keys)
+	};
+
+	let is_syscall = script.get(pos) == Some(&(OpCode::Syscall as u8)) && script.len() == pos + 5;
+	if !is_syscall || threshold == 0 || threshold > keys.len() {
+		return None
+	}
+	Some((threshold, keys))
+}
+
+/// Parses an invocation script made of pushed 64 byte signatures.
+fn parse_invocation_script(script: &[u8]) -> Option<Vec<Secp256r1Signature>> {
+	let mut pos = 0;
+	let mut signatures = vec![];
+	while pos < script.len() {
+		signatures
+			.push(Secp256r1Signature::from_bytes(read_push_data(script, &mut pos, 64)?).ok()?);
+	}
+	Some(signatures)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use neo_crypto::key_pair::KeyPair;
+
+	pub(crate) const NETWORK: u32 = 860833102;
+
+	pub(crate) fn multi_sig_script(key_pairs: &[KeyPair], threshold: u8) -> Bytes {
+		let mut script = vec![OpCode::Push0 as u8 + threshold];
+		for key_pair in key_pairs {
+			script.extend([OpCode::PushData1 as u8, 33]);
+			script.extend(key_pair.public_key().get_encoded(true));
+		}
+		script.push(OpCode::Push0 as u8 + key_pairs.len() as u8);
+		script.extend([OpCode::Syscall as u8, 0x9e, 0xd0, 0xdc, 0x3a]);
+		script
+	}
+
+	/// Creates a header following `prev`, signed by the first `threshold` validators.
+	pub(crate) fn signed_header(
+		prev: Option<&Header>,
+		validators: &[KeyPair],
+		threshold: u8,
+		merkle_root: H256,
+	) -> Header {
+		let verification = multi_sig_script(validators, threshold);
+		let mut header = Header {
+			version: 0,
+			prev_hash: prev.map(Header::hash).unwrap_or_default(),
+			merkle_root,
+			timestamp: 1_700_000_000_000 + prev.map(|h| h.index as u64 + 1).unwrap_or(0) * 15_000,
+			nonce: 42,
+			index: prev.map(|h| h.index + 1).unwrap_or(0),
+			primary_index: 0,
+			next_consensus: H160::from_script(&verification),
+			invocation: vec![],
+			verification,
+		};
+		let message = header.sign_data(NETWORK);
+		for key_pair in &validators[..threshold as usize] {
+			let signature = key_pair.private_key.sign_tx(&message).unwrap();
+			header.invocation.extend([OpCode::PushData1 as u8, 64]);
+			header.invocation.extend(signature.to_raw_bytes());
+		}
+		header
+	}
+
+	#[test]
+	fn test_header_round_trip() {
+		let validators: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let header = signed_header(None, &validators, 3, H256::repeat_byte(7));
+		let decoded = Header::from_bytes(&header.to_bytes()).unwrap();
+		assert_eq!(decoded, header);
+		assert_eq!(decoded.hash(), header.hash());
+		assert!(Header::from_bytes(&header.unsigned_bytes()).is_err());
+
+		// the script hash is serialized in little-endian byte order
+		let unsigned = header.unsigned_bytes();
+		assert_eq!(&unsigned[Header::UNSIGNED_SIZE - 20..], header.verification.sha256_ripemd160());
+	}
+
+	#[test]
+	fn test_verify_witness() {
+		let validators: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let genesis = signed_header(None, &validators, 3, H256::zero());
+		let header = signed_header(Some(&genesis), &validators, 3, H256::zero());
+		header.verify_witness(NETWORK, &genesis.next_consensus).unwrap();
+
+		assert!(header.verify_witness(NETWORK + 1, &genesis.next_consensus).is_err());
+		assert!(header.verify_witness(NETWORK, &H160::zero()).is_err());
+
+		let mut tampered = header.clone();
+		tampered.nonce += 1;
+		assert!(tampered.verify_witness(NETWORK, &genesis.next_consensus).is_err());
+
+		let mut missing = header.clone();
+		missing.invocation.truncate(66 * 2);
+		assert!(missing.verify_witness(NETWORK, &genesis.next_consensus).is_err());
+	}
+}
@@ -0,0 +1,412 @@
+use crate::{
+	core::{
+		responses::neo_get_state_root::StateRoot,
+		transaction::verification_script::VerificationScript,
+	},
+	light_client::{header::verify_multi_sig, Header, MerkleProof},
+	state::{verify_proof, StateProofError},
+	Middleware,
+};
+use neo_codec::CodecError;
+use neo_crypto::keys::Secp256r1PublicKey;
+use neo_types::{
+	encoding, natives, role::Role, script_hash::ScriptHashExt, serde_value::ValueExtension, Bytes,
+};
+use primitive_types::{H160, H256};
+use serde_json::Value;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Errors that can occur while syncing or querying a [`HeaderChain`].
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum LightClientError {
+	/// The RPC node returned an error
+	#[error("RPC error: {0}")]
+	Rpc(String),
+	/// A header could not be decoded
+	#[error("Invalid header: {0}")]
+	InvalidHeader(String),
+	/// A header does not follow the current tip of the chain
+	#[error("Header {index} does not extend the chain: {reason}")]
+	UnlinkedHeader {
+		/// Index of the rejected header
+		index: u32,
+		/// Why the header was rejected
+		reason: String,
+	},
+	/// The witness of a header is not signed by the expected validators
+	#[error("Invalid witness: {0}")]
+	InvalidWitness(String),
+	/// The requested block is not part of the synced chain
+	#[error("Block {0} is not part of the synced header chain")]
+	UnknownBlock(u32),
+	/// The merkle proof of a transaction does not match the header
+	#[error("Transaction {0:?} is not included in block {1}")]
+	NotIncluded(H256, u32),
+	/// No state root has been synced yet
+	#[error("No state root available")]
+	NoStateRoot,
+	/// A state proof is invalid
+	#[error(transparent)]
+	StateProof(#[from] StateProofError),
+}
+
+impl From<CodecError> for LightClientError {
+	fn from(err: CodecError) -> Self {
+		LightClientError::InvalidHeader(err.to_string())
+	}
+}
+
+/// A chain of block headers whose witnesses have been checked, starting from a trusted header.
+///
+/// Every header added to the chain must reference the hash of the current tip and be signed by
+/// the validators designated by the `next_consensus` field of the tip. Once synced, the chain can
+/// verify that a transaction is part of a block without trusting the RPC node that served it.
+#[derive(Debug, Clone)]
+pub struct HeaderChain {
+	network: u32,
+	headers: Vec<Header>,
+	state_root: Option<StateRoot>,
+}
+
+impl HeaderChain {
+	/// Creates a chain anchored at `trusted`, e.g. the genesis header or a header obtained from a
+	/// trusted source. `network` is the network magic used in signatures.
+	pub fn new(network: u32, trusted: Header) -> Self {
+		Self { network, headers: vec![trusted], state_root: None }
+	}
+
+	/// The network magic of the chain
+	pub fn network(&self) -> u32 {
+		self.network
+	}
+
+	/// The latest validated header
+	pub fn tip(&self) -> &Header {
+		self.headers.last().expect("chain always holds the trusted header")
+	}
+
+	/// The index of the latest validated header
+	pub fn height(&self) -> u32 {
+		self.tip().index
+	}
+
+	/// The validated header at `index`, if it is part of the chain
+	pub fn header(&self, index: u32) -> Option<&Header> {
+		let first = self.headers[0].index;
+		index.checked_sub(first).and_then(|offset| self.headers.get(offset as usize))
+	}
+
+	/// The latest synced state root
+	pub fn state_root(&self) -> Option<&StateRoot> {
+		self.state_root.as_ref()
+	}
+
+	/// Validates `header` against the tip and appends it to the chain.
+	pub fn add_header(&mut self, header: Header) -> Result<(), LightClientError> {
+		let tip = self.tip();
+		if header.index != tip.index + 1 {
+			return Err(LightClientError::UnlinkedHeader {
+				index: header.index,
+				reason: format!("expected index {}", tip.index + 1),
+			})
+		}
+		if header.prev_hash != tip.hash() {
+			return Err(LightClientError::UnlinkedHeader {
+				index: header.index,
+				reason: format!(
+					"previous hash {:?} is not the tip {:?}",
+					header.prev_hash,
+					tip.hash()
+				),
+			})
+		}
+		header.verify_witness(self.network, &tip.next_consensus)?;
+		self.headers.push(header);
+		Ok(())
+	}
+
+	/// Downloads and validates all headers the node knows about past the current tip.
+	///
+	/// Returns the new height of the chain.
+	pub async fn sync<M: Middleware>(&mut self, client: &M) -> Result<u32, LightClientError> {
+		let count = client.get_block_header_count().await.map_err(rpc_error)?;
+		for index in self.height() + 1..count {
+			let raw = client.get_raw_block_header_by_index(index).await.map_err(rpc_error)?;
+			self.add_header(Header::from_base64(&raw)?)?;
+		}
+		Ok(self.height())
+	}
+
+	/// Fetches the state root of the latest validated block, checks its witness and keeps track
+	/// of it.
+	///
+	/// The node must run the `StateService` plugin. State roots are signed by the state
+	/// validators rather than by the consensus nodes. Their keys are read from the `RoleManagement`
+	/// contract of the same node, so a node serving a forged designation is not detected. Use
+	/// [`add_state_root`](Self::add_state_root) with keys from a trusted source to avoid this.
+	pub async fn sync_state_root<M: Middleware>(
+		&mut self,
+		client: &M,
+	) -> Result<&StateRoot, LightClientError> {
+		let state_root = client.get_state_root(self.height()).await.map_err(rpc_error)?;
+		let validators = state_validators(client, state_root.index).await?;
+		self.add_state_root(state_root, &validators)
+	}
+
+	/// Keeps track of `state_root` once its witness is checked against `validators`, the state
+	/// validators designated at the index of the state root.
+	///
+	/// The witness must be a multi-sig of the validators requiring the same number of signatures
+	/// as the consensus, i.e. `n - (n - 1) / 3` out of `n`.
+	pub fn add_state_root(
+		&mut self,
+		state_root: StateRoot,
+		validators: &[Secp256r1PublicKey],
+	) -> Result<&StateRoot, LightClientError> {
+		if state_root.index > self.height() {
+			return Err(LightClientError::UnknownBlock(state_root.index))
+		}
+		let [witness] = state_root.witnesses.as_slice() else {
+			return Err(LightClientError::InvalidWitness(format!(
+				"state root {} must have exactly one witness",
+				state_root.index
+			)))
+		};
+		let invocation = encoding::base64_decode(&witness.invocation)
+			.map_err(|e| LightClientError::InvalidWitness(e.to_string()))?;
+		let verification = encoding::base64_decode(&witness.verification)
+			.map_err(|e| LightClientError::InvalidWitness(e.to_string()))?;
+
+		if H160::from_script(&verification) != bft_address(validators)? {
+			return Err(LightClientError::InvalidWitness(format!(
+				"verification script of state root {} does not match the state validators",
+				state_root.index
+			)))
+		}
+		verify_multi_sig(&state_root.sign_data(self.network), &invocation, &verification).map_err(
+			|reason| {
+				LightClientError::InvalidWitness(format!(
+					"state root {}: {reason}",
+					state_root.index
+				))
+			},
+		)?;
+		Ok(self.state_root.insert(state_root))
+	}
+
+	/// Verifies a `getproof` result for `key` against the tracked state root.
+	///
+	/// See [`verify_proof`] for the meaning of the result.
+	pub fn verify_state(
+		&self,
+		key: &[u8],
+		nodes: &[Bytes],
+	) -> Result<Option<Bytes>, LightClientError> {
+		let state_root = self.state_root.as_ref().ok_or(LightClientError::NoStateRoot)?;
+		Ok(verify_proof(state_root.root_hash, key, nodes)?)
+	}
+
+	/// Verifies that the transaction `tx_hash` is included in a block of the validated chain.
+	///
+	/// The transaction hashes of the block are fetched from the node and checked against the
+	/// merkle root of the validated header, so a node cannot claim a transaction that is not part
+	/// of the chain. Returns the index of the block containing the transaction.
+	pub async fn verify_tx_inclusion<M: Middleware>(
+		&self,
+		client: &M,
+		tx_hash: H256,
+	) -> Result<u32, LightClientError> {
+		let index = client.get_transaction_height(tx_hash).await.map_err(rpc_error)?;
+		let header = self.header(index).ok_or(LightClientError::UnknownBlock(index))?;
+
+		let block: Value = client
+			.provider()
+			.request("getblock", vec![index.to_value(), 1.to_value()])
+			.await
+			.map_err(rpc_error)?;
+		let hashes = block["tx"]
+			.as_array()
+			.ok_or_else(|| LightClientError::Rpc("block has no transaction list".to_string()))?
+			.iter()
+			.map(|tx| {
+				tx["hash"]
+					.as_str()
+					.and_then(|hash| H256::from_str(hash.trim_start_matches("0x")).ok())
+					.ok_or_else(|| LightClientError::Rpc("invalid transaction hash".to_string()))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let position = hashes
+			.iter()
+			.position(|hash| *hash == tx_hash)
+			.ok_or(LightClientError::NotIncluded(tx_hash, index))?;
+		let proof = MerkleProof::build(&hashes, position).expect("position is in range");
+		if !proof.verify(tx_hash, header.merkle_root) {
+			return Err(LightClientError::NotIncluded(tx_hash, index))
+		}
+		Ok(index)
+	}
+}
+
+/// Reads the keys designated for [`Role::StateValidator`] at `index` from `RoleManagement`.
+async fn state_validators<M: Middleware>(
+	client: &M,
+	index: u32,
+) -> Result<Vec<Secp256r1PublicKey>, LightClientError> {
+	let result = client
+		.invoke_function(
+			&natives::ROLE_MANAGEMENT.hash,
+			"getDesignatedByRole".to_string(),
+			vec![(&Role::StateValidator).into(), index.into()],
+			None,
+		)
+		.await
+		.map_err(rpc_error)?
+		.into_result()
+		.map_err(rpc_error)?;
+	result
+		.stack
+		.first()
+		.and_then(|item| item.as_array())
+		.ok_or_else(|| LightClientError::Rpc("no designated state validators".to_string()))?
+		.iter()
+		.map(|item| {
+			item.as_public_key()
+				.ok_or_else(|| LightClientError::Rpc("invalid state validator key".to_string()))
+		})
+		.collect()
+}
+
+/// The script hash of the `n - (n - 1) / 3` out of `n` multi-sig account of `validators`.
+fn bft_address(validators: &[Secp256r1PublicKey]) -> Result<H160, LightClientError> {
+	// keys are ordered like `ECPoint`s, by X and then Y coordinate
+	let mut keys = validators.to_vec();
+	keys.sort_by(|a, b| a.get_encoded(false).cmp(&b.get_encoded(false)));
+	let threshold = keys.len() - keys.len().saturating_sub(1) / 3;
+	let script = VerificationScript::from_multi_sig(&keys, threshold as u8)
+		.map_err(|e| LightClientError::InvalidWitness(e.to_string()))?;
+	Ok(H160::from_script(script.script()))
+}
+
+fn rpc_error(err: impl std::fmt::Display) -> LightClientError {
+	LightClientError::Rpc(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		core::responses::neo_witness::NeoWitness,
+		light_client::header::tests::{signed_header, NETWORK},
+	};
+	use neo_crypto::key_pair::KeyPair;
+	use neo_types::op_code::OpCode;
+
+	/// Creates a state root for `index` signed by the state `validators`.
+	fn signed_state_root(index: u32, validators: &[KeyPair]) -> StateRoot {
+		let mut key_pairs = validators.to_vec();
+		key_pairs.sort_by_key(|key_pair| key_pair.public_key().get_encoded(false));
+		let keys: Vec<_> = key_pairs.iter().map(KeyPair::public_key).collect();
+		let threshold = keys.len() - (keys.len() - 1) / 3;
+		let verification = VerificationScript::from_multi_sig(&keys, threshold as u8).unwrap();
+
+		let mut state_root =
+			StateRoot { version: 0, index, root_hash: H256::repeat_byte(0x42), witnesses: vec![] };
+		let message = state_root.sign_data(NETWORK);
+		let mut invocation = vec![];
+		for key_pair in &key_pairs[..threshold] {
+			invocation.extend([OpCode::PushData1 as u8, 64]);
+			invocation.extend(key_pair.private_key.sign_tx(&message).unwrap().to_raw_bytes());
+		}
+		state_root.witnesses.push(NeoWitness::new(
+			encoding::base64_encode(&invocation),
+			encoding::base64_encode(verification.script()),
+		));
+		state_root
+	}
+
+	#[test]
+	fn test_add_header() {
+		let validators: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let genesis = signed_header(None, &validators, 3, H256::zero());
+		let mut chain = HeaderChain::new(NETWORK, genesis.clone());
+
+		let first = signed_header(Some(&genesis), &validators, 3, H256::zero());
+		let second = signed_header(Some(&first), &validators, 3, H256::zero());
+		assert!(matches!(
+			chain.add_header(second.clone()),
+			Err(LightClientError::UnlinkedHeader { index: 2, .. })
+		));
+
+		chain.add_header(first.clone()).unwrap();
+		chain.add_header(second).unwrap();
+		assert_eq!(chain.height(), 2);
+		assert_eq!(chain.header(1), Some(&first));
+		assert_eq!(chain.header(3), None);
+	}
+
+	#[test]
+	fn test_reject_foreign_validators() {
+		let validators: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let others: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let genesis = signed_header(None, &validators, 3, H256::zero());
+		let mut chain = HeaderChain::new(NETWORK, genesis.clone());
+
+		let forged = signed_header(Some(&genesis), &others, 3, H256::zero());
+		assert!(matches!(chain.add_header(forged), Err(LightClientError::InvalidWitness(_))));
+		assert_eq!(chain.height(), 0);
+	}
+
+	#[test]
+	fn test_add_state_root() {
+		let validators: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let state_validators: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let keys: Vec<_> = state_validators.iter().map(KeyPair::public_key).collect();
+		let genesis = signed_header(None, &validators, 3, H256::zero());
+		let mut chain = HeaderChain::new(NETWORK, genesis);
+
+		let state_root = signed_state_root(0, &state_validators);
+		assert_eq!(chain.add_state_root(state_root.clone(), &keys), Ok(&state_root));
+		assert_eq!(chain.state_root(), Some(&state_root));
+
+		let ahead = signed_state_root(1, &state_validators);
+		assert_eq!(chain.add_state_root(ahead, &keys), Err(LightClientError::UnknownBlock(1)));
+	}
+
+	#[test]
+	fn test_reject_tampered_state_root() {
+		let validators: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let state_validators: Vec<KeyPair> = (0..4).map(|_| KeyPair::new_random()).collect();
+		let keys: Vec<_> = state_validators.iter().map(KeyPair::public_key).collect();
+		let genesis = signed_header(None, &validators, 3, H256::zero());
+		let mut chain = HeaderChain::new(NETWORK, genesis);
+		let state_root = signed_state_root(0, &state_validators);
+
+		// a root hash that is not the one the validators signed
+		let mut forged = state_root.clone();
+		forged.root_hash = H256::repeat_byte(0x66);
+		assert!(matches!(
+			chain.add_state_root(forged, &keys),
+			Err(LightClientError::InvalidWitness(_))
+		));
+
+		// a signature of the witness that was altered
+		let mut tampered = state_root.clone();
+		let mut invocation = encoding::base64_decode(&tampered.witnesses[0].invocation).unwrap();
+		invocation[10] ^= 0xff;
+		tampered.witnesses[0].invocation = encoding::base64_encode(&invocation);
+		assert!(matches!(
+			chain.add_state_root(tampered, &keys),
+			Err(LightClientError::InvalidWitness(_))
+		));
+
+		// a witness of other keys than the designated state validators
+		let consensus: Vec<_> = validators.iter().map(KeyPair::public_key).collect();
+		assert!(matches!(
+			chain.add_state_root(state_root, &consensus),
+			Err(LightClientError::InvalidWitness(_))
+		));
+		assert_eq!(chain.state_root(), None);
+	}
+}
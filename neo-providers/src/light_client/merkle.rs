@@ -0,0 +1,133 @@
+use neo_crypto::hash::HashableForVec;
+use primitive_types::H256;
+
+/// An inclusion proof for a leaf of the transaction merkle tree of a block.
+///
+/// Hashes are in the byte order used by the RPC API, the same as transaction and block hashes
+/// returned by the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+	/// Position of the leaf in the tree
+	pub index: usize,
+	/// Sibling hashes from the leaf level up to, but excluding, the root
+	pub path: Vec<H256>,
+}
+
+impl MerkleProof {
+	/// Builds the proof for the leaf at `index` of the tree made of `hashes`.
+	///
+	/// Returns `None` if `index` is out of range.
+	pub fn build(hashes: &[H256], index: usize) -> Option<Self> {
+		if index >= hashes.len() {
+			return None
+		}
+
+		let mut level: Vec<[u8; 32]> = hashes.iter().map(to_internal).collect();
+		let mut position = index;
+		let mut path = vec![];
+		while level.len() > 1 {
+			let sibling = level.get(position ^ 1).unwrap_or(&level[position]);
+			path.push(from_internal(sibling));
+			level = parent_level(&level);
+			position /= 2;
+		}
+
+		Some(Self { index, path })
+	}
+
+	/// Computes the merkle root implied by this proof for `leaf`.
+	pub fn compute_root(&self, leaf: H256) -> H256 {
+		let mut hash = to_internal(&leaf);
+		let mut position = self.index;
+		for sibling in &self.path {
+			let sibling = to_internal(sibling);
+			hash = if position % 2 == 0 {
+				hash_pair(&hash, &sibling)
+			} else {
+				hash_pair(&sibling, &hash)
+			};
+			position /= 2;
+		}
+		from_internal(&hash)
+	}
+
+	/// Checks that this proof links `leaf` to `root`.
+	pub fn verify(&self, leaf: H256, root: H256) -> bool {
+		self.compute_root(leaf) == root
+	}
+}
+
+/// Computes the merkle root of `hashes` the way Neo computes the `merkleroot` of a block.
+///
+/// Returns `None` for an empty list; blocks without transactions have a zero merkle root.
+pub fn merkle_root(hashes: &[H256]) -> Option<H256> {
+	let mut level: Vec<[u8; 32]> = hashes.iter().map(to_internal).collect();
+	while level.len() > 1 {
+		level = parent_level(&level);
+	}
+	level.first().map(from_internal)
+}
+
+fn parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+	level
+		.chunks(2)
+		.map(|pair| match pair {
+			[left, right] => hash_pair(left, right),
+			[single] => hash_pair(single, single),
+			_ => unreachable!("chunks of two"),
+		})
+		.collect()
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+	let digest = [left.as_slice(), right.as_slice()].concat().hash256().hash256();
+	let mut hash = [0u8; 32];
+	hash.copy_from_slice(&digest);
+	hash
+}
+
+fn to_internal(hash: &H256) -> [u8; 32] {
+	let mut bytes = hash.to_fixed_bytes();
+	bytes.reverse();
+	bytes
+}
+
+fn from_internal(bytes: &[u8; 32]) -> H256 {
+	let mut bytes = *bytes;
+	bytes.reverse();
+	H256::from(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn leaves(count: u8) -> Vec<H256> {
+		(1..=count).map(|i| H256::repeat_byte(i)).collect()
+	}
+
+	#[test]
+	fn test_merkle_root() {
+		assert_eq!(merkle_root(&[]), None);
+		assert_eq!(merkle_root(&leaves(1)), Some(H256::repeat_byte(1)));
+
+		let hashes = leaves(3);
+		let left = hash_pair(&to_internal(&hashes[0]), &to_internal(&hashes[1]));
+		let right = hash_pair(&to_internal(&hashes[2]), &to_internal(&hashes[2]));
+		assert_eq!(merkle_root(&hashes), Some(from_internal(&hash_pair(&left, &right))));
+	}
+
+	#[test]
+	fn test_merkle_proof() {
+		for count in 1..=7 {
+			let hashes = leaves(count);
+			let root = merkle_root(&hashes).unwrap();
+			for (index, leaf) in hashes.iter().enumerate() {
+				let proof = MerkleProof::build(&hashes, index).unwrap();
+				assert!(proof.verify(*leaf, root));
+				assert!(!proof.verify(H256::zero(), root));
+			}
+			assert!(MerkleProof::build(&hashes, hashes.len()).is_none());
+		}
+	}
+}
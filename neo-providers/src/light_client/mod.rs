@@ -0,0 +1,31 @@
+//! Light client support: a validated chain of block headers.
+//!
+//! A [`HeaderChain`] starts from a trusted header and only accepts headers that are linked to it
+//! and signed by the validators it designates. Transactions and storage values served by an
+//! untrusted RPC node can then be checked against the validated headers and state root.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use neo_providers::{light_client::{Header, HeaderChain}, Http, Middleware, Provider};
+//! use primitive_types::H256;
+//! use std::convert::TryFrom;
+//!
+//! # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! let provider = Provider::<Http>::try_from("http://seed1.neo.org:10332")?;
+//!
+//! let genesis = Header::from_base64(&provider.get_raw_block_header_by_index(0).await?)?;
+//! let mut chain = HeaderChain::new(860833102, genesis);
+//! chain.sync(&provider).await?;
+//!
+//! let block = chain.verify_tx_inclusion(&provider, H256::zero()).await?;
+//! # Ok(()) }
+//! ```
+
+mod header;
+mod header_chain;
+mod merkle;
+
+pub use header::Header;
+pub use header_chain::{HeaderChain, LightClientError};
+pub use merkle::{merkle_root, MerkleProof};
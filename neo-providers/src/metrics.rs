@@ -0,0 +1,77 @@
+//! Request metrics, recorded through the [`metrics`](https://docs.rs/metrics) facade when the
+//! `metrics` feature is enabled. Without the feature, [`instrument`] only awaits the request.
+//!
+//! Install any `metrics` recorder, e.g. `metrics-exporter-prometheus`, to export them:
+//!
+//! | Name                           | Type      | Description                              |
+//! |--------------------------------|-----------|------------------------------------------|
+//! | `neo_requests_total`           | counter   | Completed requests                       |
+//! | `neo_request_errors_total`     | counter   | Requests that returned an error          |
+//! | `neo_request_duration_seconds` | histogram | Latency of the requests                  |
+//! | `neo_requests_in_flight`       | gauge     | Requests sent but not completed yet      |
+//!
+//! Every metric is labelled with the `layer` handling the request, `provider` for the RPC
+//! requests of a [`Provider`](crate::Provider) or the name of a middleware, and the `method`.
+use std::future::Future;
+
+/// Counter of the completed requests
+pub const REQUESTS_TOTAL: &str = "neo_requests_total";
+/// Counter of the requests that returned an error
+pub const REQUEST_ERRORS_TOTAL: &str = "neo_request_errors_total";
+/// Histogram of the latency of the requests, in seconds
+pub const REQUEST_DURATION_SECONDS: &str = "neo_request_duration_seconds";
+/// Gauge of the requests sent but not completed yet
+pub const REQUESTS_IN_FLIGHT: &str = "neo_requests_in_flight";
+
+/// The `layer` label of the RPC requests sent by a [`Provider`](crate::Provider)
+pub const PROVIDER_LAYER: &str = "provider";
+
+/// Awaits `request`, a call of `method` on the middleware `layer`, and records its metrics.
+pub async fn instrument<F, T, E>(layer: &'static str, method: &str, request: F) -> Result<T, E>
+where
+	F: Future<Output = Result<T, E>>,
+{
+	#[cfg(feature = "metrics")]
+	{
+		let labels = vec![
+			::metrics::Label::new("layer", layer),
+			::metrics::Label::new("method", method.to_owned()),
+		];
+		// decremented on drop, so cancelled requests are not counted as in flight forever
+		let _in_flight = InFlight::new(labels.clone());
+		let start = instant::Instant::now();
+		let result = request.await;
+
+		::metrics::histogram!(REQUEST_DURATION_SECONDS, labels.clone())
+			.record(start.elapsed().as_secs_f64());
+		::metrics::counter!(REQUESTS_TOTAL, labels.clone()).increment(1);
+		if result.is_err() {
+			::metrics::counter!(REQUEST_ERRORS_TOTAL, labels).increment(1);
+		}
+		result
+	}
+	#[cfg(not(feature = "metrics"))]
+	{
+		let _ = (layer, method);
+		request.await
+	}
+}
+
+#[cfg(feature = "metrics")]
+struct InFlight(::metrics::Gauge);
+
+#[cfg(feature = "metrics")]
+impl InFlight {
+	fn new(labels: Vec<::metrics::Label>) -> Self {
+		let gauge = ::metrics::gauge!(REQUESTS_IN_FLIGHT, labels);
+		gauge.increment(1.0);
+		Self(gauge)
+	}
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for InFlight {
+	fn drop(&mut self) {
+		self.0.decrement(1.0);
+	}
+}
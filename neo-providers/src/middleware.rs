@@ -3,10 +3,12 @@ use crate::{
 		account::AccountTrait,
 		responses::{
 			neo_address::NeoAddress,
-			neo_application_log::ApplicationLog,
+			neo_application_log::{ApplicationLog, ApplicationLogFilter},
 			neo_balances::{Nep11Balances, Nep17Balances},
 			neo_block::NeoBlock,
 			neo_find_states::States,
+			neo_find_storage::FindStorage,
+			neo_get_candidates::Candidate,
 			neo_get_mem_pool::MemPoolDetails,
 			neo_get_next_block_validators::Validator,
 			neo_get_peers::Peers,
@@ -26,26 +28,33 @@ use crate::{
 			transaction_send_token::TransactionSendToken, witness::Witness,
 		},
 	},
-	JsonRpcClient, MiddlewareError, PendingTransaction, Provider, ProviderError,
+	maybe_connectivity, JsonRpcClient, JsonRpcError, MiddlewareError, PendingTransaction, Provider,
+	ProviderError, PubsubClient, SubscriptionStream,
 };
 use async_trait::async_trait;
 use auto_impl::auto_impl;
+use neo_codec::{encode::NeoSerializable, Decoder};
 use neo_config::NeoConfig;
 use neo_types::{
 	address::Address,
 	block::{Block, BlockId},
 	contract_parameter::ContractParameter,
 	contract_state::ContractState,
+	encoding,
+	gas::Gas,
 	invocation_result::InvocationResult,
+	log::Log,
 	native_contract_state::NativeContractState,
+	notification_filter::NotificationFilter,
 	stack_item::StackItem,
-	syncing::SyncingStatus,
 	Bytes,
 };
 use primitive_types::{H160, H256};
-use std::{collections::HashMap, fmt::Debug};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
-#[async_trait]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 #[auto_impl(&, Box, Arc)]
 pub trait Middleware: Sync + Send + Debug {
 	/// Error type returned by most operations
@@ -69,8 +78,10 @@ pub trait Middleware: Sync + Send + Debug {
 		self.inner().provider()
 	}
 
-	fn config(&self) -> &NeoConfig {
-		&self.inner().config()
+	/// A snapshot of the client config. Later updates of the config are not reflected in the
+	/// returned value.
+	fn config(&self) -> Arc<NeoConfig> {
+		self.inner().config()
 	}
 
 	/// Return the default sender (if any). This will typically be the
@@ -165,12 +176,13 @@ pub trait Middleware: Sync + Send + Debug {
 	}
 
 	/// Return current client syncing status. If IsFalse sync is over.
-	async fn syncing(&self) -> Result<SyncingStatus, Self::Error> {
+	#[cfg(feature = "interop-eth")]
+	async fn syncing(&self) -> Result<neo_types::syncing::SyncingStatus, Self::Error> {
 		self.inner().syncing().await.map_err(MiddlewareError::from_err)
 	}
 
-	/// Returns the currently configured network magic, a value used in replay-protected
-	/// transaction signing as introduced by EIP-155.
+	/// Returns the magic of the network, which is part of the data signed by witnesses, see
+	/// [`Transaction::get_hash_data`]. The configured magic is used if set.
 	async fn get_network_magic(&self) -> Result<u32, Self::Error> {
 		self.inner().get_network_magic().await.map_err(MiddlewareError::from_err)
 	}
@@ -181,7 +193,7 @@ pub trait Middleware: Sync + Send + Debug {
 	}
 
 	fn nns_resolver(&self) -> H160 {
-		H160::from(self.config().nns_resolver.clone())
+		self.config().nns_resolver
 	}
 
 	fn block_interval(&self) -> u32 {
@@ -313,6 +325,21 @@ pub trait Middleware: Sync + Send + Debug {
 			.await
 			.map_err(MiddlewareError::from_err)
 	}
+
+	/// Returns the storage entries of the contract `contract_hash` whose keys start with
+	/// `prefix`, starting at the position `start`. Requires a node with `findstorage`, see
+	/// [`Provider::find_storage_iter`] to iterate over all pages.
+	async fn find_storage(
+		&self,
+		contract_hash: H160,
+		prefix: &[u8],
+		start: u32,
+	) -> Result<FindStorage, Self::Error> {
+		self.inner()
+			.find_storage(contract_hash, prefix, start)
+			.await
+			.map_err(MiddlewareError::from_err)
+	}
 	// Blockchain methods
 
 	async fn get_transaction_height(&self, tx_hash: H256) -> Result<u32, Self::Error> {
@@ -333,6 +360,11 @@ pub trait Middleware: Sync + Send + Debug {
 		self.inner().get_committee().await.map_err(MiddlewareError::from_err)
 	}
 
+	/// The candidates for the committee with their votes
+	async fn get_candidates(&self) -> Result<Vec<Candidate>, Self::Error> {
+		self.inner().get_candidates().await.map_err(MiddlewareError::from_err)
+	}
+
 	async fn get_connection_count(&self) -> Result<u32, Self::Error> {
 		self.inner().get_connection_count().await.map_err(MiddlewareError::from_err)
 	}
@@ -346,8 +378,75 @@ pub trait Middleware: Sync + Send + Debug {
 		self.inner().get_version().await.map_err(MiddlewareError::from_err)
 	}
 
-	async fn send_raw_transaction(&self, hex: String) -> Result<RawTransaction, Self::Error> {
-		self.inner().send_raw_transaction(hex).await.map_err(MiddlewareError::from_err)
+	/// Sends a transaction, serialized and encoded in base64.
+	async fn send_raw_transaction(&self, base64: String) -> Result<RawTransaction, Self::Error> {
+		self.inner()
+			.send_raw_transaction(base64)
+			.await
+			.map_err(MiddlewareError::from_err)
+	}
+
+	/// Sends the base64 encoded transaction `base64` like [`Middleware::send_raw_transaction`],
+	/// but safe to retry after an ambiguous failure.
+	///
+	/// If sending fails with a connectivity error, like a timeout, the first attempt may still
+	/// have reached the node. Before resending, the mempool and the chain are therefore checked
+	/// for the hash of the transaction, and the transaction is only sent again if neither knows
+	/// it. A node answering that it already knows the transaction counts as a successful
+	/// submission as well. Other errors are returned as they are.
+	async fn send_raw_transaction_idempotent(
+		&self,
+		base64: String,
+	) -> Result<RawTransaction, Self::Error> {
+		/// How often a transaction is resent after connectivity errors
+		const MAX_RESENDS: u32 = 3;
+
+		let invalid = |err: String| {
+			Self::convert_err(ProviderError::IllegalState(format!("invalid transaction: {err}")))
+		};
+		let bytes = encoding::base64_decode(&base64).map_err(|err| invalid(err.to_string()))?;
+		let tx = Transaction::decode(&mut Decoder::new(&bytes))
+			.map_err(|err| invalid(err.to_string()))?;
+		let hash = tx.hash();
+
+		let mut resends = 0;
+		loop {
+			if resends > 0 && self.is_transaction_known(hash).await? {
+				tracing::debug!(?hash, "transaction reached the node before the resend");
+				return Ok(RawTransaction { hash })
+			}
+			match self.send_raw_transaction(base64.clone()).await {
+				Ok(sent) => return Ok(sent),
+				Err(err)
+					if err.as_error_response().map_or(false, JsonRpcError::is_already_known) =>
+				{
+					tracing::debug!(?hash, "transaction already known to the node");
+					return Ok(RawTransaction { hash })
+				},
+				Err(err)
+					if resends < MAX_RESENDS
+						&& err.as_provider_error().map_or(false, maybe_connectivity) =>
+				{
+					tracing::debug!(?hash, %err, "sending transaction failed ambiguously");
+					resends += 1;
+				},
+				Err(err) => return Err(err),
+			}
+		}
+	}
+
+	/// Whether the transaction `hash` is in the mempool, verified or not, or on chain.
+	async fn is_transaction_known(&self, hash: H256) -> Result<bool, Self::Error> {
+		let mempool = self.get_mem_pool().await?;
+		if mempool.verified.contains(&hash) || mempool.unverified.contains(&hash) {
+			return Ok(true)
+		}
+		match self.get_transaction_height(hash).await {
+			Ok(_) => Ok(true),
+			// the node answers with an error for unknown transactions
+			Err(err) if err.is_error_response() => Ok(false),
+			Err(err) => Err(err),
+		}
 	}
 
 	async fn submit_block(&self, hex: String) -> Result<bool, Self::Error> {
@@ -421,7 +520,7 @@ pub trait Middleware: Sync + Send + Debug {
 		self.inner().import_priv_key(priv_key).await.map_err(MiddlewareError::from_err)
 	}
 
-	async fn calculate_network_fee(&self, hex: String) -> Result<u64, Self::Error> {
+	async fn calculate_network_fee(&self, hex: String) -> Result<Gas, Self::Error> {
 		self.inner().calculate_network_fee(hex).await.map_err(MiddlewareError::from_err)
 	}
 
@@ -480,6 +579,47 @@ pub trait Middleware: Sync + Send + Debug {
 			.map_err(MiddlewareError::from_err)
 	}
 
+	/// Scans the application logs of the blocks selected by `filter` for the notifications that
+	/// pass it.
+	///
+	/// Without a block range only the latest block is scanned. Every block costs a `getblock`
+	/// request and one `getapplicationlog` request per transaction, so the range should be kept
+	/// short. The node must run the `ApplicationLogs` plugin.
+	async fn get_notifications(
+		&self,
+		filter: &NotificationFilter,
+	) -> Result<Vec<Log>, Self::Error> {
+		self.inner().get_notifications(filter).await.map_err(MiddlewareError::from_err)
+	}
+
+	/// Subscribes to the node events selected by `params` over a pubsub transport, decoding the
+	/// events into `R`.
+	async fn subscribe<T, R>(
+		&self,
+		params: T,
+	) -> Result<SubscriptionStream<'_, Self::Provider, R>, Self::Error>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned + Send + Sync,
+		<Self as Middleware>::Provider: PubsubClient,
+	{
+		self.inner().subscribe(params).await.map_err(MiddlewareError::from_err)
+	}
+
+	/// Streams the application logs of the transactions executed from now on that pass `filter`.
+	async fn subscribe_application_logs(
+		&self,
+		filter: &ApplicationLogFilter,
+	) -> Result<SubscriptionStream<'_, Self::Provider, ApplicationLog>, Self::Error>
+	where
+		<Self as Middleware>::Provider: PubsubClient,
+	{
+		self.inner()
+			.subscribe_application_logs(filter)
+			.await
+			.map_err(MiddlewareError::from_err)
+	}
+
 	async fn get_nep17_balances(&self, script_hash: H160) -> Result<Nep17Balances, Self::Error> {
 		self.inner()
 			.get_nep17_balances(script_hash)
@@ -0,0 +1,542 @@
+//! A harness running a local [neo-express](https://github.com/neo-project/neo-express) private
+//! network for end-to-end tests, the Neo counterpart of Anvil.
+//!
+//! The `neoxp` tool must be installed, e.g. with `dotnet tool install -g Neo.Express`, or its
+//! location passed to [`NeoExpress::program`].
+//!
+//! ```no_run
+//! use neo_providers::{neo_express::NeoExpress, Middleware};
+//! use neo_types::script_hash::{ScriptHash, ScriptHashExt};
+//!
+//! # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! let node = NeoExpress::new().seconds_per_block(1).spawn().await?;
+//! let alice = node.create_wallet("alice").await?;
+//! node.fund("alice", "GAS", 100).await?;
+//!
+//! let provider = node.provider();
+//! let balances = provider.get_nep17_balances(ScriptHash::from_address(&alice)?).await?;
+//! # Ok(()) }
+//! ```
+//!
+//! Checkpoints reset the chain state between test cases without creating a new network:
+//!
+//! ```no_run
+//! use neo_providers::neo_express::NeoExpress;
+//!
+//! # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! let node = NeoExpress::new().spawn().await?;
+//! // deploy contracts ...
+//! let deployed = node.checkpoint("deployed").await?;
+//!
+//! for case in 0..3 {
+//! 	node.restore(&deployed).await?;
+//! 	// run the test case against a freshly deployed contract
+//! }
+//! # Ok(()) }
+//! ```
+use crate::{ChainController, Http, Middleware, Provider, ProviderError};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::{
+	convert::TryFrom,
+	ffi::OsStr,
+	fs,
+	net::TcpListener,
+	path::{Path, PathBuf},
+	process::Stdio,
+	sync::atomic::{AtomicUsize, Ordering},
+	time::{Duration, Instant},
+};
+use thiserror::Error;
+use tokio::{
+	process::{Child, Command},
+	sync::Mutex,
+};
+use tracing::{debug, trace};
+
+/// The RPC port `neoxp create` assigns to the first consensus node. Networks created by
+/// [`NeoExpress::spawn`] are moved to free ports instead, so that instances can run in parallel.
+pub const DEFAULT_RPC_PORT: u16 = 50012;
+
+/// How long [`NeoExpress::spawn`] waits for the RPC server by default
+const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The interval between two readiness checks of the RPC server
+const READINESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Builder spawning a neo-express private network.
+///
+/// Without a [`NeoExpress::config`], a single node network listening on free ports is created in a
+/// temporary directory, which is removed once the [`NeoExpressInstance`] is dropped.
+#[derive(Clone, Debug, Default)]
+pub struct NeoExpress {
+	program: Option<PathBuf>,
+	config: Option<PathBuf>,
+	seconds_per_block: Option<u32>,
+	startup_timeout: Option<Duration>,
+	args: Vec<String>,
+}
+
+impl NeoExpress {
+	/// Creates a builder spawning `neoxp` from the `PATH`.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the path of the `neoxp` executable.
+	#[must_use]
+	pub fn program(mut self, program: impl Into<PathBuf>) -> Self {
+		self.program = Some(program.into());
+		self
+	}
+
+	/// Runs the network described by the existing `.neo-express` file at `config` instead of a
+	/// new one.
+	#[must_use]
+	pub fn config(mut self, config: impl Into<PathBuf>) -> Self {
+		self.config = Some(config.into());
+		self
+	}
+
+	/// Sets the time between two blocks.
+	#[must_use]
+	pub fn seconds_per_block(mut self, seconds: u32) -> Self {
+		self.seconds_per_block = Some(seconds);
+		self
+	}
+
+	/// Sets how long to wait for the RPC server to answer before giving up.
+	#[must_use]
+	pub fn startup_timeout(mut self, timeout: Duration) -> Self {
+		self.startup_timeout = Some(timeout);
+		self
+	}
+
+	/// Passes an additional argument to `neoxp run`.
+	#[must_use]
+	pub fn arg(mut self, arg: impl Into<String>) -> Self {
+		self.args.push(arg.into());
+		self
+	}
+
+	/// Starts the network and waits until its RPC server answers.
+	pub async fn spawn(self) -> Result<NeoExpressInstance, NeoExpressError> {
+		let program = self.program.unwrap_or_else(|| PathBuf::from("neoxp"));
+		let (config, temp_dir) = match self.config {
+			Some(config) => (config, None),
+			None => {
+				let dir = temp_dir();
+				fs::create_dir_all(&dir)?;
+				let config = dir.join("default.neo-express");
+				run(&program, &["create", "--force", "--output"], &[config.as_os_str()]).await?;
+				assign_free_ports(&config)?;
+				(config, Some(dir))
+			},
+		};
+		let port = rpc_port(&config)?;
+
+		let mut run_args = Vec::new();
+		if let Some(seconds) = self.seconds_per_block {
+			run_args.extend(["--seconds-per-block".to_string(), seconds.to_string()]);
+		}
+		run_args.extend(self.args);
+		let child = start(&program, &config, &run_args)?;
+		debug!(?config, port, "spawned neo-express");
+
+		let instance = NeoExpressInstance {
+			child: Mutex::new(child),
+			program,
+			config,
+			temp_dir,
+			port,
+			run_args,
+			startup_timeout: self.startup_timeout.unwrap_or(DEFAULT_STARTUP_TIMEOUT),
+			next_block_time: Mutex::new(None),
+		};
+		instance.wait_ready().await?;
+		Ok(instance)
+	}
+}
+
+/// A snapshot of the chain state of a [`NeoExpressInstance`], see
+/// [`NeoExpressInstance::checkpoint`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+	path: PathBuf,
+}
+
+impl Checkpoint {
+	/// Refers to the checkpoint file at `path`, e.g. one created with `neoxp checkpoint create`.
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	/// The path of the checkpoint file
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+}
+
+/// A running neo-express network, killed when dropped.
+#[derive(Debug)]
+pub struct NeoExpressInstance {
+	child: Mutex<Child>,
+	program: PathBuf,
+	config: PathBuf,
+	temp_dir: Option<PathBuf>,
+	port: u16,
+	run_args: Vec<String>,
+	startup_timeout: Duration,
+	/// Timestamp of the next block, see [`ChainController::set_next_block_time`]
+	next_block_time: Mutex<Option<u64>>,
+}
+
+impl NeoExpressInstance {
+	/// The RPC port of the node
+	pub fn port(&self) -> u16 {
+		self.port
+	}
+
+	/// The HTTP endpoint of the node
+	pub fn endpoint(&self) -> String {
+		format!("http://localhost:{}", self.port)
+	}
+
+	/// The `.neo-express` file describing the network
+	pub fn config(&self) -> &Path {
+		&self.config
+	}
+
+	/// Returns a provider connected to the node.
+	pub fn provider(&self) -> Provider<Http> {
+		Provider::<Http>::try_from(self.endpoint())
+			.expect("endpoint is a valid url")
+			.interval(Duration::from_millis(100))
+	}
+
+	/// Creates a wallet named `name` and returns the address of its account.
+	pub async fn create_wallet(&self, name: &str) -> Result<String, NeoExpressError> {
+		self.neoxp(&["wallet", "create", "--force", name]).await?;
+		self.wallet_address(name)?
+			.ok_or_else(|| NeoExpressError::Config(format!("wallet {name} was not created")))
+	}
+
+	/// The address of the default account of the wallet `name`, as stored in the config.
+	pub fn wallet_address(&self, name: &str) -> Result<Option<String>, NeoExpressError> {
+		let config = read_config(&self.config)?;
+		let mut wallets = config["wallets"].as_array().into_iter().flatten();
+		let wallet = wallets.find(|wallet| wallet["name"] == name);
+		Ok(wallet
+			.and_then(|wallet| wallet["accounts"].get(0))
+			.and_then(|account| account["script-hash"].as_str())
+			.map(str::to_string))
+	}
+
+	/// Transfers `amount` of `asset`, e.g. `GAS` or `NEO`, from the genesis account to
+	/// `receiver`, a wallet name or an address.
+	pub async fn fund(
+		&self,
+		receiver: &str,
+		asset: &str,
+		amount: u64,
+	) -> Result<(), NeoExpressError> {
+		self.neoxp(&["transfer", &amount.to_string(), asset, "genesis", receiver]).await
+	}
+
+	/// Runs the `neoxp` command `args` against this network.
+	pub async fn neoxp(&self, args: &[&str]) -> Result<(), NeoExpressError> {
+		run(&self.program, args, &["--input".as_ref(), self.config.as_os_str()]).await
+	}
+
+	/// Saves the current chain state in a checkpoint named `name`, next to the config of the
+	/// network. An existing checkpoint with the same name is replaced.
+	pub async fn checkpoint(&self, name: &str) -> Result<Checkpoint, NeoExpressError> {
+		let dir = self.config.parent().unwrap_or_else(|| Path::new("."));
+		let path = dir.join(format!("{name}.neoxp-checkpoint"));
+		if path.exists() {
+			fs::remove_file(&path)?;
+		}
+		let params = vec![Value::from(path.to_string_lossy())];
+		let created: String = self.provider().request("expresscreatecheckpoint", params).await?;
+		Ok(Checkpoint::new(created))
+	}
+
+	/// Restarts the network from `checkpoint`, discarding every block produced since.
+	pub async fn restore(&self, checkpoint: &Checkpoint) -> Result<(), NeoExpressError> {
+		let path = checkpoint.path.to_string_lossy();
+		self.restart(&["checkpoint", "restore", "--force", &path]).await
+	}
+
+	/// Restarts the network from its genesis block.
+	pub async fn reset_to_genesis(&self) -> Result<(), NeoExpressError> {
+		self.restart(&["reset", "--force"]).await
+	}
+
+	/// The timestamp of the latest block
+	async fn current_time(&self) -> Result<u64, NeoExpressError> {
+		let provider = self.provider();
+		let index = provider.get_block_count().await?.saturating_sub(1);
+		Ok(provider.get_block_by_index(index, false).await?.time)
+	}
+
+	/// Mints `count` empty blocks with `expressfastforward`, the first one `delta_millis` after
+	/// the current block.
+	async fn fast_forward(&self, count: u32, delta_millis: u64) -> Result<(), NeoExpressError> {
+		let params = vec![Value::from(count), Value::from(format_time_span(delta_millis))];
+		self.provider().request::<_, Value>("expressfastforward", params).await?;
+		Ok(())
+	}
+
+	/// Stops the node, runs the `neoxp` command `args`, which requires a stopped node, and
+	/// starts the node again.
+	async fn restart(&self, args: &[&str]) -> Result<(), NeoExpressError> {
+		*self.next_block_time.lock().await = None;
+		{
+			let mut child = self.child.lock().await;
+			// fails if the node exited already
+			let _ = child.kill().await;
+			self.neoxp(args).await?;
+			*child = start(&self.program, &self.config, &self.run_args)?;
+		}
+		self.wait_ready().await
+	}
+
+	async fn wait_ready(&self) -> Result<(), NeoExpressError> {
+		let provider = self.provider();
+		let start = Instant::now();
+		loop {
+			match provider.get_version().await {
+				Ok(_) => return Ok(()),
+				Err(err) if start.elapsed() < self.startup_timeout => {
+					trace!(err = ?err, "waiting for neo-express");
+					tokio::time::sleep(READINESS_INTERVAL).await;
+				},
+				Err(_) => return Err(NeoExpressError::Timeout(self.startup_timeout)),
+			}
+		}
+	}
+}
+
+/// neo-express can only move its clock by minting blocks, so the time set with
+/// [`ChainController::set_next_block_time`] is kept by the instance and applied to the first block
+/// minted by [`ChainController::advance_blocks`]. [`ChainController::reset`] restarts the node from
+/// the checkpoint.
+#[async_trait]
+impl ChainController for NeoExpressInstance {
+	type Error = NeoExpressError;
+
+	async fn advance_blocks(&self, count: u32) -> Result<(), NeoExpressError> {
+		if count == 0 {
+			return Ok(())
+		}
+		let mut next_block_time = self.next_block_time.lock().await;
+		match *next_block_time {
+			Some(timestamp) => {
+				let delta = timestamp.saturating_sub(self.current_time().await?);
+				self.fast_forward(1, delta).await?;
+				*next_block_time = None;
+				if count > 1 {
+					self.fast_forward(count - 1, 0).await?;
+				}
+			},
+			None => self.fast_forward(count, 0).await?,
+		}
+		Ok(())
+	}
+
+	async fn set_next_block_time(&self, timestamp: u64) -> Result<(), NeoExpressError> {
+		let current = self.current_time().await?;
+		if timestamp <= current {
+			return Err(NeoExpressError::InvalidBlockTime { timestamp, current })
+		}
+		*self.next_block_time.lock().await = Some(timestamp);
+		Ok(())
+	}
+
+	async fn create_checkpoint(&self, name: &str) -> Result<String, NeoExpressError> {
+		let checkpoint = self.checkpoint(name).await?;
+		Ok(checkpoint.path.to_string_lossy().into_owned())
+	}
+
+	async fn reset(&self, checkpoint: Option<&str>) -> Result<(), NeoExpressError> {
+		match checkpoint {
+			Some(path) => self.restore(&Checkpoint::new(path)).await,
+			None => self.reset_to_genesis().await,
+		}
+	}
+}
+
+impl Drop for NeoExpressInstance {
+	fn drop(&mut self) {
+		let _ = self.child.get_mut().start_kill();
+		if let Some(dir) = &self.temp_dir {
+			let _ = fs::remove_dir_all(dir);
+		}
+	}
+}
+
+/// Errors of the [`NeoExpress`] harness
+#[derive(Debug, Error)]
+pub enum NeoExpressError {
+	/// `neoxp` could not be started
+	#[error("could not spawn neoxp: {0}")]
+	Spawn(std::io::Error),
+	/// A `neoxp` command failed
+	#[error("neoxp {command} failed: {stderr}")]
+	Command {
+		/// The failed command
+		command: String,
+		/// The error output of the command
+		stderr: String,
+	},
+	/// The `.neo-express` file could not be read
+	#[error("invalid neo-express config: {0}")]
+	Config(String),
+	/// The RPC server did not answer in time
+	#[error("neo-express did not start within {0:?}")]
+	Timeout(Duration),
+	/// The requested block time is not later than the current block time
+	#[error("block time {timestamp} must be later than the current block time {current}")]
+	InvalidBlockTime {
+		/// The requested block time
+		timestamp: u64,
+		/// The time of the current block
+		current: u64,
+	},
+	/// An RPC request to the node failed
+	#[error(transparent)]
+	Provider(#[from] ProviderError),
+	/// IO error
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
+}
+
+async fn run(program: &Path, args: &[&str], extra: &[&OsStr]) -> Result<(), NeoExpressError> {
+	let output = Command::new(program)
+		.args(args)
+		.args(extra)
+		.stdin(Stdio::null())
+		.output()
+		.await
+		.map_err(NeoExpressError::Spawn)?;
+	if !output.status.success() {
+		return Err(NeoExpressError::Command {
+			command: args.join(" "),
+			stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+		})
+	}
+	Ok(())
+}
+
+/// Starts the node of the network at `config`.
+fn start(program: &Path, config: &Path, run_args: &[String]) -> Result<Child, NeoExpressError> {
+	Command::new(program)
+		.arg("run")
+		.arg("--input")
+		.arg(config)
+		.args(run_args)
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.kill_on_drop(true)
+		.spawn()
+		.map_err(NeoExpressError::Spawn)
+}
+
+fn read_config(path: &Path) -> Result<Value, NeoExpressError> {
+	let config = fs::read_to_string(path)?;
+	serde_json::from_str(&config).map_err(|err| NeoExpressError::Config(err.to_string()))
+}
+
+/// Formats a duration in the .NET `TimeSpan` format (`d.hh:mm:ss.fff`) expected by neo-express.
+fn format_time_span(millis: u64) -> String {
+	let (secs, millis) = (millis / 1000, millis % 1000);
+	let (mins, secs) = (secs / 60, secs % 60);
+	let (hours, mins) = (mins / 60, mins % 60);
+	let (days, hours) = (hours / 24, hours % 24);
+	format!("{days}.{hours:02}:{mins:02}:{secs:02}.{millis:03}")
+}
+
+/// The RPC port of the first consensus node in the config at `path`
+fn rpc_port(path: &Path) -> Result<u16, NeoExpressError> {
+	let config = read_config(path)?;
+	Ok(config["consensus-nodes"]
+		.get(0)
+		.and_then(|node| node["rpc-port"].as_u64())
+		.and_then(|port| u16::try_from(port).ok())
+		.unwrap_or(DEFAULT_RPC_PORT))
+}
+
+/// Moves the first consensus node of the new network at `path` from the fixed ports of
+/// `neoxp create` to free ones.
+fn assign_free_ports(path: &Path) -> Result<(), NeoExpressError> {
+	let mut config = read_config(path)?;
+	let node = config["consensus-nodes"]
+		.get_mut(0)
+		.ok_or_else(|| NeoExpressError::Config("the network has no consensus node".to_string()))?;
+	// both listeners are held until the ports are read, so they differ
+	let listeners = [TcpListener::bind(("127.0.0.1", 0))?, TcpListener::bind(("127.0.0.1", 0))?];
+	node["tcp-port"] = listeners[0].local_addr()?.port().into();
+	node["rpc-port"] = listeners[1].local_addr()?.port().into();
+	let config = serde_json::to_string_pretty(&config)
+		.map_err(|err| NeoExpressError::Config(err.to_string()))?;
+	fs::write(path, config)?;
+	Ok(())
+}
+
+/// A directory no other instance uses
+fn temp_dir() -> PathBuf {
+	static COUNTER: AtomicUsize = AtomicUsize::new(0);
+	let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+	std::env::temp_dir().join(format!("neo-express-{}-{id}", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn formats_time_spans() {
+		assert_eq!(format_time_span(0), "0.00:00:00.000");
+		assert_eq!(format_time_span(15_000), "0.00:00:15.000");
+		assert_eq!(format_time_span(90_061_001), "1.01:01:01.001");
+	}
+
+	#[test]
+	fn reads_rpc_port() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("default.neo-express");
+		fs::write(
+			&path,
+			r#"{"magic": 1, "consensus-nodes": [{"tcp-port": 50013, "rpc-port": 50112}]}"#,
+		)
+		.unwrap();
+		assert_eq!(rpc_port(&path).unwrap(), 50112);
+
+		fs::write(&path, r#"{"magic": 1, "consensus-nodes": []}"#).unwrap();
+		assert_eq!(rpc_port(&path).unwrap(), DEFAULT_RPC_PORT);
+	}
+
+	#[test]
+	fn assigns_free_ports() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("default.neo-express");
+		fs::write(
+			&path,
+			r#"{"magic": 1, "consensus-nodes": [{"tcp-port": 50013, "rpc-port": 50012, "wallet": {}}]}"#,
+		)
+		.unwrap();
+		assign_free_ports(&path).unwrap();
+
+		let config = read_config(&path).unwrap();
+		let node = &config["consensus-nodes"][0];
+		let tcp_port = node["tcp-port"].as_u64().unwrap();
+		let port = rpc_port(&path).unwrap();
+		assert_ne!(u64::from(port), tcp_port);
+		assert_ne!(port, DEFAULT_RPC_PORT);
+		assert_eq!(config["magic"], 1);
+		assert!(node["wallet"].is_object());
+
+		fs::write(&path, r#"{"magic": 1, "consensus-nodes": []}"#).unwrap();
+		assert!(matches!(assign_free_ports(&path), Err(NeoExpressError::Config(_))));
+	}
+}
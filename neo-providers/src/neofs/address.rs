@@ -0,0 +1,202 @@
+use neo_types::{contract_parameter::ContractParameter, encoding};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// An error of the NeoFS helpers
+#[derive(Error, Debug)]
+pub enum NeoFsError {
+	/// A container or object ID is not the Base58 encoding of 32 bytes
+	#[error("Invalid NeoFS ID {0}")]
+	InvalidId(String),
+	/// An address is not of the form `<container>/<object>`, optionally prefixed by `neofs:`
+	#[error("Invalid NeoFS address {0}")]
+	InvalidAddress(String),
+	/// The request to the gateway failed
+	#[error(transparent)]
+	Http(#[from] reqwest::Error),
+	/// The gateway answered with an error status
+	#[error("NeoFS gateway error {status}: {message}")]
+	Gateway {
+		/// The HTTP status code
+		status: u16,
+		/// The body of the response
+		message: String,
+	},
+}
+
+macro_rules! neofs_id {
+	($(#[$meta:meta])* $name:ident) => {
+		$(#[$meta])*
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+		pub struct $name([u8; 32]);
+
+		impl $name {
+			/// Wraps the 32 bytes of the ID.
+			pub const fn new(bytes: [u8; 32]) -> Self {
+				Self(bytes)
+			}
+
+			/// The 32 bytes of the ID
+			pub fn as_bytes(&self) -> &[u8; 32] {
+				&self.0
+			}
+		}
+
+		impl fmt::Display for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str(&encoding::base58_encode(&self.0))
+			}
+		}
+
+		impl FromStr for $name {
+			type Err = NeoFsError;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				encoding::base58_decode(s)
+					.ok()
+					.and_then(|bytes| bytes.try_into().ok())
+					.map(Self)
+					.ok_or_else(|| NeoFsError::InvalidId(s.to_string()))
+			}
+		}
+
+		impl TryFrom<&[u8]> for $name {
+			type Error = NeoFsError;
+
+			fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+				bytes
+					.try_into()
+					.map(Self)
+					.map_err(|_| NeoFsError::InvalidId(hex::encode(bytes)))
+			}
+		}
+
+		impl Serialize for $name {
+			fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_str(&self.to_string())
+			}
+		}
+
+		impl<'de> Deserialize<'de> for $name {
+			fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+			}
+		}
+	};
+}
+
+neofs_id!(
+	/// The ID of a NeoFS container, the SHA-256 of the container, written in Base58.
+	ContainerId
+);
+
+neofs_id!(
+	/// The ID of a NeoFS object, the SHA-256 of its header, written in Base58.
+	ObjectId
+);
+
+/// The address of an object: its container and its ID.
+///
+/// Written as `<container>/<object>`, or as the URL `neofs:<container>/<object>` understood by the
+/// oracle service. Parsing accepts both forms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ObjectAddress {
+	/// The container holding the object
+	pub container: ContainerId,
+	/// The ID of the object in the container
+	pub object: ObjectId,
+}
+
+impl ObjectAddress {
+	/// The scheme of NeoFS URLs
+	pub const SCHEME: &'static str = "neofs";
+
+	/// The address of `object` in `container`.
+	pub fn new(container: ContainerId, object: ObjectId) -> Self {
+		Self { container, object }
+	}
+
+	/// The `neofs:` URL of the object, e.g. for an oracle request.
+	pub fn to_url(&self) -> String {
+		format!("{}:{self}", Self::SCHEME)
+	}
+
+	/// The container ID followed by the object ID, the binary form kept by some contracts.
+	pub fn to_bytes(&self) -> [u8; 64] {
+		let mut bytes = [0; 64];
+		bytes[..32].copy_from_slice(self.container.as_bytes());
+		bytes[32..].copy_from_slice(self.object.as_bytes());
+		bytes
+	}
+
+	/// Reads the binary form written by [`ObjectAddress::to_bytes`].
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, NeoFsError> {
+		if bytes.len() != 64 {
+			return Err(NeoFsError::InvalidAddress(hex::encode(bytes)))
+		}
+		Ok(Self::new(bytes[..32].try_into()?, bytes[32..].try_into()?))
+	}
+}
+
+impl fmt::Display for ObjectAddress {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.container, self.object)
+	}
+}
+
+impl FromStr for ObjectAddress {
+	type Err = NeoFsError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let address = s
+			.strip_prefix(Self::SCHEME)
+			.and_then(|rest| rest.strip_prefix(':'))
+			.unwrap_or(s);
+		let (container, object) = address
+			.split_once('/')
+			.ok_or_else(|| NeoFsError::InvalidAddress(s.to_string()))?;
+		Ok(Self::new(container.parse()?, object.parse()?))
+	}
+}
+
+/// Passes the address to a contract as its `neofs:` URL.
+impl From<ObjectAddress> for ContractParameter {
+	fn from(address: ObjectAddress) -> Self {
+		address.to_url().into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_ids() {
+		let id = ContainerId::new([0; 32]);
+		assert_eq!(id.to_string(), "1".repeat(32));
+		assert_eq!("1".repeat(32).parse::<ContainerId>().unwrap(), id);
+
+		let id = ObjectId::new([0xab; 32]);
+		assert_eq!(id.to_string().parse::<ObjectId>().unwrap(), id);
+		assert_eq!(serde_json::to_string(&id).unwrap(), format!("\"{id}\""));
+
+		// not 32 bytes, and not Base58
+		assert!("1111".parse::<ObjectId>().is_err());
+		assert!("0OIl".parse::<ObjectId>().is_err());
+	}
+
+	#[test]
+	fn test_address() {
+		let address = ObjectAddress::new(ContainerId::new([1; 32]), ObjectId::new([2; 32]));
+		let text = format!("{}/{}", address.container, address.object);
+		assert_eq!(address.to_string(), text);
+		assert_eq!(address.to_url(), format!("neofs:{text}"));
+		assert_eq!(text.parse::<ObjectAddress>().unwrap(), address);
+		assert_eq!(address.to_url().parse::<ObjectAddress>().unwrap(), address);
+		assert_eq!(ObjectAddress::from_bytes(&address.to_bytes()).unwrap(), address);
+
+		assert!(address.container.to_string().parse::<ObjectAddress>().is_err());
+		assert!(ObjectAddress::from_bytes(&[0; 32]).is_err());
+	}
+}
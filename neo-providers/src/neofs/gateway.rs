@@ -0,0 +1,123 @@
+use super::{ContainerId, NeoFsError, ObjectAddress, ObjectId};
+use neo_types::Bytes;
+use reqwest::{multipart, Client, Response};
+use serde::Deserialize;
+use url::Url;
+
+/// A minimal client of a NeoFS HTTP gateway, putting and getting the payload of objects.
+///
+/// The gateway signs the objects it stores with its own key, so the container must allow the
+/// gateway to put objects, e.g. a public container.
+#[derive(Debug, Clone)]
+pub struct GatewayClient {
+	client: Client,
+	url: Url,
+}
+
+/// The response of the gateway to an upload
+#[derive(Deserialize)]
+struct UploadResponse {
+	object_id: ObjectId,
+	container_id: ContainerId,
+}
+
+impl GatewayClient {
+	/// A client of the gateway at `url`
+	pub fn new(url: Url) -> Self {
+		Self::with_client(Client::new(), url)
+	}
+
+	/// A client of the gateway at `url` sending its requests with `client`
+	pub fn with_client(client: Client, url: Url) -> Self {
+		Self { client, url }
+	}
+
+	/// The URL the gateway serves the payload of the object at `address` at.
+	pub fn object_url(&self, address: &ObjectAddress) -> Url {
+		self.endpoint(&["get", &address.container.to_string(), &address.object.to_string()])
+	}
+
+	/// Gets the payload of the object at `address`.
+	pub async fn get(&self, address: &ObjectAddress) -> Result<Bytes, NeoFsError> {
+		let response = self.client.get(self.object_url(address)).send().await?;
+		Ok(Self::check(response).await?.bytes().await?.to_vec())
+	}
+
+	/// Gets the payload of the object in `container` whose attribute `key` is `value`, e.g. the
+	/// `FileName` of an uploaded file.
+	pub async fn get_by_attribute(
+		&self,
+		container: &ContainerId,
+		key: &str,
+		value: &str,
+	) -> Result<Bytes, NeoFsError> {
+		let url = self.endpoint(&["get_by_attribute", &container.to_string(), key, value]);
+		let response = self.client.get(url).send().await?;
+		Ok(Self::check(response).await?.bytes().await?.to_vec())
+	}
+
+	/// Puts an object with the payload `data` and `attributes` into `container`, returning its
+	/// address.
+	pub async fn put(
+		&self,
+		container: &ContainerId,
+		data: Vec<u8>,
+		attributes: &[(&str, &str)],
+	) -> Result<ObjectAddress, NeoFsError> {
+		let url = self.endpoint(&["upload", &container.to_string()]);
+		let form = multipart::Form::new().part("file", multipart::Part::bytes(data));
+		let request = attributes
+			.iter()
+			.fold(self.client.post(url).multipart(form), |request, (key, value)| {
+				request.header(format!("X-Attribute-{key}"), *value)
+			});
+		let response = Self::check(request.send().await?).await?;
+		let uploaded: UploadResponse = response.json().await?;
+		Ok(ObjectAddress::new(uploaded.container_id, uploaded.object_id))
+	}
+
+	/// The URL of the gateway followed by the path `segments`, each of them escaped
+	fn endpoint(&self, segments: &[&str]) -> Url {
+		let mut url = self.url.clone();
+		if let Ok(mut path) = url.path_segments_mut() {
+			path.pop_if_empty().extend(segments);
+		}
+		url
+	}
+
+	/// Fails with the body of the response if the gateway answered with an error status
+	async fn check(response: Response) -> Result<Response, NeoFsError> {
+		let status = response.status();
+		if status.is_success() {
+			return Ok(response)
+		}
+		Err(NeoFsError::Gateway {
+			status: status.as_u16(),
+			message: response.text().await.unwrap_or_default(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_object_url() {
+		let address = ObjectAddress::new(ContainerId::new([1; 32]), ObjectId::new([2; 32]));
+		for base in ["https://gateway.example/", "https://gateway.example"] {
+			let gateway = GatewayClient::new(base.parse().unwrap());
+			assert_eq!(
+				gateway.object_url(&address).as_str(),
+				format!("https://gateway.example/get/{}/{}", address.container, address.object)
+			);
+		}
+
+		let gateway = GatewayClient::new("https://example.com/neofs/".parse().unwrap());
+		let url = gateway.endpoint(&["get_by_attribute", "cid", "FileName", "a b/c"]);
+		assert_eq!(
+			url.as_str(),
+			"https://example.com/neofs/get_by_attribute/cid/FileName/a%20b%2Fc"
+		);
+	}
+}
@@ -0,0 +1,27 @@
+//! Helpers for payloads stored on NeoFS, the distributed object storage of the Neo ecosystem.
+//!
+//! Objects are stored in containers and addressed by the ID of the container and the ID of the
+//! object, see [`ObjectAddress`]. Contracts usually keep such an address as a `neofs:` URL, which
+//! the oracle service can fetch as well. [`GatewayClient`] puts and gets objects through a NeoFS
+//! HTTP gateway.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use neo_providers::neofs::{ContainerId, GatewayClient, ObjectAddress};
+//!
+//! # async fn foo(container: ContainerId) -> Result<(), Box<dyn std::error::Error>> {
+//! let gateway = GatewayClient::new("https://http.fs.neo.org".parse()?);
+//! let address = gateway.put(&container, b"payload".to_vec(), &[("FileName", "payload.txt")]).await?;
+//!
+//! // stored by a contract and read back later
+//! let url = address.to_url();
+//! let payload = gateway.get(&url.parse::<ObjectAddress>()?).await?;
+//! # Ok(()) }
+//! ```
+
+mod address;
+mod gateway;
+
+pub use address::{ContainerId, NeoFsError, ObjectAddress, ObjectId};
+pub use gateway::GatewayClient;
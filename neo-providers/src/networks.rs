@@ -0,0 +1,390 @@
+//! Named networks: the RPC endpoints, magic number and address version of each network an
+//! application connects to, looked up by name.
+//!
+//! [`Networks::builtin`] knows `mainnet`, `testnet` and `privnet`, the single node network created
+//! by neo-express. More profiles are read from TOML or JSON files mapping names to profiles:
+//!
+//! ```toml
+//! [staging]
+//! endpoints = ["https://staging.example:10332"]
+//! magic = 1234567890
+//! address_version = 53
+//! ms_per_block = 5000
+//! ```
+//!
+//! A name of the form `<name>:<file>`, e.g. `privnet:networks.toml`, selects the profile `name` of
+//! the file `file`.
+//!
+//! ```no_run
+//! use neo_providers::Provider;
+//!
+//! # fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! let testnet = Provider::for_named_network("testnet")?;
+//! let staging = Provider::for_named_network("staging:networks.toml")?;
+//! # Ok(()) }
+//! ```
+//!
+//! [`Networks::from_env`] adds the profiles of the file named by [`NETWORKS_VAR`] to the built-in
+//! ones, then applies the overrides `NEO_<NAME>_ENDPOINTS`, `NEO_<NAME>_MAGIC` and
+//! `NEO_<NAME>_ADDRESS_VERSION`, where `<NAME>` is the upper case name of the network, e.g.
+//! `NEO_TESTNET_ENDPOINTS=http://localhost:20332`. Endpoints are separated by commas.
+use crate::{environment::MAINNET_ENDPOINT, Http, Provider, ProviderExt};
+use neo_config::{NeoConfig, NeoNetwork, DEFAULT_ADDRESS_VERSION};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::Path};
+use thiserror::Error;
+use url::Url;
+
+/// The profile file read by [`Networks::from_env`]
+pub const NETWORKS_VAR: &str = "NEO_NETWORKS";
+
+/// The public RPC endpoints of the N3 MainNet run by COZ
+pub const MAINNET_ENDPOINTS: &[&str] = &[MAINNET_ENDPOINT, "https://mainnet2.neo.coz.io:443"];
+/// The public RPC endpoints of the N3 TestNet run by COZ
+pub const TESTNET_ENDPOINTS: &[&str] =
+	&["https://testnet1.neo.coz.io:443", "https://testnet2.neo.coz.io:443"];
+/// The RPC endpoint of a network created by `neoxp create`
+pub const PRIVNET_ENDPOINT: &str = "http://localhost:50012";
+
+/// An error loading or resolving [`Networks`]
+#[derive(Debug, Error)]
+pub enum NetworksError {
+	/// No profile has the name
+	#[error("unknown network {0}")]
+	UnknownNetwork(String),
+	/// The profile has no endpoint
+	#[error("the network {0} has no endpoint")]
+	NoEndpoint(String),
+	/// A profile file could not be read
+	#[error(transparent)]
+	Io(#[from] io::Error),
+	/// A TOML profile file is invalid
+	#[error(transparent)]
+	Toml(#[from] toml::de::Error),
+	/// A JSON profile file is invalid
+	#[error(transparent)]
+	Json(#[from] serde_json::Error),
+	/// An environment variable is set to an invalid value
+	#[error("the environment variable {var} is invalid: {reason}")]
+	Invalid {
+		/// The name of the variable
+		var: String,
+		/// Why the value is invalid
+		reason: String,
+	},
+}
+
+/// How to reach a network and the settings of its protocol
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkProfile {
+	/// The URLs of the RPC servers. Providers are created for the first one.
+	pub endpoints: Vec<Url>,
+	/// The magic number of the network. Without it the provider asks the node.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub magic: Option<u32>,
+	/// The version byte of addresses
+	#[serde(default = "default_address_version")]
+	pub address_version: u8,
+	/// The block time in milliseconds, if it differs from the default
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub ms_per_block: Option<u32>,
+}
+
+fn default_address_version() -> u8 {
+	DEFAULT_ADDRESS_VERSION
+}
+
+impl NetworkProfile {
+	/// A profile for the node at `endpoint`, whose magic number is asked from the node.
+	pub fn new(endpoint: Url) -> Self {
+		Self {
+			endpoints: vec![endpoint],
+			magic: None,
+			address_version: DEFAULT_ADDRESS_VERSION,
+			ms_per_block: None,
+		}
+	}
+
+	/// The config of a client of the network
+	pub fn config(&self) -> NeoConfig {
+		let mut builder = NeoConfig::builder().address_version(self.address_version);
+		if let Some(magic) = self.magic {
+			builder = builder.network_magic(magic);
+		}
+		if let Some(ms_per_block) = self.ms_per_block {
+			builder = builder.ms_per_block(ms_per_block);
+		}
+		builder.build()
+	}
+
+	/// Creates an HTTP provider for the first endpoint, configured for the network.
+	pub fn provider(&self) -> Option<Provider<Http>> {
+		let endpoint = self.endpoints.first()?;
+		let mut provider = Provider::with_config(Http::new(endpoint.clone()), self.config());
+		if let Some(magic) = self.magic {
+			provider.set_network(magic);
+		}
+		Some(provider)
+	}
+
+	fn builtin(endpoints: &[&str], magic: Option<u32>) -> Self {
+		Self {
+			endpoints: endpoints
+				.iter()
+				.map(|endpoint| Url::parse(endpoint).expect("valid builtin endpoint"))
+				.collect(),
+			magic,
+			address_version: DEFAULT_ADDRESS_VERSION,
+			ms_per_block: None,
+		}
+	}
+}
+
+/// Network profiles by name, see the [module docs](self).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Networks {
+	profiles: BTreeMap<String, NetworkProfile>,
+}
+
+impl Networks {
+	/// The profiles of `mainnet`, `testnet` and `privnet`.
+	pub fn builtin() -> Self {
+		let mut networks = Self::default();
+		networks.insert(
+			"mainnet",
+			NetworkProfile::builtin(MAINNET_ENDPOINTS, Some(NeoNetwork::MainNet.to_magic())),
+		);
+		networks.insert(
+			"testnet",
+			NetworkProfile::builtin(TESTNET_ENDPOINTS, Some(NeoNetwork::TestNet.to_magic())),
+		);
+		// every neo-express network has a random magic number
+		networks.insert("privnet", NetworkProfile::builtin(&[PRIVNET_ENDPOINT], None));
+		networks
+	}
+
+	/// Reads the profiles of a TOML document.
+	pub fn from_toml(toml: &str) -> Result<Self, NetworksError> {
+		Ok(toml::from_str(toml)?)
+	}
+
+	/// Reads the profiles of a JSON document.
+	pub fn from_json(json: &str) -> Result<Self, NetworksError> {
+		Ok(serde_json::from_str(json)?)
+	}
+
+	/// Reads the profiles of the file at `path`, which is JSON if its extension is `json` and TOML
+	/// otherwise.
+	pub fn from_file(path: impl AsRef<Path>) -> Result<Self, NetworksError> {
+		let path = path.as_ref();
+		let contents = fs::read_to_string(path)?;
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some(extension) if extension.eq_ignore_ascii_case("json") => Self::from_json(&contents),
+			_ => Self::from_toml(&contents),
+		}
+	}
+
+	/// The built-in profiles, extended by the file named by [`NETWORKS_VAR`] and overridden by
+	/// the environment variables of the process, see the [module docs](self).
+	pub fn from_env() -> Result<Self, NetworksError> {
+		Self::from_lookup(|name| std::env::var(name).ok())
+	}
+
+	/// Like [`Networks::from_env`], reading the variables from `lookup`.
+	pub fn from_lookup<F>(lookup: F) -> Result<Self, NetworksError>
+	where
+		F: Fn(&str) -> Option<String>,
+	{
+		let lookup = |name: &str| lookup(name).filter(|value| !value.trim().is_empty());
+
+		let mut networks = Self::builtin();
+		if let Some(path) = lookup(NETWORKS_VAR) {
+			networks.extend(Self::from_file(path.trim())?);
+		}
+		networks.apply_overrides(lookup)?;
+		Ok(networks)
+	}
+
+	/// Applies the `NEO_<NAME>_*` overrides of `lookup` to the profiles.
+	pub fn apply_overrides<F>(&mut self, lookup: F) -> Result<(), NetworksError>
+	where
+		F: Fn(&str) -> Option<String>,
+	{
+		for (name, profile) in &mut self.profiles {
+			let prefix = format!("NEO_{}", env_name(name));
+
+			let var = format!("{prefix}_ENDPOINTS");
+			if let Some(endpoints) = lookup(&var) {
+				profile.endpoints = endpoints
+					.split(',')
+					.map(|endpoint| Url::parse(endpoint.trim()))
+					.collect::<Result<_, _>>()
+					.map_err(|e| invalid(&var, e))?;
+			}
+
+			let var = format!("{prefix}_MAGIC");
+			if let Some(magic) = lookup(&var) {
+				let magic = magic.trim();
+				let parsed = match magic.strip_prefix("0x") {
+					Some(hex) => u32::from_str_radix(hex, 16),
+					None => magic.parse(),
+				};
+				profile.magic = Some(parsed.map_err(|e| invalid(&var, e))?);
+			}
+
+			let var = format!("{prefix}_ADDRESS_VERSION");
+			if let Some(version) = lookup(&var) {
+				profile.address_version = version.trim().parse().map_err(|e| invalid(&var, e))?;
+			}
+		}
+		Ok(())
+	}
+
+	/// The profile named `name`
+	pub fn get(&self, name: &str) -> Option<&NetworkProfile> {
+		self.profiles.get(name)
+	}
+
+	/// Adds the profile `name`, replacing a profile with the same name.
+	pub fn insert(&mut self, name: impl Into<String>, profile: NetworkProfile) {
+		self.profiles.insert(name.into(), profile);
+	}
+
+	/// Adds the profiles of `other`, replacing the profiles with the same names.
+	pub fn extend(&mut self, other: Networks) {
+		self.profiles.extend(other.profiles);
+	}
+
+	/// The names of the profiles, in alphabetical order
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.profiles.keys().map(String::as_str)
+	}
+
+	/// Looks up `name`, or for `<name>:<file>` the profile `name` of the file `file`.
+	pub fn resolve(&self, name: &str) -> Result<NetworkProfile, NetworksError> {
+		let (name, profile) = match name.split_once(':') {
+			Some((name, path)) => (name, Self::from_file(path)?.get(name).cloned()),
+			None => (name, self.get(name).cloned()),
+		};
+		profile.ok_or_else(|| NetworksError::UnknownNetwork(name.to_string()))
+	}
+
+	/// Creates an HTTP provider for the network `name`, see [`Networks::resolve`].
+	pub fn provider(&self, name: &str) -> Result<Provider<Http>, NetworksError> {
+		let profile = self.resolve(name)?;
+		profile.provider().ok_or_else(|| NetworksError::NoEndpoint(name.to_string()))
+	}
+}
+
+/// The name of a network in environment variables, e.g. `NEO_MY_NET` for `my-net`
+fn env_name(name: &str) -> String {
+	name.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+		.collect()
+}
+
+fn invalid(var: &str, reason: impl ToString) -> NetworksError {
+	NetworksError::Invalid { var: var.to_string(), reason: reason.to_string() }
+}
+
+impl Provider<Http> {
+	/// Creates a provider for the network `name` of [`Networks::from_env`], e.g. `testnet` or
+	/// `privnet:networks.toml`.
+	pub fn for_named_network(name: &str) -> Result<Self, NetworksError> {
+		Networks::from_env()?.provider(name)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Middleware;
+	use std::collections::HashMap;
+
+	const PROFILES: &str = r#"
+		[staging]
+		endpoints = ["https://staging.example:10332", "https://staging2.example:10332"]
+		magic = 1234567890
+		address_version = 23
+		ms_per_block = 5000
+
+		[local]
+		endpoints = ["http://localhost:10332"]
+	"#;
+
+	fn env(vars: &[(&str, &str)]) -> Result<Networks, NetworksError> {
+		let vars: HashMap<String, String> =
+			vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+		Networks::from_lookup(|name| vars.get(name).cloned())
+	}
+
+	#[test]
+	fn test_builtin() {
+		let networks = Networks::builtin();
+		assert_eq!(networks.names().collect::<Vec<_>>(), ["mainnet", "privnet", "testnet"]);
+		let testnet = networks.resolve("testnet").unwrap();
+		assert_eq!(testnet.magic, Some(NeoNetwork::TestNet.to_magic()));
+		assert_eq!(testnet.endpoints[0].as_str(), "https://testnet1.neo.coz.io/");
+		assert_eq!(networks.get("privnet").unwrap().magic, None);
+		assert!(matches!(networks.resolve("moonnet"), Err(NetworksError::UnknownNetwork(_))));
+
+		let provider = networks.provider("mainnet").unwrap();
+		assert_eq!(provider.config().network_magic, Some(NeoNetwork::MainNet.to_magic()));
+	}
+
+	#[test]
+	fn test_files() {
+		let networks = Networks::from_toml(PROFILES).unwrap();
+		let staging = networks.get("staging").unwrap();
+		assert_eq!(staging.endpoints.len(), 2);
+		assert_eq!(staging.magic, Some(1234567890));
+		let config = staging.config();
+		assert_eq!(config.address_version, 23);
+		assert_eq!(config.block_interval, 5_000);
+		let local = networks.get("local").unwrap();
+		assert_eq!(local.address_version, DEFAULT_ADDRESS_VERSION);
+		assert_eq!(local.magic, None);
+
+		let json = serde_json::to_string(&networks).unwrap();
+		assert_eq!(Networks::from_json(&json).unwrap(), networks);
+
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("networks.toml");
+		fs::write(&path, PROFILES).unwrap();
+		let local = Networks::default().resolve(&format!("local:{}", path.display())).unwrap();
+		assert_eq!(local.endpoints[0].as_str(), "http://localhost:10332/");
+		let path = dir.path().join("networks.json");
+		fs::write(&path, json).unwrap();
+		assert_eq!(Networks::from_file(&path).unwrap(), networks);
+	}
+
+	#[test]
+	fn test_overrides() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("networks.toml");
+		fs::write(&path, PROFILES).unwrap();
+		let path = path.display().to_string();
+
+		let networks = env(&[
+			(NETWORKS_VAR, &path),
+			("NEO_TESTNET_ENDPOINTS", "http://localhost:20332, http://localhost:20333"),
+			("NEO_STAGING_MAGIC", "0x4e454e"),
+			("NEO_LOCAL_ADDRESS_VERSION", "53"),
+		])
+		.unwrap();
+		assert_eq!(networks.names().count(), 5);
+		let testnet = networks.get("testnet").unwrap();
+		assert_eq!(testnet.endpoints[1].as_str(), "http://localhost:20333/");
+		assert_eq!(testnet.magic, Some(NeoNetwork::TestNet.to_magic()));
+		assert_eq!(networks.get("staging").unwrap().magic, Some(0x4e454e));
+		assert_eq!(networks.get("local").unwrap().address_version, 53);
+
+		assert!(matches!(
+			env(&[("NEO_MAINNET_MAGIC", "mainnet")]),
+			Err(NetworksError::Invalid { var, .. }) if var == "NEO_MAINNET_MAGIC"
+		));
+		assert!(env(&[("NEO_PRIVNET_ENDPOINTS", "localhost")]).is_err());
+		assert_eq!(env_name("my-net"), "MY_NET");
+	}
+}
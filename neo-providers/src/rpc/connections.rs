@@ -23,6 +23,20 @@ pub trait JsonRpcClient: Debug + Send + Sync {
 	where
 		T: Debug + Serialize + Send + Sync,
 		R: DeserializeOwned + Send;
+
+	/// Sends a request with the JSON-RPC id `id` instead of a fresh one, so that a resent request
+	/// carries the id of the original attempt.
+	///
+	/// Transports that do not choose the ids of their requests, like websockets, ignore `id` and
+	/// fall back to [`JsonRpcClient::fetch`].
+	async fn fetch_with_id<T, R>(&self, id: u64, method: &str, params: T) -> Result<R, Self::Error>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned + Send,
+	{
+		let _ = id;
+		self.fetch(method, params).await
+	}
 }
 
 /// A transport implementation supporting pub sub subscriptions.
@@ -0,0 +1,93 @@
+//! Decoding of JSON-RPC results with the path of the offending field in errors.
+//!
+//! Nodes do not answer with exactly the same shapes, e.g. neo-go adds fields neo-cli does not
+//! know. Fields missing from the response types are ignored by default, see [`DecodeMode`].
+
+use crate::ProviderError;
+use serde::de::{DeserializeOwned, Error};
+use serde_json::Value;
+use tracing::debug;
+
+/// How the fields of a result that the response type does not know are handled, see
+/// [`Provider::with_decode_mode`](crate::Provider::with_decode_mode).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DecodeMode {
+	/// Unknown fields are ignored and logged at the debug level
+	#[default]
+	Lenient,
+	/// Unknown fields fail the request with a [`ProviderError::Decode`], e.g. to catch changes
+	/// of the node API in tests
+	Strict,
+}
+
+/// Decodes the `result` of a `method` request into an `R`.
+///
+/// Errors name the JSON path of the field that failed to decode, like `protocol.network`.
+pub(crate) fn decode<R: DeserializeOwned>(
+	method: &str,
+	result: Value,
+	mode: DecodeMode,
+) -> Result<R, ProviderError> {
+	let mut unknown = Vec::new();
+	let deserializer =
+		serde_ignored::Deserializer::new(result, |path| unknown.push(path.to_string()));
+	let decoded = serde_path_to_error::deserialize(deserializer).map_err(|e| {
+		let path = e.path().to_string();
+		ProviderError::Decode { method: method.to_string(), path, source: e.into_inner() }
+	})?;
+
+	if !unknown.is_empty() {
+		if mode == DecodeMode::Strict {
+			return Err(ProviderError::Decode {
+				method: method.to_string(),
+				path: unknown.swap_remove(0),
+				source: serde_json::Error::custom("unknown field"),
+			})
+		}
+		debug!(method, fields = ?unknown, "ignored unknown fields of the result");
+	}
+	Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::responses::neo_get_version::NeoVersion;
+	use serde_json::json;
+
+	fn version() -> Value {
+		json!({
+			"tcpport": 10333,
+			"nonce": 1,
+			"useragent": "/NEO-GO:0.104.0/",
+			"protocol": {
+				"network": 860833102,
+				"msperblock": 15000,
+				"maxvaliduntilblockincrement": 5760,
+				"maxtraceableblocks": 2102400,
+				"addressversion": 53,
+				"maxtransactionsperblock": 512,
+				"memorypoolmaxtransactions": 50000,
+				"initialgasdistribution": 5200000000000000u64
+			}
+		})
+	}
+
+	#[test]
+	fn test_error_path() {
+		let mut result = version();
+		result["protocol"]["network"] = json!("mainnet");
+		let err = decode::<NeoVersion>("getversion", result, DecodeMode::Lenient).unwrap_err();
+		assert!(matches!(&err, ProviderError::Decode { path, .. } if path == "protocol.network"));
+		assert!(err.to_string().contains("getversion"));
+	}
+
+	#[test]
+	fn test_unknown_fields() {
+		let mut result = version();
+		result["protocol"]["hardforks"] = json!([]);
+		assert!(decode::<NeoVersion>("getversion", result.clone(), DecodeMode::Lenient).is_ok());
+		let err = decode::<NeoVersion>("getversion", result, DecodeMode::Strict).unwrap_err();
+		assert!(matches!(err, ProviderError::Decode { path, .. } if path == "protocol.hardforks"));
+	}
+}
@@ -6,8 +6,19 @@ pub use provider::*;
 mod transports;
 pub use transports::*;
 
+mod decode;
+pub use decode::DecodeMode;
+
+mod node_client;
+pub use node_client::{Capability, NodeClient};
+
 mod connections;
 pub use connections::*;
 
 mod pubsub;
 pub use pubsub::{PubsubClient, SubscriptionStream};
+
+mod multiplexer;
+pub use multiplexer::{
+	MultiplexedSubscription, SlowConsumerPolicy, SubscriberOptions, SubscriptionMultiplexer,
+};
@@ -0,0 +1,540 @@
+use crate::{utils::lock, Provider, ProviderError, PubsubClient, ShutdownGuard};
+
+use futures_util::{
+	future::{poll_fn, select, Either},
+	stream::Stream,
+	StreamExt,
+};
+use primitive_types::U256;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::value::RawValue;
+use std::{
+	collections::{HashMap, VecDeque},
+	fmt::Debug,
+	future::Future,
+	marker::PhantomData,
+	pin::Pin,
+	sync::{Arc, Mutex},
+	task::{Context, Poll, Waker},
+};
+use tracing::{debug, error};
+
+/// What to do with a notification for a subscriber whose buffer is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlowConsumerPolicy {
+	/// Discard the oldest buffered notification to make room for the new one. Other subscribers
+	/// are not affected by a slow consumer.
+	#[default]
+	DropOldest,
+	/// Wait until the subscriber has room. Delivery to every subscriber of the same node
+	/// subscription is paused meanwhile, and new notifications queue up in the transport.
+	Park,
+}
+
+/// Buffering options of a subscriber
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriberOptions {
+	/// Maximum number of notifications buffered for the subscriber
+	pub capacity: usize,
+	/// What to do when the buffer is full
+	pub policy: SlowConsumerPolicy,
+}
+
+impl SubscriberOptions {
+	/// Number of notifications buffered per subscriber by default
+	pub const DEFAULT_CAPACITY: usize = 256;
+
+	pub fn new(capacity: usize, policy: SlowConsumerPolicy) -> Self {
+		Self { capacity: capacity.max(1), policy }
+	}
+}
+
+impl Default for SubscriberOptions {
+	fn default() -> Self {
+		Self::new(Self::DEFAULT_CAPACITY, SlowConsumerPolicy::default())
+	}
+}
+
+/// The bounded buffer between the fan-out task of a node subscription and one subscriber
+#[derive(Debug)]
+struct Channel {
+	state: Mutex<ChannelState>,
+}
+
+#[derive(Debug)]
+struct ChannelState {
+	queue: VecDeque<Arc<RawValue>>,
+	options: SubscriberOptions,
+	dropped: u64,
+	/// The node subscription ended, no more notifications will be pushed
+	closed: bool,
+	/// The subscriber was dropped, notifications are no longer wanted
+	detached: bool,
+	receiver: Option<Waker>,
+	sender: Option<Waker>,
+}
+
+impl Channel {
+	fn new(options: SubscriberOptions) -> Arc<Self> {
+		Arc::new(Self {
+			state: Mutex::new(ChannelState {
+				queue: VecDeque::with_capacity(options.capacity.min(64)),
+				options,
+				dropped: 0,
+				closed: false,
+				detached: false,
+				receiver: None,
+				sender: None,
+			}),
+		})
+	}
+
+	/// Pushes `item` according to the slow consumer policy. Resolves to `false` if the subscriber
+	/// is gone.
+	fn poll_push(&self, cx: &mut Context<'_>, item: &Arc<RawValue>) -> Poll<bool> {
+		let mut state = lock(&self.state);
+		if state.detached {
+			return Poll::Ready(false)
+		}
+		if state.queue.len() >= state.options.capacity {
+			match state.options.policy {
+				SlowConsumerPolicy::DropOldest => {
+					state.queue.pop_front();
+					state.dropped += 1;
+				},
+				SlowConsumerPolicy::Park => {
+					state.sender = Some(cx.waker().clone());
+					return Poll::Pending
+				},
+			}
+		}
+		state.queue.push_back(item.clone());
+		if let Some(waker) = state.receiver.take() {
+			waker.wake();
+		}
+		Poll::Ready(true)
+	}
+
+	fn poll_pop(&self, cx: &mut Context<'_>) -> Poll<Option<Arc<RawValue>>> {
+		let mut state = lock(&self.state);
+		if let Some(item) = state.queue.pop_front() {
+			if let Some(waker) = state.sender.take() {
+				waker.wake();
+			}
+			return Poll::Ready(Some(item))
+		}
+		if state.closed {
+			return Poll::Ready(None)
+		}
+		state.receiver = Some(cx.waker().clone());
+		Poll::Pending
+	}
+
+	fn close(&self) {
+		let mut state = lock(&self.state);
+		state.closed = true;
+		if let Some(waker) = state.receiver.take() {
+			waker.wake();
+		}
+	}
+
+	fn detach(&self) {
+		let mut state = lock(&self.state);
+		state.detached = true;
+		state.queue.clear();
+		if let Some(waker) = state.sender.take() {
+			waker.wake();
+		}
+	}
+}
+
+/// A subscription on the node and the subscribers it is shared with
+#[derive(Debug)]
+struct Topic {
+	id: U256,
+	subscribers: Vec<Arc<Channel>>,
+}
+
+#[derive(Debug)]
+struct Shared<P> {
+	provider: Provider<P>,
+	/// Node subscriptions by their serialized parameters
+	topics: Mutex<HashMap<String, Topic>>,
+}
+
+impl<P: PubsubClient> Shared<P> {
+	fn subscribers(&self, key: &str, id: U256) -> Option<Vec<Arc<Channel>>> {
+		let topics = lock(&self.topics);
+		topics
+			.get(key)
+			.filter(|topic| topic.id == id)
+			.map(|topic| topic.subscribers.clone())
+	}
+
+	/// Removes a subscriber, and the node subscription once its last subscriber is gone.
+	fn remove_subscriber(&self, key: &str, id: U256, channel: &Arc<Channel>) {
+		let mut topics = lock(&self.topics);
+		let Some(topic) = topics.get_mut(key).filter(|topic| topic.id == id) else { return };
+		topic.subscribers.retain(|subscriber| !Arc::ptr_eq(subscriber, channel));
+		if topic.subscribers.is_empty() {
+			topics.remove(key);
+			drop(topics);
+			debug!(%id, "last subscriber gone, unsubscribing");
+			if let Err(err) = self.provider.as_ref().unsubscribe(id) {
+				let err: ProviderError = err.into();
+				error!("failed to unsubscribe {id}: {err}");
+			}
+		}
+	}
+
+	/// Ends the streams of the subscribers after the node subscription ended.
+	fn close_topic(&self, key: &str, id: U256) {
+		let mut topics = lock(&self.topics);
+		if topics.get(key).is_some_and(|topic| topic.id == id) {
+			let topic = topics.remove(key).expect("topic exists");
+			topic.subscribers.iter().for_each(|subscriber| subscriber.close());
+		}
+	}
+}
+
+/// Forwards the notifications of one node subscription to its subscribers, until the
+/// subscription ends or the provider shuts down.
+async fn fan_out<P: PubsubClient>(
+	shared: Arc<Shared<P>>,
+	key: String,
+	id: U256,
+	mut notifications: P::NotificationStream,
+	_guard: ShutdownGuard,
+) {
+	let mut shutdown = shared.provider.shutdown().signal();
+	loop {
+		let item = match select(notifications.next(), &mut shutdown).await {
+			Either::Left((Some(item), _)) => item,
+			Either::Left((None, _)) => break,
+			Either::Right(((), _)) => {
+				debug!(%id, "shutting down, unsubscribing");
+				if let Err(err) = shared.provider.as_ref().unsubscribe(id) {
+					let err: ProviderError = err.into();
+					error!("failed to unsubscribe {id}: {err}");
+				}
+				break
+			},
+		};
+		let item: Arc<RawValue> = item.into();
+		let Some(subscribers) = shared.subscribers(&key, id) else { break };
+		for subscriber in subscribers {
+			if !poll_fn(|cx| subscriber.poll_push(cx, &item)).await {
+				shared.remove_subscriber(&key, id, &subscriber);
+			}
+		}
+	}
+	shared.close_topic(&key, id);
+}
+
+fn spawn(fut: impl Future<Output = ()> + Send + 'static) {
+	#[cfg(target_arch = "wasm32")]
+	wasm_bindgen_futures::spawn_local(fut);
+	#[cfg(not(target_arch = "wasm32"))]
+	tokio::spawn(fut);
+}
+
+/// Shares the subscriptions of one pub sub connection between many consumers.
+///
+/// Subscribing with parameters that are already subscribed to on the node adds a subscriber to
+/// the existing node subscription instead of creating another one. Every subscriber has its own
+/// bounded buffer, and a [`SlowConsumerPolicy`] deciding what happens when it fills up. The node
+/// subscription is cancelled once its last subscriber is dropped.
+///
+/// Notifications are forwarded by a background task, so the multiplexer must be used within a
+/// Tokio runtime. The tasks unsubscribe and end the streams of their subscribers when the
+/// [`Shutdown`](crate::Shutdown) of the provider is triggered.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_util::StreamExt;
+/// use neo_providers::{Provider, SubscriptionMultiplexer, Ws};
+/// use serde_json::{json, Value};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Ws>::connect("ws://localhost:10332/ws").await?;
+/// let mux = SubscriptionMultiplexer::new(provider);
+///
+/// // both streams share a single subscription on the node
+/// let mut blocks = mux.subscribe::<_, Value>(json!(["block_added"])).await?;
+/// let mut more_blocks = mux.subscribe::<_, Value>(json!(["block_added"])).await?;
+/// while let Some(block) = blocks.next().await {
+///     println!("new block: {block}");
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct SubscriptionMultiplexer<P> {
+	shared: Arc<Shared<P>>,
+}
+
+impl<P> Clone for SubscriptionMultiplexer<P> {
+	fn clone(&self) -> Self {
+		Self { shared: self.shared.clone() }
+	}
+}
+
+impl<P> SubscriptionMultiplexer<P>
+where
+	P: PubsubClient + 'static,
+	P::NotificationStream: 'static,
+{
+	/// Creates a multiplexer sharing the connection of `provider`.
+	pub fn new(provider: Provider<P>) -> Self {
+		Self { shared: Arc::new(Shared { provider, topics: Mutex::new(HashMap::new()) }) }
+	}
+
+	/// The provider the subscriptions are made with
+	pub fn provider(&self) -> &Provider<P> {
+		&self.shared.provider
+	}
+
+	/// Number of subscriptions open on the node
+	pub fn subscription_count(&self) -> usize {
+		lock(&self.shared.topics).len()
+	}
+
+	/// Number of subscribers across all subscriptions
+	pub fn subscriber_count(&self) -> usize {
+		lock(&self.shared.topics).values().map(|topic| topic.subscribers.len()).sum()
+	}
+
+	/// Subscribes to `params` with the default [`SubscriberOptions`].
+	pub async fn subscribe<T, R>(
+		&self,
+		params: T,
+	) -> Result<MultiplexedSubscription<P, R>, ProviderError>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned,
+	{
+		self.subscribe_with(params, SubscriberOptions::default()).await
+	}
+
+	/// Subscribes to `params`, reusing the node subscription for the same parameters if there is
+	/// one.
+	pub async fn subscribe_with<T, R>(
+		&self,
+		params: T,
+		options: SubscriberOptions,
+	) -> Result<MultiplexedSubscription<P, R>, ProviderError>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned,
+	{
+		if self.shared.provider.shutdown().is_triggered() {
+			return Err(ProviderError::Shutdown)
+		}
+		let key = serde_json::to_string(&params)?;
+		if let Some(subscription) = self.join(&key, options) {
+			return Ok(subscription)
+		}
+
+		let id: U256 = self.shared.provider.request("subscribe", params).await?;
+		let notifications = self.shared.provider.as_ref().subscribe(id).map_err(Into::into)?;
+		if let Some(subscription) = self.join(&key, options) {
+			// another consumer subscribed to the same parameters in the meantime
+			if let Err(err) = self.shared.provider.as_ref().unsubscribe(id) {
+				let err: ProviderError = err.into();
+				error!("failed to unsubscribe {id}: {err}");
+			}
+			return Ok(subscription)
+		}
+
+		let channel = Channel::new(options);
+		lock(&self.shared.topics)
+			.insert(key.clone(), Topic { id, subscribers: vec![channel.clone()] });
+		// taken before spawning, so a shutdown right away still waits for the task
+		let guard = self.shared.provider.shutdown().guard();
+		spawn(fan_out(self.shared.clone(), key.clone(), id, notifications, guard));
+		Ok(MultiplexedSubscription::new(self.shared.clone(), key, id, channel))
+	}
+
+	fn join<R>(
+		&self,
+		key: &str,
+		options: SubscriberOptions,
+	) -> Option<MultiplexedSubscription<P, R>>
+	where
+		R: DeserializeOwned,
+	{
+		let mut topics = lock(&self.shared.topics);
+		let topic = topics.get_mut(key)?;
+		let channel = Channel::new(options);
+		topic.subscribers.push(channel.clone());
+		Some(MultiplexedSubscription::new(self.shared.clone(), key.to_string(), topic.id, channel))
+	}
+}
+
+/// A stream of the notifications of a subscription shared through a [`SubscriptionMultiplexer`].
+///
+/// Notifications that cannot be deserialized into `R` are logged and skipped. Dropping the stream
+/// removes the subscriber.
+#[must_use = "subscriptions do nothing unless you stream them"]
+pub struct MultiplexedSubscription<P: PubsubClient, R> {
+	shared: Arc<Shared<P>>,
+	key: String,
+	id: U256,
+	channel: Arc<Channel>,
+	ret: PhantomData<fn() -> R>,
+}
+
+impl<P: PubsubClient, R> Debug for MultiplexedSubscription<P, R> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MultiplexedSubscription")
+			.field("params", &self.key)
+			.field("id", &self.id)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<P: PubsubClient, R: DeserializeOwned> MultiplexedSubscription<P, R> {
+	fn new(shared: Arc<Shared<P>>, key: String, id: U256, channel: Arc<Channel>) -> Self {
+		Self { shared, key, id, channel, ret: PhantomData }
+	}
+
+	/// The id of the underlying node subscription, shared with the other subscribers
+	pub fn id(&self) -> U256 {
+		self.id
+	}
+
+	/// Number of notifications discarded because the buffer was full
+	pub fn dropped(&self) -> u64 {
+		lock(&self.channel.state).dropped
+	}
+}
+
+impl<P: PubsubClient, R: DeserializeOwned> Stream for MultiplexedSubscription<P, R> {
+	type Item = R;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		loop {
+			return match futures_util::ready!(self.channel.poll_pop(cx)) {
+				Some(item) => match serde_json::from_str(item.get()) {
+					Ok(res) => Poll::Ready(Some(res)),
+					Err(err) => {
+						error!("failed to deserialize item {:?}", err);
+						continue
+					},
+				},
+				None => Poll::Ready(None),
+			}
+		}
+	}
+}
+
+impl<P: PubsubClient, R> Drop for MultiplexedSubscription<P, R> {
+	fn drop(&mut self) {
+		self.channel.detach();
+		self.shared.remove_subscriber(&self.key, self.id, &self.channel);
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::{JsonRpcClient, MockError, MockProvider};
+	use async_trait::async_trait;
+	use serde_json::json;
+	use tokio::sync::mpsc;
+
+	type Notifications = Pin<Box<dyn Stream<Item = Box<RawValue>> + Send>>;
+
+	/// A pub sub transport whose notifications are pushed by the test
+	#[derive(Debug, Clone, Default)]
+	struct MockPubsub {
+		inner: MockProvider,
+		senders: Arc<Mutex<HashMap<U256, mpsc::UnboundedSender<Box<RawValue>>>>>,
+		unsubscribed: Arc<Mutex<Vec<U256>>>,
+	}
+
+	impl MockPubsub {
+		fn notify(&self, id: u64, value: serde_json::Value) {
+			let raw = serde_json::value::to_raw_value(&value).unwrap();
+			self.senders.lock().unwrap()[&U256::from(id)].send(raw).unwrap();
+		}
+	}
+
+	#[async_trait]
+	impl JsonRpcClient for MockPubsub {
+		type Error = MockError;
+
+		async fn fetch<T, R>(&self, method: &str, params: T) -> Result<R, MockError>
+		where
+			T: Debug + Serialize + Send + Sync,
+			R: DeserializeOwned + Send,
+		{
+			self.inner.fetch(method, params).await
+		}
+	}
+
+	impl PubsubClient for MockPubsub {
+		type NotificationStream = Notifications;
+
+		fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Notifications, MockError> {
+			let (tx, rx) = mpsc::unbounded_channel();
+			self.senders.lock().unwrap().insert(id.into(), tx);
+			Ok(futures_util::stream::unfold(rx, |mut rx| async move {
+				rx.recv().await.map(|item| (item, rx))
+			})
+			.boxed())
+		}
+
+		fn unsubscribe<T: Into<U256>>(&self, id: T) -> Result<(), MockError> {
+			let id = id.into();
+			self.unsubscribed.lock().unwrap().push(id);
+			// ends the notification stream
+			self.senders.lock().unwrap().remove(&id);
+			Ok(())
+		}
+	}
+
+	#[tokio::test]
+	async fn shares_one_node_subscription() {
+		let transport = MockPubsub::default();
+		transport.inner.push::<U256, _>(U256::from(1)).unwrap();
+		let mux = SubscriptionMultiplexer::new(Provider::new(transport.clone()));
+
+		let mut a = mux.subscribe::<_, u32>(json!(["block_added"])).await.unwrap();
+		let mut b = mux.subscribe::<_, u32>(json!(["block_added"])).await.unwrap();
+		assert_eq!(mux.subscription_count(), 1);
+		assert_eq!(mux.subscriber_count(), 2);
+
+		transport.notify(1, json!(7));
+		assert_eq!(a.next().await, Some(7));
+		assert_eq!(b.next().await, Some(7));
+
+		drop(a);
+		assert!(transport.unsubscribed.lock().unwrap().is_empty());
+		drop(b);
+		assert_eq!(mux.subscription_count(), 0);
+		assert_eq!(*transport.unsubscribed.lock().unwrap(), vec![U256::from(1)]);
+	}
+
+	#[tokio::test]
+	async fn drops_oldest_for_slow_consumers() {
+		let transport = MockPubsub::default();
+		transport.inner.push::<U256, _>(U256::from(1)).unwrap();
+		let mux = SubscriptionMultiplexer::new(Provider::new(transport.clone()));
+
+		let options = SubscriberOptions::new(2, SlowConsumerPolicy::DropOldest);
+		let mut slow = mux.subscribe_with::<_, u32>(json!(["block_added"]), options).await.unwrap();
+		let mut fast = mux.subscribe::<_, u32>(json!(["block_added"])).await.unwrap();
+		for i in 0..4 {
+			transport.notify(1, json!(i));
+		}
+		for i in 0..4 {
+			assert_eq!(fast.next().await, Some(i));
+		}
+
+		assert_eq!(slow.next().await, Some(2));
+		assert_eq!(slow.next().await, Some(3));
+		assert_eq!(slow.dropped(), 2);
+	}
+}
@@ -0,0 +1,122 @@
+//! The node implementation behind a provider and the RPC features it supports.
+
+use crate::ProviderError;
+use semver::Version;
+use std::str::FromStr;
+
+/// The node implementation a provider is connected to, parsed from the user agent reported by
+/// `getversion`, see [`Provider::node_client`](crate::Provider::node_client).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeClient {
+	/// The C# reference node with its `RpcServer` plugin, e.g. `/Neo:3.6.0/`
+	NeoCli(Version),
+	/// neo-go, e.g. `/NEO-GO:0.104.0/`
+	NeoGo(Version),
+}
+
+/// An RPC feature that only some nodes, or node versions, support
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Capability {
+	/// `getblocksysfee`, the system fees of the transactions of a block
+	BlockSysFee,
+	/// `invokecontainedscript`, test invocations in the context of a given transaction and block
+	InvokeContainedScript,
+	/// `invokescripthistoric` and `invokefunctionhistoric`, test invocations against the state
+	/// of a past block
+	HistoricInvocations,
+	/// `traverseiterator` and `terminatesession`, iterators of test invocations traversed in
+	/// sessions
+	IteratorSessions,
+	/// Iterators of test invocations returned with their first items when sessions are disabled
+	SessionlessIterators,
+	/// `findstorage`, the storage entries of a contract by prefix
+	FindStorage,
+}
+
+impl NodeClient {
+	/// The version of the node
+	pub fn version(&self) -> &Version {
+		match self {
+			NodeClient::NeoCli(version) | NodeClient::NeoGo(version) => version,
+		}
+	}
+
+	/// Whether the implementation and version of the node support `capability`.
+	///
+	/// Some capabilities also depend on the configuration of the node, see
+	/// [`NeoVersion::supports`](crate::core::responses::neo_get_version::NeoVersion::supports).
+	pub fn supports(&self, capability: Capability) -> bool {
+		let at_least = |major, minor, patch| *self.version() >= Version::new(major, minor, patch);
+		match (self, capability) {
+			(NodeClient::NeoCli(_), Capability::IteratorSessions) => at_least(3, 3, 0),
+			(NodeClient::NeoCli(_), Capability::FindStorage) => at_least(3, 6, 0),
+			(NodeClient::NeoCli(_), _) => false,
+			(NodeClient::NeoGo(_), Capability::BlockSysFee | Capability::SessionlessIterators) =>
+				true,
+			(NodeClient::NeoGo(_), Capability::IteratorSessions) => at_least(0, 99, 0),
+			(NodeClient::NeoGo(_), Capability::HistoricInvocations) => at_least(0, 99, 3),
+			(NodeClient::NeoGo(_), Capability::InvokeContainedScript) => at_least(0, 104, 0),
+			(NodeClient::NeoGo(_), Capability::FindStorage) => at_least(0, 105, 0),
+		}
+	}
+}
+
+impl FromStr for NodeClient {
+	type Err = ProviderError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		// user agents look like `/Neo:3.6.0/`
+		let (name, version) = s
+			.trim_matches('/')
+			.split_once(':')
+			.ok_or(ProviderError::UnsupportedNodeClient)?;
+		let version = parse_version(version).ok_or(ProviderError::UnsupportedNodeClient)?;
+		match name.to_lowercase().as_str() {
+			"neo" => Ok(NodeClient::NeoCli(version)),
+			"neo-go" => Ok(NodeClient::NeoGo(version)),
+			_ => Err(ProviderError::UnsupportedNodeClient),
+		}
+	}
+}
+
+/// Parses a semantic version, or the leading `major.minor.patch` of versions like `3.6.0.0`
+fn parse_version(version: &str) -> Option<Version> {
+	Version::parse(version).ok().or_else(|| {
+		let mut parts = version.split(|c: char| !c.is_ascii_digit()).map(|part| part.parse().ok());
+		Some(Version::new(parts.next()??, parts.next()??, parts.next()??))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_user_agent() {
+		assert_eq!(
+			"/Neo:3.6.0/".parse::<NodeClient>().unwrap(),
+			NodeClient::NeoCli(Version::new(3, 6, 0))
+		);
+		let neo_go = "/NEO-GO:0.105.1-pre-20-g1a2b3c4/".parse::<NodeClient>().unwrap();
+		assert!(matches!(&neo_go, NodeClient::NeoGo(version) if version.minor == 105));
+		assert_eq!(
+			"/Neo:3.5.0.1/".parse::<NodeClient>().unwrap().version(),
+			&Version::new(3, 5, 0)
+		);
+		assert!("/Other:1.0.0/".parse::<NodeClient>().is_err());
+		assert!("Neo".parse::<NodeClient>().is_err());
+	}
+
+	#[test]
+	fn test_capabilities() {
+		let neo_cli = NodeClient::NeoCli(Version::new(3, 5, 0));
+		assert!(neo_cli.supports(Capability::IteratorSessions));
+		assert!(!neo_cli.supports(Capability::FindStorage));
+		assert!(!neo_cli.supports(Capability::BlockSysFee));
+
+		let neo_go = NodeClient::NeoGo(Version::new(0, 104, 0));
+		assert!(neo_go.supports(Capability::BlockSysFee));
+		assert!(neo_go.supports(Capability::InvokeContainedScript));
+		assert!(!neo_go.supports(Capability::FindStorage));
+	}
+}
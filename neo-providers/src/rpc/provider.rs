@@ -1,6 +1,15 @@
 use crate::{
-	call_raw::CallBuilder, errors::ProviderError, rpc::pubsub::PubsubClient, utils,
-	Http as HttpProvider, JsonRpcClient, MiddlewareError, MockProvider, RwClient,
+	block_range,
+	call_raw::CallBuilder,
+	errors::ProviderError,
+	metrics, redact,
+	rpc::{
+		decode::{decode, DecodeMode},
+		pubsub::{PubsubClient, SubscriptionStream},
+	},
+	state::StateServiceClient,
+	utils, Capability, CorrelationId, Http as HttpProvider, JsonRpcClient, MiddlewareError,
+	MockProvider, NodeClient, RwClient, Shutdown, StorageIterator,
 };
 
 pub use crate::Middleware;
@@ -9,10 +18,12 @@ use crate::{
 		account::AccountTrait,
 		responses::{
 			neo_address::NeoAddress,
-			neo_application_log::ApplicationLog,
+			neo_application_log::{ApplicationLog, ApplicationLogFilter},
 			neo_balances::{Nep11Balances, Nep17Balances},
 			neo_block::NeoBlock,
 			neo_find_states::States,
+			neo_find_storage::FindStorage,
+			neo_get_candidates::Candidate,
 			neo_get_mem_pool::MemPoolDetails,
 			neo_get_next_block_validators::Validator,
 			neo_get_peers::Peers,
@@ -22,6 +33,7 @@ use crate::{
 			neo_get_version::NeoVersion,
 			neo_get_wallet_balance::Balance,
 			neo_list_plugins::Plugin,
+			neo_network_fee::NeoNetworkFee,
 			neo_send_raw_transaction::RawTransaction,
 			neo_transfers::{Nep11Transfers, Nep17Transfers},
 			neo_validate_address::ValidateAddress,
@@ -37,27 +49,31 @@ use crate::{
 	rpc::provider::sealed::Sealed,
 };
 
-use crate::core::responses::neo_transaction_result::TransactionResult;
+use crate::{core::responses::neo_transaction_result::TransactionResult, MempoolWatcher};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::{HttpRateLimitRetryPolicy, RetryClient};
 use async_trait::async_trait;
-use futures_util::lock::Mutex;
+use futures_core::stream::Stream;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use neo_config::{NeoConfig, SharedConfig};
 use neo_crypto::keys::Secp256r1Signature;
 use neo_types::{
 	address::{Address, NameOrAddress},
 	block::{Block, BlockId},
 	contract_parameter::ContractParameter,
 	contract_state::ContractState,
-	filter::{Filter, FilterBlockOption},
+	encoding,
+	gas::Gas,
 	invocation_result::{InvocationResult, PendingSignature},
 	log::Log,
 	native_contract_state::NativeContractState,
-	script_hash::ScriptHashExtension,
+	notification_filter::NotificationFilter,
+	script_hash::ScriptHashExt,
 	serde_value::ValueExtension,
 	stack_item::StackItem,
-	syncing::SyncingStatus,
 	Bytes,
 };
+use once_cell::sync::OnceCell;
 use primitive_types::{H160, H256 as TxHash, H256, U256};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
@@ -67,7 +83,7 @@ use std::{
 	fmt::Debug,
 	future::Future,
 	net::Ipv4Addr,
-	str::FromStr,
+	ops::Range,
 	sync::Arc,
 	time::Duration,
 };
@@ -75,36 +91,9 @@ use tracing::trace;
 use tracing_futures::Instrument;
 use url::{Host, ParseError, Url};
 
-/// Node Clients
-#[derive(Copy, Clone)]
-pub enum NodeClient {
-	/// RNEO
-	NEO,
-}
-
-impl FromStr for NodeClient {
-	type Err = ProviderError;
-
-	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		match s.split('/').next().unwrap().to_lowercase().as_str() {
-			"NEO" => Ok(NodeClient::NEO),
-			_ => Err(ProviderError::UnsupportedNodeClient),
-		}
-	}
-}
-
-/// Types of filters supported by the JSON-RPC.
-#[derive(Clone, Debug)]
-pub enum FilterKind<'a> {
-	/// `neo_newBlockFilter`
-	Logs(&'a Filter),
-
-	/// `neo_newBlockFilter` filter
-	NewBlocks,
-
-	/// `neo_newPendingTransactionFilter` filter
-	PendingTransactions,
-}
+/// The number of blocks, and application logs per block, fetched at once by
+/// [`Middleware::get_notifications`]
+const NOTIFICATION_SCAN_CONCURRENCY: usize = 8;
 
 /// An abstract provider for interacting with the [Neo JSON RPC
 /// API](https://github.com/neo/wiki/wiki/JSON-RPC). Must be instantiated
@@ -134,8 +123,13 @@ pub struct Provider<P> {
 	inner: P,
 	nns: Option<Address>,
 	interval: Option<Duration>,
+	timeout: Option<Duration>,
 	from: Option<Address>,
-	_node_client: Arc<Mutex<Option<NodeClient>>>,
+	config: SharedConfig,
+	shutdown: Shutdown,
+	decode_mode: DecodeMode,
+	/// The `getversion` result, fetched once and shared by the clones of the provider
+	version: Arc<OnceCell<NeoVersion>>,
 }
 
 impl<P> AsRef<P> for Provider<P> {
@@ -146,33 +140,97 @@ impl<P> AsRef<P> for Provider<P> {
 
 // JSON RPC bindings
 impl<P: JsonRpcClient> Provider<P> {
-	/// Instantiate a new provider with a backend.
+	/// Instantiate a new provider with a backend and the default config.
 	pub fn new(provider: P) -> Self {
+		Self::with_config(provider, NeoConfig::default())
+	}
+
+	/// Instantiate a new provider with a backend and a config.
+	///
+	/// The NNS resolver, block and polling intervals and the validity window of transactions
+	/// are read from `config`. Passing a [`SharedConfig`] lets several providers share, and
+	/// update, one config.
+	pub fn with_config(provider: P, config: impl Into<SharedConfig>) -> Self {
 		Self {
 			inner: provider,
 			nns: None,
 			interval: None,
+			timeout: None,
 			from: None,
-			_node_client: Arc::new(Mutex::new(None)),
+			config: config.into(),
+			shutdown: Shutdown::new(),
+			decode_mode: DecodeMode::default(),
+			version: Arc::new(OnceCell::new()),
 		}
 	}
 
-	/// Returns the type of node we're connected to, while also caching the value for use
-	/// in other node-specific API calls, such as the get_block_receipts call.
+	/// The shared config of the provider. Updates through the handle are seen by every clone of
+	/// the provider.
+	pub fn shared_config(&self) -> &SharedConfig {
+		&self.config
+	}
+
+	/// Returns the type of node we're connected to, parsed from the user agent of
+	/// [`Provider::cached_version`].
 	pub async fn node_client(&self) -> Result<NodeClient, ProviderError> {
-		let mut node_client = self._node_client.lock().await;
+		self.cached_version().await?.node_client()
+	}
 
-		if let Some(node_client) = *node_client {
-			Ok(node_client)
-		} else {
-			let client_version = self.client_version().await?;
-			let client_version = match client_version.parse::<NodeClient>() {
-				Ok(res) => res,
-				Err(_) => return Err(ProviderError::UnsupportedNodeClient),
-			};
-			*node_client = Some(client_version);
-			Ok(client_version)
+	/// Whether the node supports `capability`, see [`NeoVersion::supports`]. Lets callers pick
+	/// a strategy up front instead of handling a method not found error.
+	pub async fn supports(&self, capability: Capability) -> Result<bool, ProviderError> {
+		Ok(self.cached_version().await?.supports(capability))
+	}
+
+	/// The system fees of the transactions of the block at `index`.
+	///
+	/// Sent as `getblocksysfee` to nodes supporting it, otherwise summed from the transactions
+	/// of the block.
+	pub async fn get_block_sys_fee(&self, index: u32) -> Result<Gas, ProviderError> {
+		if self.supports(Capability::BlockSysFee).await? {
+			return self.request("getblocksysfee", vec![index.to_value()]).await
 		}
+		let block = self.get_block_by_index(index, true).await?;
+		Ok(block
+			.transactions
+			.unwrap_or_default()
+			.iter()
+			.fold(Gas::default(), |fee, tx| fee.saturating_add(tx.sys_fee)))
+	}
+
+	/// The version of the node, requested once and then read without locking by every clone of
+	/// the provider.
+	///
+	/// Concurrent first calls may each send `getversion`, the first answer is kept. Use
+	/// [`Middleware::get_version`] for a fresh answer, e.g. the current nonce of the node.
+	pub async fn cached_version(&self) -> Result<&NeoVersion, ProviderError> {
+		if let Some(version) = self.version.get() {
+			return Ok(version)
+		}
+		let version = self.get_version().await?;
+		Ok(self.version.get_or_init(|| version))
+	}
+
+	/// The shutdown handle of the provider, shared by its clones and the background work made on
+	/// its behalf.
+	pub fn shutdown(&self) -> &Shutdown {
+		&self.shutdown
+	}
+
+	#[must_use]
+	/// Set the shutdown handle, e.g. to stop several providers at once
+	pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+		self.shutdown = shutdown;
+		self
+	}
+
+	#[must_use]
+	/// Set how fields of results unknown to the response types are handled, ignored by default.
+	/// Results that do not decode fail with a [`ProviderError::Decode`] naming the path of the
+	/// offending field either way.
+	pub fn with_decode_mode(mut self, mode: DecodeMode) -> Self {
+		self.decode_mode = mode;
+		self
 	}
 
 	#[must_use]
@@ -183,25 +241,72 @@ impl<P: JsonRpcClient> Provider<P> {
 	}
 
 	/// Make an RPC request via the internal connection, and return the result.
+	///
+	/// Fails with [`ProviderError::Timeout`] if a timeout is set with [`Provider::with_timeout`]
+	/// and the request does not complete in time.
 	pub async fn request<T, R>(&self, method: &str, params: T) -> Result<R, ProviderError>
 	where
 		T: Debug + Serialize + Send + Sync,
 		R: Serialize + DeserializeOwned + Debug + Send,
 	{
-		let span =
-			tracing::trace_span!("rpc", method = method, params = ?serde_json::to_string(&params)?);
+		match self.timeout {
+			Some(timeout) => self.request_with_timeout(method, params, timeout).await,
+			None => self.send_request(method, params).await,
+		}
+	}
+
+	/// Make an RPC request that is cancelled with [`ProviderError::Timeout`] if it does not
+	/// complete within `timeout`, overriding the timeout of the provider.
+	pub async fn request_with_timeout<T, R>(
+		&self,
+		method: &str,
+		params: T,
+		timeout: Duration,
+	) -> Result<R, ProviderError>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: Serialize + DeserializeOwned + Debug + Send,
+	{
+		crate::timeout(timeout, self.send_request(method, params))
+			.await
+			.unwrap_or(Err(ProviderError::Timeout(timeout)))
+	}
+
+	async fn send_request<T, R>(&self, method: &str, params: T) -> Result<R, ProviderError>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: Serialize + DeserializeOwned + Debug + Send,
+	{
+		let params_json = redact::redact_params(method, serde_json::to_value(&params)?);
+		let span = tracing::trace_span!(
+			"rpc",
+			method = method,
+			params = %params_json,
+			correlation_id = tracing::field::Empty
+		);
+		if let Some(id) = CorrelationId::current() {
+			span.record("correlation_id", id.as_str());
+		}
 		// https://docs.rs/tracing/0.1.22/tracing/span/struct.Span.html#in-asynchronous-code
 		let res = async move {
 			trace!("tx");
-			let res: R = self.inner.fetch(method, params).await.map_err(Into::into)?;
-			trace!(rx = ?serde_json::to_string(&res)?);
-			Ok::<_, ProviderError>(res)
+			let fetch = async { self.inner.fetch(method, params).await.map_err(Into::into) };
+			let res: Value = metrics::instrument::<_, Value, ProviderError>(
+				metrics::PROVIDER_LAYER,
+				method,
+				fetch,
+			)
+			.await?;
+			trace!(rx = %redact::redact_result(method, res.clone()));
+			decode::<R>(method, res, self.decode_mode)
 		}
 		.instrument(span)
 		.await?;
 		Ok(res)
 	}
 
+	/// Test invokes the script of `tx`. The signers, block and diagnostics of the invocation can
+	/// be overridden through the returned [`CallBuilder`].
 	pub fn call_raw<'a>(&'a self, tx: &'a Transaction) -> CallBuilder<'a, P> {
 		CallBuilder::new(self, tx)
 	}
@@ -226,14 +331,18 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		self
 	}
 
+	fn config(&self) -> Arc<NeoConfig> {
+		self.config.load()
+	}
+
 	fn default_sender(&self) -> Option<Address> {
-		self.from.clone()
+		self.from
 	}
 
 	//////////////////////// Neo methods////////////////////////////
 
 	fn nns_resolver(&self) -> H160 {
-		H160::from(self.config().nns_resolver.clone())
+		self.config().nns_resolver
 	}
 
 	fn block_interval(&self) -> u32 {
@@ -248,6 +357,16 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		self.config().max_valid_until_block_increment
 	}
 
+	async fn get_network_magic(&self) -> Result<u32, ProviderError> {
+		if let Some(magic) = self.config().network_magic {
+			return Ok(magic)
+		}
+		let version = self.cached_version().await?;
+		version.protocol.as_ref().map(|protocol| protocol.network).ok_or_else(|| {
+			ProviderError::IllegalState("the node did not report its protocol".to_string())
+		})
+	}
+
 	// Blockchain methods
 	async fn get_best_block_hash(&self) -> Result<H256, ProviderError> {
 		self.request("getbestblockhash", ()).await
@@ -340,6 +459,20 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		let params = [contract_hash.to_value(), key.to_value()];
 		self.request("getstorage", params.to_vec()).await
 	}
+
+	async fn find_storage(
+		&self,
+		contract_hash: H160,
+		prefix: &[u8],
+		start: u32,
+	) -> Result<FindStorage, ProviderError> {
+		let prefix = encoding::base64_encode(prefix);
+		self.request(
+			"findstorage",
+			vec![contract_hash.to_value(), prefix.to_value(), start.to_value()],
+		)
+		.await
+	}
 	// Blockchain methods
 
 	async fn get_transaction_height(&self, tx_hash: H256) -> Result<u32, ProviderError> {
@@ -355,6 +488,10 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		self.request("getcommittee", ()).await
 	}
 
+	async fn get_candidates(&self) -> Result<Vec<Candidate>, ProviderError> {
+		self.request("getcandidates", ()).await
+	}
+
 	async fn get_connection_count(&self) -> Result<u32, ProviderError> {
 		self.request("getconnectioncount", ()).await
 	}
@@ -369,8 +506,8 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		self.request("getversion", ()).await
 	}
 
-	async fn send_raw_transaction(&self, hex: String) -> Result<RawTransaction, ProviderError> {
-		self.request("sendrawtransaction", vec![hex.to_value()]).await
+	async fn send_raw_transaction(&self, base64: String) -> Result<RawTransaction, ProviderError> {
+		self.request("sendrawtransaction", vec![base64.to_value()]).await
 	}
 	// More node methods
 
@@ -462,8 +599,9 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		self.request("importprivkey", params).await
 	}
 
-	async fn calculate_network_fee(&self, hex: String) -> Result<u64, ProviderError> {
-		self.request("calculatenetworkfee", vec![hex.to_value()]).await
+	async fn calculate_network_fee(&self, hex: String) -> Result<Gas, ProviderError> {
+		let fee: NeoNetworkFee = self.request("calculatenetworkfee", vec![hex.to_value()]).await?;
+		Ok(fee.network_fee)
 	}
 
 	async fn list_address(&self) -> Result<Vec<NeoAddress>, ProviderError> {
@@ -493,7 +631,11 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		from: Option<H160>,
 		send_tokens: Vec<TransactionSendToken>,
 	) -> Result<Transaction, ProviderError> {
-		let params = [from.unwrap().to_value(), send_tokens.to_value()].to_vec();
+		// without a sender the node sends from any account of the open wallet
+		let mut params = vec![send_tokens.to_value()];
+		if let Some(from) = from {
+			params.insert(0, from.to_value());
+		}
 		self.request("sendmany", params).await
 	}
 
@@ -511,6 +653,78 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		self.request("getapplicationlog", vec![tx_hash.to_value()]).await
 	}
 
+	async fn get_notifications(
+		&self,
+		filter: &NotificationFilter,
+	) -> Result<Vec<Log>, ProviderError> {
+		let latest = self.get_block_count().await?.saturating_sub(1);
+		let to = filter.get_to_block().map_or(latest, |to| to.min(latest));
+		let from = filter.get_from_block().unwrap_or(to);
+		let fetch = |index| async move {
+			let block = self.get_block_by_index(index, true).await?;
+			let hashes: Vec<_> = std::iter::once(block.hash)
+				.chain(block.transactions.iter().flatten().map(|tx| tx.hash))
+				.collect();
+			let application_logs: Vec<_> = stream::iter(hashes)
+				.map(|hash| self.get_application_log(hash))
+				.buffered(NOTIFICATION_SCAN_CONCURRENCY)
+				.try_collect()
+				.await?;
+			Ok::<_, ProviderError>((index, block.hash, application_logs))
+		};
+
+		let mut logs = Vec::new();
+		let mut blocks = Box::pin(block_range::fetch_ordered(
+			from..to.saturating_add(1),
+			NOTIFICATION_SCAN_CONCURRENCY,
+			fetch,
+		));
+		while let Some(block) = blocks.next().await {
+			let (block_index, block_hash, application_logs) = block?;
+			for application_log in application_logs {
+				// the application log of the block has the hash of the block
+				let transaction_hash =
+					Some(application_log.transaction_id).filter(|hash| *hash != block_hash);
+				logs.extend(application_log.notifications_matching(filter).map(|notification| {
+					Log {
+						notification: notification.clone(),
+						block_index,
+						block_hash,
+						transaction_hash,
+					}
+				}));
+			}
+		}
+		Ok(logs)
+	}
+
+	async fn subscribe<T, R>(
+		&self,
+		params: T,
+	) -> Result<SubscriptionStream<'_, P, R>, ProviderError>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned + Send + Sync,
+		P: PubsubClient,
+	{
+		let id: U256 = self.request("subscribe", params).await?;
+		SubscriptionStream::new(id, self).map_err(Into::into)
+	}
+
+	async fn subscribe_application_logs(
+		&self,
+		filter: &ApplicationLogFilter,
+	) -> Result<SubscriptionStream<'_, P, ApplicationLog>, ProviderError>
+	where
+		P: PubsubClient,
+	{
+		let stream = self.subscribe(filter.subscription_params()).await?;
+		// events are delivered to every subscription to `transaction_executed`, whatever the
+		// filter of the subscription they were sent for
+		let filter = filter.clone();
+		Ok(stream.with_filter(move |log| filter.matches(log)))
+	}
+
 	async fn get_nep17_balances(&self, script_hash: H160) -> Result<Nep17Balances, ProviderError> {
 		self.request("getnep17balances", [script_hash.to_value()].to_vec()).await
 	}
@@ -669,11 +883,12 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		params: Vec<ContractParameter>,
 		signers: Vec<Signer>,
 	) -> Result<InvocationResult, ProviderError> {
+		let signers: Vec<TransactionSigner> = signers.iter().map(|f| f.into()).collect();
 		let params = vec![
 			contract_hash.to_value(),
 			name.to_value(),
-			serde_json::to_string(&params).unwrap().to_value(),
-			serde_json::to_string(&signers).unwrap().to_value(),
+			params.to_value(),
+			signers.to_value(),
 			true.to_value(),
 		];
 		self.request("invokefunction", params).await
@@ -684,6 +899,7 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		hex: String,
 		signers: Vec<Signer>,
 	) -> Result<InvocationResult, ProviderError> {
+		let signers: Vec<TransactionSigner> = signers.into_iter().map(Into::into).collect();
 		let params = vec![hex.to_value(), signers.to_value(), true.to_value()];
 		self.request("invokescript", params).await
 	}
@@ -746,6 +962,12 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 }
 
 impl<P: JsonRpcClient> Provider<P> {
+	/// Replaces the config of the provider. Clones made before the call keep the previous config.
+	pub fn set_config(&mut self, config: impl Into<SharedConfig>) -> &mut Self {
+		self.config = config.into();
+		self
+	}
+
 	/// Sets the default polling interval for event filters and pending transactions
 	/// (default: 7 seconds)
 	pub fn set_interval<T: Into<Duration>>(&mut self, interval: T) -> &mut Self {
@@ -760,6 +982,86 @@ impl<P: JsonRpcClient> Provider<P> {
 		self.set_interval(interval);
 		self
 	}
+
+	/// Sets the timeout of every request sent by the provider, see [`Provider::request`]
+	pub fn set_timeout<T: Into<Duration>>(&mut self, timeout: T) -> &mut Self {
+		self.timeout = Some(timeout.into());
+		self
+	}
+
+	/// Sets the timeout of every request sent by the provider, see [`Provider::request`]
+	#[must_use]
+	pub fn with_timeout<T: Into<Duration>>(mut self, timeout: T) -> Self {
+		self.set_timeout(timeout);
+		self
+	}
+
+	/// The timeout of the requests, if any
+	pub fn get_timeout(&self) -> Option<Duration> {
+		self.timeout
+	}
+
+	/// Returns the polling interval, falling back to the `polling_interval` of the config
+	pub fn get_interval(&self) -> Duration {
+		self.interval.unwrap_or_else(|| Duration::from_millis(self.polling_interval() as u64))
+	}
+
+	/// Streams the hashes of transactions as they enter the mempool of the node, by polling
+	/// `getrawmempool` every [`Provider::get_interval`].
+	///
+	/// Use [`MempoolWatcher::transactions`] to stream the full transactions instead.
+	pub fn watch_pending_transactions(&self) -> MempoolWatcher<'_, P> {
+		MempoolWatcher::new(self, self.get_interval())
+	}
+
+	/// Reads the contract storage as of the block at `height`, with every value proven against
+	/// the state root of the block, see [`StateServiceClient`].
+	pub fn state_at(&self, height: u32) -> StateServiceClient<'_, P> {
+		StateServiceClient::new(self, height)
+	}
+
+	/// Iterates over the storage entries of the contract `contract_hash` whose keys start with
+	/// `prefix`, fetching them page by page with [`Middleware::find_storage`].
+	pub fn find_storage_iter(&self, contract_hash: H160, prefix: &[u8]) -> StorageIterator<'_, P> {
+		StorageIterator::new(self, contract_hash, prefix)
+	}
+
+	/// Streams the blocks at the heights of `range` in height order, fetching up to
+	/// `concurrency` blocks at once.
+	///
+	/// Requests failing with transient errors, like timeouts, are retried with an exponential
+	/// backoff. Once the retries are exhausted, or if the node returns an error, the error is
+	/// yielded in place of the block and the stream continues with the next height. The stream
+	/// ends early when the [`Shutdown`] of the provider is triggered.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use futures_util::StreamExt;
+	/// use neo_providers::{Http, Provider};
+	/// use std::convert::TryFrom;
+	///
+	/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+	/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+	/// let mut blocks = provider.get_blocks(1_000..2_000, true, 16);
+	/// while let Some(block) = blocks.next().await {
+	///     let block = block?;
+	///     let transactions = block.transactions.map_or(0, |transactions| transactions.len());
+	///     println!("block {} with {transactions} transactions", block.index);
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn get_blocks(
+		&self,
+		range: Range<u32>,
+		full_tx: bool,
+		concurrency: usize,
+	) -> impl Stream<Item = Result<NeoBlock, ProviderError>> + '_ {
+		block_range::fetch_ordered(range, concurrency, move |index| {
+			self.get_block_by_index(index, full_tx)
+		})
+		.take_until(self.shutdown.signal())
+	}
 }
 
 #[cfg(all(feature = "ipc", any(unix, windows)))]
@@ -916,12 +1218,19 @@ pub trait ProviderExt: Sealed {
 	/// The error type that can occur when creating a provider
 	type Error: Debug;
 
-	/// Creates a new instance connected to the given `url`, exit on error
+	/// Creates a new instance connected to the given `url`.
+	///
+	/// # Panics
+	///
+	/// If the connection fails, use [`ProviderExt::try_connect`] to handle the error instead.
 	async fn connect(url: &str) -> Self
 	where
 		Self: Sized,
 	{
-		Self::try_connect(url).await.unwrap()
+		match Self::try_connect(url).await {
+			Ok(provider) => provider,
+			Err(err) => panic!("could not connect to {url}: {err:?}"),
+		}
 	}
 
 	/// Try to create a new `Provider`
@@ -950,24 +1259,24 @@ pub trait ProviderExt: Sealed {
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl ProviderExt for Provider<HttpProvider> {
-	type Error = ParseError;
+	type Error = ProviderError;
 
 	async fn try_connect(url: &str) -> Result<Self, Self::Error>
 	where
 		Self: Sized,
 	{
 		let mut provider = Provider::try_from(url)?;
-		let Some(chain) = provider.get_net_version().await.ok() else { panic!("") };
-		provider.set_network(u32::from_str(&chain).unwrap());
+		let network = provider.get_network_magic().await?;
+		provider.set_network(network);
 
 		Ok(provider)
 	}
 
 	fn set_network(&mut self, network: u32) -> &mut Self {
-		// if let Some(blocktime) = chain {
+		self.config.set_network_magic(network);
 		// use half of the block time
-		self.set_interval(Duration::from_millis(network as u64 / 2));
-		// }
+		let block_interval = self.config().block_interval;
+		self.set_interval(Duration::from_millis(block_interval as u64 / 2));
 		self
 	}
 }
@@ -1004,3 +1313,223 @@ pub fn is_local_endpoint(endpoint: &str) -> bool {
 	}
 	false
 }
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::{
+		core::responses::neo_application_log::Execution, Expectation, ExpectationMode,
+		JsonRpcError, MockProvider,
+	};
+	use neo_codec::encode::NeoSerializable;
+	use neo_types::{invocation_result::Notification, vm_state::VMState};
+	use serde_json::json;
+
+	#[test]
+	fn provider_uses_config() {
+		let config = NeoConfig {
+			polling_interval: 1_000,
+			max_valid_until_block_increment: 100,
+			nns_resolver: H160::repeat_byte(0x11),
+			..Default::default()
+		};
+		let provider = Provider::with_config(MockProvider::new(), config);
+		assert_eq!(provider.nns_resolver(), H160::repeat_byte(0x11));
+		assert_eq!(provider.max_valid_until_block_increment(), 100);
+		assert_eq!(provider.get_interval(), Duration::from_millis(1_000));
+
+		let other = Provider::with_config(MockProvider::new(), provider.shared_config().clone());
+		provider.shared_config().set_polling_interval(2_000);
+		assert_eq!(other.get_interval(), Duration::from_millis(2_000));
+		assert_eq!(
+			other.interval(Duration::from_millis(10)).get_interval(),
+			Duration::from_millis(10)
+		);
+	}
+
+	fn raw_transaction() -> (String, H256) {
+		let tx = Transaction::default();
+		(encoding::base64_encode(tx.to_array()), tx.hash())
+	}
+
+	#[tokio::test]
+	async fn idempotent_send_checks_mempool_before_resending() {
+		let (provider, mock) = Provider::mocked();
+		let provider = provider.with_timeout(Duration::from_millis(20));
+		let (base64, hash) = raw_transaction();
+		// the node receives the transaction, but answers too late
+		mock.expect(
+			Expectation::new("sendrawtransaction")
+				.returning(json!({ "hash": format!("{hash:?}") }))
+				.with_latency(Duration::from_millis(200)),
+		);
+		mock.expect(Expectation::new("getrawmempool").returning(json!({
+			"height": 1,
+			"verified": [format!("{hash:?}")],
+			"unverified": []
+		})));
+
+		let sent = provider.send_raw_transaction_idempotent(base64).await.unwrap();
+		assert_eq!(sent.hash, hash);
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn candidates_are_typed() {
+		let (provider, mock) = Provider::mocked();
+		let key = "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+		mock.expect(Expectation::new("getcandidates").returning(json!([
+			{ "publickey": key, "votes": "4213000", "active": true }
+		])));
+
+		let candidates = provider.get_candidates().await.unwrap();
+		assert_eq!(candidates.len(), 1);
+		assert_eq!(candidates[0].pubkey.get_encoded_compressed_hex(), key);
+		assert_eq!(candidates[0].votes, 4_213_000);
+		assert!(candidates[0].active);
+		assert_eq!(serde_json::to_value(&candidates[0]).unwrap()["publickey"], key);
+	}
+
+	#[tokio::test]
+	async fn idempotent_send_accepts_duplicates() {
+		let (provider, mock) = Provider::mocked();
+		let (base64, hash) = raw_transaction();
+		mock.expect(Expectation::new("sendrawtransaction").with_params([&base64]).returning_error(
+			JsonRpcError {
+				code: -501,
+				message: "Inventory already exists".to_string(),
+				data: None,
+			},
+		));
+
+		let sent = provider.send_raw_transaction_idempotent(base64).await.unwrap();
+		assert_eq!(sent.hash, hash);
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn version_is_requested_once() {
+		let (provider, mock) = Provider::mocked();
+		mock.expect(Expectation::new("getversion").returning(json!({
+			"tcpport": 10333,
+			"nonce": 1,
+			"useragent": "/Neo:3.6.0/",
+			"rpc": { "maxiteratorresultitems": 100, "sessionenabled": true },
+			"protocol": {
+				"network": 860833102,
+				"msperblock": 15000,
+				"maxvaliduntilblockincrement": 5760,
+				"maxtraceableblocks": 2102400,
+				"addressversion": 53,
+				"maxtransactionsperblock": 512,
+				"memorypoolmaxtransactions": 50000,
+				"initialgasdistribution": 5200000000000000
+			}
+		})));
+
+		let clone = provider.clone();
+		assert_eq!(provider.get_network_magic().await.unwrap(), 860833102);
+		assert!(matches!(clone.node_client().await.unwrap(), NodeClient::NeoCli(_)));
+		assert!(!clone.supports(Capability::BlockSysFee).await.unwrap());
+		assert_eq!(mock.request_count("getversion"), 1);
+	}
+
+	fn scanned_block(index: u32, hash: H256, transactions: &[H256]) -> NeoBlock {
+		NeoBlock {
+			hash,
+			size: 0,
+			version: 0,
+			prev_block_hash: H256::zero(),
+			merkle_root_hash: H256::zero(),
+			time: 0,
+			index: index as i32,
+			primary: None,
+			next_consensus: String::new(),
+			witnesses: None,
+			transactions: Some(
+				transactions
+					.iter()
+					.map(|&hash| {
+						TransactionResult::from(&Transaction { hash, ..Default::default() })
+					})
+					.collect(),
+			),
+			confirmations: 0,
+			next_block_hash: None,
+		}
+	}
+
+	fn notification(contract: H160, event_name: &str) -> Notification {
+		Notification {
+			contract,
+			event_name: event_name.to_string(),
+			state: StackItem::Array { value: vec![] },
+		}
+	}
+
+	fn application_log(id: H256, notifications: Vec<Notification>) -> ApplicationLog {
+		let execution = Execution {
+			trigger: "Application".to_string(),
+			state: VMState::Halt,
+			exception: None,
+			gas_consumed: "0".to_string(),
+			stack: vec![],
+			notifications,
+		};
+		ApplicationLog { transaction_id: id, executions: vec![execution] }
+	}
+
+	#[tokio::test]
+	async fn notifications_are_scanned_up_to_the_chain_height() {
+		let mock = MockProvider::with_mode(ExpectationMode::Unordered);
+		let provider = Provider::new(mock.clone());
+		let (gas, neo) = (H160::repeat_byte(1), H160::repeat_byte(2));
+		let (block_1, block_2, tx) =
+			(H256::repeat_byte(1), H256::repeat_byte(2), H256::repeat_byte(3));
+
+		// the chain ends at block 2, so the blocks after it are not requested
+		mock.expect(Expectation::new("getblockcount").returning(3));
+		mock.expect(
+			Expectation::new("getblock").with_params(json!([1, 1])).returning(scanned_block(
+				1,
+				block_1,
+				&[tx],
+			)),
+		);
+		mock.expect(
+			Expectation::new("getblock").with_params(json!([2, 1])).returning(scanned_block(
+				2,
+				block_2,
+				&[],
+			)),
+		);
+		let logs = [
+			application_log(
+				block_1,
+				vec![notification(gas, "Transfer"), notification(neo, "Transfer")],
+			),
+			application_log(tx, vec![notification(gas, "Approval"), notification(gas, "Transfer")]),
+			application_log(block_2, vec![notification(neo, "Transfer")]),
+		];
+		for log in logs {
+			mock.expect(
+				Expectation::new("getapplicationlog")
+					.with_params([log.transaction_id.to_value()])
+					.returning(log),
+			);
+		}
+
+		let filter = NotificationFilter::new().contract(gas).event("Transfer").blocks(1..=10);
+		let found = provider.get_notifications(&filter).await.unwrap();
+		mock.assert_expectations_met().unwrap();
+
+		assert!(found.iter().all(|log| log.notification == notification(gas, "Transfer")));
+		// the notification of the block itself has no transaction
+		let found: Vec<_> = found
+			.iter()
+			.map(|log| (log.block_index, log.block_hash, log.transaction_hash))
+			.collect();
+		assert_eq!(found, vec![(1, block_1, None), (1, block_1, Some(tx))]);
+	}
+}
@@ -27,7 +27,7 @@ pub trait PubsubClient: JsonRpcClient {
 
 #[must_use = "subscriptions do nothing unless you stream them"]
 #[pin_project(PinnedDrop)]
-/// Streams data from an installed filter via `neo_subscribe`
+/// Streams the events of a subscription made via `subscribe`
 pub struct SubscriptionStream<'a, P: PubsubClient, R: DeserializeOwned> {
 	/// The subscription's installed id on the neo node
 	pub id: U256,
@@ -40,6 +40,8 @@ pub struct SubscriptionStream<'a, P: PubsubClient, R: DeserializeOwned> {
 	rx: P::NotificationStream,
 
 	ret: PhantomData<R>,
+
+	filter: Option<Box<dyn Fn(&R) -> bool + Send + Sync>>,
 }
 
 impl<'a, P, R> SubscriptionStream<'a, P, R>
@@ -57,7 +59,22 @@ where
 	pub fn new(id: U256, provider: &'a Provider<P>) -> Result<Self, P::Error> {
 		// Call the underlying PubsubClient's subscribe
 		let rx = provider.as_ref().subscribe(id)?;
-		Ok(Self { id, provider, rx, ret: PhantomData, loaded_elements: VecDeque::new() })
+		Ok(Self {
+			id,
+			provider,
+			rx,
+			ret: PhantomData,
+			loaded_elements: VecDeque::new(),
+			filter: None,
+		})
+	}
+
+	/// Drops the received items for which `filter` returns `false`, e.g. to filter locally what
+	/// the node cannot filter. Items in the loaded elements buffer are not filtered.
+	#[must_use]
+	pub fn with_filter(mut self, filter: impl Fn(&R) -> bool + Send + Sync + 'static) -> Self {
+		self.filter = Some(Box::new(filter));
+		self
 	}
 
 	/// Set the loaded elements buffer. This buffer contains logs waiting for
@@ -91,8 +108,10 @@ where
 		let mut this = self.project();
 		loop {
 			return match futures_util::ready!(this.rx.as_mut().poll_next(ctx)) {
-				Some(item) => match serde_json::from_str(item.get()) {
-					Ok(res) => Poll::Ready(Some(res)),
+				Some(item) => match serde_json::from_str::<R>(item.get()) {
+					Ok(res) if this.filter.as_ref().map_or(true, |filter| filter(&res)) =>
+						Poll::Ready(Some(res)),
+					Ok(_) => continue,
 					Err(err) => {
 						error!("failed to deserialize item {:?}", err);
 						continue
@@ -1,8 +1,7 @@
 // Code adapted from: https://github.com/althea-net/guac_rs/tree/master/web3/src/jsonrpc
 
-use base64::{engine::general_purpose, Engine};
 use jsonwebtoken::{encode, errors::Error, get_current_timestamp, Algorithm, EncodingKey, Header};
-use neo_types::Bytes;
+use neo_types::{encoding, Bytes};
 use primitive_types::U256;
 use serde::{
 	de::{self, MapAccess, Unexpected, Visitor},
@@ -13,7 +12,7 @@ use std::fmt;
 use thiserror::Error;
 
 /// A JSON-RPC 2.0 error
-#[derive(Deserialize, Debug, Clone, Error)]
+#[derive(Serialize, Deserialize, Debug, Clone, Error)]
 pub struct JsonRpcError {
 	/// The error code
 	pub code: i64,
@@ -61,6 +60,18 @@ impl JsonRpcError {
 			.then(|| self.data.as_ref().and_then(spelunk_revert).unwrap_or_default())
 	}
 
+	/// Determine if the error of a `sendrawtransaction` request says that the node already knows
+	/// the transaction, because it is in the mempool or on chain
+	pub fn is_already_known(&self) -> bool {
+		// since Neo 3.6, `AlreadyExists` and `AlreadyInPool` have codes of their own; before, all
+		// verification failures were -500 with the verify result as message
+		if matches!(self.code, -501 | -503) {
+			return true
+		}
+		let message = self.message.to_ascii_lowercase().replace(' ', "");
+		message.contains("alreadyexists") || message.contains("alreadyinpool")
+	}
+
 	// Decode revert data (if any) into a decodeable type
 	// pub fn decode_revert_data<E: AbiDecode>(&self) -> Option<E> {
 	// 	E::decode(&self.as_revert_data()?).ok()
@@ -242,7 +253,7 @@ impl Authorization {
 	pub fn basic(username: impl AsRef<str>, password: impl AsRef<str>) -> Self {
 		let username = username.as_ref();
 		let password = password.as_ref();
-		let auth_secret = general_purpose::STANDARD.encode(format!("{username}:{password}"));
+		let auth_secret = encoding::base64_encode(format!("{username}:{password}"));
 		Self::Basic(auth_secret)
 	}
 
@@ -3,17 +3,40 @@
 use super::common::{Authorization, JsonRpcError, Request, Response};
 use crate::{errors::ProviderError, JsonRpcClient};
 use async_trait::async_trait;
-use reqwest::{header, header::HeaderValue, Client, Error as ReqwestError};
+use once_cell::sync::Lazy;
+use reqwest::{
+	header,
+	header::{HeaderMap, HeaderName, HeaderValue},
+	Client, ClientBuilder, Error as ReqwestError,
+};
 use serde::{de::DeserializeOwned, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
 use std::{
+	fmt,
 	str::FromStr,
-	sync::atomic::{AtomicU64, Ordering},
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
 };
 use thiserror::Error;
 use url::Url;
 
+/// The client of the [`Http`](Provider) transports created without a custom client, so their
+/// connections are pooled together.
+static DEFAULT_CLIENT: Lazy<Client> = Lazy::new(Client::new);
+
 /// A low-level JSON-RPC Client over HTTP.
 ///
+/// Clones share the connection pool of the underlying `reqwest` client, as do all transports
+/// created with [`Http::new`](Provider::new) or from a URL. Tune the pool with
+/// [`Http::builder`](Provider::builder).
+///
+/// On `wasm32` targets requests are sent with the browser's `fetch` API. Disable the default
+/// features there, as the `rustls` and `openssl` TLS backends do not build for the browser, which
+/// handles TLS itself.
+///
 /// # Example
 ///
 /// ```no_run
@@ -31,6 +54,18 @@ pub struct Provider {
 	id: AtomicU64,
 	client: Client,
 	url: Url,
+	request_hook: Option<RequestHook>,
+}
+
+/// Adds headers to a request before it is sent, given the JSON-RPC method of the request, see
+/// [`HttpBuilder::on_request`].
+#[derive(Clone)]
+struct RequestHook(Arc<dyn Fn(&str, &mut HeaderMap) + Send + Sync>);
+
+impl fmt::Debug for RequestHook {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("RequestHook")
+	}
 }
 
 #[derive(Error, Debug)]
@@ -90,9 +125,24 @@ impl JsonRpcClient for Provider {
 		params: T,
 	) -> Result<R, ClientError> {
 		let next_id = self.id.fetch_add(1, Ordering::SeqCst);
-		let payload = Request::new(next_id, method, params);
+		self.fetch_with_id(next_id, method, params).await
+	}
 
-		let res = self.client.post(self.url.as_ref()).json(&payload).send().await?;
+	async fn fetch_with_id<T: Serialize + Send + Sync, R: DeserializeOwned>(
+		&self,
+		id: u64,
+		method: &str,
+		params: T,
+	) -> Result<R, ClientError> {
+		let payload = Request::new(id, method, params);
+
+		let mut request = self.client.post(self.url.as_ref()).json(&payload);
+		if let Some(RequestHook(hook)) = &self.request_hook {
+			let mut headers = HeaderMap::new();
+			hook(method, &mut headers);
+			request = request.headers(headers);
+		}
+		let res = request.send().await?;
 		let body = res.bytes().await?;
 
 		let raw = match serde_json::from_slice(&body) {
@@ -132,7 +182,26 @@ impl Provider {
 	/// let provider = Http::new(url);
 	/// ```
 	pub fn new(url: impl Into<Url>) -> Self {
-		Self::new_with_client(url, Client::new())
+		Self::new_with_client(url, DEFAULT_CLIENT.clone())
+	}
+
+	/// A builder for a client with authentication or custom headers
+	///
+	/// # Example
+	///
+	/// ```
+	/// use neo_providers::Http;
+	/// use url::Url;
+	///
+	/// let url = Url::parse("https://rpc.example.com").unwrap();
+	/// let provider = Http::builder(url)
+	/// 	.bearer_auth("secret-token")
+	/// 	.header("x-api-key", "my-key")
+	/// 	.build()
+	/// 	.unwrap();
+	/// ```
+	pub fn builder(url: impl Into<Url>) -> HttpBuilder {
+		HttpBuilder::new(url)
 	}
 
 	/// The Url to which requests are made
@@ -145,6 +214,11 @@ impl Provider {
 		&mut self.url
 	}
 
+	/// The underlying `reqwest` client. Cloning it shares its connection pool.
+	pub fn client(&self) -> &Client {
+		&self.client
+	}
+
 	/// Initializes a new HTTP Client with authentication
 	///
 	/// # Example
@@ -184,7 +258,7 @@ impl Provider {
 	/// let provider = Http::new_with_client(url, client);
 	/// ```
 	pub fn new_with_client(url: impl Into<Url>, client: reqwest::Client) -> Self {
-		Self { id: AtomicU64::new(1), client, url: url.into() }
+		Self { id: AtomicU64::new(1), client, url: url.into(), request_hook: None }
 	}
 }
 
@@ -199,7 +273,183 @@ impl FromStr for Provider {
 
 impl Clone for Provider {
 	fn clone(&self) -> Self {
-		Self { id: AtomicU64::new(1), client: self.client.clone(), url: self.url.clone() }
+		Self {
+			id: AtomicU64::new(1),
+			client: self.client.clone(),
+			url: self.url.clone(),
+			request_hook: self.request_hook.clone(),
+		}
+	}
+}
+
+/// Builder for an [`Http`](Provider) client sending credentials and custom headers.
+///
+/// Credentials are sent in headers marked as sensitive, so they are not logged by `reqwest`, and
+/// never have to be part of the URL.
+#[derive(Debug)]
+pub struct HttpBuilder {
+	url: Url,
+	client: ClientBuilder,
+	headers: Vec<(String, String, bool)>,
+	request_hook: Option<RequestHook>,
+}
+
+impl HttpBuilder {
+	/// A builder for a client sending its requests to `url`
+	pub fn new(url: impl Into<Url>) -> Self {
+		Self { url: url.into(), client: Client::builder(), headers: Vec::new(), request_hook: None }
+	}
+
+	/// Sends the `Authorization` header `auth` with every request
+	pub fn auth(self, auth: Authorization) -> Self {
+		self.sensitive_header(header::AUTHORIZATION.as_str(), auth.to_string())
+	}
+
+	/// Authenticates every request with HTTP basic auth
+	pub fn basic_auth(self, username: impl AsRef<str>, password: impl AsRef<str>) -> Self {
+		self.auth(Authorization::basic(username, password))
+	}
+
+	/// Authenticates every request with the bearer token `token`
+	pub fn bearer_auth(self, token: impl Into<String>) -> Self {
+		self.auth(Authorization::bearer(token))
+	}
+
+	/// Sends the header `name` with every request
+	pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+		self.headers.push((name.into(), value.into(), false));
+		self
+	}
+
+	/// Sends the header `name` with every request, hiding its value from logs, e.g. for API
+	/// keys
+	pub fn sensitive_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+		self.headers.push((name.into(), value.into(), true));
+		self
+	}
+
+	/// Calls `hook` before each request with the JSON-RPC method of the request, to add headers
+	/// that change between requests, like short-lived tokens or request signatures.
+	///
+	/// Headers added by the hook replace static headers of the same name.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use neo_providers::Http;
+	/// use reqwest::header::HeaderValue;
+	/// use url::Url;
+	///
+	/// # fn current_token() -> String { String::new() }
+	/// let url = Url::parse("https://rpc.example.com").unwrap();
+	/// let provider = Http::builder(url)
+	/// 	.on_request(|_method, headers| {
+	/// 		let token = HeaderValue::from_str(&format!("Bearer {}", current_token())).unwrap();
+	/// 		headers.insert(reqwest::header::AUTHORIZATION, token);
+	/// 	})
+	/// 	.build()
+	/// 	.unwrap();
+	/// ```
+	pub fn on_request<F>(mut self, hook: F) -> Self
+	where
+		F: Fn(&str, &mut HeaderMap) + Send + Sync + 'static,
+	{
+		self.request_hook = Some(RequestHook(Arc::new(hook)));
+		self
+	}
+
+	/// Customizes the underlying `reqwest` client, e.g. its timeouts or proxy
+	pub fn client_builder(mut self, client: ClientBuilder) -> Self {
+		self.client = client;
+		self
+	}
+
+	/// Connects with the TLS configuration `tls`, e.g. to present a client certificate or to
+	/// trust a private certificate authority
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn tls(mut self, tls: super::TlsConfig) -> Self {
+		self.client = tls.apply(self.client);
+		self
+	}
+
+	/// Keeps at most `max` idle connections per host in the pool, unlimited by default
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+		self.client = self.client.pool_max_idle_per_host(max);
+		self
+	}
+
+	/// Closes pooled connections idle for longer than `timeout`, or never with `None`. Defaults to
+	/// 90 seconds.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.client = self.client.pool_idle_timeout(timeout);
+		self
+	}
+
+	/// Speaks HTTP/2 without negotiating it first, multiplexing concurrent requests over one
+	/// connection. The node, or the proxy in front of it, must support HTTP/2.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn http2_prior_knowledge(mut self) -> Self {
+		self.client = self.client.http2_prior_knowledge();
+		self
+	}
+
+	/// Sends HTTP/2 pings every `interval` to keep connections alive, closing them if a ping is
+	/// not answered within `timeout`. Pings are also sent on idle connections.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn http2_keep_alive(mut self, interval: Duration, timeout: Duration) -> Self {
+		self.client = self
+			.client
+			.http2_keep_alive_interval(interval)
+			.http2_keep_alive_timeout(timeout)
+			.http2_keep_alive_while_idle(true);
+		self
+	}
+
+	/// Sets `TCP_NODELAY` on connections, disabling Nagle's algorithm for lower latency of small
+	/// requests
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn tcp_nodelay(mut self, enabled: bool) -> Self {
+		self.client = self.client.tcp_nodelay(enabled);
+		self
+	}
+
+	/// Sends TCP keep-alive probes on idle connections every `interval`, or never with `None`
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn tcp_keepalive(mut self, interval: Option<Duration>) -> Self {
+		self.client = self.client.tcp_keepalive(interval);
+		self
+	}
+
+	/// Sends the requests through `proxy`. Proxies from the environment, e.g. `HTTPS_PROXY`,
+	/// are used otherwise.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+		self.client = self.client.proxy(proxy);
+		self
+	}
+
+	/// Ignores the proxies of the environment
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn no_proxy(mut self) -> Self {
+		self.client = self.client.no_proxy();
+		self
+	}
+
+	/// Builds the client
+	pub fn build(self) -> Result<Provider, HttpClientError> {
+		let mut headers = HeaderMap::new();
+		for (name, value, sensitive) in self.headers {
+			let name = HeaderName::from_bytes(name.as_bytes())?;
+			let mut value = HeaderValue::from_str(&value)?;
+			value.set_sensitive(sensitive);
+			headers.insert(name, value);
+		}
+		let client = self.client.default_headers(headers).build()?;
+		let mut provider = Provider::new_with_client(self.url, client);
+		provider.request_hook = self.request_hook;
+		Ok(provider)
 	}
 }
 
@@ -210,7 +460,47 @@ pub enum HttpClientError {
 	#[error(transparent)]
 	InvalidHeader(#[from] header::InvalidHeaderValue),
 
+	/// Thrown if a header name is invalid
+	#[error(transparent)]
+	InvalidHeaderName(#[from] header::InvalidHeaderName),
+
 	/// Thrown if unable to build client
 	#[error(transparent)]
 	ClientBuild(#[from] reqwest::Error),
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn builds_with_headers() {
+		let url = Url::parse("http://localhost:10332").unwrap();
+		let provider = Provider::builder(url.clone())
+			.basic_auth("admin", "password")
+			.header("x-api-key", "key")
+			.on_request(|method, headers| {
+				headers.insert("x-rpc-method", HeaderValue::from_str(method).unwrap());
+			})
+			.build()
+			.unwrap();
+		assert!(provider.clone().request_hook.is_some());
+
+		let invalid = Provider::builder(url).header("invalid header", "value").build();
+		assert!(matches!(invalid, Err(HttpClientError::InvalidHeaderName(_))));
+	}
+
+	#[test]
+	fn builds_with_pool_settings() {
+		let url = Url::parse("http://localhost:10332").unwrap();
+		Provider::builder(url)
+			.pool_max_idle_per_host(8)
+			.pool_idle_timeout(Some(Duration::from_secs(30)))
+			.http2_keep_alive(Duration::from_secs(10), Duration::from_secs(5))
+			.tcp_nodelay(true)
+			.tcp_keepalive(Some(Duration::from_secs(60)))
+			.proxy(reqwest::Proxy::http("http://proxy.example.com:8080").unwrap())
+			.build()
+			.unwrap();
+	}
+}
@@ -1,5 +1,7 @@
-use crate::{JsonRpcClient, ProviderError};
+use crate::{utils::lock, JsonRpcClient, ProviderError};
 use async_trait::async_trait;
+use futures_timer::Delay;
+use instant::Duration;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 use std::{
@@ -27,13 +29,220 @@ pub enum MockResponse {
 
 	/// Error response with a `JsonRpcError`.
 	Error(super::JsonRpcError),
+
+	/// Transport failure, e.g. a dropped connection, that never reached the node.
+	TransportError(String),
+}
+
+/// Matches the method of a request, see [`Expectation`].
+///
+/// Converting a `&str` yields an exact matcher, unless the pattern is `*`, matching every method,
+/// or ends with `*`, matching every method with the given prefix, e.g. `get*`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MethodMatcher {
+	/// Matches only the given method
+	Exact(String),
+	/// Matches every method starting with the given prefix
+	Prefix(String),
+	/// Matches every method
+	Any,
+}
+
+impl MethodMatcher {
+	/// Returns `true` if `method` matches
+	pub fn matches(&self, method: &str) -> bool {
+		match self {
+			MethodMatcher::Exact(expected) => expected == method,
+			MethodMatcher::Prefix(prefix) => method.starts_with(prefix.as_str()),
+			MethodMatcher::Any => true,
+		}
+	}
+}
+
+impl From<&str> for MethodMatcher {
+	fn from(pattern: &str) -> Self {
+		match pattern.strip_suffix('*') {
+			Some("") => MethodMatcher::Any,
+			Some(prefix) => MethodMatcher::Prefix(prefix.to_string()),
+			None => MethodMatcher::Exact(pattern.to_string()),
+		}
+	}
+}
+
+impl From<String> for MethodMatcher {
+	fn from(pattern: String) -> Self {
+		pattern.as_str().into()
+	}
+}
+
+/// A scripted response of a [`MockProvider`] to the requests matching a method pattern and,
+/// optionally, exact params.
+///
+/// ```
+/// use neo_providers::{Expectation, MockProvider};
+/// use std::time::Duration;
+///
+/// let mock = MockProvider::new();
+/// mock.expect(Expectation::new("getblockcount").returning(100u32).always());
+/// mock.expect(
+/// 	Expectation::new("getblockhash")
+/// 		.with_params([99u32])
+/// 		.returning("0x00")
+/// 		.with_latency(Duration::from_millis(50)),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Expectation {
+	method: MethodMatcher,
+	params: Option<Value>,
+	response: MockResponse,
+	latency: Option<Duration>,
+	/// How many more requests are answered, `None` for every matching request
+	remaining: Option<usize>,
+}
+
+impl Expectation {
+	/// Expects a single request of a method matching `method`, answered with `null`.
+	pub fn new(method: impl Into<MethodMatcher>) -> Self {
+		Self {
+			method: method.into(),
+			params: None,
+			response: MockResponse::Value(Value::Null),
+			latency: None,
+			remaining: Some(1),
+		}
+	}
+
+	/// Only matches requests with exactly these params.
+	#[must_use]
+	pub fn with_params<T: Serialize>(mut self, params: T) -> Self {
+		self.params = Some(serde_json::to_value(params).expect("could not serialize params"));
+		self
+	}
+
+	/// Answers with `value`.
+	#[must_use]
+	pub fn returning<T: Serialize>(self, value: T) -> Self {
+		let value = serde_json::to_value(value).expect("could not serialize response");
+		self.responding(MockResponse::Value(value))
+	}
+
+	/// Answers with the JSON-RPC error `error`.
+	#[must_use]
+	pub fn returning_error(self, error: super::JsonRpcError) -> Self {
+		self.responding(MockResponse::Error(error))
+	}
+
+	/// Fails the request as if the connection to the node was lost.
+	#[must_use]
+	pub fn failing(self, message: impl Into<String>) -> Self {
+		self.responding(MockResponse::TransportError(message.into()))
+	}
+
+	/// Answers with `response`.
+	#[must_use]
+	pub fn responding(mut self, response: MockResponse) -> Self {
+		self.response = response;
+		self
+	}
+
+	/// Delays the response by `latency`, in addition to the latency of the provider.
+	#[must_use]
+	pub fn with_latency(mut self, latency: Duration) -> Self {
+		self.latency = Some(latency);
+		self
+	}
+
+	/// Answers the next `times` matching requests.
+	///
+	/// # Panics
+	///
+	/// If `times` is 0, as the expectation could never answer a request.
+	#[must_use]
+	pub fn times(mut self, times: usize) -> Self {
+		assert!(times > 0, "an expectation must answer at least one request");
+		self.remaining = Some(times);
+		self
+	}
+
+	/// Answers every matching request. The expectation never counts as unmet.
+	#[must_use]
+	pub fn always(mut self) -> Self {
+		self.remaining = None;
+		self
+	}
+
+	fn matches(&self, method: &str, params: &MockParams) -> bool {
+		self.method.matches(method)
+			&& match (&self.params, params) {
+				(None, _) => true,
+				(Some(expected), MockParams::Value(params)) => expected == params,
+				(Some(_), MockParams::Zst) => false,
+			}
+	}
+
+	fn is_met(&self) -> bool {
+		!matches!(self.remaining, Some(remaining) if remaining > 0)
+	}
+}
+
+/// The order in which the expectations of a [`MockProvider`] must be met
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExpectationMode {
+	/// Every request must match the oldest unmet expectation
+	#[default]
+	Ordered,
+	/// Every request is answered by the oldest expectation it matches
+	Unordered,
+}
+
+#[derive(Debug, Default)]
+struct Expectations {
+	mode: ExpectationMode,
+	queue: VecDeque<Expectation>,
+	latency: Option<Duration>,
+}
+
+impl Expectations {
+	/// Consumes the expectation answering the request, if any.
+	fn answer(&mut self, method: &str, params: &MockParams) -> Option<(MockResponse, Duration)> {
+		let index = match self.mode {
+			ExpectationMode::Ordered => {
+				// expectations answering every request never block the ones after them
+				let index = self.queue.iter().position(|e| e.remaining.is_some());
+				let index = index.filter(|&i| self.queue[i].matches(method, params));
+				index.or_else(|| {
+					self.queue
+						.iter()
+						.position(|e| e.remaining.is_none() && e.matches(method, params))
+				})?
+			},
+			ExpectationMode::Unordered =>
+				self.queue.iter().position(|e| e.matches(method, params))?,
+		};
+		let expectation = &mut self.queue[index];
+		let response = expectation.response.clone();
+		let latency = self.latency.unwrap_or_default() + expectation.latency.unwrap_or_default();
+		if let Some(remaining) = &mut expectation.remaining {
+			*remaining -= 1;
+			if *remaining == 0 {
+				self.queue.remove(index);
+			}
+		}
+		Some((response, latency))
+	}
 }
 
 #[derive(Clone, Debug)]
 /// Mock transport used in test environments.
+///
+/// Responses are either pushed with [`MockProvider::push`] and returned in reverse order,
+/// regardless of the request, or scripted with [`MockProvider::expect`]. While expectations are
+/// pending, a request that matches none of them fails with [`MockError::UnexpectedRequest`].
 pub struct MockProvider {
 	requests: Arc<Mutex<VecDeque<(String, MockParams)>>>,
 	responses: Arc<Mutex<VecDeque<MockResponse>>>,
+	expectations: Arc<Mutex<Expectations>>,
 }
 
 impl Default for MockProvider {
@@ -59,15 +268,32 @@ impl JsonRpcClient for MockProvider {
 		} else {
 			MockParams::Value(serde_json::to_value(params)?)
 		};
-		self.requests.lock().unwrap().push_back((method.to_owned(), params));
-		let mut data = self.responses.lock().unwrap();
-		let element = data.pop_back().ok_or(MockError::EmptyResponses)?;
+		let (element, latency) = {
+			let mut expectations = lock(&self.expectations);
+			let answer = expectations.answer(method, &params);
+			let has_expectations = !expectations.queue.is_empty();
+			let latency = expectations.latency;
+			lock(&self.requests).push_back((method.to_owned(), params));
+			match answer {
+				Some(answer) => answer,
+				None if has_expectations =>
+					return Err(MockError::UnexpectedRequest(method.to_owned())),
+				None => {
+					let element = lock(&self.responses).pop_back();
+					(element.ok_or(MockError::EmptyResponses)?, latency.unwrap_or_default())
+				},
+			}
+		};
+		if !latency.is_zero() {
+			Delay::new(latency).await;
+		}
 		match element {
 			MockResponse::Value(value) => {
 				let res: R = serde_json::from_value(value)?;
 				Ok(res)
 			},
 			MockResponse::Error(error) => Err(MockError::JsonRpcError(error)),
+			MockResponse::TransportError(message) => Err(MockError::TransportError(message)),
 		}
 	}
 }
@@ -79,7 +305,7 @@ impl MockProvider {
 		method: &str,
 		data: T,
 	) -> Result<(), MockError> {
-		let (m, inp) = self.requests.lock().unwrap().pop_front().ok_or(MockError::EmptyRequests)?;
+		let (m, inp) = lock(&self.requests).pop_front().ok_or(MockError::EmptyRequests)?;
 		assert_eq!(m, method);
 		assert!(!matches!(inp, MockParams::Value(serde_json::Value::Null)));
 		if std::mem::size_of::<T>() == 0 {
@@ -98,19 +324,59 @@ impl MockProvider {
 		Self {
 			requests: Arc::new(Mutex::new(VecDeque::new())),
 			responses: Arc::new(Mutex::new(VecDeque::new())),
+			expectations: Arc::new(Mutex::new(Expectations::default())),
 		}
 	}
 
+	/// Instantiates a mock transport whose expectations are met in the order of `mode`
+	pub fn with_mode(mode: ExpectationMode) -> Self {
+		let mock = Self::new();
+		lock(&mock.expectations).mode = mode;
+		mock
+	}
+
 	/// Pushes the data to the responses
 	pub fn push<T: Serialize + Send + Sync, K: Borrow<T>>(&self, data: K) -> Result<(), MockError> {
 		let value = serde_json::to_value(data.borrow())?;
-		self.responses.lock().unwrap().push_back(MockResponse::Value(value));
+		lock(&self.responses).push_back(MockResponse::Value(value));
 		Ok(())
 	}
 
 	/// Pushes the data or error to the responses
 	pub fn push_response(&self, response: MockResponse) {
-		self.responses.lock().unwrap().push_back(response);
+		lock(&self.responses).push_back(response);
+	}
+
+	/// Registers an expectation, answered after the expectations registered before it
+	pub fn expect(&self, expectation: Expectation) {
+		lock(&self.expectations).queue.push_back(expectation);
+	}
+
+	/// Delays every response by `latency`
+	pub fn set_latency(&self, latency: Duration) {
+		lock(&self.expectations).latency = Some(latency);
+	}
+
+	/// Checks that every expectation was met, except those answering every request
+	pub fn assert_expectations_met(&self) -> Result<(), MockError> {
+		let expectations = lock(&self.expectations);
+		let unmet: Vec<_> = expectations
+			.queue
+			.iter()
+			.filter(|e| !e.is_met())
+			.map(|e| format!("{:?}", e.method))
+			.collect();
+		if !unmet.is_empty() {
+			return Err(MockError::UnmetExpectations(unmet))
+		}
+		Ok(())
+	}
+
+	/// Number of submitted requests of methods matching `method`, that were not checked with
+	/// [`MockProvider::assert_request`] yet
+	pub fn request_count(&self, method: impl Into<MethodMatcher>) -> usize {
+		let method = method.into();
+		lock(&self.requests).iter().filter(|(m, _)| method.matches(m)).count()
 	}
 }
 
@@ -132,6 +398,31 @@ pub enum MockError {
 	/// Custom JsonRpcError
 	#[error("JSON-RPC error: {0}")]
 	JsonRpcError(super::JsonRpcError),
+
+	/// Injected transport failure, see [`MockResponse::TransportError`]
+	#[error("transport error: {0}")]
+	TransportError(String),
+
+	/// A request matched none of the registered expectations
+	#[error("unexpected request: {0}")]
+	UnexpectedRequest(String),
+
+	/// Expectations were not met by the submitted requests
+	#[error("unmet expectations: {0:?}")]
+	UnmetExpectations(Vec<String>),
+
+	/// The requested block time is not later than the current block time
+	#[error("block time {timestamp} must be later than the current block time {current}")]
+	InvalidBlockTime {
+		/// The requested block time
+		timestamp: u64,
+		/// The time of the current block
+		current: u64,
+	},
+
+	/// No checkpoint was created with the given name
+	#[error("unknown checkpoint: {0}")]
+	UnknownCheckpoint(String),
 }
 
 impl crate::RpcError for MockError {
@@ -171,6 +462,12 @@ mod tests {
 		assert_eq!(block.as_u64(), 12);
 	}
 
+	#[test]
+	#[should_panic(expected = "at least one request")]
+	fn rejects_expectations_answering_no_request() {
+		let _ = Expectation::new("getblockcount").times(0);
+	}
+
 	#[tokio::test]
 	async fn empty_responses() {
 		let mock = MockProvider::new();
@@ -214,6 +511,56 @@ mod tests {
 		};
 	}
 
+	#[tokio::test]
+	async fn answers_ordered_expectations() {
+		let mock = MockProvider::new();
+		mock.expect(Expectation::new("getversion").returning("3.6.0").always());
+		mock.expect(Expectation::new("getblockhash").with_params([1u32]).returning("0x01"));
+		mock.expect(Expectation::new("getblock*").failing("connection reset"));
+
+		let hash: String = mock.fetch("getblockhash", [1u32]).await.unwrap();
+		assert_eq!(hash, "0x01");
+		let version: String = mock.fetch("getversion", ()).await.unwrap();
+		assert_eq!(version, "3.6.0");
+		let err = mock.fetch::<_, String>("getblockhash", [1u32]).await.unwrap_err();
+		assert!(matches!(err, MockError::TransportError(_)));
+		let err = mock.fetch::<_, String>("getblockcount", ()).await.unwrap_err();
+		assert!(matches!(err, MockError::UnexpectedRequest(_)));
+
+		mock.assert_expectations_met().unwrap();
+		assert_eq!(mock.request_count("getblock*"), 3);
+	}
+
+	#[tokio::test]
+	async fn answers_unordered_expectations() {
+		let mock = MockProvider::with_mode(ExpectationMode::Unordered);
+		mock.expect(Expectation::new("getblockhash").returning("0x01"));
+		mock.expect(Expectation::new("getblockcount").returning(2u32).times(2));
+
+		let count: u32 = mock.fetch("getblockcount", ()).await.unwrap();
+		assert_eq!(count, 2);
+		let _: String = mock.fetch("getblockhash", [1u32]).await.unwrap();
+		assert!(matches!(
+			mock.assert_expectations_met(),
+			Err(MockError::UnmetExpectations(unmet)) if unmet.len() == 1
+		));
+	}
+
+	#[tokio::test]
+	async fn injects_latency() {
+		let mock = MockProvider::new();
+		mock.set_latency(Duration::from_millis(20));
+		mock.expect(
+			Expectation::new("getblockcount")
+				.returning(1u32)
+				.with_latency(Duration::from_millis(20)),
+		);
+
+		let start = instant::Instant::now();
+		let _: u32 = mock.fetch("getblockcount", ()).await.unwrap();
+		assert!(start.elapsed() >= Duration::from_millis(40));
+	}
+
 	#[tokio::test]
 	async fn composes_with_provider() {
 		let (provider, mock) = crate::Provider::mocked();
@@ -0,0 +1,186 @@
+use crate::{utils::lock, ChainController, JsonRpcClient, MockError, MockProvider};
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+/// Chain state tracked by [`MockNeoNode`].
+#[derive(Clone, Debug)]
+struct MockChain {
+	/// Timestamp of every block, starting with the genesis block
+	block_times: Vec<u64>,
+	/// Timestamp of the next block, if set explicitly
+	next_block_time: Option<u64>,
+}
+
+impl MockChain {
+	fn new(genesis_time: u64) -> Self {
+		Self { block_times: vec![genesis_time], next_block_time: None }
+	}
+}
+
+/// Mock transport that simulates block production, for testing time-dependent logic.
+///
+/// The node keeps track of the block height and block timestamps and can be driven through
+/// [`ChainController`]. It answers `getblockcount` from its own state; every
+/// other request is forwarded to an inner [`MockProvider`], so responses can be scripted as usual.
+#[derive(Clone, Debug)]
+pub struct MockNeoNode {
+	chain: Arc<Mutex<MockChain>>,
+	checkpoints: Arc<Mutex<HashMap<String, MockChain>>>,
+	genesis_time: u64,
+	block_interval: u64,
+	mock: MockProvider,
+}
+
+impl Default for MockNeoNode {
+	fn default() -> Self {
+		Self::new(0)
+	}
+}
+
+impl MockNeoNode {
+	/// Default time between two blocks in milliseconds, matching the Neo N3 mainnet.
+	pub const DEFAULT_BLOCK_INTERVAL: u64 = 15_000;
+
+	/// Instantiates a node that only holds a genesis block with timestamp `genesis_time`.
+	pub fn new(genesis_time: u64) -> Self {
+		Self {
+			chain: Arc::new(Mutex::new(MockChain::new(genesis_time))),
+			checkpoints: Arc::new(Mutex::new(HashMap::new())),
+			genesis_time,
+			block_interval: Self::DEFAULT_BLOCK_INTERVAL,
+			mock: MockProvider::new(),
+		}
+	}
+
+	/// Sets the time between two blocks whose timestamp was not set explicitly.
+	pub fn with_block_interval(mut self, block_interval: u64) -> Self {
+		self.block_interval = block_interval;
+		self
+	}
+
+	/// The mock transport that answers requests the node does not handle itself
+	pub fn mock(&self) -> &MockProvider {
+		&self.mock
+	}
+
+	/// The number of blocks in the chain, including the genesis block
+	pub fn block_count(&self) -> u32 {
+		lock(&self.chain).block_times.len() as u32
+	}
+
+	/// The timestamp of the block at `index`, if it exists
+	pub fn block_time(&self, index: u32) -> Option<u64> {
+		lock(&self.chain).block_times.get(index as usize).copied()
+	}
+
+	/// The timestamp of the latest block
+	pub fn current_time(&self) -> u64 {
+		*lock(&self.chain).block_times.last().expect("chain has a genesis block")
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl JsonRpcClient for MockNeoNode {
+	type Error = MockError;
+
+	async fn fetch<T: Serialize + Send + Sync, R: DeserializeOwned>(
+		&self,
+		method: &str,
+		params: T,
+	) -> Result<R, MockError> {
+		match method {
+			"getblockcount" => Ok(serde_json::from_value(Value::from(self.block_count()))?),
+			_ => self.mock.fetch(method, params).await,
+		}
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ChainController for MockNeoNode {
+	type Error = MockError;
+
+	async fn advance_blocks(&self, count: u32) -> Result<(), MockError> {
+		let mut chain = lock(&self.chain);
+		for _ in 0..count {
+			let previous = *chain.block_times.last().expect("chain has a genesis block");
+			let time = chain.next_block_time.take().unwrap_or(previous + self.block_interval);
+			chain.block_times.push(time);
+		}
+		Ok(())
+	}
+
+	async fn set_next_block_time(&self, timestamp: u64) -> Result<(), MockError> {
+		let mut chain = lock(&self.chain);
+		let current = *chain.block_times.last().expect("chain has a genesis block");
+		if timestamp <= current {
+			return Err(MockError::InvalidBlockTime { timestamp, current })
+		}
+		chain.next_block_time = Some(timestamp);
+		Ok(())
+	}
+
+	async fn create_checkpoint(&self, name: &str) -> Result<String, MockError> {
+		let chain = lock(&self.chain).clone();
+		lock(&self.checkpoints).insert(name.to_string(), chain);
+		Ok(name.to_string())
+	}
+
+	async fn reset(&self, checkpoint: Option<&str>) -> Result<(), MockError> {
+		let restored = match checkpoint {
+			Some(name) => lock(&self.checkpoints)
+				.get(name)
+				.cloned()
+				.ok_or_else(|| MockError::UnknownCheckpoint(name.to_string()))?,
+			None => MockChain::new(self.genesis_time),
+		};
+		*lock(&self.chain) = restored;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::{Middleware, Provider};
+
+	#[tokio::test]
+	async fn advances_blocks_at_a_time() {
+		let node = MockNeoNode::new(1_000).with_block_interval(100);
+		node.advance_blocks(2).await.unwrap();
+		node.set_next_block_time(5_000).await.unwrap();
+		// pinning the time does not produce a block
+		assert_eq!(node.block_count(), 3);
+		node.advance_blocks(2).await.unwrap();
+
+		assert_eq!(node.block_count(), 5);
+		assert_eq!(node.block_time(2), Some(1_200));
+		assert_eq!(node.block_time(3), Some(5_000));
+		assert_eq!(node.current_time(), 5_100);
+		assert!(node.set_next_block_time(5_100).await.is_err());
+
+		let provider = Provider::new(node.clone());
+		assert_eq!(provider.get_block_count().await.unwrap(), 5);
+	}
+
+	#[tokio::test]
+	async fn restores_checkpoints() {
+		let node = MockNeoNode::new(0);
+		node.advance_blocks(3).await.unwrap();
+		let checkpoint = node.create_checkpoint("three").await.unwrap();
+		node.advance_blocks(10).await.unwrap();
+
+		node.reset(Some(&checkpoint)).await.unwrap();
+		assert_eq!(node.block_count(), 4);
+		node.reset(None).await.unwrap();
+		assert_eq!(node.block_count(), 1);
+		assert!(matches!(node.reset(Some("missing")).await, Err(MockError::UnknownCheckpoint(_))));
+	}
+}
@@ -0,0 +1,199 @@
+use crate::{
+	utils::lock, Expectation, JsonRpcClient, JsonRpcError, MockProvider, ProviderError, RpcError,
+};
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+	fmt::Debug,
+	fs::File,
+	io::{BufReader, BufWriter},
+	path::Path,
+	sync::{Arc, Mutex},
+};
+use thiserror::Error;
+
+/// A request captured by a [`MockRecorder`], with the response of the node
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedRequest {
+	/// The method of the request
+	pub method: String,
+	/// The params of the request, `None` if there were none
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub params: Option<Value>,
+	/// The response of the node
+	pub response: RecordedResponse,
+}
+
+/// The response to a [`RecordedRequest`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordedResponse {
+	/// The result of a successful request
+	Result(Value),
+	/// The JSON-RPC error returned by the node
+	Error(JsonRpcError),
+}
+
+/// Transport capturing the traffic of a live transport, so it can be replayed in tests with
+/// [`MockProvider::replay`].
+///
+/// Requests that fail without a response of the node, e.g. due to a connection error, are not
+/// recorded.
+///
+/// ```no_run
+/// use neo_providers::{Http, MockProvider, MockRecorder, Provider};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let recorder = MockRecorder::new("http://localhost:10332".parse::<Http>()?);
+/// let provider = Provider::new(recorder.clone());
+/// // ... exercise the provider against a live node
+/// recorder.save("tests/fixtures/session.json")?;
+///
+/// // later, in a test
+/// let mock = MockProvider::load_recording("tests/fixtures/session.json")?;
+/// let provider = Provider::new(mock);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MockRecorder<P> {
+	inner: P,
+	recording: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl<P: JsonRpcClient> MockRecorder<P> {
+	/// Records the traffic of `inner`
+	pub fn new(inner: P) -> Self {
+		Self { inner, recording: Arc::new(Mutex::new(Vec::new())) }
+	}
+
+	/// The requests recorded so far, in the order they were sent
+	pub fn recording(&self) -> Vec<RecordedRequest> {
+		lock(&self.recording).clone()
+	}
+
+	/// Writes the requests recorded so far to the JSON file at `path`
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), MockRecorderError> {
+		let file = BufWriter::new(File::create(path)?);
+		serde_json::to_writer_pretty(file, &*lock(&self.recording))?;
+		Ok(())
+	}
+
+	fn record(&self, method: &str, params: Option<Value>, response: RecordedResponse) {
+		let request = RecordedRequest { method: method.to_owned(), params, response };
+		lock(&self.recording).push(request);
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<P: JsonRpcClient> JsonRpcClient for MockRecorder<P> {
+	type Error = MockRecorderError;
+
+	async fn fetch<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned + Send,
+	{
+		let recorded_params =
+			if std::mem::size_of::<T>() == 0 { None } else { Some(serde_json::to_value(&params)?) };
+		match self.inner.fetch::<T, Value>(method, params).await {
+			Ok(value) => {
+				self.record(method, recorded_params, RecordedResponse::Result(value.clone()));
+				Ok(serde_json::from_value(value)?)
+			},
+			Err(err) => {
+				if let Some(error) = err.as_error_response() {
+					self.record(method, recorded_params, RecordedResponse::Error(error.clone()));
+				}
+				Err(MockRecorderError::ProviderError(err.into()))
+			},
+		}
+	}
+}
+
+impl MockProvider {
+	/// Instantiates a mock transport answering the recorded requests, in order
+	pub fn replay(recording: impl IntoIterator<Item = RecordedRequest>) -> Self {
+		let mock = Self::new();
+		for request in recording {
+			let mut expectation = Expectation::new(request.method.as_str());
+			if let Some(params) = request.params {
+				expectation = expectation.with_params(params);
+			}
+			mock.expect(match request.response {
+				RecordedResponse::Result(value) => expectation.returning(value),
+				RecordedResponse::Error(error) => expectation.returning_error(error),
+			});
+		}
+		mock
+	}
+
+	/// Instantiates a mock transport answering the requests recorded in the JSON file at
+	/// `path` by [`MockRecorder::save`]
+	pub fn load_recording(path: impl AsRef<Path>) -> Result<Self, MockRecorderError> {
+		let file = BufReader::new(File::open(path)?);
+		let recording: Vec<RecordedRequest> = serde_json::from_reader(file)?;
+		Ok(Self::replay(recording))
+	}
+}
+
+#[derive(Error, Debug)]
+/// Errors for the [`MockRecorder`]
+pub enum MockRecorderError {
+	/// Error of the recorded transport
+	#[error(transparent)]
+	ProviderError(ProviderError),
+
+	/// (De)Serialization error
+	#[error(transparent)]
+	SerdeJson(#[from] serde_json::Error),
+
+	/// Failed to read or write a recording
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
+}
+
+impl RpcError for MockRecorderError {
+	fn as_error_response(&self) -> Option<&JsonRpcError> {
+		match self {
+			MockRecorderError::ProviderError(err) => err.as_error_response(),
+			_ => None,
+		}
+	}
+
+	fn as_serde_error(&self) -> Option<&serde_json::Error> {
+		match self {
+			MockRecorderError::ProviderError(err) => err.as_serde_error(),
+			MockRecorderError::SerdeJson(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl From<MockRecorderError> for ProviderError {
+	fn from(src: MockRecorderError) -> Self {
+		ProviderError::JsonRpcClientError(Box::new(src))
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn replays_recorded_requests() {
+		let live = MockProvider::new();
+		live.push(7u32).unwrap();
+		live.push("0x07").unwrap();
+		let recorder = MockRecorder::new(live);
+		let hash: String = recorder.fetch("getblockhash", [7u32]).await.unwrap();
+		let count: u32 = recorder.fetch("getblockcount", ()).await.unwrap();
+
+		let replay = MockProvider::replay(recorder.recording());
+		assert_eq!(replay.fetch::<_, String>("getblockhash", [7u32]).await.unwrap(), hash);
+		assert_eq!(replay.fetch::<_, u32>("getblockcount", ()).await.unwrap(), count);
+		replay.assert_expectations_met().unwrap();
+	}
+}
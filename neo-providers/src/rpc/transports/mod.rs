@@ -2,7 +2,10 @@ pub(crate) mod common;
 pub use common::{Authorization, JsonRpcError, JwtAuth, JwtKey};
 
 mod http;
-pub use self::http::{ClientError as HttpClientError, Provider as Http};
+pub use self::http::{
+	ClientError as HttpClientError, HttpBuilder, HttpClientError as HttpBuildError,
+	Provider as Http,
+};
 
 #[cfg(all(feature = "ipc", any(unix, windows)))]
 mod ipc;
@@ -18,6 +21,11 @@ pub use rw::{RwClient, RwClientError};
 mod retry;
 pub use retry::*;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod tls;
+#[cfg(not(target_arch = "wasm32"))]
+pub use tls::TlsConfig;
+
 #[cfg(all(feature = "ws", not(feature = "legacy-ws")))]
 mod ws;
 #[cfg(all(feature = "ws", not(feature = "legacy-ws")))]
@@ -30,4 +38,12 @@ pub mod legacy_ws;
 pub use legacy_ws::{ClientError as WsClientError, Ws};
 
 mod mock;
-pub use mock::{MockError, MockProvider, MockResponse};
+pub use mock::{
+	Expectation, ExpectationMode, MethodMatcher, MockError, MockProvider, MockResponse,
+};
+
+mod mock_recorder;
+pub use mock_recorder::{MockRecorder, MockRecorderError, RecordedRequest, RecordedResponse};
+
+mod mock_node;
+pub use mock_node::MockNeoNode;
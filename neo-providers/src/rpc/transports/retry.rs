@@ -8,7 +8,7 @@ use reqwest::StatusCode;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
 	fmt::Debug,
-	sync::atomic::{AtomicU32, Ordering},
+	sync::atomic::{AtomicU32, AtomicU64, Ordering},
 	time::Duration,
 };
 use thiserror::Error;
@@ -47,6 +47,12 @@ pub trait RetryPolicy<E>: Send + Sync + Debug {
 ///     .build(http, Box::new(HttpRateLimitRetryPolicy::default()));
 /// # }
 /// ```
+///
+/// Every attempt of a request is sent with the same JSON-RPC id, so a node, or a proxy in front of
+/// it, can recognize a resent request. Note that resending a `sendrawtransaction` whose first
+/// attempt failed ambiguously may still submit the transaction twice; see
+/// [`Middleware::send_raw_transaction_idempotent`](crate::Middleware::send_raw_transaction_idempotent)
+/// for a submission that checks whether the first attempt landed.
 #[derive(Debug)]
 pub struct RetryClient<T>
 where
@@ -54,6 +60,8 @@ where
 	T::Error: crate::RpcError + Sync + Send + 'static,
 {
 	inner: T,
+	/// The id of the next request, shared by all of its attempts
+	next_id: AtomicU64,
 	requests_enqueued: AtomicU32,
 	/// The policy to use to determine whether to retry a request due to rate limiting
 	policy: Box<dyn RetryPolicy<T::Error>>,
@@ -173,6 +181,7 @@ impl RetryClientBuilder {
 		} = self;
 		RetryClient {
 			inner: client,
+			next_id: AtomicU64::new(1),
 			requests_enqueued: AtomicU32::new(0),
 			policy,
 			timeout_retries,
@@ -257,6 +266,15 @@ where
 	type Error = RetryClientError;
 
 	async fn fetch<A, R>(&self, method: &str, params: A) -> Result<R, Self::Error>
+	where
+		A: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned + Send,
+	{
+		let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+		self.fetch_with_id(id, method, params).await
+	}
+
+	async fn fetch_with_id<A, R>(&self, id: u64, method: &str, params: A) -> Result<R, Self::Error>
 	where
 		A: Debug + Serialize + Send + Sync,
 		R: DeserializeOwned + Send,
@@ -288,8 +306,9 @@ where
 			// R: Send + Sync
 			{
 				let resp = match params {
-					RetryParams::Value(ref params) => self.inner.fetch(method, params).await,
-					RetryParams::Zst(unit) => self.inner.fetch(method, unit).await,
+					RetryParams::Value(ref params) =>
+						self.inner.fetch_with_id(id, method, params).await,
+					RetryParams::Zst(unit) => self.inner.fetch_with_id(id, method, unit).await,
 				};
 				match resp {
 					Ok(ret) => {
@@ -460,7 +479,11 @@ fn compute_unit_offset_in_secs(
 
 /// Checks whether the `error` is the result of a connectivity issue, like
 /// `request::Error::TimedOut`
-fn maybe_connectivity(err: &ProviderError) -> bool {
+pub(crate) fn maybe_connectivity(err: &ProviderError) -> bool {
+	if let ProviderError::Timeout(_) = err {
+		return true
+	}
+
 	if let ProviderError::HTTPError(reqwest_err) = err {
 		if reqwest_err.is_timeout() {
 			return true
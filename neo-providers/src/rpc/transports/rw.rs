@@ -138,4 +138,16 @@ where
 			_ => self.r.fetch(method, params).await.map_err(RwClientError::Read),
 		}
 	}
+
+	async fn fetch_with_id<T, R>(&self, id: u64, method: &str, params: T) -> Result<R, Self::Error>
+	where
+		T: std::fmt::Debug + Serialize + Send + Sync,
+		R: DeserializeOwned + Send,
+	{
+		match method {
+			"neo_sendTransaction" | "neo_sendRawTransaction" =>
+				self.w.fetch_with_id(id, method, params).await.map_err(RwClientError::Write),
+			_ => self.r.fetch_with_id(id, method, params).await.map_err(RwClientError::Read),
+		}
+	}
 }
@@ -0,0 +1,145 @@
+//! Custom TLS configurations for the HTTP and websocket transports.
+
+use std::fmt;
+#[cfg(feature = "rustls")]
+use std::sync::Arc;
+
+/// A TLS configuration replacing the default one of a transport, e.g. to authenticate with a
+/// client certificate (mTLS) or to trust only a pinned or private certificate authority.
+///
+/// The configuration is built with the TLS backend enabled by the `rustls` or `openssl` feature
+/// and passed to [`HttpBuilder::tls`](crate::HttpBuilder::tls) or
+/// [`ConnectionDetails::with_tls`](crate::ConnectionDetails::with_tls).
+///
+/// # Example
+///
+/// ```no_run
+/// # #[cfg(feature = "rustls")]
+/// # fn foo(
+/// # 	roots: tls_rustls::RootCertStore,
+/// # 	certs: Vec<tls_rustls::Certificate>,
+/// # 	key: tls_rustls::PrivateKey,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// use neo_providers::{Http, TlsConfig};
+/// use url::Url;
+///
+/// let config = tls_rustls::ClientConfig::builder()
+/// 	.with_safe_defaults()
+/// 	.with_root_certificates(roots)
+/// 	.with_client_auth_cert(certs, key)?;
+/// let provider = Http::builder(Url::parse("https://node.internal:10332")?)
+/// 	.tls(TlsConfig::from(config))
+/// 	.build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub enum TlsConfig {
+	/// A `rustls` client configuration
+	#[cfg(feature = "rustls")]
+	Rustls(Arc<tls_rustls::ClientConfig>),
+	/// A `native-tls` connector
+	#[cfg(feature = "openssl")]
+	NativeTls(native_tls::TlsConnector),
+}
+
+impl TlsConfig {
+	/// Makes `builder` use this configuration.
+	// without a TLS backend the enum has no variants and `builder` is never used
+	#[cfg_attr(not(any(feature = "rustls", feature = "openssl")), allow(unused_variables))]
+	pub(crate) fn apply(self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+		match self {
+			#[cfg(feature = "rustls")]
+			TlsConfig::Rustls(config) => builder.use_preconfigured_tls(
+				Arc::try_unwrap(config).unwrap_or_else(|config| (*config).clone()),
+			),
+			#[cfg(feature = "openssl")]
+			TlsConfig::NativeTls(connector) => builder.use_preconfigured_tls(connector),
+		}
+	}
+
+	/// The websocket connector using this configuration.
+	#[cfg(all(
+		feature = "ws",
+		not(target_arch = "wasm32"),
+		any(feature = "rustls", feature = "openssl")
+	))]
+	pub(crate) fn connector(self) -> tokio_tungstenite::Connector {
+		match self {
+			#[cfg(feature = "rustls")]
+			TlsConfig::Rustls(config) => tokio_tungstenite::Connector::Rustls(config),
+			#[cfg(feature = "openssl")]
+			TlsConfig::NativeTls(connector) => tokio_tungstenite::Connector::NativeTls(connector),
+		}
+	}
+}
+
+impl fmt::Debug for TlsConfig {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			#[cfg(feature = "rustls")]
+			TlsConfig::Rustls(_) => f.write_str("TlsConfig::Rustls"),
+			#[cfg(feature = "openssl")]
+			TlsConfig::NativeTls(_) => f.write_str("TlsConfig::NativeTls"),
+		}
+	}
+}
+
+#[cfg(feature = "rustls")]
+impl From<tls_rustls::ClientConfig> for TlsConfig {
+	fn from(config: tls_rustls::ClientConfig) -> Self {
+		TlsConfig::Rustls(Arc::new(config))
+	}
+}
+
+#[cfg(feature = "rustls")]
+impl From<Arc<tls_rustls::ClientConfig>> for TlsConfig {
+	fn from(config: Arc<tls_rustls::ClientConfig>) -> Self {
+		TlsConfig::Rustls(config)
+	}
+}
+
+#[cfg(feature = "openssl")]
+impl From<native_tls::TlsConnector> for TlsConfig {
+	fn from(connector: native_tls::TlsConnector) -> Self {
+		TlsConfig::NativeTls(connector)
+	}
+}
+
+#[cfg(all(test, feature = "rustls"))]
+mod tests {
+	use super::*;
+	use crate::Http;
+	use url::Url;
+
+	fn client_config() -> tls_rustls::ClientConfig {
+		tls_rustls::ClientConfig::builder()
+			.with_safe_defaults()
+			.with_root_certificates(tls_rustls::RootCertStore::empty())
+			.with_no_client_auth()
+	}
+
+	#[test]
+	fn rustls_config_builds_http_client() {
+		let config = TlsConfig::from(client_config());
+		assert_eq!(format!("{config:?}"), "TlsConfig::Rustls");
+
+		let url = Url::parse("https://localhost:10332").unwrap();
+		assert!(Http::builder(url).tls(config).build().is_ok());
+	}
+
+	#[test]
+	fn shared_rustls_config_is_not_copied() {
+		let shared = Arc::new(client_config());
+		assert!(matches!(
+			TlsConfig::from(shared.clone()),
+			TlsConfig::Rustls(config) if Arc::ptr_eq(&config, &shared)
+		));
+	}
+
+	#[cfg(feature = "ws")]
+	#[test]
+	fn rustls_config_builds_ws_connector() {
+		let connector = TlsConfig::from(client_config()).connector();
+		assert!(matches!(connector, tokio_tungstenite::Connector::Rustls(_)));
+	}
+}
@@ -65,7 +65,7 @@ impl WsBackend {
 	pub async fn connect(
 		details: ConnectionDetails,
 	) -> Result<(Self, BackendDriver), WsClientError> {
-		let ws = connect_async(details).await?.0.fuse();
+		let ws = Self::open(details, None, false).await?;
 		Ok(Self::new(ws))
 	}
 
@@ -75,10 +75,28 @@ impl WsBackend {
 		config: WebSocketConfig,
 		disable_nagle: bool,
 	) -> Result<(Self, BackendDriver), WsClientError> {
-		let ws = connect_async_with_config(details, Some(config), disable_nagle).await?.0.fuse();
+		let ws = Self::open(details, Some(config), disable_nagle).await?;
 		Ok(Self::new(ws))
 	}
 
+	/// Opens the connection, with the TLS configuration of `details` if there is one
+	#[cfg(not(target_arch = "wasm32"))]
+	async fn open(
+		details: ConnectionDetails,
+		config: Option<WebSocketConfig>,
+		disable_nagle: bool,
+	) -> Result<InternalStream, WsClientError> {
+		#[cfg(any(feature = "rustls", feature = "openssl"))]
+		if let Some(tls) = details.tls.clone() {
+			let connector = Some(tls.connector());
+			let ws =
+				connect_async_tls_with_config(details, config, disable_nagle, connector).await?;
+			return Ok(ws.0.fuse())
+		}
+		let ws = connect_async_with_config(details, config, disable_nagle).await?;
+		Ok(ws.0.fuse())
+	}
+
 	pub fn new(server: InternalStream) -> (Self, BackendDriver) {
 		let (handler, to_handle) = mpsc::unbounded();
 		let (dispatcher, to_dispatch) = mpsc::unbounded();
@@ -121,8 +139,8 @@ impl WsBackend {
 
 				Message::Binary(buf) => Err(WsClientError::UnexpectedBinary(buf)),
 				Message::Close(frame) => {
-					if frame.is_some() {
-						error!("Close frame: {}", frame.unwrap());
+					if let Some(frame) = frame {
+						error!("Close frame: {}", frame);
 					}
 					Err(WsClientError::UnexpectedClose)
 				},
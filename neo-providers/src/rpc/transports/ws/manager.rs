@@ -5,7 +5,7 @@ use super::{
 	ActiveSub, ConnectionDetails, InFlight, Instruction, Notification, PubSubItem, Response, SubId,
 	WsClient, WsClientError,
 };
-use crate::JsonRpcError;
+use crate::{utils::lock, JsonRpcError};
 use futures_channel::{mpsc, oneshot};
 use futures_util::{select_biased, StreamExt};
 use primitive_types::U256;
@@ -22,17 +22,20 @@ pub type SharedChannelMap = Arc<Mutex<HashMap<U256, mpsc::UnboundedReceiver<Box<
 
 pub const DEFAULT_RECONNECTS: usize = 5;
 
-/// This struct manages the relationship between the u64 request ID, and U256
-/// server-side subscription ID. It does this by aliasing the server ID to the
-/// request ID, and returning the Request ID to the caller (hiding the server
-/// ID in the SubscriptionManager internals.) Giving the caller a "fake"
-/// subscription id allows the subscription to behave consistently across
-/// reconnections
+/// This struct manages the relationship between the u64 request ID, and the
+/// string subscription ID assigned by the server. It does this by aliasing the
+/// server ID to the request ID, and returning the Request ID to the caller
+/// (hiding the server ID in the SubscriptionManager internals.) Giving the
+/// caller a "fake" subscription id allows the subscription to behave
+/// consistently across reconnections
+///
+/// Notifications of the node carry the name of their event instead of a
+/// subscription id, so they are forwarded to every subscription to that event
 pub struct SubscriptionManager {
 	// Active subs indexed by request id
 	subs: BTreeMap<u64, ActiveSub>,
 	// Maps active server-side IDs to local subscription IDs
-	aliases: HashMap<U256, u64>,
+	aliases: HashMap<String, u64>,
 	// Used to share notification channels with the WsClient(s)
 	channel_map: SharedChannelMap,
 }
@@ -46,35 +49,35 @@ impl SubscriptionManager {
 		self.subs.len()
 	}
 
-	fn add_alias(&mut self, sub: U256, id: u64) {
+	fn add_alias(&mut self, sub: String, id: u64) {
 		if let Some(entry) = self.subs.get_mut(&id) {
-			entry.current_server_id = Some(sub);
+			entry.current_server_id = Some(sub.clone());
 		}
 		self.aliases.insert(sub, id);
 	}
 
-	fn remove_alias(&mut self, server_id: U256) {
-		if let Some(id) = self.aliases.get(&server_id) {
+	fn remove_alias(&mut self, server_id: &str) {
+		if let Some(id) = self.aliases.get(server_id) {
 			if let Some(sub) = self.subs.get_mut(id) {
 				sub.current_server_id = None;
 			}
 		}
-		self.aliases.remove(&server_id);
+		self.aliases.remove(server_id);
 	}
 
 	#[tracing::instrument(skip(self))]
 	fn end_subscription(&mut self, id: u64) -> Option<Box<RawValue>> {
 		if let Some(sub) = self.subs.remove(&id) {
 			if let Some(server_id) = sub.current_server_id {
-				tracing::debug!(server_id = format!("0x{server_id:x}"), "Ending subscription");
-				self.remove_alias(server_id);
+				tracing::debug!(%server_id, "Ending subscription");
+				self.remove_alias(&server_id);
 				// drop the receiver as we don't need the result
 				let (channel, _) = oneshot::channel();
 				// Serialization errors are ignored, and result in the request
 				// not being dispatched. This is fine, as worst case it will
 				// result in the server sending us notifications we ignore
 				let unsub_request = InFlight {
-					method: "neo_unsubscribe".to_string(),
+					method: "unsubscribe".to_string(),
 					params: SubId(server_id).serialize_raw().ok()?,
 					channel,
 				};
@@ -87,39 +90,32 @@ impl SubscriptionManager {
 		None
 	}
 
-	#[tracing::instrument(skip_all, fields(server_id = ?notification.subscription))]
+	#[tracing::instrument(skip_all, fields(event = %notification.method))]
 	fn handle_notification(&mut self, notification: Notification) {
-		let server_id = notification.subscription;
-
-		// If no alias, just return
-		let id_opt = self.aliases.get(&server_id).copied();
-		if id_opt.is_none() {
-			tracing::debug!(
-				server_id = format!("0x{server_id:x}"),
-				"No aliased subscription found"
-			);
+		// the payload of an event is its only parameter
+		let Some(payload) = notification.params.into_iter().next() else {
+			tracing::warn!("Notification without payload, events may have been missed");
 			return
-		}
-		let id = id_opt.unwrap();
+		};
 
-		// alias exists, or should be dropped from alias table
-		let sub_opt = self.subs.get(&id);
-		if sub_opt.is_none() {
-			tracing::trace!(id, "Aliased subscription found, but not active");
-			self.aliases.remove(&server_id);
+		let mut dropped = Vec::new();
+		for (id, active) in self.subs.iter() {
+			if active.event != notification.method || active.current_server_id.is_none() {
+				continue
+			}
+			tracing::debug!(id, "Forwarding notification to listener");
+			// receiver has dropped, so we drop the sub
+			if active.channel.unbounded_send(payload.clone()).is_err() {
+				dropped.push(*id);
+			}
 		}
-		let active = sub_opt.unwrap();
-
-		tracing::debug!(id, "Forwarding notification to listener");
-		// send the notification over the channel
-		let send_res = active.channel.unbounded_send(notification.result);
 
-		// receiver has dropped, so we drop the sub
-		if send_res.is_err() {
+		for id in dropped {
 			tracing::debug!(id, "Listener dropped. Dropping alias and subs");
 			// TODO: end subcription here?
-			self.aliases.remove(&server_id);
-			self.subs.remove(&id);
+			if let Some(server_id) = self.subs.remove(&id).and_then(|sub| sub.current_server_id) {
+				self.aliases.remove(&server_id);
+			}
 		}
 	}
 
@@ -149,14 +145,14 @@ impl SubscriptionManager {
 	) -> Result<Box<RawValue>, WsClientError> {
 		let (tx, rx) = mpsc::unbounded();
 
-		let active_sub = ActiveSub { params, channel: tx, current_server_id: None };
+		let active_sub = ActiveSub::new(params, tx);
 		let req = active_sub.serialize_raw(id)?;
 
 		// Explicit scope for the lock
 		// This insertion should be made BEFORE the request returns.
 		// So we make it before the request is even dispatched :)
 		{
-			self.channel_map.lock().unwrap().insert(id.into(), rx);
+			lock(&self.channel_map).insert(id.into(), rx);
 		}
 		self.subs.insert(id, active_sub);
 
@@ -357,7 +353,7 @@ impl RequestManager {
 		for (id, sub) in self.subs.to_reissue() {
 			let (tx, _rx) = oneshot::channel();
 			let in_flight = InFlight {
-				method: "neo_subscribe".to_string(),
+				method: "subscribe".to_string(),
 				params: sub.params.clone(),
 				channel: tx,
 			};
@@ -422,7 +418,7 @@ impl RequestManager {
 
 		// Ordering matters here. We want this block above the unbounded send,
 		// and after the serialization
-		if in_flight.method == "neo_subscribe" {
+		if in_flight.method == "subscribe" {
 			self.subs.service_subscription_request(id, in_flight.params.clone())?;
 		}
 
@@ -499,3 +495,63 @@ impl RequestManager {
 		tokio::spawn(fut);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::responses::neo_application_log::ApplicationLog;
+	use serde_json::json;
+
+	/// A `transaction_executed` event as pushed by neo-go
+	const TRANSACTION_EXECUTED: &str = concat!(
+		r#"{"jsonrpc":"2.0","method":"transaction_executed","params":[{"container":"#,
+		r#""0xe1cd5e57e721d2a2e05fb1f08721b12057b25ab1dd7fd0f33ee1639932fdfad7","#,
+		r#""trigger":"Application","vmstate":"HALT","gasconsumed":"9977780","stack":[],"#,
+		r#""notifications":[]}]}"#,
+	);
+
+	fn raw(json: &str) -> Box<RawValue> {
+		RawValue::from_string(json.to_string()).unwrap()
+	}
+
+	#[test]
+	fn test_routes_notifications_by_event() {
+		let channel_map = SharedChannelMap::default();
+		let mut subs = SubscriptionManager::new(channel_map.clone());
+		let request = subs
+			.service_subscription_request(1, raw(r#"["transaction_executed",{"state":"HALT"}]"#))
+			.unwrap();
+		let request: serde_json::Value = serde_json::from_str(request.get()).unwrap();
+		assert_eq!(request["method"], "subscribe");
+		subs.service_subscription_request(2, raw(r#"["block_added"]"#)).unwrap();
+
+		// the local id is returned in place of the id assigned by the node
+		assert_eq!(subs.req_success(1, raw(r#""0""#)).get(), r#""0x1""#);
+		subs.req_success(2, raw(r#""1""#));
+		let mut executed = lock(&channel_map).remove(&U256::from(1)).unwrap();
+		let mut blocks = lock(&channel_map).remove(&U256::from(2)).unwrap();
+
+		let PubSubItem::Notification { params } =
+			serde_json::from_str(TRANSACTION_EXECUTED).unwrap()
+		else {
+			panic!("not a notification")
+		};
+		subs.handle_notification(params);
+
+		let payload = executed.try_next().unwrap().unwrap();
+		let log: ApplicationLog = serde_json::from_str(payload.get()).unwrap();
+		assert_eq!(
+			format!("{:x}", log.transaction_id),
+			"e1cd5e57e721d2a2e05fb1f08721b12057b25ab1dd7fd0f33ee1639932fdfad7"
+		);
+		assert!(log.executions[0].is_halt());
+		assert!(blocks.try_next().is_err());
+
+		let request = subs.end_subscription(1).unwrap();
+		let request: serde_json::Value = serde_json::from_str(request.get()).unwrap();
+		assert_eq!(request["method"], "unsubscribe");
+		assert_eq!(request["params"], json!(["0"]));
+		// the stream ends once the subscription is cancelled
+		assert!(matches!(executed.try_next(), Ok(None)));
+	}
+}
@@ -20,7 +20,7 @@ use types::*;
 mod error;
 pub use error::*;
 
-use crate::{JsonRpcClient, ProviderError, PubsubClient};
+use crate::{utils::lock, JsonRpcClient, ProviderError, PubsubClient};
 use async_trait::async_trait;
 use futures_channel::{mpsc, oneshot};
 
@@ -135,9 +135,7 @@ impl PubsubClient for WsClient {
 		// due to the behavior of the request manager, we know this map has
 		// been populated by the time the `request()` call returns
 		let id = id.into();
-		self.channel_map
-			.lock()
-			.unwrap()
+		lock(&self.channel_map)
 			.remove(&id)
 			.ok_or(WsClientError::UnknownSubscription(id))
 	}
@@ -8,19 +8,23 @@ use std::fmt;
 // Normal JSON-RPC response
 pub type Response = Result<Box<RawValue>, JsonRpcError>;
 
+/// The id the node assigned to a subscription
 #[derive(serde::Deserialize, serde::Serialize)]
-pub struct SubId(pub U256);
+pub struct SubId(pub String);
 
 impl SubId {
+	/// The parameters of the `unsubscribe` request cancelling the subscription
 	pub(super) fn serialize_raw(&self) -> Result<Box<RawValue>, serde_json::Error> {
-		to_raw_value(&self)
+		to_raw_value(&[&self.0])
 	}
 }
 
-#[derive(Deserialize, Debug, Clone)]
+/// An event pushed by the node. Events do not carry a subscription id, they are routed to the
+/// subscriptions by their `method`, the name of the event, e.g. `transaction_executed`.
+#[derive(Debug, Clone)]
 pub struct Notification {
-	pub subscription: U256,
-	pub result: Box<RawValue>,
+	pub method: String,
+	pub params: Vec<Box<RawValue>>,
 }
 
 #[derive(Debug, Clone)]
@@ -115,7 +119,7 @@ impl<'de> Deserialize<'de> for PubSubItem {
 								return Err(de::Error::duplicate_field("params"))
 							}
 
-							let value: Notification = map.next_value()?;
+							let value: Vec<Box<RawValue>> = map.next_value()?;
 							params = Some(value);
 						},
 						key =>
@@ -138,8 +142,8 @@ impl<'de> Deserialize<'de> for PubSubItem {
 						Ok(PubSubItem::Error { id, error }),
 					(Some(id), Some(_), Some(error), None, None) =>
 						Ok(PubSubItem::Error { id, error }),
-					(None, None, None, Some(_), Some(params)) =>
-						Ok(PubSubItem::Notification { params }),
+					(None, None, None, Some(method), Some(params)) =>
+						Ok(PubSubItem::Notification { params: Notification { method, params } }),
 					_ => Err(de::Error::custom(
 						"response must be either a success/error or notification object",
 					)),
@@ -157,7 +161,7 @@ impl std::fmt::Display for PubSubItem {
 			PubSubItem::Success { id, .. } => write!(f, "Req success. ID: {id}"),
 			PubSubItem::Error { id, .. } => write!(f, "Req error. ID: {id}"),
 			PubSubItem::Notification { params } => {
-				write!(f, "Notification for sub: {:?}", params.subscription)
+				write!(f, "Notification of event: {}", params.method)
 			},
 		}
 	}
@@ -168,12 +172,23 @@ pub struct ConnectionDetails {
 	pub url: String,
 	#[cfg(not(target_arch = "wasm32"))]
 	pub auth: Option<crate::Authorization>,
+	/// Replaces the default TLS configuration of `wss` connections
+	#[cfg(not(target_arch = "wasm32"))]
+	pub tls: Option<crate::TlsConfig>,
 }
 
 impl ConnectionDetails {
 	#[cfg(not(target_arch = "wasm32"))]
 	pub fn new(url: impl AsRef<str>, auth: Option<crate::Authorization>) -> Self {
-		Self { url: url.as_ref().to_string(), auth }
+		Self { url: url.as_ref().to_string(), auth, tls: None }
+	}
+
+	/// Connects with the TLS configuration `tls`, e.g. to present a client certificate or to
+	/// trust a private certificate authority
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn with_tls(mut self, tls: impl Into<crate::TlsConfig>) -> Self {
+		self.tls = Some(tls.into());
+		self
 	}
 	#[cfg(target_arch = "wasm32")]
 	pub fn new(url: impl AsRef<str>) -> Self {
@@ -187,7 +202,7 @@ where
 {
 	#[cfg(not(target_arch = "wasm32"))]
 	fn from(value: T) -> Self {
-		ConnectionDetails { url: value.as_ref().to_string(), auth: None }
+		ConnectionDetails { url: value.as_ref().to_string(), auth: None, tls: None }
 	}
 	#[cfg(target_arch = "wasm32")]
 	fn from(value: T) -> Self {
@@ -214,14 +229,27 @@ impl InFlight {
 
 #[derive(Debug)]
 pub(super) struct ActiveSub {
+	/// The name of the event subscribed to, the first of the parameters
+	pub event: String,
 	pub params: Box<RawValue>,
 	pub channel: mpsc::UnboundedSender<Box<RawValue>>,
-	pub current_server_id: Option<U256>,
+	pub current_server_id: Option<String>,
 }
 
 impl ActiveSub {
+	pub(super) fn new(
+		params: Box<RawValue>,
+		channel: mpsc::UnboundedSender<Box<RawValue>>,
+	) -> Self {
+		let event = serde_json::from_str::<Vec<serde_json::Value>>(params.get())
+			.ok()
+			.and_then(|params| params.first()?.as_str().map(str::to_string))
+			.unwrap_or_default();
+		Self { event, params, channel, current_server_id: None }
+	}
+
 	pub(super) fn to_request(&self, id: u64) -> Request<'static, Box<RawValue>> {
-		Request::new(id, "neo_subscribe", self.params.clone())
+		Request::new(id, "subscribe", self.params.clone())
 	}
 
 	pub(super) fn serialize_raw(&self, id: u64) -> Result<Box<RawValue>, serde_json::Error> {
@@ -252,8 +280,10 @@ mod aliases {
 
 #[cfg(not(target_arch = "wasm32"))]
 mod aliases {
+	#[cfg(any(feature = "rustls", feature = "openssl"))]
+	pub use tokio_tungstenite::connect_async_tls_with_config;
 	pub use tokio_tungstenite::{
-		connect_async, connect_async_with_config,
+		connect_async_with_config,
 		tungstenite::{self, protocol::CloseFrame},
 	};
 	use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
@@ -297,5 +327,12 @@ mod test {
 	fn it_desers_pubsub_items() {
 		let a = "{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":\"0xcd0c3e8af590364c09d0fa6a1210faf5\"}";
 		serde_json::from_str::<PubSubItem>(a).unwrap();
+
+		let b = r#"{"jsonrpc":"2.0","method":"event_missed","params":[]}"#;
+		let PubSubItem::Notification { params } = serde_json::from_str(b).unwrap() else {
+			panic!("not a notification")
+		};
+		assert_eq!(params.method, "event_missed");
+		assert!(params.params.is_empty());
 	}
 }
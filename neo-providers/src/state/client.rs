@@ -0,0 +1,223 @@
+use crate::{
+	core::responses::neo_get_state_root::StateRoot,
+	state::{StateProof, StateProofError},
+	JsonRpcClient, Middleware, Provider, ProviderError,
+};
+use neo_types::{encoding, natives, Bytes};
+use primitive_types::{H160, H256};
+
+/// Reads the contract storage as of a block, proving every value against the state root of the
+/// block. Created by [`Provider::state_at`].
+///
+/// The node must run the `StateService` plugin with `FullState` enabled to serve the state of
+/// past blocks.
+///
+/// Without [`with_root`](Self::with_root), the state root is fetched from the same node that
+/// serves the proofs and is trusted as is: the proofs then only show that the node answers
+/// consistently with the root it claims. Pin a root checked by a
+/// [`HeaderChain`](crate::light_client::HeaderChain) to verify the values themselves.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_providers::{Http, Provider};
+/// use primitive_types::H160;
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(contract: H160) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let value = provider.state_at(100).get_storage(contract, &[0x0b]).await?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StateServiceClient<'a, P> {
+	provider: &'a Provider<P>,
+	height: u32,
+	root_hash: Option<H256>,
+}
+
+impl<'a, P: JsonRpcClient> StateServiceClient<'a, P> {
+	/// Reads the state after the block at `height`
+	pub fn new(provider: &'a Provider<P>, height: u32) -> Self {
+		Self { provider, height, root_hash: None }
+	}
+
+	/// Pins the state root to `root_hash` instead of fetching it from the node, e.g. a root
+	/// whose witness was checked by a [`HeaderChain`](crate::light_client::HeaderChain).
+	#[must_use]
+	pub fn with_root(mut self, root_hash: H256) -> Self {
+		self.root_hash = Some(root_hash);
+		self
+	}
+
+	/// The height of the block the state is read at
+	pub fn height(&self) -> u32 {
+		self.height
+	}
+
+	/// Fetches the state root of the block.
+	pub async fn state_root(&self) -> Result<StateRoot, ProviderError> {
+		self.provider.get_state_root(self.height).await
+	}
+
+	/// The hash of the state root the values are proven against, the pinned one if any.
+	pub async fn root_hash(&self) -> Result<H256, ProviderError> {
+		match self.root_hash {
+			Some(root_hash) => Ok(root_hash),
+			None => Ok(self.state_root().await?.root_hash),
+		}
+	}
+
+	/// Reads the value stored under `key` by `contract`, verifying the `getproof` result of the
+	/// node locally.
+	///
+	/// Returns `None` if the proof shows that the key is absent. Fails with a
+	/// [`StateProofError`] if the proof does not match the state root or is for another key.
+	/// The id of a deployed contract, which prefixes its storage keys, is read with
+	/// `getcontractstate`.
+	pub async fn get_storage(
+		&self,
+		contract: H160,
+		key: &[u8],
+	) -> Result<Option<Bytes>, ProviderError> {
+		let root_hash = self.root_hash().await?;
+		let id = self.contract_id(contract).await?;
+		let proof = self
+			.provider
+			.get_proof(root_hash, contract, &encoding::base64_encode(key))
+			.await?;
+		let proof = StateProof::from_base64(&proof)?;
+		// the full key is the id of the contract in little-endian followed by the key
+		if proof.key != [id.to_le_bytes().as_slice(), key].concat() {
+			return Err(StateProofError::UnexpectedKey(hex::encode(&proof.key)).into())
+		}
+		Ok(proof.verify(root_hash)?)
+	}
+
+	/// The id of `contract`, known for native contracts and fetched from the node otherwise
+	async fn contract_id(&self, contract: H160) -> Result<i32, ProviderError> {
+		match natives::by_hash(&contract) {
+			Some(native) => Ok(native.id),
+			None => Ok(self.provider.get_contract_state(contract).await?.id),
+		}
+	}
+
+	/// Reads the value stored under `key` by `contract` with `getstate`, without a proof.
+	pub async fn get_storage_unverified(
+		&self,
+		contract: H160,
+		key: &[u8],
+	) -> Result<Bytes, ProviderError> {
+		let root_hash = self.root_hash().await?;
+		let value = self
+			.provider
+			.get_state(root_hash, contract, &encoding::base64_encode(key))
+			.await?;
+		encoding::base64_decode(&value)
+			.map_err(|e| ProviderError::CustomError(format!("invalid base64 state: {e}")))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		state::{mpt_node::to_nibbles, MptNode},
+		Expectation,
+	};
+	use neo_codec::Encoder;
+	use serde_json::json;
+
+	// A trie holding only `key` => `value`: extension [nibbles of key] -> leaf
+	fn proof_of(key: &[u8], value: &[u8]) -> (H256, String) {
+		let mut writer = Encoder::new();
+		writer.write_u8(MptNode::LEAF_TYPE);
+		writer.write_var_bytes(value);
+		let leaf = writer.to_bytes();
+
+		let mut writer = Encoder::new();
+		writer.write_u8(MptNode::EXTENSION_TYPE);
+		writer.write_var_bytes(&to_nibbles(key));
+		writer.write_u8(MptNode::HASH_TYPE);
+		writer.write_bytes(&MptNode::hash_of(&leaf));
+		let root = writer.to_bytes();
+
+		let mut writer = Encoder::new();
+		writer.write_var_bytes(key);
+		writer.write_var_int(2);
+		writer.write_var_bytes(&root);
+		writer.write_var_bytes(&leaf);
+
+		let mut root_hash = MptNode::hash_of(&root);
+		root_hash.reverse();
+		(H256::from(root_hash), encoding::base64_encode(writer.to_bytes()))
+	}
+
+	fn contract_state(id: i32) -> serde_json::Value {
+		json!({
+			"id": id,
+			"updatecounter": 0,
+			"hash": format!("{:#x}", H160::zero()),
+			"nef": {
+				"magic": 860243278,
+				"compiler": "neon-test",
+				"source": "",
+				"tokens": [],
+				"script": "QA==",
+				"checksum": 0
+			},
+			"manifest": {
+				"name": "Token",
+				"groups": [],
+				"features": {},
+				"supportedstandards": [],
+				"abi": { "methods": [], "events": [] },
+				"permissions": [],
+				"trusts": [],
+				"extra": null
+			}
+		})
+	}
+
+	#[tokio::test]
+	async fn test_get_storage() {
+		let (root_hash, proof) = proof_of(&[0x01, 0x00, 0x00, 0x00, 0x0b], b"ab");
+		let (provider, mock) = Provider::mocked();
+		let state = provider.state_at(5).with_root(root_hash);
+
+		mock.expect(Expectation::new("getcontractstate").returning(contract_state(1)));
+		mock.expect(Expectation::new("getproof").returning(&proof));
+		assert_eq!(state.get_storage(H160::zero(), &[0x0b]).await.unwrap(), Some(b"ab".to_vec()));
+
+		mock.expect(Expectation::new("getcontractstate").returning(contract_state(1)));
+		mock.expect(Expectation::new("getproof").returning(&proof));
+		assert!(matches!(
+			state.get_storage(H160::zero(), &[0x0c]).await,
+			Err(ProviderError::StateProof(StateProofError::UnexpectedKey(_)))
+		));
+		mock.assert_expectations_met();
+	}
+
+	#[tokio::test]
+	async fn test_reject_proof_of_other_contract() {
+		let (root_hash, proof) = proof_of(&[0x02, 0x00, 0x00, 0x00, 0x0b], b"ab");
+		let (provider, mock) = Provider::mocked();
+		let state = provider.state_at(5).with_root(root_hash);
+
+		// the same key stored by the contract with id 2
+		mock.expect(Expectation::new("getcontractstate").returning(contract_state(1)));
+		mock.expect(Expectation::new("getproof").returning(&proof));
+		assert!(matches!(
+			state.get_storage(H160::zero(), &[0x0b]).await,
+			Err(ProviderError::StateProof(StateProofError::UnexpectedKey(_)))
+		));
+
+		// native contracts are resolved without a request
+		mock.expect(Expectation::new("getproof").returning(&proof));
+		assert!(matches!(
+			state.get_storage(natives::GAS_TOKEN.hash, &[0x0b]).await,
+			Err(ProviderError::StateProof(StateProofError::UnexpectedKey(_)))
+		));
+		mock.assert_expectations_met();
+	}
+}
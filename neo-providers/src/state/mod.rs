@@ -0,0 +1,35 @@
+//! Offline verification of state proofs.
+//!
+//! A Neo N3 state root commits to the contract storage of the whole chain through a
+//! Merkle Patricia Trie (MPT). The `getproof` RPC method returns the trie nodes on the path
+//! from the root to a storage key, which allows a client to check a storage value against a
+//! state root it trusts (for example one signed by the state validators) without having to
+//! trust the RPC node itself.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! use neo_providers::{state::StateProof, Middleware, Provider, Http};
+//! use primitive_types::H160;
+//! use std::convert::TryFrom;
+//!
+//! let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+//! let root = provider.get_state_root(100).await?;
+//! let proof = provider.get_proof(root.root_hash, H160::zero(), "AQ==").await?;
+//!
+//! let value = StateProof::from_base64(&proof)?.verify(root.root_hash)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Provider::state_at`](crate::Provider::state_at) runs these steps for a block height, see
+//! [`StateServiceClient`].
+
+mod client;
+mod mpt_node;
+mod proof;
+
+pub use client::StateServiceClient;
+pub use mpt_node::{MptChild, MptNode};
+pub use proof::{verify_proof, StateProof, StateProofError};
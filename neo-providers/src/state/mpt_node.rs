@@ -0,0 +1,107 @@
+use crate::state::StateProofError;
+use neo_codec::Decoder;
+use neo_crypto::hash::HashableForVec;
+use neo_types::Bytes;
+
+/// Number of children of a branch node: one per nibble plus the value slot.
+pub(crate) const BRANCH_CHILD_COUNT: usize = 17;
+
+/// The child of a branch or extension node as it appears in a proof.
+///
+/// Proof nodes are serialized without their children, which are replaced by the hash of the
+/// child node, or by an empty marker if there is no child at that position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MptChild {
+	/// Hash of the child node, in the byte order produced by `hash256`.
+	Hash([u8; 32]),
+	/// No child at this position.
+	Empty,
+}
+
+/// A single node of the Neo Merkle Patricia Trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MptNode {
+	/// A node with one child per nibble, plus a value child at index 16.
+	Branch { children: Vec<MptChild> },
+	/// A node that shortcuts a shared nibble path to its child.
+	Extension { key: Bytes, next: MptChild },
+	/// A node that holds a storage value.
+	Leaf { value: Bytes },
+	/// A reference to a node by its hash.
+	Hash { hash: [u8; 32] },
+	/// The empty node.
+	Empty,
+}
+
+impl MptNode {
+	pub const BRANCH_TYPE: u8 = 0x00;
+	pub const EXTENSION_TYPE: u8 = 0x01;
+	pub const LEAF_TYPE: u8 = 0x02;
+	pub const HASH_TYPE: u8 = 0x03;
+	pub const EMPTY_TYPE: u8 = 0x04;
+
+	/// Decodes a node serialized without its reference count, as returned in proofs.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, StateProofError> {
+		let mut reader = Decoder::new(bytes);
+		let node = Self::decode(&mut reader)?;
+		if reader.available() != 0 {
+			return Err(StateProofError::InvalidNode("trailing bytes after node".to_string()))
+		}
+		Ok(node)
+	}
+
+	fn decode(reader: &mut Decoder) -> Result<Self, StateProofError> {
+		match read_type(reader)? {
+			Self::BRANCH_TYPE => {
+				let children = (0..BRANCH_CHILD_COUNT)
+					.map(|_| decode_child(reader))
+					.collect::<Result<Vec<_>, _>>()?;
+				Ok(MptNode::Branch { children })
+			},
+			Self::EXTENSION_TYPE => {
+				let key = reader.read_var_bytes()?;
+				if key.is_empty() || key.iter().any(|nibble| *nibble > 0x0f) {
+					return Err(StateProofError::InvalidNode(
+						"extension key must be a non-empty nibble path".to_string(),
+					))
+				}
+				Ok(MptNode::Extension { key, next: decode_child(reader)? })
+			},
+			Self::LEAF_TYPE => Ok(MptNode::Leaf { value: reader.read_var_bytes()? }),
+			Self::HASH_TYPE => Ok(MptNode::Hash { hash: read_hash(reader)? }),
+			Self::EMPTY_TYPE => Ok(MptNode::Empty),
+			other => Err(StateProofError::InvalidNode(format!("unknown node type {other:#04x}"))),
+		}
+	}
+
+	/// Computes the hash of a serialized node, in the byte order used by child references.
+	pub fn hash_of(bytes: &[u8]) -> [u8; 32] {
+		let digest = bytes.hash256().hash256();
+		let mut hash = [0u8; 32];
+		hash.copy_from_slice(&digest);
+		hash
+	}
+}
+
+fn read_type(reader: &mut Decoder) -> Result<u8, StateProofError> {
+	Ok(reader.read_u8()?)
+}
+
+fn read_hash(reader: &mut Decoder) -> Result<[u8; 32], StateProofError> {
+	Ok(reader.read_array()?)
+}
+
+fn decode_child(reader: &mut Decoder) -> Result<MptChild, StateProofError> {
+	match read_type(reader)? {
+		MptNode::HASH_TYPE => Ok(MptChild::Hash(read_hash(reader)?)),
+		MptNode::EMPTY_TYPE => Ok(MptChild::Empty),
+		other => Err(StateProofError::InvalidNode(format!(
+			"child nodes must be hash or empty nodes, got type {other:#04x}"
+		))),
+	}
+}
+
+/// Splits every byte of `bytes` into its high and low nibble.
+pub(crate) fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+	bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
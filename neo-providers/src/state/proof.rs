@@ -0,0 +1,243 @@
+use crate::state::mpt_node::{to_nibbles, MptChild, MptNode, BRANCH_CHILD_COUNT};
+use neo_codec::{CodecError, Decoder};
+use neo_types::{encoding, Bytes};
+use primitive_types::H256;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Errors that can occur while decoding or verifying a state proof.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum StateProofError {
+	/// The proof payload is not valid base64
+	#[error("Invalid base64 proof: {0}")]
+	InvalidBase64(String),
+	/// The proof payload could not be decoded
+	#[error("Invalid proof format: {0}")]
+	InvalidFormat(String),
+	/// A trie node in the proof could not be decoded
+	#[error("Invalid trie node: {0}")]
+	InvalidNode(String),
+	/// The proof does not contain a node required to walk the path to the key
+	#[error("Proof is missing the trie node {0}")]
+	MissingNode(String),
+	/// The key is empty or too long to be a storage key
+	#[error("Invalid storage key length {0}")]
+	InvalidKey(usize),
+	/// The proof is for another key than the requested one
+	#[error("Proof is for the unexpected key {0}")]
+	UnexpectedKey(String),
+}
+
+impl From<CodecError> for StateProofError {
+	fn from(err: CodecError) -> Self {
+		StateProofError::InvalidFormat(err.to_string())
+	}
+}
+
+/// A decoded `getproof` payload: the storage key and the trie nodes proving it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateProof {
+	/// The full storage key, i.e. the little-endian contract id followed by the key.
+	pub key: Bytes,
+	/// The serialized trie nodes, in no particular order.
+	pub nodes: Vec<Bytes>,
+}
+
+impl StateProof {
+	/// The maximum length of a storage key: a 4 byte contract id plus a 64 byte key.
+	pub const MAX_KEY_LENGTH: usize = 4 + 64;
+
+	/// Decodes a proof as returned by the `getproof` RPC method.
+	pub fn from_base64(proof: &str) -> Result<Self, StateProofError> {
+		let bytes = encoding::base64_decode(proof)
+			.map_err(|e| StateProofError::InvalidBase64(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// Decodes a binary proof: the var-bytes key followed by a var-length list of var-bytes nodes.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, StateProofError> {
+		let mut reader = Decoder::new(bytes);
+		if reader.available() == 0 {
+			return Err(StateProofError::InvalidFormat("empty proof".to_string()))
+		}
+		let key = reader.read_var_bytes()?;
+		let count = reader.read_var_int()?;
+		if count > reader.available() as u64 {
+			return Err(StateProofError::InvalidFormat(format!("invalid node count {count}")))
+		}
+		let nodes = (0..count).map(|_| reader.read_var_bytes()).collect::<Result<Vec<_>, _>>()?;
+		if reader.available() != 0 {
+			return Err(StateProofError::InvalidFormat("trailing bytes after proof".to_string()))
+		}
+		Ok(Self { key, nodes })
+	}
+
+	/// Verifies the proof against `root_hash`.
+	///
+	/// See [`verify_proof`] for the meaning of the result.
+	pub fn verify(&self, root_hash: H256) -> Result<Option<Bytes>, StateProofError> {
+		verify_proof(root_hash, &self.key, &self.nodes)
+	}
+}
+
+/// Verifies that `nodes` prove the value stored under `key` in the trie with root `root_hash`.
+///
+/// `root_hash` is expected in the byte order used by the RPC API (e.g. the `roothash` field of
+/// `getstateroot`). Returns `Ok(Some(value))` if the key is included in the trie and `Ok(None)`
+/// if the proof shows that the key is absent. A proof that does not contain every node on the
+/// path to the key is rejected with [`StateProofError::MissingNode`].
+pub fn verify_proof(
+	root_hash: H256,
+	key: &[u8],
+	nodes: &[Bytes],
+) -> Result<Option<Bytes>, StateProofError> {
+	if key.is_empty() || key.len() > StateProof::MAX_KEY_LENGTH {
+		return Err(StateProofError::InvalidKey(key.len()))
+	}
+
+	let nodes: HashMap<[u8; 32], &Bytes> =
+		nodes.iter().map(|node| (MptNode::hash_of(node), node)).collect();
+
+	let mut root = root_hash.to_fixed_bytes();
+	root.reverse();
+
+	let path = to_nibbles(key);
+	let mut path = path.as_slice();
+	let mut next = MptChild::Hash(root);
+
+	loop {
+		let hash = match next {
+			MptChild::Hash(hash) => hash,
+			MptChild::Empty => return Ok(None),
+		};
+		let bytes = nodes.get(&hash).ok_or_else(|| {
+			let mut display = hash;
+			display.reverse();
+			StateProofError::MissingNode(format!("0x{}", hex::encode(display)))
+		})?;
+
+		match MptNode::from_bytes(bytes)? {
+			MptNode::Branch { children } => {
+				debug_assert_eq!(children.len(), BRANCH_CHILD_COUNT);
+				match path.split_first() {
+					Some((nibble, rest)) => {
+						next = children[*nibble as usize].clone();
+						path = rest;
+					},
+					None => next = children[BRANCH_CHILD_COUNT - 1].clone(),
+				}
+			},
+			MptNode::Extension { key, next: child } => {
+				if !path.starts_with(&key) {
+					return Ok(None)
+				}
+				path = &path[key.len()..];
+				next = child;
+			},
+			MptNode::Leaf { value } => return Ok(if path.is_empty() { Some(value) } else { None }),
+			MptNode::Hash { hash } => next = MptChild::Hash(hash),
+			MptNode::Empty => return Ok(None),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_codec::Encoder;
+
+	fn leaf(value: &[u8]) -> Bytes {
+		let mut writer = Encoder::new();
+		writer.write_u8(MptNode::LEAF_TYPE);
+		writer.write_var_bytes(value);
+		writer.to_bytes()
+	}
+
+	fn write_child(writer: &mut Encoder, child: Option<&Bytes>) {
+		match child {
+			Some(node) => {
+				writer.write_u8(MptNode::HASH_TYPE);
+				writer.write_bytes(&MptNode::hash_of(node));
+			},
+			None => writer.write_u8(MptNode::EMPTY_TYPE),
+		}
+	}
+
+	fn extension(key: &[u8], next: &Bytes) -> Bytes {
+		let mut writer = Encoder::new();
+		writer.write_u8(MptNode::EXTENSION_TYPE);
+		writer.write_var_bytes(key);
+		write_child(&mut writer, Some(next));
+		writer.to_bytes()
+	}
+
+	fn branch(children: &[(usize, &Bytes)]) -> Bytes {
+		let mut writer = Encoder::new();
+		writer.write_u8(MptNode::BRANCH_TYPE);
+		for index in 0..BRANCH_CHILD_COUNT {
+			let child = children.iter().find(|(i, _)| *i == index).map(|(_, node)| *node);
+			write_child(&mut writer, child);
+		}
+		writer.to_bytes()
+	}
+
+	fn root_of(node: &Bytes) -> H256 {
+		let mut hash = MptNode::hash_of(node);
+		hash.reverse();
+		H256::from(hash)
+	}
+
+	// Trie holding 0x0102 => "ab" and 0x0103 => "cd":
+	// extension [0, 1, 0] -> branch { 2: leaf "ab", 3: leaf "cd" }
+	fn sample_trie() -> (H256, Vec<Bytes>) {
+		let leaf_ab = leaf(b"ab");
+		let leaf_cd = leaf(b"cd");
+		let branch = branch(&[(2, &leaf_ab), (3, &leaf_cd)]);
+		let root = extension(&[0, 1, 0], &branch);
+		(root_of(&root), vec![root, branch, leaf_ab, leaf_cd])
+	}
+
+	#[test]
+	fn test_verify_inclusion() {
+		let (root, nodes) = sample_trie();
+		assert_eq!(verify_proof(root, &[0x01, 0x02], &nodes).unwrap(), Some(b"ab".to_vec()));
+		assert_eq!(verify_proof(root, &[0x01, 0x03], &nodes).unwrap(), Some(b"cd".to_vec()));
+	}
+
+	#[test]
+	fn test_verify_exclusion() {
+		let (root, nodes) = sample_trie();
+		assert_eq!(verify_proof(root, &[0x01, 0x04], &nodes).unwrap(), None);
+		assert_eq!(verify_proof(root, &[0x02, 0x02], &nodes).unwrap(), None);
+	}
+
+	#[test]
+	fn test_verify_missing_node() {
+		let (root, mut nodes) = sample_trie();
+		nodes.pop();
+		assert!(matches!(
+			verify_proof(root, &[0x01, 0x03], &nodes),
+			Err(StateProofError::MissingNode(_))
+		));
+	}
+
+	#[test]
+	fn test_verify_wrong_root() {
+		let (_, nodes) = sample_trie();
+		assert!(verify_proof(H256::zero(), &[0x01, 0x02], &nodes).is_err());
+	}
+
+	#[test]
+	fn test_decode_base64_proof() {
+		let (root, nodes) = sample_trie();
+		let mut writer = Encoder::new();
+		writer.write_var_bytes(&[0x01, 0x02]);
+		writer.write_var_int(nodes.len() as u64);
+		nodes.iter().for_each(|node| writer.write_var_bytes(node));
+		let encoded = encoding::base64_encode(writer.to_bytes());
+
+		let proof = StateProof::from_base64(&encoded).unwrap();
+		assert_eq!(proof.key, vec![0x01, 0x02]);
+		assert_eq!(proof.verify(root).unwrap(), Some(b"ab".to_vec()));
+	}
+}
@@ -0,0 +1,111 @@
+//! Bulk download of blocks, see [`Provider::get_blocks`](crate::Provider::get_blocks).
+use crate::{maybe_connectivity, ProviderError};
+use futures_core::stream::Stream;
+use futures_timer::Delay;
+use futures_util::stream::{self, StreamExt};
+use instant::Duration;
+use std::{future::Future, ops::Range};
+use tracing::trace;
+
+/// How often a request is attempted before its error is yielded
+pub const MAX_ATTEMPTS: u32 = 4;
+
+/// The backoff before the first retry, doubled on every further retry
+const INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Fetches the items at the heights of `range` with up to `concurrency` requests in flight,
+/// yielding them in height order. Transient failures are retried with an exponential backoff;
+/// once the retries are exhausted the error is yielded in place of the item and fetching
+/// continues with the next height.
+pub(crate) fn fetch_ordered<'a, T, F, Fut>(
+	range: Range<u32>,
+	concurrency: usize,
+	fetch: F,
+) -> impl Stream<Item = Result<T, ProviderError>> + 'a
+where
+	T: 'a,
+	F: Fn(u32) -> Fut + Clone + 'a,
+	Fut: Future<Output = Result<T, ProviderError>> + 'a,
+{
+	stream::iter(range)
+		.map(move |index| fetch_with_retry(fetch.clone(), index))
+		.buffered(concurrency.max(1))
+}
+
+async fn fetch_with_retry<T, F, Fut>(fetch: F, index: u32) -> Result<T, ProviderError>
+where
+	F: Fn(u32) -> Fut,
+	Fut: Future<Output = Result<T, ProviderError>>,
+{
+	let mut backoff = INITIAL_BACKOFF;
+	let mut attempt = 1;
+	loop {
+		match fetch(index).await {
+			Err(err) if attempt < MAX_ATTEMPTS && is_transient(&err) => {
+				trace!(index, attempt, err = ?err, "retrying block request");
+				Delay::new(backoff).await;
+				backoff *= 2;
+				attempt += 1;
+			},
+			result => return result,
+		}
+	}
+}
+
+/// Whether `err` is likely to go away when the request is repeated, i.e. it was not returned
+/// by the node itself.
+pub(crate) fn is_transient(err: &ProviderError) -> bool {
+	match err {
+		ProviderError::JsonRpcClientError(err) =>
+			err.as_error_response().is_none() && err.as_serde_error().is_none(),
+		err => maybe_connectivity(err),
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::MockError;
+	use std::sync::{Arc, Mutex};
+
+	#[tokio::test]
+	async fn yields_in_order_and_retries() {
+		let attempts = Arc::new(Mutex::new(Vec::new()));
+		let fetch = {
+			let attempts = attempts.clone();
+			move |index: u32| {
+				let attempts = attempts.clone();
+				async move {
+					let first = {
+						let mut attempts = attempts.lock().unwrap();
+						attempts.push(index);
+						attempts.iter().filter(|&&i| i == index).count() == 1
+					};
+					// later heights complete first
+					tokio::time::sleep(Duration::from_millis(u64::from(10 - index))).await;
+					match index {
+						3 if first => Err(ProviderError::JsonRpcClientError(Box::new(
+							MockError::EmptyResponses,
+						))),
+						7 => Err(ProviderError::CustomError("unknown block".to_string())),
+						_ => Ok(index * 10),
+					}
+				}
+			}
+		};
+
+		let results: Vec<_> = fetch_ordered(0..10, 4, fetch).collect().await;
+		assert_eq!(results.len(), 10);
+		for (index, result) in results.iter().enumerate() {
+			match index {
+				7 => assert!(matches!(result, Err(ProviderError::CustomError(_)))),
+				_ => assert_eq!(result.as_ref().unwrap(), &(index as u32 * 10)),
+			}
+		}
+		// height 3 was retried once, the permanent error of height 7 was not
+		let attempts = attempts.lock().unwrap();
+		assert_eq!(attempts.iter().filter(|&&i| i == 3).count(), 2);
+		assert_eq!(attempts.iter().filter(|&&i| i == 7).count(), 1);
+	}
+}
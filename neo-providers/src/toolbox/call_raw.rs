@@ -1,14 +1,19 @@
-//! Overrides for the `neo_call` rpc method
+//! Options for test invocations of the script of a transaction, see [`Provider::call_raw`].
 
 use crate::{
-	core::{responses::neo_find_states::States, transaction::transaction::Transaction},
+	core::transaction::{
+		signers::{signer::Signer, transaction_signer::TransactionSigner},
+		transaction::Transaction,
+	},
 	utils,
 	utils::PinBoxFut,
-	JsonRpcClient, Provider, ProviderError,
+	Capability, JsonRpcClient, Provider, ProviderError,
+};
+use neo_types::{
+	block::BlockId, encoding, invocation_result::InvocationResult, serde_value::ValueExtension,
+	stack_item::FromStackItem,
 };
-use neo_types::{block::BlockId, Bytes};
 use pin_project::pin_project;
-use serde::{ser::SerializeTuple, Serialize};
 use std::{
 	fmt,
 	future::Future,
@@ -16,12 +21,27 @@ use std::{
 	task::{Context, Poll},
 };
 
-/// Provides methods for overriding parameters to the `neo_call` rpc method
-pub trait RawCall<'a> {
-	/// Sets the block number to execute against
-	fn block(self, id: BlockId) -> Self;
+/// A [`RawCall`] decoding the first item of the result stack, see [`RawCall::decode`]
+pub type Decode<C, T> =
+	Map<C, fn(Result<InvocationResult, ProviderError>) -> Result<T, ProviderError>>;
+
+/// Provides methods for overriding the parameters of a test invocation
+pub trait RawCall {
+	/// Runs the invocation with `signers` instead of the signers of the transaction, e.g. to
+	/// check the witness scope of a signer before adding it.
+	fn signers(self, signers: Vec<Signer>) -> Self;
+
+	/// Runs the invocation against the state after the block `id` instead of the latest one.
+	///
+	/// Historic invocations are sent as `invokescripthistoric`, which requires a node keeping
+	/// the historic state, e.g. neo-go with `RemoveUntraceableBlocks` disabled. Known nodes
+	/// without [`Capability::HistoricInvocations`] fail with [`ProviderError::UnsupportedRPC`]
+	/// before anything is sent.
+	fn at_block(self, id: BlockId) -> Self;
 
-	fn state(self, state: &'a States) -> Self;
+	/// Requests the diagnostics of the invocation, the tree of invoked contracts and the storage
+	/// changes, in [`InvocationResult::diagnostics`].
+	fn with_diagnostics(self) -> Self;
 
 	/// Maps a closure `f` over the result of `.await`ing this call
 	fn map<F>(self, f: F) -> Map<Self, F>
@@ -30,19 +50,53 @@ pub trait RawCall<'a> {
 	{
 		Map::new(self, f)
 	}
+
+	/// Decodes the first item of the result stack into a `T`. Resolves to a
+	/// [`ProviderError::VmFault`] if the invocation faulted.
+	fn decode<T: FromStackItem>(self) -> Decode<Self, T>
+	where
+		Self: Sized,
+	{
+		self.map(decode_first::<T> as fn(_) -> _)
+	}
 }
 
-/// A builder which implements [`RawCall`] methods for overriding `neo_call` parameters.
+fn decode_first<T: FromStackItem>(
+	result: Result<InvocationResult, ProviderError>,
+) -> Result<T, ProviderError> {
+	let item = result?.into_result()?.stack.into_iter().next().ok_or_else(|| {
+		ProviderError::CustomError("the invocation returned an empty stack".to_string())
+	})?;
+	Ok(T::from_stack_item(item)?)
+}
+
+/// A builder which implements [`RawCall`] methods for overriding the parameters of a test
+/// invocation.
 ///
 /// `CallBuilder` also implements [`std::future::Future`], so `.await`ing a `CallBuilder` will
-/// resolve to the result of executing the `neo_call`.
+/// resolve to the result of the invocation.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_providers::{call_raw::RawCall, Http, Provider};
+/// use neo_types::block::BlockId;
+/// # use neo_providers::core::transaction::transaction::Transaction;
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(tx: Transaction) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let balance: i64 = provider.call_raw(&tx).at_block(BlockId::Number(100)).decode().await?;
+/// let result = provider.call_raw(&tx).with_diagnostics().await?;
+/// # Ok(()) }
+/// ```
 #[must_use = "call_raw::CallBuilder does nothing unless you `.await` or poll it"]
 pub enum CallBuilder<'a, P> {
 	/// The primary builder which exposes [`RawCall`] methods.
 	Build(Caller<'a, P>),
 	/// Used by the [`std::future::Future`] implementation. You are unlikely to encounter this
 	/// variant unless you are constructing your own [`RawCall`] wrapper type.
-	Wait(PinBoxFut<'a, Bytes>),
+	Wait(PinBoxFut<'a, InvocationResult>),
 }
 
 impl<P: fmt::Debug> fmt::Debug for CallBuilder<'_, P> {
@@ -84,18 +138,22 @@ impl<'a, P> CallBuilder<'a, P> {
 	}
 }
 
-impl<'a, P> RawCall<'a> for CallBuilder<'a, P> {
-	/// Sets the block number to execute against
-	fn block(self, id: BlockId) -> Self {
+impl<'a, P> RawCall for CallBuilder<'a, P> {
+	fn signers(self, signers: Vec<Signer>) -> Self {
+		self.map_input(|call| call.input.signers = Some(signers))
+	}
+
+	fn at_block(self, id: BlockId) -> Self {
 		self.map_input(|call| call.input.block = Some(id))
 	}
-	fn state(self, state: &'a States) -> Self {
-		self.map_input(|call| call.input.state = Some(state))
+
+	fn with_diagnostics(self) -> Self {
+		self.map_input(|call| call.input.diagnostics = true)
 	}
 }
 
 impl<'a, P: JsonRpcClient> Future for CallBuilder<'a, P> {
-	type Output = Result<Bytes, ProviderError>;
+	type Output = Result<InvocationResult, ProviderError>;
 
 	fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
 		let pin = self.get_mut();
@@ -111,7 +169,7 @@ impl<'a, P: JsonRpcClient> Future for CallBuilder<'a, P> {
 	}
 }
 
-/// Holds the inputs to the `neo_call` rpc method along with the rpc provider.
+/// Holds the inputs of the test invocation along with the rpc provider.
 /// This type is constructed by [`CallBuilder::new`].
 #[derive(Clone, Debug)]
 pub struct Caller<'a, P> {
@@ -127,44 +185,67 @@ impl<'a, P> Caller<'a, P> {
 }
 
 impl<'a, P: JsonRpcClient> Caller<'a, P> {
-	/// Executes an `neo_call` rpc request with the overriden parameters. Returns a future that
-	/// resolves to the result of the request.
-	fn execute(&self) -> impl Future<Output = Result<Bytes, ProviderError>> + 'a {
-		self.provider.request("neo_call", utils::serialize(&self.input))
+	/// Sends `invokescript`, or `invokescripthistoric` for a block, with the overridden
+	/// parameters. Returns a future that resolves to the result of the request.
+	fn execute(&self) -> impl Future<Output = Result<InvocationResult, ProviderError>> + 'a {
+		let (method, params) = self.input.to_params();
+		let historic = self.input.block.is_some();
+		let provider = self.provider;
+		async move {
+			if historic {
+				// unknown nodes get the request, they may still support it
+				if let Ok(client) = provider.node_client().await {
+					if !client.supports(Capability::HistoricInvocations) {
+						return Err(ProviderError::UnsupportedRPC)
+					}
+				}
+			}
+			provider.request(method, params).await
+		}
 	}
 }
 
-/// The input parameters to the `neo_call` rpc method
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// The input parameters of the test invocation
+#[derive(Clone, Debug)]
 struct CallInput<'a> {
 	tx: &'a Transaction,
+	signers: Option<Vec<Signer>>,
 	block: Option<BlockId>,
-	state: Option<&'a States>,
+	diagnostics: bool,
 }
 
 impl<'a> CallInput<'a> {
 	fn new(tx: &'a Transaction) -> Self {
-		Self { tx, block: None, state: None }
+		Self { tx, signers: None, block: None, diagnostics: false }
 	}
-}
-
-impl<'a> Serialize for CallInput<'a> {
-	fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-	where
-		S: serde::ser::Serializer,
-	{
-		let len = 2 + self.state.is_some() as usize;
 
-		let mut tup = serializer.serialize_tuple(len)?;
-		tup.serialize_element(self.tx)?;
+	/// The RPC method and its parameters
+	fn to_params(&self) -> (&'static str, Vec<serde_json::Value>) {
+		let signers: Vec<TransactionSigner> = self
+			.signers
+			.as_ref()
+			.unwrap_or(&self.tx.signers)
+			.iter()
+			.cloned()
+			.map(Into::into)
+			.collect();
 
-		let block = self.block.unwrap();
-		tup.serialize_element(&block)?;
-
-		if let Some(state) = self.state {
-			tup.serialize_element(state)?;
+		let mut params =
+			vec![encoding::base64_encode(&self.tx.script).to_value(), utils::serialize(&signers)];
+		if self.diagnostics {
+			params.push(true.to_value());
+		}
+		match self.block {
+			Some(block) => {
+				let block = match block {
+					BlockId::Number(index) => index.to_value(),
+					BlockId::Hash(hash) => hash.to_value(),
+				};
+				params.insert(0, block);
+				("invokescripthistoric", params)
+			},
+			None => ("invokescript", params),
 		}
-		tup.end()
 	}
 }
 
@@ -195,18 +276,20 @@ impl<T, F> Map<T, F> {
 	}
 }
 
-impl<'a, T, F> RawCall<'a> for Map<T, F>
+impl<T, F> RawCall for Map<T, F>
 where
-	T: RawCall<'a>,
+	T: RawCall,
 {
-	/// Sets the block number to execute against
-	fn block(self, id: BlockId) -> Self {
-		Self { inner: self.inner.block(id), f: self.f }
+	fn signers(self, signers: Vec<Signer>) -> Self {
+		Self { inner: self.inner.signers(signers), f: self.f }
+	}
+
+	fn at_block(self, id: BlockId) -> Self {
+		Self { inner: self.inner.at_block(id), f: self.f }
 	}
 
-	/// Note that not all client implementations will support this as a parameter.
-	fn state(self, state: &'a States) -> Self {
-		Self { inner: self.inner.state(state), f: self.f }
+	fn with_diagnostics(self) -> Self {
+		Self { inner: self.inner.with_diagnostics(), f: self.f }
 	}
 }
 
@@ -223,3 +306,22 @@ where
 		Poll::Ready((pin.f)(x))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_call_input_params() {
+		let tx = Transaction { script: vec![0x11, 0x40], ..Default::default() };
+		let input = CallInput::new(&tx);
+		assert_eq!(input.to_params(), ("invokescript", vec![json!("EUA="), json!([])]));
+
+		let input = CallInput { block: Some(BlockId::Number(7)), diagnostics: true, ..input };
+		assert_eq!(
+			input.to_params(),
+			("invokescripthistoric", vec![json!(7), json!("EUA="), json!([]), json!(true)])
+		);
+	}
+}
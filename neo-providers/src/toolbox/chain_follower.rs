@@ -0,0 +1,243 @@
+use crate::{
+	core::responses::neo_block::NeoBlock, JsonRpcClient, Middleware, Provider, ProviderError,
+};
+use futures_core::stream::Stream;
+use futures_timer::Delay;
+use futures_util::stream::{self, StreamExt};
+use instant::Duration;
+use std::collections::VecDeque;
+use tracing::warn;
+
+/// A change of the canonical chain, see [`ChainFollower`].
+#[derive(Debug, Clone)]
+pub enum BlockEvent {
+	/// The block was added on top of the canonical chain
+	Applied(NeoBlock),
+	/// The block was removed from the top of the canonical chain
+	Reverted(NeoBlock),
+}
+
+impl BlockEvent {
+	/// The applied or reverted block
+	pub fn block(&self) -> &NeoBlock {
+		match self {
+			BlockEvent::Applied(block) | BlockEvent::Reverted(block) => block,
+		}
+	}
+}
+
+/// Follows the head of the chain and reports every block that is added to or removed from the
+/// canonical chain.
+///
+/// Blocks persisted by a node are final, but the chain seen through an endpoint can still change:
+/// a load balancer can switch between nodes at different heights or, on a private network, the
+/// chain can be reset. The follower remembers the hashes of the most recent blocks and, on every
+/// poll, compares them with the hashes the node reports at the same heights. Blocks that no longer
+/// match are reverted, newest first, before the blocks of the new chain are applied, so state
+/// derived from the blocks can be rolled back and rebuilt.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_util::StreamExt;
+/// use neo_providers::{BlockEvent, ChainFollower, Http, Provider};
+/// use std::{convert::TryFrom, time::Duration};
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let follower = ChainFollower::new(&provider, Duration::from_secs(5));
+/// let mut events = Box::pin(follower.into_stream());
+/// while let Some(event) = events.next().await {
+///     match event? {
+///         BlockEvent::Applied(block) => println!("applied {}", block.index),
+///         BlockEvent::Reverted(block) => println!("reverted {}", block.index),
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct ChainFollower<'a, P> {
+	provider: &'a Provider<P>,
+	poll_interval: Duration,
+	max_depth: usize,
+	/// The most recent blocks of the canonical chain, the head last
+	recent: VecDeque<NeoBlock>,
+	/// The index of the next block to apply, the head of the node if unset
+	next: Option<u32>,
+}
+
+impl<'a, P: JsonRpcClient> ChainFollower<'a, P> {
+	/// Creates a follower that polls `provider` every `poll_interval`, starting at the current
+	/// head of the chain.
+	pub fn new(provider: &'a Provider<P>, poll_interval: Duration) -> Self {
+		Self { provider, poll_interval, max_depth: 64, recent: VecDeque::new(), next: None }
+	}
+
+	/// Starts at the block `index` instead of the current head.
+	#[must_use]
+	pub fn start_at(mut self, index: u32) -> Self {
+		self.next = Some(index);
+		self
+	}
+
+	/// Sets the number of recent blocks checked for reorganizations, 64 by default. Blocks
+	/// older than that are considered final.
+	#[must_use]
+	pub fn max_depth(mut self, max_depth: usize) -> Self {
+		self.max_depth = max_depth.max(1);
+		self
+	}
+
+	/// The head of the canonical chain, once the first block was applied
+	pub fn head(&self) -> Option<&NeoBlock> {
+		self.recent.back()
+	}
+
+	/// Polls the node once and returns the blocks reverted and applied since the last poll, in
+	/// the order they have to be processed.
+	pub async fn poll(&mut self) -> Result<Vec<BlockEvent>, ProviderError> {
+		let mut events = vec![];
+		let head = self.provider.get_block_count().await?.saturating_sub(1);
+
+		while let Some(tip) = self.recent.back() {
+			let index = tip.index as u32;
+			if index <= head && self.provider.get_block_hash(index).await? == tip.hash {
+				break
+			}
+			let reverted = self.recent.pop_back().expect("tip exists");
+			self.next = Some(index);
+			events.push(BlockEvent::Reverted(reverted));
+		}
+		if self.recent.is_empty() && !events.is_empty() {
+			warn!(
+				depth = self.max_depth,
+				"reorganization reached the oldest tracked block, older blocks are not checked"
+			);
+		}
+
+		let mut next = self.next.unwrap_or(head);
+		while next <= head {
+			let block = self.provider.get_block_by_index(next, true).await?;
+			if self.recent.back().map_or(false, |tip| tip.hash != block.prev_block_hash) {
+				// the chain changed while polling, the next poll reverts the stale blocks
+				break
+			}
+			self.recent.push_back(block.clone());
+			if self.recent.len() > self.max_depth {
+				self.recent.pop_front();
+			}
+			events.push(BlockEvent::Applied(block));
+			next += 1;
+		}
+		self.next = Some(next);
+		Ok(events)
+	}
+
+	/// Turns the follower into a stream of events, polling every poll interval. Polling errors
+	/// are yielded and polling continues on the next interval. The stream ends when the
+	/// [`Shutdown`](crate::Shutdown) of the provider is triggered.
+	pub fn into_stream(self) -> impl Stream<Item = Result<BlockEvent, ProviderError>> + 'a {
+		let shutdown = self.provider.shutdown().signal();
+		stream::unfold(
+			(self, VecDeque::new(), false),
+			|(mut follower, mut events, mut wait)| async move {
+				loop {
+					if let Some(event) = events.pop_front() {
+						return Some((Ok(event), (follower, events, wait)))
+					}
+					if wait {
+						Delay::new(follower.poll_interval).await;
+					}
+					wait = true;
+					match follower.poll().await {
+						Ok(polled) => events.extend(polled),
+						Err(err) => return Some((Err(err), (follower, events, wait))),
+					}
+				}
+			},
+		)
+		.take_until(shutdown)
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::{Expectation, MockProvider};
+	use neo_types::serde_value::ValueExtension;
+	use primitive_types::H256;
+
+	fn block(index: u32, hash: u8, prev: u8) -> NeoBlock {
+		NeoBlock {
+			hash: H256::repeat_byte(hash),
+			size: 0,
+			version: 0,
+			prev_block_hash: H256::repeat_byte(prev),
+			merkle_root_hash: H256::zero(),
+			time: 0,
+			index: index as i32,
+			primary: None,
+			next_consensus: String::new(),
+			witnesses: None,
+			transactions: None,
+			confirmations: 0,
+			next_block_hash: None,
+		}
+	}
+
+	fn expect_block(mock: &MockProvider, block: &NeoBlock) {
+		let params = vec![(block.index as u32).to_value(), 1.to_value()];
+		mock.expect(Expectation::new("getblock").with_params(params).returning(block));
+	}
+
+	fn summary(events: &[BlockEvent]) -> Vec<(bool, u8)> {
+		events
+			.iter()
+			.map(|event| (matches!(event, BlockEvent::Applied(_)), event.block().hash[0]))
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn reverts_replaced_blocks() {
+		let mock = MockProvider::new();
+		let provider = Provider::new(mock.clone());
+		let mut follower = ChainFollower::new(&provider, Duration::from_millis(1)).start_at(0);
+
+		mock.expect(Expectation::new("getblockcount").returning(2u32));
+		expect_block(&mock, &block(0, 0xa0, 0));
+		expect_block(&mock, &block(1, 0xa1, 0xa0));
+		let events = follower.poll().await.unwrap();
+		assert_eq!(summary(&events), vec![(true, 0xa0), (true, 0xa1)]);
+
+		// the endpoint switched to a chain that replaced block 1
+		mock.expect(Expectation::new("getblockcount").returning(3u32));
+		mock.expect(Expectation::new("getblockhash").returning(H256::repeat_byte(0xb1)));
+		mock.expect(Expectation::new("getblockhash").returning(H256::repeat_byte(0xa0)));
+		expect_block(&mock, &block(1, 0xb1, 0xa0));
+		expect_block(&mock, &block(2, 0xb2, 0xb1));
+		let events = follower.poll().await.unwrap();
+		assert_eq!(summary(&events), vec![(false, 0xa1), (true, 0xb1), (true, 0xb2)]);
+		assert_eq!(follower.head().unwrap().hash, H256::repeat_byte(0xb2));
+		mock.assert_expectations_met().unwrap();
+	}
+
+	#[tokio::test]
+	async fn stream_starts_at_head() {
+		let mock = MockProvider::new();
+		let provider = Provider::new(mock.clone());
+		mock.expect(Expectation::new("getblockcount").returning(5u32));
+		expect_block(&mock, &block(4, 0xa4, 0xa3));
+		mock.expect(Expectation::new("getblockcount").returning(6u32));
+		mock.expect(Expectation::new("getblockhash").returning(H256::repeat_byte(0xa4)));
+		expect_block(&mock, &block(5, 0xa5, 0xa4));
+
+		let events: Vec<_> = ChainFollower::new(&provider, Duration::from_millis(1))
+			.into_stream()
+			.take(2)
+			.map(Result::unwrap)
+			.collect()
+			.await;
+		assert_eq!(summary(&events), vec![(true, 0xa4), (true, 0xa5)]);
+	}
+}
@@ -0,0 +1,132 @@
+use pin_project::pin_project;
+use std::{
+	cell::RefCell,
+	fmt,
+	future::Future,
+	pin::Pin,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	task::{Context, Poll},
+};
+
+thread_local! {
+	static CURRENT: RefCell<Option<CorrelationId>> = RefCell::new(None);
+}
+
+/// Identifies the requests sent on behalf of one logical operation, e.g. all the RPC calls of a
+/// transfer, so they can be correlated in logs.
+///
+/// The id of the operation a future runs in is set with [`CorrelationId::scope`] and read with
+/// [`CorrelationId::current`]. [`Provider::request`](crate::Provider::request) records it on the
+/// span of every request.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_providers::{CorrelationId, Http, Middleware, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// // e.g. the id of the HTTP request being served
+/// let id = CorrelationId::new("req-42");
+/// let height = id.scope(provider.get_block_count()).await?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CorrelationId(Arc<str>);
+
+impl CorrelationId {
+	/// Creates an id from an existing one, e.g. received in a request header.
+	pub fn new(id: impl Into<String>) -> Self {
+		Self(id.into().into())
+	}
+
+	/// Generates an id unique within the process.
+	pub fn generate() -> Self {
+		static NEXT: AtomicU64 = AtomicU64::new(1);
+		Self::new(format!("{:016x}", NEXT.fetch_add(1, Ordering::Relaxed)))
+	}
+
+	/// The id of the operation the current future runs in, if any.
+	pub fn current() -> Option<Self> {
+		CURRENT.with(|current| current.borrow().clone())
+	}
+
+	/// The id of the current operation, or a new one if there is none.
+	pub fn current_or_generate() -> Self {
+		Self::current().unwrap_or_else(Self::generate)
+	}
+
+	/// The id as a string
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	/// Runs `future` as part of the operation identified by this id.
+	pub fn scope<F: Future>(self, future: F) -> WithCorrelationId<F> {
+		WithCorrelationId { id: self, inner: future }
+	}
+}
+
+impl fmt::Display for CorrelationId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+/// A future running with a [`CorrelationId`], see [`CorrelationId::scope`]
+#[must_use = "futures do nothing unless polled"]
+#[pin_project]
+#[derive(Debug)]
+pub struct WithCorrelationId<F> {
+	id: CorrelationId,
+	#[pin]
+	inner: F,
+}
+
+impl<F: Future> Future for WithCorrelationId<F> {
+	type Output = F::Output;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = self.project();
+		let previous = CURRENT.with(|current| current.replace(Some(this.id.clone())));
+		// restores the outer id even if polling panics
+		let _restore = Restore(previous);
+		this.inner.poll(cx)
+	}
+}
+
+struct Restore(Option<CorrelationId>);
+
+impl Drop for Restore {
+	fn drop(&mut self) {
+		let previous = self.0.take();
+		CURRENT.with(|current| *current.borrow_mut() = previous);
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn scopes_nest() {
+		assert_eq!(CorrelationId::current(), None);
+		let outer = CorrelationId::new("outer");
+		let inner = CorrelationId::generate();
+		let ids = outer
+			.clone()
+			.scope(async {
+				let nested = inner.clone().scope(async { CorrelationId::current() }).await;
+				(CorrelationId::current(), nested)
+			})
+			.await;
+		assert_eq!(ids, (Some(outer), Some(inner)));
+		assert_eq!(CorrelationId::current(), None);
+		assert_ne!(CorrelationId::generate(), CorrelationId::generate());
+	}
+}
@@ -0,0 +1,100 @@
+use crate::{Middleware, MiddlewareError, ProviderError};
+use async_trait::async_trait;
+use neo_config::NeoConfig;
+
+/// Detects the [`NeoConfig`] of the network a node is connected to.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait NeoConfigExt: Sized {
+	/// Reads the network magic, address version, block time, `max_valid_until_block_increment`
+	/// and validators count from `getversion`, and the native contract hashes from
+	/// `getnativecontracts`. The other values keep their defaults.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use neo_config::NeoConfig;
+	/// use neo_providers::{Http, NeoConfigExt, Provider};
+	/// use std::convert::TryFrom;
+	///
+	/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+	/// let provider = Provider::<Http>::try_from("http://localhost:50012")?;
+	/// let config = NeoConfig::detect(&provider).await?;
+	/// provider.shared_config().store(config);
+	/// # Ok(()) }
+	/// ```
+	async fn detect<M: Middleware>(provider: &M) -> Result<Self, M::Error>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl NeoConfigExt for NeoConfig {
+	async fn detect<M: Middleware>(provider: &M) -> Result<Self, M::Error> {
+		let version = provider.get_version().await?;
+		let protocol = version.protocol.ok_or_else(|| {
+			M::Error::from_provider_err(ProviderError::IllegalState(
+				"getversion returned no protocol settings".to_string(),
+			))
+		})?;
+		let address_version = u8::try_from(protocol.address_version).map_err(|_| {
+			M::Error::from_provider_err(ProviderError::IllegalState(format!(
+				"invalid address version {}",
+				protocol.address_version
+			)))
+		})?;
+
+		let mut builder = NeoConfig::builder()
+			.network_magic(protocol.network)
+			.address_version(address_version)
+			.ms_per_block(protocol.ms_per_block)
+			.max_valid_until_block_increment(protocol.max_valid_until_block_increment);
+		if let Some(count) = protocol.validators_count {
+			builder = builder.validators_count(count);
+		}
+		for contract in provider.get_native_contracts().await? {
+			if let Some(name) = contract.manifest().name.clone() {
+				builder = builder.native_contract(name, contract.hash());
+			}
+		}
+		Ok(builder.build())
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::{Expectation, Provider};
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn detects_private_network() {
+		let (provider, mock) = Provider::mocked();
+		mock.expect(Expectation::new("getversion").returning(json!({
+			"tcpport": 50013,
+			"nonce": 1,
+			"useragent": "/Neo:3.6.0/",
+			"protocol": {
+				"network": 1234567890,
+				"validatorscount": 1,
+				"msperblock": 1000,
+				"maxvaliduntilblockincrement": 5760,
+				"maxtraceableblocks": 2102400,
+				"addressversion": 53,
+				"maxtransactionsperblock": 512,
+				"memorypoolmaxtransactions": 50000,
+				"initialgasdistribution": 5200000000000000u64
+			}
+		})));
+		mock.expect(Expectation::new("getnativecontracts").returning(json!([])));
+
+		let config = NeoConfig::detect(&provider).await.unwrap();
+		mock.assert_expectations_met().unwrap();
+		assert_eq!(config.network_magic, Some(1234567890));
+		assert_eq!(config.block_interval, 1_000);
+		assert_eq!(config.polling_interval, 1_000);
+		assert_eq!(config.max_valid_until_block_increment, 5_760);
+		assert_eq!(config.validators_count, Some(1));
+		assert_eq!(config.address_version, 53);
+	}
+}
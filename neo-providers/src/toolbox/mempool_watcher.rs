@@ -0,0 +1,159 @@
+use crate::{
+	core::responses::neo_transaction_result::TransactionResult,
+	utils::{interval, PinBoxFut},
+	JsonRpcClient, Middleware, Provider, ShutdownSignal,
+};
+use futures_core::stream::Stream;
+use futures_util::{stream::StreamExt, FutureExt};
+use instant::Duration;
+use pin_project::pin_project;
+use primitive_types::H256;
+use std::{
+	collections::HashSet,
+	future::Future,
+	pin::Pin,
+	task::{Context, Poll},
+	vec::IntoIter,
+};
+use tracing::error;
+
+enum WatcherState<'a> {
+	/// Waiting for the next poll
+	WaitForInterval,
+	/// Fetching the content of the mempool
+	GetMempool(PinBoxFut<'a, Vec<H256>>),
+	/// Yielding the hashes that were not in the previous snapshot
+	NextItem(IntoIter<H256>),
+}
+
+/// A stream of the hashes of transactions entering the mempool of a node.
+///
+/// The watcher polls `getrawmempool` and yields the hashes that were not part of the previous
+/// snapshot. Transactions that are already in the mempool when the watcher is created are not
+/// yielded. Polling errors are logged and the watcher retries on the next interval. The stream
+/// ends when the [`Shutdown`](crate::Shutdown) of the provider is triggered.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_util::StreamExt;
+/// use neo_providers::{Http, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let mut hashes = provider.watch_pending_transactions().take(5);
+/// while let Some(hash) = hashes.next().await {
+///     println!("new transaction: {hash:?}");
+/// }
+/// # Ok(()) }
+/// ```
+#[must_use = "streams do nothing unless polled"]
+#[pin_project]
+pub struct MempoolWatcher<'a, P> {
+	provider: &'a Provider<P>,
+	interval: Box<dyn Stream<Item = ()> + Send + Unpin>,
+	previous: Option<HashSet<H256>>,
+	state: WatcherState<'a>,
+	shutdown: ShutdownSignal,
+}
+
+impl<'a, P: JsonRpcClient> MempoolWatcher<'a, P> {
+	/// Creates a watcher that polls the mempool of `provider` every `poll_interval`.
+	pub fn new(provider: &'a Provider<P>, poll_interval: Duration) -> Self {
+		Self {
+			provider,
+			interval: Box::new(interval(poll_interval)),
+			previous: None,
+			state: WatcherState::GetMempool(Box::pin(provider.get_raw_mem_pool())),
+			shutdown: provider.shutdown().signal(),
+		}
+	}
+
+	/// Sets the polling interval.
+	#[must_use]
+	pub fn interval<T: Into<Duration>>(mut self, duration: T) -> Self {
+		self.interval = Box::new(interval(duration.into()));
+		self
+	}
+
+	/// Turns the watcher into a stream of the full transactions.
+	///
+	/// Transactions that left the mempool before they could be fetched are skipped.
+	pub fn transactions(self) -> impl Stream<Item = TransactionResult> + 'a {
+		let provider = self.provider;
+		self.filter_map(move |hash| {
+			provider.get_transaction(hash).map(move |res| match res {
+				Ok(tx) => tx,
+				Err(err) => {
+					error!("failed to fetch transaction {hash:?}: {err}");
+					None
+				},
+			})
+		})
+	}
+}
+
+impl<'a, P: JsonRpcClient> Stream for MempoolWatcher<'a, P> {
+	type Item = H256;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.project();
+		if this.shutdown.poll_unpin(cx).is_ready() {
+			return Poll::Ready(None)
+		}
+		loop {
+			*this.state = match this.state {
+				WatcherState::WaitForInterval => {
+					futures_util::ready!(this.interval.poll_next_unpin(cx));
+					WatcherState::GetMempool(Box::pin(this.provider.get_raw_mem_pool()))
+				},
+				WatcherState::GetMempool(fut) =>
+					match futures_util::ready!(fut.as_mut().poll(cx)) {
+						Ok(hashes) => {
+							let new = match this.previous.as_ref() {
+								Some(previous) => hashes
+									.iter()
+									.filter(|hash| !previous.contains(*hash))
+									.copied()
+									.collect(),
+								None => vec![],
+							};
+							*this.previous = Some(hashes.into_iter().collect());
+							WatcherState::NextItem(new.into_iter())
+						},
+						Err(err) => {
+							error!("failed to fetch the mempool: {err}");
+							WatcherState::WaitForInterval
+						},
+					},
+				WatcherState::NextItem(iter) => match iter.next() {
+					Some(hash) => return Poll::Ready(Some(hash)),
+					None => WatcherState::WaitForInterval,
+				},
+			};
+		}
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::MockProvider;
+
+	#[tokio::test]
+	async fn yields_new_mempool_entries() {
+		let mock = MockProvider::new();
+		let provider = Provider::new(mock.clone());
+		let (a, b, c) = (H256::repeat_byte(1), H256::repeat_byte(2), H256::repeat_byte(3));
+		// responses are popped from the back
+		mock.push::<Vec<H256>, _>(vec![b, c]).unwrap();
+		mock.push::<Vec<H256>, _>(vec![a, b]).unwrap();
+		mock.push::<Vec<H256>, _>(vec![a]).unwrap();
+
+		let watcher = MempoolWatcher::new(&provider, Duration::from_millis(1));
+		let hashes: Vec<H256> = watcher.take(2).collect().await;
+		assert_eq!(hashes, vec![b, c]);
+	}
+}
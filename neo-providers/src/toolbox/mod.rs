@@ -1,8 +1,36 @@
 mod pending_transaction;
 pub use pending_transaction::PendingTransaction;
 
+mod mempool_watcher;
+pub use mempool_watcher::MempoolWatcher;
+
 mod pending_escalator;
 pub use pending_escalator::EscalatingPending;
 
+pub mod block_range;
+
+mod chain_follower;
+pub use chain_follower::{BlockEvent, ChainFollower};
+
+mod correlation;
+pub use correlation::{CorrelationId, WithCorrelationId};
+
+pub mod redact;
+
+mod shutdown;
+pub use shutdown::{Shutdown, ShutdownGuard, ShutdownSignal};
+
+mod storage_iterator;
+pub use storage_iterator::StorageIterator;
+
+mod timeout;
+pub use timeout::timeout;
+
+pub mod transfer_history;
+pub use transfer_history::{Nep17TransferEvent, TransferDirection, TransferHistory};
+
 pub mod call_raw;
 pub use call_raw::*;
+
+mod detect_config;
+pub use detect_config::NeoConfigExt;
@@ -1,12 +1,11 @@
 #![allow(clippy::return_self_not_must_use)]
 
 use futures_timer::Delay;
-use futures_util::{stream::FuturesUnordered, StreamExt};
+use futures_util::{stream::FuturesUnordered, FutureExt, StreamExt};
 use instant::{Duration, Instant};
-use neo_types::Bytes;
+use neo_types::{encoding, Bytes};
 use pin_project::pin_project;
 use primitive_types::H256;
-use rustc_serialize::hex::ToHex;
 use std::{future::Future, pin::Pin, task::Poll};
 
 use crate::{
@@ -14,7 +13,7 @@ use crate::{
 		neo_send_raw_transaction::RawTransaction, neo_transaction_result::TransactionResult,
 	},
 	utils::PinBoxFut,
-	JsonRpcClient, Middleware, PendingTransaction, Provider, ProviderError,
+	JsonRpcClient, Middleware, PendingTransaction, Provider, ProviderError, ShutdownSignal,
 };
 
 /// States for the EscalatingPending future
@@ -42,6 +41,7 @@ where
 	last: Instant,
 	sent: Vec<RawTransaction>,
 	state: EscalatorStates<'a>,
+	shutdown: ShutdownSignal,
 }
 
 impl<'a, P> EscalatingPending<'a, P>
@@ -72,8 +72,9 @@ where
 			last: Instant::now(),
 			sent: vec![],
 			state: EscalatorStates::Initial(Box::pin(
-				provider.send_raw_transaction(first.to_hex()),
+				provider.send_raw_transaction(encoding::base64_encode(first)),
 			)),
+			shutdown: provider.shutdown().signal(),
 		}
 	}
 
@@ -185,6 +186,10 @@ where
 
 		let this = self.project();
 
+		if !matches!(this.state, Completed) && this.shutdown.poll_unpin(cx).is_ready() {
+			completed!(this, Err(ProviderError::Shutdown));
+		}
+
 		match this.state {
 			// In the initial state we're simply waiting on the first
 			// transaction broadcast to complete.
@@ -197,8 +202,9 @@ where
 				// broadcast, broadcast it
 				if this.last.elapsed() > *this.broadcast_interval {
 					if let Some(next_to_broadcast) = this.txns.pop() {
-						let fut =
-							this.provider.send_raw_transaction(hex::encode(next_to_broadcast));
+						let fut = this
+							.provider
+							.send_raw_transaction(encoding::base64_encode(next_to_broadcast));
 						*this.state = BroadcastingNew(fut);
 						cx.waker().wake_by_ref();
 						return Poll::Pending
@@ -1,13 +1,16 @@
 use crate::{
 	core::{
-		responses::neo_transaction_result::TransactionResult, transaction::transaction::Transaction,
+		responses::{
+			neo_application_log::ApplicationLog, neo_transaction_result::TransactionResult,
+		},
+		transaction::transaction::Transaction,
 	},
 	utils::{interval, PinBoxFut},
-	JsonRpcClient, Middleware, Provider, ProviderError,
+	JsonRpcClient, Middleware, Provider, ProviderError, ShutdownSignal,
 };
 use futures_core::stream::Stream;
 use futures_timer::Delay;
-use futures_util::stream::StreamExt;
+use futures_util::{stream::StreamExt, FutureExt};
 use instant::Duration;
 use neo_types::TxHash;
 use pin_project::pin_project;
@@ -45,6 +48,7 @@ pub struct PendingTransaction<'a, P> {
 	state: PendingTxState<'a>,
 	interval: Box<dyn Stream<Item = ()> + Send + Unpin>,
 	retries_remaining: usize,
+	shutdown: ShutdownSignal,
 }
 
 const DEFAULT_RETRIES: usize = 3;
@@ -61,6 +65,7 @@ impl<'a, P: JsonRpcClient> PendingTransaction<'a, P> {
 			state: PendingTxState::InitialDelay(delay),
 			interval: Box::new(interval(Duration::new(15, 0))),
 			retries_remaining: DEFAULT_RETRIES,
+			shutdown: provider.shutdown().signal(),
 		}
 	}
 
@@ -105,6 +110,28 @@ impl<'a, P: JsonRpcClient> PendingTransaction<'a, P> {
 		self.retries_remaining = retries;
 		self
 	}
+
+	/// Waits for the transaction to be included and checks that it succeeded.
+	///
+	/// A transaction is included in a block even if its script faults, and then only pays its
+	/// fees. This fetches the application log of the included transaction and resolves to it if
+	/// all executions halted. Fails with [`ProviderError::TransactionFaulted`], holding the
+	/// exception and notifications, if one faulted, and with
+	/// [`ProviderError::TransactionDropped`] if the transaction was dropped.
+	///
+	/// The node must run the `ApplicationLogs` plugin.
+	pub async fn await_success(self) -> Result<ApplicationLog, ProviderError> {
+		let tx_hash = self.tx_hash;
+		let provider = self.provider;
+		if self.await?.is_none() {
+			return Err(ProviderError::TransactionDropped(tx_hash))
+		}
+		provider
+			.get_application_log(tx_hash)
+			.await?
+			.into_result()
+			.map_err(|fault| ProviderError::TransactionFaulted { tx_hash, fault })
+	}
 }
 
 impl<'a, P> PendingTransaction<'a, P> {
@@ -154,6 +181,14 @@ impl<'a, P: JsonRpcClient> Future for PendingTransaction<'a, P> {
 	fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
 		let this = self.project();
 
+		if !matches!(this.state, PendingTxState::Completed)
+			&& this.shutdown.poll_unpin(ctx).is_ready()
+		{
+			tracing::debug!("Shutting down, stop polling pending tx {:?}", *this.tx_hash);
+			*this.state = PendingTxState::Completed;
+			return Poll::Ready(Err(ProviderError::Shutdown))
+		}
+
 		match this.state {
 			PendingTxState::InitialDelay(fut) => {
 				futures_util::ready!(fut.as_mut().poll(ctx));
@@ -173,16 +208,12 @@ impl<'a, P: JsonRpcClient> Future for PendingTransaction<'a, P> {
 				let tx_res = futures_util::ready!(fut.as_mut().poll(ctx));
 				// If the provider errors, just try again after the interval.
 				// nbd.
-				rewake_with_new_state_if!(
-					tx_res.is_err(),
-					ctx,
-					this,
-					PendingTxState::PausedGettingTx
-				);
+				let Ok(tx_opt) = tx_res else {
+					rewake_with_new_state!(ctx, this, PendingTxState::PausedGettingTx);
+				};
 
-				let tx_opt = tx_res.unwrap();
 				// If the tx is no longer in the mempool, return Ok(None)
-				if tx_opt.is_none() {
+				let Some(tx) = tx_opt else {
 					if *this.retries_remaining == 0 {
 						tracing::debug!("Dropped from mempool, pending tx {:?}", *this.tx_hash);
 						*this.state = PendingTxState::Completed;
@@ -191,10 +222,9 @@ impl<'a, P: JsonRpcClient> Future for PendingTransaction<'a, P> {
 
 					*this.retries_remaining -= 1;
 					rewake_with_new_state!(ctx, this, PendingTxState::PausedGettingTx);
-				}
+				};
 
 				// If it hasn't confirmed yet, poll again later
-				let tx = tx_opt.unwrap();
 				rewake_with_new_state_if!(
 					tx.confirmations.is_none(),
 					ctx,
@@ -0,0 +1,61 @@
+//! Redaction of secrets from logged RPC payloads.
+use serde_json::Value;
+
+/// Replaces the redacted values
+pub const REDACTED: &str = "<redacted>";
+
+/// The positions of the secret parameters of wallet RPC methods
+const SECRET_PARAMS: &[(&str, &[usize])] = &[
+	// WIF
+	("importprivkey", &[0]),
+	// path, password
+	("openwallet", &[1]),
+];
+
+/// Methods whose result is a secret
+const SECRET_RESULTS: &[&str] = &["dumpprivkey"];
+
+/// Returns `params` of a call of `method` with the secret parameters, like the private key of
+/// `importprivkey` or the password of `openwallet`, replaced by [`REDACTED`].
+pub fn redact_params(method: &str, mut params: Value) -> Value {
+	let secret = SECRET_PARAMS.iter().find(|(secret, _)| *secret == method);
+	if let (Some((_, positions)), Some(params)) = (secret, params.as_array_mut()) {
+		for &position in positions.iter() {
+			if let Some(param) = params.get_mut(position) {
+				*param = Value::from(REDACTED);
+			}
+		}
+	}
+	params
+}
+
+/// Returns `result` of a call of `method`, or [`REDACTED`] if it is a secret like the private
+/// key returned by `dumpprivkey`.
+pub fn redact_result(method: &str, result: Value) -> Value {
+	if SECRET_RESULTS.contains(&method) {
+		Value::from(REDACTED)
+	} else {
+		result
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn redacts_secrets() {
+		let wif = "L1eV34wPoj9weqhGijdDLtVQzUpWGHszXXpdU9dPuh2nRFFzFa7E";
+		assert_eq!(redact_params("importprivkey", json!([wif])), json!([REDACTED]));
+		assert_eq!(
+			redact_params("openwallet", json!(["wallet.json", "hunter2"])),
+			json!(["wallet.json", REDACTED])
+		);
+		assert_eq!(redact_result("dumpprivkey", json!(wif)), json!(REDACTED));
+
+		let params = json!(["0xd2a4cff31913016155e38e474a2c06d08be276cf", "symbol", []]);
+		assert_eq!(redact_params("invokefunction", params.clone()), params);
+		assert_eq!(redact_result("getblockcount", json!(42)), json!(42));
+	}
+}
@@ -0,0 +1,191 @@
+use crate::utils::lock;
+use std::{
+	future::Future,
+	pin::Pin,
+	sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	task::{Context, Poll, Waker},
+};
+
+#[derive(Debug, Default)]
+struct Inner {
+	triggered: AtomicBool,
+	tasks: AtomicUsize,
+	/// Woken whenever the shutdown is triggered or a task finishes
+	wakers: Mutex<Vec<Waker>>,
+}
+
+impl Inner {
+	fn register(&self, waker: &Waker) {
+		let mut wakers = lock(&self.wakers);
+		if !wakers.iter().any(|w| w.will_wake(waker)) {
+			wakers.push(waker.clone());
+		}
+	}
+
+	fn wake_all(&self) {
+		let wakers = std::mem::take(&mut *lock(&self.wakers));
+		wakers.into_iter().for_each(Waker::wake);
+	}
+}
+
+/// A handle to stop the background work of the crate.
+///
+/// The handle is shared by a [`Provider`](crate::Provider) and everything that polls or spawns
+/// tasks on its behalf: [`PendingTransaction`](crate::PendingTransaction),
+/// [`EscalatingPending`](crate::EscalatingPending), [`MempoolWatcher`](crate::MempoolWatcher)
+/// and the tasks of a [`SubscriptionMultiplexer`](crate::SubscriptionMultiplexer). Once the
+/// shutdown is triggered, streams end, pending futures resolve to [`ProviderError::Shutdown`]
+/// and spawned tasks clean up and exit.
+///
+/// Spawned tasks hold a [`ShutdownGuard`] while they run, so [`Shutdown::shutdown`] can wait
+/// for them to finish, e.g. before persisting state at process exit.
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_providers::{Http, Provider};
+/// use std::convert::TryFrom;
+///
+/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let watcher = provider.watch_pending_transactions();
+/// // ...
+/// provider.shutdown().shutdown().await;
+/// # Ok(()) }
+/// ```
+///
+/// [`ProviderError::Shutdown`]: crate::ProviderError::Shutdown
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+	inner: Arc<Inner>,
+}
+
+impl Shutdown {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Signals every task to stop, without waiting for them.
+	pub fn trigger(&self) {
+		if !self.inner.triggered.swap(true, Ordering::SeqCst) {
+			tracing::debug!("shutdown triggered");
+			self.inner.wake_all();
+		}
+	}
+
+	/// Returns `true` once the shutdown was triggered.
+	pub fn is_triggered(&self) -> bool {
+		self.inner.triggered.load(Ordering::SeqCst)
+	}
+
+	/// Number of tasks holding a [`ShutdownGuard`]
+	pub fn active_tasks(&self) -> usize {
+		self.inner.tasks.load(Ordering::SeqCst)
+	}
+
+	/// A future resolving once the shutdown is triggered.
+	pub fn signal(&self) -> ShutdownSignal {
+		ShutdownSignal { inner: self.inner.clone() }
+	}
+
+	/// Registers a running task. The shutdown is complete once every guard is dropped.
+	pub fn guard(&self) -> ShutdownGuard {
+		self.inner.tasks.fetch_add(1, Ordering::SeqCst);
+		ShutdownGuard { inner: self.inner.clone() }
+	}
+
+	/// Triggers the shutdown and waits until every task holding a [`ShutdownGuard`] has
+	/// finished.
+	pub async fn shutdown(&self) {
+		self.trigger();
+		futures_util::future::poll_fn(|cx| {
+			if self.active_tasks() == 0 {
+				return Poll::Ready(())
+			}
+			self.inner.register(cx.waker());
+			// a task may have finished before the waker was registered
+			if self.active_tasks() == 0 {
+				Poll::Ready(())
+			} else {
+				Poll::Pending
+			}
+		})
+		.await
+	}
+}
+
+/// Resolves once the shutdown of a [`Shutdown`] handle is triggered
+#[derive(Debug, Clone)]
+#[must_use = "futures do nothing unless polled"]
+pub struct ShutdownSignal {
+	inner: Arc<Inner>,
+}
+
+impl ShutdownSignal {
+	/// Returns `true` once the shutdown was triggered.
+	pub fn is_triggered(&self) -> bool {
+		self.inner.triggered.load(Ordering::SeqCst)
+	}
+}
+
+impl Future for ShutdownSignal {
+	type Output = ();
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		if self.is_triggered() {
+			return Poll::Ready(())
+		}
+		self.inner.register(cx.waker());
+		if self.is_triggered() {
+			Poll::Ready(())
+		} else {
+			Poll::Pending
+		}
+	}
+}
+
+/// Marks a task as running until dropped, see [`Shutdown::guard`]
+#[derive(Debug)]
+#[must_use = "the task is considered finished once the guard is dropped"]
+pub struct ShutdownGuard {
+	inner: Arc<Inner>,
+}
+
+impl Drop for ShutdownGuard {
+	fn drop(&mut self) {
+		if self.inner.tasks.fetch_sub(1, Ordering::SeqCst) == 1 {
+			self.inner.wake_all();
+		}
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn shutdown_waits_for_guards() {
+		let shutdown = Shutdown::new();
+		let signal = shutdown.signal();
+		let guard = shutdown.guard();
+		let task = tokio::spawn(async move {
+			signal.await;
+			// flush state
+			tokio::time::sleep(Duration::from_millis(10)).await;
+			drop(guard);
+		});
+
+		assert_eq!(shutdown.active_tasks(), 1);
+		shutdown.shutdown().await;
+		assert!(shutdown.is_triggered());
+		assert_eq!(shutdown.active_tasks(), 0);
+		task.await.unwrap();
+		// already triggered signals resolve immediately
+		shutdown.signal().await;
+	}
+}
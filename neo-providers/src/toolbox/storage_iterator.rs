@@ -0,0 +1,130 @@
+use crate::{
+	core::responses::neo_find_storage::ContractStorageEntry, JsonRpcClient, Middleware, Provider,
+	ProviderError,
+};
+use futures_core::stream::Stream;
+use futures_util::stream;
+use primitive_types::H160;
+use std::collections::VecDeque;
+
+/// Iterates over the storage entries of a contract whose keys start with a prefix, fetching them
+/// page by page with `findstorage`, see [`Provider::find_storage_iter`].
+///
+/// # Example
+///
+/// ```no_run
+/// use neo_providers::{Http, Provider};
+/// use primitive_types::H160;
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(contract: H160) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let mut entries = provider.find_storage_iter(contract, b"\x01");
+/// while let Some(entry) = entries.next_entry().await? {
+///     println!("{} = {}", hex::encode(&entry.key), hex::encode(&entry.value));
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct StorageIterator<'a, P> {
+	provider: &'a Provider<P>,
+	contract_hash: H160,
+	prefix: Vec<u8>,
+	/// The position of the next page, `None` once the last page was fetched
+	next: Option<u32>,
+	entries: VecDeque<ContractStorageEntry>,
+}
+
+impl<'a, P: JsonRpcClient> StorageIterator<'a, P> {
+	/// Creates an iterator over the entries of `contract_hash` whose keys start with `prefix`.
+	pub fn new(provider: &'a Provider<P>, contract_hash: H160, prefix: &[u8]) -> Self {
+		Self {
+			provider,
+			contract_hash,
+			prefix: prefix.to_vec(),
+			next: Some(0),
+			entries: VecDeque::new(),
+		}
+	}
+
+	/// Fetches the next page of entries, or returns `None` once all pages were fetched. Entries
+	/// not yet returned by [`StorageIterator::next_entry`] are returned first.
+	pub async fn next_page(&mut self) -> Result<Option<Vec<ContractStorageEntry>>, ProviderError> {
+		if !self.entries.is_empty() {
+			return Ok(Some(self.entries.drain(..).collect()))
+		}
+		let Some(start) = self.next else { return Ok(None) };
+		let page = self.provider.find_storage(self.contract_hash, &self.prefix, start).await?;
+		self.next = page.truncated.then_some(page.next);
+		Ok(Some(page.results))
+	}
+
+	/// Returns the next entry, fetching the next page when needed, or `None` after the last
+	/// entry.
+	pub async fn next_entry(&mut self) -> Result<Option<ContractStorageEntry>, ProviderError> {
+		while self.entries.is_empty() {
+			match self.next_page().await? {
+				Some(page) => self.entries.extend(page),
+				None => return Ok(None),
+			}
+		}
+		Ok(self.entries.pop_front())
+	}
+
+	/// Turns the iterator into a stream of entries. The stream ends after the last entry or the
+	/// first error.
+	pub fn into_stream(
+		self,
+	) -> impl Stream<Item = Result<ContractStorageEntry, ProviderError>> + 'a {
+		stream::unfold(Some(self), |iterator| async move {
+			let mut iterator = iterator?;
+			match iterator.next_entry().await {
+				Ok(Some(entry)) => Some((Ok(entry), Some(iterator))),
+				Ok(None) => None,
+				Err(err) => Some((Err(err), None)),
+			}
+		})
+	}
+
+	/// Fetches all remaining entries.
+	pub async fn collect_all(mut self) -> Result<Vec<ContractStorageEntry>, ProviderError> {
+		let mut entries = vec![];
+		while let Some(page) = self.next_page().await? {
+			entries.extend(page);
+		}
+		Ok(entries)
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::{Expectation, MockProvider};
+	use neo_types::serde_value::ValueExtension;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn fetches_all_pages() {
+		let mock = MockProvider::new();
+		let provider = Provider::new(mock.clone());
+		let contract = H160::repeat_byte(1);
+		let params = vec![contract.to_value(), "AQ==".to_value(), 0.to_value()];
+		mock.expect(Expectation::new("findstorage").with_params(params).returning(json!({
+			"truncated": true,
+			"next": 2,
+			"results": [{ "key": "AQE=", "value": "Cg==" }, { "key": "AQI=", "value": "Cw==" }]
+		})));
+		mock.expect(Expectation::new("findstorage").returning(json!({
+			"truncated": false,
+			"next": 3,
+			"results": [{ "key": "AQM=", "value": "DA==" }]
+		})));
+
+		let entries = provider.find_storage_iter(contract, &[1]).collect_all().await.unwrap();
+		let keys: Vec<_> = entries.iter().map(|entry| entry.key.clone()).collect();
+		assert_eq!(keys, vec![vec![1, 1], vec![1, 2], vec![1, 3]]);
+		assert_eq!(entries[2].value, vec![12]);
+		mock.assert_expectations_met().unwrap();
+	}
+}
@@ -0,0 +1,27 @@
+use futures_timer::Delay;
+use futures_util::future::{select, Either};
+use instant::Duration;
+use std::future::Future;
+
+/// Awaits `future` for at most `duration`. Returns `None` if the deadline passed first, in which
+/// case `future` is dropped, cancelling the request.
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+	let future = Box::pin(future);
+	match select(future, Delay::new(duration)).await {
+		Either::Left((output, _)) => Some(output),
+		Either::Right(_) => None,
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn times_out() {
+		let slow = tokio::time::sleep(Duration::from_secs(10));
+		assert_eq!(timeout(Duration::from_millis(10), slow).await, None);
+		assert_eq!(timeout(Duration::from_secs(10), async { 42 }).await, Some(42));
+	}
+}
@@ -0,0 +1,288 @@
+//! Paginated NEP-17 transfer history, see [`TransferHistory`].
+use crate::{
+	core::responses::neo_transfers::{Nep17Transfer, Nep17Transfers},
+	Middleware,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use futures_core::stream::Stream;
+use futures_util::stream;
+use primitive_types::{H160, H256};
+use std::{
+	collections::{HashSet, VecDeque},
+	time::Duration,
+};
+use tracing::{trace, warn};
+
+/// Whether a transfer debited or credited the account
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TransferDirection {
+	/// The account sent the tokens
+	Sent,
+	/// The account received the tokens
+	Received,
+}
+
+/// A NEP-17 transfer from or to the account of a [`TransferHistory`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Nep17TransferEvent {
+	/// Whether the account sent or received the tokens
+	pub direction: TransferDirection,
+	/// The time of the block including the transfer
+	pub time: DateTime<Utc>,
+	/// The token contract
+	pub asset_hash: H160,
+	/// The other party of the transfer, empty when tokens were minted or burned
+	pub transfer_address: String,
+	/// The amount in the smallest unit of the token
+	pub amount: u64,
+	/// The block including the transfer
+	pub block_index: u32,
+	/// The index of the `Transfer` notification within the transaction
+	pub transfer_notify_index: u32,
+	/// The transaction emitting the transfer
+	pub tx_hash: H256,
+}
+
+impl Nep17TransferEvent {
+	fn new(direction: TransferDirection, transfer: Nep17Transfer) -> Self {
+		Self {
+			direction,
+			time: Utc.timestamp_millis_opt(transfer.timestamp as i64).single().unwrap_or_default(),
+			asset_hash: transfer.asset_hash,
+			transfer_address: transfer.transfer_address,
+			amount: transfer.amount,
+			block_index: transfer.block_index,
+			transfer_notify_index: transfer.transfer_notify_index,
+			tx_hash: transfer.tx_hash,
+		}
+	}
+
+	fn key(&self) -> (TransferDirection, H256, u32) {
+		(self.direction, self.tx_hash, self.transfer_notify_index)
+	}
+
+	fn timestamp(&self) -> i64 {
+		self.time.timestamp_millis()
+	}
+}
+
+/// Downloads the NEP-17 transfers of an account over long time ranges.
+///
+/// `getnep17transfers` answers a single time range with at most `MaxResults` transfers in each
+/// direction, as configured in the `TokensTracker` plugin of the node. The range is therefore
+/// requested in windows of [`TransferHistory::chunk`]; a window whose response hits the limit
+/// is split in half and requested again. Transfers on the boundary of two windows are only
+/// yielded once.
+///
+/// # Example
+///
+/// ```no_run
+/// use chrono::{Duration, Utc};
+/// use futures_util::StreamExt;
+/// use neo_providers::{Http, Provider, TransferHistory};
+/// use primitive_types::H160;
+/// use std::convert::TryFrom;
+///
+/// # async fn foo(account: H160) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("http://localhost:10332")?;
+/// let now = Utc::now();
+/// let history = TransferHistory::new(&provider, account);
+/// let mut transfers = Box::pin(history.stream(now - Duration::days(90), now));
+/// while let Some(transfer) = transfers.next().await {
+/// 	let transfer = transfer?;
+/// 	println!("{} {:?} {}", transfer.time, transfer.direction, transfer.amount);
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct TransferHistory<'a, M> {
+	client: &'a M,
+	account: H160,
+	chunk: Duration,
+	max_results: usize,
+}
+
+impl<'a, M: Middleware> TransferHistory<'a, M> {
+	/// The default size of the requested windows
+	pub const DEFAULT_CHUNK: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+	/// The default `MaxResults` of the `TokensTracker` plugin
+	pub const DEFAULT_MAX_RESULTS: usize = 1000;
+
+	/// Reads the transfers of `account` through `client`.
+	pub fn new(client: &'a M, account: H160) -> Self {
+		Self { client, account, chunk: Self::DEFAULT_CHUNK, max_results: Self::DEFAULT_MAX_RESULTS }
+	}
+
+	/// Sets the size of the windows requested at once.
+	#[must_use]
+	pub fn chunk(mut self, chunk: Duration) -> Self {
+		self.chunk = chunk;
+		self
+	}
+
+	/// Sets the `MaxResults` of the node, if it differs from the default.
+	#[must_use]
+	pub fn max_results(mut self, max_results: usize) -> Self {
+		self.max_results = max_results.max(1);
+		self
+	}
+
+	/// Yields the transfers between `from` and `to`, inclusive, ordered by block time.
+	///
+	/// The stream ends after the first error.
+	pub fn stream(
+		self,
+		from: DateTime<Utc>,
+		to: DateTime<Utc>,
+	) -> impl Stream<Item = Result<Nep17TransferEvent, M::Error>> + 'a {
+		let chunk = (self.chunk.as_millis() as u64).max(1);
+		let state = Pages {
+			history: self,
+			cursor: from.timestamp_millis().max(0) as u64,
+			end: to.timestamp_millis().max(0) as u64,
+			window: chunk,
+			chunk,
+			buffer: VecDeque::new(),
+			boundary: HashSet::new(),
+			done: from > to,
+		};
+		stream::unfold(state, |mut pages| async move {
+			loop {
+				if let Some(event) = pages.buffer.pop_front() {
+					return Some((Ok(event), pages))
+				}
+				if pages.done {
+					return None
+				}
+				if let Err(err) = pages.next_window().await {
+					pages.done = true;
+					return Some((Err(err), pages))
+				}
+			}
+		})
+	}
+}
+
+struct Pages<'a, M> {
+	history: TransferHistory<'a, M>,
+	/// Start of the next window in milliseconds
+	cursor: u64,
+	/// End of the range in milliseconds, inclusive
+	end: u64,
+	window: u64,
+	chunk: u64,
+	buffer: VecDeque<Nep17TransferEvent>,
+	/// The transfers at the end of the previous window, which starts the next window
+	boundary: HashSet<(TransferDirection, H256, u32)>,
+	done: bool,
+}
+
+impl<'a, M: Middleware> Pages<'a, M> {
+	/// Requests the window at the cursor, splitting it while the response is truncated.
+	async fn next_window(&mut self) -> Result<(), M::Error> {
+		let history = &self.history;
+		let (to, transfers) = loop {
+			let to = self.cursor.saturating_add(self.window).min(self.end);
+			trace!(from = self.cursor, to, "requesting nep17 transfers");
+			let transfers = history
+				.client
+				.get_nep17_transfers_range(history.account, self.cursor, to)
+				.await?;
+			if !self.is_truncated(&transfers) {
+				break (to, transfers)
+			}
+			if to == self.cursor {
+				warn!(timestamp = to, "more transfers in a single millisecond than MaxResults");
+				break (to, transfers)
+			}
+			self.window = ((to - self.cursor) / 2).max(1);
+		};
+
+		let sent = transfers.sent.into_iter().map(|t| (TransferDirection::Sent, t));
+		let received = transfers.received.into_iter().map(|t| (TransferDirection::Received, t));
+		let mut seen = HashSet::new();
+		let mut events: Vec<_> = sent
+			.chain(received)
+			.map(|(direction, transfer)| Nep17TransferEvent::new(direction, transfer))
+			.filter(|event| !self.boundary.contains(&event.key()) && seen.insert(event.key()))
+			.collect();
+		events.sort_by_key(|event| {
+			(
+				event.timestamp(),
+				event.block_index,
+				event.transfer_notify_index,
+				event.direction as u8,
+			)
+		});
+
+		self.boundary = events
+			.iter()
+			.filter(|event| event.timestamp() as u64 == to)
+			.map(Nep17TransferEvent::key)
+			.collect();
+		self.buffer.extend(events);
+		self.window = self.chunk;
+		self.done = to >= self.end;
+		self.cursor = to;
+		Ok(())
+	}
+
+	fn is_truncated(&self, transfers: &Nep17Transfers) -> bool {
+		let max_results = self.history.max_results;
+		transfers.sent.len() >= max_results || transfers.received.len() >= max_results
+	}
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+	use super::*;
+	use crate::{Expectation, Provider};
+	use futures_util::StreamExt;
+
+	fn transfer(timestamp: u64, notify_index: u32) -> Nep17Transfer {
+		Nep17Transfer {
+			timestamp,
+			asset_hash: H160::repeat_byte(0xcf),
+			transfer_address: String::new(),
+			amount: 1,
+			block_index: timestamp as u32,
+			transfer_notify_index: notify_index,
+			tx_hash: H256::from_low_u64_be(timestamp),
+		}
+	}
+
+	fn transfers(sent: &[u64], received: &[u64]) -> Nep17Transfers {
+		Nep17Transfers {
+			sent: sent.iter().map(|&t| transfer(t, 0)).collect(),
+			received: received.iter().map(|&t| transfer(t, 1)).collect(),
+			transfer_address: String::new(),
+		}
+	}
+
+	#[tokio::test]
+	async fn paginates_and_deduplicates() {
+		let (provider, mock) = Provider::mocked();
+		// [0, 10] is truncated and split
+		mock.expect(Expectation::new("getnep17transfers").returning(transfers(&[3, 8, 9], &[])));
+		// [0, 5]
+		mock.expect(Expectation::new("getnep17transfers").returning(transfers(&[3], &[5])));
+		// [5, 15] repeats the transfer at the boundary
+		mock.expect(Expectation::new("getnep17transfers").returning(transfers(&[8], &[5, 15])));
+		// [15, 20]
+		mock.expect(Expectation::new("getnep17transfers").returning(transfers(&[], &[15])));
+
+		let start = Utc.timestamp_millis_opt(0).unwrap();
+		let end = Utc.timestamp_millis_opt(20).unwrap();
+		let history = TransferHistory::new(&provider, H160::zero())
+			.chunk(Duration::from_millis(10))
+			.max_results(3);
+		let events: Vec<_> = history.stream(start, end).map(Result::unwrap).collect().await;
+
+		let times: Vec<_> = events.iter().map(|event| event.timestamp()).collect();
+		assert_eq!(times, [3, 5, 8, 15]);
+		assert_eq!(events[1].direction, TransferDirection::Received);
+		mock.assert_expectations_met().unwrap();
+	}
+}
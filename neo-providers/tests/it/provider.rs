@@ -1,13 +1,13 @@
 mod neo_tests {
 	use crate::spawn_anvil;
 	use neo_providers::{
-		core::transaction::transaction::Transaction, Middleware, StreamExt, GOERLI,
+		core::transaction::transaction::Transaction, Middleware, Provider, StreamExt,
 	};
 	use neo_types::{block::BlockId, Address, BlockId, BlockNumber, Transaction, H256};
 
 	#[tokio::test]
 	async fn non_existing_data_works() {
-		let provider = GOERLI.provider();
+		let provider = Provider::for_named_network("testnet").unwrap();
 
 		assert!(provider.get_transaction(H256::zero()).await.unwrap().is_none());
 		assert!(provider.get_transaction(H256::zero()).await.unwrap().is_none());
@@ -21,30 +21,11 @@ mod neo_tests {
 
 	#[tokio::test]
 	async fn client_version() {
-		let provider = GOERLI.provider();
+		let provider = Provider::for_named_network("testnet").unwrap();
 
-		// e.g., Geth/v1.10.6-omnibus-1af33248/linux-amd64/go1.16.6
-		assert!(provider
-			.client_version()
-			.await
-			.expect("Could not make web3_clientVersion call to provider")
-			.starts_with("Geth/v"));
-	}
-
-	// Without TLS this would error with "TLS Support not compiled in"
-	#[tokio::test]
-	#[cfg(all(feature = "ws", any(feature = "openssl", feature = "rustls")))]
-	async fn ssl_websocket() {
-		let provider = GOERLI.ws().await;
-		assert_ne!(provider.get_block_number().await.unwrap(), 0.into());
-	}
-
-	#[tokio::test]
-	async fn eip1559_fee_estimation() {
-		let provider = neo_providers::MAINNET.provider();
-
-		let (_max_fee_per_gas, _max_priority_fee_per_gas) =
-			provider.estimate_eip1559_fees(None).await.unwrap();
+		// e.g., /Neo:3.6.0/
+		let version = provider.get_version().await.expect("Could not make getversion call");
+		assert!(version.user_agent.starts_with("/Neo:"));
 	}
 
 	#[tokio::test]
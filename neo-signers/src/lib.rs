@@ -1,5 +1,6 @@
 #![doc = include_str!("../README.md")]
 #![deny(unsafe_code, rustdoc::broken_intra_doc_links)]
+#![cfg_attr(not(test), deny(clippy::unwrap_used))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod wallet;
@@ -19,14 +20,18 @@ mod error;
 
 use async_trait::async_trait;
 use neo_crypto::keys::Secp256r1Signature;
-use neo_providers::core::transaction::{transaction::Transaction, witness::Witness};
+use neo_providers::core::{
+	account::Account,
+	transaction::{transaction::Transaction, witness::Witness},
+};
 use neo_types::address::Address;
 use std::error::Error;
 
 /// Trait for signing transactions and messages
 ///
 /// Implement this trait to support different signing modes, e.g. Ledger, hosted etc.
-#[async_trait]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait Signer: std::fmt::Debug + Send + Sync {
 	type Error: Error + Send + Sync;
 	/// Signs the hash of the provided message after prefixing it
@@ -38,6 +43,10 @@ pub trait Signer: std::fmt::Debug + Send + Sync {
 	/// Add witness to transaction
 	async fn get_witness(&self, message: &Transaction) -> Result<Witness, Self::Error>;
 
+	/// The account the witnesses are created for. Its verification script is used to calculate
+	/// the network fee of the transactions it signs.
+	fn account(&self) -> &Account;
+
 	/// Returns the signer's neo Address
 	fn address(&self) -> Address;
 
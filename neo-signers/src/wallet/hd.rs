@@ -0,0 +1,219 @@
+//! Hierarchical deterministic key derivation for Neo accounts.
+//!
+//! Neo keys live on secp256r1, which BIP-32 does not cover, so keys are derived following
+//! [SLIP-10](https://github.com/satoshilabs/slips/blob/master/slip-0010.md) for the NIST P-256
+//! curve. Accounts are derived along the BIP-44 path `m/44'/888'/0'/0/{index}`, 888 being the
+//! coin type registered for Neo. This is the scheme used by NeoLine and O3, so their mnemonics
+//! restore the same accounts.
+use coins_bip32::path::DerivationPath;
+use coins_bip39::{Mnemonic, Wordlist};
+use hmac::{Hmac, Mac};
+use neo_crypto::keys::Secp256r1PrivateKey;
+use p256::{
+	elliptic_curve::{sec1::ToEncodedPoint, PrimeField},
+	FieldBytes, NonZeroScalar, PublicKey, Scalar,
+};
+use sha2::Sha512;
+use std::{fmt, str::FromStr};
+
+use crate::wallet::wallet_error::WalletError;
+
+/// The SLIP-44 coin type of Neo
+pub const NEO_COIN_TYPE: u32 = 888;
+
+/// The derivation path of Neo accounts, followed by the account index
+pub const NEO_DERIVATION_PATH_PREFIX: &str = "m/44'/888'/0'/0/";
+
+/// The key of the NEP-6 `extra` field holding the derivation path of an account
+pub const DERIVATION_PATH_EXTRA_KEY: &str = "derivationPath";
+
+const HARDENED: u32 = 0x8000_0000;
+const CURVE_SEED: &[u8] = b"Nist256p1 seed";
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Returns the derivation path of the Neo account at `index`.
+pub fn neo_derivation_path(index: u32) -> Result<DerivationPath, WalletError> {
+	Ok(DerivationPath::from_str(&format!("{NEO_DERIVATION_PATH_PREFIX}{index}"))?)
+}
+
+/// Formats `path` in the `m/44'/888'/0'/0/0` notation.
+pub fn format_derivation_path(path: &DerivationPath) -> String {
+	path.iter().fold("m".to_string(), |mut s, index| {
+		if index & HARDENED != 0 {
+			s.push_str(&format!("/{}'", index & !HARDENED));
+		} else {
+			s.push_str(&format!("/{index}"));
+		}
+		s
+	})
+}
+
+/// A secp256r1 private key together with its chain code, from which child keys are derived.
+#[derive(Clone)]
+pub struct ExtendedPrivateKey {
+	key: NonZeroScalar,
+	chain_code: [u8; 32],
+}
+
+impl ExtendedPrivateKey {
+	/// Derives the master key of `seed`.
+	pub fn from_seed(seed: &[u8]) -> Self {
+		let mut i = hmac_sha512(CURVE_SEED, &[seed]);
+		loop {
+			if let Some(key) = parse_key(&i[..32]) {
+				return Self { key, chain_code: i[32..].try_into().expect("32 bytes") }
+			}
+			i = hmac_sha512(CURVE_SEED, &[&i]);
+		}
+	}
+
+	/// Derives the master key of a BIP-39 mnemonic, with an optional passphrase.
+	pub fn from_mnemonic<W: Wordlist>(
+		mnemonic: &Mnemonic<W>,
+		password: Option<&str>,
+	) -> Result<Self, WalletError> {
+		Ok(Self::from_seed(&mnemonic.to_seed(password)?))
+	}
+
+	/// Derives the child key at `index`. Indices from 2^31 on are hardened.
+	pub fn derive_child(&self, index: u32) -> Self {
+		let mut data = if index & HARDENED != 0 {
+			[&[0u8][..], &self.key.to_repr()].concat()
+		} else {
+			let public_key = PublicKey::from_secret_scalar(&self.key);
+			public_key.to_encoded_point(true).as_bytes().to_vec()
+		};
+		loop {
+			let i = hmac_sha512(&self.chain_code, &[&data, &index.to_be_bytes()]);
+			let child =
+				Option::<Scalar>::from(Scalar::from_repr(*FieldBytes::from_slice(&i[..32])))
+					.and_then(|tweak| Option::from(NonZeroScalar::new(tweak + *self.key)));
+			if let Some(key) = child {
+				return Self { key, chain_code: i[32..].try_into().expect("32 bytes") }
+			}
+			// the derived key is invalid, retry with the next candidate as per SLIP-10
+			data = [&[1u8][..], &i[32..]].concat();
+		}
+	}
+
+	/// Derives the key at `path`, relative to this key.
+	pub fn derive_path(&self, path: &DerivationPath) -> Self {
+		path.iter().fold(self.clone(), |key, index| key.derive_child(*index))
+	}
+
+	/// The private key
+	pub fn private_key(&self) -> Secp256r1PrivateKey {
+		Secp256r1PrivateKey::from_bytes(&self.key.to_repr())
+			.expect("a non-zero scalar is a valid private key")
+	}
+
+	/// The chain code
+	pub fn chain_code(&self) -> &[u8; 32] {
+		&self.chain_code
+	}
+}
+
+// do not log the key
+impl fmt::Debug for ExtendedPrivateKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ExtendedPrivateKey").finish_non_exhaustive()
+	}
+}
+
+fn parse_key(bytes: &[u8]) -> Option<NonZeroScalar> {
+	Option::from(NonZeroScalar::from_repr(*FieldBytes::from_slice(bytes)))
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+	let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts keys of any size");
+	data.iter().for_each(|chunk| mac.update(chunk));
+	let mut out = [0u8; 64];
+	out.copy_from_slice(&mac.finalize().into_bytes());
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{coins_bip39::English, NEP6Account, NEP6Wallet, Wallet};
+	use neo_providers::core::{
+		account::{Account, AccountTrait},
+		wallet::WalletTrait,
+	};
+	use neo_types::address::Address;
+	use std::collections::HashMap;
+
+	const PHRASE: &str =
+		"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+	#[test]
+	fn test_slip10_vectors() {
+		let master = ExtendedPrivateKey::from_seed(
+			&hex::decode("000102030405060708090a0b0c0d0e0f").unwrap(),
+		);
+		assert_eq!(
+			hex::encode(master.private_key().to_raw_bytes()),
+			"612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2"
+		);
+		assert_eq!(
+			hex::encode(master.chain_code()),
+			"beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea"
+		);
+
+		let path = DerivationPath::from_str("m/0'/1").unwrap();
+		let child = master.derive_path(&path);
+		assert_eq!(
+			hex::encode(child.private_key().to_raw_bytes()),
+			"284e9d38d07d21e4e281b645089a94f4cf5a5a81369acf151a1c3a57f18b2129"
+		);
+		assert_eq!(
+			hex::encode(child.chain_code()),
+			"4187afff1aafa8445010097fb99d23aee9f599450c7bd140b6826ac22ba21d0c"
+		);
+		assert_eq!(format_derivation_path(&path), "m/0'/1");
+	}
+
+	#[test]
+	fn test_derive_accounts() {
+		let address = |account: &Account| account.address_or_scripthash().address().to_string();
+
+		let mut wallet = Wallet::from_mnemonic::<English>(PHRASE, None).unwrap();
+		assert_eq!(address(wallet.default_account()), "NYqCjmV8g8PFCYpyD3K4kSCkQxZff1UNMV");
+		let account = wallet.derive_account(1).unwrap();
+		assert_eq!(address(account), "Nij7Ef7uf8E5Vj9UfN9YiUkBUpRFTn4aP2");
+		let script_hash = account.get_script_hash();
+		assert_eq!(wallet.derivation_path(&script_hash), Some("m/44'/888'/0'/0/1"));
+
+		let wallet = Wallet::from_mnemonic::<English>(PHRASE, Some("TREZOR")).unwrap();
+		assert_eq!(address(wallet.default_account()), "NedD3bYTixRN4Fa7ojSJzxLi81fXn7v6ae");
+	}
+
+	#[test]
+	fn test_nep6_derivation_path() {
+		let address = Address::from_str("NYqCjmV8g8PFCYpyD3K4kSCkQxZff1UNMV").unwrap();
+		let extra = HashMap::from([(
+			DERIVATION_PATH_EXTRA_KEY.to_string(),
+			"m/44'/888'/0'/0/0".to_string(),
+		)]);
+		let account = NEP6Account::new(address, None, true, false, None, None, Some(extra));
+		let nep6 = NEP6Wallet::new(
+			"HD".to_string(),
+			"1.0".to_string(),
+			Default::default(),
+			vec![account.clone()],
+			None,
+		);
+
+		// the derivation paths are restored, the seed is not
+		let mut wallet = Wallet::from_nep6(nep6).unwrap();
+		assert_eq!(wallet.derivation_path(&address.script_hash()), Some("m/44'/888'/0'/0/0"));
+		let exported = wallet.to_nep6().unwrap();
+		assert_eq!(exported.accounts()[0].extra, account.extra);
+		assert!(exported.accounts()[0].is_default);
+
+		assert!(matches!(wallet.derive_account(1), Err(WalletError::NoMnemonic)));
+		wallet.set_mnemonic::<English>(PHRASE, None).unwrap();
+		assert!(wallet.derive_account(1).unwrap().key_pair().is_some());
+	}
+}
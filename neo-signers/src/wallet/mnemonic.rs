@@ -3,16 +3,18 @@
 use coins_bip32::path::DerivationPath;
 use coins_bip39::{Mnemonic, Wordlist};
 
-use crate::wallet::{wallet_error::WalletError, Wallet};
-use neo_types::{path_or_string::PathOrString, to_checksum};
+use crate::wallet::{
+	hd::{ExtendedPrivateKey, NEO_DERIVATION_PATH_PREFIX as DEFAULT_DERIVATION_PATH_PREFIX},
+	wallet_error::WalletError,
+	Wallet,
+};
+use neo_types::{address::Address, path_or_string::PathOrString};
 use rand::Rng;
-use std::{fs::File, io::Write, marker::PhantomData, path::PathBuf, str::FromStr};
+use std::{fmt, fs::File, io::Write, marker::PhantomData, path::PathBuf, str::FromStr};
 use thiserror::Error;
 
-const DEFAULT_DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0/";
-
 /// Represents a structure that can resolve into a `Wallet`.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct MnemonicBuilder<W: Wordlist> {
 	/// The mnemonic phrase can be supplied to the builder as a string or a path to the file whose
 	/// contents are the phrase. A builder that has a valid phrase should `build` the wallet.
@@ -21,7 +23,7 @@ pub struct MnemonicBuilder<W: Wordlist> {
 	/// number of words in the phrase. By default this is set to 12.
 	word_count: usize,
 	/// The derivation path at which the extended private key child will be derived at. By default
-	/// the mnemonic builder uses the path: "m/44'/60'/0'/0/0".
+	/// the mnemonic builder uses the path: "m/44'/888'/0'/0/0".
 	derivation_path: DerivationPath,
 	/// Optional password for the mnemonic phrase.
 	password: Option<String>,
@@ -32,6 +34,19 @@ pub struct MnemonicBuilder<W: Wordlist> {
 	_wordlist: PhantomData<W>,
 }
 
+// the phrase and the password give access to the wallet and must not end up in logs
+impl<W: Wordlist> fmt::Debug for MnemonicBuilder<W> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("MnemonicBuilder")
+			.field("phrase", &self.phrase.as_ref().map(|_| "[REDACTED]"))
+			.field("word_count", &self.word_count)
+			.field("derivation_path", &self.derivation_path)
+			.field("password", &self.password.as_ref().map(|_| "[REDACTED]"))
+			.field("write_to", &self.write_to)
+			.finish()
+	}
+}
+
 /// Error produced by the mnemonic wallet module
 #[derive(Error, Debug)]
 pub enum MnemonicBuilderError {
@@ -108,7 +123,7 @@ impl<W: Wordlist> MnemonicBuilder<W> {
 	}
 
 	/// Sets the derivation path of the child key to be derived. The derivation path is calculated
-	/// using the default derivation path prefix used in neo, i.e. "m/44'/60'/0'/0/{index}".
+	/// using the default derivation path prefix used in neo, i.e. "m/44'/888'/0'/0/{index}".
 	pub fn index<U: Into<u32>>(mut self, index: U) -> Result<Self, WalletError> {
 		self.derivation_path = DerivationPath::from_str(&format!(
 			"{}{}",
@@ -163,8 +178,8 @@ impl<W: Wordlist> MnemonicBuilder<W> {
 
 		// Write the mnemonic phrase to storage if a directory has been provided.
 		if let Some(dir) = &self.write_to {
-			let mut file =
-				File::create(dir.as_path().join(to_checksum(&wallet.default_account, None)))?;
+			let address = Address::from_script_hash(wallet.default_account);
+			let mut file = File::create(dir.as_path().join(address.to_string()))?;
 			file.write_all(mnemonic.to_phrase().as_bytes())?;
 		}
 
@@ -172,23 +187,11 @@ impl<W: Wordlist> MnemonicBuilder<W> {
 	}
 
 	fn mnemonic_to_wallet(&self, mnemonic: &Mnemonic<W>) -> Result<Wallet, WalletError> {
-		// let derived_priv_key =
-		// 	mnemonic.derive_key(&self.derivation_path, self.password.as_deref())?;
-		// // let  sign_key:ecdsa::SigningKey = ;
-		// let key: &SigningKey = derived_priv_key.as_ref();
-		// let signer = SigningKey::from_bytes(&key.to_bytes())?;
-		// let signer: Secp256r1PrivateKey =
-		// 	Secp256r1PrivateKey::from_bytes(derived_priv_key.Key.to_bytes()).unwrap();
-		// // let signer = Secp256r1PrivateKey::from_bytes(&key.to_raw_bytes().to_vec())?;
-		// let address = secret_key_to_script_hash(&signer);
-
-		Ok(Wallet {
-			name: "".to_string(),
-			version: "".to_string(),
-			scrypt_params: Default::default(),
-			accounts: Default::default(),
-			default_account: Default::default(),
-		})
+		let mut wallet = Wallet::new();
+		wallet.hd_root =
+			Some(ExtendedPrivateKey::from_mnemonic(mnemonic, self.password.as_deref())?);
+		wallet.derive_account_at(&self.derivation_path)?;
+		Ok(wallet)
 	}
 }
 
@@ -200,37 +203,30 @@ mod tests {
 	use crate::coins_bip39::English;
 	use tempfile::tempdir;
 
-	const TEST_DERIVATION_PATH: &str = "m/44'/60'/0'/2/1";
+	const TEST_DERIVATION_PATH: &str = "m/44'/888'/0'/2/1";
 
 	#[tokio::test]
 	async fn mnemonic_deterministic() {
-		// Testcases have been taken from MyCryptoWallet
-		const TESTCASES: [(&str, u32, Option<&str>, &str); 4] = [
-            (
-                "work man father plunge mystery proud hollow address reunion sauce theory bonus",
-                0u32,
-                Some("TREZOR123"),
-                "0x431a00DA1D54c281AeF638A73121B3D153e0b0F6",
-            ),
-            (
-                "inject danger program federal spice bitter term garbage coyote breeze thought funny",
-                1u32,
-                Some("LEDGER321"),
-                "0x231a3D0a05d13FAf93078C779FeeD3752ea1350C",
-            ),
-            (
-                "fire evolve buddy tenant talent favorite ankle stem regret myth dream fresh",
-                2u32,
-                None,
-                "0x1D86AD5eBb2380dAdEAF52f61f4F428C485460E9",
-            ),
-            (
-                "thumb soda tape crunch maple fresh imitate cancel order blind denial giraffe",
-                3u32,
-                None,
-                "0xFB78b25f69A8e941036fEE2A5EeAf349D81D4ccc",
-            ),
-        ];
+		const TESTCASES: [(&str, u32, Option<&str>, &str); 3] = [
+			(
+				"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+				0u32,
+				None,
+				"NYqCjmV8g8PFCYpyD3K4kSCkQxZff1UNMV",
+			),
+			(
+				"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+				1u32,
+				None,
+				"Nij7Ef7uf8E5Vj9UfN9YiUkBUpRFTn4aP2",
+			),
+			(
+				"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+				0u32,
+				Some("TREZOR"),
+				"NedD3bYTixRN4Fa7ojSJzxLi81fXn7v6ae",
+			),
+		];
 		TESTCASES.iter().for_each(|(phrase, index, password, expected_addr)| {
 			let wallet = match password {
 				Some(psswd) => MnemonicBuilder::<English>::default()
@@ -247,7 +243,10 @@ mod tests {
 					.build()
 					.unwrap(),
 			};
-			assert_eq!(&to_checksum(&wallet.address, None), expected_addr);
+			assert_eq!(
+				&Address::from_script_hash(wallet.default_account).to_string(),
+				expected_addr
+			);
 		})
 	}
 
@@ -270,7 +269,9 @@ mod tests {
 		assert_eq!(paths.count(), 1);
 
 		// Use the newly created file's path to instantiate wallet.
-		let phrase_path = dir.as_ref().join(to_checksum(&wallet1.default_account, None));
+		let phrase_path = dir
+			.as_ref()
+			.join(Address::from_script_hash(wallet1.default_account).to_string());
 		let wallet2 = MnemonicBuilder::<English>::default()
 			.phrase(phrase_path.to_str().unwrap())
 			.derivation_path(TEST_DERIVATION_PATH)
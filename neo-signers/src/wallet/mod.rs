@@ -1,3 +1,6 @@
+pub mod hd;
+pub use hd::ExtendedPrivateKey;
+
 mod mnemonic;
 pub use mnemonic::{MnemonicBuilder, MnemonicBuilderError};
 
@@ -7,6 +10,8 @@ mod nep6contract;
 pub use nep6contract::*;
 mod nep6wallet;
 pub use nep6wallet::*;
+mod portfolio;
+pub use portfolio::*;
 mod wallet;
 pub use wallet::*;
 mod wallet_error;
@@ -111,7 +116,7 @@ use p256::ecdsa::signature::hazmat::PrehashSigner;
 // 		self.network_magic
 // 	}
 //
-// 	/// Sets the wallet's network_magic, used in conjunction with EIP-155 signing
+// 	/// Sets the wallet's network_magic, which is part of the signed hash data
 // 	fn with_network_magic<T: Into<u64>>(mut self, network_magic: T) -> Self {
 // 		self.network_magic = network_magic.into();
 // 		self
@@ -45,11 +45,7 @@
 //! and other cryptographic errors.
 
 use crate::{
-	base58_helper::{base58check_decode, base58check_encode},
-	error::CryptoError,
-	hash::HashableForVec,
-	key_pair::KeyPair,
-	keys::Secp256r1PrivateKey,
+	error::CryptoError, hash::HashableForVec, key_pair::KeyPair, keys::Secp256r1PrivateKey,
 	WalletError,
 };
 use aes::{
@@ -57,8 +53,11 @@ use aes::{
 	Aes128, Aes256,
 };
 use crypto::scrypt::{scrypt, ScryptParams};
-use neo_crypto::{key_pair::KeyPair, keys::PrivateKeyExtension};
-use neo_providers::public_key_to_script_hash;
+use neo_crypto::{key_pair::KeyPair, keys::PrivateKeyExtension, secret::SecretBytes};
+use neo_types::{
+	encoding,
+	script_hash::{ScriptHash, ScriptHashExt},
+};
 
 // const DKLEN: usize = 64;
 // const NEP2_PRIVATE_KEY_LENGTH: usize = 39;
@@ -81,7 +80,8 @@ impl NEP2 {
 		nep2_string: &str,
 		params: ScryptParams,
 	) -> Result<KeyPair, CryptoError> {
-		let nep2_data = nep2_string.from_base58()?;
+		let nep2_data = encoding::base58check_decode(nep2_string)
+			.map_err(|e| CryptoError::InvalidFormat(e.to_string()))?;
 		if nep2_data.len() != Self::NEP2_PRIVATE_KEY_LENGTH
 			|| nep2_data[0] != Self::NEP2_PREFIX_1
 			|| nep2_data[1] != Self::NEP2_PREFIX_2
@@ -93,9 +93,11 @@ impl NEP2 {
 		let encrypted = &nep2_data[7..39];
 		let derived_key =
 			Self::generate_derived_scrypt_key(password.as_bytes(), address_hash, params)?;
-		let decrypted_bytes = Self::perform_cipher(encrypted, &derived_key[32..], false)?;
-		let plain_private_key = xor(&derived_key[..32], &decrypted_bytes);
-		let key_pair = KeyPair::from_private_key(&plain_private_key.into())?;
+		let decrypted_bytes =
+			SecretBytes::new(Self::perform_cipher(encrypted, &derived_key[32..], false)?);
+		let plain_private_key = SecretBytes::new(xor(&derived_key[..32], &decrypted_bytes));
+		let key_pair =
+			KeyPair::from_secret_key(&Secp256r1PrivateKey::from_bytes(&plain_private_key)?);
 		let new_address_hash = key_pair.get_address_hash()?;
 		if new_address_hash != address_hash {
 			return Err(CryptoError::InvalidPassphrase(
@@ -112,19 +114,19 @@ impl NEP2 {
 		key_pair: &KeyPair,
 		params: ScryptParams,
 	) -> Result<String, WalletError> {
-		let address_hash = public_key_to_script_hash(&key_pair.public_key);
-		let private_key = key_pair.private_key().to_vec();
+		let address_hash = ScriptHash::from_public_key(&key_pair.public_key);
+		let private_key = key_pair.private_key().to_secret_bytes();
 		let derived_key =
 			Self::generate_derived_scrypt_key(password.as_bytes(), &address_hash, params)?;
 		let derived_half1 = &derived_key[..32];
 		let derived_half2 = &derived_key[32..];
 		let encrypted_half1 = Self::perform_cipher(
-			&Self::xor_private_key_and_derived_half(private_key, derived_half1, 0..16),
+			&Self::xor_private_key_and_derived_half(&private_key, derived_half1, 0..16),
 			derived_half2,
 			true,
 		)?;
 		let encrypted_half2 = Self::perform_cipher(
-			&Self::xor_private_key_and_derived_half(private_key, derived_half1, 16..32),
+			&Self::xor_private_key_and_derived_half(&private_key, derived_half1, 16..32),
 			derived_half2,
 			true,
 		)?;
@@ -135,7 +137,7 @@ impl NEP2 {
 			encrypted_half2,
 		]
 		.concat();
-		Ok(result.to_base58())
+		Ok(encoding::base58check_encode(&result))
 	}
 
 	// Helper functions
@@ -147,8 +149,8 @@ impl NEP2 {
 		private_key: &[u8],
 		half: &[u8],
 		range: std::ops::Range<usize>,
-	) -> Vec<u8> {
-		xor(&private_key[range.clone()], &half[range])
+	) -> SecretBytes {
+		SecretBytes::new(xor(&private_key[range.clone()], &half[range]))
 	}
 
 	fn perform_cipher(data: &[u8], key: &[u8], decrypt: bool) -> Result<Vec<u8>, CryptoError> {
@@ -164,9 +166,9 @@ impl NEP2 {
 		password: &[u8],
 		salt: &[u8],
 		params: ScryptParams,
-	) -> Result<Vec<u8>, CryptoError> {
-		let mut output = vec![0u8; Self::DKLEN];
-		scrypt(password, salt, &params, &mut output)?;
+	) -> Result<SecretBytes, CryptoError> {
+		let mut output = SecretBytes::zeroed(Self::DKLEN);
+		scrypt(password, salt, &params, output.as_mut_slice())?;
 		Ok(output)
 	}
 }
@@ -0,0 +1,351 @@
+use crate::{Wallet, WalletError};
+use async_trait::async_trait;
+use neo_providers::{core::responses::neo_balances::Nep17Balance, Middleware};
+use neo_types::{
+	invocation_result::NeoVMStateType, stack_item::StackItem, token_amount::TokenAmount,
+};
+use primitive_types::H160;
+use rust_decimal::Decimal;
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// The symbol and decimals of a NEP-17 token
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenInfo {
+	pub asset_hash: H160,
+	pub symbol: String,
+	pub decimals: u8,
+}
+
+/// Caches the [`TokenInfo`] of tokens, so the token contracts are only called once.
+///
+/// Keep one registry per network; the same contract hash may refer to different tokens on
+/// different networks.
+#[derive(Debug, Default)]
+pub struct TokenRegistry {
+	tokens: Mutex<HashMap<H160, TokenInfo>>,
+}
+
+impl TokenRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds `token`, e.g. to avoid calling a contract whose metadata is known.
+	pub fn insert(&self, token: TokenInfo) {
+		self.tokens().insert(token.asset_hash, token);
+	}
+
+	/// Returns the cached info of `asset_hash`.
+	pub fn get(&self, asset_hash: &H160) -> Option<TokenInfo> {
+		self.tokens().get(asset_hash).cloned()
+	}
+
+	fn tokens(&self) -> MutexGuard<'_, HashMap<H160, TokenInfo>> {
+		// the map is never left half updated, so it is still usable if a holder panicked
+		self.tokens.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Returns the info of the token of `balance`, taken from the balance if the node returned
+	/// it, or else from the `symbol` and `decimals` methods of the token contract.
+	async fn resolve<M: Middleware + 'static>(
+		&self,
+		client: &M,
+		balance: &Nep17Balance,
+	) -> Result<TokenInfo, WalletError> {
+		let asset_hash = balance.asset_hash;
+		if let Some(token) = self.get(&asset_hash) {
+			return Ok(token)
+		}
+		let decimals = balance.decimals.as_ref().and_then(|decimals| decimals.parse().ok());
+		let (symbol, decimals) = match (balance.symbol.clone(), decimals) {
+			(Some(symbol), Some(decimals)) => (symbol, decimals),
+			_ => {
+				let symbol = call(client, &asset_hash, "symbol").await?;
+				let decimals = call(client, &asset_hash, "decimals").await?;
+				(
+					symbol.as_string().ok_or_else(|| unexpected(&asset_hash, "symbol"))?,
+					decimals
+						.as_int()
+						.and_then(|decimals| u8::try_from(decimals).ok())
+						.ok_or_else(|| unexpected(&asset_hash, "decimals"))?,
+				)
+			},
+		};
+		let token = TokenInfo { asset_hash, symbol, decimals };
+		self.insert(token.clone());
+		Ok(token)
+	}
+}
+
+async fn call<M: Middleware + 'static>(
+	client: &M,
+	contract: &H160,
+	method: &str,
+) -> Result<StackItem, WalletError> {
+	let result = client
+		.invoke_function(contract, method.to_string(), vec![], None)
+		.await
+		.map_err(WalletError::from_middleware_error)?;
+	if result.state != NeoVMStateType::Halt {
+		return Err(WalletError::AccountState(format!(
+			"Calling {method} on {contract:x} failed: {}",
+			result.exception.unwrap_or_default()
+		)))
+	}
+	result.stack.into_iter().next().ok_or_else(|| unexpected(contract, method))
+}
+
+fn unexpected(contract: &H160, method: &str) -> WalletError {
+	WalletError::AccountState(format!("Unexpected result of {method} on {contract:x}"))
+}
+
+/// Values tokens in a fiat currency.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait PriceOracle: Send + Sync {
+	/// The currency of the prices, e.g. `USD`
+	fn currency(&self) -> &str;
+
+	/// The price of one whole token, or `None` if the token has no known price.
+	async fn price(&self, token: &TokenInfo) -> Result<Option<Decimal>, WalletError>;
+}
+
+/// An amount of a token held by an account
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenHolding {
+	pub token: TokenInfo,
+	pub amount: TokenAmount,
+	/// The value of the amount, if a [`PriceOracle`] knows the price of the token
+	pub value: Option<Decimal>,
+}
+
+impl TokenHolding {
+	/// The amount in whole tokens, e.g. to multiply it with a price
+	pub fn balance(&self) -> Result<Decimal, WalletError> {
+		Decimal::try_from_i128_with_scale(self.amount.value(), u32::from(self.amount.decimals()))
+			.map_err(|e| WalletError::AccountState(e.to_string()))
+	}
+}
+
+/// The tokens held by one account of a wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountHoldings {
+	pub account: H160,
+	pub holdings: Vec<TokenHolding>,
+}
+
+/// The NEP-17 balances of all accounts of a wallet, see [`Wallet::get_portfolio`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Portfolio {
+	/// The accounts, ordered by script hash
+	pub accounts: Vec<AccountHoldings>,
+	/// The currency of the values, if the holdings were valued
+	pub currency: Option<String>,
+}
+
+impl Portfolio {
+	/// The holdings of all accounts, summed up per token and ordered by symbol
+	pub fn totals(&self) -> Result<Vec<TokenHolding>, WalletError> {
+		let mut totals: BTreeMap<(String, H160), TokenHolding> = BTreeMap::new();
+		for holding in self.accounts.iter().flat_map(|account| &account.holdings) {
+			let key = (holding.token.symbol.clone(), holding.token.asset_hash);
+			match totals.get_mut(&key) {
+				Some(total) => {
+					total.amount = total
+						.amount
+						.checked_add(&holding.amount)
+						.map_err(|e| WalletError::AccountState(e.to_string()))?;
+					total.value = total.value.zip(holding.value).map(|(a, b)| a + b);
+				},
+				None => {
+					totals.insert(key, holding.clone());
+				},
+			}
+		}
+		Ok(totals.into_values().collect())
+	}
+
+	/// The summed value of the holdings with a known price, or `None` if the holdings were not
+	/// valued
+	pub fn total_value(&self) -> Option<Decimal> {
+		self.currency.as_ref()?;
+		let holdings = self.accounts.iter().flat_map(|account| &account.holdings);
+		Some(holdings.filter_map(|holding| holding.value).sum())
+	}
+}
+
+impl Wallet {
+	/// Fetches the NEP-17 balances of all accounts.
+	pub async fn get_portfolio<M: Middleware + 'static>(
+		&self,
+		client: &M,
+	) -> Result<Portfolio, WalletError> {
+		self.get_portfolio_with(client, &TokenRegistry::new(), None).await
+	}
+
+	/// Fetches the NEP-17 balances of all accounts, resolving the tokens through `registry` and
+	/// valuing them with `oracle`, if any.
+	pub async fn get_portfolio_with<M: Middleware + 'static>(
+		&self,
+		client: &M,
+		registry: &TokenRegistry,
+		oracle: Option<&dyn PriceOracle>,
+	) -> Result<Portfolio, WalletError> {
+		let mut accounts: Vec<_> = self.accounts.keys().copied().collect();
+		accounts.sort();
+
+		let mut portfolio = Vec::with_capacity(accounts.len());
+		for account in accounts {
+			let balances = client
+				.get_nep17_balances(account)
+				.await
+				.map_err(WalletError::from_middleware_error)?;
+			let mut holdings = Vec::with_capacity(balances.balances.len());
+			for balance in &balances.balances {
+				let token = registry.resolve(client, balance).await?;
+				let value = balance.amount.parse().map_err(|_| {
+					WalletError::AccountState(format!("Invalid amount {}", balance.amount))
+				})?;
+				let amount = TokenAmount::new(value, token.decimals, token.asset_hash);
+				let mut holding = TokenHolding { token, amount, value: None };
+				if let Some(oracle) = oracle {
+					if let Some(price) = oracle.price(&holding.token).await? {
+						let value = price.checked_mul(holding.balance()?).ok_or_else(|| {
+							WalletError::AccountState(format!(
+								"The value of {} {} overflows",
+								holding.amount, holding.token.symbol
+							))
+						})?;
+						holding.value = Some(value);
+					}
+				}
+				holdings.push(holding);
+			}
+			portfolio.push(AccountHoldings { account, holdings });
+		}
+		Ok(Portfolio {
+			accounts: portfolio,
+			currency: oracle.map(|oracle| oracle.currency().to_string()),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_providers::{
+		core::{
+			account::{Account, AccountTrait},
+			responses::neo_balances::Nep17Balances,
+			wallet::WalletTrait,
+		},
+		Expectation, Provider,
+	};
+	use neo_types::invocation_result::InvocationResult;
+
+	struct FixedPrices;
+
+	#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+	#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+	impl PriceOracle for FixedPrices {
+		fn currency(&self) -> &str {
+			"USD"
+		}
+
+		async fn price(&self, token: &TokenInfo) -> Result<Option<Decimal>, WalletError> {
+			Ok((token.symbol == "GAS").then_some(Decimal::from(4)))
+		}
+	}
+
+	fn balance(asset_hash: H160, symbol: Option<&str>, amount: &str) -> Nep17Balance {
+		Nep17Balance {
+			name: None,
+			symbol: symbol.map(str::to_string),
+			decimals: symbol.map(|_| "8".to_string()),
+			amount: amount.to_string(),
+			last_updated_block: 1,
+			asset_hash,
+		}
+	}
+
+	fn halt(item: StackItem) -> InvocationResult {
+		let state = NeoVMStateType::Halt;
+		InvocationResult::new(
+			String::new(),
+			state,
+			"0".into(),
+			None,
+			None,
+			None,
+			vec![item],
+			None,
+			None,
+			None,
+		)
+	}
+
+	#[tokio::test]
+	async fn values_balances() {
+		let (provider, mock) = Provider::mocked();
+		let gas = H160::repeat_byte(0xcf);
+		let token = H160::repeat_byte(0x01);
+		let mut wallet = Wallet::new();
+		wallet.add_account(Account::create().unwrap());
+
+		mock.expect(Expectation::new("getnep17balances").returning(Nep17Balances {
+			address: String::new(),
+			balances: vec![balance(gas, Some("GAS"), "250000000"), balance(token, None, "7")],
+		}));
+		mock.expect(
+			Expectation::new("invokefunction")
+				.returning(halt(StackItem::ByteString { value: hex::encode("TKN") })),
+		);
+		mock.expect(
+			Expectation::new("invokefunction").returning(halt(StackItem::Integer { value: 0 })),
+		);
+
+		let portfolio = wallet
+			.get_portfolio_with(&provider, &TokenRegistry::new(), Some(&FixedPrices))
+			.await
+			.unwrap();
+		mock.assert_expectations_met().unwrap();
+
+		let totals = portfolio.totals().unwrap();
+		assert_eq!(totals[0].token.symbol, "GAS");
+		assert_eq!(totals[0].amount, TokenAmount::new(250_000_000, 8, gas));
+		assert_eq!(totals[0].balance().unwrap(), Decimal::new(25, 1));
+		assert_eq!(
+			totals[1].token,
+			TokenInfo { asset_hash: token, symbol: "TKN".into(), decimals: 0 }
+		);
+		assert_eq!(totals[1].value, None);
+		assert_eq!(portfolio.total_value(), Some(Decimal::from(10)));
+	}
+
+	#[tokio::test]
+	async fn holds_amounts_beyond_i64() {
+		let (provider, mock) = Provider::mocked();
+		let token = H160::repeat_byte(0x02);
+		let mut wallet = Wallet::new();
+		wallet.add_account(Account::create().unwrap());
+
+		// 12345.678 units of a token with 18 decimals
+		let balance = Nep17Balance {
+			decimals: Some("18".to_string()),
+			..balance(token, Some("TKN"), "12345678000000000000000")
+		};
+		mock.expect(
+			Expectation::new("getnep17balances")
+				.returning(Nep17Balances { address: String::new(), balances: vec![balance] }),
+		);
+
+		let portfolio =
+			wallet.get_portfolio_with(&provider, &TokenRegistry::new(), None).await.unwrap();
+		let totals = portfolio.totals().unwrap();
+		assert_eq!(totals[0].amount.value(), 12_345_678_000_000_000_000_000);
+		assert_eq!(totals[0].balance().unwrap(), Decimal::new(12_345_678, 3));
+	}
+}
@@ -1,12 +1,23 @@
 use crate::{
-	wallet::{nep6wallet::NEP6Wallet, wallet_error::WalletError},
+	wallet::{
+		hd::{
+			format_derivation_path, neo_derivation_path, ExtendedPrivateKey,
+			DERIVATION_PATH_EXTRA_KEY,
+		},
+		nep6wallet::NEP6Wallet,
+		wallet_error::WalletError,
+	},
 	NEP6Account, NEP6Contract, NEP6Parameter, Signer,
 };
 use async_trait::async_trait;
-use neo_crypto::keys::Secp256r1Signature;
+use coins_bip32::path::DerivationPath;
+use coins_bip39::{Mnemonic, Wordlist};
+use neo_config::NeoNetwork;
+use neo_crypto::{key_pair::KeyPair, keys::Secp256r1Signature, wif::private_key_from_wif};
 use neo_providers::{
 	core::{
 		account::{Account, AccountTrait},
+		error::BuilderError,
 		transaction::{
 			transaction::Transaction, verification_script::VerificationScript, witness,
 			witness::Witness,
@@ -16,10 +27,8 @@ use neo_providers::{
 	Middleware,
 };
 use neo_types::{
-	address::{Address, AddressExtension},
-	address_or_scripthash::AddressOrScriptHash,
-	contract_parameter_type::ContractParameterType,
-	ScryptParamsDef, *,
+	address::Address, address_or_scripthash::AddressOrScriptHash,
+	contract_parameter_type::ContractParameterType, ScryptParamsDef, *,
 };
 use primitive_types::H160;
 use serde_derive::{Deserialize, Serialize};
@@ -36,6 +45,15 @@ pub struct Wallet {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	pub(crate) default_account: H160,
+	/// The master key of the mnemonic the accounts are derived from
+	#[serde(skip)]
+	pub(crate) hd_root: Option<ExtendedPrivateKey>,
+	/// The derivation paths of the derived accounts
+	#[serde(skip)]
+	pub(crate) derivation_paths: HashMap<H160, String>,
+	/// The magic of the network transactions are signed for, MainNet if not set
+	#[serde(skip)]
+	pub(crate) network_magic: Option<u32>,
 }
 
 impl WalletTrait for Wallet {
@@ -96,6 +114,9 @@ impl Wallet {
 			scrypt_params: ScryptParamsDef::default(),
 			accounts: HashMap::new(),
 			default_account: H160::default(),
+			hd_root: None,
+			derivation_paths: HashMap::new(),
+			network_magic: None,
 		}
 	}
 
@@ -114,8 +135,22 @@ impl Wallet {
 	// Serialization methods
 
 	pub fn to_nep6(&self) -> Result<NEP6Wallet, WalletError> {
-		let accounts =
-			self.accounts.values().filter_map(|a| Wallet::from_account(a).ok()).collect();
+		let accounts = self
+			.accounts
+			.values()
+			.filter_map(|a| Wallet::from_account(a).ok())
+			.map(|mut nep6_account| {
+				let script_hash = nep6_account.address.script_hash();
+				nep6_account.is_default = script_hash == self.default_account;
+				if let Some(path) = self.derivation_paths.get(&script_hash) {
+					nep6_account
+						.extra
+						.get_or_insert_with(HashMap::new)
+						.insert(DERIVATION_PATH_EXTRA_KEY.to_string(), path.clone());
+				}
+				nep6_account
+			})
+			.collect();
 
 		Ok(NEP6Wallet {
 			name: self.name.clone(),
@@ -138,41 +173,45 @@ impl Wallet {
 			.iter()
 			.find(|a| a.is_default)
 			.map(|a| a.address())
-			.ok_or(WalletError::NoDefaultAccount)
-			.unwrap();
+			.ok_or(WalletError::NoDefaultAccount)?;
+
+		let derivation_paths = nep6
+			.accounts()
+			.iter()
+			.filter_map(|a| {
+				let path = a.extra.as_ref()?.get(DERIVATION_PATH_EXTRA_KEY)?;
+				Some((a.address.script_hash(), path.clone()))
+			})
+			.collect();
 
 		Ok(Self {
 			name: nep6.name().clone(),
 			version: nep6.version().clone(),
 			scrypt_params: nep6.scrypt().clone(),
 			accounts: accounts.into_iter().map(|a| (a.get_script_hash().clone(), a)).collect(),
-			default_account: default_account.to_script_hash().unwrap(),
+			default_account: default_account.script_hash(),
+			hd_root: None,
+			derivation_paths,
+			network_magic: None,
 		})
 	}
 
 	fn to_account(nep6_account: &NEP6Account) -> Result<Account, WalletError> {
-		let (verification_script, signing_threshold, nr_of_participants) =
-			match nep6_account.contract {
-				Some(ref contract) if contract.script.is_some() => {
-					let script = contract.script.clone().unwrap();
-					let verification_script = VerificationScript::from(script.as_bytes().to_vec());
-					let signing_threshold = if verification_script.is_multi_sig() {
-						Some(verification_script.get_signing_threshold().unwrap())
-					} else {
-						None
-					};
-					let nr_of_participants = if verification_script.is_multi_sig() {
-						Some(verification_script.get_nr_of_accounts().unwrap())
-					} else {
-						None
-					};
-					(Some(verification_script), signing_threshold, nr_of_participants)
-				},
-				_ => (None, None, None),
-			};
+		let verification_script = nep6_account
+			.contract
+			.as_ref()
+			.and_then(|contract| contract.script.as_ref())
+			.map(|script| VerificationScript::from(script.as_bytes().to_vec()));
+		let (signing_threshold, nr_of_participants) = match &verification_script {
+			Some(script) if script.is_multi_sig() => (
+				Some(script.get_signing_threshold().map_err(invalid_script)?),
+				Some(script.get_nr_of_accounts().map_err(invalid_script)?),
+			),
+			_ => (None, None),
+		};
 
 		Ok(Account {
-			address_or_scripthash: AddressOrScriptHash::Address(nep6_account.address.clone()),
+			address_or_scripthash: AddressOrScriptHash::Address(nep6_account.address),
 			label: nep6_account.label.clone(),
 			verification_script,
 			is_locked: nep6_account.lock,
@@ -205,8 +244,7 @@ impl Wallet {
 		let contract = match &account.verification_script {
 			Some(script) => {
 				let parameters = if script.is_multi_sig() {
-					let threshold = script.get_signing_threshold().unwrap();
-					let nr_accounts = script.get_nr_of_accounts().unwrap();
+					let nr_accounts = script.get_nr_of_accounts().map_err(invalid_script)?;
 					(0..nr_accounts)
 						.map(|i| NEP6Parameter {
 							param_name: format!("signature{}", i),
@@ -223,7 +261,7 @@ impl Wallet {
 				};
 
 				Some(NEP6Contract {
-					script: Some(script.script().to_base64()),
+					script: Some(encoding::base64_encode(script.script())),
 					nep6_parameters: parameters,
 					is_deployed: false,
 				})
@@ -244,18 +282,86 @@ impl Wallet {
 
 	pub fn save_to_file(&self, path: PathBuf) -> Result<(), WalletError> {
 		// Convert wallet to NEP6
-		let nep6 = self.to_nep6().unwrap();
+		let nep6 = self.to_nep6()?;
 
 		// Encode as JSON
-		let json = serde_json::to_string(&nep6).unwrap();
+		let json = serde_json::to_string(&nep6)?;
 
 		// Write to file at path
-		let mut file = File::create(path).unwrap();
-		file.write_all(json.as_bytes()).unwrap();
+		let mut file = File::create(path)?;
+		file.write_all(json.as_bytes())?;
 
 		Ok(())
 	}
 
+	/// Imports the private key `wif` in Wallet Import Format as a new account. The account becomes
+	/// the default account if the wallet has no account yet.
+	pub fn import_wif(&mut self, wif: &str) -> Result<&Account, WalletError> {
+		let key_pair = KeyPair::from_secret_key(&private_key_from_wif(wif)?);
+		let account = Account::from_key_pair(key_pair, None, None)
+			.map_err(|e| WalletError::AccountState(e.to_string()))?;
+		let script_hash = account.get_script_hash();
+		if self.accounts.is_empty() {
+			self.default_account = script_hash;
+		}
+		self.add_account(account);
+		Ok(&self.accounts[&script_hash])
+	}
+
+	/// Creates a wallet from a BIP-39 mnemonic, with an optional passphrase. The account at index 0
+	/// of the Neo derivation path becomes the default account, see [`hd`](crate::hd).
+	///
+	/// Mnemonics of NeoLine and O3 wallets restore the same accounts, derive them with
+	/// [`Wallet::derive_account`].
+	pub fn from_mnemonic<W: Wordlist>(
+		phrase: &str,
+		password: Option<&str>,
+	) -> Result<Self, WalletError> {
+		let mut wallet = Self::new();
+		wallet.set_mnemonic::<W>(phrase, password)?;
+		wallet.derive_account(0)?;
+		Ok(wallet)
+	}
+
+	/// Sets the mnemonic new accounts are derived from, e.g. after loading a wallet from a NEP-6
+	/// file, which does not store it.
+	pub fn set_mnemonic<W: Wordlist>(
+		&mut self,
+		phrase: &str,
+		password: Option<&str>,
+	) -> Result<(), WalletError> {
+		let mnemonic = Mnemonic::<W>::new_from_phrase(phrase)?;
+		self.hd_root = Some(ExtendedPrivateKey::from_mnemonic(&mnemonic, password)?);
+		Ok(())
+	}
+
+	/// Derives the account at `index` of the Neo derivation path `m/44'/888'/0'/0/{index}` and
+	/// adds it to the wallet. The account becomes the default account if the wallet has no
+	/// account yet.
+	pub fn derive_account(&mut self, index: u32) -> Result<&Account, WalletError> {
+		self.derive_account_at(&neo_derivation_path(index)?)
+	}
+
+	/// Derives the account at `path` and adds it to the wallet.
+	pub fn derive_account_at(&mut self, path: &DerivationPath) -> Result<&Account, WalletError> {
+		let root = self.hd_root.as_ref().ok_or(WalletError::NoMnemonic)?;
+		let key_pair = KeyPair::from_secret_key(&root.derive_path(path).private_key());
+		let account = Account::from_key_pair(key_pair, None, None)
+			.map_err(|e| WalletError::AccountState(e.to_string()))?;
+		let script_hash = account.get_script_hash();
+		if self.accounts.is_empty() {
+			self.default_account = script_hash;
+		}
+		self.derivation_paths.insert(script_hash, format_derivation_path(path));
+		self.add_account(account);
+		Ok(&self.accounts[&script_hash])
+	}
+
+	/// The derivation path of a derived account
+	pub fn derivation_path(&self, script_hash: &H160) -> Option<&str> {
+		self.derivation_paths.get(script_hash).map(String::as_str)
+	}
+
 	pub fn get_account(&self, script_hash: &H160) -> Option<&Account> {
 		self.accounts.get(script_hash)
 	}
@@ -271,6 +377,52 @@ impl Wallet {
 	}
 }
 
+impl Wallet {
+	/// Signs `hash_data` for the multi-sig `account` with the keys of the wallet that belong to
+	/// it, until its signing threshold is reached.
+	fn create_multi_sig_witness(
+		&self,
+		account: &Account,
+		hash_data: &[u8],
+	) -> Result<Witness, WalletError> {
+		let script = account.verification_script().clone().ok_or_else(|| {
+			WalletError::AccountState(
+				"The multi-sig account has no verification script".to_string(),
+			)
+		})?;
+		let threshold = script.get_signing_threshold().map_err(invalid_script)?;
+
+		// the signatures must be in the order of the public keys in the script
+		let mut signatures = Vec::with_capacity(threshold);
+		for public_key in script.get_public_keys().map_err(invalid_script)? {
+			if signatures.len() == threshold {
+				break
+			}
+			let key_pair = self
+				.accounts
+				.values()
+				.filter_map(|account| account.key_pair().as_ref())
+				.find(|key_pair| key_pair.public_key() == public_key);
+			if let Some(key_pair) = key_pair {
+				signatures.push(key_pair.private_key().sign_tx(hash_data)?);
+			}
+		}
+		if signatures.len() < threshold {
+			return Err(WalletError::AccountState(format!(
+				"The wallet holds {} of the {threshold} keys needed to sign for {}",
+				signatures.len(),
+				account.address_or_scripthash().address()
+			)))
+		}
+		Witness::create_multi_sig_witness_script(signatures, script).map_err(invalid_script)
+	}
+}
+
+fn invalid_script(err: BuilderError) -> WalletError {
+	WalletError::AccountState(format!("Invalid verification script: {err}"))
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl Signer for Wallet {
 	type Error = WalletError;
@@ -282,35 +434,70 @@ impl Signer for Wallet {
 		let binding = hash_message(message);
 		let message_hash = binding.as_bytes();
 		self.default_account()
-			.clone()
 			.key_pair()
-			.clone()
-			.unwrap()
+			.as_ref()
+			.ok_or(WalletError::NoKeyPair)?
 			.private_key()
 			.sign_tx(message_hash)
-			.map_err(|e| WalletError::NoKeyPair)
+			.map_err(WalletError::from)
 	}
 
+	/// Signs with the key of the default account. For a multi-sig default account, the keys of
+	/// the other accounts of the wallet that belong to it are used.
 	async fn get_witness(&self, tx: &Transaction) -> Result<Witness, Self::Error> {
-		let mut tx_with_chain = tx.clone();
-		if tx_with_chain.network_magic().is_none() {
-			// in the case we don't have a network_magic, let's use the signer network magic instead
-			tx_with_chain.set_network_magic(self.network_magic());
+		// in the case we don't have a network_magic, let's use the signer network magic instead
+		let network_magic = tx.network_magic().unwrap_or(self.network_magic());
+		let hash_data = tx.get_hash_data(network_magic);
+
+		let account = self.default_account();
+		if account.is_multi_sig() {
+			return self.create_multi_sig_witness(account, &hash_data)
 		}
+		let key_pair = account.key_pair.as_ref().ok_or(WalletError::NoKeyPair)?;
+		Witness::create(hash_data, key_pair).map_err(WalletError::from)
+	}
 
-		Witness::create(tx.get_hash_data()?, &self.default_account().key_pair.clone().unwrap())
-			.map_err(|e| WalletError::NoKeyPair)
+	fn account(&self) -> &Account {
+		self.default_account()
 	}
 
 	fn address(&self) -> Address {
-		self.address()
+		self.default_account().address_or_scripthash().address()
 	}
+
 	fn network_magic(&self) -> u32 {
-		todo!()
+		self.network_magic.unwrap_or_else(|| NeoNetwork::MainNet.to_magic())
 	}
 
-	/// Sets the wallet's network_magic, used in conjunction with EIP-155 signing
+	/// Sets the magic of the network the wallet signs transactions for
 	fn with_network_magic<T: Into<u32>>(mut self, network_magic: T) -> Self {
-		todo!()
+		self.network_magic = Some(network_magic.into());
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use neo_config::TestConstants;
+
+	#[test]
+	fn test_import_wif() {
+		let mut wallet = Wallet::new();
+		let account = wallet.import_wif(TestConstants::DEFAULT_ACCOUNT_WIF).unwrap();
+		assert_eq!(
+			account.address_or_scripthash().address().to_string(),
+			TestConstants::DEFAULT_ACCOUNT_ADDRESS
+		);
+		let script_hash = account.get_script_hash();
+		assert_eq!(hex::encode(script_hash), TestConstants::DEFAULT_ACCOUNT_SCRIPT_HASH);
+		assert_eq!(wallet.default_account().get_script_hash(), script_hash);
+
+		// later imports do not replace the default account
+		wallet.import_wif(TestConstants::CLIENT1_ACCOUNT_WIF).unwrap();
+		assert_eq!(wallet.accounts().len(), 2);
+		assert_eq!(wallet.default_account().get_script_hash(), script_hash);
+
+		assert!(wallet.import_wif("not a wif").is_err());
 	}
 }
@@ -1,6 +1,9 @@
 use crate::wallet::MnemonicBuilderError;
 use coins_bip39::MnemonicError;
-use neo_providers::core::transaction::transaction_error::TransactionError;
+use neo_providers::{
+	core::{error::BuilderError, transaction::transaction_error::TransactionError},
+	MiddlewareError, ProviderError,
+};
 use p256::ecdsa;
 use thiserror::Error;
 
@@ -19,8 +22,14 @@ pub enum WalletError {
 	/// Error propagated by IO operations
 	#[error(transparent)]
 	IoError(#[from] std::io::Error),
+	/// Error of the (de)serialization of a NEP-6 file
+	#[error(transparent)]
+	SerdeJson(#[from] serde_json::Error),
 	#[error("No default account")]
 	NoDefaultAccount,
+	/// Accounts can only be derived from a wallet with a mnemonic
+	#[error("Wallet has no mnemonic to derive accounts from")]
+	NoMnemonic,
 	#[error("Invalid key pair")]
 	SignHashError,
 	#[error(transparent)]
@@ -33,4 +42,24 @@ pub enum WalletError {
 	CryptoError(#[from] neo_crypto::error::CryptoError),
 	#[error(transparent)]
 	TransactionError(#[from] TransactionError),
+	/// Error of building a witness
+	#[error(transparent)]
+	BuilderError(#[from] BuilderError),
+	/// Error of a request to the node
+	#[error(transparent)]
+	ProviderError(#[from] ProviderError),
+	/// Error of the middleware stack requests are sent through
+	#[error("Middleware error: {0}")]
+	MiddlewareError(Box<dyn std::error::Error + Send + Sync>),
+	/// Error of the YubiHSM holding the key
+	#[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
+	#[error("YubiHSM error: {0}")]
+	YubiHsm(String),
+}
+
+impl WalletError {
+	/// Wraps an error of the middleware requests are sent through.
+	pub fn from_middleware_error<E: MiddlewareError + 'static>(e: E) -> Self {
+		WalletError::MiddlewareError(Box::new(e))
+	}
 }
@@ -1,18 +1,24 @@
 //! Helpers for creating wallets for YubiHSM2
-use super::Wallet;
-use elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
-
+use super::{Wallet, WalletError};
+use neo_crypto::keys::Secp256r1PublicKey;
+use neo_types::{address::Address, script_hash::ScriptHashExt};
+use p256::NistP256;
+use primitive_types::H160;
 use yubihsm::{
-	asymmetric::Algorithm::EcK256, ecdsa::Signer as YubiSigner, object, object::Label, Capability,
+	asymmetric::Algorithm::EcP256, ecdsa::Signer as YubiSigner, object, object::Label, Capability,
 	Client, Connector, Credentials, Domain,
 };
 
-impl Wallet<YubiSigner<Secp256r1>> {
+impl Wallet<YubiSigner<NistP256>> {
 	/// Connects to a yubi key's ECDSA account at the provided id
-	pub fn connect(connector: Connector, credentials: Credentials, id: object::Id) -> Self {
-		let client = Client::open(connector, credentials, true).unwrap();
-		let signer = YubiSigner::create(client, id).unwrap();
-		signer.into()
+	pub fn connect(
+		connector: Connector,
+		credentials: Credentials,
+		id: object::Id,
+	) -> Result<Self, WalletError> {
+		let client = Client::open(connector, credentials, true).map_err(yubi_error)?;
+		let signer = YubiSigner::create(client, id).map_err(yubi_error)?;
+		signer.try_into()
 	}
 
 	/// Creates a new random ECDSA keypair on the yubi at the provided id
@@ -22,13 +28,13 @@ impl Wallet<YubiSigner<Secp256r1>> {
 		id: object::Id,
 		label: Label,
 		domain: Domain,
-	) -> Self {
-		let client = Client::open(connector, credentials, true).unwrap();
+	) -> Result<Self, WalletError> {
+		let client = Client::open(connector, credentials, true).map_err(yubi_error)?;
 		let id = client
-			.generate_asymmetric_key(id, label, domain, Capability::SIGN_ECDSA, EcK256)
-			.unwrap();
-		let signer = YubiSigner::create(client, id).unwrap();
-		signer.into()
+			.generate_asymmetric_key(id, label, domain, Capability::SIGN_ECDSA, EcP256)
+			.map_err(yubi_error)?;
+		let signer = YubiSigner::create(client, id).map_err(yubi_error)?;
+		signer.try_into()
 	}
 
 	/// Uploads the provided keypair on the yubi at the provided id
@@ -39,27 +45,29 @@ impl Wallet<YubiSigner<Secp256r1>> {
 		label: Label,
 		domain: Domain,
 		key: impl Into<Vec<u8>>,
-	) -> Self {
-		let client = Client::open(connector, credentials, true).unwrap();
+	) -> Result<Self, WalletError> {
+		let client = Client::open(connector, credentials, true).map_err(yubi_error)?;
 		let id = client
-			.put_asymmetric_key(id, label, domain, Capability::SIGN_ECDSA, EcK256, key)
-			.unwrap();
-		let signer = YubiSigner::create(client, id).unwrap();
-		signer.into()
+			.put_asymmetric_key(id, label, domain, Capability::SIGN_ECDSA, EcP256, key)
+			.map_err(yubi_error)?;
+		let signer = YubiSigner::create(client, id).map_err(yubi_error)?;
+		signer.try_into()
 	}
 }
 
-impl From<YubiSigner<Secp256r1>> for Wallet<YubiSigner<Secp256r1>> {
-	fn from(signer: YubiSigner<Secp256r1>) -> Self {
-		// this will never fail
-		let public_key = Secp256r1PublicKey::from_encoded_point(signer.public_key()).unwrap();
-		let public_key = public_key.to_encoded_point(/* compress = */ false);
-		let public_key = public_key.as_bytes();
-		debug_assert_eq!(public_key[0], 0x04);
-		let hash = keccak256(&public_key[1..]);
-		let address = Address::from_slice(&hash[12..]);
+fn yubi_error(err: impl std::fmt::Display) -> WalletError {
+	WalletError::YubiHsm(err.to_string())
+}
+
+impl TryFrom<YubiSigner<NistP256>> for Wallet<YubiSigner<NistP256>> {
+	type Error = WalletError;
 
-		Self { signer, address, network_magic: 1 }
+	fn try_from(signer: YubiSigner<NistP256>) -> Result<Self, Self::Error> {
+		let public_key =
+			Secp256r1PublicKey::from_bytes(signer.public_key().as_bytes()).map_err(yubi_error)?;
+		let address = Address::from_script_hash(H160::from_public_key(&public_key));
+
+		Ok(Self { signer, address, network_magic: None })
 	}
 }
 
@@ -68,7 +76,8 @@ impl From<YubiSigner<Secp256r1>> for Wallet<YubiSigner<Secp256r1>> {
 mod tests {
 	use super::*;
 	use crate::Signer;
-	use std::str::FromStr;
+	use neo_crypto::keys::Secp256r1PrivateKey;
+	use neo_types::{hash_message, op_code::OpCode};
 
 	#[tokio::test]
 	async fn from_key() {
@@ -82,31 +91,39 @@ mod tests {
 			0,
 			Label::from_bytes(&[]).unwrap(),
 			Domain::at(1).unwrap(),
-			key,
-		);
+			key.clone(),
+		)
+		.unwrap();
 
-		let msg = "Some data";
-		let sig = wallet.sign_message(msg).await.unwrap();
-		assert_eq!(sig.recover(msg).unwrap(), wallet.address());
-		assert_eq!(
-			wallet.address(),
-			Address::from_str("2DE2C386082Cff9b28D62E60983856CE1139eC49").unwrap()
-		);
+		let public_key = Secp256r1PrivateKey::from_bytes(&key).unwrap().to_public_key();
+		assert_eq!(wallet.address(), Address::from_script_hash(H160::from_public_key(&public_key)));
 	}
 
 	#[tokio::test]
 	async fn new_key() {
 		let connector = yubihsm::Connector::mockhsm();
-		let wallet = Wallet::<YubiSigner<Secp256r1>>::new(
+		let wallet = Wallet::<YubiSigner<NistP256>>::new(
 			connector,
 			Credentials::default(),
 			0,
 			Label::from_bytes(&[]).unwrap(),
 			Domain::at(1).unwrap(),
-		);
+		)
+		.unwrap();
+
+		// the address is the script hash of the single-sig script of the generated P-256 key
+		let public_key =
+			Secp256r1PublicKey::from_bytes(wallet.signer.public_key().as_bytes()).unwrap();
+		let script = [
+			&[OpCode::PushData1 as u8, 33][..],
+			&public_key.get_encoded(true),
+			&[OpCode::Syscall as u8, 0x56, 0xe7, 0xb3, 0x27],
+		]
+		.concat();
+		assert_eq!(wallet.address(), Address::from_script_hash(H160::from_script(&script)));
 
-		let msg = "Some data";
-		let sig = wallet.sign_message(msg).await.unwrap();
-		assert_eq!(sig.recover(msg).unwrap(), wallet.address());
+		let signature = wallet.sign_message("Some data").await.unwrap();
+		assert!(public_key.verify(hash_message(b"Some data").as_bytes(), &signature).is_ok());
+		assert!(public_key.verify(hash_message(b"Other data").as_bytes(), &signature).is_err());
 	}
 }
@@ -1,15 +1,109 @@
-use crate::{error::TypeError, script_hash::ScriptHash};
-use neo_crypto::hash::HashableForVec;
+use crate::{encoding, error::TypeError, script_hash::ScriptHash};
+use neo_config::DEFAULT_ADDRESS_VERSION;
 use primitive_types::H160;
 use rand::Rng;
-use serde_derive::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_derive::{Deserialize as DeriveDeserialize, Serialize as DeriveSerialize};
+use std::{fmt, str::FromStr};
 
-pub type Address = String;
+/// A Neo address: a script hash and an address version, written in Base58Check.
+///
+/// The Base58Check payload is the version byte followed by the script hash in little-endian
+/// order, and the checksum is the first four bytes of the double SHA-256 of the payload.
+/// Conversions from and to [`ScriptHash`] are explicit, with [`Address::from_script_hash`] and
+/// [`Address::script_hash`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Address {
+	version: u8,
+	script_hash: ScriptHash,
+}
+
+impl Address {
+	/// Length of the Base58Check payload: version and script hash
+	const PAYLOAD_LENGTH: usize = 21;
+
+	/// Creates the address of `script_hash` with the default address version.
+	pub fn from_script_hash(script_hash: ScriptHash) -> Self {
+		Self::with_version(script_hash, DEFAULT_ADDRESS_VERSION)
+	}
+
+	/// Creates the address of `script_hash` with the given address version.
+	pub fn with_version(script_hash: ScriptHash, version: u8) -> Self {
+		Self { version, script_hash }
+	}
+
+	/// The script hash the address stands for
+	pub fn script_hash(&self) -> ScriptHash {
+		self.script_hash
+	}
+
+	/// The address version
+	pub fn version(&self) -> u8 {
+		self.version
+	}
+
+	/// A random address with the default address version.
+	pub fn random() -> Self {
+		let mut bytes = [0u8; 20];
+		rand::thread_rng().fill(&mut bytes);
+		Self::from_script_hash(H160(bytes))
+	}
+}
+
+impl Default for Address {
+	fn default() -> Self {
+		Self::from_script_hash(ScriptHash::zero())
+	}
+}
+
+impl fmt::Display for Address {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut payload = Vec::with_capacity(Self::PAYLOAD_LENGTH);
+		payload.push(self.version);
+		payload.extend(self.script_hash.as_bytes().iter().rev());
+		f.write_str(&encoding::base58check_encode(&payload))
+	}
+}
+
+impl fmt::Debug for Address {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Address({self})")
+	}
+}
+
+impl FromStr for Address {
+	type Err = TypeError;
+
+	fn from_str(address: &str) -> Result<Self, Self::Err> {
+		let payload =
+			encoding::base58check_decode(address).map_err(|_| TypeError::InvalidAddress)?;
+		if payload.len() != Self::PAYLOAD_LENGTH {
+			return Err(TypeError::InvalidAddress)
+		}
+		let mut script_hash = [0u8; 20];
+		script_hash.copy_from_slice(&payload[1..]);
+		script_hash.reverse();
+		Ok(Self::with_version(H160(script_hash), payload[0]))
+	}
+}
+
+impl Serialize for Address {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for Address {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let address = String::deserialize(deserializer)?;
+		address.parse().map_err(serde::de::Error::custom)
+	}
+}
 
 // NameOrAddress::Name(nns_name) => self.resolve_name(&nns_name).await?,
 // NameOrAddress::Address(addr) => addr,
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveSerialize, DeriveDeserialize)]
 pub enum NameOrAddress {
 	Name(String),
 	Address(Address),
@@ -23,31 +117,11 @@ pub trait AddressExtension {
 
 impl AddressExtension for String {
 	fn to_script_hash(&self) -> Result<ScriptHash, TypeError> {
-		// Base58-decode the address
-		let binding = match bs58::decode(self).into_vec() {
-			Ok(data) => H160::from_slice(data.as_slice()),
-			Err(_) => return Err(TypeError::InvalidAddress),
-		};
-		let decoded_data = binding.as_bytes();
-
-		// Extract the data payload
-		let data_payload = decoded_data[1..decoded_data.len() - 4].to_vec();
-
-		let script_hash = data_payload.sha256_ripemd160(); //  ripemd160.finalize();
-
-		Ok(H160::from_slice(script_hash.as_slice()))
+		Ok(Address::from_str(self)?.script_hash())
 	}
 
 	fn random() -> Self {
-		let mut rng = rand::thread_rng();
-		let mut bytes = [0u8; 20];
-		rng.fill(&mut bytes);
-		let script_hash = bytes.sha256_ripemd160();
-		let mut data = vec![0x17];
-		data.extend_from_slice(&script_hash);
-		let mut sha = &data.hash256().hash256();
-		data.extend_from_slice(&sha[..4]);
-		bs58::encode(data).into_string()
+		Address::random().to_string()
 	}
 }
 
@@ -68,4 +142,32 @@ mod tests {
 		let result = n3_address.to_string().to_script_hash();
 		assert!(result.is_err());
 	}
+
+	#[test]
+	fn test_address_round_trip() {
+		let address: Address = "NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke".parse().unwrap();
+		assert_eq!(address.version(), DEFAULT_ADDRESS_VERSION);
+		assert_eq!(hex::encode(address.script_hash()), "d6c712eb53b1a130f59fd4e5864bdac27458a509");
+		assert_eq!(address.to_string(), "NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke");
+		assert_eq!(Address::from_script_hash(address.script_hash()), address);
+
+		let json = serde_json::to_string(&address).unwrap();
+		assert_eq!(json, "\"NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke\"");
+		assert_eq!(serde_json::from_str::<Address>(&json).unwrap(), address);
+	}
+
+	#[test]
+	fn test_invalid_address() {
+		// wrong checksum
+		assert_eq!(
+			"NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8kf".parse::<Address>(),
+			Err(TypeError::InvalidAddress)
+		);
+		// too long
+		assert_eq!(
+			"NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8keas".parse::<Address>(),
+			Err(TypeError::InvalidAddress)
+		);
+		assert_eq!("0OIl".parse::<Address>(), Err(TypeError::InvalidAddress));
+	}
 }
@@ -1,8 +1,4 @@
-use crate::{
-	address::{Address, AddressExtension},
-	script_hash::ScriptHashExtension,
-	Bytes,
-};
+use crate::{address::Address, Bytes};
 use primitive_types::H160;
 use serde_derive::{Deserialize, Serialize};
 use std::{
@@ -50,13 +46,13 @@ impl From<Bytes> for AddressOrScriptHash {
 impl AddressOrScriptHash {
 	pub fn address(&self) -> Address {
 		match self {
-			AddressOrScriptHash::Address(a) => a.clone(),
-			AddressOrScriptHash::ScriptHash(s) => s.to_address(),
+			AddressOrScriptHash::Address(a) => *a,
+			AddressOrScriptHash::ScriptHash(s) => Address::from_script_hash(*s),
 		}
 	}
 	pub fn script_hash(&self) -> H160 {
 		match self {
-			AddressOrScriptHash::Address(a) => a.to_script_hash().unwrap(),
+			AddressOrScriptHash::Address(a) => a.script_hash(),
 			AddressOrScriptHash::ScriptHash(s) => s.clone(),
 		}
 	}
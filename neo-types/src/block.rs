@@ -1,4 +1,3 @@
-use ethereum_types::U64;
 use primitive_types::H256;
 use serde::{
 	de::{MapAccess, Visitor},
@@ -94,12 +93,13 @@ pub enum BlockId {
 
 impl From<u64> for BlockId {
 	fn from(num: u64) -> Self {
-		num.into()
+		BlockId::Number(num)
 	}
 }
 
-impl From<U64> for BlockId {
-	fn from(num: U64) -> Self {
+#[cfg(feature = "interop-eth")]
+impl From<ethereum_types::U64> for BlockId {
+	fn from(num: ethereum_types::U64) -> Self {
 		BlockId::Number(num.as_u64())
 	}
 }
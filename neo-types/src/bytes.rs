@@ -1,9 +1,8 @@
 use std::ops::BitXor;
 
-use bs58::encode as bs58_encode;
+use crate::encoding::{base58_encode, base58check_encode, base64_encode, hex_encode};
 use derive_more::{AsRef, Deref, Index, IndexMut, IntoIterator};
-use hex::encode as hex_encode;
-use sha2::{Digest, Sha256};
+use neo_config::DEFAULT_ADDRESS_VERSION;
 
 use serde::{Deserialize, Serialize};
 
@@ -20,22 +19,19 @@ impl Bytes {
 	}
 
 	fn base64_encoded(&self) -> String {
-		base64::encode(&self.0)
+		base64_encode(&self.0)
 	}
 
 	fn base58_encoded(&self) -> String {
-		bs58_encode(self.0.as_slice()).into_string()
+		base58_encode(&self.0)
 	}
 
 	fn base58_check_encoded(&self) -> String {
-		let checksum = &Sha256::digest(&Sha256::digest(&self.0))[..4];
-		let mut bytes = self.0.clone();
-		bytes.extend_from_slice(checksum);
-		bs58_encode(&bytes).into_string()
+		base58check_encode(&self.0)
 	}
 
 	fn no_prefix_hex(&self) -> String {
-		hex_encode(self.0.as_slice()).trim_start_matches("0x").to_string()
+		hex_encode(&self.0)
 	}
 
 	fn var_size(&self) -> usize {
@@ -43,17 +39,9 @@ impl Bytes {
 	}
 
 	fn scripthash_to_address(&self) -> String {
-		let mut script = vec![0x17];
-		script.extend_from_slice(&self.0.iter().rev().copied().collect::<Vec<_>>());
-
-		let mut hasher = Sha256::new();
-		hasher.update(&script);
-		let checksum = &hasher.finalize()[..4];
-
-		let mut address = script;
-		address.extend_from_slice(checksum);
-
-		bs58_encode(&address).into_string()
+		let mut payload = vec![DEFAULT_ADDRESS_VERSION];
+		payload.extend(self.0.iter().rev());
+		base58check_encode(&payload)
 	}
 
 	fn to_padded(&self, length: usize, trailing: bool) -> Result<Bytes, &'static str> {
@@ -1,36 +1,44 @@
+//! The manifest of a contract: its ABI, the standards it implements and the permissions it needs.
+//!
+//! The serde representation is the JSON of the manifest the node stores and returns from
+//! `getcontractstate`, so manifests round-trip between files, deployments and RPC responses.
+
 use crate::{
-	contract_parameter::ContractParameter, contract_parameter_type::ContractParameterType,
+	contract_parameter::ContractParameter,
+	contract_parameter_type::ContractParameterType,
+	script_hash::ScriptHashBE,
+	standard::{MethodSpec, Standard},
 };
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use neo_crypto::keys::Secp256r1PublicKey;
+use primitive_types::H160;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
+	fmt,
 	hash::{Hash, Hasher},
+	str::FromStr,
 };
+use thiserror::Error;
 
-use crate::{deserialize_wildcard, serialize_wildcard};
-
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct ContractManifest {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub name: Option<String>,
 	#[serde(default)]
 	pub groups: Vec<ContractGroup>,
-	#[serde(skip_serializing)]
-	pub features: Option<HashMap<String, serde_json::Value>>,
+	/// Reserved, has to be empty
+	#[serde(default)]
+	pub features: HashMap<String, serde_json::Value>,
 	#[serde(default)]
-	#[serde(serialize_with = "serialize_wildcard")]
-	#[serde(deserialize_with = "deserialize_wildcard")]
 	#[serde(rename = "supportedstandards")]
 	pub supported_standards: Vec<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub abi: Option<ContractABI>,
 	#[serde(default)]
 	pub permissions: Vec<ContractPermission>,
-	#[serde(skip_serializing)]
-	#[serde(serialize_with = "serialize_wildcard")]
-	#[serde(deserialize_with = "deserialize_wildcard")]
-	pub trusts: Vec<String>,
-	#[serde(skip_serializing_if = "Option::is_none")]
+	/// The contracts and groups that may call this contract with their witness
+	#[serde(default)]
+	pub trusts: WildcardContainer<ContractPermissionDescriptor>,
 	pub extra: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -61,8 +69,182 @@ impl Hash for ContractManifest {
 	}
 }
 
+impl ContractManifest {
+	/// Checks the rules the node enforces on deployment, and that the contract implements the
+	/// ABI of every standard of [`Standard::ALL`] it declares in `supportedstandards`.
+	///
+	/// Generated and dynamic bindings rely on a valid manifest: methods are looked up by name
+	/// and number of parameters, and arguments are named after the parameters.
+	pub fn validate(&self) -> Result<(), ManifestError> {
+		if self.name.as_deref().unwrap_or_default().is_empty() {
+			return Err(ManifestError::MissingName)
+		}
+		if !self.features.is_empty() {
+			return Err(ManifestError::Features)
+		}
+		for group in &self.groups {
+			if Secp256r1PublicKey::from_encoded(&group.pub_key).is_none() {
+				return Err(ManifestError::InvalidGroup(group.pub_key.clone()))
+			}
+		}
+
+		let abi = self.abi.as_ref().ok_or(ManifestError::MissingAbi)?;
+		let mut methods = HashSet::new();
+		for method in &abi.methods {
+			if !methods.insert((method.name.as_str(), method.parameters.len())) {
+				return Err(ManifestError::DuplicateMethod {
+					name: method.name.clone(),
+					parameters: method.parameters.len(),
+				})
+			}
+			check_parameters(&method.name, &method.parameters)?;
+		}
+		let mut events = HashSet::new();
+		for event in &abi.events {
+			if !events.insert(event.name.as_str()) {
+				return Err(ManifestError::DuplicateEvent(event.name.clone()))
+			}
+			check_parameters(&event.name, &event.parameters)?;
+		}
+
+		let mut contracts = HashSet::new();
+		for permission in &self.permissions {
+			if !contracts.insert(&permission.contract) {
+				return Err(ManifestError::DuplicatePermission(permission.contract.to_string()))
+			}
+		}
+		if let WildcardContainer::List(trusts) = &self.trusts {
+			for (index, trust) in trusts.iter().enumerate() {
+				if trusts[..index].contains(trust) {
+					return Err(ManifestError::DuplicateTrust(trust.to_string()))
+				}
+			}
+		}
+
+		let standards = self.supported_standards.iter().filter_map(|name| name.parse().ok());
+		for standard in standards {
+			if let Some(violation) = self.standard_violations(standard).into_iter().next() {
+				return Err(violation)
+			}
+		}
+		Ok(())
+	}
+
+	/// The methods and events of `standard` the ABI lacks, or declares with the wrong safe flag.
+	/// Empty if the contract conforms to the standard, whether or not it declares it.
+	pub fn standard_violations(&self, standard: Standard) -> Vec<ManifestError> {
+		let abi = match &self.abi {
+			Some(abi) => abi,
+			None => return vec![ManifestError::MissingAbi],
+		};
+
+		let mut violations = Vec::new();
+		for alternatives in standard.methods() {
+			let declared = alternatives.iter().find_map(|spec| {
+				abi.methods
+					.iter()
+					.find(|method| spec.matches(method))
+					.map(|method| (spec, method))
+			});
+			match declared {
+				None => violations.push(ManifestError::MissingMethod {
+					standard,
+					method: alternatives[0].to_string(),
+				}),
+				Some((spec, method)) if method.safe != spec.safe =>
+					violations.push(ManifestError::Safety {
+						standard,
+						method: spec.to_string(),
+						safe: spec.safe,
+					}),
+				Some(_) => {},
+			}
+		}
+		for spec in standard.events() {
+			let declared = abi.events.iter().any(|event| {
+				event.name == spec.name
+					&& types(&event.parameters).eq(spec.parameters.iter().cloned())
+			});
+			if !declared {
+				violations
+					.push(ManifestError::MissingEvent { standard, event: spec.name.to_string() });
+			}
+		}
+		violations
+	}
+}
+
+/// The types of `parameters`
+fn types(parameters: &[ContractParameter]) -> impl Iterator<Item = ContractParameterType> + '_ {
+	parameters.iter().map(ContractParameter::get_type)
+}
+
+/// Checks that the parameters of the method or event `member` have distinct, non-empty names and
+/// no `Void` type.
+fn check_parameters(member: &str, parameters: &[ContractParameter]) -> Result<(), ManifestError> {
+	let invalid = |reason: &str| ManifestError::InvalidParameters {
+		member: member.to_string(),
+		reason: reason.to_string(),
+	};
+	let mut names = HashSet::new();
+	for parameter in parameters {
+		match parameter.get_name() {
+			Some(name) if !name.is_empty() =>
+				if !names.insert(name) {
+					return Err(invalid(&format!("declare {name} twice")))
+				},
+			_ => return Err(invalid("need names")),
+		}
+		if parameter.get_type() == ContractParameterType::Void {
+			return Err(invalid("cannot be Void"))
+		}
+	}
+	Ok(())
+}
+
+impl MethodSpec {
+	/// Whether `method` has the name and signature of the spec
+	pub fn matches(&self, method: &ContractMethod) -> bool {
+		method.name == self.name
+			&& method.return_type == self.return_type
+			&& types(&method.parameters).eq(self.parameters.iter().cloned())
+	}
+}
+
+/// An invalid manifest, see [`ContractManifest::validate`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+	#[error("The manifest has no name")]
+	MissingName,
+	#[error("The manifest has no ABI")]
+	MissingAbi,
+	#[error("The features of a manifest must be empty")]
+	Features,
+	#[error("The group key {0} is not a public key")]
+	InvalidGroup(String),
+	#[error("The method {name} with {parameters} parameters is declared twice")]
+	DuplicateMethod { name: String, parameters: usize },
+	#[error("The event {0} is declared twice")]
+	DuplicateEvent(String),
+	#[error("The parameters of {member} {reason}")]
+	InvalidParameters { member: String, reason: String },
+	#[error("The permission for {0} is declared twice")]
+	DuplicatePermission(String),
+	#[error("The trust of {0} is declared twice")]
+	DuplicateTrust(String),
+	#[error("{standard} requires the method {method}")]
+	MissingMethod { standard: Standard, method: String },
+	#[error("{standard} requires {method} to be {}", if *safe { "safe" } else { "unsafe" })]
+	Safety { standard: Standard, method: String, safe: bool },
+	#[error("{standard} requires the event {event}")]
+	MissingEvent { standard: Standard, event: String },
+}
+
+/// A group of contracts, identified by a public key. The signature signs the hash of the contract
+/// with the key of the group.
 #[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Debug, Clone)]
 pub struct ContractGroup {
+	#[serde(rename = "pubkey")]
 	pub pub_key: String,
 	pub signature: String,
 }
@@ -70,8 +252,8 @@ pub struct ContractGroup {
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone)]
 pub struct ContractABI {
 	pub methods: Vec<ContractMethod>,
-	#[serde(skip_serializing_if = "Option::is_none")]
-	pub events: Option<Vec<ContractEvent>>,
+	#[serde(default)]
+	pub events: Vec<ContractEvent>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
@@ -79,6 +261,7 @@ pub struct ContractMethod {
 	pub name: String,
 	pub parameters: Vec<ContractParameter>,
 	pub offset: usize,
+	#[serde(rename = "returntype")]
 	pub return_type: ContractParameterType,
 	pub safe: bool,
 }
@@ -89,10 +272,258 @@ pub struct ContractEvent {
 	pub parameters: Vec<ContractParameter>,
 }
 
+/// The methods of the contracts matching `contract` the contract may call
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone)]
 pub struct ContractPermission {
-	pub contract: String,
-	#[serde(serialize_with = "serialize_wildcard")]
-	#[serde(deserialize_with = "deserialize_wildcard")]
-	pub methods: Vec<String>,
+	pub contract: ContractPermissionDescriptor,
+	pub methods: WildcardContainer<String>,
+}
+
+/// The contracts a permission or trust applies to: all of them, the contract with a script hash,
+/// or the contracts of a group
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractPermissionDescriptor {
+	Wildcard,
+	Hash(H160),
+	Group(Secp256r1PublicKey),
+}
+
+impl ContractPermissionDescriptor {
+	/// Whether the descriptor matches the contract `hash` in the groups `groups`
+	pub fn matches(&self, hash: &H160, groups: &[Secp256r1PublicKey]) -> bool {
+		match self {
+			ContractPermissionDescriptor::Wildcard => true,
+			ContractPermissionDescriptor::Hash(expected) => expected == hash,
+			ContractPermissionDescriptor::Group(key) => groups.contains(key),
+		}
+	}
+}
+
+impl Eq for ContractPermissionDescriptor {}
+
+impl Hash for ContractPermissionDescriptor {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		match self {
+			ContractPermissionDescriptor::Wildcard => 0u8.hash(state),
+			ContractPermissionDescriptor::Hash(hash) => hash.hash(state),
+			ContractPermissionDescriptor::Group(key) => key.get_encoded(true).hash(state),
+		}
+	}
+}
+
+impl fmt::Display for ContractPermissionDescriptor {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ContractPermissionDescriptor::Wildcard => f.write_str("*"),
+			ContractPermissionDescriptor::Hash(hash) => write!(f, "{}", ScriptHashBE(*hash)),
+			ContractPermissionDescriptor::Group(key) =>
+				f.write_str(&key.get_encoded_compressed_hex()),
+		}
+	}
+}
+
+impl FromStr for ContractPermissionDescriptor {
+	type Err = ManifestError;
+
+	/// Parses `*`, a script hash in big-endian hex or the hex of a compressed public key
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s == "*" {
+			return Ok(ContractPermissionDescriptor::Wildcard)
+		}
+		if let Ok(hash) = s.parse::<ScriptHashBE>() {
+			return Ok(ContractPermissionDescriptor::Hash(hash.0))
+		}
+		Secp256r1PublicKey::from_encoded(s)
+			.map(ContractPermissionDescriptor::Group)
+			.ok_or_else(|| ManifestError::InvalidGroup(s.to_string()))
+	}
+}
+
+impl Serialize for ContractPermissionDescriptor {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for ContractPermissionDescriptor {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		s.parse().map_err(de::Error::custom)
+	}
+}
+
+/// Either the wildcard `"*"`, standing for all values, or a list of values
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WildcardContainer<T> {
+	Wildcard,
+	List(Vec<T>),
+}
+
+impl<T> WildcardContainer<T> {
+	pub fn is_wildcard(&self) -> bool {
+		matches!(self, WildcardContainer::Wildcard)
+	}
+
+	/// Whether `value` is in the list, always true for the wildcard
+	pub fn contains(&self, value: &T) -> bool
+	where
+		T: PartialEq,
+	{
+		match self {
+			WildcardContainer::Wildcard => true,
+			WildcardContainer::List(values) => values.contains(value),
+		}
+	}
+}
+
+impl<T> Default for WildcardContainer<T> {
+	fn default() -> Self {
+		WildcardContainer::List(Vec::new())
+	}
+}
+
+impl<T: Serialize> Serialize for WildcardContainer<T> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			WildcardContainer::Wildcard => serializer.serialize_str("*"),
+			WildcardContainer::List(values) => values.serialize(serializer),
+		}
+	}
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for WildcardContainer<T> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Repr<T> {
+			Wildcard(String),
+			List(Vec<T>),
+		}
+
+		match Repr::deserialize(deserializer)? {
+			Repr::Wildcard(s) if s == "*" => Ok(WildcardContainer::Wildcard),
+			Repr::Wildcard(s) =>
+				Err(de::Error::custom(format!("expected \"*\" or a list, got {s}"))),
+			Repr::List(values) => Ok(WildcardContainer::List(values)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A NEP-17 token as compiled by neo-devpack-dotnet, with a shortened ABI
+	const TOKEN: &str = r#"{
+		"name": "Token",
+		"groups": [],
+		"features": {},
+		"supportedstandards": ["NEP-17"],
+		"abi": {
+			"methods": [
+				{"name": "symbol", "parameters": [], "returntype": "String", "offset": 0, "safe": true},
+				{"name": "decimals", "parameters": [], "returntype": "Integer", "offset": 2, "safe": true},
+				{"name": "totalSupply", "parameters": [], "returntype": "Integer", "offset": 4, "safe": true},
+				{
+					"name": "balanceOf",
+					"parameters": [{"name": "owner", "type": "Hash160"}],
+					"returntype": "Integer",
+					"offset": 6,
+					"safe": true
+				},
+				{
+					"name": "transfer",
+					"parameters": [
+						{"name": "from", "type": "Hash160"},
+						{"name": "to", "type": "Hash160"},
+						{"name": "amount", "type": "Integer"},
+						{"name": "data", "type": "Any"}
+					],
+					"returntype": "Boolean",
+					"offset": 8,
+					"safe": false
+				}
+			],
+			"events": [{
+				"name": "Transfer",
+				"parameters": [
+					{"name": "from", "type": "Hash160"},
+					{"name": "to", "type": "Hash160"},
+					{"name": "amount", "type": "Integer"}
+				]
+			}]
+		},
+		"permissions": [
+			{"contract": "*", "methods": ["onNEP17Payment"]},
+			{"contract": "0xfffdc93764dbaddd97c48f252a53ea4643faa3fd", "methods": "*"}
+		],
+		"trusts": [],
+		"extra": {"Author": "neo"}
+	}"#;
+
+	fn token() -> ContractManifest {
+		serde_json::from_str(TOKEN).unwrap()
+	}
+
+	#[test]
+	fn test_round_trip() {
+		let manifest = token();
+		assert_eq!(manifest.permissions[0].contract, ContractPermissionDescriptor::Wildcard);
+		assert!(manifest.permissions[0].methods.contains(&"onNEP17Payment".to_string()));
+		assert!(manifest.permissions[1].methods.is_wildcard());
+		assert_eq!(
+			manifest.permissions[1].contract.to_string(),
+			"0xfffdc93764dbaddd97c48f252a53ea4643faa3fd"
+		);
+
+		let json = serde_json::to_value(&manifest).unwrap();
+		assert_eq!(json["features"], serde_json::json!({}));
+		assert_eq!(json["trusts"], serde_json::json!([]));
+		assert_eq!(json["permissions"][1]["methods"], "*");
+		assert_eq!(json["abi"]["methods"][3]["parameters"][0]["type"], "Hash160");
+		assert_eq!(serde_json::from_value::<ContractManifest>(json).unwrap(), manifest);
+
+		let key = "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+		let trusts: WildcardContainer<ContractPermissionDescriptor> =
+			serde_json::from_str(&format!(r#"["{key}"]"#)).unwrap();
+		assert!(matches!(&trusts, WildcardContainer::List(t) if t[0].to_string() == key));
+		assert!(serde_json::from_str::<WildcardContainer<String>>(r#""all""#).is_err());
+	}
+
+	#[test]
+	fn test_validate() {
+		let manifest = token();
+		manifest.validate().unwrap();
+		assert!(manifest.standard_violations(Standard::Nep17).is_empty());
+		assert_eq!(manifest.standard_violations(Standard::Nep11).len(), 3);
+
+		let mut unnamed = manifest.clone();
+		unnamed.name = None;
+		assert_eq!(unnamed.validate(), Err(ManifestError::MissingName));
+
+		let mut duplicate = manifest.clone();
+		let symbol = duplicate.abi.as_ref().unwrap().methods[0].clone();
+		duplicate.abi.as_mut().unwrap().methods.push(symbol);
+		assert!(matches!(duplicate.validate(), Err(ManifestError::DuplicateMethod { .. })));
+
+		let mut unsafe_balance = manifest.clone();
+		unsafe_balance.abi.as_mut().unwrap().methods[3].safe = false;
+		assert!(matches!(
+			unsafe_balance.validate(),
+			Err(ManifestError::Safety { standard: Standard::Nep17, safe: true, .. })
+		));
+
+		let mut no_event = manifest;
+		no_event.abi.as_mut().unwrap().events.clear();
+		assert_eq!(
+			no_event.validate(),
+			Err(ManifestError::MissingEvent {
+				standard: Standard::Nep17,
+				event: "Transfer".to_string()
+			})
+		);
+		// undeclared standards are not checked
+		no_event.supported_standards.clear();
+		no_event.validate().unwrap();
+	}
 }
@@ -11,7 +11,7 @@ pub struct ContractNef {
 	#[serde_as(as = "Vec<ContractMethodToken>")]
 	pub tokens: Vec<ContractMethodToken>,
 	pub script: String,
-	pub checksum: i32,
+	pub checksum: u32,
 }
 
 impl ContractNef {
@@ -21,7 +21,7 @@ impl ContractNef {
 		source: Option<String>,
 		tokens: Vec<ContractMethodToken>,
 		script: String,
-		checksum: i32,
+		checksum: u32,
 	) -> Self {
 		Self { magic, compiler, source, tokens, script, checksum }
 	}
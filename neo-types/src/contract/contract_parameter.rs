@@ -1,8 +1,7 @@
 use crate::{
-	contract_parameter_type::ContractParameterType, nef_file::NefFile, nns_name::NNSName,
-	role::Role, serde_value::ValueExtension,
+	address::Address, contract_parameter_type::ContractParameterType, encoding, nef_file::NefFile,
+	nns_name::NNSName, role::Role, script_hash::ScriptHashBE, serde_value::ValueExtension,
 };
-use base64::encode;
 use elliptic_curve::sec1::ToEncodedPoint;
 
 use neo_codec::encode::NeoSerializable;
@@ -10,7 +9,6 @@ use neo_crypto::keys::Secp256r1PublicKey;
 use primitive_types::{H160, H256};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use sha3::Digest;
 use std::hash::{Hash, Hasher};
 use strum_macros::{Display, EnumString};
 
@@ -36,6 +34,12 @@ impl From<H160> for ContractParameter {
 	}
 }
 
+impl From<Address> for ContractParameter {
+	fn from(value: Address) -> Self {
+		Self::hash160(&value.script_hash())
+	}
+}
+
 impl From<u8> for ContractParameter {
 	fn from(value: u8) -> Self {
 		Self::integer(value as i64)
@@ -48,6 +52,12 @@ impl From<i32> for ContractParameter {
 	}
 }
 
+impl From<i64> for ContractParameter {
+	fn from(value: i64) -> Self {
+		Self::integer(value)
+	}
+}
+
 impl From<u32> for ContractParameter {
 	fn from(value: u32) -> Self {
 		Self::integer(value as i64)
@@ -217,6 +227,10 @@ impl ContractParameter {
 		Self { name: None, typ, value: None }
 	}
 
+	pub fn get_name(&self) -> Option<&str> {
+		self.name.as_deref()
+	}
+
 	pub fn get_type(&self) -> ContractParameterType {
 		self.typ.clone()
 	}
@@ -234,7 +248,7 @@ impl ContractParameter {
 	}
 
 	pub fn byte_array(value: Vec<u8>) -> Self {
-		let encoded = encode(value);
+		let encoded = encoding::base64_encode(value);
 		Self::with_value(ContractParameterType::ByteArray, ParameterValue::ByteArray(encoded))
 	}
 
@@ -244,7 +258,10 @@ impl ContractParameter {
 
 	// Other helper methods
 	pub fn hash160(value: &H160) -> Self {
-		Self::with_value(ContractParameterType::H160, ParameterValue::Hash160(value.to_string()))
+		Self::with_value(
+			ContractParameterType::H160,
+			ParameterValue::Hash160(ScriptHashBE(*value).to_string()),
+		)
 	}
 
 	pub fn hash256(value: &H256) -> Self {
@@ -254,7 +271,7 @@ impl ContractParameter {
 	pub fn public_key(value: &Secp256r1PublicKey) -> Self {
 		Self::with_value(
 			ContractParameterType::PublicKey,
-			ParameterValue::PublicKey(hex::encode(value.to_raw_bytes())),
+			ParameterValue::PublicKey(hex::encode(value.get_encoded(true))),
 		)
 	}
 
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 pub struct ContractState {
 	pub id: i32,
 	pub nef: ContractNef,
+	#[serde(rename = "updatecounter")]
 	pub update_counter: i32,
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
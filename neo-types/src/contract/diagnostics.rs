@@ -0,0 +1,129 @@
+//! The diagnostics of a test invocation, returned by `invokefunction` and `invokescript` when
+//! called with `diagnostics` enabled.
+use crate::{
+	deserialize_base64, deserialize_script_hash, serialize_base64, serialize_script_hash, Bytes,
+};
+use primitive_types::H160;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostics {
+	/// The root of the invocation tree, i.e. the invoked script
+	#[serde(rename = "invokedcontracts")]
+	pub invoked_contracts: InvokedContract,
+	/// The storage entries written by the invocation
+	#[serde(rename = "storagechanges", default)]
+	pub storage_changes: Vec<StorageChange>,
+}
+
+impl Diagnostics {
+	pub fn new(invoked_contracts: InvokedContract, storage_changes: Vec<StorageChange>) -> Self {
+		Self { invoked_contracts, storage_changes }
+	}
+
+	/// The hashes of the contracts called by the invocation, in call order and without
+	/// duplicates. The hash of the invoked script itself is not included.
+	pub fn called_contracts(&self) -> Vec<H160> {
+		let mut hashes = Vec::new();
+		for (_, call) in self.invoked_contracts.walk().skip(1) {
+			if !hashes.contains(&call.hash) {
+				hashes.push(call.hash);
+			}
+		}
+		hashes
+	}
+}
+
+/// A node of the invocation tree: a contract, and the contracts it called in order.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct InvokedContract {
+	#[serde(deserialize_with = "deserialize_script_hash")]
+	#[serde(serialize_with = "serialize_script_hash")]
+	pub hash: H160,
+	#[serde(rename = "call", default, skip_serializing_if = "Vec::is_empty")]
+	pub invoked_contracts: Vec<InvokedContract>,
+}
+
+impl InvokedContract {
+	/// Visits the tree depth-first, yielding every node with its depth, starting at 0 for `self`.
+	pub fn walk(&self) -> impl Iterator<Item = (usize, &InvokedContract)> {
+		let mut stack = vec![(0, self)];
+		std::iter::from_fn(move || {
+			let (depth, node) = stack.pop()?;
+			stack.extend(node.invoked_contracts.iter().rev().map(|child| (depth + 1, child)));
+			Some((depth, node))
+		})
+	}
+}
+
+/// How a storage entry was changed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StorageChangeState {
+	Added,
+	Changed,
+	Deleted,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StorageChange {
+	pub state: StorageChangeState,
+	/// The storage key, prefixed with the id of the contract owning the entry
+	#[serde(serialize_with = "serialize_base64", deserialize_with = "deserialize_base64")]
+	pub key: Bytes,
+	#[serde(serialize_with = "serialize_base64", deserialize_with = "deserialize_base64")]
+	pub value: Bytes,
+}
+
+impl StorageChange {
+	/// The id of the contract owning the entry
+	pub fn contract_id(&self) -> Option<i32> {
+		let id = self.key.get(..4)?;
+		Some(i32::from_le_bytes(id.try_into().expect("4 bytes")))
+	}
+
+	/// The key of the entry within the storage of the contract
+	pub fn storage_key(&self) -> &[u8] {
+		self.key.get(4..).unwrap_or_default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::str::FromStr;
+
+	#[test]
+	fn test_deserialize_diagnostics() {
+		let json = r#"{
+			"invokedcontracts": {
+				"hash": "0x8d13e4ae5ae4bf9f6da2e70c6ea4bb6c15c0bcd0",
+				"call": [{
+					"hash": "0xd2a4cff31913016155e38e474a2c06d08be276cf",
+					"call": [{ "hash": "0xfffdc93764dbaddd97c48f252a53ea4643faa3fd" }]
+				}, {
+					"hash": "0xd2a4cff31913016155e38e474a2c06d08be276cf"
+				}]
+			},
+			"storagechanges": [{
+				"state": "Changed",
+				"key": "+v///xQKr3tjjGH0U2Di3lwl8wYh9DhsHQ==",
+				"value": "QQEhBQDodkgX"
+			}]
+		}"#;
+		let diagnostics: Diagnostics = serde_json::from_str(json).unwrap();
+
+		let gas = H160::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap();
+		let management = H160::from_str("fffdc93764dbaddd97c48f252a53ea4643faa3fd").unwrap();
+		assert_eq!(diagnostics.called_contracts(), vec![gas, management]);
+		let depths: Vec<_> = diagnostics.invoked_contracts.walk().map(|(d, _)| d).collect();
+		assert_eq!(depths, vec![0, 1, 2, 1]);
+
+		let change = &diagnostics.storage_changes[0];
+		assert_eq!(change.state, StorageChangeState::Changed);
+		assert_eq!(change.contract_id(), Some(-6));
+		assert_eq!(change.storage_key()[0], 0x14);
+
+		let round_trip = serde_json::to_value(&diagnostics).unwrap();
+		assert_eq!(serde_json::from_value::<Diagnostics>(round_trip).unwrap(), diagnostics);
+	}
+}
@@ -1,3 +1,4 @@
+pub use crate::diagnostics::{Diagnostics, InvokedContract, StorageChange};
 use crate::{
 	contract_parameter::ContractParameter, deserialize_script_hash, serialize_script_hash,
 	stack_item::StackItem,
@@ -6,25 +7,36 @@ use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 use std::{
 	collections::HashMap,
+	fmt,
 	hash::{Hash, Hasher},
 };
+use thiserror::Error;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct InvocationResult {
 	pub script: String,
 	pub state: NeoVMStateType,
+	#[serde(rename = "gasconsumed")]
 	pub gas_consumed: String,
+	#[serde(default)]
 	pub exception: Option<String>,
+	#[serde(default)]
 	pub notifications: Option<Vec<Notification>>,
+	#[serde(default)]
 	pub diagnostics: Option<Diagnostics>,
 	pub stack: Vec<StackItem>,
+	#[serde(default)]
 	pub tx: Option<String>,
+	#[serde(rename = "pendingsignature", default)]
 	pub pending_signature: Option<PendingSignature>,
+	#[serde(rename = "session", default)]
 	pub session_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Hash)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum NeoVMStateType {
+	None,
 	Halt,
 	Fault,
 	Break,
@@ -69,6 +81,106 @@ impl InvocationResult {
 	pub fn get_first_stack_item(&self) -> Result<&StackItem, &str> {
 		self.stack.first().ok_or("Stack is empty")
 	}
+
+	/// Returns the result if the execution halted, or the [`VmFault`] if it faulted, e.g.
+	/// because of an `ABORT` or a failed `ASSERT`.
+	pub fn into_result(self) -> Result<Self, VmFault> {
+		match self.state {
+			NeoVMStateType::Fault => Err(VmFault {
+				exception: self.exception,
+				notifications: self.notifications.unwrap_or_default(),
+				gas_consumed: self.gas_consumed,
+			}),
+			_ => Ok(self),
+		}
+	}
+}
+
+/// A faulted execution of the NeoVM, see [`InvocationResult::into_result`].
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+#[error("VM fault: {}", .exception.as_deref().unwrap_or("no exception"))]
+pub struct VmFault {
+	/// The exception message reported by the node
+	pub exception: Option<String>,
+	/// The notifications sent before the fault
+	pub notifications: Vec<Notification>,
+	/// The GAS consumed until the fault, in GAS fractions
+	pub gas_consumed: String,
+}
+
+impl VmFault {
+	/// The cause of the fault, parsed from the exception message
+	pub fn kind(&self) -> FaultKind {
+		self.exception.as_deref().map_or(FaultKind::Unknown, FaultKind::parse)
+	}
+}
+
+/// The cause of a [`VmFault`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum FaultKind {
+	/// `ABORT` or `ABORTMSG` was executed, with the message of `ABORTMSG`
+	Abort(Option<String>),
+	/// `ASSERT` or `ASSERTMSG` was executed with a false condition, with the message of
+	/// `ASSERTMSG`
+	AssertFailed(Option<String>),
+	/// The GAS limit of the execution was reached
+	InsufficientGas,
+	/// The called contract does not exist
+	ContractNotFound,
+	/// The called contract has no method with the given name and number of parameters
+	MethodNotFound(String),
+	/// A contract threw an exception that was not caught, with its message
+	UnhandledException(String),
+	/// Any other fault, see the exception message
+	Other,
+	/// The node did not report an exception message
+	Unknown,
+}
+
+impl FaultKind {
+	/// Parses the exception message reported by the node.
+	pub fn parse(exception: &str) -> Self {
+		let message = |rest: &str| {
+			let rest = rest.trim_start_matches(|c| c == ':' || c == ' ');
+			(!rest.is_empty()).then(|| rest.to_string())
+		};
+		if let Some(rest) = exception.strip_prefix("ABORTMSG is executed. Reason") {
+			FaultKind::Abort(message(rest))
+		} else if exception.starts_with("ABORT is executed") {
+			FaultKind::Abort(None)
+		} else if let Some(rest) =
+			exception.strip_prefix("ASSERTMSG is executed with false result.")
+		{
+			FaultKind::AssertFailed(message(rest.trim_start_matches(" Reason")))
+		} else if exception.starts_with("ASSERT is executed with false result") {
+			FaultKind::AssertFailed(None)
+		} else if exception.starts_with("Insufficient GAS") {
+			FaultKind::InsufficientGas
+		} else if exception.starts_with("Called Contract Does Not Exist") {
+			FaultKind::ContractNotFound
+		} else if let Some(rest) = exception.strip_prefix("Method \"") {
+			FaultKind::MethodNotFound(rest.split('"').next().unwrap_or_default().to_string())
+		} else if let Some(rest) = exception.strip_prefix("An unhandled exception was thrown.") {
+			FaultKind::UnhandledException(rest.trim().to_string())
+		} else {
+			FaultKind::Other
+		}
+	}
+}
+
+impl fmt::Display for FaultKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			FaultKind::Abort(_) => f.write_str("abort"),
+			FaultKind::AssertFailed(_) => f.write_str("assertion failed"),
+			FaultKind::InsufficientGas => f.write_str("insufficient GAS"),
+			FaultKind::ContractNotFound => f.write_str("contract not found"),
+			FaultKind::MethodNotFound(method) => write!(f, "method {method} not found"),
+			FaultKind::UnhandledException(_) => f.write_str("unhandled exception"),
+			FaultKind::Other => f.write_str("fault"),
+			FaultKind::Unknown => f.write_str("unknown fault"),
+		}
+	}
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -103,49 +215,70 @@ impl Hash for Item {
 	}
 }
 
-// Other structs like Diagnostics, Notification
-
-// Diagnostics
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct Diagnostics {
-	pub invoked_contracts: InvokedContract,
-	pub storage_changes: Vec<StorageChange>,
-}
-
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
-pub struct InvokedContract {
-	#[serde(deserialize_with = "deserialize_script_hash")]
-	#[serde(serialize_with = "serialize_script_hash")]
-	pub hash: H160,
-	pub invoked_contracts: Option<Vec<InvokedContract>>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct StorageChange {
-	pub state: String,
-	pub key: String,
-	pub value: String,
-}
-
 // Notification
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Notification {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	pub contract: H160,
+	#[serde(rename = "eventname")]
 	pub event_name: String,
-	pub state: NotificationState,
-	pub payload: Vec<u8>,
+	/// The arguments of the event, usually an array
+	pub state: StackItem,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Hash)]
-pub enum NotificationState {
-	Failure,
-	Success,
-	Halt,
-	Fault,
-	StepInto,
-	StepOut,
-	StepOver,
-	Break,
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn result(state: NeoVMStateType, exception: Option<&str>) -> InvocationResult {
+		InvocationResult {
+			script: String::new(),
+			state,
+			gas_consumed: "1007390".to_string(),
+			exception: exception.map(str::to_string),
+			notifications: None,
+			diagnostics: None,
+			stack: vec![],
+			tx: None,
+			pending_signature: None,
+			session_id: None,
+		}
+	}
+
+	#[test]
+	fn test_into_result() {
+		assert!(result(NeoVMStateType::Halt, None).into_result().is_ok());
+
+		let fault = result(NeoVMStateType::Fault, Some("ASSERT is executed with false result."))
+			.into_result()
+			.unwrap_err();
+		assert_eq!(fault.gas_consumed, "1007390");
+		assert_eq!(fault.kind(), FaultKind::AssertFailed(None));
+		assert_eq!(
+			result(NeoVMStateType::Fault, None).into_result().unwrap_err().kind(),
+			FaultKind::Unknown
+		);
+	}
+
+	#[test]
+	fn test_parse_fault_kind() {
+		assert_eq!(
+			FaultKind::parse("ABORTMSG is executed. Reason: not owner"),
+			FaultKind::Abort(Some("not owner".to_string()))
+		);
+		assert_eq!(
+			FaultKind::parse(
+				"Method \"transfer\" with 3 parameter(s) doesn't exist in the contract \
+				 0xd2a4cff31913016155e38e474a2c06d08be276cf."
+			),
+			FaultKind::MethodNotFound("transfer".to_string())
+		);
+		assert_eq!(
+			FaultKind::parse("An unhandled exception was thrown. No authorization."),
+			FaultKind::UnhandledException("No authorization.".to_string())
+		);
+		assert_eq!(FaultKind::parse("Insufficient GAS."), FaultKind::InsufficientGas);
+		assert_eq!(FaultKind::parse("Value out of range"), FaultKind::Other);
+	}
 }
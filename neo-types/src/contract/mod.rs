@@ -8,10 +8,12 @@ pub mod contract_parameter;
 pub mod contract_parameter_type;
 pub mod contract_state;
 pub mod contract_storage_entry;
+pub mod diagnostics;
 pub mod invocation_result;
 pub mod native_contract_state;
 pub mod nef_file;
 pub mod nep17contract;
+pub mod standard;
 
 pub fn hash_message(message: &[u8]) -> H256 {
 	let mut hasher = Sha256::new();
@@ -1,10 +1,16 @@
-use crate::{contract_manifest::ContractManifest, contract_nef::ContractNef};
+use crate::{
+	contract_manifest::ContractManifest, contract_nef::ContractNef, deserialize_script_hash,
+	serialize_script_hash,
+};
+use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NativeContractState {
 	pub id: i32,
 	pub nef: ContractNef,
+	/// The blocks the contract was updated at, only reported by nodes before 3.6
+	#[serde(default, rename = "updatehistory")]
 	pub update_history: Vec<i32>,
 	#[serde(flatten)]
 	pub base: ExpressContractState,
@@ -18,12 +24,101 @@ impl NativeContractState {
 		manifest: ContractManifest,
 		update_history: Vec<i32>,
 	) -> Self {
+		let hash = H160::from(hash);
 		Self { id, nef, update_history, base: ExpressContractState { hash, manifest } }
 	}
+
+	/// The hash of the contract
+	pub fn hash(&self) -> H160 {
+		self.base.hash
+	}
+
+	/// The manifest of the contract, whose name is the name of the native contract
+	pub fn manifest(&self) -> &ContractManifest {
+		&self.base.manifest
+	}
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-struct ExpressContractState {
-	hash: [u8; 20],
+pub struct ExpressContractState {
+	#[serde(deserialize_with = "deserialize_script_hash")]
+	#[serde(serialize_with = "serialize_script_hash")]
+	hash: H160,
 	manifest: ContractManifest,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::contract_parameter_type::ContractParameterType;
+	use std::str::FromStr;
+
+	/// The `GasToken` entry of `getnativecontracts` on a 3.5 node, with a shortened ABI
+	const GAS_TOKEN: &str = r#"{
+		"id": -6,
+		"hash": "0xd2a4cff31913016155e38e474a2c06d08be276cf",
+		"nef": {
+			"magic": 860243278,
+			"compiler": "neo-core-v3.0",
+			"source": "",
+			"tokens": [],
+			"script": "EEEa93tnQBBBGvd7Z0AQQRr3e2dAEEEa93tnQBBBGvd7Z0A=",
+			"checksum": 2663858513
+		},
+		"manifest": {
+			"name": "GasToken",
+			"groups": [],
+			"features": {},
+			"supportedstandards": ["NEP-17"],
+			"abi": {
+				"methods": [{
+					"name": "balanceOf",
+					"parameters": [{"name": "account", "type": "Hash160"}],
+					"returntype": "Integer",
+					"offset": 0,
+					"safe": true
+				}],
+				"events": [{
+					"name": "Transfer",
+					"parameters": [
+						{"name": "from", "type": "Hash160"},
+						{"name": "to", "type": "Hash160"},
+						{"name": "amount", "type": "Integer"}
+					]
+				}]
+			},
+			"permissions": [{"contract": "*", "methods": "*"}],
+			"trusts": [],
+			"extra": null
+		},
+		"updatehistory": [0]
+	}"#;
+
+	#[test]
+	fn test_decode_native_contract() {
+		let state: NativeContractState = serde_json::from_str(GAS_TOKEN).unwrap();
+		assert_eq!(
+			state.hash(),
+			H160::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap()
+		);
+		assert_eq!(state.nef.checksum, 2663858513);
+		assert_eq!(state.update_history, vec![0]);
+
+		let manifest = state.manifest();
+		assert_eq!(manifest.name.as_deref(), Some("GasToken"));
+		assert_eq!(manifest.supported_standards, vec!["NEP-17"]);
+		assert!(manifest.permissions[0].methods.is_wildcard());
+		let method = &manifest.abi.as_ref().unwrap().methods[0];
+		assert_eq!(method.parameters[0].get_type(), ContractParameterType::H160);
+		assert_eq!(method.return_type, ContractParameterType::Integer);
+
+		// nodes from 3.6 report an update counter instead of the history
+		let mut json: serde_json::Value = serde_json::from_str(GAS_TOKEN).unwrap();
+		json.as_object_mut().unwrap().remove("updatehistory");
+		json["updatecounter"] = 0.into();
+		assert!(serde_json::from_value::<NativeContractState>(json)
+			.unwrap()
+			.update_history
+			.is_empty());
+	}
+}
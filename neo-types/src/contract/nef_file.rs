@@ -1,12 +1,11 @@
 use crate::{
 	contract_parameter::ContractParameter, error::TypeError, stack_item::StackItem, Bytes,
 };
-use neo_codec::{encode::NeoSerializable, CodecError, Decoder, Encoder};
+use neo_codec::{encode::NeoSerializable, Decoder, Encoder};
 use neo_crypto::hash::HashableForVec;
 use primitive_types::H160;
 use serde::{Deserializer, Serializer};
 use std::hash::Hasher;
-use tokio::io::AsyncReadExt;
 
 /*
 ┌───────────────────────────────────────────────────────────────────────┐
@@ -57,6 +56,11 @@ impl NefFile {
 		i32::from_be_bytes(bytes.try_into().unwrap())
 	}
 
+	/// The checksum of the file, from which the hash of the deployed contract is derived.
+	pub fn checksum(&self) -> u32 {
+		Self::get_checksum_as_integer(&self.checksum) as u32
+	}
+
 	fn compute_checksum(file: &NefFile) -> Bytes {
 		Self::compute_checksum_from_bytes(file.to_array())
 	}
@@ -64,7 +68,7 @@ impl NefFile {
 	fn compute_checksum_from_bytes(bytes: Bytes) -> Bytes {
 		let mut file_bytes = bytes.clone();
 		file_bytes.truncate(bytes.len() - Self::CHECKSUM_SIZE);
-		file_bytes.hash256()[..Self::CHECKSUM_SIZE].try_into().unwrap()
+		file_bytes.hash256().hash256()[..Self::CHECKSUM_SIZE].to_vec()
 	}
 
 	fn read_from_file(file: &str) -> Result<Self, TypeError> {
@@ -74,15 +78,13 @@ impl NefFile {
 		}
 
 		let mut reader = Decoder::new(&file_bytes);
-		let nef = reader.read_serializable().unwrap();
-		Ok(nef)
+		reader.read_serializable()
 	}
 
 	fn read_from_stack_item(item: StackItem) -> Result<Self, TypeError> {
 		if let StackItem::ByteString { value: bytes } = item {
-			let mut reader = Decoder::new(&bytes.as_bytes());
-			let nef = reader.read_serializable().unwrap();
-			Ok(nef)
+			let mut reader = Decoder::new(bytes.as_bytes());
+			reader.read_serializable()
 		} else {
 			Err(TypeError::UnexpectedReturnType(
 				serde_json::to_string(&item).unwrap() + StackItem::BYTE_STRING_VALUE,
@@ -111,34 +113,34 @@ impl NeoSerializable for NefFile {
 			.expect("Failed to serialize compiler");
 		writer.write_var_string(&self.source_url);
 		writer.write_u8(0);
-		writer.write_serializable_variable_list(&self.method_tokens);
+		writer.write_serializable_list(&self.method_tokens);
 		writer.write_u16(0);
 		writer.write_var_bytes(&self.script);
 		writer.write_bytes(&self.checksum);
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let magic = reader.read_u32();
+		let magic = reader.read_u32()?;
 		if magic != Self::MAGIC {
 			return Err(TypeError::InvalidEncoding("Invalid magic".to_string()))
 		}
 
-		let compiler_bytes = reader.read_bytes(Self::COMPILER_SIZE)?;
+		let compiler_bytes = reader.read_slice(Self::COMPILER_SIZE)?;
 		let compiler = String::from_utf8(compiler_bytes.to_vec())
-			.map_err(|_| CodecError::InvalidEncoding("Invalid compiler".to_string()))?;
+			.map_err(|_| reader.invalid_data("Invalid compiler"))?;
 
 		let source_url = reader.read_var_string()?;
 		if source_url.len() > Self::MAX_SOURCE_URL_SIZE {
 			return Err(TypeError::InvalidEncoding("Invalid source url".to_string()))
 		}
 
-		if reader.read_u8() != 0 {
+		if reader.read_u8()? != 0 {
 			return Err(TypeError::InvalidEncoding("Invalid reserve bytes".to_string()))
 		}
 
 		let method_tokens = reader.read_serializable_list()?;
 
-		if reader.read_u16() != 0 {
+		if reader.read_u16()? != 0 {
 			return Err(TypeError::InvalidEncoding("Invalid reserve bytes".to_string()))
 		}
 
@@ -147,13 +149,20 @@ impl NeoSerializable for NefFile {
 			return Err(TypeError::InvalidEncoding("Invalid script".to_string()))
 		}
 
-		let file =
-			Self { compiler: Some(compiler), source_url, method_tokens, script, checksum: vec![] };
+		// the checksum is computed over the file without its last four bytes
+		let mut file = Self {
+			compiler: Some(compiler.trim_end_matches('\0').to_string()),
+			source_url,
+			method_tokens,
+			script,
+			checksum: vec![0; Self::CHECKSUM_SIZE],
+		};
 
 		let checksum = reader.read_bytes(Self::CHECKSUM_SIZE)?;
 		if checksum != Self::compute_checksum(&file) {
 			return Err(TypeError::InvalidEncoding("Invalid checksum".to_string()))
 		}
+		file.checksum = checksum;
 
 		Ok(file)
 	}
@@ -207,9 +216,9 @@ impl NeoSerializable for MethodToken {
 	{
 		let hash = reader.read_serializable()?;
 		let method = reader.read_var_string()?;
-		let params_count = reader.read_u16();
-		let has_return_value = reader.read_bool();
-		let call_flags = reader.read_u8();
+		let params_count = reader.read_u16()?;
+		let has_return_value = reader.read_bool()?;
+		let call_flags = reader.read_u8()?;
 
 		Ok(Self { hash, method, params_count, has_return_value, call_flags })
 	}
@@ -220,3 +229,39 @@ impl NeoSerializable for MethodToken {
 		writer.to_bytes()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A NEF file in the layout written by nccs, with a `GAS.balanceOf` method token and a script
+	/// calling it through `CALLT`
+	const NEF: &str = concat!(
+		"4e4546334e656f2e436f6d70696c65722e43536861727020332e362e3000000000000000000000000000",
+		"0000000000000000000000000000000000000000000000000000000001cf76e28bd0062c4a478ee35561",
+		"011319f3cfa4d20962616c616e63654f660100010f00001a0c140102030405060708090a0b0c0d0e0f10",
+		"11121314370000401ec390c0",
+	);
+
+	#[test]
+	fn test_decode() {
+		let bytes = hex::decode(NEF).unwrap();
+		let file = NefFile::decode(&mut Decoder::new(&bytes)).unwrap();
+		assert_eq!(file.compiler.as_deref(), Some("Neo.Compiler.CSharp 3.6.0"));
+		assert_eq!(file.source_url, "");
+		assert_eq!(file.method_tokens.len(), 1);
+		assert_eq!(file.method_tokens[0].method, "balanceOf");
+		assert_eq!(file.method_tokens[0].params_count, 1);
+		assert_eq!(file.script.len(), 26);
+		assert_eq!(file.checksum(), 0xc090c31e);
+		assert_eq!(file.to_array(), bytes);
+	}
+
+	#[test]
+	fn test_decode_invalid_checksum() {
+		let mut bytes = hex::decode(NEF).unwrap();
+		let last = bytes.len() - 1;
+		bytes[last] ^= 1;
+		assert!(NefFile::decode(&mut Decoder::new(&bytes)).is_err());
+	}
+}
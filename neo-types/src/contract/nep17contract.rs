@@ -1,6 +1,10 @@
-use crate::{deserialize_script_hash, serialize_script_hash};
+use crate::{
+	deserialize_script_hash, error::TypeError, natives, serialize_script_hash,
+	token_amount::TokenAmount,
+};
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone)]
 pub struct Nep17Contract {
 	#[serde(serialize_with = "serialize_script_hash")]
@@ -14,4 +18,46 @@ impl Nep17Contract {
 	pub fn new(script_hash: H160, symbol: String, decimals: u8) -> Self {
 		Self { script_hash, symbol, decimals }
 	}
+
+	/// The native `NeoToken` contract
+	pub fn neo() -> Self {
+		Self::new(natives::NEO_TOKEN.hash, "NEO".to_string(), 0)
+	}
+
+	/// The native `GasToken` contract
+	pub fn gas() -> Self {
+		Self::new(natives::GAS_TOKEN.hash, "GAS".to_string(), 8)
+	}
+
+	/// Creates an amount of the token from its value in the smallest unit.
+	pub fn amount(&self, value: i128) -> TokenAmount {
+		TokenAmount::new(value, self.decimals, self.script_hash)
+	}
+
+	/// Parses a human-readable amount of the token like `1.5` or `1.5 GAS`. If the amount is
+	/// followed by a symbol, it has to be the symbol of the token.
+	pub fn parse_amount(&self, amount: &str) -> Result<TokenAmount, TypeError> {
+		let number = match amount.trim().split_once(' ') {
+			Some((number, symbol)) if symbol.trim() == self.symbol => number,
+			Some((_, symbol)) =>
+				return Err(TypeError::InvalidArgError(format!(
+					"Expected an amount of {}, got {}",
+					self.symbol,
+					symbol.trim()
+				))),
+			None => amount,
+		};
+		TokenAmount::parse(number, self.decimals, self.script_hash)
+	}
+
+	/// Formats an amount of the token followed by its symbol, e.g. `1.5 GAS`.
+	pub fn format_amount(&self, amount: &TokenAmount) -> Result<String, TypeError> {
+		if amount.asset() != self.script_hash || amount.decimals() != self.decimals {
+			return Err(TypeError::IllegalArgument(format!(
+				"The amount is not an amount of {}",
+				self.symbol
+			)))
+		}
+		Ok(format!("{amount} {}", self.symbol))
+	}
 }
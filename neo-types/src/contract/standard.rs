@@ -0,0 +1,131 @@
+//! The token standards whose ABI a manifest can be checked against, see
+//! [`ContractManifest::standard_violations`](crate::contract_manifest::ContractManifest::standard_violations).
+
+use crate::contract_parameter_type::ContractParameterType::{self, *};
+use std::{fmt, str::FromStr};
+
+/// A NEP standard, named in the `supportedstandards` of a manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Standard {
+	/// NEP-17, fungible tokens
+	Nep17,
+	/// NEP-11, non-fungible tokens
+	Nep11,
+	/// NEP-24, royalties of non-fungible tokens. Only covers `royaltyInfo`, the contract also has
+	/// to implement NEP-11.
+	Nep24,
+}
+
+/// A method a standard requires, identified by its name and parameter types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MethodSpec {
+	pub name: &'static str,
+	pub parameters: &'static [ContractParameterType],
+	pub return_type: ContractParameterType,
+	pub safe: bool,
+}
+
+/// An event a standard requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventSpec {
+	pub name: &'static str,
+	pub parameters: &'static [ContractParameterType],
+}
+
+const fn method(
+	name: &'static str,
+	parameters: &'static [ContractParameterType],
+	return_type: ContractParameterType,
+	safe: bool,
+) -> MethodSpec {
+	MethodSpec { name, parameters, return_type, safe }
+}
+
+const NEP17_METHODS: &[&[MethodSpec]] = &[
+	&[method("symbol", &[], String, true)],
+	&[method("decimals", &[], Integer, true)],
+	&[method("totalSupply", &[], Integer, true)],
+	&[method("balanceOf", &[H160], Integer, true)],
+	&[method("transfer", &[H160, H160, Integer, Any], Boolean, false)],
+];
+
+const NEP11_METHODS: &[&[MethodSpec]] = &[
+	&[method("symbol", &[], String, true)],
+	&[method("decimals", &[], Integer, true)],
+	&[method("totalSupply", &[], Integer, true)],
+	&[method("balanceOf", &[H160], Integer, true)],
+	&[method("tokensOf", &[H160], InteropInterface, true)],
+	// non-divisible or divisible tokens
+	&[
+		method("ownerOf", &[ByteArray], H160, true),
+		method("ownerOf", &[ByteArray], InteropInterface, true),
+	],
+	&[
+		method("transfer", &[H160, ByteArray, Any], Boolean, false),
+		method("transfer", &[H160, H160, Integer, ByteArray, Any], Boolean, false),
+	],
+];
+
+const NEP24_METHODS: &[&[MethodSpec]] =
+	&[&[method("royaltyInfo", &[ByteArray, H160, Integer], Array, true)]];
+
+impl Standard {
+	/// All standards that can be checked
+	pub const ALL: &'static [Standard] = &[Standard::Nep17, Standard::Nep11, Standard::Nep24];
+
+	/// The name of the standard in manifests, e.g. `NEP-17`
+	pub const fn name(&self) -> &'static str {
+		match self {
+			Standard::Nep17 => "NEP-17",
+			Standard::Nep11 => "NEP-11",
+			Standard::Nep24 => "NEP-24",
+		}
+	}
+
+	/// The required methods. Each entry lists the alternatives of which one has to be declared,
+	/// e.g. the `transfer` of divisible and non-divisible NEP-11 tokens.
+	pub fn methods(&self) -> &'static [&'static [MethodSpec]] {
+		match self {
+			Standard::Nep17 => NEP17_METHODS,
+			Standard::Nep11 => NEP11_METHODS,
+			Standard::Nep24 => NEP24_METHODS,
+		}
+	}
+
+	/// The required events
+	pub fn events(&self) -> &'static [EventSpec] {
+		match self {
+			Standard::Nep17 =>
+				&[EventSpec { name: "Transfer", parameters: &[H160, H160, Integer] }],
+			Standard::Nep11 =>
+				&[EventSpec { name: "Transfer", parameters: &[H160, H160, Integer, ByteArray] }],
+			// `RoyaltiesTransferred` is emitted by marketplaces, not by the token
+			Standard::Nep24 => &[],
+		}
+	}
+}
+
+impl fmt::Display for Standard {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for Standard {
+	type Err = strum::ParseError;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.find(|standard| standard.name().eq_ignore_ascii_case(name))
+			.copied()
+			.ok_or(strum::ParseError::VariantNotFound)
+	}
+}
+
+impl fmt::Display for MethodSpec {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let parameters: Vec<_> = self.parameters.iter().map(ToString::to_string).collect();
+		write!(f, "{}({}) -> {}", self.name, parameters.join(", "), self.return_type)
+	}
+}
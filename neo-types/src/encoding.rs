@@ -0,0 +1,174 @@
+//! # Encoding
+//!
+//! The text encodings used by Neo: Base58Check for addresses and WIF keys, Base64 for scripts and
+//! stack items in RPC responses, and hex for hashes and keys.
+//!
+//! All decoders fail with an [`EncodingError`] naming the encoding, instead of each crate
+//! reporting failures of its own.
+
+use base64::{
+	alphabet,
+	engine::{general_purpose, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+	Engine,
+};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// The URL-safe Base64 engine, encoding without padding and decoding with or without it
+const URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+	&alphabet::URL_SAFE,
+	GeneralPurposeConfig::new()
+		.with_encode_padding(false)
+		.with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EncodingError {
+	#[error("Invalid Base58: {0}")]
+	InvalidBase58(String),
+	#[error("Invalid Base58Check checksum")]
+	InvalidChecksum,
+	#[error("Invalid Base64: {0}")]
+	InvalidBase64(String),
+	#[error("Invalid hex: {0}")]
+	InvalidHex(String),
+}
+
+/// The first 4 bytes of the double SHA-256 of `data`, appended by Base58Check.
+pub fn checksum(data: &[u8]) -> [u8; 4] {
+	let hash = Sha256::digest(Sha256::digest(data));
+	[hash[0], hash[1], hash[2], hash[3]]
+}
+
+pub fn base58_encode(data: &[u8]) -> String {
+	bs58::encode(data).into_string()
+}
+
+pub fn base58_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+	bs58::decode(input)
+		.into_vec()
+		.map_err(|e| EncodingError::InvalidBase58(e.to_string()))
+}
+
+/// Encodes `data` followed by its [`checksum`] in Base58.
+pub fn base58check_encode(data: &[u8]) -> String {
+	let mut bytes = Vec::with_capacity(data.len() + 4);
+	bytes.extend_from_slice(data);
+	bytes.extend_from_slice(&checksum(data));
+	base58_encode(&bytes)
+}
+
+/// Decodes Base58Check and returns the data without the checksum, failing if the checksum does
+/// not match.
+pub fn base58check_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+	let mut bytes = base58_decode(input)?;
+	if bytes.len() < 4 {
+		return Err(EncodingError::InvalidChecksum)
+	}
+	let data_len = bytes.len() - 4;
+	if checksum(&bytes[..data_len]) != bytes[data_len..] {
+		return Err(EncodingError::InvalidChecksum)
+	}
+	bytes.truncate(data_len);
+	Ok(bytes)
+}
+
+/// Encodes `data` in standard Base64 with padding, as used by the RPC API.
+pub fn base64_encode(data: impl AsRef<[u8]>) -> String {
+	general_purpose::STANDARD.encode(data)
+}
+
+pub fn base64_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+	general_purpose::STANDARD
+		.decode(input)
+		.map_err(|e| EncodingError::InvalidBase64(e.to_string()))
+}
+
+/// Encodes `data` in URL-safe Base64 without padding, e.g. for NeoFS object IDs in URLs.
+pub fn base64_url_encode(data: impl AsRef<[u8]>) -> String {
+	URL_SAFE.encode(data)
+}
+
+/// Decodes URL-safe Base64 with or without padding.
+pub fn base64_url_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+	URL_SAFE.decode(input).map_err(|e| EncodingError::InvalidBase64(e.to_string()))
+}
+
+/// Encodes `data` in lowercase hex without prefix.
+pub fn hex_encode(data: impl AsRef<[u8]>) -> String {
+	hex::encode(data)
+}
+
+/// Encodes `data` in lowercase hex with the `0x` prefix.
+pub fn hex_encode_prefixed(data: impl AsRef<[u8]>) -> String {
+	format!("0x{}", hex::encode(data))
+}
+
+/// Decodes hex with or without the `0x` prefix.
+pub fn hex_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+	let input = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")).unwrap_or(input);
+	hex::decode(input).map_err(|e| EncodingError::InvalidHex(e.to_string()))
+}
+
+/// Decodes a serialized payload like a raw transaction or block, in Base64 as returned by the
+/// node or in hex with or without the `0x` prefix. Input of an even number of hex digits is
+/// taken as hex.
+pub fn raw_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+	let input = input.trim();
+	let hex = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")).unwrap_or(input);
+	if hex.len() % 2 == 0 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+		return hex_decode(hex)
+	}
+	base64_decode(input)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_base58check() {
+		let data = [
+			6, 161, 159, 136, 34, 110, 33, 238, 14, 79, 14, 218, 133, 13, 109, 40, 194, 236, 153,
+			44, 61, 157, 254,
+		];
+		let encoded = "tz1Y3qqTg9HdrzZGbEjiCPmwuZ7fWVxpPtRw";
+		assert_eq!(base58check_encode(&data), encoded);
+		assert_eq!(base58check_decode(encoded).unwrap(), data);
+		assert_eq!(
+			base58check_decode("tz1Y3qqTg9HdrzZGbEjiCPmwuZ7fWVxpPtrW"),
+			Err(EncodingError::InvalidChecksum)
+		);
+		assert!(matches!(base58check_decode("0oO1lL"), Err(EncodingError::InvalidBase58(_))));
+	}
+
+	#[test]
+	fn test_base64() {
+		let data = [0xfb, 0xff, 0x01];
+		assert_eq!(base64_encode(data), "+/8B");
+		assert_eq!(base64_decode("+/8B").unwrap(), data);
+		assert_eq!(base64_url_encode(data), "-_8B");
+		assert_eq!(base64_url_decode("-_8B").unwrap(), data);
+		assert_eq!(base64_url_encode([1]), "AQ");
+		assert_eq!(base64_url_decode("AQ==").unwrap(), [1]);
+		assert!(base64_decode("-_8B").is_err());
+	}
+
+	#[test]
+	fn test_hex() {
+		assert_eq!(hex_encode([0xab, 0x01]), "ab01");
+		assert_eq!(hex_encode_prefixed([0xab, 0x01]), "0xab01");
+		assert_eq!(hex_decode("0xAB01").unwrap(), [0xab, 0x01]);
+		assert_eq!(hex_decode("0Xab01").unwrap(), [0xab, 0x01]);
+		assert_eq!(hex_decode("ab01").unwrap(), [0xab, 0x01]);
+		assert!(hex_decode("0xabc").is_err());
+	}
+
+	#[test]
+	fn test_raw() {
+		assert_eq!(raw_decode("0xab01").unwrap(), [0xab, 0x01]);
+		assert_eq!(raw_decode("ab01\n").unwrap(), [0xab, 0x01]);
+		assert_eq!(raw_decode("+/8B").unwrap(), [0xfb, 0xff, 0x01]);
+		assert!(raw_decode("0xabc").is_err());
+	}
+}
@@ -52,4 +52,6 @@ pub enum TypeError {
 	WifError(String),
 	#[error(transparent)]
 	CodecError(#[from] neo_codec::CodecError),
+	#[error(transparent)]
+	EncodingError(#[from] crate::encoding::EncodingError),
 }
@@ -0,0 +1,160 @@
+use crate::{error::TypeError, nep17contract::Nep17Contract, token_amount::TokenAmount};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// An amount of GAS in datoms, its smallest unit of 10^-8 GAS.
+///
+/// Fees and GAS balances are integers of datoms on chain and in the RPC responses, where they
+/// are sent as strings like `"997750"` for 0.0099775 GAS. `Gas` (de)serializes in that format and
+/// converts from and to the decimal form shown to users explicitly, so the 10^8 scaling is never
+/// implied by a bare integer.
+///
+/// # Example
+///
+/// ```
+/// use neo_types::gas::Gas;
+///
+/// let fee = Gas::from_decimal_str("0.0099775").unwrap();
+/// assert_eq!(fee.datoms(), 997750);
+/// assert_eq!(fee.saturating_mul(2).to_decimal_string(), "0.019955");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gas(i64);
+
+impl Gas {
+	/// The decimals of GAS
+	pub const DECIMALS: u8 = 8;
+	pub const ZERO: Gas = Gas(0);
+
+	pub const fn from_datoms(datoms: i64) -> Self {
+		Self(datoms)
+	}
+
+	/// The amount in datoms
+	pub const fn datoms(&self) -> i64 {
+		self.0
+	}
+
+	/// Parses a decimal amount of GAS like `1.5`. Fails if it has more than 8 decimal places.
+	pub fn from_decimal_str(amount: &str) -> Result<Self, TypeError> {
+		Nep17Contract::gas().parse_amount(amount)?.value_i64().map(Self)
+	}
+
+	/// Formats the amount as decimal number of GAS without trailing zeros, e.g. `1.5`.
+	pub fn to_decimal_string(&self) -> String {
+		self.to_token_amount().to_decimal_string()
+	}
+
+	/// The amount as [`TokenAmount`] of the `GasToken` contract
+	pub fn to_token_amount(&self) -> TokenAmount {
+		Nep17Contract::gas().amount(self.0.into())
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.0 == 0
+	}
+
+	pub fn checked_add(self, other: Gas) -> Option<Gas> {
+		self.0.checked_add(other.0).map(Self)
+	}
+
+	pub fn checked_sub(self, other: Gas) -> Option<Gas> {
+		self.0.checked_sub(other.0).map(Self)
+	}
+
+	pub fn saturating_add(self, other: Gas) -> Gas {
+		Self(self.0.saturating_add(other.0))
+	}
+
+	pub fn saturating_sub(self, other: Gas) -> Gas {
+		Self(self.0.saturating_sub(other.0))
+	}
+
+	pub fn saturating_mul(self, factor: i64) -> Gas {
+		Self(self.0.saturating_mul(factor))
+	}
+}
+
+impl fmt::Display for Gas {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} GAS", self.to_decimal_string())
+	}
+}
+
+/// Parses a decimal amount of GAS, optionally followed by the symbol, e.g. `1.5 GAS`.
+impl FromStr for Gas {
+	type Err = TypeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::from_decimal_str(s)
+	}
+}
+
+impl From<Gas> for TokenAmount {
+	fn from(gas: Gas) -> Self {
+		gas.to_token_amount()
+	}
+}
+
+impl TryFrom<TokenAmount> for Gas {
+	type Error = TypeError;
+
+	fn try_from(amount: TokenAmount) -> Result<Self, Self::Error> {
+		Nep17Contract::gas().format_amount(&amount)?;
+		amount.value_i64().map(Self)
+	}
+}
+
+impl Serialize for Gas {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.0.to_string())
+	}
+}
+
+impl<'de> Deserialize<'de> for Gas {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		// the RPC sends datoms as strings, but some plugins send plain numbers
+		#[derive(serde_derive::Deserialize)]
+		#[serde(untagged)]
+		enum Datoms {
+			String(String),
+			Number(i64),
+		}
+		match Datoms::deserialize(deserializer)? {
+			Datoms::String(datoms) => datoms.parse().map(Self).map_err(serde::de::Error::custom),
+			Datoms::Number(datoms) => Ok(Self(datoms)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_decimal_string() {
+		assert_eq!(Gas::from_decimal_str("1.5").unwrap(), Gas::from_datoms(150_000_000));
+		assert_eq!(Gas::from_str("0.1 GAS").unwrap(), Gas::from_datoms(10_000_000));
+		assert!(Gas::from_decimal_str("0.123456789").is_err());
+		assert!(Gas::from_decimal_str("1 NEO").is_err());
+		assert_eq!(Gas::from_datoms(1).to_decimal_string(), "0.00000001");
+		assert_eq!(Gas::from_datoms(2_0000_0000).to_string(), "2 GAS");
+	}
+
+	#[test]
+	fn test_saturating() {
+		let max = Gas::from_datoms(i64::MAX);
+		assert_eq!(max.saturating_add(Gas::from_datoms(1)), max);
+		assert_eq!(max.checked_add(Gas::from_datoms(1)), None);
+		assert_eq!(Gas::ZERO.saturating_sub(max), Gas::from_datoms(-i64::MAX));
+	}
+
+	#[test]
+	fn test_serde() {
+		let gas: Gas = serde_json::from_str("\"997750\"").unwrap();
+		assert_eq!(gas, Gas::from_datoms(997750));
+		assert_eq!(serde_json::from_str::<Gas>("42").unwrap(), Gas::from_datoms(42));
+		assert_eq!(serde_json::to_string(&gas).unwrap(), "\"997750\"");
+		assert!(serde_json::from_str::<Gas>("\"1.5\"").is_err());
+	}
+}
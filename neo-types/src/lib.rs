@@ -1,4 +1,3 @@
-use base64::{engine::general_purpose, Engine};
 use elliptic_curve::sec1::ToEncodedPoint;
 use primitive_types::H256;
 use serde_derive::{Deserialize, Serialize};
@@ -7,22 +6,31 @@ mod contract;
 mod nns;
 
 pub use contract::*;
-use neo_crypto::keys::{Secp256r1PrivateKey, Secp256r1PublicKey};
+use neo_crypto::{hash::sha256, keys::Secp256r1PublicKey};
 pub use nns::*;
 
 pub mod address;
 pub mod address_or_scripthash;
 pub mod block;
 pub mod bytes;
-pub mod filter;
+pub mod encoding;
+pub mod gas;
 pub mod log;
+pub mod natives;
+pub mod notification_filter;
 pub mod numeric;
 pub mod op_code;
 pub mod path_or_string;
 pub mod plugin_type;
 pub mod serde_value;
 pub mod serde_with_utils;
-use crate::{address::Address, script_hash::ScriptHash, string::StringExt};
+use crate::{
+	address::Address,
+	error::TypeError,
+	op_code::OpCode,
+	script_hash::{ScriptHash, ScriptHashExt},
+	string::StringExt,
+};
 pub use serde_with_utils::*;
 
 pub mod error;
@@ -30,8 +38,14 @@ pub mod role;
 pub mod script_hash;
 pub mod stack_item;
 pub mod string;
+#[cfg(feature = "interop-eth")]
+#[cfg_attr(docsrs, doc(cfg(feature = "interop-eth")))]
 pub mod syncing;
+pub mod token_amount;
+#[cfg(feature = "interop-eth")]
+#[cfg_attr(docsrs, doc(cfg(feature = "interop-eth")))]
 pub mod tx_pool;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod url_session;
 pub mod util;
 pub mod vm_state;
@@ -46,7 +60,7 @@ pub trait ExternBase64 {
 
 impl ExternBase64 for String {
 	fn to_base64(&self) -> String {
-		general_purpose::STANDARD.encode(self.as_bytes())
+		encoding::base64_encode(self)
 	}
 }
 
@@ -64,75 +78,44 @@ impl Default for ScryptParamsDef {
 	}
 }
 
-// Extend Vec<u8> with a to_base64 method
-pub trait Base64Encode {
-	fn to_base64(&self) -> String;
-}
-
-impl Base64Encode for Vec<u8> {
-	fn to_base64(&self) -> String {
-		base64::encode(&self)
-	}
-}
-
-impl Base64Encode for &[u8] {
-	fn to_base64(&self) -> String {
-		base64::encode(&self)
-	}
+/// The interop hash of `System.Crypto.CheckSig`, the first 4 bytes of the SHA-256 of its name
+const CHECK_SIG_HASH: [u8; 4] = {
+	let hash = sha256(b"System.Crypto.CheckSig");
+	[hash[0], hash[1], hash[2], hash[3]]
+};
+
+/// Builds the single signature verification script of `pubkey`, which pushes the compressed
+/// public key and calls `System.Crypto.CheckSig`.
+pub fn verification_script(pubkey: &Secp256r1PublicKey) -> Bytes {
+	let encoded = pubkey.get_encoded(true);
+	let mut script = Vec::with_capacity(encoded.len() + 7);
+	script.push(OpCode::PushData1 as u8);
+	script.push(encoded.len() as u8);
+	script.extend_from_slice(&encoded);
+	script.push(OpCode::Syscall as u8);
+	script.extend_from_slice(&CHECK_SIG_HASH);
+	script
 }
 
-pub fn secret_key_to_script_hash(secret_key: &Secp256r1PrivateKey) -> ScriptHash {
-	let public_key = secret_key.to_public_key().unwrap();
-	public_key_to_script_hash(&public_key)
-}
-
-pub fn public_key_to_script_hash(pubkey: &Secp256r1PublicKey) -> ScriptHash {
-	raw_public_key_to_script_hash(&pubkey.to_raw_bytes()[1..])
-}
-
-pub fn raw_public_key_to_script_hash<T: AsRef<[u8]>>(pubkey: T) -> ScriptHash {
-	let pubkey = pubkey.as_ref();
-	assert_eq!(pubkey.len(), 64, "raw public key must be 64 bytes");
-	let digest = vec![]; // keccak256(pubkey);
-	ScriptHash::from_slice(&digest)
-}
-
-pub fn to_checksum(addr: &ScriptHash, chain_id: Option<u8>) -> String {
-	// if !addr.is_valid_address(){
-	// 	panic!("invalid address");
-	// }
-	let prefixed_addr = match chain_id {
-		Some(chain_id) => format!("{chain_id}0x{addr:x}"),
-		None => format!("{addr:x}"),
-	};
-	let hash = hex::encode(prefixed_addr);
-	let hash = hash.as_bytes();
-
-	let addr_hex = hex::encode(addr.as_bytes());
-	let addr_hex = addr_hex.as_bytes();
-
-	addr_hex.iter().zip(hash).fold("0x".to_owned(), |mut encoded, (addr, hash)| {
-		encoded.push(if *hash >= 56 {
-			addr.to_ascii_uppercase() as char
-		} else {
-			addr.to_ascii_lowercase() as char
-		});
-		encoded
-	})
+/// Computes the script hash of a SEC1 encoded public key, compressed or uncompressed.
+pub fn raw_public_key_to_script_hash<T: AsRef<[u8]>>(pubkey: T) -> Result<ScriptHash, TypeError> {
+	let pubkey =
+		Secp256r1PublicKey::from_bytes(pubkey.as_ref()).map_err(|_| TypeError::InvalidPublicKey)?;
+	Ok(ScriptHash::from_public_key(&pubkey))
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use hex;
-	use rustc_serialize::base64::FromBase64;
+	use neo_config::{TestConstants, DEFAULT_ADDRESS_VERSION};
 
 	#[test]
 	fn test_base64_encode_bytes() {
 		let input = hex::decode("150c14242dbf5e2f6ac2568b59b7822278d571b75f17be0c14242dbf5e2f6ac2568b59b7822278d571b75f17be13c00c087472616e736665720c14897720d8cd76f4f00abfa37c0edd889c208fde9b41627d5b5238").unwrap();
 		let expected = "FQwUJC2/Xi9qwlaLWbeCInjVcbdfF74MFCQtv14vasJWi1m3giJ41XG3Xxe+E8AMCHRyYW5zZmVyDBSJdyDYzXb08Aq/o3wO3YicII/em0FifVtSOA==";
 
-		let encoded = input.to_base64();
+		let encoded = encoding::base64_encode(input);
 
 		assert_eq!(encoded, expected);
 	}
@@ -142,9 +125,30 @@ mod tests {
 		let encoded = "FQwUJC2/Xi9qwlaLWbeCInjVcbdfF74MFCQtv14vasJWi1m3giJ41XG3Xxe+E8AMCHRyYW5zZmVyDBSJdyDYzXb08Aq/o3wO3YicII/em0FifVtSOA==";
 		let expected = "150c14242dbf5e2f6ac2568b59b7822278d571b75f17be0c14242dbf5e2f6ac2568b59b7822278d571b75f17be13c00c087472616e736665720c14897720d8cd76f4f00abfa37c0edd889c208fde9b41627d5b5238";
 
-		let decoded = encoded.from_base64().unwrap();
+		let decoded = encoding::base64_decode(encoded).unwrap();
 		let decoded_hex = hex::encode(decoded);
 
 		assert_eq!(decoded_hex, expected);
 	}
+
+	#[test]
+	fn test_public_key_to_script_hash() {
+		let encoded = hex::decode(TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY).unwrap();
+		let pubkey = Secp256r1PublicKey::from_bytes(&encoded).unwrap();
+
+		assert_eq!(
+			hex::encode(verification_script(&pubkey)),
+			TestConstants::DEFAULT_ACCOUNT_VERIFICATION_SCRIPT
+		);
+		let script_hash = ScriptHash::from_public_key(&pubkey);
+		assert_eq!(hex::encode(script_hash), TestConstants::DEFAULT_ACCOUNT_SCRIPT_HASH);
+		assert_eq!(
+			script_hash.to_address(DEFAULT_ADDRESS_VERSION),
+			TestConstants::DEFAULT_ACCOUNT_ADDRESS
+		);
+
+		let uncompressed = pubkey.get_encoded(false);
+		assert_eq!(raw_public_key_to_script_hash(uncompressed).unwrap(), script_hash);
+		assert!(raw_public_key_to_script_hash([0u8; 64]).is_err());
+	}
 }
@@ -0,0 +1,129 @@
+//! The native contracts of Neo N3, with their IDs, names and script hashes.
+//!
+//! The script hash of a native contract is derived from its name only, so it is the same on
+//! every network.
+//!
+//! # Example
+//!
+//! ```
+//! use neo_types::natives;
+//!
+//! assert_eq!(natives::by_name("GasToken"), Some(&natives::GAS_TOKEN));
+//! assert_eq!(natives::by_id(-6).map(|native| native.hash), Some(natives::GAS_TOKEN.hash));
+//! ```
+
+use primitive_types::H160;
+
+/// A native contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NativeContract {
+	/// The ID of the contract, negative for native contracts
+	pub id: i32,
+	/// The name the script hash is derived from
+	pub name: &'static str,
+	/// The script hash in the big-endian order of the RPC interface
+	pub hash: H160,
+}
+
+pub const CONTRACT_MANAGEMENT: NativeContract =
+	native(-1, "ContractManagement", "fffdc93764dbaddd97c48f252a53ea4643faa3fd");
+pub const STD_LIB: NativeContract =
+	native(-2, "StdLib", "acce6fd80d44e1796aa0c2c625e9e4e0ce39efc0");
+pub const CRYPTO_LIB: NativeContract =
+	native(-3, "CryptoLib", "726cb6e0cd8628a1350a611384688911ab75f51b");
+pub const LEDGER_CONTRACT: NativeContract =
+	native(-4, "LedgerContract", "da65b600f7124ce6c79950c1772a36403104f2be");
+pub const NEO_TOKEN: NativeContract =
+	native(-5, "NeoToken", "ef4073a0f2b305a38ec4050e4d3d28bc40ea63f5");
+pub const GAS_TOKEN: NativeContract =
+	native(-6, "GasToken", "d2a4cff31913016155e38e474a2c06d08be276cf");
+pub const POLICY_CONTRACT: NativeContract =
+	native(-7, "PolicyContract", "cc5e4edd9f5f8dba8bb65734541df7a1c081c67b");
+pub const ROLE_MANAGEMENT: NativeContract =
+	native(-8, "RoleManagement", "49cf4e5378ffcd4dec034fd98a174c5491e395e2");
+pub const ORACLE_CONTRACT: NativeContract =
+	native(-9, "OracleContract", "fe924b7cfe89ddd271abaf7210a80a7e11178758");
+/// The notary contract of neo-go networks, and of Neo since 3.8
+pub const NOTARY: NativeContract =
+	native(-10, "Notary", "c1e14f19c3e60d0b9244d06dd7ba9b113135ec3b");
+
+/// All native contracts, ordered by descending ID
+pub const ALL: [NativeContract; 10] = [
+	CONTRACT_MANAGEMENT,
+	STD_LIB,
+	CRYPTO_LIB,
+	LEDGER_CONTRACT,
+	NEO_TOKEN,
+	GAS_TOKEN,
+	POLICY_CONTRACT,
+	ROLE_MANAGEMENT,
+	ORACLE_CONTRACT,
+	NOTARY,
+];
+
+/// The native contract named `name`, e.g. `GasToken`
+pub fn by_name(name: &str) -> Option<&'static NativeContract> {
+	ALL.iter().find(|native| native.name == name)
+}
+
+/// The native contract with the ID `id`
+pub fn by_id(id: i32) -> Option<&'static NativeContract> {
+	ALL.iter().find(|native| native.id == id)
+}
+
+/// The native contract with the script hash `hash`
+pub fn by_hash(hash: &H160) -> Option<&'static NativeContract> {
+	ALL.iter().find(|native| native.hash == *hash)
+}
+
+const fn native(id: i32, name: &'static str, hash: &str) -> NativeContract {
+	let hex = hash.as_bytes();
+	assert!(hex.len() == 40, "a script hash has 40 hex digits");
+	let mut bytes = [0u8; 20];
+	let mut i = 0;
+	while i < 20 {
+		bytes[i] = nibble(hex[2 * i]) << 4 | nibble(hex[2 * i + 1]);
+		i += 1;
+	}
+	NativeContract { id, name, hash: H160(bytes) }
+}
+
+const fn nibble(digit: u8) -> u8 {
+	match digit {
+		b'0'..=b'9' => digit - b'0',
+		b'a'..=b'f' => digit - b'a' + 10,
+		_ => panic!("invalid hex digit in a script hash"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::script_hash::ScriptHashExt;
+	use std::str::FromStr;
+
+	#[test]
+	fn test_hashes() {
+		for native in ALL {
+			// ABORT, the zero sender, the checksum 0 and the name, see `Helper.GetContractHash`
+			let mut script = vec![0x38, 0x0c, 0x14];
+			script.extend([0; 20]);
+			script.extend([0x10, 0x0c, native.name.len() as u8]);
+			script.extend(native.name.as_bytes());
+			assert_eq!(H160::from_script(&script), native.hash, "{}", native.name);
+		}
+		assert_eq!(
+			GAS_TOKEN.hash,
+			H160::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap()
+		);
+	}
+
+	#[test]
+	fn test_lookup() {
+		assert_eq!(by_name("NeoToken"), Some(&NEO_TOKEN));
+		assert_eq!(by_id(-9), Some(&ORACLE_CONTRACT));
+		assert_eq!(by_hash(&POLICY_CONTRACT.hash), Some(&POLICY_CONTRACT));
+		assert_eq!(by_name("neotoken"), None);
+		assert_eq!(by_id(0), None);
+	}
+}
@@ -0,0 +1,235 @@
+use crate::invocation_result::Notification;
+use primitive_types::H160;
+use std::{collections::HashSet, ops::RangeInclusive};
+
+/// A compiled set of event name patterns.
+///
+/// Matching is case-insensitive. A pattern may contain `*`, which matches any sequence of
+/// characters, e.g. `"Transfer*"` matches both `Transfer` and `TransferOwnership`. An empty
+/// matcher, or one containing the pattern `"*"`, matches every event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventMatcher {
+	exact: HashSet<String>,
+	globs: Vec<Vec<String>>,
+	any: bool,
+}
+
+impl EventMatcher {
+	/// Compiles `patterns` into a matcher.
+	pub fn new(patterns: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+		let mut matcher = Self::default();
+		for pattern in patterns {
+			matcher.add(pattern.as_ref());
+		}
+		matcher
+	}
+
+	/// A matcher that accepts every event name.
+	pub fn any() -> Self {
+		Self::default()
+	}
+
+	/// Adds a single pattern to the matcher.
+	pub fn add(&mut self, pattern: &str) {
+		let pattern = pattern.to_lowercase();
+		if pattern.chars().all(|c| c == '*') {
+			self.any = true;
+		} else if pattern.contains('*') {
+			self.globs.push(pattern.split('*').map(str::to_string).collect());
+		} else {
+			self.exact.insert(pattern);
+		}
+	}
+
+	/// Returns `true` if the matcher accepts every event name.
+	pub fn is_any(&self) -> bool {
+		self.any || (self.exact.is_empty() && self.globs.is_empty())
+	}
+
+	/// Returns `true` if `event_name` matches one of the patterns.
+	pub fn matches(&self, event_name: &str) -> bool {
+		if self.is_any() {
+			return true
+		}
+		let event_name = event_name.to_lowercase();
+		self.exact.contains(&event_name)
+			|| self.globs.iter().any(|glob| glob_match(glob, &event_name))
+	}
+}
+
+/// Matches `name` against the literal parts of a pattern that were separated by `*`.
+fn glob_match(parts: &[String], name: &str) -> bool {
+	let (first, rest) = match parts.split_first() {
+		Some(split) => split,
+		None => return true,
+	};
+	let Some(mut remaining) = name.strip_prefix(first.as_str()) else { return false };
+	let (last, middle) = match rest.split_last() {
+		Some(split) => split,
+		None => return remaining.is_empty(),
+	};
+	for part in middle {
+		match remaining.find(part.as_str()) {
+			Some(index) => remaining = &remaining[index + part.len()..],
+			None => return false,
+		}
+	}
+	remaining.ends_with(last.as_str())
+}
+
+/// Selects notifications by emitting contract and event name, and optionally by the range of
+/// blocks they were emitted in.
+///
+/// The block range is only used when scanning the chain for past notifications, e.g. by
+/// `Middleware::get_notifications`. [`NotificationFilter::matches`] ignores it.
+///
+/// # Example
+///
+/// ```
+/// use neo_types::notification_filter::NotificationFilter;
+/// use primitive_types::H160;
+///
+/// let gas = H160::repeat_byte(0xcf);
+/// let filter = NotificationFilter::new().contract(gas).events(["Transfer", "Mint*"]);
+/// assert!(filter.matches(&gas, "transfer"));
+/// assert!(filter.matches(&gas, "MintBatch"));
+/// assert!(!filter.matches(&H160::zero(), "Transfer"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotificationFilter {
+	contracts: HashSet<H160>,
+	events: EventMatcher,
+	from_block: Option<u32>,
+	to_block: Option<u32>,
+}
+
+impl NotificationFilter {
+	/// A filter matching every notification of every contract.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Restricts the filter to notifications emitted by `contract`. Can be called several times
+	/// to accept several contracts.
+	#[must_use]
+	pub fn contract(mut self, contract: H160) -> Self {
+		self.contracts.insert(contract);
+		self
+	}
+
+	/// Restricts the filter to notifications emitted by one of `contracts`.
+	#[must_use]
+	pub fn contracts(mut self, contracts: impl IntoIterator<Item = H160>) -> Self {
+		self.contracts.extend(contracts);
+		self
+	}
+
+	/// Restricts the filter to events matching `pattern`. Can be called several times to accept
+	/// several event names.
+	#[must_use]
+	pub fn event(mut self, pattern: &str) -> Self {
+		self.events.add(pattern);
+		self
+	}
+
+	/// Restricts the filter to events matching one of `patterns`.
+	#[must_use]
+	pub fn events(mut self, patterns: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+		for pattern in patterns {
+			self.events.add(pattern.as_ref());
+		}
+		self
+	}
+
+	/// Restricts the filter to notifications emitted at or after the block `index`.
+	#[must_use]
+	pub fn from_block(mut self, index: u32) -> Self {
+		self.from_block = Some(index);
+		self
+	}
+
+	/// Restricts the filter to notifications emitted at or before the block `index`.
+	#[must_use]
+	pub fn to_block(mut self, index: u32) -> Self {
+		self.to_block = Some(index);
+		self
+	}
+
+	/// Restricts the filter to notifications emitted in the blocks of `range`.
+	#[must_use]
+	pub fn blocks(self, range: RangeInclusive<u32>) -> Self {
+		self.from_block(*range.start()).to_block(*range.end())
+	}
+
+	/// The first block to scan, if set
+	pub fn get_from_block(&self) -> Option<u32> {
+		self.from_block
+	}
+
+	/// The last block to scan, if set
+	pub fn get_to_block(&self) -> Option<u32> {
+		self.to_block
+	}
+
+	/// The compiled event name matcher
+	pub fn event_matcher(&self) -> &EventMatcher {
+		&self.events
+	}
+
+	/// Returns `true` if the filter accepts every notification.
+	pub fn is_any(&self) -> bool {
+		self.contracts.is_empty() && self.events.is_any()
+	}
+
+	/// Returns `true` if an event `event_name` emitted by `contract` passes the filter.
+	pub fn matches(&self, contract: &H160, event_name: &str) -> bool {
+		(self.contracts.is_empty() || self.contracts.contains(contract))
+			&& self.events.matches(event_name)
+	}
+
+	/// Returns `true` if `notification` passes the filter.
+	pub fn matches_notification(&self, notification: &Notification) -> bool {
+		self.matches(&notification.contract, &notification.event_name)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_event_matcher() {
+		let matcher = EventMatcher::new(["Transfer", "Mint*", "*Changed", "Vote*Cast"]);
+		assert!(matcher.matches("transfer"));
+		assert!(matcher.matches("TRANSFER"));
+		assert!(!matcher.matches("TransferOwnership"));
+		assert!(matcher.matches("Mint"));
+		assert!(matcher.matches("MintBatch"));
+		assert!(matcher.matches("OwnerChanged"));
+		assert!(matcher.matches("VoteCast"));
+		assert!(matcher.matches("VoteForCandidateCast"));
+		assert!(!matcher.matches("Burn"));
+
+		assert!(EventMatcher::any().matches("Anything"));
+		assert!(EventMatcher::new(["*"]).matches("Anything"));
+	}
+
+	#[test]
+	fn test_notification_filter() {
+		let neo = H160::repeat_byte(0xef);
+		let gas = H160::repeat_byte(0xcf);
+
+		let filter = NotificationFilter::new().event("Transfer");
+		assert!(filter.matches(&neo, "Transfer"));
+		assert!(filter.matches(&gas, "transfer"));
+
+		let filter = filter.contract(neo);
+		assert!(filter.matches(&neo, "Transfer"));
+		assert!(!filter.matches(&gas, "Transfer"));
+		assert!(!filter.matches(&neo, "Vote"));
+
+		let filter = filter.blocks(10..=20);
+		assert_eq!((filter.get_from_block(), filter.get_to_block()), (Some(10), Some(20)));
+		assert!(filter.matches(&neo, "Transfer"));
+	}
+}
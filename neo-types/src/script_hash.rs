@@ -1,19 +1,20 @@
-use crate::error::TypeError;
+use crate::{address::Address, error::TypeError, verification_script};
 use hex::FromHexError;
-use neo_config::DEFAULT_ADDRESS_VERSION;
-use neo_crypto::hash::HashableForVec;
+use neo_crypto::{hash::HashableForVec, keys::Secp256r1PublicKey};
 use primitive_types::H160;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
 
 pub type ScriptHash = H160;
 
-/// Trait that provides additional methods for types related to `ScriptHash`.
-pub trait ScriptHashExtension
+/// Conversions between script hashes, addresses, public keys and scripts.
+///
+/// This is the one place these conversions live; [`Address`] holds the Base58Check encoding
+/// itself, and everything else goes through this trait.
+pub trait ScriptHashExt
 where
 	Self: Sized,
 {
-	/// Returns a string representation of the object.
-	fn to_string(&self) -> String;
-
 	/// Creates an instance from a byte slice.
 	///
 	/// # Errors
@@ -21,38 +22,38 @@ where
 	/// Returns an error if the slice has an invalid length.
 	fn from_slice(slice: &[u8]) -> Result<Self, TypeError>;
 
-	/// Creates an instance from a hex string.
+	/// Creates an instance from a hex string, with or without a `0x` prefix.
 	///
 	/// # Errors
 	///
-	/// Returns an error if the hex string is invalid.
-	fn from_hex(hex: &str) -> Result<Self, hex::FromHexError>;
+	/// Returns an error if the hex string is invalid or does not hold 20 bytes.
+	fn from_hex(hex: &str) -> Result<Self, FromHexError>;
 
-	/// Creates an instance from an address string representation.
+	/// Creates an instance from an address of any address version.
 	///
 	/// # Errors
 	///
 	/// Returns an error if the address is invalid.
 	fn from_address(address: &str) -> Result<Self, TypeError>;
 
-	/// Converts the object into its address string representation.
-	fn to_address(&self) -> String;
+	/// The script hash of the single signature verification script of `public_key`.
+	fn from_public_key(public_key: &Secp256r1PublicKey) -> Self;
+
+	/// The script hash of `script`, the RIPEMD-160 of its SHA-256 in little-endian order.
+	fn from_script(script: &[u8]) -> Self;
+
+	/// Encodes the script hash as an address with the address version `version`, see
+	/// [`DEFAULT_ADDRESS_VERSION`](neo_config::DEFAULT_ADDRESS_VERSION).
+	fn to_address(&self, version: u8) -> String;
 
 	/// Converts the object into a byte vector.
 	fn to_vec(&self) -> Vec<u8>;
 
 	/// Converts the object into a little-endian byte vector.
 	fn to_le_vec(&self) -> Vec<u8>;
-
-	/// Creates an instance from a script byte slice.
-	fn from_script(script: &[u8]) -> Self;
 }
 
-impl ScriptHashExtension for H160 {
-	fn to_string(&self) -> String {
-		bs58::encode(self.0).into_string()
-	}
-
+impl ScriptHashExt for H160 {
 	fn from_slice(slice: &[u8]) -> Result<Self, TypeError> {
 		if slice.len() != 20 {
 			return Err(TypeError::InvalidAddress)
@@ -64,38 +65,29 @@ impl ScriptHashExtension for H160 {
 	}
 
 	fn from_hex(hex: &str) -> Result<Self, FromHexError> {
-		let hex = if hex.starts_with("0x") { &hex[2..] } else { hex };
+		let hex = hex.strip_prefix("0x").unwrap_or(hex);
 		let bytes = hex::decode(hex)?;
-		Ok(Self::from_slice(&bytes))
+		Self::from_slice(&bytes).map_err(|_| FromHexError::InvalidStringLength)
 	}
 
 	fn from_address(address: &str) -> Result<Self, TypeError> {
-		let bytes = match bs58::decode(address).into_vec() {
-			Ok(bytes) => bytes,
-			Err(_) => return Err(TypeError::InvalidAddress),
-		};
+		Ok(address.parse::<Address>()?.script_hash())
+	}
 
-		let salt = bytes[0];
-		let hash = &bytes[1..21];
-		let checksum = &bytes[21..25];
-		let mut sha = &bytes[..21].hash256().hash256();
-		let check = &sha[..4];
-		if checksum != check {
-			return Err(TypeError::InvalidAddress)
-		}
+	fn from_public_key(public_key: &Secp256r1PublicKey) -> Self {
+		Self::from_script(&verification_script(public_key))
+	}
 
-		let mut rev = [0u8; 20];
-		rev.clone_from_slice(hash);
-		rev.reverse();
-		Ok(Self::from_slice(&rev))
+	fn from_script(script: &[u8]) -> Self {
+		let mut hash = script.sha256_ripemd160();
+		hash.reverse();
+		let mut arr = [0u8; 20];
+		arr.copy_from_slice(&hash);
+		Self(arr)
 	}
 
-	fn to_address(&self) -> String {
-		let mut data = vec![DEFAULT_ADDRESS_VERSION];
-		data.extend_from_slice(&self.0);
-		let mut sha = &data.hash256().hash256();
-		data.extend_from_slice(&sha[..4]);
-		bs58::encode(data).into_string()
+	fn to_address(&self, version: u8) -> String {
+		Address::with_version(*self, version).to_string()
 	}
 
 	fn to_vec(&self) -> Vec<u8> {
@@ -107,20 +99,108 @@ impl ScriptHashExtension for H160 {
 		vec.reverse();
 		vec
 	}
+}
 
-	fn from_script(script: &[u8]) -> Self {
-		let mut hash = script.sha256_ripemd160();
-		hash.reverse();
-		let mut arr = [0u8; 20];
-		arr.copy_from_slice(&hash);
-		Self(arr)
+/// A script hash in the big-endian form of the RPC interface, e.g.
+/// `0xd2a4cff31913016155e38e474a2c06d08be276cf` for GAS.
+///
+/// This is the order [`H160`] stores and nodes accept for script hash parameters and return in
+/// responses like `assethash` or `contract`. Parsing accepts the hex with or without the `0x`
+/// prefix, formatting always adds it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScriptHashBE(pub H160);
+
+/// A script hash in the little-endian form it takes inside scripts and serialized transactions,
+/// e.g. `cf76e28bd0062c4a478ee35561011319f3cfa4d2` for GAS.
+///
+/// Hashes in the JSON of nodes are big-endian, so parsing rejects a `0x` prefix instead of
+/// silently reading such a hash in the wrong order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScriptHashLE(pub H160);
+
+fn decode_script_hash(hex: &str) -> Result<[u8; 20], TypeError> {
+	let bytes = hex::decode(hex)
+		.map_err(|e| TypeError::InvalidEncoding(format!("Invalid script hash {hex}: {e}")))?;
+	bytes.try_into().map_err(|bytes: Vec<u8>| {
+		TypeError::InvalidEncoding(format!(
+			"A script hash has 20 bytes, not {} in {hex}",
+			bytes.len()
+		))
+	})
+}
+
+impl fmt::Display for ScriptHashBE {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "0x{}", hex::encode(self.0.as_bytes()))
+	}
+}
+
+impl FromStr for ScriptHashBE {
+	type Err = TypeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(Self(H160(decode_script_hash(s.strip_prefix("0x").unwrap_or(s))?)))
+	}
+}
+
+impl fmt::Display for ScriptHashLE {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&hex::encode(self.0.to_le_vec()))
 	}
 }
 
+impl FromStr for ScriptHashLE {
+	type Err = TypeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.starts_with("0x") {
+			return Err(TypeError::InvalidEncoding(format!(
+				"{s} is a big-endian script hash, little-endian ones have no 0x prefix"
+			)))
+		}
+		let mut bytes = decode_script_hash(s)?;
+		bytes.reverse();
+		Ok(Self(H160(bytes)))
+	}
+}
+
+macro_rules! script_hash_form {
+	($form:ident) => {
+		impl From<H160> for $form {
+			fn from(hash: H160) -> Self {
+				Self(hash)
+			}
+		}
+
+		impl From<$form> for H160 {
+			fn from(hash: $form) -> Self {
+				hash.0
+			}
+		}
+
+		impl Serialize for $form {
+			fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.collect_str(self)
+			}
+		}
+
+		impl<'de> Deserialize<'de> for $form {
+			fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				let s = String::deserialize(deserializer)?;
+				s.parse().map_err(serde::de::Error::custom)
+			}
+		}
+	};
+}
+
+script_hash_form!(ScriptHashBE);
+script_hash_form!(ScriptHashLE);
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
+	use neo_config::DEFAULT_ADDRESS_VERSION;
 	use rustc_serialize::hex::ToHex;
 	use std::str::FromStr;
 
@@ -183,6 +263,83 @@ mod tests {
 		let hash = H160::from_address("NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke").unwrap();
 		let expected = hex::decode("09a55874c2da4b86e5d49ff530a1b153eb12c7d6").unwrap();
 		assert_eq!(hash.to_le_vec(), expected);
+		assert_eq!(hash.to_address(DEFAULT_ADDRESS_VERSION), "NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke");
+		assert_ne!(hash.to_address(0x17), "NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke");
+	}
+
+	#[test]
+	fn test_script_hash_forms() {
+		let gas = H160::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap();
+		assert_eq!(ScriptHashBE(gas).to_string(), "0xd2a4cff31913016155e38e474a2c06d08be276cf");
+		assert_eq!(ScriptHashLE(gas).to_string(), "cf76e28bd0062c4a478ee35561011319f3cfa4d2");
+		for hash in [
+			"0xd2a4cff31913016155e38e474a2c06d08be276cf",
+			"d2a4cff31913016155e38e474a2c06d08be276cf",
+		] {
+			assert_eq!(hash.parse::<ScriptHashBE>().unwrap().0, gas);
+		}
+		assert_eq!("cf76e28bd0062c4a478ee35561011319f3cfa4d2".parse(), Ok(ScriptHashLE(gas)));
+
+		// a big-endian hash is never taken for a little-endian one
+		assert!("0xd2a4cff31913016155e38e474a2c06d08be276cf".parse::<ScriptHashLE>().is_err());
+		assert!("0xd2a4cff31913016155e38e474a2c06d08be276".parse::<ScriptHashBE>().is_err());
+		assert!("NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke".parse::<ScriptHashBE>().is_err());
+	}
+
+	#[test]
+	fn test_recorded_payloads() {
+		use crate::{
+			contract_parameter::{ContractParameter, ParameterValue},
+			serde_value::ValueExtension,
+		};
+		use serde_json::json;
+
+		// an entry of `getnep17balances`
+		#[derive(serde::Serialize, serde::Deserialize)]
+		struct Balance {
+			assethash: ScriptHashBE,
+			amount: String,
+		}
+		let recorded =
+			json!({"assethash": "0xd2a4cff31913016155e38e474a2c06d08be276cf", "amount": "3000000"});
+		let balance: Balance = serde_json::from_value(recorded.clone()).unwrap();
+		assert_eq!(
+			balance.assethash.0.to_le_vec(),
+			hex::decode("cf76e28bd0062c4a478ee35561011319f3cfa4d2").unwrap()
+		);
+		assert_eq!(serde_json::to_value(&balance).unwrap(), recorded);
+
+		// an entry of `getnep17transfers`, whose accounts are addresses
+		#[derive(serde::Deserialize)]
+		struct Transfer {
+			#[serde(deserialize_with = "crate::deserialize_script_hash")]
+			transferaddress: H160,
+			#[serde(deserialize_with = "crate::deserialize_script_hash")]
+			assethash: H160,
+		}
+		let transfer: Transfer = serde_json::from_value(json!({
+			"transferaddress": "NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke",
+			"assethash": "0xd2a4cff31913016155e38e474a2c06d08be276cf",
+		}))
+		.unwrap();
+		assert_eq!(
+			transfer.transferaddress,
+			H160::from_address("NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke").unwrap()
+		);
+		assert_eq!(transfer.assethash, balance.assethash.0);
+		assert!(serde_json::from_value::<Transfer>(json!({
+			"transferaddress": "not an account",
+			"assethash": "0xd2a4cff31913016155e38e474a2c06d08be276cf",
+		}))
+		.is_err());
+
+		// the parameters of `invokefunction` and `getnep17balances`
+		let gas = balance.assethash.0;
+		assert_eq!(gas.to_value(), json!("0xd2a4cff31913016155e38e474a2c06d08be276cf"));
+		assert_eq!(
+			ContractParameter::hash160(&gas).value,
+			Some(ParameterValue::Hash160("0xd2a4cff31913016155e38e474a2c06d08be276cf".to_string()))
+		);
 	}
 
 	#[test]
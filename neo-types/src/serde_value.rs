@@ -4,7 +4,7 @@ use neo_crypto::keys::Secp256r1PublicKey;
 use primitive_types::{H160, H256};
 use serde_json::Value;
 
-use crate::Bytes;
+use crate::{address::Address, script_hash::ScriptHashBE, Bytes};
 
 pub trait ValueExtension {
 	fn to_value(&self) -> Value;
@@ -30,7 +30,13 @@ impl ValueExtension for &str {
 
 impl ValueExtension for H160 {
 	fn to_value(&self) -> Value {
-		Value::String(bs58::encode(self.0).into_string())
+		Value::String(ScriptHashBE(*self).to_string())
+	}
+}
+
+impl ValueExtension for Address {
+	fn to_value(&self) -> Value {
+		Value::String(self.to_string())
 	}
 }
 
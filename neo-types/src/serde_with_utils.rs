@@ -10,22 +10,20 @@ use serde_substrate as serde;
 
 use hex;
 use primitive_types::{H160, H256, U256};
-use reqwest::Url;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
 	collections::{HashMap, HashSet},
 	convert::TryInto,
 };
+use url::Url;
 
 use crate::{
 	address::Address,
 	address_or_scripthash::AddressOrScriptHash,
+	encoding,
 	nef_file::MethodToken,
-	script_hash::{ScriptHash, ScriptHashExtension},
-	util::{
-		encode_string_h160, encode_string_h256, parse_address, parse_string_h256,
-		parse_string_u256, parse_string_u64,
-	},
+	script_hash::{ScriptHash, ScriptHashBE, ScriptHashExt},
+	util::{encode_string_h256, parse_string_h256, parse_string_u256, parse_string_u64},
 };
 use neo_crypto::keys::{Secp256r1PrivateKey, Secp256r1PublicKey};
 use serde::ser::{SerializeMap, SerializeSeq};
@@ -45,8 +43,22 @@ where
 	D: Deserializer<'de>,
 {
 	let s: String = Deserialize::deserialize(deserializer)?;
-	let bytes = hex::decode(s.trim_start_matches("0x")).unwrap();
-	Ok(bytes)
+	encoding::hex_decode(&s).map_err(serde::de::Error::custom)
+}
+
+pub fn serialize_base64<S>(item: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	serializer.serialize_str(&encoding::base64_encode(item))
+}
+
+pub fn deserialize_base64<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let s: String = Deserialize::deserialize(deserializer)?;
+	encoding::base64_decode(&s).map_err(serde::de::Error::custom)
 }
 
 pub fn serialize_url<S>(item: Url, serializer: S) -> Result<S::Ok, S::Error>
@@ -125,11 +137,17 @@ pub fn deserialize_wildcard<'de, D>(deserializer: D) -> Result<Vec<String>, D::E
 where
 	D: Deserializer<'de>,
 {
-	let s: String = Deserialize::deserialize(deserializer)?;
-	if s == "*" {
-		Ok(vec!["*".to_string()])
-	} else {
-		Ok(vec![s])
+	// either the wildcard `"*"` or a list
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Wildcard {
+		One(String),
+		Many(Vec<String>),
+	}
+
+	match Wildcard::deserialize(deserializer)? {
+		Wildcard::One(s) => Ok(vec![s]),
+		Wildcard::Many(values) => Ok(values),
 	}
 }
 
@@ -214,21 +232,28 @@ where
 	Ok(parse_string_u64(&s))
 }
 
+/// Parses a big-endian script hash, see [`ScriptHashBE`], or an address, the two forms nodes use
+/// for accounts in responses.
+fn parse_script_hash<E: serde::de::Error>(s: &str) -> Result<ScriptHash, E> {
+	s.parse::<ScriptHashBE>()
+		.map(|hash| hash.0)
+		.or_else(|_| ScriptHash::from_address(s))
+		.map_err(|_| E::custom(format!("{s} is neither a script hash nor an address")))
+}
+
 pub fn deserialize_script_hash<'de, D>(deserializer: D) -> Result<ScriptHash, D::Error>
 where
 	D: Deserializer<'de>,
 {
 	let s: String = Deserialize::deserialize(deserializer)?;
-	let addr = parse_address(&s);
-	Ok(addr)
+	parse_script_hash(&s)
 }
 
 pub fn serialize_script_hash<S>(item: &ScriptHash, serializer: S) -> Result<S::Ok, S::Error>
 where
 	S: Serializer,
 {
-	let item_str = encode_string_h160(item);
-	serializer.serialize_str(&item_str)
+	ScriptHashBE(*item).serialize(serializer)
 }
 
 pub fn deserialize_address_or_script_hash<'de, D>(
@@ -238,8 +263,7 @@ where
 	D: Deserializer<'de>,
 {
 	let s: String = Deserialize::deserialize(deserializer)?;
-	let addr = parse_address(&s);
-	Ok(AddressOrScriptHash::ScriptHash(addr))
+	Ok(AddressOrScriptHash::ScriptHash(parse_script_hash(&s)?))
 }
 
 pub fn serialize_address_or_script_hash<S>(
@@ -249,8 +273,7 @@ pub fn serialize_address_or_script_hash<S>(
 where
 	S: Serializer,
 {
-	let item_str = encode_string_h160(&item.script_hash());
-	serializer.serialize_str(&item_str)
+	ScriptHashBE(item.script_hash()).serialize(serializer)
 }
 
 pub fn deserialize_vec_script_hash<'de, D>(deserializer: D) -> Result<Vec<ScriptHash>, D::Error>
@@ -325,10 +348,7 @@ where
 	S: Serializer,
 {
 	match item {
-		Some(addr) => {
-			let addr_str = encode_string_h160(&addr);
-			serializer.serialize_str(&addr_str)
-		},
+		Some(addr) => ScriptHashBE(*addr).serialize(serializer),
 		None => serializer.serialize_none(),
 	}
 }
@@ -341,10 +361,7 @@ where
 {
 	let s: Option<String> = Deserialize::deserialize(deserializer)?;
 	match s {
-		Some(s) => {
-			let addr = parse_address(&s);
-			Ok(Some(addr))
-		},
+		Some(s) => Ok(Some(parse_script_hash(&s)?)),
 		None => Ok(None),
 	}
 }
@@ -359,7 +376,7 @@ where
 {
 	let mut map = serializer.serialize_map(Some(item.len()))?;
 	for (k, v) in item {
-		map.serialize_entry(&encode_string_h160(k), &v)?;
+		map.serialize_entry(&ScriptHashBE(*k), &v)?;
 	}
 	map.end()
 }
@@ -375,8 +392,7 @@ where
 	let mut hashmap: HashMap<H160, Account> = HashMap::new();
 
 	for (k, v) in map {
-		let k_h160 = parse_address(&k);
-		hashmap.insert(k_h160, v);
+		hashmap.insert(parse_script_hash(&k)?, v);
 	}
 	Ok(hashmap)
 }
@@ -404,21 +420,22 @@ where
 }
 
 // Secp256r1PublicKey
+/// Deserializes a public key from the hex of its encoding, with or without `0x` prefix
 pub fn deserialize_public_key<'de, D>(deserializer: D) -> Result<Secp256r1PublicKey, D::Error>
 where
 	D: Deserializer<'de>,
 {
 	let s: String = Deserialize::deserialize(deserializer)?;
-	let key = Secp256r1PublicKey::from_bytes(parse_string_h256(&s).as_bytes()).unwrap();
-	Ok(key)
+	Secp256r1PublicKey::from_encoded(&s)
+		.ok_or_else(|| serde::de::Error::custom(format!("{s} is not an encoded public key")))
 }
 
+/// Serializes a public key as the hex of its compressed encoding, the form nodes use
 pub fn serialize_public_key<S>(item: &Secp256r1PublicKey, serializer: S) -> Result<S::Ok, S::Error>
 where
 	S: Serializer,
 {
-	let item_str = encode_string_h256(&H256::from_slice(&item.to_raw_bytes().to_vec()));
-	serializer.serialize_str(&item_str)
+	serializer.serialize_str(&item.get_encoded_compressed_hex())
 }
 
 pub fn deserialize_vec_public_key<'de, D>(
@@ -683,9 +700,9 @@ where
 	let mut hashmap: HashMap<Address, U256> = HashMap::new();
 
 	for (k, v) in map {
-		// let k_h160 = parse_address(&k);
+		let address = k.parse().map_err(serde::de::Error::custom)?;
 		let v_u256 = parse_string_u256(&v);
-		hashmap.insert(k, v_u256);
+		hashmap.insert(address, v_u256);
 	}
 	Ok(hashmap)
 }
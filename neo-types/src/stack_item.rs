@@ -2,7 +2,7 @@
 /// `StackItem` is a recursive enum that can represent any type of value that can be stored on the stack, including arrays, maps, and custom types.
 /// `MapEntry` is a simple struct that represents a key-value pair in a `StackItem::Map`.
 /// The `StackItem` enum also provides several utility methods for converting between different types and formats.
-use crate::{address::Address, script_hash::ScriptHashExtension};
+use crate::{address::Address, error::TypeError, script_hash::ScriptHashExt};
 
 use neo_crypto::keys::Secp256r1PublicKey;
 use primitive_types::{H160, H256};
@@ -139,7 +139,7 @@ impl StackItem {
 	pub fn as_string(&self) -> Option<String> {
 		match self {
 			StackItem::ByteString { value } | StackItem::Buffer { value } =>
-				hex::decode(value).ok().map(|bytes| String::from_utf8(bytes).ok()).unwrap(),
+				hex::decode(value).ok().and_then(|bytes| String::from_utf8(bytes).ok()),
 			StackItem::Integer { value } => Some(value.to_string()),
 			StackItem::Boolean { value } => Some(value.to_string()),
 			_ => None,
@@ -229,9 +229,9 @@ impl StackItem {
 
 	/// Returns the `Address` value of a `StackItem::ByteString` or `StackItem::Buffer`.
 	pub fn as_address(&self) -> Option<Address> {
-		self.as_bytes().and_then(|mut bytes| {
+		self.as_bytes().filter(|bytes| bytes.len() == 20).map(|mut bytes| {
 			bytes.reverse();
-			Some(H160::from_slice(&bytes).to_address())
+			Address::from_script_hash(H160::from_slice(&bytes))
 		})
 	}
 
@@ -346,3 +346,104 @@ impl From<&str> for StackItem {
 		StackItem::ByteString { value: value.to_string() }
 	}
 }
+
+/// Decodes a value from a [`StackItem`] returned by the Neo VM.
+pub trait FromStackItem: Sized {
+	/// Decodes `item`, failing if it does not hold a value of this type.
+	fn from_stack_item(item: StackItem) -> Result<Self, TypeError>;
+}
+
+fn unexpected(item: &StackItem, expected: &str) -> TypeError {
+	TypeError::UnexpectedReturnType(format!("expected {expected}, got {}", item.to_string()))
+}
+
+impl FromStackItem for StackItem {
+	fn from_stack_item(item: StackItem) -> Result<Self, TypeError> {
+		Ok(item)
+	}
+}
+
+impl FromStackItem for bool {
+	fn from_stack_item(item: StackItem) -> Result<Self, TypeError> {
+		item.as_bool().ok_or_else(|| unexpected(&item, "Boolean"))
+	}
+}
+
+impl FromStackItem for i64 {
+	fn from_stack_item(item: StackItem) -> Result<Self, TypeError> {
+		item.as_int().ok_or_else(|| unexpected(&item, "Integer"))
+	}
+}
+
+impl FromStackItem for String {
+	fn from_stack_item(item: StackItem) -> Result<Self, TypeError> {
+		item.as_string().ok_or_else(|| unexpected(&item, "String"))
+	}
+}
+
+impl FromStackItem for Vec<u8> {
+	fn from_stack_item(item: StackItem) -> Result<Self, TypeError> {
+		item.as_bytes().ok_or_else(|| unexpected(&item, "ByteString"))
+	}
+}
+
+/// Script hashes are pushed in little-endian byte order.
+impl FromStackItem for H160 {
+	fn from_stack_item(item: StackItem) -> Result<Self, TypeError> {
+		match item.as_bytes() {
+			Some(mut bytes) if bytes.len() == 20 => {
+				bytes.reverse();
+				Ok(H160::from_slice(&bytes))
+			},
+			_ => Err(unexpected(&item, "Hash160")),
+		}
+	}
+}
+
+/// Hashes are pushed in little-endian byte order.
+impl FromStackItem for H256 {
+	fn from_stack_item(item: StackItem) -> Result<Self, TypeError> {
+		match item.as_bytes() {
+			Some(mut bytes) if bytes.len() == 32 => {
+				bytes.reverse();
+				Ok(H256::from_slice(&bytes))
+			},
+			_ => Err(unexpected(&item, "Hash256")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hashes_from_stack_item() {
+		// the script hash of the GAS token, pushed in little-endian byte order
+		let item =
+			StackItem::ByteString { value: "cf76e28bd0062c4a478ee35561011319f3cfa4d2".into() };
+		assert_eq!(
+			H160::from_stack_item(item).unwrap(),
+			H160::from_hex("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap()
+		);
+
+		let mut bytes: Vec<u8> = (0..32).collect();
+		let item = StackItem::ByteString { value: hex::encode(&bytes) };
+		bytes.reverse();
+		assert_eq!(H256::from_stack_item(item).unwrap(), H256::from_slice(&bytes));
+
+		let short = StackItem::ByteString { value: "cf76".into() };
+		assert!(H160::from_stack_item(short).is_err());
+	}
+
+	#[test]
+	fn test_as_string() {
+		let item = StackItem::ByteString { value: hex::encode("NEO") };
+		assert_eq!(item.as_string().as_deref(), Some("NEO"));
+		assert_eq!(StackItem::Integer { value: -5 }.as_string().as_deref(), Some("-5"));
+
+		// neither valid hex nor valid UTF-8 is a string
+		assert_eq!(StackItem::ByteString { value: "zz".into() }.as_string(), None);
+		assert_eq!(StackItem::Buffer { value: "ff".into() }.as_string(), None);
+	}
+}
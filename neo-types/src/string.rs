@@ -1,13 +1,14 @@
-use crate::script_hash::ScriptHash;
-use bs58;
-use sha2::{Digest, Sha256};
+use crate::{
+	encoding::{self, EncodingError},
+	script_hash::ScriptHash,
+};
 
 extern crate hex;
 
 pub trait StringExt {
-	fn bytes_from_hex(&self) -> Result<Vec<u8>, hex::FromHexError>;
+	fn bytes_from_hex(&self) -> Result<Vec<u8>, EncodingError>;
 
-	fn base64_decoded(&self) -> Result<Vec<u8>, base64::DecodeError>;
+	fn base64_decoded(&self) -> Result<Vec<u8>, EncodingError>;
 
 	fn base64_encoded(&self) -> String;
 
@@ -29,28 +30,28 @@ pub trait StringExt {
 }
 
 impl StringExt for String {
-	fn bytes_from_hex(&self) -> Result<Vec<u8>, hex::FromHexError> {
-		hex::decode(self.trim_start_matches("0x"))
+	fn bytes_from_hex(&self) -> Result<Vec<u8>, EncodingError> {
+		encoding::hex_decode(self)
 	}
 
-	fn base64_decoded(&self) -> Result<Vec<u8>, base64::DecodeError> {
-		base64::decode(self)
+	fn base64_decoded(&self) -> Result<Vec<u8>, EncodingError> {
+		encoding::base64_decode(self)
 	}
 
 	fn base64_encoded(&self) -> String {
-		base64::encode(self.as_bytes())
+		encoding::base64_encode(self)
 	}
 
 	fn base58_decoded(&self) -> Option<Vec<u8>> {
-		bs58::decode(self).into_vec().ok()
+		encoding::base58_decode(self).ok()
 	}
 
 	fn base58_check_decoded(&self) -> Option<Vec<u8>> {
-		bs58::decode(self).into_vec().ok()
+		encoding::base58check_decode(self).ok()
 	}
 
 	fn base58_encoded(&self) -> String {
-		bs58::encode(self.as_bytes()).into_string()
+		encoding::base58_encode(self.as_bytes())
 	}
 
 	fn var_size(&self) -> usize {
@@ -64,16 +65,8 @@ impl StringExt for String {
 	}
 
 	fn is_valid_address(&self) -> bool {
-		if let Some(data) = self.base58_decoded() {
-			if data.len() == 25 && data[0] == 0x17 {
-				let checksum = &Sha256::digest(&Sha256::digest(&data[..21]))[..4];
-				checksum == &data[21..]
-			} else {
-				false
-			}
-		} else {
-			false
-		}
+		self.base58_check_decoded()
+			.map_or(false, |data| data.len() == 21 && data[0] == 0x17)
 	}
 
 	fn is_valid_hex(&self) -> bool {
@@ -0,0 +1,240 @@
+use crate::{
+	deserialize_script_hash, error::TypeError, nep17contract::Nep17Contract, serialize_script_hash,
+};
+use primitive_types::H160;
+use serde_derive::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// An amount of a NEP-17 token: the value in the smallest unit of the token together with the
+/// decimals and the script hash of the token.
+///
+/// Contracts only deal with the value, `1.5 GAS` is transferred as `150000000`. Keeping the
+/// decimals with the value makes the scaling explicit: amounts are created from human-readable
+/// strings with [`TokenAmount::parse`] or [`Nep17Contract::parse_amount`], arithmetic is only
+/// allowed between amounts of the same token and fails instead of overflowing.
+///
+/// The value is held as `i128`, so amounts of tokens with 18 decimals are not limited to the
+/// about 9.22 units an `i64` would hold.
+///
+/// # Example
+///
+/// ```
+/// use neo_types::token_amount::TokenAmount;
+/// use std::str::FromStr;
+///
+/// let amount = TokenAmount::from_str("1.5 GAS").unwrap();
+/// assert_eq!(amount.value(), 150_000_000);
+/// let fee = TokenAmount::from_str("0.0123 GAS").unwrap();
+/// assert_eq!(amount.checked_sub(&fee).unwrap().to_string(), "1.4877");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TokenAmount {
+	value: i128,
+	decimals: u8,
+	#[serde(serialize_with = "serialize_script_hash")]
+	#[serde(deserialize_with = "deserialize_script_hash")]
+	asset: H160,
+}
+
+impl TokenAmount {
+	/// Creates an amount of `value` fractions of the token `asset` with `decimals` decimals.
+	pub fn new(value: i128, decimals: u8, asset: H160) -> Self {
+		Self { value, decimals, asset }
+	}
+
+	/// Parses a decimal number like `1.5` into an amount of the token `asset` with `decimals`
+	/// decimals. Fails if the number has more decimal places than the token.
+	pub fn parse(amount: &str, decimals: u8, asset: H160) -> Result<Self, TypeError> {
+		let invalid = || TypeError::InvalidArgError(format!("Invalid amount {amount}"));
+		let (negative, digits) = match amount.trim().strip_prefix('-') {
+			Some(digits) => (true, digits),
+			None => (false, amount.trim()),
+		};
+		let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+		if integer.is_empty() && fraction.is_empty()
+			|| !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+		{
+			return Err(invalid())
+		}
+		if fraction.len() > decimals as usize {
+			return Err(TypeError::InvalidArgError(format!(
+				"Amount {amount} has more than the {decimals} decimal places of the token"
+			)))
+		}
+
+		let parse = |digits: &str| -> Result<i128, TypeError> {
+			if digits.is_empty() {
+				return Ok(0)
+			}
+			digits.parse().map_err(|_| TypeError::NumericOverflow)
+		};
+		let factor = 10i128.checked_pow(decimals as u32).ok_or(TypeError::NumericOverflow)?;
+		let padding = factor / 10i128.pow(fraction.len() as u32);
+		let value = parse(integer)?
+			.checked_mul(factor)
+			.and_then(|value| value.checked_add(parse(fraction).ok()? * padding))
+			.ok_or(TypeError::NumericOverflow)?;
+		Ok(Self::new(if negative { -value } else { value }, decimals, asset))
+	}
+
+	/// The amount in the smallest unit of the token, as passed to the token contract
+	pub fn value(&self) -> i128 {
+		self.value
+	}
+
+	/// The value as `i64`, e.g. for transfers built from integer contract parameters. Fails if
+	/// the value does not fit.
+	pub fn value_i64(&self) -> Result<i64, TypeError> {
+		i64::try_from(self.value).map_err(|_| TypeError::NumericOverflow)
+	}
+
+	/// The decimals of the token
+	pub fn decimals(&self) -> u8 {
+		self.decimals
+	}
+
+	/// The script hash of the token
+	pub fn asset(&self) -> H160 {
+		self.asset
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.value == 0
+	}
+
+	pub fn is_negative(&self) -> bool {
+		self.value < 0
+	}
+
+	/// Adds `other`, which has to be an amount of the same token.
+	pub fn checked_add(&self, other: &Self) -> Result<Self, TypeError> {
+		self.check_same_token(other)?;
+		let value = self.value.checked_add(other.value).ok_or(TypeError::NumericOverflow)?;
+		Ok(Self { value, ..*self })
+	}
+
+	/// Subtracts `other`, which has to be an amount of the same token.
+	pub fn checked_sub(&self, other: &Self) -> Result<Self, TypeError> {
+		self.check_same_token(other)?;
+		let value = self.value.checked_sub(other.value).ok_or(TypeError::NumericOverflow)?;
+		Ok(Self { value, ..*self })
+	}
+
+	/// Multiplies the amount by `factor`, e.g. to pay the same amount to several recipients.
+	pub fn checked_mul(&self, factor: i64) -> Result<Self, TypeError> {
+		let value = self.value.checked_mul(i128::from(factor)).ok_or(TypeError::NumericOverflow)?;
+		Ok(Self { value, ..*self })
+	}
+
+	/// Formats the amount as decimal number without trailing zeros, e.g. `1.5`.
+	pub fn to_decimal_string(&self) -> String {
+		let decimals = self.decimals as usize;
+		let sign = if self.value < 0 { "-" } else { "" };
+		let digits = format!("{:0width$}", self.value.unsigned_abs(), width = decimals + 1);
+		let (integer, fraction) = digits.split_at(digits.len() - decimals);
+		let fraction = fraction.trim_end_matches('0');
+		if fraction.is_empty() {
+			format!("{sign}{integer}")
+		} else {
+			format!("{sign}{integer}.{fraction}")
+		}
+	}
+
+	fn check_same_token(&self, other: &Self) -> Result<(), TypeError> {
+		if self.asset != other.asset || self.decimals != other.decimals {
+			return Err(TypeError::IllegalArgument(format!(
+				"Cannot combine amounts of the tokens {:?} and {:?}",
+				self.asset, other.asset
+			)))
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for TokenAmount {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.to_decimal_string())
+	}
+}
+
+/// Parses an amount of NEO or GAS followed by the symbol of the token, e.g. `1.5 GAS`. Amounts
+/// of other tokens are parsed with [`Nep17Contract::parse_amount`].
+impl FromStr for TokenAmount {
+	type Err = TypeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		[Nep17Contract::neo(), Nep17Contract::gas()]
+			.iter()
+			.find(|token| s.trim_end().ends_with(&format!(" {}", token.symbol)))
+			.ok_or_else(|| TypeError::InvalidArgError(format!("Unknown token in amount {s}")))?
+			.parse_amount(s)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const GAS_DECIMALS: u8 = 8;
+
+	fn gas(amount: &str) -> Result<TokenAmount, TypeError> {
+		TokenAmount::parse(amount, GAS_DECIMALS, Nep17Contract::gas().script_hash)
+	}
+
+	#[test]
+	fn test_parse() {
+		assert_eq!(gas("1.5").unwrap().value(), 150_000_000);
+		assert_eq!(gas("0.00000001").unwrap().value(), 1);
+		assert_eq!(gas(".5").unwrap().value(), 50_000_000);
+		assert_eq!(gas("-2").unwrap().value(), -200_000_000);
+		assert_eq!(gas("12").unwrap().value(), 1_200_000_000);
+		assert!(gas("0.000000001").is_err());
+		assert!(gas("1e5").is_err());
+		assert!(gas(".").is_err());
+		assert_eq!(gas("10000000000000000000000000000000"), Err(TypeError::NumericOverflow));
+		assert!(TokenAmount::parse("1.5", 0, H160::zero()).is_err());
+	}
+
+	#[test]
+	fn test_format() {
+		assert_eq!(gas("1.50").unwrap().to_string(), "1.5");
+		assert_eq!(gas("3").unwrap().to_string(), "3");
+		assert_eq!(gas("-0.001").unwrap().to_string(), "-0.001");
+		assert_eq!(TokenAmount::new(7, 0, H160::zero()).to_string(), "7");
+	}
+
+	#[test]
+	fn test_from_str() {
+		let amount = TokenAmount::from_str("1.5 GAS").unwrap();
+		assert_eq!(amount, gas("1.5").unwrap());
+		assert_eq!(TokenAmount::from_str("10 NEO").unwrap().value(), 10);
+		assert!(TokenAmount::from_str("0.5 NEO").is_err());
+		assert!(TokenAmount::from_str("1 FLM").is_err());
+		assert_eq!(Nep17Contract::gas().format_amount(&amount).unwrap(), "1.5 GAS");
+	}
+
+	#[test]
+	fn test_arithmetic() {
+		let amount = gas("1").unwrap();
+		assert_eq!(amount.checked_add(&gas("0.5").unwrap()).unwrap().value(), 150_000_000);
+		assert_eq!(amount.checked_mul(3).unwrap().to_string(), "3");
+		let neo = TokenAmount::from_str("1 NEO").unwrap();
+		assert!(amount.checked_add(&neo).is_err());
+		let max = TokenAmount::new(i128::MAX, GAS_DECIMALS, amount.asset());
+		assert_eq!(max.checked_add(&amount), Err(TypeError::NumericOverflow));
+	}
+
+	#[test]
+	fn test_amounts_beyond_i64() {
+		let token = H160::repeat_byte(1);
+		let amount = TokenAmount::parse("12345.678", 18, token).unwrap();
+		assert_eq!(amount.value(), 12_345_678_000_000_000_000_000);
+		assert!(amount.value() > i128::from(i64::MAX));
+		assert_eq!(amount.to_string(), "12345.678");
+		assert_eq!(amount.value_i64(), Err(TypeError::NumericOverflow));
+
+		let balance = TokenAmount::new("9300000000000000000".parse().unwrap(), 18, token);
+		assert_eq!(balance.checked_add(&amount).unwrap().to_string(), "12354.978");
+		assert_eq!(TokenAmount::new(5, 18, token).value_i64(), Ok(5));
+	}
+}
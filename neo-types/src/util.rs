@@ -8,11 +8,10 @@ use rand::{
 	SeedableRng,
 };
 
-use crate::script_hash::ScriptHash;
+use crate::{encoding, script_hash::ScriptHash};
 use futures::AsyncWriteExt;
 use neo_crypto::hash::HashableForVec;
 use primitive_types::{H160, H256, U256};
-use tiny_keccak::{Hasher, Keccak};
 
 pub fn parse_string_u64(u64_str: &str) -> u64 {
 	if u64_str.starts_with("0x") {
@@ -31,7 +30,7 @@ pub fn parse_string_u256(u256_str: &str) -> U256 {
 }
 
 pub fn parse_address(address: &str) -> ScriptHash {
-	let bytes = hex::decode(address.trim_start_matches("0x")).unwrap();
+	let bytes = encoding::hex_decode(address).unwrap();
 	let mut padded_bytes = [0_u8; 20];
 	padded_bytes[20 - bytes.len()..].copy_from_slice(&bytes);
 	ScriptHash::from_slice(&padded_bytes)
@@ -42,7 +41,7 @@ pub fn encode_string_h160(h160: &H160) -> String {
 }
 
 pub fn parse_string_h256(h256_str: &str) -> H256 {
-	let bytes = hex::decode(h256_str.trim_start_matches("0x")).unwrap();
+	let bytes = encoding::hex_decode(h256_str).unwrap();
 	// pad the bytes to 32bytes
 	let mut padded_bytes = [0_u8; 32];
 	padded_bytes[32 - bytes.len()..].copy_from_slice(&bytes);
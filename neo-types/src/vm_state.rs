@@ -16,6 +16,7 @@ use strum_macros::{Display, EnumString};
 	Deserialize,
 )]
 #[repr(u8)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum VMState {
 	#[strum(serialize = "NONE")]
 	None = 0,
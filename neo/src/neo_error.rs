@@ -1,9 +1,18 @@
+use neo_codec::CodecError;
 use neo_contract::error::ContractError;
-use neo_crypto::error::SignError;
-use neo_providers::core::transaction::transaction_error::TransactionError;
+use neo_crypto::error::{CryptoError, SignError};
+use neo_providers::{
+	core::{error::BuilderError, transaction::transaction_error::TransactionError},
+	ProviderError,
+};
 use neo_signers::WalletError;
+use neo_types::error::TypeError;
 use thiserror::Error;
 
+/// The error of any of the crates of the SDK.
+///
+/// Every crate has its own error type, they all convert into `NeoError`, so a function calling
+/// into several crates can return `Result<T, NeoError>` and use `?` throughout.
 #[derive(Error, Debug)]
 pub enum NeoError {
 	#[error("Illegal argument: {0}")]
@@ -38,6 +47,16 @@ pub enum NeoError {
 	SignError(#[from] SignError),
 	#[error("Transaction error: {0}")]
 	TransactionError(#[from] TransactionError),
+	#[error("Builder error: {0}")]
+	BuilderError(#[from] BuilderError),
+	#[error("Provider error: {0}")]
+	ProviderError(#[from] ProviderError),
+	#[error("Type error: {0}")]
+	TypeError(#[from] TypeError),
+	#[error("Codec error: {0}")]
+	CodecError(#[from] CodecError),
+	#[error("Crypto error: {0}")]
+	CryptoError(#[from] CryptoError),
 	#[error("Unexpected returned type")]
 	UnexpectedReturnType,
 	#[error("Invalid private key")]
@@ -58,8 +77,11 @@ pub enum NeoError {
 	WifError(String),
 }
 
-impl Into<TransactionError> for NeoError {
-	fn into(self) -> TransactionError {
-		TransactionError::TransactionConfiguration(self.to_string())
+impl From<NeoError> for TransactionError {
+	fn from(err: NeoError) -> Self {
+		match err {
+			NeoError::TransactionError(err) => err,
+			err => TransactionError::TransactionConfiguration(err.to_string()),
+		}
 	}
 }